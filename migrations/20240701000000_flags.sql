@@ -0,0 +1,10 @@
+CREATE TABLE flags(
+  flag_id INTEGER PRIMARY KEY NOT NULL,
+  user_id INTEGER NOT NULL,
+  project_id INTEGER NOT NULL,
+  flag TEXT NOT NULL,
+  message TEXT,
+  flagged_at INTEGER NOT NULL,
+  FOREIGN KEY(user_id) REFERENCES users(user_id),
+  FOREIGN KEY(project_id) REFERENCES projects(project_id)
+);
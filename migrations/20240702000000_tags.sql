@@ -0,0 +1,13 @@
+CREATE TABLE tags(
+  project_id INTEGER NOT NULL,
+  tag TEXT NOT NULL,
+  FOREIGN KEY(project_id) REFERENCES projects(project_id),
+  UNIQUE(project_id, tag)
+);
+
+CREATE TABLE project_data_tags(
+  project_data_id INTEGER NOT NULL,
+  tag TEXT NOT NULL,
+  FOREIGN KEY(project_data_id) REFERENCES project_data(project_data_id),
+  UNIQUE(project_data_id, tag)
+);
@@ -0,0 +1 @@
+ALTER TABLE flags ADD COLUMN resolved_at INTEGER;
@@ -0,0 +1,4 @@
+ALTER TABLE releases ADD COLUMN requires TEXT NOT NULL DEFAULT '';
+ALTER TABLE releases ADD COLUMN requires_major INTEGER;
+ALTER TABLE releases ADD COLUMN requires_minor INTEGER;
+ALTER TABLE releases ADD COLUMN requires_patch INTEGER;
@@ -0,0 +1 @@
+ALTER TABLE flags ADD COLUMN resolved_by INTEGER REFERENCES users(user_id);
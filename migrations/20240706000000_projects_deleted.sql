@@ -0,0 +1,2 @@
+ALTER TABLE projects ADD COLUMN deleted_at INTEGER;
+ALTER TABLE projects ADD COLUMN deleted_by INTEGER REFERENCES users(user_id);
@@ -0,0 +1 @@
+ALTER TABLE files ADD COLUMN is_primary INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,6 @@
+CREATE TABLE project_redirects (
+  name TEXT NOT NULL,
+  project_id INTEGER NOT NULL,
+  UNIQUE(name),
+  FOREIGN KEY(project_id) REFERENCES projects(project_id)
+);
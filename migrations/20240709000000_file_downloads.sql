@@ -0,0 +1,7 @@
+CREATE TABLE file_downloads (
+  file_id INTEGER NOT NULL,
+  day INTEGER NOT NULL,
+  downloads INTEGER NOT NULL,
+  UNIQUE(file_id, day),
+  FOREIGN KEY(file_id) REFERENCES files(file_id)
+);
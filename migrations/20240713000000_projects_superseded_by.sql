@@ -0,0 +1 @@
+ALTER TABLE projects ADD COLUMN superseded_by INTEGER REFERENCES projects(project_id);
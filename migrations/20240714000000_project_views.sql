@@ -0,0 +1,15 @@
+CREATE TABLE project_views (
+  project_id INTEGER NOT NULL,
+  day INTEGER NOT NULL,
+  views INTEGER NOT NULL,
+  UNIQUE(project_id, day),
+  FOREIGN KEY(project_id) REFERENCES projects(project_id)
+);
+
+CREATE TABLE release_downloads (
+  release_id INTEGER NOT NULL,
+  day INTEGER NOT NULL,
+  downloads INTEGER NOT NULL,
+  UNIQUE(release_id, day),
+  FOREIGN KEY(release_id) REFERENCES releases(release_id)
+);
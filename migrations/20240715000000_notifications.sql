@@ -0,0 +1,11 @@
+CREATE TABLE notifications (
+  notification_id INTEGER PRIMARY KEY NOT NULL,
+  user_id INTEGER NOT NULL,
+  kind TEXT NOT NULL,
+  project TEXT NOT NULL,
+  package TEXT,
+  version TEXT,
+  created_at INTEGER NOT NULL,
+  read_at INTEGER,
+  FOREIGN KEY(user_id) REFERENCES users(user_id)
+);
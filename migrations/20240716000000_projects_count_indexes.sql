@@ -0,0 +1,26 @@
+/* Project counts filter on these columns and nothing else */
+CREATE INDEX projects_listed ON projects(
+  deleted_at,
+  archived,
+  superseded_by
+);
+
+CREATE INDEX projects_publisher_listed ON projects(
+  game_publisher,
+  deleted_at,
+  archived,
+  superseded_by
+);
+
+CREATE INDEX projects_year_listed ON projects(
+  game_year,
+  deleted_at,
+  archived,
+  superseded_by
+);
+
+/* Tag facets compare without regard to case */
+CREATE INDEX tags_project_id_tag_nocase ON tags(
+  project_id,
+  tag COLLATE NOCASE
+);
@@ -0,0 +1 @@
+ALTER TABLE players ADD COLUMN subscribed INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,9 @@
+CREATE TABLE galleries (
+  gallery_id INTEGER PRIMARY KEY NOT NULL,
+  project_id INTEGER NOT NULL,
+  filename TEXT NOT NULL,
+  description TEXT NOT NULL DEFAULT '',
+  position INTEGER NOT NULL,
+  FOREIGN KEY(project_id, filename) REFERENCES images(project_id, filename),
+  UNIQUE(project_id, filename)
+);
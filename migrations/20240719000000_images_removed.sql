@@ -0,0 +1 @@
+ALTER TABLE images ADD COLUMN removed INTEGER NOT NULL DEFAULT 0;
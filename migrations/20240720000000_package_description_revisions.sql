@@ -0,0 +1,15 @@
+CREATE TABLE package_description_revisions (
+  package_id INTEGER NOT NULL,
+  description TEXT NOT NULL,
+  modified_at INTEGER NOT NULL,
+  FOREIGN KEY(package_id) REFERENCES packages(package_id),
+  UNIQUE(package_id, modified_at)
+);
+
+INSERT INTO package_description_revisions (
+  package_id,
+  description,
+  modified_at
+)
+SELECT package_id, description, created_at
+FROM packages;
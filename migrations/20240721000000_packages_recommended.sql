@@ -0,0 +1 @@
+ALTER TABLE packages ADD COLUMN recommended_release_id INTEGER REFERENCES releases(release_id);
@@ -0,0 +1,8 @@
+CREATE TABLE package_redirects (
+  name TEXT NOT NULL,
+  project_id INTEGER NOT NULL,
+  package_id INTEGER NOT NULL,
+  UNIQUE(project_id, name),
+  FOREIGN KEY(project_id) REFERENCES projects(project_id),
+  FOREIGN KEY(package_id) REFERENCES packages(package_id)
+);
@@ -0,0 +1,29 @@
+CREATE TABLE downloads (
+  package_id INTEGER NOT NULL,
+  version TEXT NOT NULL,
+  filename TEXT NOT NULL,
+  day INTEGER NOT NULL,
+  downloads INTEGER NOT NULL,
+  UNIQUE(package_id, version, filename, day),
+  FOREIGN KEY(package_id) REFERENCES packages(package_id)
+);
+
+INSERT INTO downloads (
+  package_id,
+  version,
+  filename,
+  day,
+  downloads
+)
+SELECT
+  releases.package_id,
+  releases.version,
+  releases.filename,
+  release_downloads.day,
+  release_downloads.downloads
+FROM release_downloads
+JOIN releases
+ON release_downloads.release_id = releases.release_id;
+
+DROP TABLE release_downloads;
+DROP TABLE file_downloads;
@@ -2,11 +2,19 @@ use axum::extract::FromRef;
 
 use crate::{
     core::CoreArc,
-    jwt::DecodingKey
+    forum::ServiceTokens,
+    jwt::DecodingKey,
+    maintenance::Maintenance,
+    ratelimit::RateLimiter,
+    robots::RobotsTxt
 };
 
 #[derive(Clone, FromRef)]
 pub struct AppState {
     pub key: DecodingKey,
-    pub core: CoreArc
+    pub core: CoreArc,
+    pub robots: RobotsTxt,
+    pub forum_tokens: ServiceTokens,
+    pub maintenance: Maintenance,
+    pub rate_limiter: RateLimiter
 }
@@ -0,0 +1,90 @@
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::{Method, StatusCode},
+    middleware::Next,
+    response::Response
+};
+use std::sync::Arc;
+use tower::ServiceExt;
+use tower_http::services::ServeDir;
+
+#[derive(Clone, Debug)]
+pub struct StaticAssets {
+    api: Arc<str>,
+    dir: ServeDir
+}
+
+impl StaticAssets {
+    pub fn new(api: &str, dir: &str) -> Self {
+        StaticAssets {
+            api: api.trim_end_matches('/').into(),
+            dir: ServeDir::new(dir)
+        }
+    }
+
+    // nothing under the API base is served from the directory, so that
+    // API requests which match no route still get an API response
+    fn serves(&self, path: &str) -> bool {
+        !(
+            path == &*self.api ||
+            path.strip_prefix(&*self.api).is_some_and(|p| p.starts_with('/'))
+        )
+    }
+}
+
+// Static assets are tried only after the app has found nothing for a
+// request, so they can never shadow a route.
+pub async fn serve(
+    State(assets): State<StaticAssets>,
+    request: Request,
+    next: Next
+) -> Response
+{
+    let read = request.method() == Method::GET ||
+        request.method() == Method::HEAD;
+
+    if !read || !assets.serves(request.uri().path()) {
+        return next.run(request).await;
+    }
+
+    // the request is consumed by the app, so keep what's needed to
+    // ask for the file afterwards
+    let mut file_request = Request::new(Body::empty());
+    *file_request.method_mut() = request.method().clone();
+    *file_request.uri_mut() = request.uri().clone();
+    *file_request.headers_mut() = request.headers().clone();
+
+    let response = next.run(request).await;
+    if response.status() != StatusCode::NOT_FOUND {
+        return response;
+    }
+
+    match assets.dir.oneshot(file_request).await {
+        Ok(r) if r.status() != StatusCode::NOT_FOUND => r.map(Body::new),
+        _ => response
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn static_assets_serves() {
+        let assets = StaticAssets::new("/api/v1", "static");
+        assert!(assets.serves("/"));
+        assert!(assets.serves("/favicon.ico"));
+        assert!(assets.serves("/api/v10"));
+        assert!(!assets.serves("/api/v1"));
+        assert!(!assets.serves("/api/v1/"));
+        assert!(!assets.serves("/api/v1/projects"));
+    }
+
+    #[test]
+    fn static_assets_serves_trailing_slash_base() {
+        let assets = StaticAssets::new("/api/v1/", "static");
+        assert!(!assets.serves("/api/v1/projects"));
+        assert!(assets.serves("/docs/index.html"));
+    }
+}
@@ -0,0 +1,159 @@
+use std::fmt::Write;
+
+use crate::model::ReleaseFeed;
+
+pub const ATOM_CONTENT_TYPE: &str = "application/atom+xml";
+
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            _ => out.push(c)
+        }
+    }
+    out
+}
+
+fn feed_id(project: &str) -> String {
+    format!("urn:vassal:project:{project}")
+}
+
+pub fn to_atom(feed: &ReleaseFeed) -> String {
+    let mut out = String::new();
+
+    // writing to a String cannot fail
+    let _ = write!(
+        out,
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
+<feed xmlns=\"http://www.w3.org/2005/Atom\">\n\
+<id>{}</id>\n\
+<title>{}</title>\n\
+<updated>{}</updated>\n",
+        escape(&feed_id(&feed.project)),
+        escape(&feed.title),
+        escape(&feed.updated)
+    );
+
+    for e in &feed.entries {
+        let _ = write!(
+            out,
+            "<entry>\n\
+<id>{}</id>\n\
+<title>{} {}</title>\n\
+<updated>{}</updated>\n\
+<author><name>{}</name></author>\n",
+            escape(
+                &format!("{}:{}:{}", feed_id(&feed.project), e.package, e.version)
+            ),
+            escape(&e.package),
+            escape(&e.version),
+            escape(&e.published_at),
+            escape(&e.published_by)
+        );
+
+        for f in &e.files {
+            let _ = writeln!(
+                out,
+                "<link rel=\"enclosure\" href=\"{}\" title=\"{}\" length=\"{}\"/>",
+                escape(&f.url),
+                escape(&f.filename),
+                f.size
+            );
+        }
+
+        out.push_str("</entry>\n");
+    }
+
+    out.push_str("</feed>\n");
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::model::{FileData, ReleaseFeedEntry};
+
+    #[test]
+    fn escape_markup() {
+        assert_eq!(
+            escape("<a href=\"x\">Tom & Jerry's</a>"),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&apos;s&lt;/a&gt;"
+        );
+    }
+
+    #[test]
+    fn to_atom_empty() {
+        let feed = ReleaseFeed {
+            project: "a_game".into(),
+            title: "A Game".into(),
+            updated: "2023-12-09T15:56:29.180282477+00:00".into(),
+            entries: vec![]
+        };
+
+        assert_eq!(
+            to_atom(&feed),
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>
+<feed xmlns=\"http://www.w3.org/2005/Atom\">
+<id>urn:vassal:project:a_game</id>
+<title>A Game</title>
+<updated>2023-12-09T15:56:29.180282477+00:00</updated>
+</feed>
+"
+        );
+    }
+
+    #[test]
+    fn to_atom_entries() {
+        let feed = ReleaseFeed {
+            project: "test_game".into(),
+            title: "Tom & Jerry".into(),
+            updated: "2023-12-14T15:56:29.180282477+00:00".into(),
+            entries: vec![
+                ReleaseFeedEntry {
+                    package: "a_package".into(),
+                    version: "1.2.4".into(),
+                    published_at: "2023-12-14T15:56:29.180282477+00:00".into(),
+                    published_by: "alice".into(),
+                    files: vec![
+                        FileData {
+                            version: "1.2.4".into(),
+                            filename: "a_package-1.2.4".into(),
+                            url: "https://example.com/a_package-1.2.4".into(),
+                            size: 5678,
+                            checksum: "79fdd8fe3128f818e446e919cce5dcfb81815f8f4341c53f4d6b58ded48cebf2".into(),
+                            published_at: "2023-12-14T15:56:29.180282477+00:00".into(),
+                            published_by: "alice".into(),
+                            requires: ">= 3.7.12".into(),
+                            authors: vec!["alice".into(), "bob".into()],
+                            primary_file: None
+                        }
+                    ]
+                }
+            ]
+        };
+
+        assert_eq!(
+            to_atom(&feed),
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>
+<feed xmlns=\"http://www.w3.org/2005/Atom\">
+<id>urn:vassal:project:test_game</id>
+<title>Tom &amp; Jerry</title>
+<updated>2023-12-14T15:56:29.180282477+00:00</updated>
+<entry>
+<id>urn:vassal:project:test_game:a_package:1.2.4</id>
+<title>a_package 1.2.4</title>
+<updated>2023-12-14T15:56:29.180282477+00:00</updated>
+<author><name>alice</name></author>
+<link rel=\"enclosure\" href=\"https://example.com/a_package-1.2.4\" title=\"a_package-1.2.4\" length=\"5678\"/>
+</entry>
+</feed>
+"
+        );
+    }
+}
@@ -1,4 +1,41 @@
 use serde::Deserialize;
+use std::net::IpAddr;
+
+use crate::{
+    maintenance::DEFAULT_RETRY_AFTER,
+    pagination::{DEFAULT_LIMIT, DEFAULT_MAX_LIMIT},
+    ratelimit::DEFAULT_WINDOW,
+    upload::DEFAULT_STORAGE_PREFIX_DEPTH,
+    year::NonNumericYears
+};
+
+fn default_storage_prefix_depth() -> usize {
+    DEFAULT_STORAGE_PREFIX_DEPTH
+}
+
+fn default_max_limit() -> u8 {
+    DEFAULT_MAX_LIMIT
+}
+
+fn default_default_limit() -> u8 {
+    DEFAULT_LIMIT
+}
+
+fn default_maintenance_retry_after() -> u32 {
+    DEFAULT_RETRY_AFTER
+}
+
+fn default_rate_limit_window() -> u32 {
+    DEFAULT_WINDOW
+}
+
+fn default_record_usage() -> bool {
+    true
+}
+
+fn default_integrity_check() -> bool {
+    true
+}
 
 #[derive(Debug, Deserialize)]
 pub struct Config {
@@ -8,5 +45,55 @@ pub struct Config {
     pub listen_ip: String,
     pub listen_port: u16,
     pub max_release_size: u32,
-    pub max_image_size: u32
+    pub max_image_size: u32,
+    #[serde(default = "default_storage_prefix_depth")]
+    pub storage_prefix_depth: usize,
+    // reread stored files to check that they match what was uploaded
+    #[serde(default = "default_integrity_check")]
+    pub integrity_check: bool,
+    #[serde(default)]
+    pub non_numeric_years: NonNumericYears,
+    #[serde(default = "default_max_limit")]
+    pub max_limit: u8,
+    #[serde(default = "default_default_limit")]
+    pub default_limit: u8,
+    #[serde(default)]
+    pub robots_allow_api: bool,
+    #[serde(default)]
+    pub read_only: bool,
+    // list the new token alongside the old one while rotating
+    #[serde(default)]
+    pub forum_service_tokens: Vec<String>,
+    // seconds to reuse the unfiltered project count; 0 always counts
+    #[serde(default)]
+    pub approximate_count_ttl: u32,
+    // seconds for which seek links remain valid; 0 for no limit
+    #[serde(default)]
+    pub seek_max_age: u32,
+    // count project views and release downloads for project stats
+    #[serde(default = "default_record_usage")]
+    pub record_usage: bool,
+    // reread on SIGHUP, so maintenance can be toggled without a restart
+    #[serde(default)]
+    pub maintenance: bool,
+    pub maintenance_message: Option<String>,
+    #[serde(default = "default_maintenance_retry_after")]
+    pub maintenance_retry_after: u32,
+    // reads per window per address; 0 for no limit
+    #[serde(default)]
+    pub anonymous_rate_limit: u32,
+    #[serde(default)]
+    pub user_rate_limit: u32,
+    // seconds
+    #[serde(default = "default_rate_limit_window")]
+    pub rate_limit_window: u32,
+    // proxies trusted to report the client address in X-Forwarded-For
+    #[serde(default)]
+    pub trusted_proxies: Vec<IpAddr>,
+    // files served outside the API base, e.g., a favicon; none if unset
+    #[serde(default)]
+    pub static_dir: Option<String>,
+    // accept request bodies sent with Content-Encoding: gzip
+    #[serde(default)]
+    pub decompress_requests: bool
 }
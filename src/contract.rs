@@ -0,0 +1,403 @@
+// Contract tests pin the shape of the JSON which clients see. Each model
+// registered below is serialized from a fully populated sample and
+// compared with its golden file in test/contract. Values do not matter;
+// every field in the golden file must still be present, with the same
+// JSON type, in the same order relative to the other fields. Adding
+// fields is fine; removing, retyping, or reordering them is not.
+//
+// To add a field to a model, append it after the existing fields and
+// mark it #[serde(default)], so that payloads written before the field
+// existed still deserialize; each golden file is also deserialized into
+// its model to check this. Then regenerate the golden files with
+//
+//     GLS_BLESS_CONTRACTS=1 cargo test contract
+//
+// and review the diff. New models take part by adding a contract! line
+// with a sample in which every Option is Some and every Vec nonempty.
+
+use serde::{
+    Deserialize, Deserializer, Serialize,
+    de::{DeserializeOwned, MapAccess, SeqAccess, Visitor}
+};
+use std::{env, fmt, fs};
+
+use crate::{
+    HttpError,
+    model::{FacetCounts, FileData, Flag, Flags, GalleryImage, GameData, Inbox, InboxItem, PackageData, PackageSection, ProjectData, Projects, ProjectSummary, Users},
+    pagination::{Pagination, SeekLink}
+};
+
+#[derive(Debug)]
+enum Shape {
+    Null,
+    Bool,
+    Number,
+    String,
+    Array(Vec<Shape>),
+    // fields are kept in document order
+    Object(Vec<(String, Shape)>)
+}
+
+impl Shape {
+    fn kind(&self) -> &'static str {
+        match self {
+            Shape::Null => "null",
+            Shape::Bool => "bool",
+            Shape::Number => "number",
+            Shape::String => "string",
+            Shape::Array(_) => "array",
+            Shape::Object(_) => "object"
+        }
+    }
+}
+
+struct ShapeVisitor;
+
+impl<'de> Visitor<'de> for ShapeVisitor {
+    type Value = Shape;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("any JSON value")
+    }
+
+    fn visit_unit<E>(self) -> Result<Shape, E> {
+        Ok(Shape::Null)
+    }
+
+    fn visit_none<E>(self) -> Result<Shape, E> {
+        Ok(Shape::Null)
+    }
+
+    fn visit_some<D>(self, d: D) -> Result<Shape, D::Error>
+    where
+        D: Deserializer<'de>
+    {
+        Shape::deserialize(d)
+    }
+
+    fn visit_bool<E>(self, _v: bool) -> Result<Shape, E> {
+        Ok(Shape::Bool)
+    }
+
+    fn visit_i64<E>(self, _v: i64) -> Result<Shape, E> {
+        Ok(Shape::Number)
+    }
+
+    fn visit_u64<E>(self, _v: u64) -> Result<Shape, E> {
+        Ok(Shape::Number)
+    }
+
+    fn visit_f64<E>(self, _v: f64) -> Result<Shape, E> {
+        Ok(Shape::Number)
+    }
+
+    fn visit_str<E>(self, _v: &str) -> Result<Shape, E> {
+        Ok(Shape::String)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Shape, A::Error>
+    where
+        A: SeqAccess<'de>
+    {
+        let mut items = vec![];
+        while let Some(item) = seq.next_element()? {
+            items.push(item);
+        }
+        Ok(Shape::Array(items))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Shape, A::Error>
+    where
+        A: MapAccess<'de>
+    {
+        let mut fields = vec![];
+        while let Some(field) = map.next_entry()? {
+            fields.push(field);
+        }
+        Ok(Shape::Object(fields))
+    }
+}
+
+impl<'de> Deserialize<'de> for Shape {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>
+    {
+        d.deserialize_any(ShapeVisitor)
+    }
+}
+
+fn shape_errors(
+    path: &str,
+    golden: &Shape,
+    current: &Shape,
+    errors: &mut Vec<String>
+)
+{
+    match (golden, current) {
+        // a null in the golden file does not fix the type
+        (Shape::Null, _) => {},
+        (Shape::Object(gf), Shape::Object(cf)) => {
+            let mut last = None;
+            for (key, gv) in gf {
+                let fpath = format!("{path}.{key}");
+                match cf.iter().position(|(k, _)| k == key) {
+                    Some(i) => {
+                        if last.is_some_and(|l| i < l) {
+                            errors.push(format!("{fpath}: moved"));
+                        }
+                        last = Some(i);
+                        shape_errors(&fpath, gv, &cf[i].1, errors);
+                    },
+                    None => errors.push(format!("{fpath}: removed"))
+                }
+            }
+        },
+        (Shape::Array(ga), Shape::Array(ca)) => {
+            if let Some(gv) = ga.first() {
+                match ca.first() {
+                    Some(cv) => shape_errors(&format!("{path}[0]"), gv, cv, errors),
+                    None => errors.push(format!("{path}: sample is empty"))
+                }
+            }
+        },
+        (g, c) if g.kind() != c.kind() => errors.push(
+            format!("{path}: was {}, now {}", g.kind(), c.kind())
+        ),
+        _ => {}
+    }
+}
+
+fn check_contract<T>(name: &str, sample: T)
+where
+    T: Serialize + DeserializeOwned
+{
+    let path = format!(
+        "{}/test/contract/{name}.json",
+        env!("CARGO_MANIFEST_DIR")
+    );
+
+    if env::var_os("GLS_BLESS_CONTRACTS").is_some() {
+        let json = serde_json::to_string_pretty(&sample).unwrap();
+        fs::write(&path, json + "\n").unwrap();
+        return;
+    }
+
+    let golden = fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("{path}: {e}"));
+
+    let current = serde_json::from_str::<Shape>(
+        &serde_json::to_string(&sample).unwrap()
+    ).unwrap();
+
+    let mut errors = vec![];
+    shape_errors(
+        name,
+        &serde_json::from_str::<Shape>(&golden).unwrap(),
+        &current,
+        &mut errors
+    );
+    assert!(errors.is_empty(), "{name} contract broken:\n{}", errors.join("\n"));
+
+    // what clients sent before must still be accepted
+    if let Err(e) = serde_json::from_str::<T>(&golden) {
+        panic!("{name} golden file no longer deserializes: {e}");
+    }
+}
+
+macro_rules! contract {
+    ($test:ident, $name:literal, $sample:expr) => {
+        #[test]
+        fn $test() {
+            check_contract($name, $sample);
+        }
+    };
+}
+
+fn seek_link(s: &str) -> SeekLink {
+    serde_json::from_value(serde_json::Value::String(s.into())).unwrap()
+}
+
+fn game_data() -> GameData {
+    GameData {
+        title: "A Game of Tests".into(),
+        title_sort_key: "Game of Tests, A".into(),
+        publisher: "Test Game Company".into(),
+        year: "1979".into()
+    }
+}
+
+fn project_summary() -> ProjectSummary {
+    ProjectSummary {
+        name: "test_game".into(),
+        description: "Brian's Trademarked Game of Being a Test Case".into(),
+        revision: 1,
+        created_at: "2023-11-12T15:50:06.419538067+00:00".into(),
+        modified_at: "2023-12-14T15:50:06.419538067+00:00".into(),
+        tags: vec!["wargame".into()],
+        game: game_data(),
+        modified_by: Some("bob".into())
+    }
+}
+
+fn file_data() -> FileData {
+    FileData {
+        version: "1.2.3".into(),
+        filename: "a_package-1.2.3.vmod".into(),
+        url: "https://example.com/a_package-1.2.3.vmod".into(),
+        size: 1234,
+        checksum: "c0e0fa7373a12b45a91e4f4d4e2e186442fc6ee9b346caa2fdc1c09026a2144a".into(),
+        published_at: "2023-12-09T15:56:29.180282477+00:00".into(),
+        published_by: "alice".into(),
+        requires: ">= 3.6".into(),
+        authors: vec!["alice".into()],
+        primary_file: Some("a_package-1.2.3.vmod".into())
+    }
+}
+
+fn project_data() -> ProjectData {
+    ProjectData {
+        name: "test_game".into(),
+        description: "Brian's Trademarked Game of Being a Test Case".into(),
+        revision: 3,
+        created_at: "2023-11-12T15:50:06.419538067+00:00".into(),
+        modified_at: "2023-12-14T15:50:06.419538067+00:00".into(),
+        tags: vec!["wargame".into()],
+        game: game_data(),
+        readme: "Some readme".into(),
+        image: Some("img.png".into()),
+        archived: false,
+        superseded_by: Some(project_summary()),
+        supersedes: vec!["old_game".into()],
+        owners: vec!["bob".into()],
+        packages: vec![
+            PackageData {
+                name: "a_package".into(),
+                description: "Module".into(),
+                section: Some("Modules".into()),
+                releases: vec![file_data()],
+                files: vec![file_data()],
+                recommended_release: None
+            }
+        ],
+        package_sections: vec![
+            PackageSection {
+                name: Some("Modules".into()),
+                package_slugs: vec!["a_package".into()]
+            }
+        ],
+        modified_by: Some("bob".into()),
+        gallery: vec![
+            GalleryImage {
+                id: 1,
+                filename: "map.png".into(),
+                description: "The map".into()
+            }
+        ]
+    }
+}
+
+fn projects() -> Projects {
+    Projects {
+        projects: vec![project_summary()],
+        meta: Pagination {
+            prev_page: Some(seek_link("/api/v1/projects?seek=prev")),
+            next_page: Some(seek_link("/api/v1/projects?seek=next")),
+            total: 2,
+            total_pages: 2,
+            approximate: false,
+            position: Some(1)
+        },
+        facets: Some(
+            FacetCounts {
+                tags: [("wargame".into(), 1)].into()
+            }
+        )
+    }
+}
+
+fn flags() -> Flags {
+    Flags {
+        flags: vec![
+            Flag {
+                flag_id: 1,
+                project: "test_game".into(),
+                flagged_by: "alice".into(),
+                flagged_at: "2023-12-26T05:50:06.419538067+00:00".into(),
+                flag: "inappropriate".into(),
+                message: Some("rude words".into()),
+                resolved_at: Some("2024-01-06T19:23:26.419538067+00:00".into()),
+                resolved_by: Some("bob".into())
+            }
+        ]
+    }
+}
+
+fn inbox() -> Inbox {
+    Inbox {
+        notifications: vec![
+            InboxItem {
+                id: 4,
+                kind: "release_published".into(),
+                project: "test_game".into(),
+                package: Some("a_package".into()),
+                version: Some("1.2.4".into()),
+                created_at: "2023-12-26T05:36:46.419538067+00:00".into(),
+                read: false
+            }
+        ],
+        unread: 2,
+        next_before: Some(4)
+    }
+}
+
+contract!(project_data_contract, "project_data", project_data());
+contract!(project_summary_contract, "project_summary", project_summary());
+contract!(projects_contract, "projects", projects());
+contract!(users_contract, "users", Users { users: vec!["bob".into()] });
+contract!(flags_contract, "flags", flags());
+contract!(inbox_contract, "inbox", inbox());
+contract!(
+    error_contract,
+    "error",
+    HttpError {
+        error: "Payload too large".into(),
+        details: serde_json::json!({ "max_size": 1048576 })
+    }
+);
+
+#[test]
+fn shape_errors_ok() {
+    let golden = serde_json::from_str::<Shape>(
+        r#"{ "a": 1, "b": ["x"], "c": null }"#
+    ).unwrap();
+    let current = serde_json::from_str::<Shape>(
+        r#"{ "a": 2, "b": ["y"], "c": true, "d": {} }"#
+    ).unwrap();
+
+    let mut errors = vec![];
+    shape_errors("t", &golden, &current, &mut errors);
+    assert!(errors.is_empty(), "{errors:?}");
+}
+
+#[test]
+fn shape_errors_broken() {
+    let golden = serde_json::from_str::<Shape>(
+        r#"{ "a": 1, "b": ["x"], "c": "z", "d": { "e": 1 } }"#
+    ).unwrap();
+    let current = serde_json::from_str::<Shape>(
+        r#"{ "c": "z", "a": "1", "b": [], "d": {} }"#
+    ).unwrap();
+
+    let mut errors = vec![];
+    shape_errors("t", &golden, &current, &mut errors);
+    assert_eq!(
+        errors,
+        [
+            "t.a: was number, now string",
+            "t.b: sample is empty",
+            "t.c: moved",
+            "t.d.e: removed"
+        ]
+    );
+}
@@ -12,9 +12,10 @@ use std::{
 use thiserror::Error;
 
 use crate::{
-    model::{Owner, PackageDataPost, Package, Projects, ProjectData, ProjectDataPatch, ProjectDataPost, Project, User, Users},
-    params::ProjectsParams,
-    pagination,
+    forum::ForumUserSummary,
+    model::{BadTimestamps, FileData, Flags, GalleryImage, Inbox, LibraryStats, MalformedYears, Owner, PackageData, PackageDataPatch, PackageDataPost, Package, PopularProjects, Projects, ProjectsByName, RecentProjects, ReleaseData, ReleaseFeed, Releases, ProjectData, ProjectDataPatch, ProjectDataPatchResult, ProjectDataPost, Project, ProjectDiff, ProjectRevisionBatch, ProjectRevisions, ProjectStats, ProjectStatus, SlugRecompute, User, UserProfile, Users},
+    params::{InboxParams, ProjectsParams, ReadmeFormat, ReleasesParams},
+    pagination::{self, Facet, Limit},
     time,
     version::Version
 };
@@ -24,19 +25,57 @@ pub enum CoreError {
     #[error("Unsupported media type")]
     BadMimeType,
     #[error("File too large")]
-    TooLarge,
+    TooLarge(u64),
     #[error("Cannot remove last owner")]
     CannotRemoveLastOwner,
+    #[error("Flag already resolved")]
+    FlagAlreadyResolved,
+    #[error("Invalid filename")]
+    InvalidFilename,
+    #[error("Invalid module")]
+    InvalidModule,
+    #[error("Module version mismatch")]
+    ModuleVersionMismatch { expected: String, found: String },
+    #[error("Invalid package section")]
+    InvalidPackageSection,
+    #[error("Invalid packages: {}", .0.join("; "))]
+    InvalidPackages(Vec<String>),
+    #[error("Invalid year")]
+    InvalidYear,
     #[error("Invalid project name")]
     InvalidProjectName,
+    #[error("Invalid package name")]
+    InvalidPackageName,
     #[error("Project name in use")]
     ProjectNameInUse,
+    #[error("Package name in use")]
+    PackageNameInUse,
+    #[error("Project moved")]
+    ProjectMoved(String),
+    #[error("Project has releases")]
+    ProjectHasReleases,
+    #[error("Package has releases")]
+    PackageHasReleases,
+    #[error("Image in use")]
+    ImageInUse,
+    #[error("Project archived")]
+    ProjectArchived,
+    #[error("Invalid supersession")]
+    InvalidSupersession,
+    #[error("Revision mismatch")]
+    RevisionMismatch,
+    #[error("Limit out of range")]
+    LimitOutOfRange,
     #[error("Malformed query")]
     MalformedQuery,
     #[error("Not a found")]
     NotFound,
+    #[error("Not a flag")]
+    NotAFlag,
     #[error("Not a package")]
     NotAPackage,
+    #[error("Not a player")]
+    NotAPlayer,
     #[error("Not a project")]
     NotAProject,
     #[error("Not a revision")]
@@ -47,6 +86,8 @@ pub enum CoreError {
     NotAVersion,
     #[error("Internal error")]
     InternalError,
+    #[error("Stored file does not match upload")]
+    IntegrityError,
     #[error("{0}")]
     DatabaseError(#[from] sqlx::Error),
     #[error("{0}")]
@@ -133,6 +174,14 @@ pub trait Core {
         unimplemented!();
     }
 
+    async fn get_projects_by_name(
+        &self,
+        _names: &[String]
+    ) -> Result<ProjectsByName, CoreError>
+    {
+        unimplemented!();
+    }
+
     async fn get_project(
         &self,
         _proj: Project
@@ -141,12 +190,53 @@ pub trait Core {
         unimplemented!();
     }
 
+    async fn record_project_view(
+        &self,
+        _proj: Project
+    )
+    {
+        unimplemented!();
+    }
+
+    async fn get_popular_projects(
+        &self,
+        _limit: Option<Limit>
+    ) -> Result<PopularProjects, CoreError>
+    {
+        unimplemented!();
+    }
+
+    async fn get_recent_projects(
+        &self,
+        _limit: Option<Limit>
+    ) -> Result<RecentProjects, CoreError>
+    {
+        unimplemented!();
+    }
+
+    async fn get_random_project(
+        &self
+    ) -> Result<ProjectData, CoreError>
+    {
+        unimplemented!();
+    }
+
+    async fn get_releases(
+        &self,
+        _proj: Project,
+        _pkg: Package,
+        _params: ReleasesParams
+    ) -> Result<Releases, CoreError>
+    {
+        unimplemented!();
+    }
+
     async fn create_project(
         &self,
         _user: User,
         _proj: &str,
         _proj_data: &ProjectDataPost
-    ) -> Result<(), CoreError>
+    ) -> Result<Project, CoreError>
     {
         unimplemented!();
     }
@@ -155,7 +245,18 @@ pub trait Core {
         &self,
         _owner: Owner,
         _proj: Project,
-        _proj_data: &ProjectDataPatch
+        _proj_data: &ProjectDataPatch,
+        _expected_revision: Option<i64>
+    ) -> Result<ProjectDataPatchResult, CoreError>
+    {
+        unimplemented!();
+    }
+
+    async fn delete_project(
+        &self,
+        _owner: Owner,
+        _proj: Project,
+        _force: bool
     ) -> Result<(), CoreError>
     {
         unimplemented!();
@@ -170,6 +271,33 @@ pub trait Core {
         unimplemented!();
     }
 
+    async fn get_project_revision_batch(
+        &self,
+        _proj: Project,
+        _revisions: &[i64]
+    ) -> Result<ProjectRevisionBatch, CoreError>
+    {
+        unimplemented!();
+    }
+
+    async fn get_project_diff(
+        &self,
+        _proj: Project,
+        _from: i64,
+        _to: i64
+    ) -> Result<ProjectDiff, CoreError>
+    {
+        unimplemented!();
+    }
+
+    async fn get_project_revisions(
+        &self,
+        _proj: Project
+    ) -> Result<ProjectRevisions, CoreError>
+    {
+        unimplemented!();
+    }
+
     async fn create_package(
         &self,
         _owner: Owner,
@@ -181,6 +309,58 @@ pub trait Core {
         unimplemented!();
     }
 
+    async fn update_package(
+        &self,
+        _owner: Owner,
+        _proj: Project,
+        _pkg: Package,
+        _pkg_data: &PackageDataPatch
+    ) -> Result<(), CoreError>
+    {
+        unimplemented!();
+    }
+
+    async fn delete_package(
+        &self,
+        _owner: Owner,
+        _proj: Project,
+        _pkg: Package,
+        _force: bool
+    ) -> Result<(), CoreError>
+    {
+        unimplemented!();
+    }
+
+    async fn reorder_packages(
+        &self,
+        _owner: Owner,
+        _proj: Project,
+        _order: &[String]
+    ) -> Result<(), CoreError>
+    {
+        unimplemented!();
+    }
+
+    async fn delete_release(
+        &self,
+        _owner: Owner,
+        _proj: Project,
+        _pkg: Package,
+        _version: &Version
+    ) -> Result<(), CoreError>
+    {
+        unimplemented!();
+    }
+
+    async fn get_package(
+        &self,
+        _proj: Project,
+        _pkg: Package
+    ) -> Result<PackageData, CoreError>
+    {
+        unimplemented!();
+    }
+
     async fn get_release(
         &self,
         _proj: Project,
@@ -190,6 +370,35 @@ pub trait Core {
         unimplemented!();
     }
 
+    async fn get_latest_release_url(
+        &self,
+        _proj: Project,
+        _pkg: Package
+    ) -> Result<String, CoreError>
+    {
+        unimplemented!();
+    }
+
+    async fn get_recommended_release_url(
+        &self,
+        _proj: Project,
+        _pkg: Package
+    ) -> Result<String, CoreError>
+    {
+        unimplemented!();
+    }
+
+    async fn set_recommended_release(
+        &self,
+        _owner: Owner,
+        _proj: Project,
+        _pkg: Package,
+        _version: Option<&Version>
+    ) -> Result<(), CoreError>
+    {
+        unimplemented!();
+    }
+
     async fn get_release_version(
         &self,
         _proj: Project,
@@ -200,6 +409,114 @@ pub trait Core {
         unimplemented!();
     }
 
+    async fn get_release_data(
+        &self,
+        _proj: Project,
+        _pkg: Package,
+        _version: &Version
+    ) -> Result<ReleaseData, CoreError>
+    {
+        unimplemented!();
+    }
+
+    async fn get_malformed_years(
+        &self
+    ) -> Result<MalformedYears, CoreError>
+    {
+        unimplemented!();
+    }
+
+    async fn correct_year(
+        &self,
+        _admin: User,
+        _proj: Project,
+        _year: &str
+    ) -> Result<(), CoreError>
+    {
+        unimplemented!();
+    }
+
+    async fn get_forum_user_summary(
+        &self,
+        _user: User
+    ) -> Result<ForumUserSummary, CoreError>
+    {
+        unimplemented!();
+    }
+
+    async fn get_user_profile(
+        &self,
+        _user: User
+    ) -> Result<UserProfile, CoreError>
+    {
+        unimplemented!();
+    }
+
+    async fn get_project_stats(
+        &self,
+        _proj: Project
+    ) -> Result<ProjectStats, CoreError>
+    {
+        unimplemented!();
+    }
+
+    async fn get_project_status(
+        &self,
+        _proj: Project
+    ) -> Result<ProjectStatus, CoreError>
+    {
+        unimplemented!();
+    }
+
+    async fn get_project_readme(
+        &self,
+        _proj: Project,
+        _format: ReadmeFormat
+    ) -> Result<String, CoreError>
+    {
+        unimplemented!();
+    }
+
+    async fn get_library_stats(
+        &self
+    ) -> Result<LibraryStats, CoreError>
+    {
+        unimplemented!();
+    }
+
+    async fn get_release_feed(
+        &self,
+        _proj: Project
+    ) -> Result<ReleaseFeed, CoreError>
+    {
+        unimplemented!();
+    }
+
+    async fn set_primary_file(
+        &self,
+        _owner: Owner,
+        _proj: Project,
+        _pkg: Package,
+        _version: &Version,
+        _filename: &str
+    ) -> Result<(), CoreError>
+    {
+        unimplemented!();
+    }
+
+    async fn add_file(
+        &self,
+        _owner: Owner,
+        _proj: Project,
+        _pkg: Package,
+        _version: &Version,
+        _filename: &str,
+        _data: Bytes
+    ) -> Result<FileData, CoreError>
+    {
+        unimplemented!();
+    }
+
     async fn add_release(
         &self,
         _owner: Owner,
@@ -239,6 +556,24 @@ pub trait Core {
         unimplemented!();
     }
 
+    async fn subscribe(
+        &self,
+        _player: User,
+        _proj: Project
+    ) -> Result<(), CoreError>
+    {
+        unimplemented!();
+    }
+
+    async fn unsubscribe(
+        &self,
+        _player: User,
+        _proj: Project
+    ) -> Result<(), CoreError>
+    {
+        unimplemented!();
+    }
+
     async fn get_image(
         &self,
         _proj: Project,
@@ -270,6 +605,79 @@ pub trait Core {
     {
         unimplemented!();
     }
+
+    async fn remove_image(
+        &self,
+        _owner: Owner,
+        _proj: Project,
+        _img_name: &str
+    ) -> Result<(), CoreError>
+    {
+        unimplemented!();
+    }
+
+    async fn add_gallery_image(
+        &self,
+        _owner: Owner,
+        _proj: Project,
+        _img_name: &str,
+        _content_type: &Mime,
+        _content_length: Option<u64>,
+        _stream: Box<dyn Stream<Item = Result<Bytes, io::Error>> + Send>
+    ) -> Result<GalleryImage, CoreError>
+    {
+        unimplemented!();
+    }
+
+    async fn get_flags(
+        &self,
+        _facets: &[Facet]
+    ) -> Result<Flags, CoreError>
+    {
+        unimplemented!();
+    }
+
+    async fn resolve_flag(
+        &self,
+        _admin: User,
+        _flag_id: i64
+    ) -> Result<(), CoreError>
+    {
+        unimplemented!();
+    }
+
+    async fn get_inbox(
+        &self,
+        _user: User,
+        _params: InboxParams
+    ) -> Result<Inbox, CoreError>
+    {
+        unimplemented!();
+    }
+
+    async fn mark_inbox_read(
+        &self,
+        _user: User,
+        _ids: Option<&[i64]>
+    ) -> Result<(), CoreError>
+    {
+        unimplemented!();
+    }
+
+    async fn recompute_slugs(
+        &self,
+        _aliases: bool
+    ) -> Result<SlugRecompute, CoreError>
+    {
+        unimplemented!();
+    }
+
+    async fn get_bad_timestamps(
+        &self
+    ) -> Result<BadTimestamps, CoreError>
+    {
+        unimplemented!();
+    }
 }
 
 pub type CoreArc = Arc<dyn Core + Send + Sync>;
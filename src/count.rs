@@ -0,0 +1,73 @@
+use std::sync::{Arc, Mutex};
+
+use crate::time::NANOS_PER_SEC;
+
+// Counting every project for each unfiltered listing is costly on a large
+// library, but the total changes slowly, so it may be reused for a while.
+// A TTL of zero disables the cache, so that every count is exact.
+#[derive(Clone, Debug, Default)]
+pub struct CountCache {
+    ttl: i64,
+    cached: Arc<Mutex<Option<(i64, i64)>>>
+}
+
+impl CountCache {
+    pub fn new(ttl_secs: u32) -> Self {
+        CountCache {
+            ttl: ttl_secs as i64 * NANOS_PER_SEC,
+            cached: Arc::default()
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.ttl > 0
+    }
+
+    pub fn get(&self, now: i64) -> Option<i64> {
+        match *self.cached.lock().ok()? {
+            Some((at, count)) if self.enabled() && now - at < self.ttl =>
+                Some(count),
+            _ => None
+        }
+    }
+
+    pub fn set(&self, now: i64, count: i64) {
+        if self.enabled() {
+            if let Ok(mut cached) = self.cached.lock() {
+                *cached = Some((now, count));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn count_cache_disabled() {
+        let cache = CountCache::new(0);
+        cache.set(0, 5);
+        assert_eq!(cache.get(0), None);
+    }
+
+    #[test]
+    fn count_cache_empty() {
+        let cache = CountCache::new(60);
+        assert_eq!(cache.get(0), None);
+    }
+
+    #[test]
+    fn count_cache_fresh() {
+        let cache = CountCache::new(60);
+        cache.set(0, 5);
+        assert_eq!(cache.get(59 * NANOS_PER_SEC), Some(5));
+    }
+
+    #[test]
+    fn count_cache_stale() {
+        let cache = CountCache::new(60);
+        cache.set(0, 5);
+        assert_eq!(cache.get(60 * NANOS_PER_SEC), None);
+    }
+}
@@ -1,12 +1,14 @@
 use axum::async_trait;
 use serde::Deserialize;
 use sqlx::FromRow;
+use std::collections::HashMap;
 
 use crate::{
     core::CoreError,
-    model::{Owner, Package, PackageDataPost, Project, ProjectDataPatch, ProjectDataPost, User, Users},
-    pagination::{Direction, SortBy},
-    version::Version
+    model::{GalleryImage, LibraryStats, Owner, Package, PackageDataPatch, PackageDataPost, Project, ProjectDataPatch, ProjectDataPatchResult, ProjectDataPost, SlugRecompute, User, Users},
+    notify::Notification,
+    pagination::{Direction, Facet, ReleaseAnchor, SortBy},
+    version::{Requires, Version}
 };
 
 #[derive(Debug, Deserialize, FromRow, PartialEq)]
@@ -18,11 +20,14 @@ pub struct ProjectSummaryRow {
     pub revision: i64,
     pub created_at: i64,
     pub modified_at: i64,
+    pub modified_by: Option<String>,
     pub game_title: String,
     pub game_title_sort: String,
     pub game_publisher: String,
     pub game_year: String,
-    pub image: Option<String>
+    pub image: Option<String>,
+    // a JSON array, sorted lexicographically
+    pub tags: String
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
@@ -33,21 +38,33 @@ pub struct ProjectRow {
     pub revision: i64,
     pub created_at: i64,
     pub modified_at: i64,
-    pub modified_by: i64,
+    pub modified_by: Option<String>,
     pub game_title: String,
     pub game_title_sort: String,
     pub game_publisher: String,
     pub game_year: String,
     pub image: Option<String>,
-    pub readme: String
+    pub readme: String,
+    pub archived: bool,
+    pub superseded_by: Option<i64>
+}
+
+#[derive(Debug, Deserialize, Eq, PartialEq)]
+pub struct RevisionRow {
+    pub revision: i64,
+    pub modified_at: i64,
+    pub modified_by: String
 }
 
 #[derive(Debug, Deserialize, Eq, PartialEq)]
 pub struct PackageRow {
     pub package_id: i64,
     pub name: String,
-    pub created_at: i64
-//    description: String
+    pub created_at: i64,
+    pub description: String,
+    pub section: Option<String>,
+    // the version of the recommended release, if any
+    pub recommended: Option<String>
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
@@ -64,10 +81,105 @@ pub struct FileRow {
     pub size: i64,
     pub checksum: String,
     pub published_at: i64,
-    pub published_by: String
+    pub published_by: String,
+    pub is_primary: bool
 //    requires: String
 }
 
+#[derive(Debug, Deserialize, Eq, FromRow, PartialEq)]
+pub struct FlagRow {
+    pub flag_id: i64,
+    pub project: String,
+    pub flagged_by: String,
+    pub flagged_at: i64,
+    pub flag: String,
+    pub message: Option<String>,
+    pub resolved_at: Option<i64>,
+    pub resolved_by: Option<String>
+}
+
+#[derive(Debug, Deserialize, Eq, FromRow, PartialEq)]
+pub struct TimestampRow {
+    pub table_name: String,
+    pub id: i64,
+    pub column_name: String,
+    pub value: i64
+}
+
+#[derive(Debug, Deserialize, Eq, FromRow, PartialEq)]
+pub struct SupersessionRow {
+    pub project_id: i64,
+    pub superseded_by: i64
+}
+
+#[derive(Debug, Deserialize, Eq, FromRow, PartialEq)]
+pub struct ProjectYearRow {
+    pub name: String,
+    pub game_year: String
+}
+
+#[derive(Debug, Deserialize, Eq, FromRow, PartialEq)]
+pub struct UserReleaseRow {
+    pub project: String,
+    pub package: String,
+    pub version: String,
+    pub published_at: i64
+}
+
+#[derive(Debug, Deserialize, Eq, FromRow, PartialEq)]
+pub struct UserProfileRow {
+    pub username: String,
+    pub owned_projects: i64,
+    pub played_projects: i64
+}
+
+#[derive(Debug, Deserialize, Eq, FromRow, PartialEq)]
+pub struct NotificationRow {
+    pub notification_id: i64,
+    pub kind: String,
+    pub project: String,
+    pub package: Option<String>,
+    pub version: Option<String>,
+    pub created_at: i64,
+    pub read_at: Option<i64>
+}
+
+#[derive(Debug, Deserialize, Eq, FromRow, PartialEq)]
+pub struct ProjectStatsRow {
+    pub files: i64,
+    pub bytes: i64,
+    pub releases: i64,
+    pub packages: i64,
+    pub players: i64,
+    pub owners: i64,
+    pub flags: i64,
+    pub views: i64,
+    pub downloads: i64,
+    pub created_at: i64
+}
+
+#[derive(Debug, Deserialize, Eq, FromRow, PartialEq)]
+pub struct ProjectStatusRow {
+    pub open_flags: i64,
+    pub archived: bool,
+    pub bytes: i64
+}
+
+#[derive(Debug, Deserialize, Eq, FromRow, PartialEq)]
+pub struct DailyStatsRow {
+    pub day: i64,
+    pub views: i64,
+    pub downloads: i64
+}
+
+#[derive(Debug, Deserialize, Eq, FromRow, PartialEq)]
+pub struct ReleaseUrlRow {
+    pub release_id: i64,
+    pub version: String,
+    pub filename: String,
+    pub url: String
+}
+
 #[async_trait]
 pub trait DatabaseClient {
     async fn get_project_id(
@@ -75,20 +187,58 @@ pub trait DatabaseClient {
         _projname: &str
     ) -> Result<Project, CoreError>;
 
+    async fn get_random_project_id(
+        &self
+    ) -> Result<Project, CoreError>;
+
+    async fn get_package_id(
+        &self,
+        _proj: Project,
+        _pkgname: &str
+    ) -> Result<Package, CoreError>;
+
     async fn get_projects_count(
         &self,
+        _facets: &[Facet]
     ) -> Result<i64, CoreError>;
 
     async fn get_projects_query_count(
         &self,
-        _query: &str
+        _query: &str,
+        _facets: &[Facet]
     ) -> Result<i64, CoreError>;
 
+    async fn get_tag_facet_counts(
+        &self,
+        _facets: &[Facet]
+    ) -> Result<HashMap<String, i64>, CoreError>;
+
     async fn get_user_id(
         &self,
         _username: &str
     ) -> Result<User, CoreError>;
 
+    async fn get_username(
+        &self,
+        _user: User
+    ) -> Result<String, CoreError>;
+
+    async fn get_user_profile_row(
+        &self,
+        _user: User
+    ) -> Result<UserProfileRow, CoreError>;
+
+    async fn get_owned_projects(
+        &self,
+        _user: User
+    ) -> Result<Vec<ProjectSummaryRow>, CoreError>;
+
+    async fn get_user_releases(
+        &self,
+        _user: User,
+        _limit: u32
+    ) -> Result<Vec<UserReleaseRow>, CoreError>;
+
     async fn get_owners(
         &self,
         _proj: Project
@@ -129,10 +279,39 @@ pub trait DatabaseClient {
         _proj: Project
     ) -> Result<bool, CoreError>;
 
+    async fn get_project_years(
+        &self
+    ) -> Result<Vec<ProjectYearRow>, CoreError>;
+
+    async fn get_project_summary_row(
+        &self,
+        _proj: Project
+    ) -> Result<Option<ProjectSummaryRow>, CoreError>;
+
+    async fn get_project_summary_rows_by_name(
+        &self,
+        _names: &[String]
+    ) -> Result<Vec<ProjectSummaryRow>, CoreError>;
+
+    async fn get_supersedes(
+        &self,
+        _proj: Project
+    ) -> Result<Vec<String>, CoreError>;
+
+    async fn get_supersessions(
+        &self
+    ) -> Result<Vec<SupersessionRow>, CoreError>;
+
+    async fn get_popular_projects(
+        &self,
+        _limit: u32
+    ) -> Result<Vec<ProjectSummaryRow>, CoreError>;
+
     async fn get_projects_end_window(
         &self,
         _sort_by: SortBy,
         _dir: Direction,
+        _facets: &[Facet],
         _limit: u32
     ) -> Result<Vec<ProjectSummaryRow>, CoreError>;
 
@@ -141,6 +320,7 @@ pub trait DatabaseClient {
         _query: &str,
         _sort_by: SortBy,
         _dir: Direction,
+        _facets: &[Facet],
         _limit: u32
     ) -> Result<Vec<ProjectSummaryRow>, CoreError>;
 
@@ -150,9 +330,11 @@ pub trait DatabaseClient {
         _dir: Direction,
         _field: &str,
         _id: u32,
+        _facets: &[Facet],
         _limit: u32
     ) -> Result<Vec<ProjectSummaryRow>, CoreError>;
 
+    #[allow(clippy::too_many_arguments)]
     async fn get_projects_query_mid_window(
         &self,
         _query: &str,
@@ -160,22 +342,51 @@ pub trait DatabaseClient {
         _dir: Direction,
         _field: &str,
         _id: u32,
+        _facets: &[Facet],
         _limit: u32
     ) -> Result<Vec<ProjectSummaryRow>, CoreError>;
 
+    async fn get_projects_position(
+        &self,
+        _sort_by: SortBy,
+        _dir: Direction,
+        _field: &str,
+        _id: u32,
+        _facets: &[Facet]
+    ) -> Result<i64, CoreError>;
+
+    async fn get_projects_query_position(
+        &self,
+        _query: &str,
+        _sort_by: SortBy,
+        _dir: Direction,
+        _field: &str,
+        _id: u32,
+        _facets: &[Facet]
+    ) -> Result<i64, CoreError>;
+
     async fn create_project(
         &self,
         _user: User,
         _proj: &str,
         _proj_data: &ProjectDataPost,
         _now: i64
-    ) -> Result<(), CoreError>;
+    ) -> Result<Project, CoreError>;
 
     async fn update_project(
         &self,
         _owner: Owner,
         _proj: Project,
         _proj_data: &ProjectDataPatch,
+        _expected_revision: Option<i64>,
+        _now: i64
+    ) -> Result<ProjectDataPatchResult, CoreError>;
+
+    async fn delete_project(
+        &self,
+        _owner: Owner,
+        _proj: Project,
+        _force: bool,
         _now: i64
     ) -> Result<(), CoreError>;
 
@@ -190,6 +401,11 @@ pub trait DatabaseClient {
         _revision: i64
     ) -> Result<ProjectRow, CoreError>;
 
+    async fn get_project_revisions(
+        &self,
+        _proj: Project
+    ) -> Result<Vec<RevisionRow>, CoreError>;
+
     async fn get_packages(
         &self,
         _proj: Project
@@ -210,11 +426,58 @@ pub trait DatabaseClient {
         _now: i64
     ) -> Result<(), CoreError>;
 
+    async fn update_package(
+        &self,
+        _owner: Owner,
+        _proj: Project,
+        _pkg: Package,
+        _pkg_data: &PackageDataPatch,
+        _now: i64
+    ) -> Result<(), CoreError>;
+
+    async fn delete_package(
+        &self,
+        _owner: Owner,
+        _proj: Project,
+        _pkg: Package,
+        _force: bool,
+        _now: i64
+    ) -> Result<(), CoreError>;
+
+    async fn reorder_packages(
+        &self,
+        _owner: Owner,
+        _proj: Project,
+        _order: &[String],
+        _now: i64
+    ) -> Result<(), CoreError>;
+
+    async fn delete_release(
+        &self,
+        _owner: Owner,
+        _proj: Project,
+        _pkg: Package,
+        _version: &Version,
+        _now: i64
+    ) -> Result<(), CoreError>;
+
     async fn get_releases(
         &self,
         _pkg: Package
     ) -> Result<Vec<FileRow>, CoreError>;
 
+    async fn get_releases_window(
+        &self,
+        _pkg: Package,
+        _anchor: &ReleaseAnchor,
+        _limit: u32
+    ) -> Result<Vec<FileRow>, CoreError>;
+
+    async fn get_releases_count(
+        &self,
+        _pkg: Package
+    ) -> Result<i64, CoreError>;
+
     async fn get_releases_at(
         &self,
         _pkg: Package,
@@ -237,16 +500,54 @@ pub trait DatabaseClient {
         _pkg_ver_id: i64
     ) -> Result<Users, CoreError>;
 
+    async fn get_file_authors(
+        &self,
+        _file_id: i64
+    ) -> Result<Users, CoreError>;
+
     async fn get_release_url(
         &self,
         _pkg: Package
-    ) -> Result<String, CoreError>;
+    ) -> Result<ReleaseUrlRow, CoreError>;
 
     async fn get_release_version_url(
         &self,
         _pkg: Package,
         _version: &Version
-    ) -> Result<String, CoreError>;
+    ) -> Result<ReleaseUrlRow, CoreError>;
+
+    async fn set_primary_file(
+        &self,
+        _owner: Owner,
+        _proj: Project,
+        _pkg: Package,
+        _version: &Version,
+        _filename: &str,
+        _now: i64
+    ) -> Result<(), CoreError>;
+
+    async fn set_recommended_release(
+        &self,
+        _owner: Owner,
+        _proj: Project,
+        _pkg: Package,
+        _version: Option<&Version>,
+        _now: i64
+    ) -> Result<(), CoreError>;
+
+    #[allow(clippy::too_many_arguments)]
+    async fn add_file_url(
+        &self,
+        _owner: Owner,
+        _proj: Project,
+        _pkg: Package,
+        _version: &Version,
+        _filename: &str,
+        _size: i64,
+        _checksum: &str,
+        _url: &str,
+        _now: i64
+    ) -> Result<FileRow, CoreError>;
 
     async fn add_release_url(
         &self,
@@ -254,6 +555,7 @@ pub trait DatabaseClient {
         _proj: Project,
         _pkg: Package,
         _version: &Version,
+        _requires: Option<&Requires>,
         _filename: &str,
         _size: i64,
         _checksum: &str,
@@ -278,6 +580,18 @@ pub trait DatabaseClient {
         _proj: Project
     ) -> Result<(), CoreError>;
 
+    async fn get_subscribers(
+        &self,
+        _proj: Project
+    ) -> Result<Users, CoreError>;
+
+    async fn set_subscribed(
+        &self,
+        _player: User,
+        _proj: Project,
+        _subscribed: bool
+    ) -> Result<(), CoreError>;
+
     async fn get_image_url(
         &self,
         _proj: Project,
@@ -299,4 +613,122 @@ pub trait DatabaseClient {
         _url: &str,
         _now: i64
     ) -> Result<(), CoreError>;
+
+    async fn remove_image(
+        &self,
+        _owner: Owner,
+        _proj: Project,
+        _img_name: &str,
+        _now: i64
+    ) -> Result<(), CoreError>;
+
+    async fn get_gallery(
+        &self,
+        _proj: Project
+    ) -> Result<Vec<GalleryImage>, CoreError>;
+
+    async fn add_gallery_image(
+        &self,
+        _owner: Owner,
+        _proj: Project,
+        _img_name: &str,
+        _url: &str,
+        _now: i64
+    ) -> Result<GalleryImage, CoreError>;
+
+    async fn get_tags(
+        &self,
+        proj: Project
+    ) -> Result<Vec<String>, CoreError>;
+
+    async fn get_tags_at(
+        &self,
+        proj: Project,
+        revision: i64
+    ) -> Result<Vec<String>, CoreError>;
+
+    async fn get_flags(
+        &self,
+        _facets: &[Facet]
+    ) -> Result<Vec<FlagRow>, CoreError>;
+
+    async fn get_flag_project(
+        &self,
+        _flag_id: i64
+    ) -> Result<Project, CoreError>;
+
+    async fn resolve_flag(
+        &self,
+        _admin: User,
+        _flag_id: i64,
+        _now: i64
+    ) -> Result<(), CoreError>;
+
+    async fn add_notification(
+        &self,
+        _notification: &Notification,
+        _now: i64
+    ) -> Result<(), CoreError>;
+
+    async fn get_notifications(
+        &self,
+        _user: User,
+        _before: Option<i64>,
+        _limit: u32
+    ) -> Result<Vec<NotificationRow>, CoreError>;
+
+    async fn get_unread_notifications_count(
+        &self,
+        _user: User
+    ) -> Result<i64, CoreError>;
+
+    async fn mark_notifications_read(
+        &self,
+        _user: User,
+        _ids: Option<&[i64]>,
+        _now: i64
+    ) -> Result<(), CoreError>;
+
+    async fn recompute_normalized_names(
+        &self,
+        _aliases: bool
+    ) -> Result<SlugRecompute, CoreError>;
+
+    async fn get_timestamps(
+        &self
+    ) -> Result<Vec<TimestampRow>, CoreError>;
+
+    async fn get_project_stats(
+        &self,
+        _proj: Project
+    ) -> Result<ProjectStatsRow, CoreError>;
+
+    async fn get_project_daily_stats(
+        &self,
+        _proj: Project,
+        _since: i64
+    ) -> Result<Vec<DailyStatsRow>, CoreError>;
+
+    async fn get_project_status(
+        &self,
+        _proj: Project
+    ) -> Result<ProjectStatusRow, CoreError>;
+
+    async fn increment_project_views(
+        &self,
+        _proj: Project,
+        _day: i64
+    ) -> Result<(), CoreError>;
+
+    async fn increment_file_downloads(
+        &self,
+        _pkg: Package,
+        _version: &str,
+        _filename: &str,
+        _day: i64
+    ) -> Result<(), CoreError>;
+
+    async fn get_library_stats(
+        &self
+    ) -> Result<LibraryStats, CoreError>;
 }
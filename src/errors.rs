@@ -1,3 +1,4 @@
+use serde_json::{Value, json};
 use thiserror::Error;
 
 use crate::core::CoreError;
@@ -8,17 +9,31 @@ pub enum AppError {
     #[error("Unsupported media type")]
     BadMimeType,
     #[error("Payload too large")]
-    TooLarge,
+    TooLarge(u64),
 //    #[error("Cannot remove last project owner")]
     #[error("Bad request")]
     CannotRemoveLastOwner,
+    #[error("Conflict")]
+    Conflict,
     #[error("{0}")]
     DatabaseError(String),
 // TODO: Internal error should have a string? cause?
+    #[error("Forbidden")]
+    Forbidden,
     #[error("Internal error")]
     InternalError,
+    #[error("Bad request")]
+    MalformedUpload,
+    #[error("Bad request")]
+    InvalidFilename,
+    #[error("Bad request")]
+    InvalidModule,
+    #[error("Bad request")]
+    ModuleVersionMismatch { expected: String, found: String },
     #[error("Unprocessable entity")]
     JsonError,
+    #[error("{0}")]
+    InvalidPackages(String),
     #[error("Bad request")]
     LimitOutOfRange,
     #[error("Bad request")]
@@ -29,6 +44,16 @@ pub enum AppError {
     NotAUser,
     #[error("Not found")]
     NotFound,
+    #[error("Permanent redirect")]
+    PermanentRedirect(String),
+    #[error("Project archived")]
+    ProjectArchived,
+    #[error("Precondition failed")]
+    PreconditionFailed,
+    #[error("Payload too large")]
+    TooManyFiles(usize),
+    #[error("Too many requests")]
+    TooManyRequests,
     #[error("Unauthorized")]
     Unauthorized
 }
@@ -37,21 +62,85 @@ impl From<CoreError> for AppError {
     fn from(err: CoreError) -> Self {
         match err {
             CoreError::BadMimeType => AppError::BadMimeType,
-            CoreError::TooLarge => AppError::TooLarge,
+            CoreError::TooLarge(max) => AppError::TooLarge(max),
             CoreError::CannotRemoveLastOwner => AppError::CannotRemoveLastOwner  ,
+            CoreError::FlagAlreadyResolved => AppError::Conflict,
+            CoreError::InvalidFilename => AppError::InvalidFilename,
+            CoreError::InvalidModule => AppError::InvalidModule,
+            CoreError::ModuleVersionMismatch { expected, found } =>
+                AppError::ModuleVersionMismatch { expected, found },
+            CoreError::InvalidPackageSection => AppError::JsonError,
+            e @ CoreError::InvalidPackages(_) =>
+                AppError::InvalidPackages(e.to_string()),
+            CoreError::InvalidYear => AppError::JsonError,
             CoreError::InvalidProjectName => AppError::MalformedQuery, // FIXME
-            CoreError::ProjectNameInUse => AppError::MalformedQuery, // FIXME
+            CoreError::InvalidPackageName => AppError::MalformedQuery,
+            CoreError::ProjectNameInUse => AppError::Conflict,
+            CoreError::PackageNameInUse => AppError::Conflict,
+            // handled by the extractors, which know the request path
+            CoreError::ProjectMoved(_) => AppError::NotFound,
+            CoreError::ProjectHasReleases => AppError::Conflict,
+            CoreError::PackageHasReleases => AppError::Conflict,
+            CoreError::ImageInUse => AppError::Conflict,
+            CoreError::ProjectArchived => AppError::ProjectArchived,
+            CoreError::InvalidSupersession => AppError::JsonError,
+            CoreError::RevisionMismatch => AppError::PreconditionFailed,
+            CoreError::LimitOutOfRange => AppError::LimitOutOfRange,
             CoreError::MalformedQuery => AppError::MalformedQuery,
             CoreError::NotFound => AppError::NotFound,
+            CoreError::NotAFlag => AppError::NotFound,
             CoreError::NotAPackage => AppError::NotFound,
+            CoreError::NotAPlayer => AppError::Forbidden,
             CoreError::NotAProject => AppError::NotFound,
             CoreError::NotARevision => AppError::NotFound,
             CoreError::NotAUser => AppError::NotAUser,
             CoreError::NotAVersion => AppError::NotFound,
             CoreError::InternalError => AppError::InternalError,
+            CoreError::IntegrityError => AppError::InternalError,
             CoreError::DatabaseError(e) => AppError::DatabaseError(e.to_string()),
             CoreError::TimeError(_) => AppError::InternalError,
             CoreError::SeekError(_) => AppError::InternalError
         }
     }
 }
+
+impl AppError {
+    // Uploaders see these errors most, so upload errors say what would
+    // put them right; everything else has no details.
+    pub fn details(&self) -> Value {
+        match self {
+            AppError::TooLarge(max) => json!({ "max_size": max }),
+            AppError::TooManyFiles(max) => json!({ "max_files": max }),
+            AppError::InvalidFilename => json!({
+                "hint": "filenames must not contain path separators"
+            }),
+            AppError::InvalidModule => json!({
+                "hint": "modules must be zip archives containing moduledata"
+            }),
+            AppError::ModuleVersionMismatch { expected, found } => json!({
+                "expected": expected,
+                "found": found,
+                "hint": "the module version must match the release version"
+            }),
+            AppError::BadMimeType |
+            AppError::CannotRemoveLastOwner |
+            AppError::Conflict |
+            AppError::DatabaseError(_) |
+            AppError::Forbidden |
+            AppError::InternalError |
+            AppError::MalformedUpload |
+            AppError::JsonError |
+            AppError::InvalidPackages(_) |
+            AppError::LimitOutOfRange |
+            AppError::MalformedQuery |
+            AppError::MalformedVersion |
+            AppError::NotAUser |
+            AppError::NotFound |
+            AppError::PermanentRedirect(_) |
+            AppError::ProjectArchived |
+            AppError::PreconditionFailed |
+            AppError::TooManyRequests |
+            AppError::Unauthorized => Value::Null
+        }
+    }
+}
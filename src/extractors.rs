@@ -4,7 +4,7 @@ use axum::{
         FromRequest, FromRequestParts, FromRef, Path, Request, State,
         rejection::{JsonRejection, QueryRejection}
     },
-    http::request::Parts
+    http::{Uri, request::Parts}
 };
 use axum_extra::{
     TypedHeader,
@@ -18,10 +18,12 @@ use itertools::Itertools;
 use unwrap_infallible::UnwrapInfallible;
 
 use crate::{
-    core::CoreArc,
+    core::{CoreArc, CoreError},
     errors::AppError,
+    forum::{ForumService, ServiceTokens},
     jwt::{self, Claims, DecodingKey},
-    model::{Owned, Owner, Package, Project, User},
+    model::{AdminUser, Owned, OwnedOrAdmin, Owner, Package, Project, User},
+    upload::require_filename,
     version::Version
 };
 
@@ -74,6 +76,56 @@ where
     }
 }
 
+#[async_trait]
+impl<S> FromRequestParts<S> for AdminUser
+where
+    S: Send + Sync,
+    DecodingKey: FromRef<S>
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &S
+    ) -> Result<Self, Self::Rejection>
+    {
+        // check that the requester is authorized
+        let claims = Claims::from_request_parts(parts, state).await?;
+
+        // check that the requester is an admin
+        match claims.admin {
+            true => Ok(AdminUser(claims.sub)),
+            false => Err(AppError::Forbidden)
+        }
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for ForumService
+where
+    S: Send + Sync,
+    ServiceTokens: FromRef<S>
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &S
+    ) -> Result<Self, Self::Rejection>
+    {
+        // the forum authenticates with a shared token, not a user JWT
+        let TypedHeader(Authorization(bearer)) = parts
+            .extract::<TypedHeader::<Authorization<Bearer>>>()
+            .await
+            .or(Err(AppError::Unauthorized))?;
+
+        match ServiceTokens::from_ref(state).accepts(bearer.token()) {
+            true => Ok(ForumService),
+            false => Err(AppError::Unauthorized)
+        }
+    }
+}
+
 async fn get_state<S>(
     parts: &mut Parts,
     state: &S
@@ -88,23 +140,197 @@ where
         .0
 }
 
-async fn get_path_iter<S>(
+async fn get_path_param<S>(
     parts: &mut Parts,
-    state: &S
-) -> Result<impl Iterator<Item=String>, AppError>
+    state: &S,
+    name: &str
+) -> Result<String, AppError>
 where
-    S: Send + Sync,
-    CoreArc: FromRef<S>
+    S: Send + Sync
 {
-    // extract however many path elements there are; should never fail
-    Ok(
-        Path::<Vec<(String, String)>>::from_request_parts(parts, state)
-            .await
-            .map_err(|_| AppError::InternalError)?
-            .0
-            .into_iter()
-            .map(|p| p.1)
-    )
+    // path elements are percent-decoded; failing that, they're malformed
+    Path::<Vec<(String, String)>>::from_request_parts(parts, state)
+        .await
+        .or(Err(AppError::MalformedQuery))?
+        .0
+        .into_iter()
+        .find(|(k, _)| k == name)
+        .map(|(_, v)| v)
+        // a route without the element is a bug, not a bad request
+        .ok_or(AppError::InternalError)
+}
+
+// Nothing longer than this can name a project or a package, so longer
+// path elements are refused before they reach the database.
+const MAX_SLUG_LENGTH: usize = 64;
+
+const MAX_FILENAME_LENGTH: usize = 255;
+
+fn check_slug(slug: String) -> Result<String, AppError> {
+    if slug.is_empty() ||
+        slug.chars().count() > MAX_SLUG_LENGTH ||
+        slug.chars().any(|c| c.is_control() || c == '/')
+    {
+        Err(AppError::MalformedQuery)
+    }
+    else {
+        Ok(slug)
+    }
+}
+
+fn check_filename(filename: String) -> Result<String, AppError> {
+    // a filename must be a single path component
+    if filename.len() > MAX_FILENAME_LENGTH ||
+        filename.chars().any(char::is_control) ||
+        require_filename(&filename).is_err()
+    {
+        Err(AppError::MalformedQuery)
+    }
+    else {
+        Ok(filename)
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct ProjectSlug(pub String);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for ProjectSlug
+where
+    S: Send + Sync
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &S
+    ) -> Result<Self, Self::Rejection>
+    {
+        let proj = get_path_param(parts, state, "proj").await?;
+        Ok(ProjectSlug(check_slug(proj)?))
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct PackageSlug(pub String);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for PackageSlug
+where
+    S: Send + Sync
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &S
+    ) -> Result<Self, Self::Rejection>
+    {
+        let pkg = get_path_param(parts, state, "pkg_name").await?;
+        Ok(PackageSlug(check_slug(pkg)?))
+    }
+}
+
+// usernames come from the forum, and are held to the same rules as slugs
+#[derive(Debug, Eq, PartialEq)]
+pub struct Username(pub String);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for Username
+where
+    S: Send + Sync
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &S
+    ) -> Result<Self, Self::Rejection>
+    {
+        let username = get_path_param(parts, state, "username").await?;
+        Ok(Username(check_slug(username)?))
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct VersionParam(pub Version);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for VersionParam
+where
+    S: Send + Sync
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &S
+    ) -> Result<Self, Self::Rejection>
+    {
+        get_path_param(parts, state, "version")
+            .await?
+            .parse::<Version>()
+            .map(VersionParam)
+            .or(Err(AppError::MalformedVersion))
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct FileName(pub String);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for FileName
+where
+    S: Send + Sync
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &S
+    ) -> Result<Self, Self::Rejection>
+    {
+        let filename = get_path_param(parts, state, "img_name").await?;
+        Ok(FileName(check_filename(filename)?))
+    }
+}
+
+fn moved_location(uri: &Uri, old_name: &str, new_name: &str) -> String {
+    // replace the project name which follows the projects path segment
+    let segs = uri.path().split('/').collect::<Vec<_>>();
+
+    let path = segs.iter()
+        .enumerate()
+        .map(|(i, seg)|
+            if *seg == old_name && i > 0 && segs[i - 1] == "projects" {
+                new_name
+            }
+            else {
+                seg
+            }
+        )
+        .join("/");
+
+    match uri.query() {
+        Some(query) => format!("{path}?{query}"),
+        None => path
+    }
+}
+
+async fn lookup_project(
+    core: &CoreArc,
+    parts: &Parts,
+    proj: &str
+) -> Result<Project, AppError>
+{
+    match core.get_project_id(proj).await {
+        Ok(proj) => Ok(proj),
+        // send requests for renamed projects to the current name
+        Err(CoreError::ProjectMoved(name)) => Err(
+            AppError::PermanentRedirect(moved_location(&parts.uri, proj, &name))
+        ),
+        Err(e) => Err(e.into())
+    }
 }
 
 #[async_trait]
@@ -120,15 +346,13 @@ where
         state: &S
     ) -> Result<Self, Self::Rejection>
     {
-        let (proj, ) = get_path_iter(parts, state)
-            .await?
-            .next_tuple()
-            .ok_or(AppError::InternalError)?;
+        let ProjectSlug(proj) = ProjectSlug::from_request_parts(parts, state)
+            .await?;
 
         let core = get_state(parts, state).await;
 
         // look up the project id
-        Ok(core.get_project_id(&proj).await?)
+        lookup_project(&core, parts, &proj).await
     }
 }
 
@@ -164,16 +388,16 @@ where
         state: &S
     ) -> Result<Self, Self::Rejection>
     {
-        let (proj, pkg) = get_path_iter(parts, state)
-            .await?
-            .next_tuple()
-            .ok_or(AppError::InternalError)?;
+        let ProjectSlug(proj) = ProjectSlug::from_request_parts(parts, state)
+            .await?;
+        let PackageSlug(pkg) = PackageSlug::from_request_parts(parts, state)
+            .await?;
 
         let core = get_state(parts, state).await;
 
 // TODO: could combine project-package lookup?
         // look up the project id
-        let proj = core.get_project_id(&proj).await?;
+        let proj = lookup_project(&core, parts, &proj).await?;
 
         // look up the package id
         let pkg = core.get_package_id(proj, &pkg).await?;
@@ -197,23 +421,12 @@ where
         state: &S
     ) -> Result<Self, Self::Rejection>
     {
-        let (proj, pkg, ver) = get_path_iter(parts, state)
-            .await?
-            .next_tuple()
-            .ok_or(AppError::InternalError)?;
-
-        let core = get_state(parts, state).await;
+        // check the version before looking anything up
+        let VersionParam(ver) = VersionParam::from_request_parts(parts, state)
+            .await?;
 
-// TODO: could combine project-package lookup?
-        // look up the project id
-        let proj = core.get_project_id(&proj).await?;
-
-        // look up the package id
-        let pkg = core.get_package_id(proj, &pkg).await?;
-
-        // parse the version
-        let ver = ver.parse::<Version>()
-            .or(Err(AppError::NotFound))?;
+        let ProjectPackage(proj, pkg) =
+            ProjectPackage::from_request_parts(parts, state).await?;
 
         Ok(ProjectPackageVersion(proj, pkg, ver))
     }
@@ -249,6 +462,42 @@ where
     }
 }
 
+#[async_trait]
+impl<S> FromRequestParts<S> for OwnedOrAdmin
+where
+    S: Send + Sync,
+    DecodingKey: FromRef<S>,
+    CoreArc: FromRef<S>
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &S
+    ) -> Result<Self, Self::Rejection>
+    {
+        // check that the requester is authorized
+        let claims = Claims::from_request_parts(parts, state).await?;
+        let user = User(claims.sub);
+
+        // check that that project exists
+        let proj = Project::from_request_parts(parts, state).await?;
+
+        // admins may see any project
+        if claims.admin {
+            return Ok(OwnedOrAdmin(user, proj));
+        }
+
+        let core = get_state(parts, state).await;
+
+        // others should not learn that the resource exists
+        match core.user_is_owner(user, proj).await? {
+            true => Ok(OwnedOrAdmin(user, proj)),
+            false => Err(AppError::NotFound)
+        }
+    }
+}
+
 impl From<JsonRejection> for AppError {
     fn from(err: JsonRejection) -> Self {
         match err {
@@ -322,7 +571,10 @@ mod test {
         app::AppState,
         core::{Core, CoreError},
         jwt::EncodingKey,
-        model::Users
+        maintenance::Maintenance,
+        model::Users,
+        ratelimit::RateLimiter,
+        robots::RobotsTxt
     };
 
     const KEY: &[u8] = b"@wlD+3L)EHdv28u)OFWx@83_*TxhVf9IdUncaAz6ICbM~)j+dH=sR2^LXp(tW31z";
@@ -331,7 +583,8 @@ mod test {
         Claims {
             sub: 1,
             exp: 899999999999,
-            iat: 0
+            iat: 0,
+            admin: false
         }
     }
 
@@ -339,7 +592,8 @@ mod test {
         Claims {
             sub: 1,
             exp: 0,
-            iat: 0
+            iat: 0,
+            admin: false
         }
     }
 
@@ -348,12 +602,127 @@ mod test {
         let token = jwt::issue(
             &ekey,
             claims.sub,
+            claims.admin,
             claims.iat,
             claims.exp
         ).unwrap();
         format!("Bearer {token}")
     }
 
+    #[test]
+    fn moved_location_project() {
+        assert_eq!(
+            moved_location(
+                &"/api/v1/projects/old_name".parse::<Uri>().unwrap(),
+                "old_name",
+                "new_name"
+            ),
+            "/api/v1/projects/new_name"
+        );
+    }
+
+    #[test]
+    fn moved_location_subpath_query() {
+        assert_eq!(
+            moved_location(
+                &"/api/v1/projects/projects/packages/projects?x=projects"
+                    .parse::<Uri>()
+                    .unwrap(),
+                "projects",
+                "new_name"
+            ),
+            "/api/v1/projects/new_name/packages/projects?x=projects"
+        );
+    }
+
+    #[test]
+    fn check_slug_ok() {
+        assert_eq!(check_slug("a_project".into()).unwrap(), "a_project");
+    }
+
+    #[test]
+    fn check_slug_max_length() {
+        // the limit is on characters, not bytes
+        let slug = "é".repeat(MAX_SLUG_LENGTH);
+        assert_eq!(check_slug(slug.clone()).unwrap(), slug);
+    }
+
+    #[test]
+    fn check_slug_non_ascii() {
+        // names which predate the ASCII rule for new projects must still
+        // be reachable
+        assert_eq!(
+            check_slug("宇宙戦艦ヤマト".into()).unwrap(),
+            "宇宙戦艦ヤマト"
+        );
+    }
+
+    #[test]
+    fn check_slug_too_long() {
+        assert_eq!(
+            check_slug("x".repeat(MAX_SLUG_LENGTH + 1)).unwrap_err(),
+            AppError::MalformedQuery
+        );
+    }
+
+    #[test]
+    fn check_slug_empty() {
+        assert_eq!(check_slug("".into()).unwrap_err(), AppError::MalformedQuery);
+    }
+
+    #[test]
+    fn check_slug_control() {
+        assert_eq!(
+            check_slug("a\nproject".into()).unwrap_err(),
+            AppError::MalformedQuery
+        );
+    }
+
+    #[test]
+    fn check_slug_slash() {
+        assert_eq!(
+            check_slug("a/project".into()).unwrap_err(),
+            AppError::MalformedQuery
+        );
+    }
+
+    #[test]
+    fn check_filename_ok() {
+        assert_eq!(check_filename("img.png".into()).unwrap(), "img.png");
+    }
+
+    #[test]
+    fn check_filename_too_long() {
+        assert_eq!(
+            check_filename("x".repeat(MAX_FILENAME_LENGTH + 1)).unwrap_err(),
+            AppError::MalformedQuery
+        );
+    }
+
+    #[test]
+    fn check_filename_path() {
+        assert_eq!(
+            check_filename("../img.png".into()).unwrap_err(),
+            AppError::MalformedQuery
+        );
+        assert_eq!(
+            check_filename("a/img.png".into()).unwrap_err(),
+            AppError::MalformedQuery
+        );
+        assert_eq!(
+            check_filename("..".into()).unwrap_err(),
+            AppError::MalformedQuery
+        );
+    }
+
+    #[test]
+    fn check_filename_control() {
+        assert_eq!(
+            check_filename("img\0.png".into()).unwrap_err(),
+            AppError::MalformedQuery
+        );
+    }
+
     #[tokio::test]
     async fn claims_from_request_parts_ok() {
         let exp = bob_ok();
@@ -540,10 +909,127 @@ mod test {
         assert!(act.is_err());
     }
 
+    #[tokio::test]
+    async fn admin_user_from_request_parts_ok() {
+        let exp = Claims { admin: true, ..bob_ok() };
+        let dkey = DecodingKey::from_secret(KEY);
+
+        let request = Request::builder()
+            .method(Method::GET)
+            .uri("/")
+            .header(AUTHORIZATION, token(KEY, &exp))
+            .body(())
+            .unwrap();
+
+        let mut parts;
+        (parts, _) = request.into_parts();
+
+        let act = AdminUser::from_request_parts(&mut parts, &dkey)
+            .await
+            .unwrap();
+        assert_eq!(act, AdminUser(exp.sub));
+    }
+
+    #[tokio::test]
+    async fn admin_user_from_request_parts_not_admin() {
+        let exp = bob_ok();
+        let dkey = DecodingKey::from_secret(KEY);
+
+        let request = Request::builder()
+            .method(Method::GET)
+            .uri("/")
+            .header(AUTHORIZATION, token(KEY, &exp))
+            .body(())
+            .unwrap();
+
+        let mut parts;
+        (parts, _) = request.into_parts();
+
+        let act = AdminUser::from_request_parts(&mut parts, &dkey).await;
+        assert_eq!(act.unwrap_err(), AppError::Forbidden);
+    }
+
+    #[tokio::test]
+    async fn admin_user_from_request_parts_no_auth_header() {
+        let dkey = DecodingKey::from_secret(KEY);
+
+        let request = Request::builder()
+            .method(Method::GET)
+            .uri("/")
+            .body(())
+            .unwrap();
+
+        let mut parts;
+        (parts, _) = request.into_parts();
+
+        let act = AdminUser::from_request_parts(&mut parts, &dkey).await;
+        assert_eq!(act.unwrap_err(), AppError::Unauthorized);
+    }
+
+    #[tokio::test]
+    async fn forum_service_from_request_parts_ok() {
+        let tokens = ServiceTokens::new(&["old", "new"]);
+
+        for token in ["old", "new"] {
+            let request = Request::builder()
+                .method(Method::GET)
+                .uri("/")
+                .header(AUTHORIZATION, format!("Bearer {token}"))
+                .body(())
+                .unwrap();
+
+            let mut parts;
+            (parts, _) = request.into_parts();
+
+            let act = ForumService::from_request_parts(&mut parts, &tokens)
+                .await;
+            assert_eq!(act.unwrap(), ForumService);
+        }
+    }
+
+    #[tokio::test]
+    async fn forum_service_from_request_parts_wrong_token() {
+        let tokens = ServiceTokens::new(&["new"]);
+
+        let request = Request::builder()
+            .method(Method::GET)
+            .uri("/")
+            .header(AUTHORIZATION, "Bearer old")
+            .body(())
+            .unwrap();
+
+        let mut parts;
+        (parts, _) = request.into_parts();
+
+        let act = ForumService::from_request_parts(&mut parts, &tokens).await;
+        assert_eq!(act.unwrap_err(), AppError::Unauthorized);
+    }
+
+    #[tokio::test]
+    async fn forum_service_from_request_parts_no_auth_header() {
+        let tokens = ServiceTokens::new(&["new"]);
+
+        let request = Request::builder()
+            .method(Method::GET)
+            .uri("/")
+            .body(())
+            .unwrap();
+
+        let mut parts;
+        (parts, _) = request.into_parts();
+
+        let act = ForumService::from_request_parts(&mut parts, &tokens).await;
+        assert_eq!(act.unwrap_err(), AppError::Unauthorized);
+    }
+
     fn make_state(core: impl Core + Send + Sync + 'static) -> AppState {
         AppState {
             key: DecodingKey::from_secret(KEY),
-            core: Arc::new(core) as CoreArc
+            core: Arc::new(core) as CoreArc,
+            robots: RobotsTxt::new("", true),
+            forum_tokens: ServiceTokens::default(),
+            maintenance: Maintenance::default(),
+            rate_limiter: RateLimiter::default()
         }
     }
 
@@ -623,6 +1109,112 @@ mod test {
         assert_eq!(response.status(), StatusCode::NOT_FOUND);
     }
 
+    #[tokio::test]
+    async fn project_id_from_request_parts_overlong() {
+        let app = Router::new()
+            .route("/:proj", get(project_fail))
+            .with_state(make_state(ProjectTestCore {}));
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method(Method::GET)
+                    .uri(format!("/{}", "x".repeat(MAX_SLUG_LENGTH + 1)))
+                    .body(Body::empty())
+                    .unwrap()
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn project_id_from_request_parts_bad_encoding() {
+        let app = Router::new()
+            .route("/:proj", get(project_fail))
+            .with_state(make_state(ProjectTestCore {}));
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method(Method::GET)
+                    .uri("/%FF")
+                    .body(Body::empty())
+                    .unwrap()
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    async fn version_ok(VersionParam(version): VersionParam) {
+        assert_eq!(version, "1.2.3".parse::<Version>().unwrap());
+    }
+
+    async fn version_fail(_version: VersionParam) {
+        unreachable!();
+    }
+
+    #[tokio::test]
+    async fn version_param_from_request_parts_ok() {
+        let app = Router::new().route("/:version", get(version_ok));
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method(Method::GET)
+                    .uri("/1.2.3")
+                    .body(Body::empty())
+                    .unwrap()
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn version_param_from_request_parts_malformed() {
+        let app = Router::new().route("/:version", get(version_fail));
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method(Method::GET)
+                    .uri("/bogus")
+                    .body(Body::empty())
+                    .unwrap()
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    async fn filename_fail(_filename: FileName) {
+        unreachable!();
+    }
+
+    #[tokio::test]
+    async fn filename_from_request_parts_path() {
+        let app = Router::new().route("/:img_name", get(filename_fail));
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method(Method::GET)
+                    .uri("/..%2Fimg.png")
+                    .body(Body::empty())
+                    .unwrap()
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
     // We have to test Owner::from_request_parts via a Router because
     // Path uses a private extension to get parameters from the request
 
@@ -704,7 +1296,8 @@ mod test {
         let exp = Claims {
             sub: 2,
             exp: 899999999999,
-            iat: 0
+            iat: 0,
+            admin: false
         };
 
         let app = Router::new()
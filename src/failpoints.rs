@@ -0,0 +1,56 @@
+// Failure injection for exercising error paths in tests. Failpoints are
+// compiled in only with the "failpoints" feature; otherwise fail_point!
+// expands to nothing.
+//
+// A failpoint is active if it has been enabled on the current thread or
+// if it is named in the comma-separated GLS_FAILPOINTS env var.
+
+#[cfg(feature = "failpoints")]
+use std::{
+    cell::RefCell,
+    collections::HashSet,
+    env
+};
+
+#[cfg(feature = "failpoints")]
+thread_local! {
+    static ACTIVE: RefCell<HashSet<String>> = RefCell::new(HashSet::new());
+}
+
+#[cfg(all(test, feature = "failpoints"))]
+pub fn enable(name: &str) {
+    ACTIVE.with(|a| a.borrow_mut().insert(name.into()));
+}
+
+#[cfg(feature = "failpoints")]
+pub fn is_active(name: &str) -> bool {
+    ACTIVE.with(|a| a.borrow().contains(name)) ||
+    env::var("GLS_FAILPOINTS")
+        .is_ok_and(|v| v.split(',').any(|f| f.trim() == name))
+}
+
+#[cfg(feature = "failpoints")]
+macro_rules! fail_point {
+    ($name:literal) => {
+        if $crate::failpoints::is_active($name) {
+            return Err($crate::core::CoreError::InternalError);
+        }
+    };
+}
+
+#[cfg(not(feature = "failpoints"))]
+macro_rules! fail_point {
+    ($name:literal) => {};
+}
+
+#[cfg(all(test, feature = "failpoints"))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn enable_is_active() {
+        assert!(!is_active("failpoints_test"));
+        enable("failpoints_test");
+        assert!(is_active("failpoints_test"));
+    }
+}
@@ -0,0 +1,2 @@
+-- a timestamp which cannot be converted to a date
+UPDATE projects SET modified_at = -1 WHERE project_id = 6;
@@ -0,0 +1,13 @@
+INSERT INTO flags (
+  flag_id,
+  user_id,
+  project_id,
+  flag,
+  message,
+  flagged_at,
+  resolved_at,
+  resolved_by
+)
+VALUES
+  (1, 2, 42, "inappropriate", "rude words", 1703569006419538067, NULL, NULL),
+  (2, 3, 6, "spam", NULL, 1702569006419538067, 1704569006419538067, 1);
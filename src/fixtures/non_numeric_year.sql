@@ -0,0 +1,2 @@
+-- a year which is not a number
+UPDATE projects SET game_year = 'c. 1979' WHERE project_id = 6;
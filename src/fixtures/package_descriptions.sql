@@ -0,0 +1,2 @@
+UPDATE packages SET description = "The module" WHERE package_id = 1;
+UPDATE packages SET description = "Extra scenarios" WHERE package_id = 3;
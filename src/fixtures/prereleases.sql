@@ -0,0 +1,65 @@
+INSERT INTO releases (
+  release_id,
+  package_id,
+  version,
+  version_major,
+  version_minor,
+  version_patch,
+  version_pre,
+  version_build,
+  url,
+  filename,
+  size,
+  checksum,
+  published_at,
+  published_by
+)
+VALUES
+  (
+    4,
+    1,
+    "1.2.4-rc.1",
+    1,
+    2,
+    4,
+    "rc.1",
+    "",
+    "https://example.com/a_package-1.2.4-rc.1",
+    "a_package-1.2.4-rc.1",
+    5670,
+    "6d1f4e1b8c0b8a3a0b7c2e9f5d3a1c4e7b9f2a6d8c0e3b5a7f1d9c2e4b6a8f0d",
+    1702300000000000000,
+    2
+  ),
+  (
+    5,
+    2,
+    "2.0.0-beta",
+    2,
+    0,
+    0,
+    "beta",
+    "",
+    "https://example.com/b_package-2.0.0-beta",
+    "b_package-2.0.0-beta",
+    4567,
+    "2b4d6f8a0c2e4a6c8e0a2c4e6a8c0e2a4c6e8a0c2e4a6c8e0a2c4e6a8c0e2a4c",
+    1702300000000000000,
+    1
+  ),
+  (
+    6,
+    2,
+    "1.9.0",
+    1,
+    9,
+    0,
+    "",
+    "",
+    "https://example.com/b_package-1.9.0",
+    "b_package-1.9.0",
+    4566,
+    "9e7c5a3f1d9b7e5c3a1f9d7b5e3c1a9f7d5b3e1c9a7f5d3b1e9c7a5f3d1b9e7c",
+    1702200000000000000,
+    1
+  );
@@ -0,0 +1,10 @@
+INSERT INTO tags (
+  project_id,
+  tag
+)
+VALUES
+  (2, "even"),
+  (4, "even"),
+  (6, "even"),
+  (8, "even"),
+  (10, "even");
@@ -0,0 +1,125 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+
+// The forum integration is versioned separately from the main API, so
+// that the forum is not broken by changes made there. Bump this only
+// when the shape of ForumUserSummary changes incompatibly.
+pub const FORUM_SUMMARY_VERSION: u32 = 1;
+
+pub const FORUM_SUMMARY_MAX_ITEMS: usize = 5;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct ForumService;
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ForumProject {
+    pub name: String,
+    pub title: String,
+    pub description: String,
+    pub image: Option<String>,
+    pub modified_at: String
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ForumRelease {
+    pub project: String,
+    pub package: String,
+    pub version: String,
+    pub published_at: String
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ForumUserSummary {
+    pub version: u32,
+    pub username: String,
+    pub owned_projects: usize,
+    pub recent_releases: Vec<ForumRelease>,
+    pub projects: Vec<ForumProject>
+}
+
+// Service tokens are stored as digests so that comparing them takes the
+// same time regardless of the length or content of the presented token.
+// More than one token may be configured, so that the forum can move to
+// a new token before the old one is retired.
+#[derive(Clone, Debug, Default)]
+pub struct ServiceTokens(Arc<Vec<[u8; 32]>>);
+
+impl ServiceTokens {
+    pub fn new<S: AsRef<str>>(tokens: &[S]) -> Self {
+        ServiceTokens(
+            Arc::new(
+                tokens.iter()
+                    .map(|t| t.as_ref())
+                    .filter(|t| !t.is_empty())
+                    .map(|t| Sha256::digest(t.as_bytes()).into())
+                    .collect()
+            )
+        )
+    }
+
+    pub fn accepts(&self, token: &str) -> bool {
+        let digest: [u8; 32] = Sha256::digest(token.as_bytes()).into();
+
+        // check every token, so as not to reveal which one matched
+        self.0.iter()
+            .fold(false, |acc, t| acc | constant_time_eq(t, &digest))
+    }
+}
+
+fn constant_time_eq(a: &[u8; 32], b: &[u8; 32]) -> bool {
+    a.iter()
+        .zip(b.iter())
+        .fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn constant_time_eq_equal() {
+        assert!(constant_time_eq(&[7; 32], &[7; 32]));
+    }
+
+    #[test]
+    fn constant_time_eq_unequal() {
+        let mut b = [7; 32];
+        b[31] = 8;
+        assert!(!constant_time_eq(&[7; 32], &b));
+    }
+
+    #[test]
+    fn service_tokens_accepts_ok() {
+        let tokens = ServiceTokens::new(&["secret"]);
+        assert!(tokens.accepts("secret"));
+    }
+
+    #[test]
+    fn service_tokens_accepts_wrong() {
+        let tokens = ServiceTokens::new(&["secret"]);
+        assert!(!tokens.accepts("secrets"));
+        assert!(!tokens.accepts(""));
+    }
+
+    #[test]
+    fn service_tokens_accepts_rotation() {
+        let tokens = ServiceTokens::new(&["old", "new"]);
+        assert!(tokens.accepts("old"));
+        assert!(tokens.accepts("new"));
+        assert!(!tokens.accepts("other"));
+    }
+
+    #[test]
+    fn service_tokens_none_configured() {
+        let tokens = ServiceTokens::new::<&str>(&[]);
+        assert!(!tokens.accepts(""));
+        assert!(!tokens.accepts("secret"));
+    }
+
+    #[test]
+    fn service_tokens_empty_ignored() {
+        let tokens = ServiceTokens::new(&[""]);
+        assert!(!tokens.accepts(""));
+    }
+}
@@ -1,24 +1,44 @@
 use axum::{
     body::Bytes,
-    extract::{Path, Query, Request, State},
-    response::{Json, Redirect}
+    extract::{Extension, Multipart, OriginalUri, Path, Query, Request, State, multipart::Field},
+    http::{HeaderMap, Method, StatusCode, header::{ACCEPT, CONTENT_TYPE, HeaderName, IF_MATCH, LOCATION}},
+    response::{IntoResponse, Json, Redirect, Response}
 };
 use axum_extra::{
     TypedHeader,
-    headers::{ContentLength, ContentType}
+    headers::{ContentLength, ContentType, ETag, IfNoneMatch}
 };
 use futures::{Stream, TryStreamExt};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
 use std::io;
 
 use crate::{
+    atom::{self, ATOM_CONTENT_TYPE},
     core::CoreArc,
     errors::AppError,
-    extractors::{ProjectPackage, ProjectPackageVersion, Wrapper},
-    model::{Owned, Package, PackageDataPost, ProjectData, ProjectDataPatch, ProjectDataPost, Project, Projects, Users, User},
-    params::ProjectsParams,
+    extractors::{FileName, PackageSlug, ProjectPackage, ProjectPackageVersion, ProjectSlug, Username, VersionParam, Wrapper},
+    forum::{ForumService, ForumUserSummary},
+    maintenance::Maintenance,
+    model::{AdminUser, BadTimestamps, FileData, FileResult, FileResults, Flags, GalleryImage, Inbox, LibraryStats, MarkRead, MalformedYears, Owned, OwnedOrAdmin, PackageDataPatch, PackageDataPost, PopularProjects, PrimaryFile, RecentProjects, RecommendedRelease, ProjectData, ProjectDataPatch, ProjectDataPatchResult, ProjectDataPost, Project, ProjectDiff, ProjectRevisionBatch, ProjectRevisions, ProjectStats, ProjectStatus, ProjectsByName, Releases, SlugRecompute, Users, User, UserProfile, YearCorrection},
+    params::{FlagsParams, InboxParams, LimitParams, PackageDeleteParams, ProjectDeleteParams, ProjectNamesParams, ProjectsParams, ReadmeFormat, ReadmeParams, ReleasesParams, RevisionBatchParams, SlugRecomputeParams},
+    readme::{HTML_CONTENT_TYPE, MARKDOWN_CONTENT_TYPE},
+    robots::RobotsTxt,
     version::Version
 };
 
+pub const X_ROBOTS_TAG: HeaderName = HeaderName::from_static("x-robots-tag");
+
+pub const X_TOTAL_COUNT: HeaderName = HeaderName::from_static("x-total-count");
+
+pub const MAX_FILES_PER_REQUEST: usize = 20;
+
+pub const MAX_PROJECTS_PER_BATCH: usize = 100;
+
+// the largest file which may be uploaded, in bytes
+#[derive(Clone, Copy, Debug)]
+pub struct MaxFileSize(pub u64);
+
 pub async fn not_found() -> Result<(), AppError>
 {
     Err(AppError::NotFound)
@@ -28,39 +48,229 @@ pub async fn root_get() -> &'static str {
     "hello world"
 }
 
+pub async fn healthz_get() -> &'static str {
+    "ok"
+}
+
+pub async fn readyz_get(
+    State(maintenance): State<Maintenance>
+) -> Response
+{
+    if maintenance.enabled() {
+        maintenance.response()
+    }
+    else {
+        "ok".into_response()
+    }
+}
+
+pub async fn robots_get(
+    State(robots): State<RobotsTxt>
+) -> String
+{
+    robots.0.to_string()
+}
+
+fn make_etag(data: &[u8]) -> ETag {
+    format!("\"{:x}\"", Sha256::digest(data))
+        .parse()
+        .expect("a quoted hex digest is a valid ETag")
+}
+
+fn is_not_modified(
+    if_none_match: Option<TypedHeader<IfNoneMatch>>,
+    etag: &ETag
+) -> bool
+{
+    if_none_match.is_some_and(|TypedHeader(inm)| !inm.precondition_passes(etag))
+}
+
+fn etagged_json<T: Serialize>(
+    if_none_match: Option<TypedHeader<IfNoneMatch>>,
+    etag: ETag,
+    data: T
+) -> Response
+{
+    if is_not_modified(if_none_match, &etag) {
+        (StatusCode::NOT_MODIFIED, TypedHeader(etag)).into_response()
+    }
+    else {
+        (TypedHeader(etag), Json(data)).into_response()
+    }
+}
+
 pub async fn projects_get(
+    names: Option<Query<ProjectNamesParams>>,
     Wrapper(Query(params)): Wrapper<Query<ProjectsParams>>,
+    if_none_match: Option<TypedHeader<IfNoneMatch>>,
     State(core): State<CoreArc>
-) -> Result<Json<Projects>, AppError>
+) -> Result<Response, AppError>
 {
-    Ok(Json(core.get_projects(params).await?))
+    // a list of names looks up just those projects
+    if let Some(Query(ProjectNamesParams { names })) = names {
+        return projects_by_name(&names, &core)
+            .await
+            .map(IntoResponse::into_response);
+    }
+
+    let projects = core.get_projects(params).await?;
+
+    // a page has no revision of its own, so its tag is its content
+    let etag = make_etag(
+        &serde_json::to_vec(&projects).or(Err(AppError::InternalError))?
+    );
+
+    // keep crawlers from indexing listing pages, which are endless
+    Ok(
+        (
+            [
+                (X_ROBOTS_TAG, "noindex".to_string()),
+                (X_TOTAL_COUNT, projects.meta.total.to_string())
+            ],
+            etagged_json(if_none_match, etag, projects)
+        ).into_response()
+    )
+}
+
+async fn projects_by_name(
+    names: &str,
+    core: &CoreArc
+) -> Result<Json<ProjectsByName>, AppError>
+{
+    let names = names.split(',')
+        .filter(|n| !n.is_empty())
+        .map(String::from)
+        .collect::<Vec<_>>();
+
+    if names.len() > MAX_PROJECTS_PER_BATCH {
+        return Err(AppError::MalformedQuery);
+    }
+
+    Ok(Json(core.get_projects_by_name(&names).await?))
 }
 
 pub async fn project_get(
     proj: Project,
+    method: Method,
+    if_none_match: Option<TypedHeader<IfNoneMatch>>,
+    State(core): State<CoreArc>
+) -> Result<Response, AppError>
+{
+    let proj_data = core.get_project(proj).await?;
+
+    // most changes to a project make a new revision, but archiving,
+    // changing owners, and changes to linked projects do not, so those
+    // must be part of the tag also
+    let etag = make_etag(
+        format!(
+            "{}:{}:{}:{}:{}:{}",
+            proj_data.revision,
+            proj_data.modified_at,
+            proj_data.archived,
+            proj_data.owners.join(","),
+            proj_data.superseded_by.as_ref().map_or(
+                String::new(),
+                |s| format!("{}@{}", s.name, s.revision)
+            ),
+            proj_data.supersedes.join(",")
+        ).as_bytes()
+    );
+
+    let response = etagged_json(if_none_match, etag, proj_data);
+
+    // only sending the project counts as a view; HEAD requests and
+    // revalidations do not
+    if method != Method::HEAD && response.status() == StatusCode::OK {
+        core.record_project_view(proj).await;
+    }
+
+    Ok(response)
+}
+
+pub async fn projects_popular_get(
+    Wrapper(Query(params)): Wrapper<Query<LimitParams>>,
+    State(core): State<CoreArc>
+) -> Result<Json<PopularProjects>, AppError>
+{
+    Ok(Json(core.get_popular_projects(params.limit).await?))
+}
+
+pub async fn projects_recent_get(
+    Wrapper(Query(params)): Wrapper<Query<LimitParams>>,
+    State(core): State<CoreArc>
+) -> Result<Json<RecentProjects>, AppError>
+{
+    Ok(Json(core.get_recent_projects(params.limit).await?))
+}
+
+pub async fn project_random_get(
     State(core): State<CoreArc>
 ) -> Result<Json<ProjectData>, AppError>
 {
-    Ok(Json(core.get_project(proj).await?))
+    Ok(Json(core.get_random_project().await?))
 }
 
 pub async fn project_post(
     owner: User,
-    Path(proj): Path<String>,
+    ProjectSlug(proj): ProjectSlug,
+    OriginalUri(uri): OriginalUri,
     State(core): State<CoreArc>,
     Wrapper(Json(proj_data)): Wrapper<Json<ProjectDataPost>>
-) -> Result<(), AppError>
+) -> Result<Response, AppError>
+{
+    let proj = core.create_project(owner, &proj, &proj_data).await?;
+
+    // send back the project as stored, so clients need not fetch it
+    Ok(
+        (
+            StatusCode::CREATED,
+            [(LOCATION, uri.path().to_owned())],
+            Json(core.get_project(proj).await?)
+        )
+        .into_response()
+    )
+}
+
+fn if_match_revision(
+    headers: &HeaderMap
+) -> Result<Option<i64>, AppError>
 {
-    Ok(core.create_project(owner, &proj, &proj_data).await?)
+    // If-Match carries the quoted revision the client last saw; "*"
+    // matches any revision, and anything unparsable matches none
+    match headers.get(IF_MATCH).map(|v| v.to_str().map(str::trim)) {
+        None | Some(Ok("*")) => Ok(None),
+        Some(Ok(v)) => v.strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .and_then(|v| v.parse().ok())
+            .map(Some)
+            .ok_or(AppError::PreconditionFailed),
+        Some(Err(_)) => Err(AppError::PreconditionFailed)
+    }
 }
 
 pub async fn project_patch(
     Owned(owner, proj): Owned,
+    headers: HeaderMap,
     State(core): State<CoreArc>,
     Wrapper(Json(proj_data)): Wrapper<Json<ProjectDataPatch>>
+) -> Result<Json<ProjectDataPatchResult>, AppError>
+{
+    let expected_revision = if_match_revision(&headers)?;
+    Ok(
+        Json(
+            core.update_project(owner, proj, &proj_data, expected_revision)
+                .await?
+        )
+    )
+}
+
+pub async fn project_delete(
+    Owned(owner, proj): Owned,
+    Wrapper(Query(params)): Wrapper<Query<ProjectDeleteParams>>,
+    State(core): State<CoreArc>
 ) -> Result<(), AppError>
 {
-    Ok(core.update_project(owner, proj, &proj_data).await?)
+    Ok(core.delete_project(owner, proj, params.force).await?)
 }
 
 pub async fn project_revision_get(
@@ -72,6 +282,87 @@ pub async fn project_revision_get(
     Ok(Json(core.get_project_revision(proj, revision as i64).await?))
 }
 
+pub async fn project_diff_get(
+    proj: Project,
+    Path((_, from, to)): Path<(String, u32, u32)>,
+    State(core): State<CoreArc>
+) -> Result<Json<ProjectDiff>, AppError>
+{
+    Ok(Json(core.get_project_diff(proj, from as i64, to as i64).await?))
+}
+
+pub async fn project_stats_get(
+    proj: Project,
+    State(core): State<CoreArc>
+) -> Result<Json<ProjectStats>, AppError>
+{
+    Ok(Json(core.get_project_stats(proj).await?))
+}
+
+pub async fn project_status_get(
+    OwnedOrAdmin(_, proj): OwnedOrAdmin,
+    State(core): State<CoreArc>
+) -> Result<Json<ProjectStatus>, AppError>
+{
+    Ok(Json(core.get_project_status(proj).await?))
+}
+
+pub async fn library_stats_get(
+    State(core): State<CoreArc>
+) -> Result<Json<LibraryStats>, AppError>
+{
+    Ok(Json(core.get_library_stats().await?))
+}
+
+pub async fn project_releases_atom_get(
+    proj: Project,
+    State(core): State<CoreArc>
+) -> Result<([(HeaderName, &'static str); 1], String), AppError>
+{
+    Ok(
+        (
+            [(CONTENT_TYPE, ATOM_CONTENT_TYPE)],
+            atom::to_atom(&core.get_release_feed(proj).await?)
+        )
+    )
+}
+
+pub async fn project_readme_get(
+    proj: Project,
+    Wrapper(Query(params)): Wrapper<Query<ReadmeParams>>,
+    State(core): State<CoreArc>
+) -> Result<([(HeaderName, &'static str); 1], String), AppError>
+{
+    let content_type = match params.format {
+        ReadmeFormat::Markdown => MARKDOWN_CONTENT_TYPE,
+        ReadmeFormat::Html => HTML_CONTENT_TYPE
+    };
+
+    Ok(
+        (
+            [(CONTENT_TYPE, content_type)],
+            core.get_project_readme(proj, params.format).await?
+        )
+    )
+}
+
+pub async fn project_revisions_get(
+    proj: Project,
+    State(core): State<CoreArc>
+) -> Result<Json<ProjectRevisions>, AppError>
+{
+    Ok(Json(core.get_project_revisions(proj).await?))
+}
+
+pub async fn project_revision_batch_get(
+    proj: Project,
+    Wrapper(Query(params)): Wrapper<Query<RevisionBatchParams>>,
+    State(core): State<CoreArc>
+) -> Result<Json<ProjectRevisionBatch>, AppError>
+{
+    Ok(Json(core.get_project_revision_batch(proj, &params.nums).await?))
+}
+
 pub async fn owners_get(
     proj: Project,
     State(core): State<CoreArc>
@@ -124,9 +415,27 @@ pub async fn players_remove(
     Ok(core.remove_player(requester, proj).await?)
 }
 
+pub async fn subscribe_post(
+    requester: User,
+    proj: Project,
+    State(core): State<CoreArc>
+) -> Result<(), AppError>
+{
+    Ok(core.subscribe(requester, proj).await?)
+}
+
+pub async fn unsubscribe_post(
+    requester: User,
+    proj: Project,
+    State(core): State<CoreArc>
+) -> Result<(), AppError>
+{
+    Ok(core.unsubscribe(requester, proj).await?)
+}
+
 pub async fn packages_post(
     Owned(owner, proj): Owned,
-    Path((_, pkg)): Path<(String, String)>,
+    PackageSlug(pkg): PackageSlug,
     State(core): State<CoreArc>,
     Wrapper(Json(pkg_data)): Wrapper<Json<PackageDataPost>>
 ) -> Result<(), AppError>
@@ -134,26 +443,129 @@ pub async fn packages_post(
     Ok(core.create_package(owner, proj, &pkg, &pkg_data).await?)
 }
 
-// TODO
-//pub async fn packages_patch(
+pub async fn package_patch(
+    Owned(owner, _): Owned,
+    ProjectPackage(proj, pkg): ProjectPackage,
+    State(core): State<CoreArc>,
+    Wrapper(Json(pkg_data)): Wrapper<Json<PackageDataPatch>>
+) -> Result<(), AppError>
+{
+    Ok(core.update_package(owner, proj, pkg, &pkg_data).await?)
+}
+
+pub async fn packages_order_put(
+    Owned(owner, proj): Owned,
+    State(core): State<CoreArc>,
+    Wrapper(Json(order)): Wrapper<Json<Vec<String>>>
+) -> Result<(), AppError>
+{
+    Ok(core.reorder_packages(owner, proj, &order).await?)
+}
+
+pub async fn package_delete(
+    Owned(owner, _): Owned,
+    ProjectPackage(proj, pkg): ProjectPackage,
+    Wrapper(Query(params)): Wrapper<Query<PackageDeleteParams>>,
+    State(core): State<CoreArc>
+) -> Result<(), AppError>
+{
+    Ok(core.delete_package(owner, proj, pkg, params.force).await?)
+}
 
 pub async fn release_get(
+    ProjectPackage(proj, pkg): ProjectPackage,
+    headers: HeaderMap,
+    State(core): State<CoreArc>
+) -> Result<Response, AppError>
+{
+    // clients asking for JSON get the package data; all others get
+    // redirected to the latest release, as before
+    Ok(
+        if accepts_json(&headers) {
+            Json(core.get_package(proj, pkg).await?).into_response()
+        }
+        else {
+            Redirect::to(&core.get_release(proj, pkg).await?)
+                .into_response()
+        }
+    )
+}
+
+pub async fn release_latest_get(
     ProjectPackage(proj, pkg): ProjectPackage,
     State(core): State<CoreArc>
 ) -> Result<Redirect, AppError>
 {
-    Ok(Redirect::to(&core.get_release(proj, pkg).await?))
+    Ok(Redirect::to(&core.get_latest_release_url(proj, pkg).await?))
+}
+
+pub async fn release_recommended_get(
+    ProjectPackage(proj, pkg): ProjectPackage,
+    State(core): State<CoreArc>
+) -> Result<Redirect, AppError>
+{
+    Ok(Redirect::to(&core.get_recommended_release_url(proj, pkg).await?))
+}
+
+pub async fn release_recommended_put(
+    Owned(owner, _): Owned,
+    ProjectPackage(proj, pkg): ProjectPackage,
+    State(core): State<CoreArc>,
+    Wrapper(Json(recommended)): Wrapper<Json<RecommendedRelease>>
+) -> Result<(), AppError>
+{
+    let version = recommended.version.parse::<Version>()
+        .or(Err(AppError::MalformedVersion))?;
+
+    Ok(
+        core.set_recommended_release(owner, proj, pkg, Some(&version))
+            .await?
+    )
+}
+
+pub async fn release_recommended_delete(
+    Owned(owner, _): Owned,
+    ProjectPackage(proj, pkg): ProjectPackage,
+    State(core): State<CoreArc>
+) -> Result<(), AppError>
+{
+    Ok(core.set_recommended_release(owner, proj, pkg, None).await?)
+}
+
+pub async fn releases_get(
+    ProjectPackage(proj, pkg): ProjectPackage,
+    Wrapper(Query(params)): Wrapper<Query<ReleasesParams>>,
+    State(core): State<CoreArc>
+) -> Result<Json<Releases>, AppError>
+{
+    Ok(Json(core.get_releases(proj, pkg, params).await?))
+}
+
+fn accepts_json(headers: &HeaderMap) -> bool {
+    headers.get(ACCEPT)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.contains("application/json"))
 }
 
 pub async fn release_version_get(
     ProjectPackageVersion(proj, pkg, version): ProjectPackageVersion,
+    headers: HeaderMap,
     State(core): State<CoreArc>
-) -> Result<Redirect, AppError>
+) -> Result<Response, AppError>
 {
+    // clients asking for JSON get the release data; all others get
+    // redirected to the file, as before
     Ok(
-        Redirect::to(
-            &core.get_release_version(proj, pkg, &version).await?
-        )
+        if accepts_json(&headers) {
+            Json(core.get_release_data(proj, pkg, &version).await?)
+                .into_response()
+        }
+        else {
+            Redirect::to(
+                &core.get_release_version(proj, pkg, &version).await?
+            )
+            .into_response()
+        }
     )
 }
 
@@ -170,14 +582,12 @@ fn into_stream(
 
 pub async fn release_put(
     Owned(owner, proj): Owned,
-    Path((_, pkg, version)): Path<(String, String, String)>,
+    PackageSlug(pkg): PackageSlug,
+    VersionParam(version): VersionParam,
     State(core): State<CoreArc>,
     request: Request
 ) -> Result<(), AppError>
 {
-    let version = version.parse::<Version>()
-        .or(Err(AppError::NotFound))?;
-
 /*
     let stream = request.into_body()
         .into_data_stream()
@@ -191,9 +601,145 @@ pub async fn release_put(
     Ok(())
 }
 
+pub async fn release_delete(
+    Owned(owner, proj): Owned,
+    ProjectPackageVersion(_, pkg, version): ProjectPackageVersion,
+    State(core): State<CoreArc>
+) -> Result<(), AppError>
+{
+    Ok(core.delete_release(owner, proj, pkg, &version).await?)
+}
+
+fn file_result(
+    filename: String,
+    result: Result<FileData, AppError>
+) -> FileResult
+{
+    match result {
+        Ok(file) => FileResult {
+            filename,
+            status: StatusCode::CREATED.as_u16(),
+            file: Some(file),
+            error: None,
+            details: serde_json::Value::Null
+        },
+        Err(err) => FileResult {
+            filename,
+            status: StatusCode::from(&err).as_u16(),
+            file: None,
+            details: err.details(),
+            error: Some(err.to_string())
+        }
+    }
+}
+
+// A part which exceeds the limit is abandoned as soon as it does, rather
+// than being read in full first.
+async fn read_field(
+    mut field: Field<'_>,
+    limit: u64
+) -> Result<Bytes, AppError>
+{
+    let mut data = vec![];
+
+    while let Some(chunk) = field.chunk()
+        .await
+        .or(Err(AppError::MalformedUpload))?
+    {
+        if (data.len() + chunk.len()) as u64 > limit {
+            return Err(AppError::TooLarge(limit));
+        }
+        data.extend_from_slice(&chunk);
+    }
+
+    Ok(data.into())
+}
+
+pub async fn files_post(
+    Owned(owner, proj): Owned,
+    ProjectPackageVersion(_, pkg, version): ProjectPackageVersion,
+    Extension(MaxFileSize(max_file_size)): Extension<MaxFileSize>,
+    State(core): State<CoreArc>,
+    mut multipart: Multipart
+) -> Result<Json<FileResults>, AppError>
+{
+    let mut files = vec![];
+
+    // parts are processed in order; a bad part does not fail the batch
+    loop {
+        let field = match multipart.next_field().await {
+            Ok(Some(field)) => field,
+            Ok(None) => break,
+            // nothing has been stored, so the request as a whole failed
+            Err(_) if files.is_empty() => {
+                return Err(AppError::MalformedUpload);
+            },
+            // earlier parts have been stored, so report them along with
+            // the failure; the rest of the body cannot be read
+            Err(_) => {
+                files.push(
+                    file_result(String::new(), Err(AppError::MalformedUpload))
+                );
+                break;
+            }
+        };
+
+        let filename = field.file_name().unwrap_or_default().to_owned();
+
+        if files.len() >= MAX_FILES_PER_REQUEST {
+            files.push(
+                file_result(
+                    filename,
+                    Err(AppError::TooManyFiles(MAX_FILES_PER_REQUEST))
+                )
+            );
+            continue;
+        }
+
+        match read_field(field, max_file_size).await {
+            Ok(data) => {
+                let result = core.add_file(
+                    owner,
+                    proj,
+                    pkg,
+                    &version,
+                    &filename,
+                    data
+                ).await.map_err(AppError::from);
+
+                files.push(file_result(filename, result));
+            },
+            // the rest of an oversized part is skipped by the next read
+            Err(err @ AppError::TooLarge(_)) => {
+                files.push(file_result(filename, Err(err)));
+            },
+            Err(err) => {
+                // a part which cannot be read leaves the body unreadable
+                files.push(file_result(filename, Err(err)));
+                break;
+            }
+        }
+    }
+
+    Ok(Json(FileResults { files }))
+}
+
+pub async fn primary_file_put(
+    Owned(owner, proj): Owned,
+    ProjectPackageVersion(_, pkg, version): ProjectPackageVersion,
+    State(core): State<CoreArc>,
+    Wrapper(Json(primary)): Wrapper<Json<PrimaryFile>>
+) -> Result<(), AppError>
+{
+    Ok(
+        core.set_primary_file(owner, proj, pkg, &version, &primary.filename)
+            .await?
+    )
+}
+
 pub async fn image_get(
     proj: Project,
-    Path((_, img_name)): Path<(String, String)>,
+    FileName(img_name): FileName,
     State(core): State<CoreArc>
 ) -> Result<Redirect, AppError>
 {
@@ -202,7 +748,8 @@ pub async fn image_get(
 
 pub async fn image_revision_get(
     proj: Project,
-    Path((_, img_name, revision)): Path<(String, String, u32)>,
+    FileName(img_name): FileName,
+    Path((_, _, revision)): Path<(String, String, u32)>,
     State(core): State<CoreArc>
 ) -> Result<Redirect, AppError>
 {
@@ -215,7 +762,7 @@ pub async fn image_revision_get(
 
 pub async fn image_post(
     Owned(owner, proj): Owned,
-    Path((_, img_name)): Path<(String, String)>,
+    FileName(img_name): FileName,
     content_type: Option<TypedHeader<ContentType>>,
     content_length: Option<TypedHeader<ContentLength>>,
     State(core): State<CoreArc>,
@@ -236,6 +783,43 @@ pub async fn image_post(
     )
 }
 
+// Removing an image makes it unavailable; unsetting it as the cover image
+// is done by patching the project, and must be done first.
+pub async fn image_delete(
+    Owned(owner, proj): Owned,
+    FileName(img_name): FileName,
+    State(core): State<CoreArc>
+) -> Result<(), AppError>
+{
+    Ok(core.remove_image(owner, proj, &img_name).await?)
+}
+
+pub async fn gallery_post(
+    Owned(owner, proj): Owned,
+    FileName(img_name): FileName,
+    content_type: Option<TypedHeader<ContentType>>,
+    content_length: Option<TypedHeader<ContentLength>>,
+    State(core): State<CoreArc>,
+    request: Request
+) -> Result<(StatusCode, Json<GalleryImage>), AppError>
+{
+    Ok(
+        (
+            StatusCode::CREATED,
+            Json(
+                core.add_gallery_image(
+                    owner,
+                    proj,
+                    &img_name,
+                    &content_type.ok_or(AppError::BadMimeType)?.0.into(),
+                    content_length.map(|h| h.0.0),
+                    into_stream(request)
+                ).await?
+            )
+        )
+    )
+}
+
 pub async fn flag_post(
     _requester: User,
     _proj: Project,
@@ -244,3 +828,92 @@ pub async fn flag_post(
 {
     todo!();
 }
+
+pub async fn inbox_get(
+    user: User,
+    Wrapper(Query(params)): Wrapper<Query<InboxParams>>,
+    State(core): State<CoreArc>
+) -> Result<Json<Inbox>, AppError>
+{
+    Ok(Json(core.get_inbox(user, params).await?))
+}
+
+pub async fn inbox_read_post(
+    user: User,
+    State(core): State<CoreArc>,
+    Wrapper(Json(body)): Wrapper<Json<MarkRead>>
+) -> Result<(), AppError>
+{
+    Ok(core.mark_inbox_read(user, body.ids.as_deref()).await?)
+}
+
+pub async fn admin_flags_get(
+    _admin: AdminUser,
+    Wrapper(Query(params)): Wrapper<Query<FlagsParams>>,
+    State(core): State<CoreArc>
+) -> Result<Json<Flags>, AppError>
+{
+    Ok(Json(core.get_flags(&params.facets).await?))
+}
+
+pub async fn admin_flag_resolve_post(
+    AdminUser(admin): AdminUser,
+    Path(flag_id): Path<i64>,
+    State(core): State<CoreArc>
+) -> Result<(), AppError>
+{
+    Ok(core.resolve_flag(User(admin), flag_id).await?)
+}
+
+pub async fn admin_slugs_recompute_post(
+    _admin: AdminUser,
+    Wrapper(Query(params)): Wrapper<Query<SlugRecomputeParams>>,
+    State(core): State<CoreArc>
+) -> Result<Json<SlugRecompute>, AppError>
+{
+    Ok(Json(core.recompute_slugs(params.aliases).await?))
+}
+
+pub async fn admin_timestamps_get(
+    _admin: AdminUser,
+    State(core): State<CoreArc>
+) -> Result<Json<BadTimestamps>, AppError>
+{
+    Ok(Json(core.get_bad_timestamps().await?))
+}
+
+pub async fn admin_years_malformed_get(
+    _admin: AdminUser,
+    State(core): State<CoreArc>
+) -> Result<Json<MalformedYears>, AppError>
+{
+    Ok(Json(core.get_malformed_years().await?))
+}
+
+pub async fn admin_year_correct_post(
+    AdminUser(admin): AdminUser,
+    proj: Project,
+    State(core): State<CoreArc>,
+    Wrapper(Json(correction)): Wrapper<Json<YearCorrection>>
+) -> Result<(), AppError>
+{
+    Ok(core.correct_year(User(admin), proj, &correction.year).await?)
+}
+
+pub async fn user_profile_get(
+    Username(username): Username,
+    State(core): State<CoreArc>
+) -> Result<Json<UserProfile>, AppError>
+{
+    let user = core.get_user_id(&username).await?;
+    Ok(Json(core.get_user_profile(user).await?))
+}
+
+pub async fn forum_user_summary_get(
+    _service: ForumService,
+    Path(user_id): Path<i64>,
+    State(core): State<CoreArc>
+) -> Result<Json<ForumUserSummary>, AppError>
+{
+    Ok(Json(core.get_forum_user_summary(User(user_id)).await?))
+}
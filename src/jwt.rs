@@ -9,7 +9,9 @@ pub struct Error(#[from] jsonwebtoken::errors::Error);
 pub struct Claims {
     pub sub: i64,
     pub exp: u64,
-    pub iat: u64
+    pub iat: u64,
+    #[serde(default)]
+    pub admin: bool
 }
 
 #[derive(Clone)]
@@ -44,6 +46,7 @@ impl EncodingKey {
 pub fn issue(
     key: &EncodingKey,
     uid: i64,
+    admin: bool,
     now: u64,
     expiry: u64
 ) -> Result<String, Error>
@@ -51,7 +54,8 @@ pub fn issue(
     let claims = Claims {
         sub: uid,
         exp: expiry,
-        iat: now
+        iat: now,
+        admin
     };
 
     Ok(jsonwebtoken::encode(&Header::default(), &claims, &key.0)?)
@@ -1,14 +1,15 @@
 use axum::{
     Router, serve,
     body::{Body, Bytes},
-    extract::Request,
-    http::StatusCode,
+    extract::{DefaultBodyLimit, Extension, Request},
+    http::{StatusCode, header},
+    middleware,
     response::{IntoResponse, Json, Response},
-    routing::{get, post}
+    routing::{get, post, put}
 };
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
-use sqlx::sqlite::SqlitePoolOptions;
+use sqlx::sqlite::{SqlitePool, SqlitePoolOptions};
 use std::{
     fs,
     io,
@@ -21,34 +22,60 @@ use tower::ServiceBuilder;
 use tower_http::{
     compression::CompressionLayer,
     cors::CorsLayer,
+    decompression::RequestDecompressionLayer,
     timeout::TimeoutLayer
 };
 
 mod app;
+mod assets;
+mod atom;
 mod config;
+#[cfg(test)]
+mod contract;
 mod core;
+mod count;
 mod db;
 mod errors;
 mod extractors;
+#[macro_use]
+mod failpoints;
+mod forum;
 mod handlers;
 mod jwt;
+mod maintenance;
 mod model;
 mod module;
+mod notify;
+mod options;
 mod pagination;
 mod params;
 mod prod_core;
+mod ratelimit;
+mod readme;
+mod robots;
 mod sqlite;
 mod time;
 mod upload;
 mod version;
+mod year;
 
 use crate::{
     app::AppState,
+    assets::StaticAssets,
     config::Config,
     core::CoreArc,
+    count::CountCache,
     prod_core::ProdCore,
     errors::AppError,
+    forum::ServiceTokens,
+    robots::RobotsTxt,
     jwt::DecodingKey,
+    maintenance::Maintenance,
+    ratelimit::RateLimiter,
+    notify::{InboxSink, Notifier},
+    options::{Discovery, ReadOnly},
+    pagination::Limit,
+    readme::ReadmeCache,
     sqlite::SqlxDatabaseClient,
     upload::LocalUploader,
 };
@@ -57,16 +84,28 @@ impl From<&AppError> for StatusCode {
     fn from(err: &AppError) -> Self {
         match err {
             AppError::BadMimeType => StatusCode::UNSUPPORTED_MEDIA_TYPE,
-            AppError::TooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+            AppError::TooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
             AppError::CannotRemoveLastOwner => StatusCode::BAD_REQUEST,
+            AppError::Conflict => StatusCode::CONFLICT,
             AppError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            AppError::Forbidden => StatusCode::FORBIDDEN,
             AppError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            AppError::MalformedUpload => StatusCode::BAD_REQUEST,
+            AppError::InvalidFilename => StatusCode::BAD_REQUEST,
+            AppError::InvalidModule => StatusCode::BAD_REQUEST,
+            AppError::ModuleVersionMismatch { .. } => StatusCode::BAD_REQUEST,
             AppError::JsonError => StatusCode::UNPROCESSABLE_ENTITY,
+            AppError::InvalidPackages(_) => StatusCode::UNPROCESSABLE_ENTITY,
             AppError::LimitOutOfRange => StatusCode::BAD_REQUEST,
             AppError::MalformedQuery => StatusCode::BAD_REQUEST,
             AppError::MalformedVersion => StatusCode::BAD_REQUEST,
             AppError::NotAUser => StatusCode::NOT_FOUND,
             AppError::NotFound => StatusCode::NOT_FOUND,
+            AppError::PermanentRedirect(_) => StatusCode::PERMANENT_REDIRECT,
+            AppError::ProjectArchived => StatusCode::CONFLICT,
+            AppError::PreconditionFailed => StatusCode::PRECONDITION_FAILED,
+            AppError::TooManyFiles(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            AppError::TooManyRequests => StatusCode::TOO_MANY_REQUESTS,
             AppError::Unauthorized => StatusCode::UNAUTHORIZED
         }
     }
@@ -74,38 +113,121 @@ impl From<&AppError> for StatusCode {
 
 #[derive(Debug, Deserialize, PartialEq, Serialize)]
 struct HttpError {
-    error: String
+    error: String,
+    #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
+    details: serde_json::Value
 }
 
 impl From<AppError> for HttpError {
     fn from(err: AppError) -> Self {
-        HttpError { error: format!("{}", err) }
+        HttpError { error: format!("{}", err), details: err.details() }
     }
 }
 
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
         let code = StatusCode::from(&self);
-        let body = Json(HttpError::from(self));
-        (code, body).into_response()
+        match self {
+            AppError::PermanentRedirect(ref location) => {
+                let location = location.clone();
+                let body = Json(HttpError::from(self));
+                (code, [(header::LOCATION, location)], body).into_response()
+            },
+            _ => {
+                let body = Json(HttpError::from(self));
+                (code, body).into_response()
+            }
+        }
     }
 }
 
-fn routes(api: &str) -> Router<AppState> {
-    Router::new()
+fn resources(
+    api: &str,
+    read_only: bool,
+    max_file_size: u64
+) -> Router<AppState>
+{
+    let project_path = format!("{api}/projects/:proj");
+    let package_path = format!("{api}/projects/:proj/packages/:pkg_name");
+    let release_path = format!("{api}/projects/:proj/packages/:pkg_name/:version");
+    let recommended_path = format!("{api}/projects/:proj/packages/:pkg_name/recommended");
+    let image_path = format!("{api}/projects/:proj/images/:img_name");
+
+    let router = Router::new()
+        .route(
+            "/healthz",
+            get(handlers::healthz_get)
+        )
+        .route(
+            "/readyz",
+            get(handlers::readyz_get)
+        )
         .route(
             &format!("{api}/"),
             get(handlers::root_get)
         )
+        .route(
+            "/robots.txt",
+            get(handlers::robots_get)
+        )
+        .route(
+            &format!("{api}/stats"),
+            get(handlers::library_stats_get)
+        )
+        .route(
+            &format!("{api}/users/:username"),
+            get(handlers::user_profile_get)
+        )
         .route(
             &format!("{api}/projects"),
             get(handlers::projects_get)
         )
         .route(
-            &format!("{api}/projects/:proj"),
+            &format!("{api}/projects/popular"),
+            get(handlers::projects_popular_get)
+        )
+        .route(
+            &format!("{api}/projects/recent"),
+            get(handlers::projects_recent_get)
+        )
+        .route(
+            &format!("{api}/projects/random"),
+            get(handlers::project_random_get)
+        )
+        .route(
+            &project_path,
             get(handlers::project_get)
             .post(handlers::project_post)
             .patch(handlers::project_patch)
+            .delete(handlers::project_delete)
+        )
+        .route(
+            &format!("{api}/projects/:proj/revisions"),
+            get(handlers::project_revisions_get)
+        )
+        .route(
+            &format!("{api}/projects/:proj/revisions/batch"),
+            get(handlers::project_revision_batch_get)
+        )
+        .route(
+            &format!("{api}/projects/:proj/stats"),
+            get(handlers::project_stats_get)
+        )
+        .route(
+            &format!("{api}/projects/:proj/status"),
+            get(handlers::project_status_get)
+        )
+        .route(
+            &format!("{api}/projects/:proj/readme"),
+            get(handlers::project_readme_get)
+        )
+        .route(
+            &format!("{api}/projects/:proj/releases.atom"),
+            get(handlers::project_releases_atom_get)
+        )
+        .route(
+            &format!("{api}/projects/:proj/revisions/:r1/diff/:r2"),
+            get(handlers::project_diff_get)
         )
         .route(
             &format!("{api}/projects/:proj/:revision"),
@@ -124,29 +246,138 @@ fn routes(api: &str) -> Router<AppState> {
             .delete(handlers::players_remove)
         )
         .route(
-            &format!("{api}/projects/:proj/packages/:pkg_name"),
+            &format!("{api}/projects/:proj/subscribe"),
+            post(handlers::subscribe_post)
+        )
+        .route(
+            &format!("{api}/projects/:proj/unsubscribe"),
+            post(handlers::unsubscribe_post)
+        )
+        .route(
+            &format!("{api}/projects/:proj/packages/order"),
+            put(handlers::packages_order_put)
+        )
+        .route(
+            &package_path,
             get(handlers::release_get)
             .post(handlers::packages_post)
+            .patch(handlers::package_patch)
+            .delete(handlers::package_delete)
         )
         .route(
-            &format!("{api}/projects/:proj/packages/:pkg_name/:version"),
+            &format!("{api}/projects/:proj/packages/:pkg_name/releases"),
+            get(handlers::releases_get)
+        )
+        .route(
+            &format!("{api}/projects/:proj/packages/:pkg_name/latest"),
+            get(handlers::release_latest_get)
+        )
+        .route(
+            &recommended_path,
+            get(handlers::release_recommended_get)
+            .put(handlers::release_recommended_put)
+            .delete(handlers::release_recommended_delete)
+        )
+        .route(
+            &release_path,
             get(handlers::release_version_get)
             .put(handlers::release_put)
+            .delete(handlers::release_delete)
+        )
+        .route(
+            &format!("{api}/projects/:proj/packages/:pkg_name/:version/files"),
+            post(handlers::files_post)
+                .layer(
+                    ServiceBuilder::new()
+                        .layer(Extension(handlers::MaxFileSize(max_file_size)))
+                        // the default body limit would cut off all but
+                        // the smallest uploads
+                        .layer(DefaultBodyLimit::max(
+                            (max_file_size as usize)
+                                .saturating_mul(handlers::MAX_FILES_PER_REQUEST)
+                        ))
+                )
         )
         .route(
-            &format!("{api}/projects/:proj/images/:img_name"),
+            &format!("{api}/projects/:proj/packages/:pkg_name/:version/primary"),
+            put(handlers::primary_file_put)
+        )
+        .route(
+            &image_path,
             get(handlers::image_get)
             .post(handlers::image_post)
+            .delete(handlers::image_delete)
         )
         .route(
             &format!("{api}/projects/:proj/images/:img_name/:revision"),
             get(handlers::image_revision_get)
         )
+        .route(
+            &format!("{api}/projects/:proj/gallery/:img_name"),
+            post(handlers::gallery_post)
+        )
         .route(
             &format!("{api}/projects/:proj/flag"),
             post(handlers::flag_post)
         )
+        .route(
+            &format!("{api}/me/notifications"),
+            get(handlers::inbox_get)
+        )
+        .route(
+            &format!("{api}/me/notifications/read"),
+            post(handlers::inbox_read_post)
+        )
+        .route(
+            "/integrations/forum/users/:user_id/summary",
+            get(handlers::forum_user_summary_get)
+        );
+
+    let router = if read_only {
+        router
+    }
+    else {
+        router
+            .route(
+                &format!("{api}/admin/flags"),
+                get(handlers::admin_flags_get)
+            )
+            .route(
+                &format!("{api}/admin/flags/:flag_id/resolve"),
+                post(handlers::admin_flag_resolve_post)
+            )
+            .route(
+                &format!("{api}/admin/slugs/recompute"),
+                post(handlers::admin_slugs_recompute_post)
+            )
+            .route(
+                &format!("{api}/admin/timestamps"),
+                get(handlers::admin_timestamps_get)
+            )
+            .route(
+                &format!("{api}/admin/years/malformed"),
+                get(handlers::admin_years_malformed_get)
+            )
+            .route(
+                &format!("{api}/admin/years/malformed/:proj"),
+                post(handlers::admin_year_correct_post)
+            )
+    };
+
+    router
+        .route_layer(
+            middleware::from_fn_with_state(ReadOnly(read_only), options::guard)
+        )
         .fallback(handlers::not_found)
+}
+
+fn routes(
+    api: &str,
+    read_only: bool,
+    max_file_size: u64
+) -> Router<AppState>
+{
+    resources(api, read_only, max_file_size)
         .layer(
             ServiceBuilder::new()
                 .layer(CorsLayer::very_permissive())
@@ -156,6 +387,57 @@ fn routes(api: &str) -> Router<AppState> {
         )
 }
 
+fn app(
+    api: &str,
+    read_only: bool,
+    max_file_size: u64,
+    state: AppState
+) -> Router
+{
+    let guarded = |router: Router<AppState>| router
+        .with_state(state.clone())
+        .layer(middleware::from_fn_with_state(state.clone(), ratelimit::guard))
+        .layer(
+            middleware::from_fn_with_state(
+                state.maintenance.clone(),
+                maintenance::guard
+            )
+        );
+
+    // the CORS layer answers every OPTIONS request as a preflight, so
+    // explicit OPTIONS requests go to the resources without it
+    let discovery = Discovery {
+        read_only: ReadOnly(read_only),
+        router: guarded(resources(api, read_only, max_file_size))
+    };
+
+    guarded(routes(api, read_only, max_file_size))
+        .layer(middleware::from_fn_with_state(discovery, options::discover))
+}
+
+// Bodies are decompressed before they are extracted, so the limits on
+// what handlers will read, such as the body limit for JSON and the image
+// and release size limits for uploads, apply to the decompressed size;
+// a small compressed body cannot expand without bound.
+fn with_request_decompression(app: Router, enabled: bool) -> Router {
+    match enabled {
+        true => app.layer(RequestDecompressionLayer::new()),
+        false => app
+    }
+}
+
+fn with_static_assets(app: Router, api: &str, static_dir: Option<&str>) -> Router {
+    match static_dir {
+        Some(dir) => app.layer(
+            middleware::from_fn_with_state(
+                StaticAssets::new(api, dir),
+                assets::serve
+            )
+        ),
+        None => app
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 enum StartupError {
     #[error("{0}")]
@@ -165,7 +447,22 @@ enum StartupError {
     #[error("{0}")]
     DatabaseError(#[from] sqlx::Error),
     #[error("{0}")]
-    IOError(#[from] io::Error)
+    MigrateError(#[from] sqlx::migrate::MigrateError),
+    #[error("{0}")]
+    IOError(#[from] io::Error),
+    #[error("invalid page limits: default {0}, max {1}")]
+    LimitError(u8, u8)
+}
+
+fn page_limits(
+    default_limit: u8,
+    max_limit: u8
+) -> Result<(Limit, Limit), StartupError>
+{
+    match (Limit::new(default_limit), Limit::new(max_limit)) {
+        (Some(dl), Some(ml)) if dl <= ml => Ok((dl, ml)),
+        _ => Err(StartupError::LimitError(default_limit, max_limit))
+    }
 }
 
 async fn shutdown_signal() {
@@ -188,35 +485,127 @@ async fn shutdown_signal() {
     }
 }
 
+fn read_config() -> Result<Config, StartupError> {
+    Ok(toml::from_str(&fs::read_to_string("config.toml")?)?)
+}
+
+async fn reload_on_hangup(maintenance: Maintenance) {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut hangup = signal(SignalKind::hangup())
+        .expect("failed to install signal handler");
+
+    while hangup.recv().await.is_some() {
+        match read_config() {
+            Ok(config) => {
+                eprintln!(
+                    "maintenance {} on SIGHUP",
+                    if config.maintenance { "on" } else { "off" }
+                );
+                maintenance.set(config.maintenance, config.maintenance_message);
+            },
+            Err(e) => eprintln!("config not reloaded on SIGHUP: {e}")
+        }
+    }
+}
+
+async fn run_migrations(
+    db_pool: SqlitePool,
+    maintenance: Maintenance
+) -> Result<(), StartupError>
+{
+    sqlx::migrate!().run(&db_pool).await?;
+    maintenance.set_migrating(false);
+    Ok(())
+}
+
 #[tokio::main]
 async fn main() -> Result<(), StartupError> {
-    let config: Config = toml::from_str(&fs::read_to_string("config.toml")?)?;
+    let config = read_config()?;
+
+    let (default_limit, max_limit) = page_limits(
+        config.default_limit,
+        config.max_limit
+    )?;
 
     let db_pool = SqlitePoolOptions::new()
         .max_connections(5)
         .connect(&format!("sqlite://{}", &config.db_path))
         .await?;
 
+    let max_file_size = (config.max_release_size as u64) << 20; // MB to bytes
+
     let core = ProdCore {
-        db: SqlxDatabaseClient(db_pool),
-        uploader: LocalUploader { uploads_directory: "uploads".into() },
+        db: SqlxDatabaseClient(db_pool.clone()),
+        uploader: LocalUploader {
+            uploads_directory: "uploads".into(),
+            integrity_check: config.integrity_check
+        },
         now: Utc::now,
-        max_image_size: (config.max_image_size as u64) << 20 // MB to bytes
+        max_image_size: (config.max_image_size as u64) << 20, // MB to bytes
+        max_file_size,
+        storage_prefix_depth: config.storage_prefix_depth,
+        non_numeric_years: config.non_numeric_years,
+        max_limit,
+        default_limit,
+        count_cache: CountCache::new(config.approximate_count_ttl),
+        seek_max_age: config.seek_max_age,
+        record_usage: config.record_usage,
+        notifier: Notifier::new(
+            InboxSink {
+                db: SqlxDatabaseClient(db_pool.clone()),
+                now: Utc::now
+            }
+        ),
+        readme_cache: ReadmeCache::default()
     };
 
+    let api = &config.api_base_path;
+
+    let maintenance = Maintenance::new(
+        config.maintenance,
+        config.maintenance_message.clone(),
+        config.maintenance_retry_after
+    );
+
+    // serve maintenance responses until the schema is current
+    maintenance.set_migrating(true);
+
     let state = AppState {
         key: DecodingKey::from_secret(config.jwt_key.as_bytes()),
-        core: Arc::new(core) as CoreArc
+        core: Arc::new(core) as CoreArc,
+        robots: RobotsTxt::new(api, config.robots_allow_api),
+        forum_tokens: ServiceTokens::new(&config.forum_service_tokens),
+        maintenance: maintenance.clone(),
+        rate_limiter: RateLimiter::new(
+            config.anonymous_rate_limit,
+            config.user_rate_limit,
+            config.rate_limit_window,
+            config.trusted_proxies.clone()
+        )
     };
 
-    let api = &config.api_base_path;
-
-    let app: Router = routes(api)
-        .with_state(state);
+    let app = app(api, config.read_only, max_file_size, state);
+    let app = with_request_decompression(app, config.decompress_requests);
+    let app = with_static_assets(app, api, config.static_dir.as_deref());
 
     let ip: IpAddr = config.listen_ip.parse()?;
     let addr = SocketAddr::from((ip, config.listen_port));
     let listener = TcpListener::bind(addr).await?;
+
+    tokio::spawn(reload_on_hangup(maintenance.clone()));
+
+    // migrate once listening, so that requests meanwhile are answered
+    // with maintenance responses rather than refused
+    tokio::spawn(async move {
+        if let Err(e) = run_migrations(db_pool, maintenance).await {
+            eprintln!("migrations failed, staying in maintenance: {e}");
+        }
+    });
+
+    // the rate limiter needs the peer address
+    let app = app.into_make_service_with_connect_info::<SocketAddr>();
+
     serve(listener, app)
         .with_graceful_shutdown(shutdown_signal())
         .await?;
@@ -230,9 +619,10 @@ mod test {
 
     use axum::{
         body::{self, Body, Bytes},
+        extract::ConnectInfo,
         http::{
             Method, Request,
-            header::{ACCEPT_ENCODING, AUTHORIZATION, CONTENT_LENGTH, CONTENT_TYPE, LOCATION}
+            header::{ACCEPT, ACCEPT_ENCODING, AUTHORIZATION, CONTENT_ENCODING, CONTENT_LENGTH, CONTENT_TYPE, ETAG, IF_MATCH, IF_NONE_MATCH, LOCATION, RETRY_AFTER}
         }
     };
     use futures::Stream;
@@ -247,15 +637,21 @@ mod test {
 
     use crate::{
         core::{Core, CoreError},
+        forum::{ForumProject, ForumRelease, ForumUserSummary},
+        handlers::{MAX_FILES_PER_REQUEST, MAX_PROJECTS_PER_BATCH},
         jwt::{self, EncodingKey},
-        model::{GameData, Owner, PackageData, Package, ProjectData, ProjectDataPatch, ProjectDataPost, Project, Projects, ProjectSummary, FileData, User, Users},
-        pagination::{Anchor, Direction, Limit, SortBy, Pagination, Seek, SeekLink},
-        params::ProjectsParams,
+        maintenance::MaintenanceError,
+        model::{PROJECT_DESCRIPTION_MAX_LENGTH, DailyStats, FieldChange, Flag, Flags, GalleryImage, GameData, Inbox, InboxItem, LibraryStats, MalformedYear, MalformedYears, Owner, PackageData, PackageDataPatch, PackageSection, Package, ProjectData, ProjectDataPatch, ProjectDataPatchResult, PopularProjects, RecentProjects, ProjectDataPost, Project, ProjectDiff, ProjectRevisionBatch, ProjectRevisions, ProjectRevisionSummary, ProjectStats, ProjectStatus, Projects, ProjectsByName, ProjectSummary, ReleaseData, ReleaseFeed, ReleaseFeedEntry, Releases, FileData, FileResult, SlugChange, SlugRecompute, BadTimestamp, BadTimestamps, FileResults, TagChanges, User, UserProfile, Users, YearCorrection},
+        pagination::{Anchor, Direction, Facet, Limit, SortBy, Pagination, Seek, SeekLink},
+        params::{InboxParams, ProjectsParams, ReadmeFormat, ReleasesParams},
         version::Version
     };
 
     const API_V1: &str = "/api/v1";
+    const FORUM_TOKEN: &str = "forum-token";
+    const FORUM_TOKEN_OLD: &str = "old-forum-token";
     const KEY: &[u8] = b"@wlD+3L)EHdv28u)OFWx@83_*TxhVf9IdUncaAz6ICbM~)j+dH=sR2^LXp(tW31z";
+    const MAX_FILE_SIZE: u64 = 1 << 20;
 
     async fn body_bytes(r: Response) -> Bytes {
         body::to_bytes(r.into_body(), usize::MAX).await.unwrap()
@@ -282,7 +678,8 @@ mod test {
                 title_sort_key: "a".into(),
                 publisher: "p".into(),
                 year: "2024".into()
-            }
+            },
+            modified_by: Some("bob".into())
         }
     );
 
@@ -299,12 +696,69 @@ mod test {
                 title_sort_key: "b".into(),
                 publisher: "p".into(),
                 year: "2024".into()
-            }
+            },
+            modified_by: Some("bob".into())
         }
     );
 
     const BOB_UID: i64 = 1;
 
+    static LIBRARY_STATS: Lazy<LibraryStats> = Lazy::new(||
+        LibraryStats {
+            projects: 2,
+            packages: 3,
+            releases: 4,
+            files: 7,
+            bytes: 123456,
+            players: 5,
+            owners: 2,
+            flags: 1
+        }
+    );
+
+    static INBOX: Lazy<Inbox> = Lazy::new(||
+        Inbox {
+            notifications: vec![
+                InboxItem {
+                    id: 2,
+                    kind: "flag_resolved".into(),
+                    project: "test_game".into(),
+                    package: None,
+                    version: None,
+                    created_at: "2024-01-06T19:23:26.419538067+00:00".into(),
+                    read: false
+                }
+            ],
+            unread: 1,
+            next_before: None
+        }
+    );
+
+    static FORUM_USER_SUMMARY: Lazy<ForumUserSummary> = Lazy::new(||
+        ForumUserSummary {
+            version: 1,
+            username: "bob".into(),
+            owned_projects: 1,
+            recent_releases: vec![
+                ForumRelease {
+                    project: "a_project".into(),
+                    package: "a_package".into(),
+                    version: "1.2.3".into(),
+                    published_at: "2023-12-27T05:36:46.419538067+00:00".into()
+                }
+            ],
+            projects: vec![
+                ForumProject {
+                    name: "a_project".into(),
+                    title: "A Game".into(),
+                    description: "Abandon all hope".into(),
+                    image: None,
+                    modified_at: "2023-12-27T05:36:46.419538067+00:00".into()
+                }
+            ]
+        }
+    );
+
     static EIA_PROJECT_DATA: Lazy<ProjectData> = Lazy::new(||
         ProjectData {
             name: "eia".into(),
@@ -321,11 +775,21 @@ mod test {
             },
             readme: "".into(),
             image: None,
+            archived: false,
+            superseded_by: None,
+            supersedes: vec![],
             owners: vec!["alice".into(), "bob".into()],
+            package_sections: vec![
+                PackageSection {
+                    name: None,
+                    package_slugs: vec!["a_package".into()]
+                }
+            ],
             packages: vec![
                 PackageData {
                     name: "a_package".into(),
                     description: "Some package".into(),
+                    section: None,
                     releases: vec![
                         FileData {
                             version: "1.2.3".into(),
@@ -336,12 +800,16 @@ mod test {
                             published_at: "2023-10-30T18:53:53,056386142+00:00".into(),
                             published_by: "alice".into(),
                             requires: "".into(),
-                            authors: vec![]
+                            authors: vec![],
+                            primary_file: None
                         }
                     ],
-                    files: vec![]
+                    files: vec![],
+                    recommended_release: None
                 }
-            ]
+            ],
+            modified_by: Some("bob".into()),
+            gallery: vec![]
         }
     );
 
@@ -356,7 +824,8 @@ mod test {
         ) -> Result<Project, CoreError>
         {
             match proj {
-                "a_project" => Ok(Project(1)),
+                "a_project" | "宇宙戦艦ヤマト" => Ok(Project(1)),
+                "old_project" => Err(CoreError::ProjectMoved("a_project".into())),
                 _ => Err(CoreError::NotAProject)
             }
         }
@@ -369,6 +838,8 @@ mod test {
         {
             match pkg {
                 "a_package" => Ok(Package(1)),
+                "empty_package" => Ok(Package(2)),
+                "full_package" => Ok(Package(3)),
                 _ => Err(CoreError::NotAPackage)
             }
         }
@@ -432,7 +903,8 @@ mod test {
                                 &Seek {
                                     anchor: Anchor::Before("project_a".into(), 0),
                                     sort_by: SortBy::ProjectName,
-                                    dir: Direction::Ascending
+                                    dir: Direction::Ascending,
+                                    facets: vec![]
                                 },
                                 params.limit
                             ).unwrap()
@@ -442,13 +914,18 @@ mod test {
                                 &Seek {
                                     anchor: Anchor::After("project_b".into(), 0),
                                     sort_by: SortBy::ProjectName,
-                                    dir: Direction::Ascending
+                                    dir: Direction::Ascending,
+                                    facets: vec![]
                                 },
                                 params.limit
                             ).unwrap()
                         ),
-                        total: 1234
-                    }
+                        total: 1234,
+                        total_pages: 124,
+                        approximate: false,
+                        position: None
+                    },
+                    facets: None
                 }
             )
         }
@@ -461,110 +938,629 @@ mod test {
             Ok(EIA_PROJECT_DATA.clone())
         }
 
-        async fn create_project(
+        async fn record_project_view(
             &self,
-            _user: User,
-            _proj: &str,
-            _proj_data: &ProjectDataPost
-        ) -> Result<(), CoreError>
+            _proj: Project
+        )
         {
-            Ok(())
         }
 
-        async fn update_project(
+        async fn get_popular_projects(
             &self,
-            _owner: Owner,
-            _proj: Project,
-            _proj_data: &ProjectDataPatch
-        ) -> Result<(), CoreError>
+            limit: Option<Limit>
+        ) -> Result<PopularProjects, CoreError>
         {
-            Ok(())
+            match limit {
+                Some(l) if l.get() > 100 => Err(CoreError::LimitOutOfRange),
+                _ => Ok(
+                    PopularProjects {
+                        projects: vec![PROJECT_SUMMARY_A.clone()]
+                    }
+                )
+            }
         }
 
-        async fn get_project_revision(
+        async fn get_projects_by_name(
             &self,
-            proj: Project,
-            revision: i64
+            names: &[String]
+        ) -> Result<ProjectsByName, CoreError>
+        {
+            Ok(
+                ProjectsByName {
+                    projects: [&*PROJECT_SUMMARY_A, &*PROJECT_SUMMARY_B]
+                        .into_iter()
+                        .filter(|s| names.contains(&s.name))
+                        .map(|s| (s.name.clone(), s.clone()))
+                        .collect()
+                }
+            )
+        }
+
+        async fn get_recent_projects(
+            &self,
+            _limit: Option<Limit>
+        ) -> Result<RecentProjects, CoreError>
+        {
+            Ok(
+                RecentProjects {
+                    projects: vec![PROJECT_SUMMARY_A.clone()]
+                }
+            )
+        }
+
+        async fn get_random_project(
+            &self
         ) -> Result<ProjectData, CoreError>
         {
-            match revision {
-                1 => self.get_project(proj).await,
-                _ => Err(CoreError::NotARevision)
+            Ok(EIA_PROJECT_DATA.clone())
+        }
+
+        async fn create_project(
+            &self,
+            _user: User,
+            _proj: &str,
+            proj_data: &ProjectDataPost
+        ) -> Result<Project, CoreError>
+        {
+            let bad = proj_data.packages.iter()
+                .enumerate()
+                .filter(|(_, p)| p.name.is_empty())
+                .map(|(i, _)| format!("packages[{i}].name is invalid"))
+                .collect::<Vec<_>>();
+
+            if !bad.is_empty() {
+                return Err(CoreError::InvalidPackages(bad));
             }
+
+            Ok(Project(1))
         }
 
-        async fn get_release(
+        async fn add_file(
             &self,
+            _owner: Owner,
             _proj: Project,
-            _pkg: Package
-        ) -> Result<String, CoreError>
+            _pkg: Package,
+            version: &Version,
+            filename: &str,
+            data: Bytes
+        ) -> Result<FileData, CoreError>
         {
-            Ok("https://example.com/package".into())
+            match filename {
+                "bad.vmod" => Err(
+                    CoreError::ModuleVersionMismatch {
+                        expected: version.into(),
+                        found: "0.0".into()
+                    }
+                ),
+                "bogus.vmod" => Err(CoreError::InvalidModule),
+                "huge.vsav" => Err(CoreError::TooLarge(1 << 20)),
+                "" | "../scenario.vsav" => Err(CoreError::InvalidFilename),
+                _ => Ok(
+                    FileData {
+                        version: String::from(version),
+                        filename: filename.into(),
+                        url: format!("https://example.com/{filename}"),
+                        size: data.len() as i64,
+                        checksum: "".into(),
+                        published_at: "2023-12-27T05:36:46.419538067+00:00".into(),
+                        published_by: "bob".into(),
+                        requires: "".into(),
+                        authors: vec![],
+                        primary_file: None
+                    }
+                )
+            }
         }
 
-        async fn get_release_version(
+        async fn set_primary_file(
             &self,
+            _owner: Owner,
             _proj: Project,
             _pkg: Package,
-            version: &Version
-        ) -> Result<String, CoreError>
+            _version: &Version,
+            filename: &str
+        ) -> Result<(), CoreError>
         {
-            match version {
-                Version { major: 1, minor: 2, patch: 3, .. } => {
-                    Ok("https://example.com/package-1.2.3".into())
-                },
-                _ => Err(CoreError::NotAVersion)
+            match filename {
+                "a_package-1.2.3.vmod" => Ok(()),
+                _ => Err(CoreError::NotFound)
             }
         }
 
-        async fn get_players(
+        async fn delete_project(
             &self,
-            _proj: Project
-        ) -> Result<Users, CoreError>
+            _owner: Owner,
+            _proj: Project,
+            force: bool
+        ) -> Result<(), CoreError>
         {
+            // a_project has releases
+            match force {
+                true => Ok(()),
+                false => Err(CoreError::ProjectHasReleases)
+            }
+        }
+
+        async fn update_project(
+            &self,
+            _owner: Owner,
+            _proj: Project,
+            proj_data: &ProjectDataPatch,
+            expected_revision: Option<i64>
+        ) -> Result<ProjectDataPatchResult, CoreError>
+        {
+            if expected_revision.is_some_and(|r| r != 1) {
+                return Err(CoreError::RevisionMismatch);
+            }
+
             Ok(
-                Users {
-                    users: vec![
-                        "player 1".into(),
-                        "player 2".into()
-                    ]
+                ProjectDataPatchResult {
+                    revision: 2,
+                    tags: proj_data.tags.as_ref().map(|tags|
+                        TagChanges {
+                            tags: tags.clone(),
+                            added: tags.clone(),
+                            removed: vec![]
+                        }
+                    )
                 }
             )
         }
 
-        async fn add_player(
+        async fn get_project_diff(
             &self,
-            _player: User,
-            _proj: Project
-        ) -> Result<(), CoreError>
+            _proj: Project,
+            from: i64,
+            to: i64
+        ) -> Result<ProjectDiff, CoreError>
         {
-            Ok(())
+            match (from, to) {
+                (1, 2) => Ok(
+                    ProjectDiff {
+                        changed_fields: [
+                            (
+                                "description".into(),
+                                FieldChange {
+                                    from: "old".into(),
+                                    to: "new".into()
+                                }
+                            )
+                        ].into()
+                    }
+                ),
+                _ => Err(CoreError::MalformedQuery)
+            }
         }
 
-        async fn remove_player(
+        async fn get_project_revisions(
             &self,
-            _player: User,
             _proj: Project
-        ) -> Result<(), CoreError>
+        ) -> Result<ProjectRevisions, CoreError>
         {
-            Ok(())
+            Ok(
+                ProjectRevisions {
+                    revisions: vec![
+                        ProjectRevisionSummary {
+                            revision: 2,
+                            modified_at: "2023-12-28T05:36:46.419538067+00:00".into(),
+                            modified_by: "alice".into()
+                        },
+                        ProjectRevisionSummary {
+                            revision: 1,
+                            modified_at: "2023-12-27T05:36:46.419538067+00:00".into(),
+                            modified_by: "bob".into()
+                        }
+                    ]
+                }
+            )
         }
 
-        async fn get_image(
+        async fn get_project_revision(
             &self,
             proj: Project,
-            img_name: &str
-        ) -> Result<String, CoreError>
+            revision: i64
+        ) -> Result<ProjectData, CoreError>
         {
-            if proj == Project(1) && img_name == "img.png" {
-                Ok("https://example.com/img.png".into())
-            }
-            else {
-                Err(CoreError::NotFound)
+            match revision {
+                1 => self.get_project(proj).await,
+                _ => Err(CoreError::NotARevision)
             }
         }
 
-        async fn add_image(
+        async fn get_project_revision_batch(
+            &self,
+            proj: Project,
+            revisions: &[i64]
+        ) -> Result<ProjectRevisionBatch, CoreError>
+        {
+            let mut batch = vec![];
+            for &r in revisions {
+                if let Ok(pd) = self.get_project_revision(proj, r).await {
+                    batch.push(pd);
+                }
+            }
+            Ok(ProjectRevisionBatch { revisions: batch })
+        }
+
+        async fn update_package(
+            &self,
+            _owner: Owner,
+            _proj: Project,
+            _pkg: Package,
+            pkg_data: &PackageDataPatch
+        ) -> Result<(), CoreError>
+        {
+            match pkg_data.name.as_deref() {
+                Some("empty_package") => Err(CoreError::PackageNameInUse),
+                _ => Ok(())
+            }
+        }
+
+        async fn delete_package(
+            &self,
+            _owner: Owner,
+            _proj: Project,
+            pkg: Package,
+            force: bool
+        ) -> Result<(), CoreError>
+        {
+            match (pkg, force) {
+                (Package(3), false) => Err(CoreError::PackageHasReleases),
+                _ => Ok(())
+            }
+        }
+
+        async fn reorder_packages(
+            &self,
+            _owner: Owner,
+            _proj: Project,
+            order: &[String]
+        ) -> Result<(), CoreError>
+        {
+            match order.iter().find(|name| *name != "a_package") {
+                Some(name) => Err(
+                    CoreError::InvalidPackages(
+                        vec![format!("unknown package {name}")]
+                    )
+                ),
+                None => Ok(())
+            }
+        }
+
+        async fn get_package(
+            &self,
+            _proj: Project,
+            _pkg: Package
+        ) -> Result<PackageData, CoreError>
+        {
+            Ok(
+                PackageData {
+                    name: "a_package".into(),
+                    description: "Module".into(),
+                    section: None,
+                    releases: vec![file_data("a_package-1.2.3", 1234)],
+                    files: vec![file_data("a_package-1.2.3", 1234)],
+                    recommended_release: None
+                }
+            )
+        }
+
+        async fn get_release(
+            &self,
+            _proj: Project,
+            _pkg: Package
+        ) -> Result<String, CoreError>
+        {
+            Ok("https://example.com/package".into())
+        }
+
+        async fn get_recommended_release_url(
+            &self,
+            _proj: Project,
+            _pkg: Package
+        ) -> Result<String, CoreError>
+        {
+            Ok("https://example.com/recommended".into())
+        }
+
+        async fn set_recommended_release(
+            &self,
+            _owner: Owner,
+            _proj: Project,
+            _pkg: Package,
+            version: Option<&Version>
+        ) -> Result<(), CoreError>
+        {
+            match version {
+                Some(v) if v.major != 1 =>
+                    Err(CoreError::NotAVersion),
+                _ => Ok(())
+            }
+        }
+
+        async fn get_latest_release_url(
+            &self,
+            _proj: Project,
+            pkg: Package
+        ) -> Result<String, CoreError>
+        {
+            match pkg {
+                Package(2) => Err(CoreError::NotFound),
+                _ => Ok("https://example.com/latest".into())
+            }
+        }
+
+        async fn get_releases(
+            &self,
+            _proj: Project,
+            _pkg: Package,
+            params: ReleasesParams
+        ) -> Result<Releases, CoreError>
+        {
+            match params.limit {
+                Some(l) if l.get() > 100 => Err(CoreError::LimitOutOfRange),
+                _ => Ok(
+                    Releases {
+                        releases: vec![
+                            ReleaseData {
+                                version: "1.2.3".into(),
+                                files: vec![file_data("a_package-1.2.3", 1234)]
+                            }
+                        ],
+                        meta: Pagination {
+                            prev_page: None,
+                            next_page: None,
+                            total: 1,
+                            total_pages: 1,
+                            approximate: false,
+                            position: None
+                        }
+                    }
+                )
+            }
+        }
+
+        async fn delete_release(
+            &self,
+            _owner: Owner,
+            _proj: Project,
+            _pkg: Package,
+            version: &Version
+        ) -> Result<(), CoreError>
+        {
+            match version {
+                Version { major: 1, minor: 2, patch: 3, .. } => Ok(()),
+                _ => Err(CoreError::NotAVersion)
+            }
+        }
+
+        async fn get_release_version(
+            &self,
+            _proj: Project,
+            _pkg: Package,
+            version: &Version
+        ) -> Result<String, CoreError>
+        {
+            match version {
+                Version { major: 1, minor: 2, patch: 3, .. } => {
+                    Ok("https://example.com/package-1.2.3".into())
+                },
+                _ => Err(CoreError::NotAVersion)
+            }
+        }
+
+        async fn get_release_data(
+            &self,
+            _proj: Project,
+            _pkg: Package,
+            version: &Version
+        ) -> Result<ReleaseData, CoreError>
+        {
+            match version {
+                Version { major: 1, minor: 2, patch: 3, .. } => Ok(
+                    ReleaseData {
+                        version: "1.2.3".into(),
+                        files: vec![file_data("a_package-1.2.3", 1234)]
+                    }
+                ),
+                _ => Err(CoreError::NotAVersion)
+            }
+        }
+
+        async fn get_user_id(
+             &self,
+            username: &str
+        ) -> Result<User, CoreError>
+        {
+            match username {
+                "bob" => Ok(User(1)),
+                _ => Err(CoreError::NotAUser)
+            }
+        }
+
+        async fn get_user_profile(
+            &self,
+            _user: User
+        ) -> Result<UserProfile, CoreError>
+        {
+            Ok(
+                UserProfile {
+                    username: "bob".into(),
+                    owned_projects: 2,
+                    played_projects: 1
+                }
+            )
+        }
+
+        async fn get_forum_user_summary(
+            &self,
+            user: User
+        ) -> Result<ForumUserSummary, CoreError>
+        {
+            match user {
+                User(1) => Ok(FORUM_USER_SUMMARY.clone()),
+                _ => Err(CoreError::NotAUser)
+            }
+        }
+
+        async fn get_project_stats(
+            &self,
+            _proj: Project
+        ) -> Result<ProjectStats, CoreError>
+        {
+            Ok(
+                ProjectStats {
+                    files: 3,
+                    bytes: 12345,
+                    releases: 2,
+                    packages: 1,
+                    players: 2,
+                    owners: 2,
+                    flags: 0,
+                    age_days: 42,
+                    views: 7,
+                    downloads: 3,
+                    daily: vec![
+                        DailyStats {
+                            date: "2023-11-12".into(),
+                            views: 7,
+                            downloads: 3
+                        }
+                    ]
+                }
+            )
+        }
+
+        async fn get_project_readme(
+            &self,
+            _proj: Project,
+            format: ReadmeFormat
+        ) -> Result<String, CoreError>
+        {
+            Ok(
+                match format {
+                    ReadmeFormat::Markdown => "# Hi".into(),
+                    ReadmeFormat::Html => "<h1>Hi</h1>\n".into()
+                }
+            )
+        }
+
+        async fn get_project_status(
+            &self,
+            _proj: Project
+        ) -> Result<ProjectStatus, CoreError>
+        {
+            Ok(
+                ProjectStatus {
+                    open_flags: 2,
+                    archived: true,
+                    bytes: 12345
+                }
+            )
+        }
+
+        async fn get_library_stats(
+            &self
+        ) -> Result<LibraryStats, CoreError>
+        {
+            Ok(LIBRARY_STATS.clone())
+        }
+
+        async fn get_release_feed(
+            &self,
+            _proj: Project
+        ) -> Result<ReleaseFeed, CoreError>
+        {
+            Ok(
+                ReleaseFeed {
+                    project: "a_project".into(),
+                    title: "A Game".into(),
+                    updated: "2023-12-27T05:36:46.419538067+00:00".into(),
+                    entries: vec![
+                        ReleaseFeedEntry {
+                            package: "a_package".into(),
+                            version: "1.2.3".into(),
+                            published_at: "2023-12-27T05:36:46.419538067+00:00".into(),
+                            published_by: "bob".into(),
+                            files: vec![file_data("a_package-1.2.3", 1234)]
+                        }
+                    ]
+                }
+            )
+        }
+
+        async fn get_players(
+            &self,
+            _proj: Project
+        ) -> Result<Users, CoreError>
+        {
+            Ok(
+                Users {
+                    users: vec![
+                        "player 1".into(),
+                        "player 2".into()
+                    ]
+                }
+            )
+        }
+
+        async fn add_player(
+            &self,
+            _player: User,
+            _proj: Project
+        ) -> Result<(), CoreError>
+        {
+            Ok(())
+        }
+
+        async fn remove_player(
+            &self,
+            _player: User,
+            _proj: Project
+        ) -> Result<(), CoreError>
+        {
+            Ok(())
+        }
+
+        async fn subscribe(
+            &self,
+            player: User,
+            _proj: Project
+        ) -> Result<(), CoreError>
+        {
+            match player {
+                User(1) | User(2) => Ok(()),
+                _ => Err(CoreError::NotAPlayer)
+            }
+        }
+
+        async fn unsubscribe(
+            &self,
+            player: User,
+            _proj: Project
+        ) -> Result<(), CoreError>
+        {
+            match player {
+                User(1) | User(2) => Ok(()),
+                _ => Err(CoreError::NotAPlayer)
+            }
+        }
+
+        async fn get_image(
+            &self,
+            proj: Project,
+            img_name: &str
+        ) -> Result<String, CoreError>
+        {
+            if proj == Project(1) && img_name == "img.png" {
+                Ok("https://example.com/img.png".into())
+            }
+            else {
+                Err(CoreError::NotFound)
+            }
+        }
+
+        async fn add_image(
             &self,
             _owner: Owner,
             _proj: Project,
@@ -575,7 +1571,7 @@ mod test {
         ) -> Result<(), CoreError>
         {
             if content_length > Some(1 << 20) {
-                Err(CoreError::TooLarge)
+                Err(CoreError::TooLarge(1 << 20))
             }
             else if content_type == &TEXT_PLAIN {
                 Err(CoreError::BadMimeType)
@@ -584,186 +1580,5269 @@ mod test {
                 Ok(())
             }
         }
+
+        async fn remove_image(
+            &self,
+            _owner: Owner,
+            _proj: Project,
+            img_name: &str
+        ) -> Result<(), CoreError>
+        {
+            match img_name {
+                "img.png" => Ok(()),
+                "cover.png" => Err(CoreError::ImageInUse),
+                _ => Err(CoreError::NotFound)
+            }
+        }
+
+        async fn add_gallery_image(
+            &self,
+            _owner: Owner,
+            _proj: Project,
+            img_name: &str,
+            content_type: &Mime,
+            content_length: Option<u64>,
+            _stream: Box<dyn Stream<Item = Result<Bytes, io::Error>> + Send>
+        ) -> Result<GalleryImage, CoreError>
+        {
+            if content_length > Some(1 << 20) {
+                Err(CoreError::TooLarge(1 << 20))
+            }
+            else if content_type == &TEXT_PLAIN {
+                Err(CoreError::BadMimeType)
+            }
+            else {
+                Ok(
+                    GalleryImage {
+                        id: 3,
+                        filename: img_name.into(),
+                        description: "".into()
+                    }
+                )
+            }
+        }
+
+        async fn get_flags(
+            &self,
+            facets: &[Facet]
+        ) -> Result<Flags, CoreError>
+        {
+            let flags = [FLAG_1.clone(), FLAG_2.clone()];
+
+            Ok(
+                Flags {
+                    flags: flags.into_iter()
+                        .filter(|f|
+                            !facets.contains(&Facet::UnresolvedOnly) ||
+                            f.resolved_at.is_none()
+                        )
+                        .collect()
+                }
+            )
+        }
+
+        async fn resolve_flag(
+            &self,
+            _admin: User,
+            flag_id: i64
+        ) -> Result<(), CoreError>
+        {
+            match flag_id {
+                1 => Ok(()),
+                2 => Err(CoreError::FlagAlreadyResolved),
+                _ => Err(CoreError::NotAFlag)
+            }
+        }
+
+        async fn get_inbox(
+            &self,
+            _user: User,
+            params: InboxParams
+        ) -> Result<Inbox, CoreError>
+        {
+            match params.before {
+                Some(_) => Ok(
+                    Inbox {
+                        notifications: vec![],
+                        unread: 1,
+                        next_before: None
+                    }
+                ),
+                None => Ok(INBOX.clone())
+            }
+        }
+
+        async fn mark_inbox_read(
+            &self,
+            _user: User,
+            _ids: Option<&[i64]>
+        ) -> Result<(), CoreError>
+        {
+            Ok(())
+        }
+
+        async fn recompute_slugs(
+            &self,
+            _aliases: bool
+        ) -> Result<SlugRecompute, CoreError>
+        {
+            Ok(
+                SlugRecompute {
+                    changed: vec![
+                        SlugChange {
+                            project: "A-Project".into(),
+                            from: "a-project".into(),
+                            to: "a_project".into()
+                        }
+                    ],
+                    collisions: vec![]
+                }
+            )
+        }
+
+        async fn get_bad_timestamps(
+            &self
+        ) -> Result<BadTimestamps, CoreError>
+        {
+            Ok(
+                BadTimestamps {
+                    timestamps: vec![
+                        BadTimestamp {
+                            table: "projects".into(),
+                            id: 1,
+                            column: "modified_at".into(),
+                            value: -1
+                        }
+                    ]
+                }
+            )
+        }
+
+        async fn get_malformed_years(
+            &self
+        ) -> Result<MalformedYears, CoreError>
+        {
+            Ok(
+                MalformedYears {
+                    projects: vec![
+                        MalformedYear {
+                            project: "a_project".into(),
+                            year: "c. 1979".into()
+                        }
+                    ]
+                }
+            )
+        }
+
+        async fn correct_year(
+            &self,
+            _admin: User,
+            _proj: Project,
+            year: &str
+        ) -> Result<(), CoreError>
+        {
+            match year {
+                "1979" => Ok(()),
+                _ => Err(CoreError::InvalidYear)
+            }
+        }
+    }
+
+    static FLAG_1: Lazy<Flag> = Lazy::new(||
+        Flag {
+            flag_id: 1,
+            project: "a_project".into(),
+            flagged_by: "alice".into(),
+            flagged_at: "2023-12-26T05:36:46.419538067+00:00".into(),
+            flag: "spam".into(),
+            message: None,
+            resolved_at: None,
+            resolved_by: None
+        }
+    );
+
+    static FLAG_2: Lazy<Flag> = Lazy::new(||
+        Flag {
+            flag_id: 2,
+            project: "a_project".into(),
+            flagged_by: "chuck".into(),
+            flagged_at: "2023-12-27T05:36:46.419538067+00:00".into(),
+            flag: "spam".into(),
+            message: None,
+            resolved_at: Some("2023-12-28T05:36:46.419538067+00:00".into()),
+            resolved_by: Some("bob".into())
+        }
+    );
+
+    fn test_state() -> AppState {
+        AppState {
+            key: DecodingKey::from_secret(KEY),
+            core: Arc::new(TestCore {}) as CoreArc,
+            robots: RobotsTxt::new(API_V1, true),
+            forum_tokens: ServiceTokens::new(&[FORUM_TOKEN, FORUM_TOKEN_OLD]),
+            maintenance: Maintenance::default(),
+            rate_limiter: RateLimiter::default()
+        }
+    }
+
+    fn token(uid: i64) -> String {
+        let ekey = EncodingKey::from_secret(KEY);
+        let token = jwt::issue(&ekey, uid, false, 0, 899999999999).unwrap();
+        format!("Bearer {token}")
+    }
+
+    fn admin_token(uid: i64) -> String {
+        let ekey = EncodingKey::from_secret(KEY);
+        let token = jwt::issue(&ekey, uid, true, 0, 899999999999).unwrap();
+        format!("Bearer {token}")
+    }
+
+    async fn try_request(request: Request<Body>) -> Response {
+        routes(API_V1, false, MAX_FILE_SIZE)
+            .with_state(test_state())
+            .oneshot(request)
+            .await
+            .unwrap()
+    }
+
+    fn headers<'a>(
+        response: &'a Response,
+        header_name: &str
+    ) -> Vec<&'a [u8]>
+    {
+        let mut values = response
+            .headers()
+            .get_all(header_name)
+            .iter()
+            .flat_map(|v| v.as_ref().split(|b| b == &b','))
+            .map(|v| if v[0] == b' ' { &v[1..] } else { v })
+            .collect::<Vec<_>>();
+
+        values.sort();
+        values
+    }
+
+    async fn assert_shutdown(sig: Signal) {
+        let listener = TcpListener::bind("localhost:0").await.unwrap();
+        let app = Router::new();
+        let pid = Pid::this();
+
+        let server_handle = tokio::spawn(
+            serve(listener, app)
+                .with_graceful_shutdown(shutdown_signal())
+                .into_future()
+        );
+
+        // ensure that the server has a chance to start
+        tokio::task::yield_now().await;
+
+        sys::signal::kill(pid, sig).unwrap();
+
+        server_handle.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn graceful_shutdown_sigint() {
+        assert_shutdown(Signal::SIGTERM).await;
+    }
+
+    #[tokio::test]
+    async fn graceful_shutdown_sigquit() {
+        assert_shutdown(Signal::SIGQUIT).await;
+    }
+
+    #[tokio::test]
+    async fn graceful_shutdown_sigterm() {
+        assert_shutdown(Signal::SIGTERM).await;
+    }
+
+    #[tokio::test]
+    async fn cors_ok() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        assert_eq!(
+            headers(&response, "access-control-allow-credentials"),
+            ["true".as_bytes()]
+        );
+
+        assert_eq!(
+            headers(&response, "vary"),
+            [
+                "access-control-request-headers".as_bytes(),
+                "access-control-request-method".as_bytes(),
+                "origin".as_bytes()
+            ]
+        );
+    }
+
+    async fn try_compression(comp: &str) {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects"))
+                .header(ACCEPT_ENCODING, comp)
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        assert_eq!(
+            headers(&response, "content-encoding"),
+            [comp.as_bytes()]
+        );
+    }
+
+    #[tokio::test]
+    async fn compression_br() {
+        try_compression("br").await;
+    }
+
+    #[tokio::test]
+    async fn compression_deflate() {
+        try_compression("deflate").await;
+    }
+
+    #[tokio::test]
+    async fn compression_gzip() {
+        try_compression("gzip").await;
+    }
+
+    #[tokio::test]
+    async fn compression_zstd() {
+        try_compression("zstd").await;
+    }
+
+    #[tokio::test]
+    async fn bad_path() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/bogus/whatever"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::NotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn root_ok() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(&body_bytes(response).await[..], b"hello world");
+    }
+
+    async fn maintenance_request(
+        maintenance: &Maintenance,
+        uri: &str
+    ) -> Response
+    {
+        let state = AppState {
+            maintenance: maintenance.clone(),
+            ..test_state()
+        };
+
+        app(API_V1, false, MAX_FILE_SIZE, state)
+            .oneshot(
+                Request::builder()
+                    .method(Method::GET)
+                    .uri(uri)
+                    .body(Body::empty())
+                    .unwrap()
+            )
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn maintenance_off() {
+        let maintenance = Maintenance::new(false, None, 120);
+
+        for uri in ["/healthz", "/readyz", &format!("{API_V1}/")] {
+            let response = maintenance_request(&maintenance, uri).await;
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+    }
+
+    #[tokio::test]
+    async fn maintenance_on() {
+        let maintenance = Maintenance::new(false, None, 120);
+        maintenance.set(true, Some("Upgrading, back soon".into()));
+
+        let response = maintenance_request(&maintenance, "/healthz").await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        for uri in ["/readyz", &format!("{API_V1}/projects")] {
+            let response = maintenance_request(&maintenance, uri).await;
+            assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+            assert_eq!(headers(&response, "retry-after"), [b"120"]);
+            assert_eq!(
+                body_as::<MaintenanceError>(response).await,
+                MaintenanceError {
+                    error: "maintenance".into(),
+                    message: Some("Upgrading, back soon".into())
+                }
+            );
+        }
+
+        // turning maintenance off takes effect immediately
+        maintenance.set(false, None);
+
+        let response = maintenance_request(
+            &maintenance,
+            &format!("{API_V1}/projects")
+        ).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn maintenance_migrating() {
+        let maintenance = Maintenance::new(false, None, 120);
+        maintenance.set_migrating(true);
+
+        let response = maintenance_request(&maintenance, "/healthz").await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = maintenance_request(&maintenance, "/readyz").await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        let response = maintenance_request(
+            &maintenance,
+            &format!("{API_V1}/")
+        ).await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(
+            body_as::<MaintenanceError>(response).await,
+            MaintenanceError {
+                error: "maintenance".into(),
+                message: None
+            }
+        );
+    }
+
+    async fn rate_limited_request(
+        app: &Router,
+        addr: &str,
+        auth: Option<String>
+    ) -> Response
+    {
+        let mut request = Request::builder()
+            .method(Method::GET)
+            .uri(&format!("{API_V1}/projects"));
+
+        if let Some(auth) = auth {
+            request = request.header(AUTHORIZATION, auth);
+        }
+
+        let mut request = request.body(Body::empty()).unwrap();
+        request.extensions_mut().insert(
+            ConnectInfo(addr.parse::<SocketAddr>().unwrap())
+        );
+
+        app.clone().oneshot(request).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn rate_limit_anonymous_exceeded() {
+        let state = AppState {
+            rate_limiter: RateLimiter::new(2, 0, 3600, vec![]),
+            ..test_state()
+        };
+
+        let app = app(API_V1, false, MAX_FILE_SIZE, state);
+
+        for remaining in ["1", "0"] {
+            let response = rate_limited_request(&app, "192.0.2.1:1234", None).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(
+                response.headers().get("x-ratelimit-limit").unwrap(),
+                "2"
+            );
+            assert_eq!(
+                response.headers().get("x-ratelimit-remaining").unwrap(),
+                remaining
+            );
+            assert!(response.headers().contains_key("x-ratelimit-reset"));
+            assert!(!response.headers().contains_key(RETRY_AFTER));
+        }
+
+        let response = rate_limited_request(&app, "192.0.2.1:1234", None).await;
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(
+            response.headers().get("x-ratelimit-limit").unwrap(),
+            "2"
+        );
+        assert_eq!(
+            response.headers().get("x-ratelimit-remaining").unwrap(),
+            "0"
+        );
+        assert!(response.headers().contains_key(RETRY_AFTER));
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::TooManyRequests)
+        );
+
+        // another address is unaffected
+        let response = rate_limited_request(&app, "192.0.2.2:1234", None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // an authenticated requester at the same address is exempt
+        let response = rate_limited_request(
+            &app,
+            "192.0.2.1:1234",
+            Some(token(BOB_UID))
+        ).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(!response.headers().contains_key("x-ratelimit-limit"));
+    }
+
+    #[tokio::test]
+    async fn rate_limit_forged_token_not_exempt() {
+        let state = AppState {
+            rate_limiter: RateLimiter::new(1, 0, 3600, vec![]),
+            ..test_state()
+        };
+
+        let app = app(API_V1, false, MAX_FILE_SIZE, state);
+
+        let bogus = Some("Bearer bogus".to_string());
+
+        let response = rate_limited_request(&app, "192.0.2.1:1234", bogus.clone()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = rate_limited_request(&app, "192.0.2.1:1234", bogus).await;
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[tokio::test]
+    async fn get_projects_not_modified() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let etag = response.headers().get(ETAG).unwrap().clone();
+
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects"))
+                .header(IF_NONE_MATCH, &etag)
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers().get(ETAG).unwrap(), etag);
+        assert_eq!(headers(&response, "x-robots-tag"), [b"noindex"]);
+        assert!(body_bytes(response).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn get_projects_etag_differs_by_page() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        let etag = response.headers().get(ETAG).unwrap().clone();
+
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects?limit=5"))
+                .header(IF_NONE_MATCH, &etag)
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_ne!(response.headers().get(ETAG).unwrap(), etag);
+    }
+
+    #[tokio::test]
+    async fn get_projects_no_params_ok() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let total = headers(&response, "x-total-count")[0].to_vec();
+        let projects = body_as::<Projects>(response).await;
+        assert_eq!(total, projects.meta.total.to_string().as_bytes());
+        assert_eq!(
+            projects,
+            Projects {
+                projects: vec![
+                    PROJECT_SUMMARY_A.clone(),
+                    PROJECT_SUMMARY_B.clone()
+                ],
+                meta: Pagination {
+                    prev_page: Some(
+                        SeekLink::new(
+                            &Seek {
+                                anchor: Anchor::Before("project_a".into(), 0),
+                                sort_by: SortBy::ProjectName,
+                                dir: Direction::Ascending,
+                                facets: vec![]
+
+                            },
+                            None
+                        ).unwrap()
+                    ),
+                    next_page: Some(
+                        SeekLink::new(
+                            &Seek {
+                                anchor: Anchor::After("project_b".into(), 0),
+                                sort_by: SortBy::ProjectName,
+                                dir: Direction::Ascending,
+                                facets: vec![]
+                            },
+                            None
+                        ).unwrap()
+                    ),
+                    total: 1234,
+                    total_pages: 124,
+                    approximate: false,
+                    position: None
+                },
+                facets: None
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn get_projects_limit_ok() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects?limit=5"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            body_as::<Projects>(response).await,
+            Projects {
+                projects: vec![
+                    PROJECT_SUMMARY_A.clone(),
+                    PROJECT_SUMMARY_B.clone()
+                ],
+                meta: Pagination {
+                    prev_page: Some(
+                        SeekLink::new(
+                            &Seek {
+                                anchor: Anchor::Before("project_a".into(), 0),
+                                sort_by: SortBy::ProjectName,
+                                dir: Direction::Ascending,
+                                facets: vec![]
+                            },
+                            Limit::new(5)
+                        ).unwrap()
+                    ),
+                    next_page: Some(
+                        SeekLink::new(
+                            &Seek {
+                                anchor: Anchor::After("project_b".into(), 0),
+                                sort_by: SortBy::ProjectName,
+                                dir: Direction::Ascending,
+                                facets: vec![]
+                            },
+                            Limit::new(5)
+                        ).unwrap()
+                    ),
+                    total: 1234,
+                    total_pages: 124,
+                    approximate: false,
+                    position: None
+                },
+                facets: None
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn get_projects_limit_zero() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects?limit=0"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::LimitOutOfRange)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_projects_limit_too_large() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects?limit=100000"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::LimitOutOfRange)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_projects_limit_empty() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects?limit="))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::LimitOutOfRange)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_projects_limit_not_a_number() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects?limit=eleventeen"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::LimitOutOfRange)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_projects_seek_start_ok() {
+        let query = SeekLink::new(
+            &Seek {
+                anchor: Anchor::Start,
+                sort_by: SortBy::ProjectName,
+                dir: Direction::Ascending,
+                facets: vec![]
+            },
+            None
+        ).unwrap();
+
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects{query}"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            body_as::<Projects>(response).await,
+            Projects {
+                projects: vec![
+                    PROJECT_SUMMARY_A.clone(),
+                    PROJECT_SUMMARY_B.clone()
+                ],
+                meta: Pagination {
+                    prev_page: Some(
+                        SeekLink::new(
+                            &Seek {
+                                anchor: Anchor::Before("project_a".into(), 0),
+                                sort_by: SortBy::ProjectName,
+                                dir: Direction::Ascending,
+                                facets: vec![]
+                            },
+                            None
+                        ).unwrap()
+                    ),
+                    next_page: Some(
+                        SeekLink::new(
+                            &Seek {
+                                anchor: Anchor::After("project_b".into(), 0),
+                                sort_by: SortBy::ProjectName,
+                                dir: Direction::Ascending,
+                                facets: vec![]
+                            },
+                            None
+                        ).unwrap()
+                    ),
+                    total: 1234,
+                    total_pages: 124,
+                    approximate: false,
+                    position: None
+                },
+                facets: None
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn get_projects_seek_end_ok() {
+        let query = SeekLink::new(
+            &Seek {
+                anchor: Anchor::Start,
+                sort_by: SortBy::ProjectName,
+                dir: Direction::Descending,
+                facets: vec![]
+            },
+            None
+        ).unwrap();
+
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects{query}"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            body_as::<Projects>(response).await,
+            Projects {
+                projects: vec![
+                    PROJECT_SUMMARY_A.clone(),
+                    PROJECT_SUMMARY_B.clone()
+                ],
+                meta: Pagination {
+                    prev_page: Some(
+                        SeekLink::new(
+                            &Seek {
+                                anchor: Anchor::Before("project_a".into(), 0),
+                                sort_by: SortBy::ProjectName,
+                                dir: Direction::Ascending,
+                                facets: vec![]
+                            },
+                            None
+                        ).unwrap()
+                    ),
+                    next_page: Some(
+                        SeekLink::new(
+                            &Seek {
+                                anchor: Anchor::After("project_b".into(), 0),
+                                sort_by: SortBy::ProjectName,
+                                dir: Direction::Ascending,
+                                facets: vec![]
+                            },
+                            None
+                        ).unwrap()
+                    ),
+                    total: 1234,
+                    total_pages: 124,
+                    approximate: false,
+                    position: None
+                },
+                facets: None
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn get_projects_seek_before_ok() {
+        let query = SeekLink::new(
+            &Seek {
+                anchor: Anchor::Before("xyz".into(), 0),
+                sort_by: SortBy::ProjectName,
+                dir: Direction::Ascending,
+                facets: vec![]
+            },
+            None
+        ).unwrap();
+
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects{query}"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            body_as::<Projects>(response).await,
+            Projects {
+                projects: vec![
+                    PROJECT_SUMMARY_A.clone(),
+                    PROJECT_SUMMARY_B.clone()
+                ],
+                meta: Pagination {
+                    prev_page: Some(
+                        SeekLink::new(
+                            &Seek {
+                                anchor: Anchor::Before("project_a".into(), 0),
+                                sort_by: SortBy::ProjectName,
+                                dir: Direction::Ascending,
+                                facets: vec![]
+                            },
+                            None
+                        ).unwrap()
+                    ),
+                    next_page: Some(
+                        SeekLink::new(
+                            &Seek {
+                                anchor: Anchor::After("project_b".into(), 0),
+                                sort_by: SortBy::ProjectName,
+                                dir: Direction::Ascending,
+                                facets: vec![]
+                            },
+                            None
+                        ).unwrap()
+                    ),
+                    total: 1234,
+                    total_pages: 124,
+                    approximate: false,
+                    position: None
+                },
+                facets: None
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn get_projects_seek_after_ok() {
+        let query = SeekLink::new(
+            &Seek {
+                anchor: Anchor::After("xyz".into(), 0),
+                sort_by: SortBy::ProjectName,
+                dir: Direction::Ascending,
+                facets: vec![]
+            },
+            None
+        ).unwrap();
+
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects{query}"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            body_as::<Projects>(response).await,
+            Projects {
+                projects: vec![
+                    PROJECT_SUMMARY_A.clone(),
+                    PROJECT_SUMMARY_B.clone()
+                ],
+                meta: Pagination {
+                    prev_page: Some(
+                        SeekLink::new(
+                            &Seek {
+                                anchor: Anchor::Before("project_a".into(), 0),
+                                sort_by: SortBy::ProjectName,
+                                dir: Direction::Ascending,
+                                facets: vec![]
+                            },
+                            None
+                        ).unwrap()
+                    ),
+                    next_page: Some(
+                        SeekLink::new(
+                            &Seek {
+                                anchor: Anchor::After("project_b".into(), 0),
+                                sort_by: SortBy::ProjectName,
+                                dir: Direction::Ascending,
+                                facets: vec![]
+                            },
+                            None
+                        ).unwrap()
+                    ),
+                    total: 1234,
+                    total_pages: 124,
+                    approximate: false,
+                    position: None
+                },
+                facets: None
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn get_projects_seek_and_facet() {
+        let query = SeekLink::new(
+            &Seek {
+                anchor: Anchor::Start,
+                sort_by: SortBy::ProjectName,
+                dir: Direction::Ascending,
+                facets: vec![]
+            },
+            None
+        ).unwrap();
+
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects{query}&tag=wwii"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::MalformedQuery)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_projects_contradictory_facets() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects?year=1979&year=1980"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::MalformedQuery)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_projects_seek_empty() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects?seek="))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::MalformedQuery)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_projects_facet_counts_bad() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects?facet_counts=publishers"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::MalformedQuery)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_projects_seek_bad() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects?seek=%@$"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::MalformedQuery)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_projects_seek_too_long() {
+        let long = "x".repeat(1000);
+
+        let query = SeekLink::new(
+            &Seek {
+                anchor: Anchor::Before(long, 0),
+                sort_by: SortBy::ProjectName,
+                dir: Direction::Ascending,
+                facets: vec![]
+            },
+            Limit::new(5)
+        ).unwrap();
+
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects?seek={query}"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::MalformedQuery)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_projects_seek_and_limit_ok() {
+        let query = SeekLink::new(
+            &Seek {
+                anchor: Anchor::Start,
+                sort_by: SortBy::ProjectName,
+                dir: Direction::Ascending,
+                facets: vec![]
+            },
+            Limit::new(5)
+        ).unwrap();
+
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects{query}"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            body_as::<Projects>(response).await,
+            Projects {
+                projects: vec![
+                    PROJECT_SUMMARY_A.clone(),
+                    PROJECT_SUMMARY_B.clone()
+                ],
+                meta: Pagination {
+                    prev_page: Some(
+                        SeekLink::new(
+                            &Seek {
+                                anchor: Anchor::Before("project_a".into(), 0),
+                                sort_by: SortBy::ProjectName,
+                                dir: Direction::Ascending,
+                                facets: vec![]
+                            },
+                            Limit::new(5)
+                        ).unwrap()
+                    ),
+                    next_page: Some(
+                        SeekLink::new(
+                            &Seek {
+                                anchor: Anchor::After("project_b".into(), 0),
+                                sort_by: SortBy::ProjectName,
+                                dir: Direction::Ascending,
+                                facets: vec![]
+                            },
+                            Limit::new(5)
+                        ).unwrap()
+                    ),
+                    total: 1234,
+                    total_pages: 124,
+                    approximate: false,
+                    position: None
+                },
+                facets: None
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn get_projects_limit_and_seek_ok() {
+        let query = SeekLink::new(
+            &Seek {
+                anchor: Anchor::Start,
+                sort_by: SortBy::ProjectName,
+                dir: Direction::Ascending,
+                facets: vec![]
+            },
+            Limit::new(5)
+        ).unwrap();
+
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects{query}"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            body_as::<Projects>(response).await,
+            Projects {
+                projects: vec![
+                    PROJECT_SUMMARY_A.clone(),
+                    PROJECT_SUMMARY_B.clone()
+                ],
+                meta: Pagination {
+                    prev_page: Some(
+                        SeekLink::new(
+                            &Seek {
+                                anchor: Anchor::Before("project_a".into(), 0),
+                                sort_by: SortBy::ProjectName,
+                                dir: Direction::Ascending,
+                                facets: vec![]
+                            },
+                            Limit::new(5)
+                        ).unwrap()
+                    ),
+                    next_page: Some(
+                        SeekLink::new(
+                            &Seek {
+                                anchor: Anchor::After("project_b".into(), 0),
+                                sort_by: SortBy::ProjectName,
+                                dir: Direction::Ascending,
+                                facets: vec![]
+                            },
+                            Limit::new(5)
+                        ).unwrap()
+                    ),
+                    total: 1234,
+                    total_pages: 124,
+                    approximate: false,
+                    position: None
+                },
+                facets: None
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn get_project_ok() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/a_project"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            body_as::<ProjectData>(response).await,
+            *EIA_PROJECT_DATA
+        );
+    }
+
+    #[tokio::test]
+    async fn head_project_ok() {
+        let get = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/a_project"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        let response = try_request(
+            Request::builder()
+                .method(Method::HEAD)
+                .uri(&format!("{API_V1}/projects/a_project"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers(), get.headers());
+        assert!(body_bytes(response).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn head_project_not_a_project() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::HEAD)
+                .uri(&format!("{API_V1}/projects/not_a_project"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert!(body_bytes(response).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn get_project_not_modified() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/a_project"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let etag = response.headers().get(ETAG).unwrap().clone();
+
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/a_project"))
+                .header(IF_NONE_MATCH, &etag)
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers().get(ETAG).unwrap(), etag);
+        assert!(body_bytes(response).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn get_project_etag_mismatch() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/a_project"))
+                .header(IF_NONE_MATCH, "\"stale\"")
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers().get(ETAG).is_some());
+        assert_eq!(
+            body_as::<ProjectData>(response).await,
+            *EIA_PROJECT_DATA
+        );
+    }
+
+    // A core with one project, which gets a new revision on each update
+    // and counts its views
+    #[derive(Default)]
+    struct RevisingCore {
+        revision: std::sync::atomic::AtomicI64,
+        views: std::sync::atomic::AtomicUsize
+    }
+
+    #[axum::async_trait]
+    impl Core for RevisingCore {
+        async fn get_project_id(
+            &self,
+            _proj: &str
+        ) -> Result<Project, CoreError>
+        {
+            Ok(Project(1))
+        }
+
+        async fn user_is_owner(
+            &self,
+            _user: User,
+            _proj: Project
+        ) -> Result<bool, CoreError>
+        {
+            Ok(true)
+        }
+
+        async fn get_project(
+            &self,
+            _proj: Project
+        ) -> Result<ProjectData, CoreError>
+        {
+            Ok(
+                ProjectData {
+                    revision: self.revision.load(
+                        std::sync::atomic::Ordering::SeqCst
+                    ),
+                    ..EIA_PROJECT_DATA.clone()
+                }
+            )
+        }
+
+        async fn record_project_view(
+            &self,
+            _proj: Project
+        )
+        {
+            self.views.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        }
+
+        async fn update_project(
+            &self,
+            _owner: Owner,
+            _proj: Project,
+            _proj_data: &ProjectDataPatch,
+            _expected_revision: Option<i64>
+        ) -> Result<ProjectDataPatchResult, CoreError>
+        {
+            let revision = self.revision.fetch_add(
+                1,
+                std::sync::atomic::Ordering::SeqCst
+            ) + 1;
+
+            Ok(ProjectDataPatchResult { revision, tags: None })
+        }
+    }
+
+    #[tokio::test]
+    async fn get_project_etag_changes_after_update() {
+        let app = routes(API_V1, false, MAX_FILE_SIZE)
+            .with_state(
+                AppState {
+                    core: Arc::new(RevisingCore::default()) as CoreArc,
+                    ..test_state()
+                }
+            );
+
+        let response = app.clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::GET)
+                    .uri(&format!("{API_V1}/projects/a_project"))
+                    .body(Body::empty())
+                    .unwrap()
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let etag = response.headers().get(ETAG).unwrap().clone();
+
+        let proj_data = ProjectDataPatch {
+            description: Some("A module for Empires in Arms".into()),
+            ..Default::default()
+        };
+
+        let response = app.clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::PATCH)
+                    .uri(&format!("{API_V1}/projects/a_project"))
+                    .header(AUTHORIZATION, token(BOB_UID))
+                    .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                    .body(Body::from(serde_json::to_vec(&proj_data).unwrap()))
+                    .unwrap()
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method(Method::GET)
+                    .uri(&format!("{API_V1}/projects/a_project"))
+                    .header(IF_NONE_MATCH, &etag)
+                    .body(Body::empty())
+                    .unwrap()
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_ne!(response.headers().get(ETAG).unwrap(), etag);
+    }
+
+    #[tokio::test]
+    async fn get_project_records_view() {
+        let core = Arc::new(RevisingCore::default());
+
+        let app = routes(API_V1, false, MAX_FILE_SIZE)
+            .with_state(
+                AppState {
+                    core: core.clone() as CoreArc,
+                    ..test_state()
+                }
+            );
+
+        let views = || core.views.load(std::sync::atomic::Ordering::SeqCst);
+
+        let response = app.clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::GET)
+                    .uri(&format!("{API_V1}/projects/a_project"))
+                    .body(Body::empty())
+                    .unwrap()
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(views(), 1);
+        let etag = response.headers().get(ETAG).unwrap().clone();
+
+        // a revalidation is not a view
+        let response = app.clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::GET)
+                    .uri(&format!("{API_V1}/projects/a_project"))
+                    .header(IF_NONE_MATCH, &etag)
+                    .body(Body::empty())
+                    .unwrap()
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(views(), 1);
+
+        // nor is a HEAD request
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method(Method::HEAD)
+                    .uri(&format!("{API_V1}/projects/a_project"))
+                    .body(Body::empty())
+                    .unwrap()
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(views(), 1);
+    }
+
+    #[tokio::test]
+    async fn get_robots_txt() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri("/robots.txt")
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            &body_bytes(response).await,
+            "User-agent: *\nDisallow: /api/v1/*seek=\nAllow: /api/v1/\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn get_projects_noindex() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(headers(&response, "x-robots-tag"), [b"noindex"]);
+    }
+
+    #[tokio::test]
+    async fn get_project_indexable() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/a_project"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(headers(&response, "x-robots-tag").is_empty());
+    }
+
+    #[tokio::test]
+    async fn get_projects_by_name_ok() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects?names=project_b,not_a_project"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            body_as::<ProjectsByName>(response).await,
+            ProjectsByName {
+                projects: [
+                    ("project_b".into(), PROJECT_SUMMARY_B.clone())
+                ].into()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn get_projects_by_name_read_only() {
+        let response = routes(API_V1, true, MAX_FILE_SIZE)
+            .with_state(test_state())
+            .oneshot(
+                Request::builder()
+                    .method(Method::GET)
+                    .uri(&format!("{API_V1}/projects?names=project_b"))
+                    .body(Body::empty())
+                    .unwrap()
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn get_projects_by_name_too_many() {
+        let names = (0..=MAX_PROJECTS_PER_BATCH)
+            .map(|i| format!("project_{i}"))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects?names={names}"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::MalformedQuery)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_projects_popular_ok() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/popular?limit=5"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            body_as::<PopularProjects>(response).await,
+            PopularProjects {
+                projects: vec![PROJECT_SUMMARY_A.clone()]
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn get_projects_popular_limit_too_large() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/popular?limit=101"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::LimitOutOfRange)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_projects_popular_limit_zero() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/popular?limit=0"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn get_projects_recent_ok() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/recent?limit=5"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            body_as::<RecentProjects>(response).await,
+            RecentProjects {
+                projects: vec![PROJECT_SUMMARY_A.clone()]
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn get_projects_recent_read_only_ok() {
+        let response = routes(API_V1, true, MAX_FILE_SIZE)
+            .with_state(test_state())
+            .oneshot(
+                Request::builder()
+                    .method(Method::GET)
+                    .uri(&format!("{API_V1}/projects/recent"))
+                    .body(Body::empty())
+                    .unwrap()
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn get_project_random_ok() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/random"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            body_as::<ProjectData>(response).await,
+            *EIA_PROJECT_DATA
+        );
+    }
+
+    #[tokio::test]
+    async fn get_project_random_read_only_ok() {
+        let response = routes(API_V1, true, MAX_FILE_SIZE)
+            .with_state(test_state())
+            .oneshot(
+                Request::builder()
+                    .method(Method::GET)
+                    .uri(&format!("{API_V1}/projects/random"))
+                    .body(Body::empty())
+                    .unwrap()
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            body_as::<ProjectData>(response).await,
+            *EIA_PROJECT_DATA
+        );
+    }
+
+    #[tokio::test]
+    async fn get_project_moved() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/old_project"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::PERMANENT_REDIRECT);
+        assert_eq!(
+            response.headers().get(LOCATION).unwrap(),
+            &format!("{API_V1}/projects/a_project")
+        );
+    }
+
+    #[tokio::test]
+    async fn get_package_project_moved() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/old_project/packages/a_package"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::PERMANENT_REDIRECT);
+        assert_eq!(
+            response.headers().get(LOCATION).unwrap(),
+            &format!("{API_V1}/projects/a_project/packages/a_package")
+        );
+    }
+
+    #[tokio::test]
+    async fn delete_package_ok() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::DELETE)
+                .uri(&format!("{API_V1}/projects/a_project/packages/a_package"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn delete_package_not_a_project() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::DELETE)
+                .uri(&format!("{API_V1}/projects/not_a_project/packages/a_package"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::NotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn delete_package_not_a_package() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::DELETE)
+                .uri(&format!("{API_V1}/projects/a_project/packages/not_a_package"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::NotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn delete_package_not_owner() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::DELETE)
+                .uri(&format!("{API_V1}/projects/a_project/packages/a_package"))
+                .header(AUTHORIZATION, token(0))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::Unauthorized)
+        );
+    }
+
+    #[tokio::test]
+    async fn delete_package_unauth() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::DELETE)
+                .uri(&format!("{API_V1}/projects/a_project/packages/a_package"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::Unauthorized)
+        );
+    }
+
+    #[tokio::test]
+    async fn delete_package_read_only() {
+        let response = routes(API_V1, true, MAX_FILE_SIZE)
+            .with_state(test_state())
+            .oneshot(
+                Request::builder()
+                    .method(Method::DELETE)
+                    .uri(&format!("{API_V1}/projects/a_project/packages/a_package"))
+                    .header(AUTHORIZATION, token(BOB_UID))
+                    .body(Body::empty())
+                    .unwrap()
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::Forbidden)
+        );
+    }
+
+    #[tokio::test]
+    async fn delete_package_has_releases() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::DELETE)
+                .uri(&format!("{API_V1}/projects/a_project/packages/full_package"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::Conflict)
+        );
+    }
+
+    #[tokio::test]
+    async fn delete_package_has_releases_force() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::DELETE)
+                .uri(&format!("{API_V1}/projects/a_project/packages/full_package?force=true"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(body_empty(response).await);
+    }
+
+    #[tokio::test]
+    async fn patch_package_ok() {
+        let pkg_data = PackageDataPatch {
+            name: None,
+            description: Some("Scenarios for the module".into()),
+            section: Some(None)
+        };
+
+        let response = try_request(
+            Request::builder()
+                .method(Method::PATCH)
+                .uri(&format!("{API_V1}/projects/a_project/packages/a_package"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .body(Body::from(serde_json::to_vec(&pkg_data).unwrap()))
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(body_empty(response).await);
+    }
+
+    #[tokio::test]
+    async fn patch_package_no_data() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::PATCH)
+                .uri(&format!("{API_V1}/projects/a_project/packages/a_package"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .body(Body::from("{}"))
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::JsonError)
+        );
+    }
+
+    #[tokio::test]
+    async fn patch_package_name_in_use() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::PATCH)
+                .uri(&format!("{API_V1}/projects/a_project/packages/a_package"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .body(Body::from(r#"{"name":"empty_package"}"#))
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::Conflict)
+        );
+    }
+
+    #[tokio::test]
+    async fn patch_package_not_a_package() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::PATCH)
+                .uri(&format!("{API_V1}/projects/a_project/packages/not_a_package"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .body(Body::from(r#"{"description":"x"}"#))
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::NotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn patch_package_not_owner() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::PATCH)
+                .uri(&format!("{API_V1}/projects/a_project/packages/a_package"))
+                .header(AUTHORIZATION, token(0))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .body(Body::from(r#"{"description":"x"}"#))
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::Unauthorized)
+        );
+    }
+
+    #[tokio::test]
+    async fn patch_package_read_only() {
+        let response = routes(API_V1, true, MAX_FILE_SIZE)
+            .with_state(test_state())
+            .oneshot(
+                Request::builder()
+                    .method(Method::PATCH)
+                    .uri(&format!("{API_V1}/projects/a_project/packages/a_package"))
+                    .header(AUTHORIZATION, token(BOB_UID))
+                    .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                    .body(Body::from(r#"{"description":"x"}"#))
+                    .unwrap()
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::Forbidden)
+        );
+    }
+
+    #[tokio::test]
+    async fn put_packages_order_ok() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::PUT)
+                .uri(&format!("{API_V1}/projects/a_project/packages/order"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .body(Body::from(r#"["a_package"]"#))
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(body_empty(response).await);
+    }
+
+    #[tokio::test]
+    async fn put_packages_order_mismatch() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::PUT)
+                .uri(&format!("{API_V1}/projects/a_project/packages/order"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .body(Body::from(r#"["a_package","x_package"]"#))
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(
+                AppError::InvalidPackages(
+                    "Invalid packages: unknown package x_package".into()
+                )
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn put_packages_order_not_owner() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::PUT)
+                .uri(&format!("{API_V1}/projects/a_project/packages/order"))
+                .header(AUTHORIZATION, token(0))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .body(Body::from(r#"["a_package"]"#))
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::Unauthorized)
+        );
+    }
+
+    #[tokio::test]
+    async fn put_packages_order_not_json() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::PUT)
+                .uri(&format!("{API_V1}/projects/a_project/packages/order"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .body(Body::from(r#"{"a_package":1}"#))
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::JsonError)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_project_not_a_project() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/not_a_project"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::NotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_project_non_ascii_name() {
+        // the path is percent-encoded; the name is matched decoded
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!(
+                    "{API_V1}/projects/%E5%AE%87%E5%AE%99%E6%88%A6%E8%89%A6%E3%83%A4%E3%83%9E%E3%83%88"
+                ))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn get_project_overlong_name() {
+        let proj = "x".repeat(65);
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/{proj}"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::MalformedQuery)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_project_bad_encoding() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/%FF"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::MalformedQuery)
+        );
+    }
+
+    #[tokio::test]
+    async fn post_project_ok() {
+        let proj_data = ProjectDataPost {
+            description: "A module for Empires in Arms".into(),
+            tags: vec![],
+            game: GameData {
+                title: "Empires in Arms".into(),
+                title_sort_key: "Empires in Arms".into(),
+                publisher: "Avalon Hill".into(),
+                year: "1983".into()
+            },
+            readme: "".into(),
+            image: None,
+            packages: vec![]
+        };
+
+        let response = try_request(
+            Request::builder()
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/projects/not_a_project"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .body(Body::from(serde_json::to_vec(&proj_data).unwrap()))
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(
+            response.headers().get(LOCATION).unwrap(),
+            &format!("{API_V1}/projects/not_a_project")
+        );
+        assert_eq!(
+            body_as::<ProjectData>(response).await,
+            *EIA_PROJECT_DATA
+        );
+    }
+
+    #[tokio::test]
+    async fn post_project_read_only() {
+        let proj_data = ProjectDataPost {
+            description: "A module for Empires in Arms".into(),
+            tags: vec![],
+            game: GameData {
+                title: "Empires in Arms".into(),
+                title_sort_key: "Empires in Arms".into(),
+                publisher: "Avalon Hill".into(),
+                year: "1983".into()
+            },
+            readme: "".into(),
+            image: None,
+            packages: vec![]
+        };
+
+        let response = routes(API_V1, true, MAX_FILE_SIZE)
+            .with_state(test_state())
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri(&format!("{API_V1}/projects/not_a_project"))
+                    .header(AUTHORIZATION, token(BOB_UID))
+                    .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                    .body(Body::from(serde_json::to_vec(&proj_data).unwrap()))
+                    .unwrap()
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::Forbidden)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_project_read_only_ok() {
+        let response = routes(API_V1, true, MAX_FILE_SIZE)
+            .with_state(test_state())
+            .oneshot(
+                Request::builder()
+                    .method(Method::GET)
+                    .uri(&format!("{API_V1}/projects/a_project"))
+                    .body(Body::empty())
+                    .unwrap()
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn not_found_read_only() {
+        let response = routes(API_V1, true, MAX_FILE_SIZE)
+            .with_state(test_state())
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/bogus")
+                    .body(Body::empty())
+                    .unwrap()
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn post_project_with_packages_ok() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/projects/not_a_project"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .body(Body::from(r#"{
+  "description": "",
+  "tags": [],
+  "game": {
+    "title": "Empires in Arms",
+    "title_sort_key": "Empires in Arms",
+    "publisher": "Avalon Hill",
+    "year": "1983"
+  },
+  "readme": "",
+  "image": null,
+  "packages": [
+    { "name": "module", "description": "The module" },
+    { "name": "scenarios", "section": "Extras" }
+  ]
+}"#))
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(
+            body_as::<ProjectData>(response).await,
+            *EIA_PROJECT_DATA
+        );
+    }
+
+    #[tokio::test]
+    async fn post_project_invalid_packages() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/projects/not_a_project"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .body(Body::from(r#"{
+  "description": "",
+  "tags": [],
+  "game": {
+    "title": "Empires in Arms",
+    "title_sort_key": "Empires in Arms",
+    "publisher": "Avalon Hill",
+    "year": "1983"
+  },
+  "readme": "",
+  "image": null,
+  "packages": [
+    { "name": "module" },
+    { "name": "" }
+  ]
+}"#))
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError {
+                error: "Invalid packages: packages[1].name is invalid".into(),
+                details: serde_json::Value::Null
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn post_project_unauth() {
+        let proj_data = ProjectDataPost {
+            description: "A module for Empires in Arms".into(),
+            tags: vec![],
+            game: GameData {
+                title: "Empires in Arms".into(),
+                title_sort_key: "Empires in Arms".into(),
+                publisher: "Avalon Hill".into(),
+                year: "1983".into()
+            },
+            readme: "".into(),
+            image: None,
+            packages: vec![]
+        };
+
+        let response = try_request(
+            Request::builder()
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/projects/not_a_project"))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .body(Body::from(serde_json::to_vec(&proj_data).unwrap()))
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::Unauthorized)
+        );
+    }
+
+    #[tokio::test]
+    async fn post_project_wrong_json() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/projects/not_a_project"))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .header(AUTHORIZATION, token(BOB_UID))
+                .body(Body::from(r#"{ "garbage": "whatever" }"#))
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::JsonError)
+        );
+    }
+
+    #[tokio::test]
+    async fn post_project_wrong_mime_type() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/projects/not_a_project"))
+                .header(CONTENT_TYPE, TEXT_PLAIN.as_ref())
+                .header(AUTHORIZATION, token(BOB_UID))
+                .body(Body::from("stuff"))
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::BadMimeType)
+        );
+    }
+
+    #[tokio::test]
+    async fn post_project_no_mime_type() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/projects/not_a_project"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .body(Body::from("stuff"))
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::BadMimeType)
+        );
+    }
+
+    #[tokio::test]
+    async fn patch_project_ok() {
+        let proj_data = ProjectDataPatch {
+            description: Some("A module for Empires in Arms".into()),
+            ..Default::default()
+        };
+
+        let response = try_request(
+            Request::builder()
+                .method(Method::PATCH)
+                .uri(&format!("{API_V1}/projects/a_project"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .body(Body::from(serde_json::to_vec(&proj_data).unwrap()))
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            body_as::<ProjectDataPatchResult>(response).await,
+            ProjectDataPatchResult {
+                revision: 2,
+                tags: None
+            }
+        );
+    }
+
+    async fn patch_project_if_match(if_match: &str) -> Response {
+        let proj_data = ProjectDataPatch {
+            description: Some("A module for Empires in Arms".into()),
+            ..Default::default()
+        };
+
+        try_request(
+            Request::builder()
+                .method(Method::PATCH)
+                .uri(&format!("{API_V1}/projects/a_project"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .header(IF_MATCH, if_match)
+                .body(Body::from(serde_json::to_vec(&proj_data).unwrap()))
+                .unwrap()
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn patch_project_if_match_ok() {
+        let response = patch_project_if_match("\"1\"").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            body_as::<ProjectDataPatchResult>(response).await,
+            ProjectDataPatchResult {
+                revision: 2,
+                tags: None
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn patch_project_if_match_any() {
+        let response = patch_project_if_match("*").await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn patch_project_if_match_stale() {
+        let response = patch_project_if_match("\"3\"").await;
+        assert_eq!(response.status(), StatusCode::PRECONDITION_FAILED);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::PreconditionFailed)
+        );
+    }
+
+    #[tokio::test]
+    async fn patch_project_if_match_malformed() {
+        let response = patch_project_if_match("1").await;
+        assert_eq!(response.status(), StatusCode::PRECONDITION_FAILED);
+    }
+
+    #[tokio::test]
+    async fn patch_project_tags_ok() {
+        let proj_data = ProjectDataPatch {
+            tags: Some(vec!["wwii".into()]),
+            ..Default::default()
+        };
+
+        let response = try_request(
+            Request::builder()
+                .method(Method::PATCH)
+                .uri(&format!("{API_V1}/projects/a_project"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .body(Body::from(serde_json::to_vec(&proj_data).unwrap()))
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            body_as::<ProjectDataPatchResult>(response).await,
+            ProjectDataPatchResult {
+                revision: 2,
+                tags: Some(
+                    TagChanges {
+                        tags: vec!["wwii".into()],
+                        added: vec!["wwii".into()],
+                        removed: vec![]
+                    }
+                )
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn patch_project_clear_image_ok() {
+        let proj_data = ProjectDataPatch {
+            image: Some(None),
+            ..Default::default()
+        };
+
+        let response = try_request(
+            Request::builder()
+                .method(Method::PATCH)
+                .uri(&format!("{API_V1}/projects/a_project"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .body(Body::from(serde_json::to_vec(&proj_data).unwrap()))
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            body_as::<ProjectDataPatchResult>(response).await,
+            ProjectDataPatchResult {
+                revision: 2,
+                tags: None
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn patch_project_no_data() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::PATCH)
+                .uri(&format!("{API_V1}/projects/a_project"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .body(Body::from("{}"))
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(),  StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::JsonError)
+        );
+    }
+
+    #[tokio::test]
+    async fn patch_project_description_too_long() {
+        let body = serde_json::json!({
+            "description": "x".repeat(PROJECT_DESCRIPTION_MAX_LENGTH + 1)
+        });
+
+        let response = try_request(
+            Request::builder()
+                .method(Method::PATCH)
+                .uri(&format!("{API_V1}/projects/a_project"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .body(Body::from(serde_json::to_vec(&body).unwrap()))
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::JsonError)
+        );
+    }
+
+    #[tokio::test]
+    async fn patch_project_bad_tag() {
+        let body = serde_json::json!({ "tags": ["wwii", " armor"] });
+
+        let response = try_request(
+            Request::builder()
+                .method(Method::PATCH)
+                .uri(&format!("{API_V1}/projects/a_project"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .body(Body::from(serde_json::to_vec(&body).unwrap()))
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::JsonError)
+        );
+    }
+
+    #[tokio::test]
+    async fn patch_project_unauth() {
+        let proj_data = ProjectDataPatch {
+            description: Some("A module for Empires in Arms".into()),
+            ..Default::default()
+        };
+
+        let response = try_request(
+            Request::builder()
+                .method(Method::PATCH)
+                .uri(&format!("{API_V1}/projects/a_project"))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .body(Body::from(serde_json::to_vec(&proj_data).unwrap()))
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::Unauthorized)
+        );
+    }
+
+    #[tokio::test]
+    async fn patch_project_not_owner() {
+        let proj_data = ProjectDataPatch {
+            description: Some("A module for Empires in Arms".into()),
+            ..Default::default()
+        };
+
+        let response = try_request(
+            Request::builder()
+                .method(Method::PATCH)
+                .uri(&format!("{API_V1}/projects/a_project"))
+                .header(AUTHORIZATION, token(0))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .body(Body::from(serde_json::to_vec(&proj_data).unwrap()))
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::Unauthorized)
+        );
+    }
+
+    #[tokio::test]
+    async fn patch_project_wrong_json() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::PATCH)
+                .uri(&format!("{API_V1}/projects/a_project"))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .header(AUTHORIZATION, token(BOB_UID))
+                .body(Body::from(r#"{ "garbage": "whatever" }"#))
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::JsonError)
+        );
+    }
+
+    #[tokio::test]
+    async fn patch_project_wrong_mime_type() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::PATCH)
+                .uri(&format!("{API_V1}/projects/a_project"))
+                .header(CONTENT_TYPE, TEXT_PLAIN.as_ref())
+                .header(AUTHORIZATION, token(BOB_UID))
+                .body(Body::from("stuff"))
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::BadMimeType)
+        );
+    }
+
+    #[tokio::test]
+    async fn patch_project_no_mime_type() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::PATCH)
+                .uri(&format!("{API_V1}/projects/a_project"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .body(Body::from("stuff"))
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::BadMimeType)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_project_revision_ok() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/a_project/1"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            body_as::<ProjectData>(response).await,
+            *EIA_PROJECT_DATA
+        );
+    }
+
+    #[tokio::test]
+    async fn get_project_revision_batch_ok() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/a_project/revisions/batch?nums=1,2"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            body_as::<ProjectRevisionBatch>(response).await,
+            ProjectRevisionBatch { revisions: vec![EIA_PROJECT_DATA.clone()] }
+        );
+    }
+
+    #[tokio::test]
+    async fn get_project_revision_batch_bad_nums() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/a_project/revisions/batch?nums=1,x"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::MalformedQuery)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_project_revision_batch_no_nums() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/a_project/revisions/batch"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::MalformedQuery)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_project_revision_not_a_project() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/not_a_project/1"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::NotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_project_revision_not_a_revision() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/a_project/2"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::NotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_project_diff_ok() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/a_project/revisions/1/diff/2"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            body_as::<serde_json::Value>(response).await,
+            serde_json::json!({
+                "changed_fields": {
+                    "description": { "from": "old", "to": "new" }
+                }
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn get_project_diff_same_revision() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/a_project/revisions/2/diff/2"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::MalformedQuery)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_project_diff_bad_revision() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/a_project/revisions/x/diff/2"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn get_project_revisions_ok() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/a_project/revisions"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            body_as::<ProjectRevisions>(response).await,
+            ProjectRevisions {
+                revisions: vec![
+                    ProjectRevisionSummary {
+                        revision: 2,
+                        modified_at: "2023-12-28T05:36:46.419538067+00:00".into(),
+                        modified_by: "alice".into()
+                    },
+                    ProjectRevisionSummary {
+                        revision: 1,
+                        modified_at: "2023-12-27T05:36:46.419538067+00:00".into(),
+                        modified_by: "bob".into()
+                    }
+                ]
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn get_project_revisions_not_a_project() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/not_a_project/revisions"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::NotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_package_ok() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/a_project/packages/a_package"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+        assert_eq!(
+            response.headers().get(LOCATION).unwrap(),
+            "https://example.com/package"
+        );
+    }
+
+    #[tokio::test]
+    async fn get_latest_release_ok() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/a_project/packages/a_package/latest"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+        assert_eq!(
+            response.headers().get(LOCATION).unwrap(),
+            "https://example.com/latest"
+        );
+    }
+
+    #[tokio::test]
+    async fn get_recommended_release_ok() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/a_project/packages/a_package/recommended"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+        assert_eq!(
+            response.headers().get(LOCATION).unwrap(),
+            "https://example.com/recommended"
+        );
+    }
+
+    async fn put_recommended_request(version: &str, uid: i64) -> Response {
+        try_request(
+            Request::builder()
+                .method(Method::PUT)
+                .uri(&format!("{API_V1}/projects/a_project/packages/a_package/recommended"))
+                .header(AUTHORIZATION, token(uid))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .body(Body::from(
+                    serde_json::to_vec(
+                        &serde_json::json!({ "version": version })
+                    ).unwrap()
+                ))
+                .unwrap()
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn put_recommended_release_ok() {
+        let response = put_recommended_request("1.2.3", BOB_UID).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(body_empty(response).await);
+    }
+
+    #[tokio::test]
+    async fn put_recommended_release_not_a_version() {
+        let response = put_recommended_request("9.9.9", BOB_UID).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::NotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn put_recommended_release_bad_version() {
+        let response = put_recommended_request("bogus", BOB_UID).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::MalformedVersion)
+        );
+    }
+
+    #[tokio::test]
+    async fn put_recommended_release_not_owner() {
+        let response = put_recommended_request("1.2.3", 0).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::Unauthorized)
+        );
+    }
+
+    #[tokio::test]
+    async fn delete_recommended_release_ok() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::DELETE)
+                .uri(&format!("{API_V1}/projects/a_project/packages/a_package/recommended"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(body_empty(response).await);
+    }
+
+    #[tokio::test]
+    async fn delete_recommended_release_read_only() {
+        let response = routes(API_V1, true, MAX_FILE_SIZE)
+            .with_state(test_state())
+            .oneshot(
+                Request::builder()
+                    .method(Method::DELETE)
+                    .uri(&format!("{API_V1}/projects/a_project/packages/a_package/recommended"))
+                    .header(AUTHORIZATION, token(BOB_UID))
+                    .body(Body::empty())
+                    .unwrap()
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::Forbidden)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_latest_release_no_releases() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/a_project/packages/empty_package/latest"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::NotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_latest_release_not_a_package() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/a_project/packages/not_a_package/latest"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::NotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_releases_ok() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/a_project/packages/a_package/releases?limit=5"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            body_as::<Releases>(response).await,
+            Releases {
+                releases: vec![
+                    ReleaseData {
+                        version: "1.2.3".into(),
+                        files: vec![file_data("a_package-1.2.3", 1234)]
+                    }
+                ],
+                meta: Pagination {
+                    prev_page: None,
+                    next_page: None,
+                    total: 1,
+                    total_pages: 1,
+                    approximate: false,
+                    position: None
+                }
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn get_releases_limit_too_large() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/a_project/packages/a_package/releases?limit=101"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::LimitOutOfRange)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_releases_bad_seek() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/a_project/packages/a_package/releases?seek=eCwxLjIuMw"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn get_releases_not_a_package() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/a_project/packages/not_a_package/releases"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::NotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_package_not_a_project() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/not_a_project/packages/a_package"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::NotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_package_json_ok() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/a_project/packages/a_package"))
+                .header(ACCEPT, "application/json")
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            body_as::<PackageData>(response).await,
+            PackageData {
+                name: "a_package".into(),
+                description: "Module".into(),
+                section: None,
+                releases: vec![file_data("a_package-1.2.3", 1234)],
+                files: vec![file_data("a_package-1.2.3", 1234)],
+                recommended_release: None
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn get_package_json_not_a_package() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/a_project/packages/not_a_package"))
+                .header(ACCEPT, "application/json")
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::NotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_package_json_read_only() {
+        let response = routes(API_V1, true, MAX_FILE_SIZE)
+            .with_state(test_state())
+            .oneshot(
+                Request::builder()
+                    .method(Method::GET)
+                    .uri(&format!("{API_V1}/projects/a_project/packages/a_package"))
+                    .header(ACCEPT, "application/json")
+                    .body(Body::empty())
+                    .unwrap()
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        use std::io::Write;
+
+        let mut enc = flate2::write::GzEncoder::new(
+            vec![],
+            flate2::Compression::default()
+        );
+        enc.write_all(data).unwrap();
+        enc.finish().unwrap()
+    }
+
+    async fn gzip_request(enabled: bool, body: Vec<u8>) -> Response {
+        let app = app(API_V1, false, MAX_FILE_SIZE, test_state());
+        with_request_decompression(app, enabled)
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri(&format!("{API_V1}/projects/not_a_project"))
+                    .header(AUTHORIZATION, token(BOB_UID))
+                    .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                    .header(CONTENT_ENCODING, "gzip")
+                    .body(Body::from(gzip(&body)))
+                    .unwrap()
+            )
+            .await
+            .unwrap()
+    }
+
+    fn gzip_project_data(readme: String) -> Vec<u8> {
+        serde_json::to_vec(
+            &ProjectDataPost {
+                description: "A module for Empires in Arms".into(),
+                tags: vec![],
+                game: GameData {
+                    title: "Empires in Arms".into(),
+                    title_sort_key: "Empires in Arms".into(),
+                    publisher: "Avalon Hill".into(),
+                    year: "1983".into()
+                },
+                readme,
+                image: None,
+                packages: vec![]
+            }
+        ).unwrap()
+    }
+
+    #[tokio::test]
+    async fn post_project_gzip_ok() {
+        let response = gzip_request(true, gzip_project_data("".into())).await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+
+    #[tokio::test]
+    async fn post_project_gzip_disabled() {
+        let response = gzip_request(false, gzip_project_data("".into())).await;
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::JsonError)
+        );
+    }
+
+    #[tokio::test]
+    async fn post_project_gzip_bomb() {
+        // a valid project, but compressed from far over the body limit
+        let body = gzip_project_data("a".repeat(16 << 20));
+
+        let response = gzip_request(true, body).await;
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::JsonError)
+        );
+    }
+
+    async fn static_request(dir: &std::path::Path, uri: &str) -> Response {
+        let app = app(API_V1, false, MAX_FILE_SIZE, test_state());
+        with_static_assets(app, API_V1, dir.to_str())
+            .oneshot(
+                Request::builder()
+                    .method(Method::GET)
+                    .uri(uri)
+                    .body(Body::empty())
+                    .unwrap()
+            )
+            .await
+            .unwrap()
+    }
+
+    fn static_dir() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("favicon.ico"), b"icon").unwrap();
+        fs::write(dir.path().join("healthz"), b"shadow").unwrap();
+        let api_dir = dir.path().join("api/v1/projects");
+        fs::create_dir_all(&api_dir).unwrap();
+        fs::write(api_dir.join("a_project"), b"shadow").unwrap();
+        fs::write(api_dir.join("not_a_project"), b"shadow").unwrap();
+        dir
+    }
+
+    #[tokio::test]
+    async fn static_file_ok() {
+        let dir = static_dir();
+        let response = static_request(dir.path(), "/favicon.ico").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(body_bytes(response).await, &b"icon"[..]);
+    }
+
+    #[tokio::test]
+    async fn static_file_not_found() {
+        let dir = static_dir();
+        let response = static_request(dir.path(), "/nothing.txt").await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::NotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn static_file_route_wins() {
+        let dir = static_dir();
+        let response = static_request(dir.path(), "/healthz").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(body_bytes(response).await, &b"ok"[..]);
+    }
+
+    #[tokio::test]
+    async fn static_file_api_route_wins() {
+        let dir = static_dir();
+        let response = static_request(
+            dir.path(),
+            &format!("{API_V1}/projects/a_project")
+        ).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers().get(CONTENT_TYPE).unwrap(), "application/json");
+    }
+
+    #[tokio::test]
+    async fn static_file_not_under_api() {
+        // unmatched API requests are not answered from the directory
+        let dir = static_dir();
+        let response = static_request(
+            dir.path(),
+            &format!("{API_V1}/projects/not_a_project")
+        ).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::NotFound)
+        );
+    }
+
+    async fn options_request(read_only: bool, uri: &str) -> Response {
+        app(API_V1, read_only, MAX_FILE_SIZE, test_state())
+            .oneshot(
+                Request::builder()
+                    .method(Method::OPTIONS)
+                    .uri(uri)
+                    .body(Body::empty())
+                    .unwrap()
+            )
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn options_project_read_write() {
+        let response = options_request(
+            false,
+            &format!("{API_V1}/projects/a_project")
+        ).await;
+
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert_eq!(
+            response.headers().get(header::ALLOW).unwrap(),
+            "GET,HEAD,POST,PATCH,DELETE,OPTIONS"
+        );
+        assert!(body_empty(response).await);
+    }
+
+    #[tokio::test]
+    async fn options_project_read_only() {
+        let response = options_request(
+            true,
+            &format!("{API_V1}/projects/a_project")
+        ).await;
+
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert_eq!(
+            response.headers().get(header::ALLOW).unwrap(),
+            "GET,HEAD,OPTIONS"
+        );
+    }
+
+    #[tokio::test]
+    async fn options_package_read_only() {
+        let response = options_request(
+            true,
+            &format!("{API_V1}/projects/a_project/packages/a_package")
+        ).await;
+
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert_eq!(
+            response.headers().get(header::ALLOW).unwrap(),
+            "GET,HEAD,OPTIONS"
+        );
+    }
+
+    #[tokio::test]
+    async fn options_owners_read_only() {
+        let response = options_request(
+            true,
+            &format!("{API_V1}/projects/a_project/owners")
+        ).await;
+
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert_eq!(
+            response.headers().get(header::ALLOW).unwrap(),
+            "GET,HEAD,OPTIONS"
+        );
+    }
+
+    #[tokio::test]
+    async fn options_not_found() {
+        let response = options_request(false, "/bogus").await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn options_cors_preflight() {
+        let response = app(API_V1, false, MAX_FILE_SIZE, test_state())
+            .oneshot(
+                Request::builder()
+                    .method(Method::OPTIONS)
+                    .uri(&format!("{API_V1}/projects/a_project"))
+                    .header(header::ORIGIN, "https://example.com")
+                    .header(header::ACCESS_CONTROL_REQUEST_METHOD, "PATCH")
+                    .body(Body::empty())
+                    .unwrap()
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(
+            response.headers()
+                .contains_key(header::ACCESS_CONTROL_ALLOW_METHODS)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_package_not_a_package() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/a_project/packages/not_a_package"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::NotFound)
+        );
+    }
+
+// TODO: get_release tests
+
+    #[tokio::test]
+    async fn get_release_version_ok() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/a_project/packages/a_package/1.2.3"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+        assert_eq!(
+            response.headers().get(LOCATION).unwrap(),
+            "https://example.com/package-1.2.3"
+        );
+    }
+
+    #[tokio::test]
+    async fn get_release_version_json_ok() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/a_project/packages/a_package/1.2.3"))
+                .header(ACCEPT, "application/json")
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            body_as::<ReleaseData>(response).await,
+            ReleaseData {
+                version: "1.2.3".into(),
+                files: vec![file_data("a_package-1.2.3", 1234)]
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn get_release_version_json_not_a_version() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/a_project/packages/a_package/1.2.4"))
+                .header(ACCEPT, "application/json")
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::NotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_library_stats_ok() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/stats"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(body_as::<LibraryStats>(response).await, *LIBRARY_STATS);
+    }
+
+    #[tokio::test]
+    async fn get_library_stats_read_only_ok() {
+        let response = routes(API_V1, true, MAX_FILE_SIZE)
+            .with_state(test_state())
+            .oneshot(
+                Request::builder()
+                    .method(Method::GET)
+                    .uri(&format!("{API_V1}/stats"))
+                    .body(Body::empty())
+                    .unwrap()
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(body_as::<LibraryStats>(response).await, *LIBRARY_STATS);
+    }
+
+    #[tokio::test]
+    async fn get_project_stats_ok() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/a_project/stats"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            body_as::<ProjectStats>(response).await,
+            ProjectStats {
+                files: 3,
+                bytes: 12345,
+                releases: 2,
+                packages: 1,
+                players: 2,
+                owners: 2,
+                flags: 0,
+                age_days: 42,
+                views: 7,
+                downloads: 3,
+                daily: vec![
+                    DailyStats {
+                        date: "2023-11-12".into(),
+                        views: 7,
+                        downloads: 3
+                    }
+                ]
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn get_project_status_owner_ok() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/a_project/status"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            body_as::<ProjectStatus>(response).await,
+            ProjectStatus {
+                open_flags: 2,
+                archived: true,
+                bytes: 12345
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn get_project_status_admin_ok() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/a_project/status"))
+                .header(AUTHORIZATION, admin_token(3))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            body_as::<ProjectStatus>(response).await,
+            ProjectStatus {
+                open_flags: 2,
+                archived: true,
+                bytes: 12345
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn get_project_status_not_owner() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/a_project/status"))
+                .header(AUTHORIZATION, token(3))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::NotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_project_status_no_token() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/a_project/status"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::Unauthorized)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_project_status_not_a_project() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/not_a_project/status"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::NotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_project_stats_not_a_project() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/not_a_project/stats"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::NotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_project_readme_markdown_ok() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/a_project/readme"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get(CONTENT_TYPE).unwrap(),
+            "text/markdown; charset=utf-8"
+        );
+        assert_eq!(&body_bytes(response).await[..], b"# Hi");
+    }
+
+    #[tokio::test]
+    async fn get_project_readme_html_ok() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/a_project/readme?format=html"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get(CONTENT_TYPE).unwrap(),
+            "text/html; charset=utf-8"
+        );
+        assert_eq!(&body_bytes(response).await[..], b"<h1>Hi</h1>\n");
+    }
+
+    #[tokio::test]
+    async fn get_project_readme_bad_format() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/a_project/readme?format=pdf"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::MalformedQuery)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_project_readme_not_a_project() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/not_a_project/readme"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::NotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_project_releases_atom_ok() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/a_project/releases.atom"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get(CONTENT_TYPE).unwrap(),
+            "application/atom+xml"
+        );
+
+        let body = String::from_utf8(body_bytes(response).await.to_vec())
+            .unwrap();
+        assert!(body.contains("<id>urn:vassal:project:a_project:a_package:1.2.3</id>"));
+        assert!(body.contains("<link rel=\"enclosure\" href=\"https://example.com/a_package-1.2.3\" title=\"a_package-1.2.3\" length=\"1234\"/>"));
+    }
+
+    #[tokio::test]
+    async fn get_project_releases_atom_read_only_ok() {
+        let response = routes(API_V1, true, MAX_FILE_SIZE)
+            .with_state(test_state())
+            .oneshot(
+                Request::builder()
+                    .method(Method::GET)
+                    .uri(&format!("{API_V1}/projects/a_project/releases.atom"))
+                    .body(Body::empty())
+                    .unwrap()
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn get_project_releases_atom_not_a_project() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/not_a_project/releases.atom"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::NotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_release_version_not_a_project() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/not_a_project/packages/a_package/1.2.3"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::NotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_release_version_not_a_package() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/a_project/packages/not_a_package/1.2.3"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::NotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_release_version_malformed_version() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/a_project/packages/a_package/bogus"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::MalformedVersion)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_release_version_overlong_package() {
+        let pkg = "x".repeat(65);
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/a_project/packages/{pkg}/1.2.3"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::MalformedQuery)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_owners_ok() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/a_project/owners"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            body_as::<Users>(response).await,
+            Users {
+                users: vec![
+                    "alice".into(),
+                    "bob".into()
+                ]
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn get_owners_bad_project() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/not_a_project/owners"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::NotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn delete_project_force_ok() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::DELETE)
+                .uri(&format!("{API_V1}/projects/a_project?force=true"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(body_empty(response).await);
+    }
+
+    #[tokio::test]
+    async fn delete_project_has_releases() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::DELETE)
+                .uri(&format!("{API_V1}/projects/a_project"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::Conflict)
+        );
+    }
+
+    #[tokio::test]
+    async fn delete_project_bad_force() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::DELETE)
+                .uri(&format!("{API_V1}/projects/a_project?force=maybe"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::MalformedQuery)
+        );
+    }
+
+    #[tokio::test]
+    async fn delete_project_not_a_project() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::DELETE)
+                .uri(&format!("{API_V1}/projects/not_a_project"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::NotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn delete_project_not_owner() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::DELETE)
+                .uri(&format!("{API_V1}/projects/a_project"))
+                .header(AUTHORIZATION, token(0))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::Unauthorized)
+        );
+    }
+
+    #[tokio::test]
+    async fn delete_project_unauth() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::DELETE)
+                .uri(&format!("{API_V1}/projects/a_project"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::Unauthorized)
+        );
+    }
+
+    #[tokio::test]
+    async fn delete_project_read_only() {
+        let response = routes(API_V1, true, MAX_FILE_SIZE)
+            .with_state(test_state())
+            .oneshot(
+                Request::builder()
+                    .method(Method::DELETE)
+                    .uri(&format!("{API_V1}/projects/a_project?force=true"))
+                    .header(AUTHORIZATION, token(BOB_UID))
+                    .body(Body::empty())
+                    .unwrap()
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::Forbidden)
+        );
+    }
+
+    const BOUNDARY: &str = "XXXXXXXXXXXXXXXX";
+
+    fn multipart_body(parts: &[(&str, &str)]) -> String {
+        parts.iter()
+            .map(|(filename, content)| format!(
+                "--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{filename}\"\r\nContent-Type: application/octet-stream\r\n\r\n{content}\r\n"
+            ))
+            .chain(std::iter::once(format!("--{BOUNDARY}--\r\n")))
+            .collect()
+    }
+
+    fn file_data(filename: &str, size: i64) -> FileData {
+        FileData {
+            version: "1.2.3".into(),
+            filename: filename.into(),
+            url: format!("https://example.com/{filename}"),
+            size,
+            checksum: "".into(),
+            published_at: "2023-12-27T05:36:46.419538067+00:00".into(),
+            published_by: "bob".into(),
+            requires: "".into(),
+            authors: vec![],
+            primary_file: None
+        }
+    }
+
+    #[tokio::test]
+    async fn post_files_mixed_ok() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/projects/a_project/packages/a_package/1.2.3/files"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .header(
+                    CONTENT_TYPE,
+                    format!("multipart/form-data; boundary={BOUNDARY}")
+                )
+                .body(Body::from(multipart_body(&[
+                    ("good.vmod", "module"),
+                    ("bad.vmod", "module"),
+                    ("scenario.vsav", "save")
+                ])))
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            body_as::<FileResults>(response).await,
+            FileResults {
+                files: vec![
+                    FileResult {
+                        filename: "good.vmod".into(),
+                        status: 201,
+                        file: Some(file_data("good.vmod", 6)),
+                        error: None,
+                        details: serde_json::Value::Null
+                    },
+                    FileResult {
+                        filename: "bad.vmod".into(),
+                        status: 400,
+                        file: None,
+                        error: Some("Bad request".into()),
+                        details: serde_json::json!({
+                            "expected": "1.2.3",
+                            "found": "0.0",
+                            "hint": "the module version must match the release version"
+                        })
+                    },
+                    FileResult {
+                        filename: "scenario.vsav".into(),
+                        status: 201,
+                        file: Some(file_data("scenario.vsav", 4)),
+                        error: None,
+                        details: serde_json::Value::Null
+                    }
+                ]
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn post_files_too_many() {
+        let names = (0..=MAX_FILES_PER_REQUEST)
+            .map(|i| format!("{i}.vsav"))
+            .collect::<Vec<_>>();
+
+        let parts = names.iter()
+            .map(|n| (n.as_str(), "save"))
+            .collect::<Vec<_>>();
+
+        let response = try_request(
+            Request::builder()
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/projects/a_project/packages/a_package/1.2.3/files"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .header(
+                    CONTENT_TYPE,
+                    format!("multipart/form-data; boundary={BOUNDARY}")
+                )
+                .body(Body::from(multipart_body(&parts)))
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let files = body_as::<FileResults>(response).await.files;
+        assert_eq!(files.len(), MAX_FILES_PER_REQUEST + 1);
+        assert!(files[..MAX_FILES_PER_REQUEST].iter().all(|f| f.status == 201));
+        assert_eq!(files[MAX_FILES_PER_REQUEST].status, 413);
+        assert_eq!(files[MAX_FILES_PER_REQUEST].file, None);
+        assert_eq!(
+            files[MAX_FILES_PER_REQUEST].details,
+            serde_json::json!({ "max_files": MAX_FILES_PER_REQUEST })
+        );
+    }
+
+    #[tokio::test]
+    async fn post_files_over_default_body_limit() {
+        // three parts of 1MB each exceed axum's 2MB default body limit
+        let content = "x".repeat(MAX_FILE_SIZE as usize);
+
+        let response = try_request(
+            Request::builder()
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/projects/a_project/packages/a_package/1.2.3/files"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .header(
+                    CONTENT_TYPE,
+                    format!("multipart/form-data; boundary={BOUNDARY}")
+                )
+                .body(Body::from(multipart_body(&[
+                    ("a.vsav", &content),
+                    ("b.vsav", &content),
+                    ("c.vsav", &content)
+                ])))
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            body_as::<FileResults>(response).await
+                .files
+                .into_iter()
+                .map(|f| (f.filename, f.status, f.file.map(|f| f.size)))
+                .collect::<Vec<_>>(),
+            [
+                ("a.vsav".into(), 201, Some(MAX_FILE_SIZE as i64)),
+                ("b.vsav".into(), 201, Some(MAX_FILE_SIZE as i64)),
+                ("c.vsav".into(), 201, Some(MAX_FILE_SIZE as i64))
+            ]
+        );
+    }
+
+    // a body which arrives in pieces, as it would from a client
+    fn chunked(
+        body: String
+    ) -> impl futures::Stream<Item = Result<Bytes, std::io::Error>>
+    {
+        use futures::StreamExt;
+
+        futures::stream::iter(
+            body.into_bytes()
+                .chunks(1 << 16)
+                .map(Bytes::copy_from_slice)
+                .collect::<Vec<_>>()
+        )
+        .then(|chunk| async {
+            // let the reader catch up before the next piece arrives
+            tokio::task::yield_now().await;
+            Ok(chunk)
+        })
+    }
+
+    #[tokio::test]
+    async fn post_files_over_body_limit_partial() {
+        let content = "x".repeat(
+            MAX_FILES_PER_REQUEST * MAX_FILE_SIZE as usize + 1
+        );
+
+        let response = try_request(
+            Request::builder()
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/projects/a_project/packages/a_package/1.2.3/files"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .header(
+                    CONTENT_TYPE,
+                    format!("multipart/form-data; boundary={BOUNDARY}")
+                )
+                .body(Body::from_stream(chunked(multipart_body(&[
+                    ("good.vmod", "module"),
+                    ("big.vsav", &content),
+                    ("scenario.vsav", "save")
+                ]))))
+                .unwrap()
+        )
+        .await;
+
+        // the part stored before the limit was reached is reported, and
+        // the oversized part is refused before the body runs out
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            body_as::<FileResults>(response).await,
+            FileResults {
+                files: vec![
+                    FileResult {
+                        filename: "good.vmod".into(),
+                        status: 201,
+                        file: Some(file_data("good.vmod", 6)),
+                        error: None,
+                        details: serde_json::Value::Null
+                    },
+                    FileResult {
+                        filename: "big.vsav".into(),
+                        status: 413,
+                        file: None,
+                        error: Some("Payload too large".into()),
+                        details: serde_json::json!({ "max_size": 1 << 20 })
+                    },
+                    FileResult {
+                        filename: "".into(),
+                        status: 400,
+                        file: None,
+                        error: Some("Bad request".into()),
+                        details: serde_json::Value::Null
+                    }
+                ]
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn post_files_over_file_size_limit() {
+        let content = "x".repeat(MAX_FILE_SIZE as usize + 1);
+
+        let response = try_request(
+            Request::builder()
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/projects/a_project/packages/a_package/1.2.3/files"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .header(
+                    CONTENT_TYPE,
+                    format!("multipart/form-data; boundary={BOUNDARY}")
+                )
+                .body(Body::from_stream(chunked(multipart_body(&[
+                    ("big.vsav", &content),
+                    ("scenario.vsav", "save")
+                ]))))
+                .unwrap()
+        )
+        .await;
+
+        // the oversized part is refused, but the next part is stored
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            body_as::<FileResults>(response).await
+                .files
+                .into_iter()
+                .map(|f| (f.filename, f.status))
+                .collect::<Vec<_>>(),
+            [
+                ("big.vsav".into(), 413),
+                ("scenario.vsav".into(), 201)
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn post_files_error_details() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/projects/a_project/packages/a_package/1.2.3/files"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .header(
+                    CONTENT_TYPE,
+                    format!("multipart/form-data; boundary={BOUNDARY}")
+                )
+                .body(Body::from(multipart_body(&[
+                    ("bogus.vmod", "not a zip"),
+                    ("../scenario.vsav", "save"),
+                    ("huge.vsav", "save")
+                ])))
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            body_as::<FileResults>(response).await
+                .files
+                .into_iter()
+                .map(|f| (f.status, f.error, f.details))
+                .collect::<Vec<_>>(),
+            [
+                (
+                    400,
+                    Some("Bad request".into()),
+                    serde_json::json!({
+                        "hint": "modules must be zip archives containing moduledata"
+                    })
+                ),
+                (
+                    400,
+                    Some("Bad request".into()),
+                    serde_json::json!({
+                        "hint": "filenames must not contain path separators"
+                    })
+                ),
+                (
+                    413,
+                    Some("Payload too large".into()),
+                    serde_json::json!({ "max_size": 1 << 20 })
+                )
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn post_files_not_multipart() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/projects/a_project/packages/a_package/1.2.3/files"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .body(Body::from("{}"))
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn post_files_not_owner() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/projects/a_project/packages/a_package/1.2.3/files"))
+                .header(AUTHORIZATION, token(0))
+                .header(
+                    CONTENT_TYPE,
+                    format!("multipart/form-data; boundary={BOUNDARY}")
+                )
+                .body(Body::from(multipart_body(&[("good.vmod", "module")])))
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::Unauthorized)
+        );
+    }
+
+    #[tokio::test]
+    async fn delete_release_ok() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::DELETE)
+                .uri(&format!("{API_V1}/projects/a_project/packages/a_package/1.2.3"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(body_empty(response).await);
+    }
+
+    #[tokio::test]
+    async fn delete_release_not_a_version() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::DELETE)
+                .uri(&format!("{API_V1}/projects/a_project/packages/a_package/9.9.9"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::NotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn delete_release_bad_version() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::DELETE)
+                .uri(&format!("{API_V1}/projects/a_project/packages/a_package/x"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::MalformedVersion)
+        );
+    }
+
+    #[tokio::test]
+    async fn delete_release_not_a_package() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::DELETE)
+                .uri(&format!("{API_V1}/projects/a_project/packages/not_a_package/1.2.3"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::NotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn delete_release_not_a_project() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::DELETE)
+                .uri(&format!("{API_V1}/projects/not_a_project/packages/a_package/1.2.3"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::NotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn delete_release_not_owner() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::DELETE)
+                .uri(&format!("{API_V1}/projects/a_project/packages/a_package/1.2.3"))
+                .header(AUTHORIZATION, token(0))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::Unauthorized)
+        );
+    }
+
+    #[tokio::test]
+    async fn delete_release_unauth() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::DELETE)
+                .uri(&format!("{API_V1}/projects/a_project/packages/a_package/1.2.3"))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::Unauthorized)
+        );
+    }
+
+    #[tokio::test]
+    async fn delete_release_read_only() {
+        let response = routes(API_V1, true, MAX_FILE_SIZE)
+            .with_state(test_state())
+            .oneshot(
+                Request::builder()
+                    .method(Method::DELETE)
+                    .uri(&format!("{API_V1}/projects/a_project/packages/a_package/1.2.3"))
+                    .header(AUTHORIZATION, token(BOB_UID))
+                    .body(Body::empty())
+                    .unwrap()
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::Forbidden)
+        );
+    }
+
+    #[tokio::test]
+    async fn put_primary_file_ok() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::PUT)
+                .uri(&format!("{API_V1}/projects/a_project/packages/a_package/1.2.3/primary"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .body(Body::from(r#"{ "filename": "a_package-1.2.3.vmod" }"#))
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(body_empty(response).await);
+    }
+
+    #[tokio::test]
+    async fn put_primary_file_not_a_file() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::PUT)
+                .uri(&format!("{API_V1}/projects/a_project/packages/a_package/1.2.3/primary"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .body(Body::from(r#"{ "filename": "bogus.vmod" }"#))
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::NotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn put_primary_file_not_a_package() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::PUT)
+                .uri(&format!("{API_V1}/projects/a_project/packages/not_a_package/1.2.3/primary"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .body(Body::from(r#"{ "filename": "a_package-1.2.3.vmod" }"#))
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::NotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn put_primary_file_bad_version() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::PUT)
+                .uri(&format!("{API_V1}/projects/a_project/packages/a_package/xyz/primary"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .body(Body::from(r#"{ "filename": "a_package-1.2.3.vmod" }"#))
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::MalformedVersion)
+        );
+    }
+
+    #[tokio::test]
+    async fn put_primary_file_not_owner() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::PUT)
+                .uri(&format!("{API_V1}/projects/a_project/packages/a_package/1.2.3/primary"))
+                .header(AUTHORIZATION, token(0))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .body(Body::from(r#"{ "filename": "a_package-1.2.3.vmod" }"#))
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::Unauthorized)
+        );
     }
 
-    fn test_state() -> AppState {
-        AppState {
-            key: DecodingKey::from_secret(KEY),
-            core: Arc::new(TestCore {}) as CoreArc
-        }
+    #[tokio::test]
+    async fn put_primary_file_unauth() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::PUT)
+                .uri(&format!("{API_V1}/projects/a_project/packages/a_package/1.2.3/primary"))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .body(Body::from(r#"{ "filename": "a_package-1.2.3.vmod" }"#))
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::Unauthorized)
+        );
     }
 
-    fn token(uid: i64) -> String {
-        let ekey = EncodingKey::from_secret(KEY);
-        let token = jwt::issue(&ekey, uid, 0, 899999999999).unwrap();
-        format!("Bearer {token}")
+    #[tokio::test]
+    async fn put_owners_ok() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::PUT)
+                .uri(&format!("{API_V1}/projects/a_project/owners"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .body(Body::from(r#"{ "users": ["alice", "bob"] }"#))
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(body_empty(response).await);
     }
 
-    async fn try_request(request: Request<Body>) -> Response {
-        routes(API_V1)
+    #[tokio::test]
+    async fn put_owners_read_only() {
+        let response = routes(API_V1, true, MAX_FILE_SIZE)
             .with_state(test_state())
-            .oneshot(request)
+            .oneshot(
+                Request::builder()
+                    .method(Method::PUT)
+                    .uri(&format!("{API_V1}/projects/a_project/owners"))
+                    .header(AUTHORIZATION, token(BOB_UID))
+                    .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                    .body(Body::from(r#"{ "users": ["alice", "bob"] }"#))
+                    .unwrap()
+            )
             .await
-            .unwrap()
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::Forbidden)
+        );
     }
 
-    fn headers<'a>(
-        response: &'a Response,
-        header_name: &str
-    ) -> Vec<&'a [u8]>
-    {
-        let mut values = response
-            .headers()
-            .get_all(header_name)
-            .iter()
-            .flat_map(|v| v.as_ref().split(|b| b == &b','))
-            .map(|v| if v[0] == b' ' { &v[1..] } else { v })
-            .collect::<Vec<_>>();
+    #[tokio::test]
+    async fn put_owners_bad_project() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::PUT)
+                .uri(&format!("{API_V1}/projects/not_a_project/owners"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .body(Body::from(r#"{ "users": ["alice", "bob"] }"#))
+                .unwrap()
+        )
+        .await;
 
-        values.sort();
-        values
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::NotFound)
+        );
     }
 
-    async fn assert_shutdown(sig: Signal) {
-        let listener = TcpListener::bind("localhost:0").await.unwrap();
-        let app = Router::new();
-        let pid = Pid::this();
+    #[tokio::test]
+    async fn put_owners_unauth() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::PUT)
+                .uri(&format!("{API_V1}/projects/a_project/owners"))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .body(Body::from(r#"{ "users": ["alice", "bob"] }"#))
+                .unwrap()
+        )
+        .await;
 
-        let server_handle = tokio::spawn(
-            serve(listener, app)
-                .with_graceful_shutdown(shutdown_signal())
-                .into_future()
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::Unauthorized)
         );
+    }
 
-        // ensure that the server has a chance to start
-        tokio::task::yield_now().await;
-
-        sys::signal::kill(pid, sig).unwrap();
+    #[tokio::test]
+    async fn put_owners_not_owner() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::PUT)
+                .uri(&format!("{API_V1}/projects/a_project/owners"))
+                .header(AUTHORIZATION, token(0))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .body(Body::from(r#"{ "users": ["alice", "bob"] }"#))
+                .unwrap()
+        )
+        .await;
 
-        server_handle.await.unwrap().unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::Unauthorized)
+        );
     }
 
     #[tokio::test]
-    async fn graceful_shutdown_sigint() {
-        assert_shutdown(Signal::SIGTERM).await;
+    async fn put_owners_wrong_json() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::PUT)
+                .uri(&format!("{API_V1}/projects/a_project/owners"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .body(Body::from(r#"{ "garbage": "whatever" }"#))
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::JsonError)
+        );
     }
 
     #[tokio::test]
-    async fn graceful_shutdown_sigquit() {
-        assert_shutdown(Signal::SIGQUIT).await;
+    async fn put_owners_wrong_mime_type() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::PUT)
+                .uri(&format!("{API_V1}/projects/a_project/owners"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .header(CONTENT_TYPE, TEXT_PLAIN.as_ref())
+                .body(Body::from("stuff"))
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::BadMimeType)
+        );
     }
 
     #[tokio::test]
-    async fn graceful_shutdown_sigterm() {
-        assert_shutdown(Signal::SIGTERM).await;
+    async fn put_owners_no_mime_type() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::PUT)
+                .uri(&format!("{API_V1}/projects/a_project/owners"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .body(Body::from(r#"{ "users": ["alice", "bob"] }"#))
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::BadMimeType)
+        );
     }
 
     #[tokio::test]
-    async fn cors_ok() {
+    async fn delete_owners_ok() {
         let response = try_request(
             Request::builder()
-                .method(Method::GET)
-                .uri(&format!("{API_V1}/"))
-                .body(Body::empty())
+                .method(Method::DELETE)
+                .uri(&format!("{API_V1}/projects/a_project/owners"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .body(Body::from(r#"{ "users": ["alice", "bob"] }"#))
                 .unwrap()
         )
         .await;
 
         assert_eq!(response.status(), StatusCode::OK);
+        assert!(body_empty(response).await);
+    }
+
+    #[tokio::test]
+    async fn delete_owners_bad_project() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::DELETE)
+                .uri(&format!("{API_V1}/projects/not_a_project/owners"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .body(Body::from(r#"{ "users": ["alice", "bob"] }"#))
+                .unwrap()
+        )
+        .await;
 
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
         assert_eq!(
-            headers(&response, "access-control-allow-credentials"),
-            ["true".as_bytes()]
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::NotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn delete_owners_unauth() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::DELETE)
+                .uri(&format!("{API_V1}/projects/a_project/owners"))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .body(Body::from(r#"{ "users": ["alice", "bob"] }"#))
+                .unwrap()
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::Unauthorized)
         );
+    }
+
+    #[tokio::test]
+    async fn delete_owners_not_owner() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::DELETE)
+                .uri(&format!("{API_V1}/projects/a_project/owners"))
+                .header(AUTHORIZATION, token(0))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .body(Body::from(r#"{ "users": ["alice", "bob"] }"#))
+                .unwrap()
+        )
+        .await;
 
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
         assert_eq!(
-            headers(&response, "vary"),
-            [
-                "access-control-request-headers".as_bytes(),
-                "access-control-request-method".as_bytes(),
-                "origin".as_bytes()
-            ]
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::Unauthorized)
         );
     }
 
-    async fn try_compression(comp: &str) {
+    #[tokio::test]
+    async fn delete_owners_wrong_json() {
         let response = try_request(
             Request::builder()
-                .method(Method::GET)
-                .uri(&format!("{API_V1}/projects"))
-                .header(ACCEPT_ENCODING, comp)
-                .body(Body::empty())
+                .method(Method::DELETE)
+                .uri(&format!("{API_V1}/projects/a_project/owners"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .body(Body::from(r#"{ "garbage": "whatever" }"#))
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::OK);
-
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
         assert_eq!(
-            headers(&response, "content-encoding"),
-            [comp.as_bytes()]
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::JsonError)
         );
     }
 
     #[tokio::test]
-    async fn compression_br() {
-        try_compression("br").await;
-    }
-
-    #[tokio::test]
-    async fn compression_deflate() {
-        try_compression("deflate").await;
-    }
-
-    #[tokio::test]
-    async fn compression_gzip() {
-        try_compression("gzip").await;
-    }
-
-    #[tokio::test]
-    async fn compression_zstd() {
-        try_compression("zstd").await;
-    }
-
-    #[tokio::test]
-    async fn bad_path() {
+    async fn delete_owners_wrong_mime_type() {
         let response = try_request(
             Request::builder()
-                .method(Method::GET)
-                .uri(&format!("{API_V1}/bogus/whatever"))
-                .body(Body::empty())
+                .method(Method::DELETE)
+                .uri(&format!("{API_V1}/projects/a_project/owners"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .header(CONTENT_TYPE, TEXT_PLAIN.as_ref())
+                .body(Body::from("stuff"))
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
         assert_eq!(
             body_as::<HttpError>(response).await,
-            HttpError::from(AppError::NotFound)
+            HttpError::from(AppError::BadMimeType)
         );
     }
 
     #[tokio::test]
-    async fn root_ok() {
+    async fn delete_owners_no_mime_type() {
         let response = try_request(
             Request::builder()
-                .method(Method::GET)
-                .uri(&format!("{API_V1}/"))
-                .body(Body::empty())
+                .method(Method::DELETE)
+                .uri(&format!("{API_V1}/projects/a_project/owners"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .body(Body::from(r#"{ "users": ["alice", "bob"] }"#))
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::OK);
-        assert_eq!(&body_bytes(response).await[..], b"hello world");
+        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::BadMimeType)
+        );
     }
 
     #[tokio::test]
-    async fn get_projects_no_params_ok() {
+    async fn get_players_ok() {
         let response = try_request(
             Request::builder()
                 .method(Method::GET)
-                .uri(&format!("{API_V1}/projects"))
+                .uri(&format!("{API_V1}/projects/a_project/players"))
                 .body(Body::empty())
                 .unwrap()
         )
@@ -771,724 +6850,421 @@ mod test {
 
         assert_eq!(response.status(), StatusCode::OK);
         assert_eq!(
-            body_as::<Projects>(response).await,
-            Projects {
-                projects: vec![
-                    PROJECT_SUMMARY_A.clone(),
-                    PROJECT_SUMMARY_B.clone()
-                ],
-                meta: Pagination {
-                    prev_page: Some(
-                        SeekLink::new(
-                            &Seek {
-                                anchor: Anchor::Before("project_a".into(), 0),
-                                sort_by: SortBy::ProjectName,
-                                dir: Direction::Ascending
-
-                            },
-                            None
-                        ).unwrap()
-                    ),
-                    next_page: Some(
-                        SeekLink::new(
-                            &Seek {
-                                anchor: Anchor::After("project_b".into(), 0),
-                                sort_by: SortBy::ProjectName,
-                                dir: Direction::Ascending
-                            },
-                            None
-                        ).unwrap()
-                    ),
-                    total: 1234
-                }
+            body_as::<Users>(response).await,
+            Users {
+                users: vec![
+                    "player 1".into(),
+                    "player 2".into()
+                ]
             }
         );
     }
 
     #[tokio::test]
-    async fn get_projects_limit_ok() {
+    async fn get_players_not_a_project() {
         let response = try_request(
             Request::builder()
                 .method(Method::GET)
-                .uri(&format!("{API_V1}/projects?limit=5"))
+                .uri(&format!("{API_V1}/projects/not_a_project/players"))
                 .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
         assert_eq!(
-            body_as::<Projects>(response).await,
-            Projects {
-                projects: vec![
-                    PROJECT_SUMMARY_A.clone(),
-                    PROJECT_SUMMARY_B.clone()
-                ],
-                meta: Pagination {
-                    prev_page: Some(
-                        SeekLink::new(
-                            &Seek {
-                                anchor: Anchor::Before("project_a".into(), 0),
-                                sort_by: SortBy::ProjectName,
-                                dir: Direction::Ascending
-                            },
-                            Limit::new(5)
-                        ).unwrap()
-                    ),
-                    next_page: Some(
-                        SeekLink::new(
-                            &Seek {
-                                anchor: Anchor::After("project_b".into(), 0),
-                                sort_by: SortBy::ProjectName,
-                                dir: Direction::Ascending
-                            },
-                            Limit::new(5)
-                        ).unwrap()
-                    ),
-                    total: 1234
-                }
-            }
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::NotFound)
         );
     }
 
     #[tokio::test]
-    async fn get_projects_limit_zero() {
+    async fn put_players_ok() {
         let response = try_request(
             Request::builder()
-                .method(Method::GET)
-                .uri(&format!("{API_V1}/projects?limit=0"))
+                .method(Method::PUT)
+                .uri(&format!("{API_V1}/projects/a_project/players"))
+                .header(AUTHORIZATION, token(BOB_UID))
                 .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
-        assert_eq!(
-            body_as::<HttpError>(response).await,
-            HttpError::from(AppError::LimitOutOfRange)
-        );
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(body_empty(response).await);
     }
 
     #[tokio::test]
-    async fn get_projects_limit_too_large() {
+    async fn put_players_not_a_project() {
         let response = try_request(
             Request::builder()
-                .method(Method::GET)
-                .uri(&format!("{API_V1}/projects?limit=100000"))
+                .method(Method::PUT)
+                .uri(&format!("{API_V1}/projects/not_a_project/owners"))
+                .header(AUTHORIZATION, token(BOB_UID))
                 .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
         assert_eq!(
             body_as::<HttpError>(response).await,
-            HttpError::from(AppError::LimitOutOfRange)
+            HttpError::from(AppError::NotFound)
         );
     }
 
     #[tokio::test]
-    async fn get_projects_limit_empty() {
+    async fn put_players_unauth() {
         let response = try_request(
             Request::builder()
-                .method(Method::GET)
-                .uri(&format!("{API_V1}/projects?limit="))
+                .method(Method::PUT)
+                .uri(&format!("{API_V1}/projects/a_project/players"))
                 .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
         assert_eq!(
             body_as::<HttpError>(response).await,
-            HttpError::from(AppError::LimitOutOfRange)
+            HttpError::from(AppError::Unauthorized)
         );
     }
 
     #[tokio::test]
-    async fn get_projects_limit_not_a_number() {
+    async fn delete_players_ok() {
         let response = try_request(
             Request::builder()
-                .method(Method::GET)
-                .uri(&format!("{API_V1}/projects?limit=eleventeen"))
+                .method(Method::DELETE)
+                .uri(&format!("{API_V1}/projects/a_project/players"))
+                .header(AUTHORIZATION, token(8))
                 .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
-        assert_eq!(
-            body_as::<HttpError>(response).await,
-            HttpError::from(AppError::LimitOutOfRange)
-        );
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(body_empty(response).await);
     }
 
     #[tokio::test]
-    async fn get_projects_seek_start_ok() {
-        let query = SeekLink::new(
-            &Seek {
-                anchor: Anchor::Start,
-                sort_by: SortBy::ProjectName,
-                dir: Direction::Ascending
-            },
-            None
-        ).unwrap();
-
+    async fn delete_players_not_a_project() {
         let response = try_request(
             Request::builder()
-                .method(Method::GET)
-                .uri(&format!("{API_V1}/projects{query}"))
+                .method(Method::DELETE)
+                .uri(&format!("{API_V1}/projects/not_a_project/players"))
+                .header(AUTHORIZATION, token(8))
                 .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
         assert_eq!(
-            body_as::<Projects>(response).await,
-            Projects {
-                projects: vec![
-                    PROJECT_SUMMARY_A.clone(),
-                    PROJECT_SUMMARY_B.clone()
-                ],
-                meta: Pagination {
-                    prev_page: Some(
-                        SeekLink::new(
-                            &Seek {
-                                anchor: Anchor::Before("project_a".into(), 0),
-                                sort_by: SortBy::ProjectName,
-                                dir: Direction::Ascending
-                            },
-                            None
-                        ).unwrap()
-                    ),
-                    next_page: Some(
-                        SeekLink::new(
-                            &Seek {
-                                anchor: Anchor::After("project_b".into(), 0),
-                                sort_by: SortBy::ProjectName,
-                                dir: Direction::Ascending
-                            },
-                            None
-                        ).unwrap()
-                    ),
-                    total: 1234
-                }
-            }
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::NotFound)
         );
-    }
-
-    #[tokio::test]
-    async fn get_projects_seek_end_ok() {
-        let query = SeekLink::new(
-            &Seek {
-                anchor: Anchor::Start,
-                sort_by: SortBy::ProjectName,
-                dir: Direction::Descending
-            },
-            None
-        ).unwrap();
+    }
 
+    #[tokio::test]
+    async fn delete_players_unauth() {
         let response = try_request(
             Request::builder()
-                .method(Method::GET)
-                .uri(&format!("{API_V1}/projects{query}"))
+                .method(Method::DELETE)
+                .uri(&format!("{API_V1}/projects/a_project/players"))
                 .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
         assert_eq!(
-            body_as::<Projects>(response).await,
-            Projects {
-                projects: vec![
-                    PROJECT_SUMMARY_A.clone(),
-                    PROJECT_SUMMARY_B.clone()
-                ],
-                meta: Pagination {
-                    prev_page: Some(
-                        SeekLink::new(
-                            &Seek {
-                                anchor: Anchor::Before("project_a".into(), 0),
-                                sort_by: SortBy::ProjectName,
-                                dir: Direction::Ascending
-                            },
-                            None
-                        ).unwrap()
-                    ),
-                    next_page: Some(
-                        SeekLink::new(
-                            &Seek {
-                                anchor: Anchor::After("project_b".into(), 0),
-                                sort_by: SortBy::ProjectName,
-                                dir: Direction::Ascending
-                            },
-                            None
-                        ).unwrap()
-                    ),
-                    total: 1234
-                }
-            }
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::Unauthorized)
         );
     }
 
     #[tokio::test]
-    async fn get_projects_seek_before_ok() {
-        let query = SeekLink::new(
-            &Seek {
-                anchor: Anchor::Before("xyz".into(), 0),
-                sort_by: SortBy::ProjectName,
-                dir: Direction::Ascending
-            },
-            None
-        ).unwrap();
-
+    async fn post_subscribe_ok() {
         let response = try_request(
             Request::builder()
-                .method(Method::GET)
-                .uri(&format!("{API_V1}/projects{query}"))
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/projects/a_project/subscribe"))
+                .header(AUTHORIZATION, token(BOB_UID))
                 .body(Body::empty())
                 .unwrap()
         )
         .await;
 
         assert_eq!(response.status(), StatusCode::OK);
-        assert_eq!(
-            body_as::<Projects>(response).await,
-            Projects {
-                projects: vec![
-                    PROJECT_SUMMARY_A.clone(),
-                    PROJECT_SUMMARY_B.clone()
-                ],
-                meta: Pagination {
-                    prev_page: Some(
-                        SeekLink::new(
-                            &Seek {
-                                anchor: Anchor::Before("project_a".into(), 0),
-                                sort_by: SortBy::ProjectName,
-                                dir: Direction::Ascending
-                            },
-                            None
-                        ).unwrap()
-                    ),
-                    next_page: Some(
-                        SeekLink::new(
-                            &Seek {
-                                anchor: Anchor::After("project_b".into(), 0),
-                                sort_by: SortBy::ProjectName,
-                                dir: Direction::Ascending
-                            },
-                            None
-                        ).unwrap()
-                    ),
-                    total: 1234
-                }
-            }
-        );
+        assert!(body_empty(response).await);
     }
 
     #[tokio::test]
-    async fn get_projects_seek_after_ok() {
-        let query = SeekLink::new(
-            &Seek {
-                anchor: Anchor::After("xyz".into(), 0),
-                sort_by: SortBy::ProjectName,
-                dir: Direction::Ascending
-            },
-            None
-        ).unwrap();
-
+    async fn post_subscribe_not_a_player() {
         let response = try_request(
             Request::builder()
-                .method(Method::GET)
-                .uri(&format!("{API_V1}/projects{query}"))
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/projects/a_project/subscribe"))
+                .header(AUTHORIZATION, token(3))
                 .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
         assert_eq!(
-            body_as::<Projects>(response).await,
-            Projects {
-                projects: vec![
-                    PROJECT_SUMMARY_A.clone(),
-                    PROJECT_SUMMARY_B.clone()
-                ],
-                meta: Pagination {
-                    prev_page: Some(
-                        SeekLink::new(
-                            &Seek {
-                                anchor: Anchor::Before("project_a".into(), 0),
-                                sort_by: SortBy::ProjectName,
-                                dir: Direction::Ascending
-                            },
-                            None
-                        ).unwrap()
-                    ),
-                    next_page: Some(
-                        SeekLink::new(
-                            &Seek {
-                                anchor: Anchor::After("project_b".into(), 0),
-                                sort_by: SortBy::ProjectName,
-                                dir: Direction::Ascending
-                            },
-                            None
-                        ).unwrap()
-                    ),
-                    total: 1234
-                }
-            }
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::Forbidden)
         );
     }
 
     #[tokio::test]
-    async fn get_projects_seek_empty() {
+    async fn post_subscribe_not_a_project() {
         let response = try_request(
             Request::builder()
-                .method(Method::GET)
-                .uri(&format!("{API_V1}/projects?seek="))
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/projects/not_a_project/subscribe"))
+                .header(AUTHORIZATION, token(BOB_UID))
                 .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
         assert_eq!(
             body_as::<HttpError>(response).await,
-            HttpError::from(AppError::MalformedQuery)
+            HttpError::from(AppError::NotFound)
         );
     }
 
     #[tokio::test]
-    async fn get_projects_seek_bad() {
+    async fn post_subscribe_unauth() {
         let response = try_request(
             Request::builder()
-                .method(Method::GET)
-                .uri(&format!("{API_V1}/projects?seek=%@$"))
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/projects/a_project/subscribe"))
                 .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
         assert_eq!(
             body_as::<HttpError>(response).await,
-            HttpError::from(AppError::MalformedQuery)
+            HttpError::from(AppError::Unauthorized)
         );
     }
 
     #[tokio::test]
-    async fn get_projects_seek_too_long() {
-        let long = "x".repeat(1000);
+    async fn post_unsubscribe_ok() {
+        let response = try_request(
+            Request::builder()
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/projects/a_project/unsubscribe"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .body(Body::empty())
+                .unwrap()
+        )
+        .await;
 
-        let query = SeekLink::new(
-            &Seek {
-                anchor: Anchor::Before(long, 0),
-                sort_by: SortBy::ProjectName,
-                dir: Direction::Ascending
-            },
-            Limit::new(5)
-        ).unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(body_empty(response).await);
+    }
 
+    #[tokio::test]
+    async fn post_unsubscribe_not_a_player() {
         let response = try_request(
             Request::builder()
-                .method(Method::GET)
-                .uri(&format!("{API_V1}/projects?seek={query}"))
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/projects/a_project/unsubscribe"))
+                .header(AUTHORIZATION, token(3))
                 .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
         assert_eq!(
             body_as::<HttpError>(response).await,
-            HttpError::from(AppError::MalformedQuery)
+            HttpError::from(AppError::Forbidden)
         );
     }
 
     #[tokio::test]
-    async fn get_projects_seek_and_limit_ok() {
-        let query = SeekLink::new(
-            &Seek {
-                anchor: Anchor::Start,
-                sort_by: SortBy::ProjectName,
-                dir: Direction::Ascending
-            },
-            Limit::new(5)
-        ).unwrap();
-
+    async fn post_unsubscribe_not_a_project() {
         let response = try_request(
             Request::builder()
-                .method(Method::GET)
-                .uri(&format!("{API_V1}/projects{query}"))
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/projects/not_a_project/unsubscribe"))
+                .header(AUTHORIZATION, token(BOB_UID))
                 .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
         assert_eq!(
-            body_as::<Projects>(response).await,
-            Projects {
-                projects: vec![
-                    PROJECT_SUMMARY_A.clone(),
-                    PROJECT_SUMMARY_B.clone()
-                ],
-                meta: Pagination {
-                    prev_page: Some(
-                        SeekLink::new(
-                            &Seek {
-                                anchor: Anchor::Before("project_a".into(), 0),
-                                sort_by: SortBy::ProjectName,
-                                dir: Direction::Ascending
-                            },
-                            Limit::new(5)
-                        ).unwrap()
-                    ),
-                    next_page: Some(
-                        SeekLink::new(
-                            &Seek {
-                                anchor: Anchor::After("project_b".into(), 0),
-                                sort_by: SortBy::ProjectName,
-                                dir: Direction::Ascending
-                            },
-                            Limit::new(5)
-                        ).unwrap()
-                    ),
-                    total: 1234
-                }
-            }
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::NotFound)
         );
     }
 
     #[tokio::test]
-    async fn get_projects_limit_and_seek_ok() {
-        let query = SeekLink::new(
-            &Seek {
-                anchor: Anchor::Start,
-                sort_by: SortBy::ProjectName,
-                dir: Direction::Ascending
-            },
-            Limit::new(5)
-        ).unwrap();
-
+    async fn post_unsubscribe_unauth() {
         let response = try_request(
             Request::builder()
-                .method(Method::GET)
-                .uri(&format!("{API_V1}/projects{query}"))
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/projects/a_project/unsubscribe"))
                 .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
         assert_eq!(
-            body_as::<Projects>(response).await,
-            Projects {
-                projects: vec![
-                    PROJECT_SUMMARY_A.clone(),
-                    PROJECT_SUMMARY_B.clone()
-                ],
-                meta: Pagination {
-                    prev_page: Some(
-                        SeekLink::new(
-                            &Seek {
-                                anchor: Anchor::Before("project_a".into(), 0),
-                                sort_by: SortBy::ProjectName,
-                                dir: Direction::Ascending
-                            },
-                            Limit::new(5)
-                        ).unwrap()
-                    ),
-                    next_page: Some(
-                        SeekLink::new(
-                            &Seek {
-                                anchor: Anchor::After("project_b".into(), 0),
-                                sort_by: SortBy::ProjectName,
-                                dir: Direction::Ascending
-                            },
-                            Limit::new(5)
-                        ).unwrap()
-                    ),
-                    total: 1234
-                }
-            }
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::Unauthorized)
         );
     }
 
     #[tokio::test]
-    async fn get_project_ok() {
+    async fn get_image_ok() {
         let response = try_request(
             Request::builder()
                 .method(Method::GET)
-                .uri(&format!("{API_V1}/projects/a_project"))
+                .uri(&format!("{API_V1}/projects/a_project/images/img.png"))
                 .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
         assert_eq!(
-            body_as::<ProjectData>(response).await,
-            *EIA_PROJECT_DATA
+            response.headers().get(LOCATION).unwrap(),
+            "https://example.com/img.png"
         );
     }
 
     #[tokio::test]
-    async fn get_project_not_a_project() {
+    async fn head_image_ok() {
         let response = try_request(
             Request::builder()
-                .method(Method::GET)
-                .uri(&format!("{API_V1}/projects/not_a_project"))
+                .method(Method::HEAD)
+                .uri(&format!("{API_V1}/projects/a_project/images/img.png"))
                 .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
         assert_eq!(
-            body_as::<HttpError>(response).await,
-            HttpError::from(AppError::NotFound)
+            response.headers().get(LOCATION).unwrap(),
+            "https://example.com/img.png"
         );
+        assert!(body_bytes(response).await.is_empty());
     }
 
     #[tokio::test]
-    async fn post_project_ok() {
-        let proj_data = ProjectDataPost {
-            description: "A module for Empires in Arms".into(),
-            tags: vec![],
-            game: GameData {
-                title: "Empires in Arms".into(),
-                title_sort_key: "Empires in Arms".into(),
-                publisher: "Avalon Hill".into(),
-                year: "1983".into()
-            },
-            readme: "".into(),
-            image: None
-        };
-
+    async fn head_image_not_an_image() {
         let response = try_request(
             Request::builder()
-                .method(Method::POST)
-                .uri(&format!("{API_V1}/projects/not_a_project"))
-                .header(AUTHORIZATION, token(BOB_UID))
-                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
-                .body(Body::from(serde_json::to_vec(&proj_data).unwrap()))
+                .method(Method::HEAD)
+                .uri(&format!("{API_V1}/projects/a_project/images/not_a.png"))
+                .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::OK);
-        assert!(body_empty(response).await);
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert!(body_bytes(response).await.is_empty());
     }
 
     #[tokio::test]
-    async fn post_project_unauth() {
-        let proj_data = ProjectDataPost {
-            description: "A module for Empires in Arms".into(),
-            tags: vec![],
-            game: GameData {
-                title: "Empires in Arms".into(),
-                title_sort_key: "Empires in Arms".into(),
-                publisher: "Avalon Hill".into(),
-                year: "1983".into()
-            },
-            readme: "".into(),
-            image: None
-        };
-
+    async fn get_image_not_a_project() {
         let response = try_request(
             Request::builder()
-                .method(Method::POST)
-                .uri(&format!("{API_V1}/projects/not_a_project"))
-                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
-                .body(Body::from(serde_json::to_vec(&proj_data).unwrap()))
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/not_a_project/images/img.png"))
+                .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
         assert_eq!(
             body_as::<HttpError>(response).await,
-            HttpError::from(AppError::Unauthorized)
+            HttpError::from(AppError::NotFound)
         );
     }
 
-    #[tokio::test]
-    async fn post_project_wrong_json() {
+   #[tokio::test]
+    async fn get_image_not_an_image() {
         let response = try_request(
             Request::builder()
-                .method(Method::POST)
-                .uri(&format!("{API_V1}/projects/not_a_project"))
-                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
-                .header(AUTHORIZATION, token(BOB_UID))
-                .body(Body::from(r#"{ "garbage": "whatever" }"#))
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/a_project/images/not_a.png"))
+                .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
         assert_eq!(
             body_as::<HttpError>(response).await,
-            HttpError::from(AppError::JsonError)
+            HttpError::from(AppError::NotFound)
         );
     }
 
     #[tokio::test]
-    async fn post_project_wrong_mime_type() {
+    async fn get_image_not_a_filename() {
         let response = try_request(
             Request::builder()
-                .method(Method::POST)
-                .uri(&format!("{API_V1}/projects/not_a_project"))
-                .header(CONTENT_TYPE, TEXT_PLAIN.as_ref())
-                .header(AUTHORIZATION, token(BOB_UID))
-                .body(Body::from("stuff"))
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/projects/a_project/images/..%2Fsecret.png"))
+                .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
         assert_eq!(
             body_as::<HttpError>(response).await,
-            HttpError::from(AppError::BadMimeType)
+            HttpError::from(AppError::MalformedQuery)
         );
     }
 
     #[tokio::test]
-    async fn post_project_no_mime_type() {
+    async fn post_image_not_a_project() {
         let response = try_request(
             Request::builder()
                 .method(Method::POST)
-                .uri(&format!("{API_V1}/projects/not_a_project"))
+                .uri(&format!("{API_V1}/projects/not_a_project/images/img.png"))
                 .header(AUTHORIZATION, token(BOB_UID))
-                .body(Body::from("stuff"))
+                .header(CONTENT_LENGTH, 1234)
+                .header(CONTENT_TYPE, IMAGE_PNG.as_ref())
+                .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
         assert_eq!(
             body_as::<HttpError>(response).await,
-            HttpError::from(AppError::BadMimeType)
+            HttpError::from(AppError::NotFound)
         );
     }
 
-    #[tokio::test]
-    async fn patch_project_ok() {
-        let proj_data = ProjectDataPatch {
-            description: Some("A module for Empires in Arms".into()),
-            ..Default::default()
-        };
-
+   #[tokio::test]
+    async fn post_image_ok() {
         let response = try_request(
             Request::builder()
-                .method(Method::PATCH)
-                .uri(&format!("{API_V1}/projects/a_project"))
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/projects/a_project/images/img.png"))
                 .header(AUTHORIZATION, token(BOB_UID))
-                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
-                .body(Body::from(serde_json::to_vec(&proj_data).unwrap()))
+                .header(CONTENT_LENGTH, 1234)
+                .header(CONTENT_TYPE, IMAGE_PNG.as_ref())
+                .body(Body::empty())
                 .unwrap()
         )
         .await;
@@ -1498,179 +7274,207 @@ mod test {
     }
 
     #[tokio::test]
-    async fn patch_project_clear_image_ok() {
-        let proj_data = ProjectDataPatch {
-            image: Some(None),
-            ..Default::default()
-        };
-
+    async fn post_image_unauth() {
         let response = try_request(
             Request::builder()
-                .method(Method::PATCH)
-                .uri(&format!("{API_V1}/projects/a_project"))
-                .header(AUTHORIZATION, token(BOB_UID))
-                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
-                .body(Body::from(serde_json::to_vec(&proj_data).unwrap()))
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/projects/a_project/images/img.png"))
+                .header(CONTENT_LENGTH, 1234)
+                .header(CONTENT_TYPE, IMAGE_PNG.as_ref())
+                .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::OK);
-        assert!(body_empty(response).await);
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::Unauthorized)
+        );
     }
 
     #[tokio::test]
-    async fn patch_project_no_data() {
+    async fn post_image_not_owner() {
         let response = try_request(
             Request::builder()
-                .method(Method::PATCH)
-                .uri(&format!("{API_V1}/projects/a_project"))
-                .header(AUTHORIZATION, token(BOB_UID))
-                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
-                .body(Body::from("{}"))
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/projects/a_project/images/img.png"))
+                .header(AUTHORIZATION, token(0))
+                .header(CONTENT_LENGTH, 1234)
+                .header(CONTENT_TYPE, IMAGE_PNG.as_ref())
+                .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(),  StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
         assert_eq!(
             body_as::<HttpError>(response).await,
-            HttpError::from(AppError::JsonError)
+            HttpError::from(AppError::Unauthorized)
         );
     }
 
     #[tokio::test]
-    async fn patch_project_unauth() {
-        let proj_data = ProjectDataPatch {
-            description: Some("A module for Empires in Arms".into()),
-            ..Default::default()
-        };
-
+    async fn post_image_no_mime_type() {
         let response = try_request(
             Request::builder()
-                .method(Method::PATCH)
-                .uri(&format!("{API_V1}/projects/a_project"))
-                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
-                .body(Body::from(serde_json::to_vec(&proj_data).unwrap()))
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/projects/a_project/images/img.png"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .header(CONTENT_LENGTH, 1234)
+                .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
         assert_eq!(
             body_as::<HttpError>(response).await,
-            HttpError::from(AppError::Unauthorized)
+            HttpError::from(AppError::BadMimeType)
         );
     }
 
     #[tokio::test]
-    async fn patch_project_not_owner() {
-        let proj_data = ProjectDataPatch {
-            description: Some("A module for Empires in Arms".into()),
-            ..Default::default()
-        };
-
+    async fn post_image_bad_mime_type() {
         let response = try_request(
             Request::builder()
-                .method(Method::PATCH)
-                .uri(&format!("{API_V1}/projects/a_project"))
-                .header(AUTHORIZATION, token(0))
-                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
-                .body(Body::from(serde_json::to_vec(&proj_data).unwrap()))
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/projects/a_project/images/img.png"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .header(CONTENT_TYPE, TEXT_PLAIN.as_ref())
+                .header(CONTENT_LENGTH, 1234)
+                .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
         assert_eq!(
             body_as::<HttpError>(response).await,
-            HttpError::from(AppError::Unauthorized)
+            HttpError::from(AppError::BadMimeType)
         );
     }
 
     #[tokio::test]
-    async fn patch_project_wrong_json() {
+    async fn post_image_too_large() {
         let response = try_request(
             Request::builder()
-                .method(Method::PATCH)
-                .uri(&format!("{API_V1}/projects/a_project"))
-                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/projects/a_project/images/img.png"))
                 .header(AUTHORIZATION, token(BOB_UID))
-                .body(Body::from(r#"{ "garbage": "whatever" }"#))
+                .header(CONTENT_TYPE, IMAGE_PNG.as_ref())
+                .header(CONTENT_LENGTH, u64::MAX)
+                .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
         assert_eq!(
             body_as::<HttpError>(response).await,
-            HttpError::from(AppError::JsonError)
+            HttpError {
+                error: "Payload too large".into(),
+                details: serde_json::json!({ "max_size": 1 << 20 })
+            }
+        );
+    }
+
+    async fn delete_image_request(read_only: bool, img_name: &str, uid: i64) -> Response {
+        routes(API_V1, read_only, MAX_FILE_SIZE)
+            .with_state(test_state())
+            .oneshot(
+                Request::builder()
+                    .method(Method::DELETE)
+                    .uri(&format!("{API_V1}/projects/a_project/images/{img_name}"))
+                    .header(AUTHORIZATION, token(uid))
+                    .body(Body::empty())
+                    .unwrap()
+            )
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn delete_image_ok() {
+        let response = delete_image_request(false, "img.png", BOB_UID).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(body_empty(response).await);
+    }
+
+    #[tokio::test]
+    async fn delete_image_not_found() {
+        let response = delete_image_request(false, "bogus.png", BOB_UID).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::NotFound)
         );
     }
 
     #[tokio::test]
-    async fn patch_project_wrong_mime_type() {
-        let response = try_request(
-            Request::builder()
-                .method(Method::PATCH)
-                .uri(&format!("{API_V1}/projects/a_project"))
-                .header(CONTENT_TYPE, TEXT_PLAIN.as_ref())
-                .header(AUTHORIZATION, token(BOB_UID))
-                .body(Body::from("stuff"))
-                .unwrap()
-        )
-        .await;
+    async fn delete_image_cover() {
+        let response = delete_image_request(false, "cover.png", BOB_UID).await;
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::Conflict)
+        );
+    }
 
-        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    #[tokio::test]
+    async fn delete_image_not_owner() {
+        let response = delete_image_request(false, "img.png", 0).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
         assert_eq!(
             body_as::<HttpError>(response).await,
-            HttpError::from(AppError::BadMimeType)
+            HttpError::from(AppError::Unauthorized)
         );
     }
 
     #[tokio::test]
-    async fn patch_project_no_mime_type() {
-        let response = try_request(
-            Request::builder()
-                .method(Method::PATCH)
-                .uri(&format!("{API_V1}/projects/a_project"))
-                .header(AUTHORIZATION, token(BOB_UID))
-                .body(Body::from("stuff"))
-                .unwrap()
-        )
-        .await;
-
-        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    async fn delete_image_read_only() {
+        let response = delete_image_request(true, "img.png", BOB_UID).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
         assert_eq!(
             body_as::<HttpError>(response).await,
-            HttpError::from(AppError::BadMimeType)
+            HttpError::from(AppError::Forbidden)
         );
     }
 
     #[tokio::test]
-    async fn get_project_revision_ok() {
+    async fn post_gallery_ok() {
         let response = try_request(
             Request::builder()
-                .method(Method::GET)
-                .uri(&format!("{API_V1}/projects/a_project/1"))
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/projects/a_project/gallery/img.png"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .header(CONTENT_LENGTH, 1234)
+                .header(CONTENT_TYPE, IMAGE_PNG.as_ref())
                 .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.status(), StatusCode::CREATED);
         assert_eq!(
-            body_as::<ProjectData>(response).await,
-            *EIA_PROJECT_DATA
+            body_as::<GalleryImage>(response).await,
+            GalleryImage {
+                id: 3,
+                filename: "img.png".into(),
+                description: "".into()
+            }
         );
     }
 
     #[tokio::test]
-    async fn get_project_revision_not_a_project() {
+    async fn post_gallery_not_a_project() {
         let response = try_request(
             Request::builder()
-                .method(Method::GET)
-                .uri(&format!("{API_V1}/projects/not_a_project/1"))
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/projects/not_a_project/gallery/img.png"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .header(CONTENT_LENGTH, 1234)
+                .header(CONTENT_TYPE, IMAGE_PNG.as_ref())
                 .body(Body::empty())
                 .unwrap()
         )
@@ -1684,237 +7488,245 @@ mod test {
     }
 
     #[tokio::test]
-    async fn get_project_revision_not_a_revision() {
+    async fn post_gallery_not_owner() {
         let response = try_request(
             Request::builder()
-                .method(Method::GET)
-                .uri(&format!("{API_V1}/projects/a_project/2"))
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/projects/a_project/gallery/img.png"))
+                .header(AUTHORIZATION, token(0))
+                .header(CONTENT_LENGTH, 1234)
+                .header(CONTENT_TYPE, IMAGE_PNG.as_ref())
                 .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
         assert_eq!(
             body_as::<HttpError>(response).await,
-            HttpError::from(AppError::NotFound)
+            HttpError::from(AppError::Unauthorized)
         );
     }
 
     #[tokio::test]
-    async fn get_package_ok() {
+    async fn post_gallery_bad_mime_type() {
         let response = try_request(
             Request::builder()
-                .method(Method::GET)
-                .uri(&format!("{API_V1}/projects/a_project/packages/a_package"))
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/projects/a_project/gallery/img.png"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .header(CONTENT_TYPE, TEXT_PLAIN.as_ref())
+                .header(CONTENT_LENGTH, 1234)
                 .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
         assert_eq!(
-            response.headers().get(LOCATION).unwrap(),
-            "https://example.com/package"
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::BadMimeType)
         );
     }
 
     #[tokio::test]
-    async fn get_package_not_a_project() {
+    async fn post_gallery_too_large() {
         let response = try_request(
             Request::builder()
-                .method(Method::GET)
-                .uri(&format!("{API_V1}/projects/not_a_project/packages/a_package"))
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/projects/a_project/gallery/img.png"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .header(CONTENT_TYPE, IMAGE_PNG.as_ref())
+                .header(CONTENT_LENGTH, u64::MAX)
                 .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::NOT_FOUND);
-        assert_eq!(
-            body_as::<HttpError>(response).await,
-            HttpError::from(AppError::NotFound)
-        );
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
     }
 
     #[tokio::test]
-    async fn get_package_not_a_package() {
+    async fn get_admin_flags_ok() {
         let response = try_request(
             Request::builder()
                 .method(Method::GET)
-                .uri(&format!("{API_V1}/projects/a_project/packages/not_a_package"))
+                .uri(&format!("{API_V1}/admin/flags"))
+                .header(AUTHORIZATION, admin_token(BOB_UID))
                 .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(response.status(), StatusCode::OK);
         assert_eq!(
-            body_as::<HttpError>(response).await,
-            HttpError::from(AppError::NotFound)
+            body_as::<Flags>(response).await,
+            Flags {
+                flags: vec![FLAG_1.clone(), FLAG_2.clone()]
+            }
         );
     }
 
-// TODO: get_release tests
-
     #[tokio::test]
-    async fn get_release_version_ok() {
+    async fn get_admin_flags_unresolved_ok() {
         let response = try_request(
             Request::builder()
                 .method(Method::GET)
-                .uri(&format!("{API_V1}/projects/a_project/packages/a_package/1.2.3"))
+                .uri(&format!("{API_V1}/admin/flags?resolved=false"))
+                .header(AUTHORIZATION, admin_token(BOB_UID))
                 .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+        assert_eq!(response.status(), StatusCode::OK);
         assert_eq!(
-            response.headers().get(LOCATION).unwrap(),
-            "https://example.com/package-1.2.3"
+            body_as::<Flags>(response).await,
+            Flags {
+                flags: vec![FLAG_1.clone()]
+            }
         );
     }
 
     #[tokio::test]
-    async fn get_release_version_not_a_project() {
+    async fn get_admin_flags_resolved_unsupported() {
         let response = try_request(
             Request::builder()
                 .method(Method::GET)
-                .uri(&format!("{API_V1}/projects/not_a_project/packages/a_package/1.2.3"))
+                .uri(&format!("{API_V1}/admin/flags?resolved=true"))
+                .header(AUTHORIZATION, admin_token(BOB_UID))
                 .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
         assert_eq!(
             body_as::<HttpError>(response).await,
-            HttpError::from(AppError::NotFound)
+            HttpError::from(AppError::MalformedQuery)
         );
     }
 
     #[tokio::test]
-    async fn get_release_version_not_a_package() {
-        let response = try_request(
-            Request::builder()
-                .method(Method::GET)
-                .uri(&format!("{API_V1}/projects/a_project/packages/not_a_package/1.2.3"))
-                .body(Body::empty())
-                .unwrap()
-        )
-        .await;
+    async fn get_admin_flags_read_only() {
+        let response = routes(API_V1, true, MAX_FILE_SIZE)
+            .with_state(test_state())
+            .oneshot(
+                Request::builder()
+                    .method(Method::GET)
+                    .uri(&format!("{API_V1}/admin/flags"))
+                    .header(AUTHORIZATION, admin_token(BOB_UID))
+                    .body(Body::empty())
+                    .unwrap()
+            )
+            .await
+            .unwrap();
 
         assert_eq!(response.status(), StatusCode::NOT_FOUND);
-        assert_eq!(
-            body_as::<HttpError>(response).await,
-            HttpError::from(AppError::NotFound)
-        );
     }
 
     #[tokio::test]
-    async fn get_release_version_not_a_version() {
+    async fn get_admin_flags_not_admin() {
         let response = try_request(
             Request::builder()
                 .method(Method::GET)
-                .uri(&format!("{API_V1}/projects/a_project/packages/a_package/bogus"))
+                .uri(&format!("{API_V1}/admin/flags"))
+                .header(AUTHORIZATION, token(BOB_UID))
                 .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
         assert_eq!(
             body_as::<HttpError>(response).await,
-            HttpError::from(AppError::NotFound)
+            HttpError::from(AppError::Forbidden)
         );
     }
 
     #[tokio::test]
-    async fn get_owners_ok() {
+    async fn get_admin_flags_unauth() {
         let response = try_request(
             Request::builder()
                 .method(Method::GET)
-                .uri(&format!("{API_V1}/projects/a_project/owners"))
+                .uri(&format!("{API_V1}/admin/flags"))
                 .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
         assert_eq!(
-            body_as::<Users>(response).await,
-            Users {
-                users: vec![
-                    "alice".into(),
-                    "bob".into()
-                ]
-            }
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::Unauthorized)
         );
     }
 
     #[tokio::test]
-    async fn get_owners_bad_project() {
+    async fn get_inbox_ok() {
         let response = try_request(
             Request::builder()
                 .method(Method::GET)
-                .uri(&format!("{API_V1}/projects/not_a_project/owners"))
+                .uri(&format!("{API_V1}/me/notifications"))
+                .header(AUTHORIZATION, token(BOB_UID))
                 .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::NOT_FOUND);
-        assert_eq!(
-            body_as::<HttpError>(response).await,
-            HttpError::from(AppError::NotFound)
-        );
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(body_as::<Inbox>(response).await, *INBOX);
     }
 
     #[tokio::test]
-    async fn put_owners_ok() {
+    async fn get_inbox_before_ok() {
         let response = try_request(
             Request::builder()
-                .method(Method::PUT)
-                .uri(&format!("{API_V1}/projects/a_project/owners"))
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/me/notifications?before=2&limit=5"))
                 .header(AUTHORIZATION, token(BOB_UID))
-                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
-                .body(Body::from(r#"{ "users": ["alice", "bob"] }"#))
+                .body(Body::empty())
                 .unwrap()
         )
         .await;
 
         assert_eq!(response.status(), StatusCode::OK);
-        assert!(body_empty(response).await);
+        assert_eq!(
+            body_as::<Inbox>(response).await,
+            Inbox {
+                notifications: vec![],
+                unread: 1,
+                next_before: None
+            }
+        );
     }
 
     #[tokio::test]
-    async fn put_owners_bad_project() {
+    async fn get_inbox_bad_before() {
         let response = try_request(
             Request::builder()
-                .method(Method::PUT)
-                .uri(&format!("{API_V1}/projects/not_a_project/owners"))
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/me/notifications?before=x"))
                 .header(AUTHORIZATION, token(BOB_UID))
-                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
-                .body(Body::from(r#"{ "users": ["alice", "bob"] }"#))
+                .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
         assert_eq!(
             body_as::<HttpError>(response).await,
-            HttpError::from(AppError::NotFound)
+            HttpError::from(AppError::MalformedQuery)
         );
     }
 
     #[tokio::test]
-    async fn put_owners_unauth() {
+    async fn get_inbox_unauth() {
         let response = try_request(
             Request::builder()
-                .method(Method::PUT)
-                .uri(&format!("{API_V1}/projects/a_project/owners"))
-                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
-                .body(Body::from(r#"{ "users": ["alice", "bob"] }"#))
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/me/notifications"))
+                .body(Body::empty())
                 .unwrap()
         )
         .await;
@@ -1927,225 +7739,234 @@ mod test {
     }
 
     #[tokio::test]
-    async fn put_owners_not_owner() {
+    async fn post_inbox_read_ok() {
         let response = try_request(
             Request::builder()
-                .method(Method::PUT)
-                .uri(&format!("{API_V1}/projects/a_project/owners"))
-                .header(AUTHORIZATION, token(0))
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/me/notifications/read"))
+                .header(AUTHORIZATION, token(BOB_UID))
                 .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
-                .body(Body::from(r#"{ "users": ["alice", "bob"] }"#))
+                .body(Body::from(r#"{ "ids": [1, 2] }"#))
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
-        assert_eq!(
-            body_as::<HttpError>(response).await,
-            HttpError::from(AppError::Unauthorized)
-        );
+        assert_eq!(response.status(), StatusCode::OK);
     }
 
     #[tokio::test]
-    async fn put_owners_wrong_json() {
+    async fn post_inbox_read_all_ok() {
         let response = try_request(
             Request::builder()
-                .method(Method::PUT)
-                .uri(&format!("{API_V1}/projects/a_project/owners"))
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/me/notifications/read"))
                 .header(AUTHORIZATION, token(BOB_UID))
                 .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
-                .body(Body::from(r#"{ "garbage": "whatever" }"#))
+                .body(Body::from("{}"))
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
-        assert_eq!(
-            body_as::<HttpError>(response).await,
-            HttpError::from(AppError::JsonError)
-        );
+        assert_eq!(response.status(), StatusCode::OK);
     }
 
     #[tokio::test]
-    async fn put_owners_wrong_mime_type() {
+    async fn post_inbox_read_bad_ids() {
         let response = try_request(
             Request::builder()
-                .method(Method::PUT)
-                .uri(&format!("{API_V1}/projects/a_project/owners"))
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/me/notifications/read"))
                 .header(AUTHORIZATION, token(BOB_UID))
-                .header(CONTENT_TYPE, TEXT_PLAIN.as_ref())
-                .body(Body::from("stuff"))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .body(Body::from(r#"{ "ids": "all" }"#))
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
-        assert_eq!(
-            body_as::<HttpError>(response).await,
-            HttpError::from(AppError::BadMimeType)
-        );
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
     }
 
     #[tokio::test]
-    async fn put_owners_no_mime_type() {
+    async fn post_inbox_read_unauth() {
         let response = try_request(
             Request::builder()
-                .method(Method::PUT)
-                .uri(&format!("{API_V1}/projects/a_project/owners"))
-                .header(AUTHORIZATION, token(BOB_UID))
-                .body(Body::from(r#"{ "users": ["alice", "bob"] }"#))
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/me/notifications/read"))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .body(Body::from("{}"))
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
         assert_eq!(
             body_as::<HttpError>(response).await,
-            HttpError::from(AppError::BadMimeType)
+            HttpError::from(AppError::Unauthorized)
         );
     }
 
     #[tokio::test]
-    async fn delete_owners_ok() {
+    async fn post_admin_flag_resolve_ok() {
         let response = try_request(
             Request::builder()
-                .method(Method::DELETE)
-                .uri(&format!("{API_V1}/projects/a_project/owners"))
-                .header(AUTHORIZATION, token(BOB_UID))
-                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
-                .body(Body::from(r#"{ "users": ["alice", "bob"] }"#))
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/admin/flags/1/resolve"))
+                .header(AUTHORIZATION, admin_token(BOB_UID))
+                .body(Body::empty())
                 .unwrap()
         )
         .await;
 
         assert_eq!(response.status(), StatusCode::OK);
-        assert!(body_empty(response).await);
     }
 
     #[tokio::test]
-    async fn delete_owners_bad_project() {
+    async fn post_admin_flag_resolve_already_resolved() {
         let response = try_request(
             Request::builder()
-                .method(Method::DELETE)
-                .uri(&format!("{API_V1}/projects/not_a_project/owners"))
-                .header(AUTHORIZATION, token(BOB_UID))
-                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
-                .body(Body::from(r#"{ "users": ["alice", "bob"] }"#))
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/admin/flags/2/resolve"))
+                .header(AUTHORIZATION, admin_token(BOB_UID))
+                .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(response.status(), StatusCode::CONFLICT);
         assert_eq!(
             body_as::<HttpError>(response).await,
-            HttpError::from(AppError::NotFound)
+            HttpError::from(AppError::Conflict)
         );
     }
 
     #[tokio::test]
-    async fn delete_owners_unauth() {
+    async fn post_admin_flag_resolve_not_a_flag() {
         let response = try_request(
             Request::builder()
-                .method(Method::DELETE)
-                .uri(&format!("{API_V1}/projects/a_project/owners"))
-                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
-                .body(Body::from(r#"{ "users": ["alice", "bob"] }"#))
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/admin/flags/3/resolve"))
+                .header(AUTHORIZATION, admin_token(BOB_UID))
+                .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
         assert_eq!(
             body_as::<HttpError>(response).await,
-            HttpError::from(AppError::Unauthorized)
+            HttpError::from(AppError::NotFound)
         );
     }
 
     #[tokio::test]
-    async fn delete_owners_not_owner() {
+    async fn post_admin_flag_resolve_not_admin() {
         let response = try_request(
             Request::builder()
-                .method(Method::DELETE)
-                .uri(&format!("{API_V1}/projects/a_project/owners"))
-                .header(AUTHORIZATION, token(0))
-                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
-                .body(Body::from(r#"{ "users": ["alice", "bob"] }"#))
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/admin/flags/1/resolve"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
         assert_eq!(
             body_as::<HttpError>(response).await,
-            HttpError::from(AppError::Unauthorized)
+            HttpError::from(AppError::Forbidden)
         );
     }
 
     #[tokio::test]
-    async fn delete_owners_wrong_json() {
+    async fn post_admin_flag_resolve_unauth() {
         let response = try_request(
             Request::builder()
-                .method(Method::DELETE)
-                .uri(&format!("{API_V1}/projects/a_project/owners"))
-                .header(AUTHORIZATION, token(BOB_UID))
-                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
-                .body(Body::from(r#"{ "garbage": "whatever" }"#))
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/admin/flags/1/resolve"))
+                .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
         assert_eq!(
             body_as::<HttpError>(response).await,
-            HttpError::from(AppError::JsonError)
+            HttpError::from(AppError::Unauthorized)
         );
     }
 
     #[tokio::test]
-    async fn delete_owners_wrong_mime_type() {
+    async fn post_admin_flag_resolve_read_only() {
+        let response = routes(API_V1, true, MAX_FILE_SIZE)
+            .with_state(test_state())
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri(&format!("{API_V1}/admin/flags/1/resolve"))
+                    .header(AUTHORIZATION, admin_token(BOB_UID))
+                    .body(Body::empty())
+                    .unwrap()
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn post_admin_slugs_recompute_ok() {
         let response = try_request(
             Request::builder()
-                .method(Method::DELETE)
-                .uri(&format!("{API_V1}/projects/a_project/owners"))
-                .header(AUTHORIZATION, token(BOB_UID))
-                .header(CONTENT_TYPE, TEXT_PLAIN.as_ref())
-                .body(Body::from("stuff"))
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/admin/slugs/recompute?aliases=true"))
+                .header(AUTHORIZATION, admin_token(BOB_UID))
+                .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert_eq!(response.status(), StatusCode::OK);
         assert_eq!(
-            body_as::<HttpError>(response).await,
-            HttpError::from(AppError::BadMimeType)
+            body_as::<SlugRecompute>(response).await,
+            SlugRecompute {
+                changed: vec![
+                    SlugChange {
+                        project: "A-Project".into(),
+                        from: "a-project".into(),
+                        to: "a_project".into()
+                    }
+                ],
+                collisions: vec![]
+            }
         );
     }
 
     #[tokio::test]
-    async fn delete_owners_no_mime_type() {
+    async fn post_admin_slugs_recompute_not_admin() {
         let response = try_request(
             Request::builder()
-                .method(Method::DELETE)
-                .uri(&format!("{API_V1}/projects/a_project/owners"))
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/admin/slugs/recompute"))
                 .header(AUTHORIZATION, token(BOB_UID))
-                .body(Body::from(r#"{ "users": ["alice", "bob"] }"#))
+                .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
         assert_eq!(
             body_as::<HttpError>(response).await,
-            HttpError::from(AppError::BadMimeType)
+            HttpError::from(AppError::Forbidden)
         );
     }
 
     #[tokio::test]
-    async fn get_players_ok() {
+    async fn get_admin_timestamps_ok() {
         let response = try_request(
             Request::builder()
                 .method(Method::GET)
-                .uri(&format!("{API_V1}/projects/a_project/players"))
+                .uri(&format!("{API_V1}/admin/timestamps"))
+                .header(AUTHORIZATION, admin_token(BOB_UID))
                 .body(Body::empty())
                 .unwrap()
         )
@@ -2153,111 +7974,136 @@ mod test {
 
         assert_eq!(response.status(), StatusCode::OK);
         assert_eq!(
-            body_as::<Users>(response).await,
-            Users {
-                users: vec![
-                    "player 1".into(),
-                    "player 2".into()
+            body_as::<BadTimestamps>(response).await,
+            BadTimestamps {
+                timestamps: vec![
+                    BadTimestamp {
+                        table: "projects".into(),
+                        id: 1,
+                        column: "modified_at".into(),
+                        value: -1
+                    }
                 ]
             }
         );
     }
 
     #[tokio::test]
-    async fn get_players_not_a_project() {
+    async fn get_admin_timestamps_not_admin() {
         let response = try_request(
             Request::builder()
                 .method(Method::GET)
-                .uri(&format!("{API_V1}/projects/not_a_project/players"))
+                .uri(&format!("{API_V1}/admin/timestamps"))
+                .header(AUTHORIZATION, token(BOB_UID))
                 .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
         assert_eq!(
             body_as::<HttpError>(response).await,
-            HttpError::from(AppError::NotFound)
+            HttpError::from(AppError::Forbidden)
         );
     }
 
     #[tokio::test]
-    async fn put_players_ok() {
+    async fn get_admin_years_malformed_ok() {
         let response = try_request(
             Request::builder()
-                .method(Method::PUT)
-                .uri(&format!("{API_V1}/projects/a_project/players"))
-                .header(AUTHORIZATION, token(BOB_UID))
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/admin/years/malformed"))
+                .header(AUTHORIZATION, admin_token(BOB_UID))
                 .body(Body::empty())
                 .unwrap()
         )
         .await;
 
         assert_eq!(response.status(), StatusCode::OK);
-        assert!(body_empty(response).await);
+        assert_eq!(
+            body_as::<MalformedYears>(response).await,
+            MalformedYears {
+                projects: vec![
+                    MalformedYear {
+                        project: "a_project".into(),
+                        year: "c. 1979".into()
+                    }
+                ]
+            }
+        );
     }
 
     #[tokio::test]
-    async fn put_players_not_a_project() {
+    async fn get_admin_years_malformed_not_admin() {
         let response = try_request(
             Request::builder()
-                .method(Method::PUT)
-                .uri(&format!("{API_V1}/projects/not_a_project/owners"))
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/admin/years/malformed"))
                 .header(AUTHORIZATION, token(BOB_UID))
                 .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
         assert_eq!(
             body_as::<HttpError>(response).await,
-            HttpError::from(AppError::NotFound)
+            HttpError::from(AppError::Forbidden)
         );
     }
 
     #[tokio::test]
-    async fn put_players_unauth() {
+    async fn post_admin_year_correct_ok() {
+        let correction = YearCorrection { year: "1979".into() };
+
         let response = try_request(
             Request::builder()
-                .method(Method::PUT)
-                .uri(&format!("{API_V1}/projects/a_project/players"))
-                .body(Body::empty())
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/admin/years/malformed/a_project"))
+                .header(AUTHORIZATION, admin_token(BOB_UID))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .body(Body::from(serde_json::to_vec(&correction).unwrap()))
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
-        assert_eq!(
-            body_as::<HttpError>(response).await,
-            HttpError::from(AppError::Unauthorized)
-        );
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(body_bytes(response).await.is_empty());
     }
 
     #[tokio::test]
-    async fn delete_players_ok() {
+    async fn post_admin_year_correct_invalid_year() {
+        let correction = YearCorrection { year: "c. 1979".into() };
+
         let response = try_request(
             Request::builder()
-                .method(Method::DELETE)
-                .uri(&format!("{API_V1}/projects/a_project/players"))
-                .header(AUTHORIZATION, token(8))
-                .body(Body::empty())
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/admin/years/malformed/a_project"))
+                .header(AUTHORIZATION, admin_token(BOB_UID))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .body(Body::from(serde_json::to_vec(&correction).unwrap()))
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::OK);
-        assert!(body_empty(response).await);
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::JsonError)
+        );
     }
 
     #[tokio::test]
-    async fn delete_players_not_a_project() {
+    async fn post_admin_year_correct_not_a_project() {
+        let correction = YearCorrection { year: "1979".into() };
+
         let response = try_request(
             Request::builder()
-                .method(Method::DELETE)
-                .uri(&format!("{API_V1}/projects/not_a_project/players"))
-                .header(AUTHORIZATION, token(8))
-                .body(Body::empty())
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/admin/years/malformed/not_a_project"))
+                .header(AUTHORIZATION, admin_token(BOB_UID))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .body(Body::from(serde_json::to_vec(&correction).unwrap()))
                 .unwrap()
         )
         .await;
@@ -2270,86 +8116,178 @@ mod test {
     }
 
     #[tokio::test]
-    async fn delete_players_unauth() {
+    async fn post_admin_year_correct_not_admin() {
+        let correction = YearCorrection { year: "1979".into() };
+
         let response = try_request(
             Request::builder()
-                .method(Method::DELETE)
-                .uri(&format!("{API_V1}/projects/a_project/players"))
-                .body(Body::empty())
+                .method(Method::POST)
+                .uri(&format!("{API_V1}/admin/years/malformed/a_project"))
+                .header(AUTHORIZATION, token(BOB_UID))
+                .header(CONTENT_TYPE, APPLICATION_JSON.as_ref())
+                .body(Body::from(serde_json::to_vec(&correction).unwrap()))
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
         assert_eq!(
             body_as::<HttpError>(response).await,
-            HttpError::from(AppError::Unauthorized)
+            HttpError::from(AppError::Forbidden)
         );
     }
 
     #[tokio::test]
-    async fn get_image_ok() {
+    async fn post_admin_slugs_recompute_read_only() {
+        let response = routes(API_V1, true, MAX_FILE_SIZE)
+            .with_state(test_state())
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri(&format!("{API_V1}/admin/slugs/recompute"))
+                    .header(AUTHORIZATION, admin_token(BOB_UID))
+                    .body(Body::empty())
+                    .unwrap()
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn page_limits_ok() {
+        assert_eq!(
+            page_limits(10, 100).unwrap(),
+            (Limit::new(10).unwrap(), Limit::new(100).unwrap())
+        );
+    }
+
+    #[test]
+    fn page_limits_equal_ok() {
+        assert_eq!(
+            page_limits(50, 50).unwrap(),
+            (Limit::new(50).unwrap(), Limit::new(50).unwrap())
+        );
+    }
+
+    #[test]
+    fn page_limits_default_too_large() {
+        assert!(
+            matches!(
+                page_limits(101, 100).unwrap_err(),
+                StartupError::LimitError(101, 100)
+            )
+        );
+    }
+
+    #[test]
+    fn page_limits_zero() {
+        assert!(
+            matches!(
+                page_limits(0, 100).unwrap_err(),
+                StartupError::LimitError(0, 100)
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn get_forum_user_summary_ok() {
         let response = try_request(
             Request::builder()
                 .method(Method::GET)
-                .uri(&format!("{API_V1}/projects/a_project/images/img.png"))
+                .uri("/integrations/forum/users/1/summary")
+                .header(AUTHORIZATION, format!("Bearer {FORUM_TOKEN}"))
                 .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+        assert_eq!(response.status(), StatusCode::OK);
         assert_eq!(
-            response.headers().get(LOCATION).unwrap(),
-            "https://example.com/img.png"
+            body_as::<ForumUserSummary>(response).await,
+            *FORUM_USER_SUMMARY
         );
     }
 
     #[tokio::test]
-    async fn get_image_not_a_project() {
+    async fn get_forum_user_summary_old_token_ok() {
         let response = try_request(
             Request::builder()
                 .method(Method::GET)
-                .uri(&format!("{API_V1}/projects/not_a_project/images/img.png"))
+                .uri("/integrations/forum/users/1/summary")
+                .header(AUTHORIZATION, format!("Bearer {FORUM_TOKEN_OLD}"))
                 .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::NOT_FOUND);
-        assert_eq!(
-            body_as::<HttpError>(response).await,
-            HttpError::from(AppError::NotFound)
-        );
+        assert_eq!(response.status(), StatusCode::OK);
     }
 
-   #[tokio::test]
-    async fn get_image_not_an_image() {
+    #[tokio::test]
+    async fn get_forum_user_summary_read_only_ok() {
+        let response = routes(API_V1, true, MAX_FILE_SIZE)
+            .with_state(test_state())
+            .oneshot(
+                Request::builder()
+                    .method(Method::GET)
+                    .uri("/integrations/forum/users/1/summary")
+                    .header(AUTHORIZATION, format!("Bearer {FORUM_TOKEN}"))
+                    .body(Body::empty())
+                    .unwrap()
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn get_user_profile_ok() {
         let response = try_request(
             Request::builder()
                 .method(Method::GET)
-                .uri(&format!("{API_V1}/projects/a_project/images/not_a.png"))
+                .uri(&format!("{API_V1}/users/bob"))
                 .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(response.status(), StatusCode::OK);
         assert_eq!(
-            body_as::<HttpError>(response).await,
-            HttpError::from(AppError::NotFound)
+            body_as::<UserProfile>(response).await,
+            UserProfile {
+                username: "bob".into(),
+                owned_projects: 2,
+                played_projects: 1
+            }
         );
     }
 
     #[tokio::test]
-    async fn post_image_not_a_project() {
+    async fn get_user_profile_read_only_ok() {
+        let response = routes(API_V1, true, MAX_FILE_SIZE)
+            .with_state(test_state())
+            .oneshot(
+                Request::builder()
+                    .method(Method::GET)
+                    .uri(&format!("{API_V1}/users/bob"))
+                    .body(Body::empty())
+                    .unwrap()
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn get_user_profile_not_a_user() {
         let response = try_request(
             Request::builder()
-                .method(Method::POST)
-                .uri(&format!("{API_V1}/projects/not_a_project/images/img.png"))
-                .header(AUTHORIZATION, token(BOB_UID))
-                .header(CONTENT_LENGTH, 1234)
-                .header(CONTENT_TYPE, IMAGE_PNG.as_ref())
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/users/nobody"))
                 .body(Body::empty())
                 .unwrap()
         )
@@ -2358,128 +8296,120 @@ mod test {
         assert_eq!(response.status(), StatusCode::NOT_FOUND);
         assert_eq!(
             body_as::<HttpError>(response).await,
-            HttpError::from(AppError::NotFound)
+            HttpError::from(AppError::NotAUser)
         );
     }
 
-   #[tokio::test]
-    async fn post_image_ok() {
+    #[tokio::test]
+    async fn get_user_profile_overlong_name() {
+        let username = "x".repeat(65);
         let response = try_request(
             Request::builder()
-                .method(Method::POST)
-                .uri(&format!("{API_V1}/projects/a_project/images/img.png"))
-                .header(AUTHORIZATION, token(BOB_UID))
-                .header(CONTENT_LENGTH, 1234)
-                .header(CONTENT_TYPE, IMAGE_PNG.as_ref())
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/users/{username}"))
                 .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::OK);
-        assert!(body_empty(response).await);
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body_as::<HttpError>(response).await,
+            HttpError::from(AppError::MalformedQuery)
+        );
     }
 
     #[tokio::test]
-    async fn post_image_unauth() {
+    async fn get_user_profile_bad_encoding() {
         let response = try_request(
             Request::builder()
-                .method(Method::POST)
-                .uri(&format!("{API_V1}/projects/a_project/images/img.png"))
-                .header(CONTENT_LENGTH, 1234)
-                .header(CONTENT_TYPE, IMAGE_PNG.as_ref())
+                .method(Method::GET)
+                .uri(&format!("{API_V1}/users/%FF"))
                 .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
         assert_eq!(
             body_as::<HttpError>(response).await,
-            HttpError::from(AppError::Unauthorized)
+            HttpError::from(AppError::MalformedQuery)
         );
     }
 
     #[tokio::test]
-    async fn post_image_not_owner() {
+    async fn get_forum_user_summary_not_a_user() {
         let response = try_request(
             Request::builder()
-                .method(Method::POST)
-                .uri(&format!("{API_V1}/projects/a_project/images/img.png"))
-                .header(AUTHORIZATION, token(0))
-                .header(CONTENT_LENGTH, 1234)
-                .header(CONTENT_TYPE, IMAGE_PNG.as_ref())
+                .method(Method::GET)
+                .uri("/integrations/forum/users/3/summary")
+                .header(AUTHORIZATION, format!("Bearer {FORUM_TOKEN}"))
                 .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
         assert_eq!(
             body_as::<HttpError>(response).await,
-            HttpError::from(AppError::Unauthorized)
+            HttpError::from(AppError::NotAUser)
         );
     }
 
     #[tokio::test]
-    async fn post_image_no_mime_type() {
+    async fn get_forum_user_summary_no_token() {
         let response = try_request(
             Request::builder()
-                .method(Method::POST)
-                .uri(&format!("{API_V1}/projects/a_project/images/img.png"))
-                .header(AUTHORIZATION, token(BOB_UID))
-                .header(CONTENT_LENGTH, 1234)
+                .method(Method::GET)
+                .uri("/integrations/forum/users/1/summary")
                 .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
         assert_eq!(
             body_as::<HttpError>(response).await,
-            HttpError::from(AppError::BadMimeType)
+            HttpError::from(AppError::Unauthorized)
         );
     }
 
     #[tokio::test]
-    async fn post_image_bad_mime_type() {
+    async fn get_forum_user_summary_wrong_token() {
         let response = try_request(
             Request::builder()
-                .method(Method::POST)
-                .uri(&format!("{API_V1}/projects/a_project/images/img.png"))
-                .header(AUTHORIZATION, token(BOB_UID))
-                .header(CONTENT_TYPE, TEXT_PLAIN.as_ref())
-                .header(CONTENT_LENGTH, 1234)
+                .method(Method::GET)
+                .uri("/integrations/forum/users/1/summary")
+                .header(AUTHORIZATION, "Bearer not-the-forum-token")
                 .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
         assert_eq!(
             body_as::<HttpError>(response).await,
-            HttpError::from(AppError::BadMimeType)
+            HttpError::from(AppError::Unauthorized)
         );
     }
 
     #[tokio::test]
-    async fn post_image_too_large() {
+    async fn get_forum_user_summary_user_token() {
+        // user JWTs are no good here
         let response = try_request(
             Request::builder()
-                .method(Method::POST)
-                .uri(&format!("{API_V1}/projects/a_project/images/img.png"))
+                .method(Method::GET)
+                .uri("/integrations/forum/users/1/summary")
                 .header(AUTHORIZATION, token(BOB_UID))
-                .header(CONTENT_TYPE, IMAGE_PNG.as_ref())
-                .header(CONTENT_LENGTH, u64::MAX)
                 .body(Body::empty())
                 .unwrap()
         )
         .await;
 
-        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
         assert_eq!(
             body_as::<HttpError>(response).await,
-            HttpError::from(AppError::TooLarge)
+            HttpError::from(AppError::Unauthorized)
         );
     }
 
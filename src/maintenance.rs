@@ -0,0 +1,138 @@
+use axum::{
+    extract::{Request, State},
+    http::{StatusCode, header::RETRY_AFTER},
+    middleware::Next,
+    response::{IntoResponse, Json, Response}
+};
+use serde::{Deserialize, Serialize};
+use std::sync::{
+    Arc, RwLock,
+    atomic::{AtomicBool, Ordering}
+};
+
+pub const DEFAULT_RETRY_AFTER: u32 = 300;
+
+// Health checks must answer even during maintenance, so that the service
+// is not restarted for being unresponsive; readiness reports maintenance.
+const EXEMPT_PATHS: [&str; 2] = ["/healthz", "/readyz"];
+
+#[derive(Debug, Default)]
+struct MaintenanceState {
+    // set by the operator, via the config
+    operator: AtomicBool,
+    // set while startup migrations run
+    migrating: AtomicBool,
+    message: RwLock<Option<String>>,
+    retry_after: u32
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Maintenance(Arc<MaintenanceState>);
+
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct MaintenanceError {
+    pub error: String,
+    pub message: Option<String>
+}
+
+impl Maintenance {
+    pub fn new(
+        enabled: bool,
+        message: Option<String>,
+        retry_after: u32
+    ) -> Self
+    {
+        Maintenance(
+            Arc::new(
+                MaintenanceState {
+                    operator: AtomicBool::new(enabled),
+                    message: RwLock::new(message),
+                    retry_after,
+                    ..Default::default()
+                }
+            )
+        )
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.0.operator.load(Ordering::Relaxed) ||
+        self.0.migrating.load(Ordering::Relaxed)
+    }
+
+    pub fn set(&self, enabled: bool, message: Option<String>) {
+        if let Ok(mut m) = self.0.message.write() {
+            *m = message;
+        }
+        self.0.operator.store(enabled, Ordering::Relaxed);
+    }
+
+    pub fn set_migrating(&self, migrating: bool) {
+        self.0.migrating.store(migrating, Ordering::Relaxed);
+    }
+
+    fn message(&self) -> Option<String> {
+        self.0.message.read().ok().and_then(|m| m.clone())
+    }
+
+    pub fn response(&self) -> Response {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            [(RETRY_AFTER, self.0.retry_after.to_string())],
+            Json(
+                MaintenanceError {
+                    error: "maintenance".into(),
+                    message: self.message()
+                }
+            )
+        ).into_response()
+    }
+}
+
+pub async fn guard(
+    State(maintenance): State<Maintenance>,
+    request: Request,
+    next: Next
+) -> Response
+{
+    if maintenance.enabled() && !EXEMPT_PATHS.contains(&request.uri().path()) {
+        maintenance.response()
+    }
+    else {
+        next.run(request).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn maintenance_default_disabled() {
+        assert!(!Maintenance::default().enabled());
+    }
+
+    #[test]
+    fn maintenance_operator() {
+        let m = Maintenance::default();
+        m.set(true, Some("back soon".into()));
+        assert!(m.enabled());
+        assert_eq!(m.message(), Some("back soon".into()));
+        m.set(false, None);
+        assert!(!m.enabled());
+        assert_eq!(m.message(), None);
+    }
+
+    #[test]
+    fn maintenance_migrating() {
+        let m = Maintenance::default();
+        m.set_migrating(true);
+        assert!(m.enabled());
+
+        // the operator turning maintenance off does not end a migration
+        m.set(false, None);
+        assert!(m.enabled());
+
+        m.set_migrating(false);
+        assert!(!m.enabled());
+    }
+}
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
 
-use crate::pagination::Pagination;
+use crate::pagination::{Pagination, tag_valid};
 
 #[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct User(pub i64);
@@ -10,6 +11,14 @@ pub struct Users {
     pub users: Vec<String>
 }
 
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct UserProfile {
+    pub username: String,
+    // deleted projects are not counted
+    pub owned_projects: i64,
+    pub played_projects: i64
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub struct Package(pub i64);
 
@@ -22,6 +31,12 @@ pub struct Owner(pub i64);
 #[derive(Debug, Eq, PartialEq)]
 pub struct Owned(pub Owner, pub Project);
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct AdminUser(pub i64);
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct OwnedOrAdmin(pub User, pub Project);
+
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct GameData {
     pub title: String,
@@ -40,7 +55,9 @@ pub struct FileData {
     pub published_at: String,
     pub published_by: String,
     pub requires: String,
-    pub authors: Vec<String>
+    // usernames, not free text; a file lists the authors of its release
+    pub authors: Vec<String>,
+    pub primary_file: Option<String>
 }
 
 // TODO: probably needs slug
@@ -48,14 +65,96 @@ pub struct FileData {
 pub struct PackageData {
     pub name: String,
     pub description: String,
+    pub section: Option<String>,
     pub releases: Vec<FileData>,
-    pub files: Vec<FileData>
+    pub files: Vec<FileData>,
+    // the version owners recommend, which need not be the latest
+    #[serde(default)]
+    pub recommended_release: Option<String>
+}
+
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct FileResult {
+    pub filename: String,
+    pub status: u16,
+    pub file: Option<FileData>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
+    pub details: serde_json::Value
+}
+
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct FileResults {
+    pub files: Vec<FileResult>
+}
+
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct PrimaryFile {
+    pub filename: String
+}
+
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct RecommendedRelease {
+    pub version: String
 }
 
 #[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct PackageDataPost {
 // TODO: display name?
 //    pub name: String,
+    pub description: String,
+    #[serde(default)]
+    pub section: Option<String>
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct MaybePackageDataPatch {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub section: Option<Option<String>>
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(try_from = "MaybePackageDataPatch")]
+pub struct PackageDataPatch {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub section: Option<Option<String>>
+}
+
+#[derive(Debug, thiserror::Error, Eq, PartialEq)]
+#[error("invalid data {0:?}")]
+pub struct PackageDataPatchError(MaybePackageDataPatch);
+
+impl TryFrom<MaybePackageDataPatch> for PackageDataPatch {
+    type Error = PackageDataPatchError;
+
+    fn try_from(m: MaybePackageDataPatch) -> Result<Self, Self::Error> {
+        // at least one element must be present to be a valid request
+        match m {
+            MaybePackageDataPatch {
+                name: None,
+                description: None,
+                section: None
+            } => Err(PackageDataPatchError(m)),
+            MaybePackageDataPatch { name, description, section } =>
+                Ok(PackageDataPatch { name, description, section })
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct PackageSection {
+    pub name: Option<String>,
+    pub package_slugs: Vec<String>
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct GalleryImage {
+    pub id: i64,
+    pub filename: String,
     pub description: String
 }
 
@@ -66,12 +165,24 @@ pub struct ProjectData {
     pub revision: i64,
     pub created_at: String,
     pub modified_at: String,
+    // always sorted lexicographically
     pub tags: Vec<String>,
     pub game: GameData,
     pub readme: String,
     pub image: Option<String>,
+    pub archived: bool,
+    pub superseded_by: Option<ProjectSummary>,
+    // always sorted lexicographically
+    pub supersedes: Vec<String>,
     pub owners: Vec<String>,
-    pub packages: Vec<PackageData>
+    pub packages: Vec<PackageData>,
+    pub package_sections: Vec<PackageSection>,
+    // absent for revisions whose author is unknown
+    #[serde(default)]
+    pub modified_by: Option<String>,
+    // in display order; galleries are not revisioned
+    #[serde(default)]
+    pub gallery: Vec<GalleryImage>
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
@@ -91,20 +202,46 @@ where
     Deserialize::deserialize(de).map(Some)
 }
 
+// descriptions are shown in project listings, so must be short
+pub const PROJECT_DESCRIPTION_MAX_LENGTH: usize = 256;
+
+// tags are shown alongside projects and searched as facets
+pub const TAG_MAX_LENGTH: usize = 64;
+pub const TAG_MAX_COUNT: usize = 20;
+
+pub fn is_valid_tag(s: &str) -> bool {
+    s.chars().count() <= TAG_MAX_LENGTH &&
+    tag_valid(s) &&
+    !s.chars().any(char::is_control) &&
+    s.trim() == s &&
+    !s.chars()
+        .zip(s.chars().skip(1))
+        .any(|(a, b)| a.is_whitespace() && b.is_whitespace())
+}
+
+fn tags_valid(tags: &[String]) -> bool {
+    tags.len() <= TAG_MAX_COUNT && tags.iter().all(|t| is_valid_tag(t))
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 pub struct MaybeProjectDataPatch {
+    pub name: Option<String>,
     pub description: Option<String>,
     pub tags: Option<Vec<String>>,
     pub game: Option<GameDataPatch>,
     pub readme: Option<String>,
     #[serde(default, deserialize_with = "double_option")]
-    pub image: Option<Option<String>>
+    pub image: Option<Option<String>>,
+    pub archived: Option<bool>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub superseded_by: Option<Option<String>>
 }
 
 impl MaybeProjectDataPatch {
     fn empty(&self) -> bool {
         match self {
             MaybeProjectDataPatch {
+                name: None,
                 description: None,
                 tags: None,
                 game: None | Some(GameDataPatch {
@@ -114,7 +251,9 @@ impl MaybeProjectDataPatch {
                     year: None
                 }),
                 readme: None,
-                image: None
+                image: None,
+                archived: None,
+                superseded_by: None
             }
             => true,
             _ => false
@@ -125,12 +264,15 @@ impl MaybeProjectDataPatch {
 #[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(try_from = "MaybeProjectDataPatch")]
 pub struct ProjectDataPatch {
+    pub name: Option<String>,
     pub description: Option<String>,
     pub tags: Option<Vec<String>>,
     #[serde(default)]
     pub game: GameDataPatch,
     pub readme: Option<String>,
-    pub image: Option<Option<String>>
+    pub image: Option<Option<String>>,
+    pub archived: Option<bool>,
+    pub superseded_by: Option<Option<String>>
 }
 
 #[derive(Debug, thiserror::Error, Eq, PartialEq)]
@@ -142,30 +284,100 @@ impl TryFrom<MaybeProjectDataPatch> for ProjectDataPatch {
 
     fn try_from(m: MaybeProjectDataPatch) -> Result<Self, Self::Error> {
         // at least one element must be present to be a valid request
-        if m.empty() {
+        if m.empty() ||
+            m.description.as_ref().is_some_and(|d|
+                d.chars().count() > PROJECT_DESCRIPTION_MAX_LENGTH
+            ) ||
+            m.tags.as_ref().is_some_and(|t| !tags_valid(t))
+        {
             Err(ProjectDataPatchError(m))
         }
         else {
             Ok(
                 ProjectDataPatch {
+                    name: m.name,
                     description: m.description,
                     tags: m.tags,
                     game: m.game.unwrap_or_default(),
                     readme: m.readme,
-                    image: m.image
+                    image: m.image,
+                    archived: m.archived,
+                    superseded_by: m.superseded_by
                 }
             )
         }
     }
 }
 
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct TagChanges {
+    pub tags: Vec<String>,
+    pub added: Vec<String>,
+    pub removed: Vec<String>
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ProjectDataPatchResult {
+    pub revision: i64,
+    pub tags: Option<TagChanges>
+}
+
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ProjectPackagePost {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub section: Option<String>
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct MaybeProjectDataPost {
+    pub description: String,
+    pub tags: Vec<String>,
+    pub game: GameData,
+    pub readme: String,
+    pub image: Option<String>,
+    #[serde(default)]
+    pub packages: Vec<ProjectPackagePost>
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(try_from = "MaybeProjectDataPost")]
 pub struct ProjectDataPost {
     pub description: String,
     pub tags: Vec<String>,
     pub game: GameData,
     pub readme: String,
-    pub image: Option<String>
+    pub image: Option<String>,
+    // packages created along with the project
+    pub packages: Vec<ProjectPackagePost>
+}
+
+#[derive(Debug, thiserror::Error, Eq, PartialEq)]
+#[error("invalid data {0:?}")]
+pub struct ProjectDataPostError(MaybeProjectDataPost);
+
+impl TryFrom<MaybeProjectDataPost> for ProjectDataPost {
+    type Error = ProjectDataPostError;
+
+    fn try_from(m: MaybeProjectDataPost) -> Result<Self, Self::Error> {
+        if !tags_valid(&m.tags) {
+            Err(ProjectDataPostError(m))
+        }
+        else {
+            Ok(
+                ProjectDataPost {
+                    description: m.description,
+                    tags: m.tags,
+                    game: m.game,
+                    readme: m.readme,
+                    image: m.image,
+                    packages: m.packages
+                }
+            )
+        }
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -175,16 +387,230 @@ pub struct ProjectSummary {
     pub revision: i64,
     pub created_at: String,
     pub modified_at: String,
+    // always sorted lexicographically
     pub tags: Vec<String>,
-    pub game: GameData
+    pub game: GameData,
+    #[serde(default)]
+    pub modified_by: Option<String>
 }
 
 #[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct Projects {
     pub projects: Vec<ProjectSummary>,
+    pub meta: Pagination,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub facets: Option<FacetCounts>
+}
+
+#[derive(Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct FacetCounts {
+    pub tags: BTreeMap<String, i64>
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ReleaseData {
+    pub version: String,
+    pub files: Vec<FileData>
+}
+
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Releases {
+    pub releases: Vec<ReleaseData>,
     pub meta: Pagination
 }
 
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ReleaseFeedEntry {
+    pub package: String,
+    pub version: String,
+    pub published_at: String,
+    pub published_by: String,
+    pub files: Vec<FileData>
+}
+
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ReleaseFeed {
+    pub project: String,
+    pub title: String,
+    pub updated: String,
+    pub entries: Vec<ReleaseFeedEntry>
+}
+
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct PopularProjects {
+    pub projects: Vec<ProjectSummary>
+}
+
+#[derive(Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ProjectsByName {
+    // keyed by project name; unknown names are absent
+    pub projects: BTreeMap<String, ProjectSummary>
+}
+
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct RecentProjects {
+    pub projects: Vec<ProjectSummary>
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ProjectRevisionSummary {
+    pub revision: i64,
+    pub modified_at: String,
+    pub modified_by: String
+}
+
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ProjectRevisions {
+    pub revisions: Vec<ProjectRevisionSummary>
+}
+
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ProjectRevisionBatch {
+    pub revisions: Vec<ProjectData>
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct FieldChange {
+    pub from: serde_json::Value,
+    pub to: serde_json::Value
+}
+
+#[derive(Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ProjectDiff {
+    pub changed_fields: BTreeMap<String, FieldChange>
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Flag {
+    pub flag_id: i64,
+    pub project: String,
+    pub flagged_by: String,
+    pub flagged_at: String,
+    pub flag: String,
+    pub message: Option<String>,
+    pub resolved_at: Option<String>,
+    pub resolved_by: Option<String>
+}
+
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Flags {
+    pub flags: Vec<Flag>
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SlugChange {
+    pub project: String,
+    pub from: String,
+    pub to: String
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SlugCollision {
+    pub slug: String,
+    pub projects: Vec<String>
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct BadTimestamp {
+    pub table: String,
+    pub id: i64,
+    pub column: String,
+    pub value: i64
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct MalformedYear {
+    pub project: String,
+    pub year: String
+}
+
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct MalformedYears {
+    pub projects: Vec<MalformedYear>
+}
+
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct YearCorrection {
+    pub year: String
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct LibraryStats {
+    pub projects: i64,
+    pub packages: i64,
+    pub releases: i64,
+    pub files: i64,
+    pub bytes: i64,
+    pub players: i64,
+    pub owners: i64,
+    pub flags: i64
+}
+
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ProjectStats {
+    pub files: i64,
+    pub bytes: i64,
+    pub releases: i64,
+    pub packages: i64,
+    pub players: i64,
+    pub owners: i64,
+    pub flags: i64,
+    pub age_days: i64,
+    pub views: i64,
+    pub downloads: i64,
+    pub daily: Vec<DailyStats>
+}
+
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct DailyStats {
+    pub date: String,
+    pub views: i64,
+    pub downloads: i64
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct InboxItem {
+    pub id: i64,
+    pub kind: String,
+    pub project: String,
+    pub package: Option<String>,
+    pub version: Option<String>,
+    pub created_at: String,
+    pub read: bool
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Inbox {
+    pub notifications: Vec<InboxItem>,
+    pub unread: i64,
+    pub next_before: Option<i64>
+}
+
+#[derive(Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct MarkRead {
+    // marks everything read when absent
+    #[serde(default)]
+    pub ids: Option<Vec<i64>>
+}
+
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ProjectStatus {
+    pub open_flags: i64,
+    pub archived: bool,
+    pub bytes: i64
+}
+
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct BadTimestamps {
+    pub timestamps: Vec<BadTimestamp>
+}
+
+#[derive(Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SlugRecompute {
+    pub changed: Vec<SlugChange>,
+    pub collisions: Vec<SlugCollision>
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -228,6 +654,18 @@ mod test {
         );
     }
 
+    #[test]
+    fn maybe_project_data_patch_from_json_superseded_by_clear() {
+        let json = "{\"superseded_by\": null}";
+        assert_eq!(
+            serde_json::from_str::<MaybeProjectDataPatch>(json).unwrap(),
+            MaybeProjectDataPatch {
+                superseded_by: Some(None),
+                ..Default::default()
+            }
+        );
+    }
+
     #[test]
     fn maybe_project_data_patch_default_empty() {
         assert!(MaybeProjectDataPatch::default().empty());
@@ -253,6 +691,16 @@ mod test {
         );
     }
 
+    #[test]
+    fn maybe_project_data_patch_name_not_empty() {
+        assert!(
+            !MaybeProjectDataPatch {
+                name: Some("foo".into()),
+                ..Default::default()
+            }.empty()
+        );
+    }
+
     #[test]
     fn maybe_project_data_patch_game_not_empty() {
         assert!(
@@ -281,6 +729,36 @@ mod test {
         );
     }
 
+    #[test]
+    fn try_from_project_data_patch_description_max_length() {
+        // the limit is on characters, not bytes
+        let d = "é".repeat(PROJECT_DESCRIPTION_MAX_LENGTH);
+        assert_eq!(
+            ProjectDataPatch::try_from(
+                MaybeProjectDataPatch {
+                    description: Some(d.clone()),
+                    ..Default::default()
+                }
+            ).unwrap(),
+            ProjectDataPatch {
+                description: Some(d),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn try_from_project_data_patch_description_too_long() {
+        let m = MaybeProjectDataPatch {
+            description: Some("x".repeat(PROJECT_DESCRIPTION_MAX_LENGTH + 1)),
+            ..Default::default()
+        };
+        assert_eq!(
+            ProjectDataPatch::try_from(m.clone()).unwrap_err(),
+            ProjectDataPatchError(m)
+        );
+    }
+
     #[test]
     fn try_from_project_data_patch_image_clear() {
         assert_eq!(
@@ -305,4 +783,157 @@ mod test {
             ProjectDataPatchError(MaybeProjectDataPatch::default())
         );
     }
+
+    #[test]
+    fn is_valid_tag_ok() {
+        assert!(is_valid_tag("wwii"));
+        assert!(is_valid_tag("era:wwii"));
+        assert!(is_valid_tag("east front"));
+        // the limit is on characters, not bytes
+        assert!(is_valid_tag(&"é".repeat(TAG_MAX_LENGTH)));
+    }
+
+    #[test]
+    fn is_valid_tag_empty() {
+        assert!(!is_valid_tag(""));
+    }
+
+    #[test]
+    fn is_valid_tag_too_long() {
+        assert!(!is_valid_tag(&"x".repeat(TAG_MAX_LENGTH + 1)));
+    }
+
+    #[test]
+    fn is_valid_tag_control() {
+        assert!(!is_valid_tag("ww\u{0}ii"));
+        assert!(!is_valid_tag("ww\u{7f}ii"));
+    }
+
+    #[test]
+    fn is_valid_tag_leading_whitespace() {
+        assert!(!is_valid_tag(" wwii"));
+    }
+
+    #[test]
+    fn is_valid_tag_trailing_whitespace() {
+        assert!(!is_valid_tag("wwii "));
+    }
+
+    #[test]
+    fn is_valid_tag_consecutive_whitespace() {
+        assert!(!is_valid_tag("east  front"));
+        assert!(!is_valid_tag("east \u{3000}front"));
+    }
+
+    #[test]
+    fn is_valid_tag_empty_namespace() {
+        assert!(!is_valid_tag(":wwii"));
+    }
+
+    #[test]
+    fn try_from_project_data_patch_bad_tag() {
+        let m = MaybeProjectDataPatch {
+            tags: Some(vec!["wwii".into(), "".into()]),
+            ..Default::default()
+        };
+        assert_eq!(
+            ProjectDataPatch::try_from(m.clone()).unwrap_err(),
+            ProjectDataPatchError(m)
+        );
+    }
+
+    #[test]
+    fn try_from_project_data_patch_too_many_tags() {
+        let m = MaybeProjectDataPatch {
+            tags: Some(
+                (0..=TAG_MAX_COUNT).map(|i| format!("t{i}")).collect()
+            ),
+            ..Default::default()
+        };
+        assert_eq!(
+            ProjectDataPatch::try_from(m.clone()).unwrap_err(),
+            ProjectDataPatchError(m)
+        );
+    }
+
+    fn maybe_project_data_post(tags: Vec<String>) -> MaybeProjectDataPost {
+        MaybeProjectDataPost {
+            description: "d".into(),
+            tags,
+            game: GameData {
+                title: "t".into(),
+                title_sort_key: "t".into(),
+                publisher: "p".into(),
+                year: "1979".into()
+            },
+            readme: "".into(),
+            image: None,
+            packages: vec![]
+        }
+    }
+
+    #[test]
+    fn try_from_project_data_post_ok() {
+        let tags = (0..TAG_MAX_COUNT).map(|i| format!("t{i}")).collect();
+        let m = maybe_project_data_post(tags);
+        assert_eq!(
+            ProjectDataPost::try_from(m.clone()).unwrap().tags,
+            m.tags
+        );
+    }
+
+    #[test]
+    fn try_from_project_data_post_bad_tag() {
+        let m = maybe_project_data_post(vec!["x".repeat(TAG_MAX_LENGTH + 1)]);
+        assert_eq!(
+            ProjectDataPost::try_from(m.clone()).unwrap_err(),
+            ProjectDataPostError(m)
+        );
+    }
+
+    #[test]
+    fn try_from_project_data_post_too_many_tags() {
+        let m = maybe_project_data_post(
+            (0..=TAG_MAX_COUNT).map(|i| format!("t{i}")).collect()
+        );
+        assert_eq!(
+            ProjectDataPost::try_from(m.clone()).unwrap_err(),
+            ProjectDataPostError(m)
+        );
+    }
+
+    #[test]
+    fn package_data_patch_from_json_section_clear() {
+        let json = "{\"section\": null}";
+        assert_eq!(
+            serde_json::from_str::<PackageDataPatch>(json).unwrap(),
+            PackageDataPatch {
+                name: None,
+                description: None,
+                section: Some(None)
+            }
+        );
+    }
+
+    #[test]
+    fn package_data_patch_from_json_name() {
+        let json = "{\"name\": \"b_package\"}";
+        assert_eq!(
+            serde_json::from_str::<PackageDataPatch>(json).unwrap(),
+            PackageDataPatch {
+                name: Some("b_package".into()),
+                description: None,
+                section: None
+            }
+        );
+    }
+
+    #[test]
+    fn try_from_package_data_patch_err() {
+        assert_eq!(
+            PackageDataPatch::try_from(MaybePackageDataPatch::default())
+                .unwrap_err(),
+            PackageDataPatchError(MaybePackageDataPatch::default())
+        );
+    }
 }
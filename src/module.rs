@@ -1,5 +1,5 @@
 use std::{
-    io::{self, Read},
+    io::{self, Read, Seek},
     fs::File
 };
 use zip::{
@@ -20,9 +20,15 @@ pub enum Error {
 }
 
 fn dump_file(zippath: &str, filepath: &str) -> Result<String, Error> {
+    dump_file_from(File::open(zippath)?, filepath)
+}
+
+fn dump_file_from<R>(reader: R, filepath: &str) -> Result<String, Error>
+where
+    R: Read + Seek
+{
     // open module as zip archive
-    let zipfile = File::open(zippath)?;
-    let mut archive = ZipArchive::new(zipfile)?;
+    let mut archive = ZipArchive::new(reader)?;
 
     // read moduledata file
     let mut file = archive.by_name(filepath)?;
@@ -44,6 +50,14 @@ pub fn extract_version(path: &str) -> Result<String, Error> {
     version_in_moduledata(&md)
 }
 
+pub fn extract_version_from<R>(reader: R) -> Result<String, Error>
+where
+    R: Read + Seek
+{
+    let md = dump_file_from(reader, "moduledata")?;
+    version_in_moduledata(&md)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -105,6 +119,25 @@ mod test {
         );
     }
 
+    #[test]
+    fn extract_version_from_ok() {
+        let data = std::fs::read("test/test.vmod").unwrap();
+        assert_eq!(
+            extract_version_from(io::Cursor::new(data)).unwrap(),
+            "0.0"
+        );
+    }
+
+    #[test]
+    fn extract_version_from_not_a_zip() {
+        assert!(
+            matches!(
+                extract_version_from(io::Cursor::new(b"bogus")).unwrap_err(),
+                Error::Zip(_)
+            )
+        );
+    }
+
     #[test]
     fn extract_version_ok() {
         assert_eq!(
@@ -0,0 +1,163 @@
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+
+use crate::db::DatabaseClient;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Notification {
+    OwnerAdded { user: String, project: String },
+    OwnerRemoved { user: String, project: String },
+    FlagResolved { user: String, project: String },
+    ReleasePublished {
+        user: String,
+        project: String,
+        package: String,
+        version: String
+    }
+}
+
+impl Notification {
+    pub fn user(&self) -> &str {
+        match self {
+            Notification::OwnerAdded { user, .. } |
+            Notification::OwnerRemoved { user, .. } |
+            Notification::FlagResolved { user, .. } |
+            Notification::ReleasePublished { user, .. } => user
+        }
+    }
+
+    pub fn project(&self) -> &str {
+        match self {
+            Notification::OwnerAdded { project, .. } |
+            Notification::OwnerRemoved { project, .. } |
+            Notification::FlagResolved { project, .. } |
+            Notification::ReleasePublished { project, .. } => project
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Notification::OwnerAdded { .. } => "owner_added",
+            Notification::OwnerRemoved { .. } => "owner_removed",
+            Notification::FlagResolved { .. } => "flag_resolved",
+            Notification::ReleasePublished { .. } => "release_published"
+        }
+    }
+
+    pub fn release(&self) -> Option<(&str, &str)> {
+        match self {
+            Notification::ReleasePublished { package, version, .. } =>
+                Some((package, version)),
+            _ => None
+        }
+    }
+}
+
+// Delivery is best-effort: a sink must return promptly and must not
+// report failure, as the change it announces has already been made.
+pub trait NotificationSink: Send + Sync {
+    fn deliver(&self, notification: Notification);
+}
+
+// Discards everything; used when nothing is listening.
+pub struct NullSink;
+
+impl NotificationSink for NullSink {
+    fn deliver(&self, _notification: Notification) {}
+}
+
+// Stores notifications in their users' inboxes. Storing happens on its
+// own task, so that the request which caused it need not wait.
+pub struct InboxSink<C> {
+    pub db: C,
+    pub now: fn() -> DateTime<Utc>
+}
+
+impl<C> NotificationSink for InboxSink<C>
+where
+    C: DatabaseClient + Clone + Send + Sync + 'static
+{
+    fn deliver(&self, notification: Notification) {
+        let Some(now) = (self.now)().timestamp_nanos_opt() else { return };
+        let db = self.db.clone();
+
+        tokio::spawn(async move {
+            if let Err(e) = db.add_notification(&notification, now).await {
+                eprintln!("failed to notify {}: {e}", notification.user());
+            }
+        });
+    }
+}
+
+#[derive(Clone)]
+pub struct Notifier(Arc<dyn NotificationSink>);
+
+impl Notifier {
+    pub fn new<S: NotificationSink + 'static>(sink: S) -> Self {
+        Notifier(Arc::new(sink))
+    }
+
+    pub fn notify(&self, notification: Notification) {
+        self.0.deliver(notification);
+    }
+}
+
+impl Default for Notifier {
+    fn default() -> Self {
+        Notifier::new(NullSink)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use std::sync::Mutex;
+
+    #[derive(Clone, Default)]
+    struct VecSink(Arc<Mutex<Vec<Notification>>>);
+
+    impl NotificationSink for VecSink {
+        fn deliver(&self, notification: Notification) {
+            self.0.lock().unwrap().push(notification);
+        }
+    }
+
+    #[test]
+    fn notification_fields() {
+        let n = Notification::OwnerRemoved {
+            user: "alice".into(),
+            project: "test_game".into()
+        };
+        assert_eq!(n.user(), "alice");
+        assert_eq!(n.project(), "test_game");
+        assert_eq!(n.kind(), "owner_removed");
+        assert_eq!(n.release(), None);
+    }
+
+    #[test]
+    fn notification_release() {
+        let n = Notification::ReleasePublished {
+            user: "alice".into(),
+            project: "test_game".into(),
+            package: "a_package".into(),
+            version: "1.2.4".into()
+        };
+        assert_eq!(n.kind(), "release_published");
+        assert_eq!(n.release(), Some(("a_package", "1.2.4")));
+    }
+
+    #[test]
+    fn notifier_delivers() {
+        let sink = VecSink::default();
+        let notifier = Notifier::new(sink.clone());
+
+        let n = Notification::OwnerAdded {
+            user: "alice".into(),
+            project: "test_game".into()
+        };
+        notifier.notify(n.clone());
+
+        assert_eq!(*sink.0.lock().unwrap(), [n]);
+    }
+}
@@ -0,0 +1,144 @@
+use axum::{
+    Router,
+    extract::{Request, State},
+    http::{
+        Method, StatusCode,
+        header::{ACCESS_CONTROL_REQUEST_METHOD, ALLOW}
+    },
+    middleware::Next,
+    response::{IntoResponse, Response}
+};
+use tower::ServiceExt;
+
+use crate::errors::AppError;
+
+// When read-only, every method which could modify something is refused;
+// refused methods are not advertised as allowed.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ReadOnly(pub bool);
+
+impl ReadOnly {
+    fn refuses(&self, method: &str) -> bool {
+        self.0 && ![
+            Method::GET,
+            Method::HEAD,
+            Method::OPTIONS
+        ].iter().any(|m| m.as_str() == method)
+    }
+}
+
+fn allow_value(read_only: ReadOnly, allow: &str) -> String {
+    allow.split(',')
+        .map(str::trim)
+        .filter(|m| !m.is_empty() && !read_only.refuses(m))
+        .chain(std::iter::once(Method::OPTIONS.as_str()))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+// The routes, without the CORS layer, which would answer any OPTIONS
+// request as a preflight.
+#[derive(Clone)]
+pub struct Discovery {
+    pub read_only: ReadOnly,
+    pub router: Router
+}
+
+// An explicit OPTIONS, which lacks Access-Control-Request-Method, is sent
+// to the routes without the CORS layer. No route accepts OPTIONS, so the
+// router answers with a 405 and sets the Allow header to the methods the
+// route does accept.
+pub async fn discover(
+    State(discovery): State<Discovery>,
+    request: Request,
+    next: Next
+) -> Response
+{
+    if request.method() != Method::OPTIONS ||
+        request.headers().contains_key(ACCESS_CONTROL_REQUEST_METHOD)
+    {
+        return next.run(request).await;
+    }
+
+    let response = discovery.router
+        .oneshot(request)
+        .await
+        .unwrap_or_else(|e| match e {});
+
+    if response.status() != StatusCode::METHOD_NOT_ALLOWED {
+        return response;
+    }
+
+    let allow = response.headers()
+        .get(ALLOW)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+
+    (
+        StatusCode::NO_CONTENT,
+        [(ALLOW, allow_value(discovery.read_only, allow))]
+    ).into_response()
+}
+
+// Applied to the routes, so that paths which do not exist are still
+// not found when read-only
+pub async fn guard(
+    State(read_only): State<ReadOnly>,
+    request: Request,
+    next: Next
+) -> Response
+{
+    if read_only.refuses(request.method().as_str()) {
+        AppError::Forbidden.into_response()
+    }
+    else {
+        next.run(request).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn allow_value_adds_options() {
+        assert_eq!(
+            allow_value(ReadOnly(false), "GET,HEAD"),
+            "GET,HEAD,OPTIONS"
+        );
+    }
+
+    #[test]
+    fn allow_value_read_write() {
+        assert_eq!(
+            allow_value(ReadOnly(false), "GET,HEAD,POST,DELETE"),
+            "GET,HEAD,POST,DELETE,OPTIONS"
+        );
+    }
+
+    #[test]
+    fn allow_value_drops_refused() {
+        assert_eq!(
+            allow_value(ReadOnly(true), "GET,HEAD,POST,PUT,PATCH,DELETE"),
+            "GET,HEAD,OPTIONS"
+        );
+    }
+
+    #[test]
+    fn allow_value_empty() {
+        assert_eq!(allow_value(ReadOnly(false), ""), "OPTIONS");
+    }
+
+    #[test]
+    fn read_only_refuses() {
+        let ro = ReadOnly(true);
+        assert!(!ro.refuses("GET"));
+        assert!(!ro.refuses("HEAD"));
+        assert!(!ro.refuses("OPTIONS"));
+        assert!(ro.refuses("POST"));
+        assert!(ro.refuses("PUT"));
+        assert!(ro.refuses("PATCH"));
+        assert!(ro.refuses("DELETE"));
+        assert!(!ReadOnly(false).refuses("DELETE"));
+    }
+}
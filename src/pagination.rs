@@ -7,6 +7,12 @@ use std::{
     num::NonZeroU8
 };
 
+use crate::version::{MalformedVersion, Version};
+
+// page limits when none are configured
+pub const DEFAULT_LIMIT: u8 = 10;
+pub const DEFAULT_MAX_LIMIT: u8 = 100;
+
 #[derive(Debug, thiserror::Error, Eq, PartialEq)]
 pub enum LimitError {
     #[error("limit {0} out of range")]
@@ -15,19 +21,17 @@ pub enum LimitError {
     Malformed(String)
 }
 
-#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
 #[serde(try_from = "&str")]
 #[repr(transparent)]
 pub struct Limit(NonZeroU8);
 
 impl Limit {
     pub const fn new(limit: u8) -> Option<Limit> {
-        match limit {
-            limit if limit > 100 => None,
-            limit => match NonZeroU8::new(limit) {
-                Some(n) => Some(Limit(n)),
-                None => None
-            }
+        // the upper bound is configurable, so is checked by the core
+        match NonZeroU8::new(limit) {
+            Some(n) => Some(Limit(n)),
+            None => None
         }
     }
 
@@ -36,12 +40,6 @@ impl Limit {
     }
 }
 
-impl Default for Limit {
-    fn default() -> Self {
-        Limit::new(10).expect("0 < 10 <= 100")
-    }
-}
-
 impl TryFrom<&str> for Limit {
     type Error = LimitError;
 
@@ -310,6 +308,162 @@ impl SortBy {
     }
 }
 
+#[derive(Debug, thiserror::Error, Eq, PartialEq)]
+#[error("facet tag {0} unknown")]
+pub struct FacetTagError(String);
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(try_from = "&str", into = "String")]
+enum FacetTag {
+    Tag,
+    Publisher,
+    Year,
+    UnresolvedOnly,
+    MaxVassal,
+    Archived,
+    Superseded
+}
+
+impl From<FacetTag> for String {
+    fn from(value: FacetTag) -> Self {
+        match value {
+            FacetTag::Tag => "t".into(),
+            FacetTag::Publisher => "p".into(),
+            FacetTag::Year => "y".into(),
+            FacetTag::UnresolvedOnly => "u".into(),
+            FacetTag::MaxVassal => "v".into(),
+            FacetTag::Archived => "a".into(),
+            FacetTag::Superseded => "s".into()
+        }
+    }
+}
+
+impl TryFrom<&str> for FacetTag {
+    type Error = FacetTagError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "t" => Ok(FacetTag::Tag),
+            "p" => Ok(FacetTag::Publisher),
+            "y" => Ok(FacetTag::Year),
+            "u" => Ok(FacetTag::UnresolvedOnly),
+            "v" => Ok(FacetTag::MaxVassal),
+            "a" => Ok(FacetTag::Archived),
+            "s" => Ok(FacetTag::Superseded),
+            _ => Err(FacetTagError(value.into()))
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+struct RawFacet {
+    tag: FacetTag,
+    value: String
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(try_from = "RawFacet", into = "RawFacet")]
+pub enum Facet {
+    Tag(String),
+    Publisher(String),
+    Year(String),
+    UnresolvedOnly,
+    MaxVassal(Version),
+    // true includes archived projects, false excludes them
+    Archived(bool),
+    // true includes superseded projects, false excludes them
+    Superseded(bool)
+}
+
+#[derive(Debug, thiserror::Error, Eq, PartialEq)]
+pub enum FacetError {
+    #[error("{0}")]
+    MalformedVersion(#[from] MalformedVersion),
+    #[error("{0} is not a boolean")]
+    MalformedBool(String)
+}
+
+impl TryFrom<RawFacet> for Facet {
+    type Error = FacetError;
+
+    fn try_from(rf: RawFacet) -> Result<Self, Self::Error> {
+        Ok(
+            match rf.tag {
+                FacetTag::Tag => Facet::Tag(rf.value),
+                FacetTag::Publisher => Facet::Publisher(rf.value),
+                FacetTag::Year => Facet::Year(rf.value),
+                FacetTag::UnresolvedOnly => Facet::UnresolvedOnly,
+                FacetTag::MaxVassal => Facet::MaxVassal(rf.value.parse()?),
+                FacetTag::Archived => Facet::Archived(
+                    rf.value.parse()
+                        .or(Err(FacetError::MalformedBool(rf.value)))?
+                ),
+                FacetTag::Superseded => Facet::Superseded(
+                    rf.value.parse()
+                        .or(Err(FacetError::MalformedBool(rf.value)))?
+                )
+            }
+        )
+    }
+}
+
+impl From<Facet> for RawFacet {
+    fn from(f: Facet) -> Self {
+        match f {
+            Facet::Tag(value) => RawFacet { tag: FacetTag::Tag, value },
+            Facet::Publisher(value) => RawFacet { tag: FacetTag::Publisher, value },
+            Facet::Year(value) => RawFacet { tag: FacetTag::Year, value },
+            Facet::UnresolvedOnly => RawFacet {
+                tag: FacetTag::UnresolvedOnly,
+                value: "".into()
+            },
+            Facet::MaxVassal(v) => RawFacet {
+                tag: FacetTag::MaxVassal,
+                value: String::from(&v)
+            },
+            Facet::Archived(a) => RawFacet {
+                tag: FacetTag::Archived,
+                value: a.to_string()
+            },
+            Facet::Superseded(s) => RawFacet {
+                tag: FacetTag::Superseded,
+                value: s.to_string()
+            }
+        }
+    }
+}
+
+// Tags may be namespaced as "namespace:value". The namespace is everything
+// before the first colon, so "a:b:c" is the value "b:c" in namespace "a".
+// A bare namespace "namespace:" matches every tag in that namespace.
+pub fn tag_namespace(tag: &str) -> Option<&str> {
+    match tag.split_once(':') {
+        Some((ns, "")) if !ns.is_empty() => Some(ns),
+        _ => None
+    }
+}
+
+pub fn tag_valid(tag: &str) -> bool {
+    // a tag may not be empty or have an empty namespace
+    !tag.is_empty() && !tag.starts_with(':')
+}
+
+pub fn facets_consistent(facets: &[Facet]) -> bool {
+    // a project has only one publisher and one year, so asking for more
+    // than one of either is contradictory; any number of tags is fine;
+    // resolution applies to flags, not to projects
+    facets.iter().filter(|f| matches!(f, Facet::Publisher(_))).count() <= 1 &&
+    facets.iter().filter(|f| matches!(f, Facet::Year(_))).count() <= 1 &&
+    facets.iter().filter(|f| matches!(f, Facet::MaxVassal(_))).count() <= 1 &&
+    facets.iter().filter(|f| matches!(f, Facet::Archived(_))).count() <= 1 &&
+    facets.iter().filter(|f| matches!(f, Facet::Superseded(_))).count() <= 1 &&
+    !facets.contains(&Facet::UnresolvedOnly) &&
+    facets.iter().all(|f| match f {
+        Facet::Tag(tag) => tag_valid(tag),
+        _ => true
+    })
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum SeekError {
     #[error("{0}")]
@@ -320,8 +474,12 @@ pub enum SeekError {
     Utf8Error(#[from] std::string::FromUtf8Error),
     #[error("Relevance must be paired with a query anchor, not {0:?}")]
     RelevanceMismatch(Anchor),
+    #[error("Contradictory facets {0:?}")]
+    ContradictoryFacets(Vec<Facet>),
     #[error("Empty seek")]
-    EmptySeek
+    EmptySeek,
+    #[error("Malformed release anchor {0:?}")]
+    MalformedReleaseAnchor(String)
 }
 
 impl PartialEq for SeekError {
@@ -340,7 +498,10 @@ impl PartialEq for SeekError {
 pub struct Seek {
     pub sort_by: SortBy,
     pub dir: Direction,
-    pub anchor: Anchor
+    pub anchor: Anchor,
+    // facets are written as additional records following the seek
+    #[serde(skip)]
+    pub facets: Vec<Facet>
 }
 
 impl Default for Seek {
@@ -348,7 +509,8 @@ impl Default for Seek {
         Seek {
             anchor: Anchor::Start,
             sort_by: SortBy::ProjectName,
-            dir: Direction::Ascending
+            dir: Direction::Ascending,
+            facets: vec![]
         }
     }
 }
@@ -367,9 +529,15 @@ impl TryFrom<&Seek> for String {
     fn try_from(s: &Seek) -> Result<Self, Self::Error> {
         let mut w = csv::WriterBuilder::new()
             .has_headers(false)
+            .flexible(true)
             .from_writer(vec![]);
 
         w.serialize(s)?;
+
+        for f in &s.facets {
+            w.serialize(f)?;
+        }
+
         let mut b = w.into_inner()
             .map_err(|e| SeekError::CsvIntoInnerError(Box::new(e)))?;
         b.pop(); // drop the terminator
@@ -383,10 +551,22 @@ impl FromStr for Seek {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut r = csv::ReaderBuilder::new()
             .has_headers(false)
+            .flexible(true)
             .from_reader(s.as_bytes());
 
-        if let Some(result) = r.deserialize().next() {
-            let seek: Seek = result?;
+        let mut records = r.records();
+
+        if let Some(result) = records.next() {
+            let mut seek: Seek = result?.deserialize(None)?;
+
+            // any further records are facets
+            seek.facets = records
+                .map(|rec| rec?.deserialize::<Facet>(None))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            if !facets_consistent(&seek.facets) {
+                return Err(SeekError::ContradictoryFacets(seek.facets));
+            }
 
             // Relevance must be paired with StartQuery, AfterQuery, BeforeQuery
             match seek.sort_by {
@@ -405,19 +585,107 @@ impl FromStr for Seek {
     }
 }
 
+// Releases have only one order, by version descending, so a release
+// anchor needs only the version at which the page starts or ends
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub enum ReleaseAnchor {
+    #[default]
+    Start,
+    Before(Version),
+    After(Version)
+}
+
+impl fmt::Display for ReleaseAnchor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReleaseAnchor::Start => write!(f, "s"),
+            ReleaseAnchor::Before(v) => write!(f, "b,{}", String::from(v)),
+            ReleaseAnchor::After(v) => write!(f, "a,{}", String::from(v))
+        }
+    }
+}
+
+impl FromStr for ReleaseAnchor {
+    type Err = SeekError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || SeekError::MalformedReleaseAnchor(s.into());
+
+        match s.split_once(',') {
+            None if s == "s" => Ok(ReleaseAnchor::Start),
+            Some(("b", v)) => Ok(
+                ReleaseAnchor::Before(v.parse().map_err(|_| err())?)
+            ),
+            Some(("a", v)) => Ok(
+                ReleaseAnchor::After(v.parse().map_err(|_| err())?)
+            ),
+            _ => Err(err())
+        }
+    }
+}
+
+// A seek may end with a record giving the time at which it was issued,
+// so that seeks which have gone stale can be refused.
+const ISSUED_TAG: &str = "i,";
+
+pub fn split_issued(s: &str) -> (&str, Option<i64>) {
+    // a quoted facet value cannot end the record as a bare number would
+    s.rsplit_once('\n')
+        .and_then(|(seek, last)| Some((
+            seek,
+            last.strip_prefix(ISSUED_TAG)?.parse::<i64>().ok()?
+        )))
+        .map_or((s, None), |(seek, issued_at)| (seek, Some(issued_at)))
+}
+
 #[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct SeekLink(String);
 
 impl SeekLink {
     pub fn new(seek: &Seek, limit: Option<Limit>) -> Result<SeekLink, SeekError> {
-        let s = String::try_from(seek)?;
+        Ok(SeekLink::encode(&String::try_from(seek)?, limit))
+    }
+
+    pub fn new_issued(
+        seek: &Seek,
+        limit: Option<Limit>,
+        issued_at: i64
+    ) -> Result<SeekLink, SeekError>
+    {
+        let s = format!("{}\n{ISSUED_TAG}{issued_at}", String::try_from(seek)?);
+        Ok(SeekLink::encode(&s, limit))
+    }
+
+    fn encode(s: &str, limit: Option<Limit>) -> SeekLink {
         let s = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(s);
 
         match limit {
-            Some(l) => Ok(SeekLink(format!("?limit={}&seek={}", l, s))),
-            None => Ok(SeekLink(format!("?seek={}", s)))
+            Some(l) => SeekLink(format!("?limit={}&seek={}", l, s)),
+            None => SeekLink(format!("?seek={}", s))
         }
     }
+
+    pub fn from_release_anchor(
+        anchor: &ReleaseAnchor,
+        limit: Option<Limit>
+    ) -> SeekLink
+    {
+        let s = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .encode(anchor.to_string());
+
+        match limit {
+            Some(l) => SeekLink(format!("?limit={}&seek={}", l, s)),
+            None => SeekLink(format!("?seek={}", s))
+        }
+    }
+
+    pub fn with_position(self) -> SeekLink {
+        SeekLink(format!("{}&with_position=true", self.0))
+    }
+
+    pub fn with_facet_counts(self) -> SeekLink {
+        SeekLink(format!("{}&facet_counts=tags", self.0))
+    }
 }
 
 impl fmt::Display for SeekLink {
@@ -430,7 +698,11 @@ impl fmt::Display for SeekLink {
 pub struct Pagination {
     pub prev_page: Option<SeekLink>,
     pub next_page: Option<SeekLink>,
-    pub total: i64
+    pub total: i64,
+    pub total_pages: i64,
+    // set when total is an estimate rather than an exact count
+    pub approximate: bool,
+    pub position: Option<i64>
 }
 
 #[cfg(test)]
@@ -467,8 +739,16 @@ mod test {
     }
 
     #[test]
-    fn string_to_limit_one_hundred_one_err() {
-        assert!(Limit::try_from("101").is_err());
+    fn string_to_limit_two_hundred_fifty_five_ok() {
+        assert_eq!(
+            Limit::try_from("255").unwrap(),
+            Limit::new(255).unwrap()
+        );
+    }
+
+    #[test]
+    fn string_to_limit_two_hundred_fifty_six_err() {
+        assert!(Limit::try_from("256").is_err());
     }
 
     #[track_caller]
@@ -620,6 +900,7 @@ mod test {
             sort_by: SortBy::ProjectName,
             dir: Direction::Ascending,
             anchor: Anchor::Start,
+            facets: vec![],
         };
 
         assert_eq!(
@@ -638,7 +919,8 @@ mod test {
                 Seek {
                     sort_by: SortBy::ProjectName,
                     dir: Direction::Ascending,
-                    anchor: Anchor::Start
+                    anchor: Anchor::Start,
+                    facets: vec![]
                 }
             ).unwrap(),
             "p,a,s,,,"
@@ -652,7 +934,8 @@ mod test {
                 Seek {
                     sort_by: SortBy::ProjectName,
                     dir: Direction::Descending,
-                    anchor: Anchor::Start
+                    anchor: Anchor::Start,
+                    facets: vec![]
                 }
             ).unwrap(),
             "p,d,s,,,"
@@ -667,6 +950,7 @@ mod test {
                     sort_by: SortBy::ProjectName,
                     dir: Direction::Ascending,
                     anchor: Anchor::Before("abc".into(), 0),
+                    facets: vec![],
                 }
             ).unwrap(),
             "p,a,b,abc,,0"
@@ -680,7 +964,8 @@ mod test {
                 Seek {
                     sort_by: SortBy::ProjectName,
                     dir: Direction::Ascending,
-                    anchor: Anchor::After("abc".into(), 0)
+                    anchor: Anchor::After("abc".into(), 0),
+                    facets: vec![]
                 }
             ).unwrap(),
             "p,a,a,abc,,0"
@@ -694,7 +979,8 @@ mod test {
             Seek {
                 sort_by: SortBy::ProjectName,
                 dir: Direction::Ascending,
-                anchor: Anchor::Start
+                anchor: Anchor::Start,
+                facets: vec![]
             }
         );
     }
@@ -706,7 +992,8 @@ mod test {
             Seek {
                 sort_by: SortBy::ProjectName,
                 dir: Direction::Descending,
-                anchor: Anchor::Start
+                anchor: Anchor::Start,
+                facets: vec![]
             }
         );
     }
@@ -718,7 +1005,8 @@ mod test {
             Seek {
                 sort_by: SortBy::ProjectName,
                 dir: Direction::Ascending,
-                anchor: Anchor::Before("abc".into(), 0)
+                anchor: Anchor::Before("abc".into(), 0),
+                facets: vec![]
             }
         );
     }
@@ -730,7 +1018,8 @@ mod test {
             Seek {
                 sort_by: SortBy::ProjectName,
                 dir: Direction::Ascending,
-                anchor: Anchor::After("abc".into(), 0)
+                anchor: Anchor::After("abc".into(), 0),
+                facets: vec![]
             }
         );
     }
@@ -739,4 +1028,235 @@ mod test {
     fn string_to_seek_err() {
         assert!("$$$".parse::<Seek>().is_err());
     }
+
+    #[test]
+    fn seek_to_string_facets() {
+        assert_eq!(
+            &String::try_from(
+                Seek {
+                    sort_by: SortBy::ProjectName,
+                    dir: Direction::Ascending,
+                    anchor: Anchor::After("abc".into(), 0),
+                    facets: vec![
+                        Facet::Tag("wwii".into()),
+                        Facet::Publisher("Avalon Hill, Inc.".into())
+                    ]
+                }
+            ).unwrap(),
+            "p,a,a,abc,,0\nt,wwii\np,\"Avalon Hill, Inc.\""
+        );
+    }
+
+    #[test]
+    fn seek_roundtrip_facets() {
+        let seek = Seek {
+            sort_by: SortBy::GameTitle,
+            dir: Direction::Descending,
+            anchor: Anchor::Before("abc".into(), 3),
+            facets: vec![
+                Facet::Tag("wwii".into()),
+                Facet::Tag("tactical".into()),
+                Facet::Publisher("Avalon Hill".into()),
+                Facet::Year("1979".into()),
+                Facet::MaxVassal("3.6.0".parse().unwrap())
+            ]
+        };
+
+        assert_eq!(
+            String::try_from(&seek)
+                .unwrap()
+                .parse::<Seek>()
+                .unwrap(),
+            seek
+        );
+    }
+
+    #[test]
+    fn seek_roundtrip_facet_archived() {
+        let seek = Seek {
+            sort_by: SortBy::ProjectName,
+            dir: Direction::Ascending,
+            anchor: Anchor::Start,
+            facets: vec![Facet::Archived(true)]
+        };
+
+        let s = String::try_from(&seek).unwrap();
+        assert_eq!(s, "p,a,s,,,\na,true");
+        assert_eq!(s.parse::<Seek>().unwrap(), seek);
+    }
+
+    #[test]
+    fn seek_roundtrip_facet_superseded() {
+        let seek = Seek {
+            sort_by: SortBy::ProjectName,
+            dir: Direction::Ascending,
+            anchor: Anchor::Start,
+            facets: vec![Facet::Superseded(false)]
+        };
+
+        let s = String::try_from(&seek).unwrap();
+        assert_eq!(s, "p,a,s,,,\ns,false");
+        assert_eq!(s.parse::<Seek>().unwrap(), seek);
+    }
+
+    #[test]
+    fn split_issued_none() {
+        assert_eq!(split_issued("p,a,s,,,"), ("p,a,s,,,", None));
+        assert_eq!(
+            split_issued("p,a,s,,,\na,true"),
+            ("p,a,s,,,\na,true", None)
+        );
+    }
+
+    #[test]
+    fn split_issued_some() {
+        assert_eq!(
+            split_issued("p,a,s,,,\na,true\ni,1699804206419538067"),
+            ("p,a,s,,,\na,true", Some(1699804206419538067))
+        );
+    }
+
+    #[test]
+    fn split_issued_quoted_facet() {
+        // a tag which looks like an issue time is not one
+        let seek = Seek {
+            facets: vec![Facet::Tag("x\ni,5".into())],
+            ..Default::default()
+        };
+
+        let s = String::try_from(&seek).unwrap();
+        assert_eq!(split_issued(&s), (s.as_str(), None));
+    }
+
+    #[test]
+    fn string_to_seek_facet_archived_malformed() {
+        assert!("p,a,s,,,\na,maybe".parse::<Seek>().is_err());
+    }
+
+    #[test]
+    fn string_to_seek_facets_archived_contradictory() {
+        assert!(
+            matches!(
+                "p,a,s,,,\na,true\na,false".parse::<Seek>().unwrap_err(),
+                SeekError::ContradictoryFacets(_)
+            )
+        );
+    }
+
+    #[test]
+    fn string_to_seek_facets_contradictory() {
+        assert!(
+            matches!(
+                "p,a,s,,,\ny,1979\ny,1980".parse::<Seek>().unwrap_err(),
+                SeekError::ContradictoryFacets(_)
+            )
+        );
+    }
+
+    #[test]
+    fn string_to_seek_facet_bad_tag() {
+        assert!("p,a,s,,,\nx,1979".parse::<Seek>().is_err());
+    }
+
+    #[test]
+    fn string_to_seek_facet_unresolved_only() {
+        assert!(
+            matches!(
+                "p,a,s,,,\nu,".parse::<Seek>().unwrap_err(),
+                SeekError::ContradictoryFacets(_)
+            )
+        );
+    }
+
+    #[test]
+    fn string_to_seek_facet_bad_max_vassal() {
+        assert!("p,a,s,,,\nv,bogus".parse::<Seek>().is_err());
+    }
+
+    #[test]
+    fn tag_namespace_bare() {
+        assert_eq!(tag_namespace("era:"), Some("era"));
+    }
+
+    #[test]
+    fn tag_namespace_full() {
+        assert_eq!(tag_namespace("era:wwii"), None);
+        assert_eq!(tag_namespace("wwii"), None);
+    }
+
+    #[test]
+    fn tag_namespace_many_colons() {
+        // only the first colon separates the namespace
+        assert_eq!(tag_namespace("era:wwii:"), None);
+        assert_eq!(tag_namespace("era::"), None);
+        assert_eq!(tag_namespace("era:wwii:pacific"), None);
+    }
+
+    #[test]
+    fn tag_namespace_empty() {
+        assert_eq!(tag_namespace(":"), None);
+        assert_eq!(tag_namespace(""), None);
+    }
+
+    #[test]
+    fn tag_valid_ok() {
+        assert!(tag_valid("wwii"));
+        assert!(tag_valid("era:"));
+        assert!(tag_valid("era:wwii:pacific"));
+    }
+
+    #[test]
+    fn tag_valid_bad() {
+        assert!(!tag_valid(""));
+        assert!(!tag_valid(":"));
+        assert!(!tag_valid(":wwii"));
+    }
+
+    #[test]
+    fn string_to_seek_facet_invalid_tag() {
+        assert!(
+            matches!(
+                "p,a,s,,,\nt,:wwii".parse::<Seek>().unwrap_err(),
+                SeekError::ContradictoryFacets(_)
+            )
+        );
+    }
+
+    #[test]
+    fn release_anchor_round_trip() {
+        let anchors = [
+            ReleaseAnchor::Start,
+            ReleaseAnchor::Before("1.2.3".parse().unwrap()),
+            ReleaseAnchor::After("1.2.3-rc.1+build.5".parse().unwrap())
+        ];
+
+        for a in anchors {
+            assert_eq!(a.to_string().parse::<ReleaseAnchor>().unwrap(), a);
+        }
+    }
+
+    #[test]
+    fn release_anchor_malformed() {
+        for s in ["", "x", "b", "b,", "a,bogus", "s,1.2.3"] {
+            assert!(
+                matches!(
+                    s.parse::<ReleaseAnchor>().unwrap_err(),
+                    SeekError::MalformedReleaseAnchor(_)
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn seek_link_from_release_anchor() {
+        let anchor = ReleaseAnchor::After("1.2.3".parse().unwrap());
+        assert_eq!(
+            SeekLink::from_release_anchor(&anchor, None),
+            SeekLink("?seek=YSwxLjIuMw".into())
+        );
+        assert_eq!(
+            SeekLink::from_release_anchor(&anchor, Limit::new(5)),
+            SeekLink("?limit=5&seek=YSwxLjIuMw".into())
+        );
+    }
 }
@@ -2,7 +2,10 @@ use base64::{Engine as _};
 use serde::Deserialize;
 use std::str;
 
-use crate::pagination::{Anchor, Limit, Direction, SortBy, Seek, SeekError};
+use crate::{
+    pagination::{Anchor, Facet, Limit, Direction, ReleaseAnchor, SortBy, Seek, SeekError, split_issued, tag_valid},
+    version::Version
+};
 
 #[derive(Debug, Default, Deserialize, Eq, PartialEq)]
 pub struct MaybeProjectsParams {
@@ -11,12 +14,26 @@ pub struct MaybeProjectsParams {
     pub order: Option<Direction>,
     pub from: Option<String>,
     pub seek: Option<String>,
-    pub limit: Option<Limit>
+    pub limit: Option<Limit>,
+    pub with_position: Option<bool>,
+    pub tag: Option<String>,
+    pub publisher: Option<String>,
+    pub year: Option<String>,
+    pub max_vassal: Option<Version>,
+    pub archived: Option<bool>,
+    pub superseded: Option<bool>,
+    pub facet_counts: Option<FacetCountsKind>
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum FacetCountsKind {
+    Tags
 }
 
 impl MaybeProjectsParams {
     fn valid(&self) -> bool {
-        // sort, order, query, from are incompatible with seek
+        // sort, order, query, from, facets are incompatible with seek
         // from is incompatible with query
         !(
             (
@@ -25,7 +42,13 @@ impl MaybeProjectsParams {
                     self.sort.is_some() ||
                     self.order.is_some() ||
                     self.from.is_some() ||
-                    self.q.is_some()
+                    self.q.is_some() ||
+                    self.tag.is_some() ||
+                    self.publisher.is_some() ||
+                    self.year.is_some() ||
+                    self.max_vassal.is_some() ||
+                    self.archived.is_some() ||
+                    self.superseded.is_some()
                 )
             )
             ||
@@ -38,29 +61,62 @@ impl MaybeProjectsParams {
 #[serde(try_from = "MaybeProjectsParams")]
 pub struct ProjectsParams {
     pub seek: Seek,
-    pub limit: Option<Limit>
+    pub limit: Option<Limit>,
+    pub with_position: bool,
+    pub facet_counts: bool,
+    // when the seek was issued; None if there is no seek
+    pub issued_at: Option<i64>
 }
 
 #[derive(Debug, thiserror::Error, PartialEq)]
 pub enum Error {
     #[error("invalid combination {0:?}")]
-    InvalidCombination(MaybeProjectsParams),
+    InvalidCombination(Box<MaybeProjectsParams>),
     #[error("invalid base64 {0}")]
     Base64DecodeError(#[from] base64::DecodeError),
     #[error("invalid UTF-8 {0}")]
     Utf8Error(#[from] std::str::Utf8Error),
     #[error("{0}")]
-    SeekError(#[from] SeekError)
+    SeekError(#[from] SeekError),
+    #[error("resolved=true is not supported")]
+    ResolvedOnly,
+    #[error("invalid tag {0:?}")]
+    InvalidTag(String),
+    #[error("invalid revision {0:?}")]
+    InvalidRevision(String)
 }
 
-fn decode_seek(enc: &str) -> Result<Seek, Error> {
+fn normalize_seek(enc: &str) -> String {
+    // Seeks are encoded with the URL-safe alphabet without padding, but
+    // older links used the standard alphabet with padding. Proxies which
+    // decode and re-encode query strings may also have turned '+' into
+    // a space. Map all of these onto the URL-safe alphabet.
+    enc.trim_end_matches('=')
+        .chars()
+        .map(|c| match c {
+            '+' | ' ' => '-',
+            '/' => '_',
+            c => c
+        })
+        .collect()
+}
+
+fn decode_seek(enc: &str) -> Result<(Seek, Option<i64>), Error> {
     // base64-decode the seek string
     let buf = base64::engine::general_purpose::URL_SAFE_NO_PAD
-        .decode(enc)?;
+        .decode(normalize_seek(enc))?;
+
+    let (seek, issued_at) = split_issued(str::from_utf8(&buf)?);
+    Ok((seek.parse::<Seek>()?, issued_at))
+}
+
+fn decode_release_anchor(enc: &str) -> Result<ReleaseAnchor, Error> {
+    let buf = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(normalize_seek(enc))?;
 
     Ok(
         str::from_utf8(&buf)?
-            .parse::<Seek>()?
+            .parse::<ReleaseAnchor>()?
     )
 }
 
@@ -83,28 +139,193 @@ fn convert_non_seek(m: MaybeProjectsParams) -> Seek {
 
     let dir = m.order.unwrap_or_else(|| sort_by.default_direction());
 
-    Seek { sort_by, dir, anchor }
+    let facets = [
+        m.tag.map(Facet::Tag),
+        m.publisher.map(Facet::Publisher),
+        m.year.map(Facet::Year),
+        m.max_vassal.map(Facet::MaxVassal),
+        m.archived.map(Facet::Archived),
+        m.superseded.map(Facet::Superseded)
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    Seek { sort_by, dir, anchor, facets }
 }
 
 impl TryFrom<MaybeProjectsParams> for ProjectsParams {
     type Error = Error;
 
     fn try_from(m: MaybeProjectsParams) -> Result<Self, Self::Error> {
+        if let Some(tag) = m.tag.as_ref().filter(|t| !tag_valid(t)) {
+            return Err(Error::InvalidTag(tag.clone()));
+        }
+
         match m.valid() {
-            true => Ok(
-                ProjectsParams {
-                    limit: m.limit,
-                    seek: match m.seek {
-                        Some(enc) => decode_seek(&enc)?,
-                        None => convert_non_seek(m)
+            true => {
+                let limit = m.limit;
+                let with_position = m.with_position.unwrap_or(false);
+                let facet_counts = m.facet_counts.is_some();
+                let (seek, issued_at) = match m.seek {
+                    Some(ref enc) => {
+                        // a seek which does not say when it was issued,
+                        // as when that has been cut off, is as old as can
+                        // be, so that it cannot outlive the max age
+                        let (seek, issued_at) = decode_seek(enc)?;
+                        (seek, Some(issued_at.unwrap_or(0)))
+                    },
+                    None => (convert_non_seek(m), None)
+                };
+
+                Ok(
+                    ProjectsParams {
+                        seek,
+                        limit,
+                        with_position,
+                        facet_counts,
+                        issued_at
                     }
-                }
+                )
+            },
+            false => Err(Error::InvalidCombination(Box::new(m)))
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Eq, PartialEq)]
+pub struct MaybeFlagsParams {
+    pub resolved: Option<bool>
+}
+
+#[derive(Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(try_from = "MaybeFlagsParams")]
+pub struct FlagsParams {
+    pub facets: Vec<Facet>
+}
+
+impl TryFrom<MaybeFlagsParams> for FlagsParams {
+    type Error = Error;
+
+    fn try_from(m: MaybeFlagsParams) -> Result<Self, Self::Error> {
+        match m.resolved {
+            None => Ok(FlagsParams { facets: vec![] }),
+            Some(false) => Ok(
+                FlagsParams { facets: vec![Facet::UnresolvedOnly] }
             ),
-            false => Err(Error::InvalidCombination(m))
+            // there is no facet for resolved flags only
+            Some(true) => Err(Error::ResolvedOnly)
         }
     }
 }
 
+#[derive(Debug, Default, Deserialize, Eq, PartialEq)]
+pub struct MaybeReleasesParams {
+    pub seek: Option<String>,
+    pub limit: Option<Limit>
+}
+
+#[derive(Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(try_from = "MaybeReleasesParams")]
+pub struct ReleasesParams {
+    pub anchor: ReleaseAnchor,
+    pub limit: Option<Limit>
+}
+
+impl TryFrom<MaybeReleasesParams> for ReleasesParams {
+    type Error = Error;
+
+    fn try_from(m: MaybeReleasesParams) -> Result<Self, Self::Error> {
+        Ok(
+            ReleasesParams {
+                anchor: match m.seek {
+                    Some(enc) => decode_release_anchor(&enc)?,
+                    None => ReleaseAnchor::Start
+                },
+                limit: m.limit
+            }
+        )
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Eq, PartialEq)]
+pub struct MaybeRevisionBatchParams {
+    pub nums: String
+}
+
+#[derive(Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(try_from = "MaybeRevisionBatchParams")]
+pub struct RevisionBatchParams {
+    pub nums: Vec<i64>
+}
+
+impl TryFrom<MaybeRevisionBatchParams> for RevisionBatchParams {
+    type Error = Error;
+
+    fn try_from(m: MaybeRevisionBatchParams) -> Result<Self, Self::Error> {
+        // revision numbers are comma-separated
+        Ok(
+            RevisionBatchParams {
+                nums: m.nums.split(',')
+                    .map(|n| n.trim()
+                        .parse::<i64>()
+                        .or(Err(Error::InvalidRevision(n.into())))
+                    )
+                    .collect::<Result<Vec<_>, _>>()?
+            }
+        )
+    }
+}
+
+// comma-separated, as project names cannot contain commas
+#[derive(Debug, Default, Deserialize, Eq, PartialEq)]
+pub struct ProjectNamesParams {
+    pub names: String
+}
+
+#[derive(Debug, Default, Deserialize, Eq, PartialEq)]
+pub struct LimitParams {
+    pub limit: Option<Limit>
+}
+
+#[derive(Debug, Default, Deserialize, Eq, PartialEq)]
+pub struct InboxParams {
+    pub limit: Option<Limit>,
+    pub before: Option<i64>
+}
+
+#[derive(Debug, Default, Deserialize, Eq, PartialEq)]
+pub struct ProjectDeleteParams {
+    #[serde(default)]
+    pub force: bool
+}
+
+#[derive(Debug, Default, Deserialize, Eq, PartialEq)]
+pub struct PackageDeleteParams {
+    #[serde(default)]
+    pub force: bool
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReadmeFormat {
+    #[default]
+    Markdown,
+    Html
+}
+
+#[derive(Debug, Default, Deserialize, Eq, PartialEq)]
+pub struct ReadmeParams {
+    #[serde(default)]
+    pub format: ReadmeFormat
+}
+
+#[derive(Debug, Default, Deserialize, Eq, PartialEq)]
+pub struct SlugRecomputeParams {
+    #[serde(default)]
+    pub aliases: bool
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -173,14 +394,81 @@ mod test {
     fn decode_seek_ok() {
         assert_eq!(
             decode_seek("cCxhLGEsYWJjLCww").unwrap(),
-            Seek {
-                sort_by: SortBy::ProjectName,
-                dir: Direction::Ascending,
-                anchor: Anchor::After("abc".into(), 0)
-            }
+            (
+                Seek {
+                    sort_by: SortBy::ProjectName,
+                    dir: Direction::Ascending,
+                    anchor: Anchor::After("abc".into(), 0),
+                    facets: vec![]
+                },
+                None
+            )
         );
     }
 
+    #[test]
+    fn decode_seek_issued() {
+        let seek = Seek::default();
+        let enc = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .encode(format!("{}\ni,12345", String::try_from(&seek).unwrap()));
+
+        assert_eq!(decode_seek(&enc).unwrap(), (seek, Some(12345)));
+    }
+
+    #[test]
+    fn normalize_seek_url_safe() {
+        assert_eq!(normalize_seek("a-b_c"), "a-b_c");
+    }
+
+    #[test]
+    fn normalize_seek_standard() {
+        assert_eq!(normalize_seek("a+b/c=="), "a-b_c");
+    }
+
+    #[test]
+    fn normalize_seek_plus_as_space() {
+        assert_eq!(normalize_seek("a b/c="), "a-b_c");
+    }
+
+    #[test]
+    fn decode_seek_old_encoding() {
+        let seek = Seek {
+            sort_by: SortBy::ProjectName,
+            dir: Direction::Ascending,
+            anchor: Anchor::After("é>ßé".into(), 0),
+            facets: vec![]
+        };
+
+        let old = base64::engine::general_purpose::STANDARD
+            .encode(String::try_from(&seek).unwrap());
+        assert!(old.contains('+') && old.contains('/') && old.ends_with('='));
+
+        assert_eq!(decode_seek(&old).unwrap(), (seek, None));
+    }
+
+    #[test]
+    fn decode_seek_after_reencoding() {
+        let seek = Seek {
+            sort_by: SortBy::ProjectName,
+            dir: Direction::Ascending,
+            anchor: Anchor::After("é>ßé".into(), 0),
+            facets: vec![]
+        };
+
+        // simulate a proxy which percent-decodes the query and re-encodes
+        // it, treating '+' as a space
+        let old = base64::engine::general_purpose::STANDARD
+            .encode(String::try_from(&seek).unwrap());
+        let reenc = old.replace('+', " ");
+        assert_eq!(decode_seek(&reenc).unwrap(), (seek.clone(), None));
+
+        // the new encoding is unaffected by the round trip
+        let new = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .encode(String::try_from(&seek).unwrap());
+        assert_eq!(new.replace('+', " "), new);
+        assert_eq!(decode_seek(&new).unwrap(), (seek, None));
+    }
+
     #[test]
     fn decode_seek_bad_base64() {
         assert!(
@@ -214,9 +502,38 @@ mod test {
             seek: Seek {
                 sort_by: SortBy::ProjectName,
                 dir: Direction::Ascending,
-                anchor: Anchor::Start
+                anchor: Anchor::Start,
+                facets: vec![]
             },
-            limit: None
+            limit: None,
+            with_position: false,
+            facet_counts: false,
+            issued_at: None
+        };
+
+        assert_eq!(ProjectsParams::try_from(mpp).unwrap(), pp);
+    }
+
+    #[test]
+    fn maybe_projects_params_try_from_with_position_ok() {
+        let mpp = MaybeProjectsParams {
+            seek: Some("cCxhLGEsYWJjLCww".into()),
+            with_position: Some(true),
+            ..Default::default()
+        };
+
+        let pp = ProjectsParams {
+            seek: Seek {
+                sort_by: SortBy::ProjectName,
+                dir: Direction::Ascending,
+                anchor: Anchor::After("abc".into(), 0),
+                facets: vec![]
+            },
+            limit: None,
+            with_position: true,
+            facet_counts: false,
+            // the seek does not say when it was issued
+            issued_at: Some(0)
         };
 
         assert_eq!(ProjectsParams::try_from(mpp).unwrap(), pp);
@@ -238,6 +555,32 @@ mod test {
         );
     }
 
+    #[test]
+    fn maybe_projects_params_try_from_tag_namespace() {
+        let mpp = MaybeProjectsParams {
+            tag: Some("era:".into()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            ProjectsParams::try_from(mpp).unwrap().seek.facets,
+            [Facet::Tag("era:".into())]
+        );
+    }
+
+    #[test]
+    fn maybe_projects_params_try_from_invalid_tag() {
+        let mpp = MaybeProjectsParams {
+            tag: Some(":wwii".into()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            ProjectsParams::try_from(mpp).unwrap_err(),
+            Error::InvalidTag(":wwii".into())
+        );
+    }
+
     #[test]
     fn maybe_projects_params_try_from_bad_base64() {
         let mpp = MaybeProjectsParams {
@@ -268,4 +611,187 @@ mod test {
             )
         );
     }
+
+    #[test]
+    fn maybe_projects_params_invalid_seek_and_facet() {
+        let mpp = MaybeProjectsParams {
+            seek: Some("whatever".into()),
+            tag: Some("wwii".into()),
+            ..Default::default()
+        };
+        assert!(!mpp.valid());
+    }
+
+    #[test]
+    fn maybe_projects_params_try_from_facets_ok() {
+        let mpp = MaybeProjectsParams {
+            tag: Some("wwii".into()),
+            publisher: Some("Avalon Hill".into()),
+            year: Some("1979".into()),
+            max_vassal: Some("3.6.0".parse().unwrap()),
+            ..Default::default()
+        };
+
+        let pp = ProjectsParams {
+            seek: Seek {
+                sort_by: SortBy::GameTitle,
+                dir: Direction::Ascending,
+                anchor: Anchor::Start,
+                facets: vec![
+                    Facet::Tag("wwii".into()),
+                    Facet::Publisher("Avalon Hill".into()),
+                    Facet::Year("1979".into()),
+                    Facet::MaxVassal("3.6.0".parse().unwrap())
+                ]
+            },
+            limit: None,
+            with_position: false,
+            facet_counts: false,
+            issued_at: None
+        };
+
+        assert_eq!(ProjectsParams::try_from(mpp).unwrap(), pp);
+    }
+
+    #[test]
+    fn maybe_projects_params_try_from_archived_ok() {
+        let mpp = MaybeProjectsParams {
+            archived: Some(true),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            ProjectsParams::try_from(mpp).unwrap().seek.facets,
+            [Facet::Archived(true)]
+        );
+    }
+
+    #[test]
+    fn maybe_projects_params_try_from_superseded_ok() {
+        let mpp = MaybeProjectsParams {
+            superseded: Some(false),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            ProjectsParams::try_from(mpp).unwrap().seek.facets,
+            [Facet::Superseded(false)]
+        );
+    }
+
+    #[test]
+    fn maybe_projects_params_try_from_facet_counts_ok() {
+        let mpp = MaybeProjectsParams {
+            facet_counts: Some(FacetCountsKind::Tags),
+            ..Default::default()
+        };
+
+        assert!(ProjectsParams::try_from(mpp).unwrap().facet_counts);
+    }
+
+    #[test]
+    fn maybe_projects_params_facet_counts_with_seek_ok() {
+        let mpp = MaybeProjectsParams {
+            seek: Some("cCxhLGEsYWJjLCww".into()),
+            facet_counts: Some(FacetCountsKind::Tags),
+            ..Default::default()
+        };
+
+        assert!(ProjectsParams::try_from(mpp).unwrap().facet_counts);
+    }
+
+    #[test]
+    fn maybe_projects_params_invalid_seek_and_archived() {
+        let mpp = MaybeProjectsParams {
+            seek: Some("whatever".into()),
+            archived: Some(true),
+            ..Default::default()
+        };
+        assert!(!mpp.valid());
+    }
+
+    #[test]
+    fn maybe_revision_batch_params_try_from_ok() {
+        let m = MaybeRevisionBatchParams { nums: "1, 3,5".into() };
+        assert_eq!(
+            RevisionBatchParams::try_from(m).unwrap(),
+            RevisionBatchParams { nums: vec![1, 3, 5] }
+        );
+    }
+
+    #[test]
+    fn maybe_revision_batch_params_try_from_bad() {
+        let m = MaybeRevisionBatchParams { nums: "1,,5".into() };
+        assert_eq!(
+            RevisionBatchParams::try_from(m).unwrap_err(),
+            Error::InvalidRevision("".into())
+        );
+    }
+
+    #[test]
+    fn maybe_flags_params_try_from_ok() {
+        assert_eq!(
+            FlagsParams::try_from(MaybeFlagsParams::default()).unwrap(),
+            FlagsParams { facets: vec![] }
+        );
+    }
+
+    #[test]
+    fn maybe_flags_params_try_from_unresolved_ok() {
+        let mfp = MaybeFlagsParams { resolved: Some(false) };
+
+        assert_eq!(
+            FlagsParams::try_from(mfp).unwrap(),
+            FlagsParams { facets: vec![Facet::UnresolvedOnly] }
+        );
+    }
+
+    #[test]
+    fn maybe_flags_params_try_from_resolved_err() {
+        let mfp = MaybeFlagsParams { resolved: Some(true) };
+
+        assert_eq!(
+            FlagsParams::try_from(mfp).unwrap_err(),
+            Error::ResolvedOnly
+        );
+    }
+
+    #[test]
+    fn maybe_releases_params_try_from_default() {
+        assert_eq!(
+            ReleasesParams::try_from(MaybeReleasesParams::default()).unwrap(),
+            ReleasesParams::default()
+        );
+    }
+
+    #[test]
+    fn maybe_releases_params_try_from_seek() {
+        let mrp = MaybeReleasesParams {
+            seek: Some("YSwxLjIuMw".into()),
+            limit: Limit::new(5)
+        };
+
+        assert_eq!(
+            ReleasesParams::try_from(mrp).unwrap(),
+            ReleasesParams {
+                anchor: ReleaseAnchor::After("1.2.3".parse().unwrap()),
+                limit: Limit::new(5)
+            }
+        );
+    }
+
+    #[test]
+    fn maybe_releases_params_try_from_bad_seek() {
+        let mrp = MaybeReleasesParams {
+            seek: Some("eCwxLjIuMw".into()),
+            limit: None
+        };
+
+        assert!(
+            matches!(
+                ReleasesParams::try_from(mrp).unwrap_err(),
+                Error::SeekError(SeekError::MalformedReleaseAnchor(_))
+            )
+        );
+    }
 }
@@ -3,28 +3,39 @@ use axum::{
     body::Bytes
 };
 use chrono::{DateTime, Utc};
-use futures::Stream;
+use futures::{Stream, stream};
 use futures_util::{
     StreamExt,
     future::try_join_all
 };
+use itertools::Itertools;
 use mime::Mime;
 use once_cell::sync::Lazy;
 use regex::Regex;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
 use std::{
+    collections::{BTreeMap, HashMap},
     future::Future,
-    io
+    io,
+    sync::Arc
 };
 
 use crate::{
     core::{Core, CoreError},
-    db::{DatabaseClient, PackageRow, ProjectRow, ProjectSummaryRow, FileRow},
-    model::{GameData, Owner, Package, PackageData, PackageDataPost, ProjectData, ProjectDataPatch, ProjectDataPost, Project, Projects, ProjectSummary, FileData, User, Users},
-    pagination::{Anchor, Direction, Limit, SortBy, Pagination, Seek, SeekLink},
-    params::ProjectsParams,
-    time::nanos_to_rfc3339,
-    upload::{LocalUploader, Uploader},
-    version::Version
+    count::CountCache,
+    forum::{FORUM_SUMMARY_MAX_ITEMS, FORUM_SUMMARY_VERSION, ForumProject, ForumRelease, ForumUserSummary},
+    db::{DatabaseClient, FlagRow, NotificationRow, PackageRow, ProjectRow, ProjectSummaryRow, FileRow, RevisionRow, SupersessionRow},
+    model::{BadTimestamp, BadTimestamps, DailyStats, FacetCounts, FieldChange, Flag, Flags, GalleryImage, GameData, GameDataPatch, Inbox, InboxItem, LibraryStats, MalformedYear, MalformedYears, Owner, Package, PackageData, PackageDataPatch, PackageDataPost, PackageSection, PopularProjects, ProjectPackagePost, ProjectData, ProjectDataPatch, ProjectDataPatchResult, ProjectDataPost, Project, ProjectDiff, ProjectRevisionBatch, ProjectRevisions, ProjectRevisionSummary, ProjectStats, ProjectStatus, Projects, ProjectsByName, ProjectSummary, RecentProjects, ReleaseData, ReleaseFeed, ReleaseFeedEntry, Releases, FileData, SlugRecompute, User, UserProfile, Users},
+    module::extract_version_from,
+    notify::{Notification, Notifier},
+    pagination::{Anchor, Direction, Facet, Limit, ReleaseAnchor, SortBy, Pagination, Seek, SeekLink},
+    params::{InboxParams, ProjectsParams, ReadmeFormat, ReleasesParams},
+    readme::{ReadmeCache, render_html},
+    time::{NANOS_PER_DAY, NANOS_PER_SEC, day_to_date, nanos_to_rfc3339},
+    upload::{LocalUploader, UploadError, Uploader, require_filename, storage_path},
+    version::Version,
+    year::{NonNumericYears, is_malformed_year, is_plausible_year}
 };
 
 #[derive(Clone)]
@@ -32,13 +43,24 @@ pub struct ProdCore<C: DatabaseClient, U: Uploader> {
     pub db: C,
     pub uploader: U,
     pub now: fn() -> DateTime<Utc>,
-    pub max_image_size: u64
+    pub max_image_size: u64,
+    pub max_file_size: u64,
+    pub storage_prefix_depth: usize,
+    pub non_numeric_years: NonNumericYears,
+    pub max_limit: Limit,
+    pub default_limit: Limit,
+    pub count_cache: CountCache,
+    // seconds for which a seek remains valid; 0 for no limit
+    pub seek_max_age: u32,
+    pub record_usage: bool,
+    pub notifier: Notifier,
+    pub readme_cache: ReadmeCache
 }
 
 #[async_trait]
 impl<C, U> Core for ProdCore<C, U>
 where
-    C: DatabaseClient + Send + Sync,
+    C: DatabaseClient + Clone + Send + Sync + 'static,
     U: Uploader + Send + Sync
 {
     async fn get_user_id(
@@ -57,6 +79,15 @@ where
         self.db.get_project_id(proj).await
     }
 
+    async fn get_package_id(
+         &self,
+        proj: Project,
+        pkg: &str
+    ) -> Result<Package, CoreError>
+    {
+        self.db.get_package_id(proj, pkg).await
+    }
+
     async fn get_owners(
         &self,
         proj: Project
@@ -71,7 +102,21 @@ where
         proj: Project
     ) -> Result<(), CoreError>
     {
-        self.db.add_owners(owners, proj).await
+        let before = self.db.get_owners(proj).await?;
+        self.db.add_owners(owners, proj).await?;
+
+        // only those who were not owners already are told
+        let added = owners.users.iter()
+            .filter(|u| !before.users.contains(u))
+            .unique()
+            .cloned()
+            .collect::<Vec<_>>();
+
+        self.notify_users(proj, added, |user, project|
+            Notification::OwnerAdded { user, project }
+        ).await;
+
+        Ok(())
     }
 
     async fn remove_owners(
@@ -80,7 +125,21 @@ where
         proj: Project
     ) -> Result<(), CoreError>
     {
-        self.db.remove_owners(owners, proj).await
+        let before = self.db.get_owners(proj).await?;
+        self.db.remove_owners(owners, proj).await?;
+
+        // only those who were owners are told
+        let removed = owners.users.iter()
+            .filter(|u| before.users.contains(u))
+            .unique()
+            .cloned()
+            .collect::<Vec<_>>();
+
+        self.notify_users(proj, removed, |user, project|
+            Notification::OwnerRemoved { user, project }
+        ).await;
+
+        Ok(())
     }
 
     async fn user_is_owner(
@@ -97,29 +156,96 @@ where
         params: ProjectsParams
     ) -> Result<Projects, CoreError>
     {
-        let ProjectsParams { seek, limit } = params;
-        let (prev, next, projects, total) = self.get_projects_from(
-            seek, limit.unwrap_or_default()
+        let ProjectsParams {
+            seek,
+            limit,
+            with_position,
+            facet_counts,
+            issued_at
+        } = params;
+
+        if limit > Some(self.max_limit) {
+            return Err(CoreError::LimitOutOfRange);
+        }
+
+        // stale seeks must be refused, so that clients start paging over
+        let now = self.now_nanos()?;
+        let max_age = self.seek_max_age as i64 * NANOS_PER_SEC;
+
+        if max_age > 0 && issued_at.is_some_and(|t| now - t > max_age) {
+            return Err(CoreError::MalformedQuery);
+        }
+
+        let years_ok = seek.facets.iter().all(|f| match f {
+            Facet::Year(year) => self.non_numeric_years.filterable(year),
+            _ => true
+        });
+
+        if !years_ok {
+            return Err(CoreError::MalformedQuery);
+        }
+
+        let page_size = limit.unwrap_or(self.default_limit);
+
+        // counting tags is another query, so do it only when asked
+        let facets = match facet_counts {
+            true => Some(
+                FacetCounts {
+                    tags: self.db.get_tag_facet_counts(&seek.facets)
+                        .await?
+                        .into_iter()
+                        .collect()
+                }
+            ),
+            false => None
+        };
+
+        let (prev, next, projects, total, approximate) = self.get_projects_from(
+            seek, page_size
         ).await?;
 
+        // the prev link is anchored on the first item of this page
+        let position = match prev {
+            _ if !with_position => None,
+            Some(ref prev) => Some(self.get_projects_position(prev).await?),
+            None if projects.is_empty() => None,
+            None => Some(0)
+        };
+
+        let make_link = |seek: &Seek| -> Result<SeekLink, CoreError> {
+            let link = match max_age {
+                0 => SeekLink::new(seek, limit)?,
+                _ => SeekLink::new_issued(seek, limit, now)?
+            };
+            let link = if with_position { link.with_position() } else { link };
+            Ok(if facet_counts { link.with_facet_counts() } else { link })
+        };
+
         let prev_page = match prev {
-            Some(prev) => Some(SeekLink::new(&prev, limit)?),
+            Some(prev) => Some(make_link(&prev)?),
             None => None
         };
 
         let next_page = match next {
-            Some(next) => Some(SeekLink::new(&next, limit)?),
+            Some(next) => Some(make_link(&next)?),
             None => None
         };
 
+        let page_size = page_size.get() as i64;
+        let total_pages = (total + page_size - 1) / page_size;
+
         Ok(
             Projects {
                 projects,
                 meta: Pagination {
                     prev_page,
                     next_page,
-                    total
-                }
+                    total,
+                    total_pages,
+                    approximate,
+                    position
+                },
+                facets
             },
         )
     }
@@ -132,35 +258,241 @@ where
         self.get_project_impl(
             proj,
             self.db.get_project_row(proj).await?,
+            self.db.get_tags(proj).await?,
             self.db.get_packages(proj).await?,
             |pc, pkg| pc.db.get_releases(pkg),
             |pc, pkg| pc.db.get_files(pkg)
         ).await
     }
 
+    async fn record_project_view(
+        &self,
+        proj: Project
+    )
+    {
+        self.record_view(proj);
+    }
+
 // TODO: length limits on strings
 // TODO: packages might need display names?
 
+    async fn get_popular_projects(
+        &self,
+        limit: Option<Limit>
+    ) -> Result<PopularProjects, CoreError>
+    {
+        if limit > Some(self.max_limit) {
+            return Err(CoreError::LimitOutOfRange);
+        }
+
+        let limit = limit.unwrap_or(self.default_limit);
+
+        Ok(
+            PopularProjects {
+                projects: self.db.get_popular_projects(limit.get() as u32)
+                    .await?
+                    .into_iter()
+                    .map(ProjectSummary::from)
+                    .collect()
+            }
+        )
+    }
+
+    async fn get_projects_by_name(
+        &self,
+        names: &[String]
+    ) -> Result<ProjectsByName, CoreError>
+    {
+        if names.is_empty() {
+            return Ok(ProjectsByName::default());
+        }
+
+        Ok(
+            ProjectsByName {
+                projects: self.db.get_project_summary_rows_by_name(names)
+                    .await?
+                    .into_iter()
+                    .map(|r| {
+                        let s = ProjectSummary::from(r);
+                        (s.name.clone(), s)
+                    })
+                    .collect()
+            }
+        )
+    }
+
+    async fn get_recent_projects(
+        &self,
+        limit: Option<Limit>
+    ) -> Result<RecentProjects, CoreError>
+    {
+        // this is a shortcut for home pages, so keep it small
+        let limit = limit.unwrap_or(self.default_limit)
+            .min(RECENT_PROJECTS_MAX_LIMIT);
+
+        let (_, _, projects, ..) = self.get_projects_from(
+            Seek {
+                sort_by: SortBy::ModificationTime,
+                dir: Direction::Descending,
+                anchor: Anchor::Start,
+                facets: vec![]
+            },
+            limit
+        ).await?;
+
+        Ok(RecentProjects { projects })
+    }
+
+    async fn get_random_project(
+        &self
+    ) -> Result<ProjectData, CoreError>
+    {
+        let proj = self.db.get_random_project_id().await?;
+        self.get_project(proj).await
+    }
+
+    async fn get_releases(
+        &self,
+        _proj: Project,
+        pkg: Package,
+        params: ReleasesParams
+    ) -> Result<Releases, CoreError>
+    {
+        if params.limit > Some(self.max_limit) {
+            return Err(CoreError::LimitOutOfRange);
+        }
+
+        let page_size = params.limit.unwrap_or(self.default_limit);
+        let limit = page_size.get() as usize;
+
+        // get one extra so we can tell if there is another page
+        let mut rows = self.db.get_releases_window(
+            pkg,
+            &params.anchor,
+            limit as u32 + 1
+        ).await?;
+
+        let (has_prev, has_next) = match params.anchor {
+            ReleaseAnchor::Start => {
+                let more = rows.len() > limit;
+                rows.truncate(limit);
+                (false, more)
+            },
+            ReleaseAnchor::After(_) => {
+                let more = rows.len() > limit;
+                rows.truncate(limit);
+                (true, more)
+            },
+            ReleaseAnchor::Before(_) => {
+                let more = rows.len() > limit;
+                if more {
+                    rows.remove(0);
+                }
+                (more, true)
+            }
+        };
+
+        let make_link = |anchor: ReleaseAnchor| {
+            SeekLink::from_release_anchor(&anchor, params.limit)
+        };
+
+        let prev_page = rows.first()
+            .filter(|_| has_prev)
+            .map(|r| make_link(ReleaseAnchor::Before(Version::from(r))));
+
+        let next_page = rows.last()
+            .filter(|_| has_next)
+            .map(|r| make_link(ReleaseAnchor::After(Version::from(r))));
+
+        let file_rows = self.db.get_files(pkg).await?;
+
+        let releases = try_join_all(
+            rows.into_iter()
+                .map(|vr| self.make_release_data(vr, &file_rows))
+        ).await?;
+
+        let total = self.db.get_releases_count(pkg).await?;
+        let page_size = page_size.get() as i64;
+        let total_pages = (total + page_size - 1) / page_size;
+
+        Ok(
+            Releases {
+                releases,
+                meta: Pagination {
+                    prev_page,
+                    next_page,
+                    total,
+                    total_pages,
+                    approximate: false,
+                    position: None
+                }
+            }
+        )
+    }
+
     async fn create_project(
         &self,
         user: User,
         proj: &str,
         proj_data: &ProjectDataPost
-    ) -> Result<(), CoreError>
+    ) -> Result<Project, CoreError>
     {
+        check_new_project_name(proj)?;
+
+        let proj_data = ProjectDataPost {
+            tags: normalize_tags(&proj_data.tags),
+            packages: check_project_packages(&proj_data.packages)?,
+            ..proj_data.clone()
+        };
+
         let now = self.now_nanos()?;
-        self.db.create_project(user, proj, proj_data, now).await
+        self.db.create_project(user, proj, &proj_data, now).await
     }
 
     async fn update_project(
         &self,
         owner: Owner,
         proj: Project,
-        proj_data: &ProjectDataPatch
+        proj_data: &ProjectDataPatch,
+        expected_revision: Option<i64>
+    ) -> Result<ProjectDataPatchResult, CoreError>
+    {
+        if let Some(name) = &proj_data.name {
+            check_new_project_name(name)?;
+        }
+
+        // the cover image must exist and not have been removed
+        if let Some(Some(image)) = &proj_data.image {
+            self.db.get_image_url(proj, image).await?;
+        }
+
+        let superseded_by = match &proj_data.superseded_by {
+            Some(Some(target)) => Some(Some(
+                self.check_superseded_by(proj, target).await?
+            )),
+            sb => sb.clone()
+        };
+
+        let proj_data = ProjectDataPatch {
+            tags: proj_data.tags.as_deref().map(normalize_tags),
+            superseded_by,
+            ..proj_data.clone()
+        };
+
+        let now = self.now_nanos()?;
+        self.db.update_project(owner, proj, &proj_data, expected_revision, now)
+            .await
+    }
+
+    async fn delete_project(
+        &self,
+        owner: Owner,
+        proj: Project,
+        force: bool
     ) -> Result<(), CoreError>
     {
         let now = self.now_nanos()?;
-        self.db.update_project(owner, proj, proj_data, now).await
+        self.db.delete_project(owner, proj, force, now).await
     }
 
     async fn get_project_revision(
@@ -173,17 +505,90 @@ where
             .await?;
         let mtime = proj_row.modified_at;
 
+        let tags = self.db.get_tags_at(proj, revision).await?;
         let package_rows = self.db.get_packages_at(proj, mtime).await?;
 
         self.get_project_impl(
             proj,
             proj_row,
+            tags,
             package_rows,
             |pc, pkg| pc.db.get_releases_at(pkg, mtime),
             |pc, pkg| pc.db.get_files_at(pkg, mtime)
         ).await
     }
 
+    async fn get_project_revision_batch(
+        &self,
+        proj: Project,
+        revisions: &[i64]
+    ) -> Result<ProjectRevisionBatch, CoreError>
+    {
+        if revisions.len() > MAX_REVISION_BATCH {
+            return Err(CoreError::LimitOutOfRange);
+        }
+
+        // make sure the project exists, as the revisions might not
+        self.db.get_project_row(proj).await?;
+
+        // revisions which don't exist are skipped, as are repeats
+        let mut batch = vec![];
+        for (i, &r) in revisions.iter().enumerate() {
+            if revisions[..i].contains(&r) {
+                continue;
+            }
+
+            match self.get_project_revision(proj, r).await {
+                Ok(pd) => batch.push(pd),
+                Err(CoreError::NotARevision) => {},
+                Err(e) => return Err(e)
+            }
+        }
+
+        Ok(ProjectRevisionBatch { revisions: batch })
+    }
+
+    async fn get_project_diff(
+        &self,
+        proj: Project,
+        from: i64,
+        to: i64
+    ) -> Result<ProjectDiff, CoreError>
+    {
+        if from == to {
+            return Err(CoreError::MalformedQuery);
+        }
+
+        // a diff against a nonexistent revision is a bad request
+        let get_revision = |r| async move {
+            match self.get_project_revision(proj, r).await {
+                Err(CoreError::NotARevision) => Err(CoreError::MalformedQuery),
+                r => r
+            }
+        };
+
+        let from = get_revision(from).await?;
+        let to = get_revision(to).await?;
+
+        project_diff(&from, &to)
+    }
+
+    async fn get_project_revisions(
+        &self,
+        proj: Project
+    ) -> Result<ProjectRevisions, CoreError>
+    {
+        Ok(
+            ProjectRevisions {
+                revisions: self.db.get_project_revisions(proj)
+                    .await?
+                    .into_iter()
+                    .map(ProjectRevisionSummary::try_from)
+                    .collect::<Result<Vec<_>, _>>()?
+            }
+        )
+    }
+
     async fn create_package(
         &self,
         owner: Owner,
@@ -192,1877 +597,7419 @@ where
         pkg_data: &PackageDataPost
     ) -> Result<(), CoreError>
     {
+        check_package_name(pkg)?;
+        self.require_not_archived(proj).await?;
+
+        let pkg_data = PackageDataPost {
+            description: pkg_data.description.clone(),
+            section: normalize_section(pkg_data.section.as_deref())?
+        };
+
         let now = self.now_nanos()?;
-        self.db.create_package(owner, proj, pkg, pkg_data, now).await
+        self.db.create_package(owner, proj, pkg, &pkg_data, now).await
     }
 
-    async fn get_release(
+    async fn update_package(
         &self,
-        _proj: Project,
-        pkg: Package
-    ) -> Result<String, CoreError>
+        owner: Owner,
+        proj: Project,
+        pkg: Package,
+        pkg_data: &PackageDataPatch
+    ) -> Result<(), CoreError>
     {
-        self.db.get_release_url(pkg).await
+        if let Some(name) = &pkg_data.name {
+            check_package_name(name)?;
+        }
+
+        self.require_not_archived(proj).await?;
+
+        let pkg_data = PackageDataPatch {
+            name: pkg_data.name.clone(),
+            description: pkg_data.description.clone(),
+            section: match &pkg_data.section {
+                Some(section) => Some(normalize_section(section.as_deref())?),
+                None => None
+            }
+        };
+
+        let now = self.now_nanos()?;
+        self.db.update_package(owner, proj, pkg, &pkg_data, now).await
     }
 
-    async fn get_release_version(
+    async fn delete_package(
         &self,
-        _proj: Project,
+        owner: Owner,
+        proj: Project,
         pkg: Package,
-        version: &Version
-    ) -> Result<String, CoreError>
+        force: bool
+    ) -> Result<(), CoreError>
     {
-        self.db.get_release_version_url(pkg, version).await
+        self.require_not_archived(proj).await?;
+        let now = self.now_nanos()?;
+        self.db.delete_package(owner, proj, pkg, force, now).await
     }
 
-    async fn get_players(
+    async fn reorder_packages(
         &self,
-        proj: Project
-    ) -> Result<Users, CoreError>
+        owner: Owner,
+        proj: Project,
+        order: &[String]
+    ) -> Result<(), CoreError>
     {
-        self.db.get_players(proj).await
+        self.require_not_archived(proj).await?;
+        let now = self.now_nanos()?;
+        self.db.reorder_packages(owner, proj, order, now).await
     }
 
-    async fn add_player(
+    async fn delete_release(
         &self,
-        player: User,
-        proj: Project
+        owner: Owner,
+        proj: Project,
+        pkg: Package,
+        version: &Version
     ) -> Result<(), CoreError>
     {
-        self.db.add_player(player, proj).await
+        self.require_not_archived(proj).await?;
+        let now = self.now_nanos()?;
+        self.db.delete_release(owner, proj, pkg, version, now).await
     }
 
-    async fn remove_player(
+    async fn get_package(
         &self,
-        player: User,
-        proj: Project
-    ) -> Result<(), CoreError>
+        proj: Project,
+        pkg: Package
+    ) -> Result<PackageData, CoreError>
     {
-        self.db.remove_player(player, proj).await
+        let row = self.db.get_packages(proj)
+            .await?
+            .into_iter()
+            .find(|r| r.package_id == pkg.0)
+            .ok_or(CoreError::NotAPackage)?;
+
+        self.make_package_data(
+            row,
+            &|pc, pkg| pc.db.get_releases(pkg),
+            &|pc, pkg| pc.db.get_files(pkg)
+        ).await
     }
 
-    async fn get_image(
+    async fn get_release(
         &self,
-        proj: Project,
-        img_name: &str
+        _proj: Project,
+        pkg: Package
     ) -> Result<String, CoreError>
     {
-        self.db.get_image_url(proj, img_name).await
+        let row = self.db.get_release_url(pkg).await?;
+        self.record_download(pkg, row.version, row.filename);
+        Ok(row.url)
     }
 
-    async fn get_image_revision(
+    async fn get_latest_release_url(
+        &self,
+        _proj: Project,
+        pkg: Package
+    ) -> Result<String, CoreError>
+    {
+        // releases are in Version order, newest first, so a prerelease
+        // is latest only if no release of that version exists
+        let release = self.db.get_releases(pkg)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or(CoreError::NotFound)?;
+
+        self.release_file_url(pkg, release).await
+    }
+
+    async fn get_recommended_release_url(
         &self,
         proj: Project,
-        revision: i64,
-        img_name: &str
+        pkg: Package
     ) -> Result<String, CoreError>
     {
-        let proj_row = self.db.get_project_row_revision(proj, revision).await?;
-        let mtime = proj_row.modified_at;
-        self.db.get_image_url_at(proj, img_name, mtime).await
+        let recommended = self.db.get_packages(proj)
+            .await?
+            .into_iter()
+            .find(|pr| pr.package_id == pkg.0)
+            .ok_or(CoreError::NotAPackage)?
+            .recommended;
+
+        // with no recommendation, the latest release is recommended
+        let Some(version) = recommended else {
+            return self.get_latest_release_url(proj, pkg).await;
+        };
+
+        let release = self.db.get_releases(pkg)
+            .await?
+            .into_iter()
+            .find(|r| r.version == version)
+            .ok_or(CoreError::NotFound)?;
+
+        self.release_file_url(pkg, release).await
     }
 
-// TODO: tests
-    async fn add_image(
+    async fn set_recommended_release(
         &self,
         owner: Owner,
         proj: Project,
-        img_name: &str,
-        content_type: &Mime,
-        content_length: Option<u64>,
-        stream: Box<dyn Stream<Item = Result<Bytes, io::Error>> + Send>
+        pkg: Package,
+        version: Option<&Version>
     ) -> Result<(), CoreError>
     {
-        // santiy checks
-        if !image_mime_type_ok(content_type) {
-          return Err(CoreError::BadMimeType);
-        }
+        self.require_not_archived(proj).await?;
+        let now = self.now_nanos()?;
+        self.db.set_recommended_release(owner, proj, pkg, version, now).await
+    }
 
-        if content_length > Some(self.max_image_size) {
-          return Err(CoreError::TooLarge);
-        }
-
-        let now = self.now_nanos()?;
+    async fn get_release_version(
+        &self,
+        _proj: Project,
+        pkg: Package,
+        version: &Version
+    ) -> Result<String, CoreError>
+    {
+        let row = self.db.get_release_version_url(pkg, version).await?;
+        self.record_download(pkg, row.version, row.filename);
+        Ok(row.url)
+    }
 
-        // write file
-        let url = self.uploader.upload(img_name, Box::into_pin(stream))
-            .await
-            .or(Err(CoreError::InternalError))?;
+    async fn get_release_data(
+        &self,
+        _proj: Project,
+        pkg: Package,
+        version: &Version
+    ) -> Result<ReleaseData, CoreError>
+    {
+        let vr = self.db.get_releases(pkg)
+            .await?
+            .into_iter()
+            .find(|r| Version::from(r) == *version)
+            .ok_or(CoreError::NotAVersion)?;
 
-        // update record
-        self.db.add_image_url(owner, proj, img_name, &url, now).await?;
+        let file_rows = self.db.get_files(pkg).await?;
 
-        Ok(())
+        self.make_release_data(vr, &file_rows).await
     }
-}
 
-fn image_mime_type_ok(mime: &Mime) -> bool {
-    mime == &mime::IMAGE_PNG ||
-    mime == &mime::IMAGE_GIF ||
-    mime == &mime::IMAGE_JPEG ||
-    mime == &mime::IMAGE_SVG ||
-    (
-        mime.type_() == mime::IMAGE && (
-            mime.subtype() == "avif" ||
-            mime.subtype() == "webp"
+    async fn get_malformed_years(
+        &self
+    ) -> Result<MalformedYears, CoreError>
+    {
+        Ok(
+            MalformedYears {
+                projects: self.db.get_project_years()
+                    .await?
+                    .into_iter()
+                    .filter(|r| is_malformed_year(&r.game_year))
+                    .map(|r| MalformedYear {
+                        project: r.name,
+                        year: r.game_year
+                    })
+                    .collect()
+            }
         )
-    )
-}
+    }
 
-impl<C, U> ProdCore<C, U>
-where
-    C: DatabaseClient + Send + Sync,
-    U: Uploader + Send + Sync
-{
-    fn now_nanos(&self) -> Result<i64, CoreError> {
-        (self.now)()
-            .timestamp_nanos_opt()
-            .ok_or(CoreError::InternalError)
+    async fn correct_year(
+        &self,
+        admin: User,
+        proj: Project,
+        year: &str
+    ) -> Result<(), CoreError>
+    {
+        // a correction must not leave the year malformed or unknown
+        if !is_plausible_year(year) {
+            return Err(CoreError::InvalidYear);
+        }
+
+        let proj_data = ProjectDataPatch {
+            game: GameDataPatch {
+                year: Some(year.into()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // the correction is a new revision, made by the admin
+        let now = self.now_nanos()?;
+        self.db.update_project(Owner(admin.0), proj, &proj_data, None, now)
+            .await?;
+        Ok(())
     }
 
-    async fn make_version_data(
+    async fn get_forum_user_summary(
         &self,
-        r: FileRow
-    ) -> Result<FileData, CoreError>
+        user: User
+    ) -> Result<ForumUserSummary, CoreError>
     {
-        let authors = self.db.get_authors(r.id)
+        let username = self.db.get_username(user).await?;
+
+        let owned = self.db.get_owned_projects(user).await?;
+        let owned_projects = owned.len();
+
+        let projects = owned.into_iter()
+            .take(FORUM_SUMMARY_MAX_ITEMS)
+            .map(|r| ForumProject {
+                modified_at: summary_timestamp(r.project_id, r.modified_at),
+                name: r.name,
+                title: r.game_title,
+                description: r.description,
+                image: r.image
+            })
+            .collect();
+
+        let recent_releases = self.db.get_user_releases(
+            user,
+            FORUM_SUMMARY_MAX_ITEMS as u32
+        )
             .await?
-            .users;
+            .into_iter()
+            .map(|r| Ok(
+                ForumRelease {
+                    project: r.project,
+                    package: r.package,
+                    version: r.version,
+                    published_at: nanos_to_rfc3339(r.published_at)?
+                }
+            ))
+            .collect::<Result<Vec<_>, CoreError>>()?;
 
         Ok(
-            FileData {
-                version: r.version,
-                filename: r.filename,
-                url: r.url,
-                size: r.size,
-                checksum: r.checksum,
-                published_at: nanos_to_rfc3339(r.published_at)?,
-                published_by: r.published_by,
-                requires: "".into(),
-                authors
+            ForumUserSummary {
+                version: FORUM_SUMMARY_VERSION,
+                username,
+                owned_projects,
+                recent_releases,
+                projects
             }
         )
     }
 
-    async fn make_package_data<'s, RF, RR, FF, FR>(
-        &'s self,
-        pr: PackageRow,
-        get_release_rows: &RF,
-        get_files_rows: &FF
-    ) -> Result<PackageData, CoreError>
-    where
-        RF: Fn(&'s Self, Package) -> RR,
-        RR: Future<Output = Result<Vec<FileRow>, CoreError>>,
-        FF: Fn(&'s Self, Package) -> FR,
-        FR: Future<Output = Result<Vec<FileRow>, CoreError>>
+    async fn get_user_profile(
+        &self,
+        user: User
+    ) -> Result<UserProfile, CoreError>
     {
-        let releases = try_join_all(
-            get_release_rows(self, Package(pr.package_id))
-                .await?
-                .into_iter()
-                .map(|vr| self.make_version_data(vr))
-        ).await?;
-
-        let files = try_join_all(
-            get_files_rows(self, Package(pr.package_id))
-                .await?
-                .into_iter()
-                .map(|vr| self.make_version_data(vr))
-        ).await?;
+        let row = self.db.get_user_profile_row(user).await?;
 
         Ok(
-            PackageData {
-                name: pr.name,
-                description: "".into(),
-                releases,
-                files
+            UserProfile {
+                username: row.username,
+                owned_projects: row.owned_projects,
+                played_projects: row.played_projects
             }
         )
     }
 
-    async fn get_project_impl<'s, RF, RR, FF, FR>(
-        &'s self,
-        proj: Project,
-        proj_row: ProjectRow,
-        package_rows: Vec<PackageRow>,
-        get_release_rows: RF,
-        get_file_rows: FF,
-    ) -> Result<ProjectData, CoreError>
-    where
-        RF: Fn(&'s Self, Package) -> RR,
-        RR: Future<Output = Result<Vec<FileRow>, CoreError>>,
-        FF: Fn(&'s Self, Package) -> FR,
-        FR: Future<Output = Result<Vec<FileRow>, CoreError>>
+    async fn get_project_stats(
+        &self,
+        proj: Project
+    ) -> Result<ProjectStats, CoreError>
     {
-        let owners = self.get_owners(proj)
-            .await?
-            .users;
+        let row = self.db.get_project_stats(proj).await?;
 
-        let packages = try_join_all(
-            package_rows
-                .into_iter()
-                .map(|pr| self.make_package_data(
-                    pr,
-                    &get_release_rows,
-                    &get_file_rows
-                ))
-        ).await?;
+        let now = self.now_nanos()?;
+        let age_days = (now - row.created_at).max(0) / NANOS_PER_DAY;
+
+        // days without views or downloads are absent from the rows
+        let today = now / NANOS_PER_DAY;
+        let since = today - (STATS_DAYS - 1);
+        let mut rows = self.db.get_project_daily_stats(proj, since)
+            .await?
+            .into_iter()
+            .peekable();
+
+        let daily = (since..=today)
+            .map(|day| {
+                let (views, downloads) = rows.next_if(|r| r.day == day)
+                    .map_or((0, 0), |r| (r.views, r.downloads));
+
+                Ok(
+                    DailyStats {
+                        date: day_to_date(day)?,
+                        views,
+                        downloads
+                    }
+                )
+            })
+            .collect::<Result<Vec<_>, CoreError>>()?;
 
         Ok(
-            ProjectData {
-                name: proj_row.name,
-                description: proj_row.description,
-                revision: proj_row.revision,
-                created_at: nanos_to_rfc3339(proj_row.created_at)?,
-                modified_at: nanos_to_rfc3339(proj_row.modified_at)?,
-                tags: vec![],
-                game: GameData {
-                    title: proj_row.game_title,
-                    title_sort_key: proj_row.game_title_sort,
-                    publisher: proj_row.game_publisher,
-                    year: proj_row.game_year
-                },
-                readme: proj_row.readme,
-                image: proj_row.image,
-                owners,
-                packages
+            ProjectStats {
+                files: row.files,
+                bytes: row.bytes,
+                releases: row.releases,
+                packages: row.packages,
+                players: row.players,
+                owners: row.owners,
+                flags: row.flags,
+                age_days,
+                views: row.views,
+                downloads: row.downloads,
+                daily
             }
         )
     }
 
-    async fn get_projects_window(
+    async fn get_project_status(
         &self,
-        anchor: &Anchor,
-        sort_by: SortBy,
-        dir: Direction,
-        limit_extra: u32
-    ) -> Result<Vec<ProjectSummaryRow>, CoreError>
+        proj: Project
+    ) -> Result<ProjectStatus, CoreError>
     {
-        match anchor {
-            Anchor::Start =>
-                self.db.get_projects_end_window(
-                    sort_by,
-                    dir,
-                    limit_extra
-                ),
-            Anchor::After(field, id) =>
-                self.db.get_projects_mid_window(
-                    sort_by,
-                    dir,
-                    field,
-                    *id,
-                    limit_extra
-                ),
-            Anchor::Before(field, id) =>
-                self.db.get_projects_mid_window(
-                    sort_by,
-                    dir.rev(),
-                    field,
-                    *id,
-                    limit_extra
-                ),
-            Anchor::StartQuery(query) =>
-                self.db.get_projects_query_end_window(
-                    query,
-                    sort_by,
-                    dir,
-                    limit_extra
-                ),
-            Anchor::AfterQuery(query, field, id) =>
-                self.db.get_projects_query_mid_window(
-                    query,
-                    sort_by,
-                    dir,
-                    field,
-                    *id,
-                    limit_extra
-                ),
-            Anchor::BeforeQuery(query, field, id) =>
-                self.db.get_projects_query_mid_window(
-                    query,
-                    sort_by,
-                    dir.rev(),
-                    field,
-                    *id,
-                    limit_extra
-                )
-        }.await
+        let row = self.db.get_project_status(proj).await?;
+
+        Ok(
+            ProjectStatus {
+                open_flags: row.open_flags,
+                archived: row.archived,
+                bytes: row.bytes
+            }
+        )
     }
 
-    async fn get_projects_from(
+    async fn get_project_readme(
         &self,
-        seek: Seek,
-        limit: Limit
-    ) -> Result<(Option<Seek>, Option<Seek>, Vec<ProjectSummary>, i64), CoreError>
+        proj: Project,
+        format: ReadmeFormat
+    ) -> Result<String, CoreError>
     {
-        // unpack the seek
-        let Seek { sort_by, dir, anchor } = seek;
+        let row = self.db.get_project_row(proj).await?;
 
-        // try to get one extra so we can tell if we're at an endpoint
-        let limit_extra = limit.get() as u32 + 1;
+        match format {
+            ReadmeFormat::Markdown => Ok(row.readme),
+            ReadmeFormat::Html => {
+                if let Some(html) = self.readme_cache.get(proj, row.revision) {
+                    return Ok(html.to_string());
+                }
 
-        // get the window
-        let mut projects = self.get_projects_window(
-            &anchor,
-            sort_by,
-            dir,
-            limit_extra
-        ).await?;
+                let html: Arc<str> = render_html(&row.readme).into();
+                self.readme_cache.insert(proj, row.revision, html.clone());
+                Ok(html.to_string())
+            }
+        }
+    }
 
-        // get the prev, next links
-        let (prev, next) = get_links(
-            &anchor,
-            sort_by,
-            dir,
-            limit_extra,
-            &mut projects
-        )?;
+    async fn get_library_stats(
+        &self
+    ) -> Result<LibraryStats, CoreError>
+    {
+        self.db.get_library_stats().await
+    }
 
-        // get the total number of responsive items
-        let total = match anchor {
-            Anchor::StartQuery(ref q) |
-            Anchor::AfterQuery(ref q, ..) |
-            Anchor::BeforeQuery(ref q, ..) =>
-                self.db.get_projects_query_count(q),
-            _ => self.db.get_projects_count()
-        }.await?;
+    async fn get_release_feed(
+        &self,
+        proj: Project
+    ) -> Result<ReleaseFeed, CoreError>
+    {
+        let proj_row = self.db.get_project_row(proj).await?;
 
-        // convert the rows to summaries
-        let pi = projects.into_iter().map(ProjectSummary::try_from);
-        let psums = match anchor {
-            Anchor::Before(..) |
-            Anchor::BeforeQuery(..) => pi.rev().collect::<Result<Vec<_>, _>>(),
-            _ => pi.collect::<Result<Vec<_>, _>>()
-        }?;
+        let mut releases = vec![];
+        for pr in self.db.get_packages(proj).await? {
+            let pkg = Package(pr.package_id);
+            let file_rows = self.db.get_files(pkg).await?;
 
-        Ok((prev, next, psums, total))
-    }
-}
+            for vr in self.db.get_releases(pkg).await? {
+                let published_at = vr.published_at;
+                let release = self.make_release_data(vr, &file_rows).await?;
+                releases.push((published_at, pr.name.clone(), release));
+            }
+        }
 
-fn check_new_project_name(projname: &str) -> Result<(), CoreError> {
-    // Require that project name matches ^[A-Za-z0-9][A-Za-z0-9_-]{0,63}$
-    static PAT: Lazy<Regex> = Lazy::new(||
-        Regex::new("^[A-Za-z0-9][A-Za-z0-9_-]{0,63}$")
-            .expect("bad regex")
-    );
+        // newest first
+        releases.sort_by_key(|r| std::cmp::Reverse(r.0));
+
+        let entries = releases.into_iter()
+            .map(|(_, package, release)| ReleaseFeedEntry {
+                package,
+                version: release.version,
+                published_at: release.files[0].published_at.clone(),
+                published_by: release.files[0].published_by.clone(),
+                files: release.files
+            })
+            .collect::<Vec<_>>();
+
+        // the feed was last updated by its newest entry, if it has any
+        let updated = match entries.first() {
+            Some(e) => e.published_at.clone(),
+            None => nanos_to_rfc3339(proj_row.modified_at)?
+        };
 
-    if !PAT.is_match(projname) {
-        Err(CoreError::InvalidProjectName)
+        Ok(
+            ReleaseFeed {
+                project: proj_row.name,
+                title: proj_row.game_title,
+                updated,
+                entries
+            }
+        )
     }
-    else {
-        Ok(())
+
+    async fn set_primary_file(
+        &self,
+        owner: Owner,
+        proj: Project,
+        pkg: Package,
+        version: &Version,
+        filename: &str
+    ) -> Result<(), CoreError>
+    {
+        let now = self.now_nanos()?;
+        self.db.set_primary_file(owner, proj, pkg, version, filename, now)
+            .await
     }
-}
 
-fn get_prev_for_before(
-    anchor: &Anchor,
-    sort_by: SortBy,
-    dir: Direction,
-    limit_extra: u32,
-    projects: &mut Vec<ProjectSummaryRow>
-) -> Result<Option<Seek>, CoreError>
-{
-    // make the prev link
-    if projects.len() == limit_extra as usize {
-        // there are more pages in the forward direction
+    async fn get_players(
+        &self,
+        proj: Project
+    ) -> Result<Users, CoreError>
+    {
+        self.db.get_players(proj).await
+    }
 
-        // remove the "extra" item which proves we are not at the end
-        projects.pop();
+    async fn add_player(
+        &self,
+        player: User,
+        proj: Project
+    ) -> Result<(), CoreError>
+    {
+        self.db.add_player(player, proj).await
+    }
 
-        // the prev page is after the last item
-        let last = projects.last().expect("element must exist");
+    async fn remove_player(
+        &self,
+        player: User,
+        proj: Project
+    ) -> Result<(), CoreError>
+    {
+        self.db.remove_player(player, proj).await
+    }
 
-        let prev_anchor = match anchor {
-            Anchor::BeforeQuery(ref q, ..) => Anchor::BeforeQuery(
-                q.clone(),
-                last.sort_field(sort_by)?,
-                last.project_id as u32
-            ),
-            Anchor::Before(..) => Anchor::Before(
-                last.sort_field(sort_by)?,
-                last.project_id as u32
-            ),
-            Anchor::Start |
-            Anchor::StartQuery(..) |
-            Anchor::After(..) |
-            Anchor::AfterQuery(..) => unreachable!()
+    async fn subscribe(
+        &self,
+        player: User,
+        proj: Project
+    ) -> Result<(), CoreError>
+    {
+        self.db.set_subscribed(player, proj, true).await
+    }
+
+    async fn unsubscribe(
+        &self,
+        player: User,
+        proj: Project
+    ) -> Result<(), CoreError>
+    {
+        self.db.set_subscribed(player, proj, false).await
+    }
+
+    async fn get_image(
+        &self,
+        proj: Project,
+        img_name: &str
+    ) -> Result<String, CoreError>
+    {
+        self.db.get_image_url(proj, img_name).await
+    }
+
+    async fn get_image_revision(
+        &self,
+        proj: Project,
+        revision: i64,
+        img_name: &str
+    ) -> Result<String, CoreError>
+    {
+        let proj_row = self.db.get_project_row_revision(proj, revision).await?;
+        let mtime = proj_row.modified_at;
+        self.db.get_image_url_at(proj, img_name, mtime).await
+    }
+
+// TODO: tests
+    async fn add_image(
+        &self,
+        owner: Owner,
+        proj: Project,
+        img_name: &str,
+        content_type: &Mime,
+        content_length: Option<u64>,
+        stream: Box<dyn Stream<Item = Result<Bytes, io::Error>> + Send>
+    ) -> Result<(), CoreError>
+    {
+        self.store_image(
+            proj,
+            img_name,
+            content_type,
+            content_length,
+            stream,
+            |url, now| async move {
+                self.db.add_image_url(owner, proj, img_name, &url, now).await
+            }
+        ).await
+    }
+
+    async fn remove_image(
+        &self,
+        owner: Owner,
+        proj: Project,
+        img_name: &str
+    ) -> Result<(), CoreError>
+    {
+        self.require_not_archived(proj).await?;
+
+        let now = self.now_nanos()?;
+
+        // The stored file is kept, since earlier revisions of the project
+        // may still link to it.
+        self.db.remove_image(owner, proj, img_name, now).await
+    }
+
+    async fn add_gallery_image(
+        &self,
+        owner: Owner,
+        proj: Project,
+        img_name: &str,
+        content_type: &Mime,
+        content_length: Option<u64>,
+        stream: Box<dyn Stream<Item = Result<Bytes, io::Error>> + Send>
+    ) -> Result<GalleryImage, CoreError>
+    {
+        self.store_image(
+            proj,
+            img_name,
+            content_type,
+            content_length,
+            stream,
+            |url, now| async move {
+                self.db.add_gallery_image(owner, proj, img_name, &url, now).await
+            }
+        ).await
+    }
+
+    async fn add_file(
+        &self,
+        owner: Owner,
+        proj: Project,
+        pkg: Package,
+        version: &Version,
+        filename: &str,
+        data: Bytes
+    ) -> Result<FileData, CoreError>
+    {
+        // sanity checks
+        if data.len() as u64 > self.max_file_size {
+            return Err(CoreError::TooLarge(self.max_file_size));
+        }
+
+        require_filename(filename).or(Err(CoreError::InvalidFilename))?;
+
+        self.require_not_archived(proj).await?;
+
+        // modules must have the version of the release they belong to
+        if is_module(filename) {
+            let mversion = extract_version_from(io::Cursor::new(&data))
+                .or(Err(CoreError::InvalidModule))?;
+
+            if !module_version_matches(&mversion, version) {
+                return Err(
+                    CoreError::ModuleVersionMismatch {
+                        expected: version.into(),
+                        found: mversion
+                    }
+                );
+            }
+        }
+
+        let size = data.len() as i64;
+        let checksum = format!("{:x}", Sha256::digest(&data));
+        let now = self.now_nanos()?;
+
+        // write file
+        let path = storage_path(&checksum, filename, self.storage_prefix_depth);
+
+        let url = self.uploader.upload(
+            &path,
+            stream::once(async { Ok::<_, io::Error>(data) }),
+            self.max_file_size
+        )
+            .await
+            .map_err(|e| match e {
+                UploadError::IntegrityError => CoreError::IntegrityError,
+                _ => CoreError::InternalError
+            })?;
+
+        // update record
+        let recorded = self.db.add_file_url(
+            owner,
+            proj,
+            pkg,
+            version,
+            filename,
+            size,
+            &checksum,
+            &url,
+            now
+        ).await;
+
+        match recorded {
+            Ok(row) => {
+                let fd = self.make_file_data(row).await?;
+                self.notify_release(proj, pkg, version, &fd.published_by).await;
+                Ok(fd)
+            },
+            Err(e) => {
+                // don't leave behind a file which no record refers to
+                self.uploader.remove(&path)
+                    .await
+                    .or(Err(CoreError::InternalError))?;
+                Err(e)
+            }
+        }
+    }
+
+    async fn get_flags(
+        &self,
+        facets: &[Facet]
+    ) -> Result<Flags, CoreError>
+    {
+        Ok(
+            Flags {
+                flags: self.db.get_flags(facets)
+                    .await?
+                    .into_iter()
+                    .map(Flag::try_from)
+                    .collect::<Result<Vec<_>, _>>()?
+            }
+        )
+    }
+
+    async fn resolve_flag(
+        &self,
+        admin: User,
+        flag_id: i64
+    ) -> Result<(), CoreError>
+    {
+        let now = self.now_nanos()?;
+        self.db.resolve_flag(admin, flag_id, now).await?;
+
+        // the owners hear that the flag has been dealt with; the flag is
+        // resolved regardless of whether they do
+        match self.db.get_flag_project(flag_id).await {
+            Ok(proj) => match self.db.get_owners(proj).await {
+                Ok(owners) => self.notify_users(proj, owners.users, |user, project|
+                    Notification::FlagResolved { user, project }
+                ).await,
+                Err(e) => eprintln!("failed to notify owners of {}: {}", proj.0, e)
+            },
+            Err(e) => eprintln!("failed to notify owners for flag {flag_id}: {e}")
+        }
+
+        Ok(())
+    }
+
+    async fn get_inbox(
+        &self,
+        user: User,
+        params: InboxParams
+    ) -> Result<Inbox, CoreError>
+    {
+        if params.limit > Some(self.max_limit) {
+            return Err(CoreError::LimitOutOfRange);
+        }
+
+        let limit = params.limit.unwrap_or(self.default_limit).get() as u32;
+
+        let notifications = self.db.get_notifications(
+            user,
+            params.before,
+            limit
+        )
+            .await?
+            .into_iter()
+            .map(InboxItem::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // a full page may have more after it
+        let next_before = match notifications.len() == limit as usize {
+            true => notifications.last().map(|n| n.id),
+            false => None
         };
 
-        Ok(Some(Seek { anchor: prev_anchor, sort_by, dir }))
+        Ok(
+            Inbox {
+                notifications,
+                unread: self.db.get_unread_notifications_count(user).await?,
+                next_before
+            }
+        )
     }
-    else {
-        // there are no pages in the forward direction
-        Ok(None)
+
+    async fn mark_inbox_read(
+        &self,
+        user: User,
+        ids: Option<&[i64]>
+    ) -> Result<(), CoreError>
+    {
+        let now = self.now_nanos()?;
+        self.db.mark_notifications_read(user, ids, now).await
+    }
+
+    async fn recompute_slugs(
+        &self,
+        aliases: bool
+    ) -> Result<SlugRecompute, CoreError>
+    {
+        self.db.recompute_normalized_names(aliases).await
+    }
+
+    async fn get_bad_timestamps(
+        &self
+    ) -> Result<BadTimestamps, CoreError>
+    {
+        Ok(
+            BadTimestamps {
+                timestamps: self.db.get_timestamps()
+                    .await?
+                    .into_iter()
+                    .filter(|r| nanos_to_rfc3339(r.value).is_err())
+                    .map(|r| BadTimestamp {
+                        table: r.table_name,
+                        id: r.id,
+                        column: r.column_name,
+                        value: r.value
+                    })
+                    .collect()
+            }
+        )
+    }
+}
+
+fn is_module(filename: &str) -> bool {
+    std::path::Path::new(filename)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext|
+            ext.eq_ignore_ascii_case("vmod") || ext.eq_ignore_ascii_case("vmdx")
+        )
+}
+
+fn module_version_matches(mversion: &str, version: &Version) -> bool {
+    match mversion.parse::<Version>() {
+        Ok(mv) => &mv == version,
+        Err(_) => {
+            // module versions are often not semver, e.g. "1.2"; compare
+            // these numerically, treating missing components as zero
+            let parts = mversion.split('.')
+                .map(|p| p.parse::<i64>())
+                .collect::<Result<Vec<_>, _>>();
+
+            match parts {
+                Ok(p) if !p.is_empty() && p.len() <= 3 => {
+                    version.pre.is_none() &&
+                    version.build.is_none() &&
+                    p.into_iter()
+                        .chain(std::iter::repeat(0))
+                        .take(3)
+                        .eq([version.major, version.minor, version.patch])
+                },
+                _ => false
+            }
+        }
     }
 }
 
-fn get_next_for_before(
-    anchor: &Anchor,
-    sort_by: SortBy,
-    dir: Direction,
-    projects: &[ProjectSummaryRow]
-) -> Result<Option<Seek>, CoreError>
-{
-    // make the next link
-    if projects.is_empty() {
-        Ok(None)
+fn image_mime_type_ok(mime: &Mime) -> bool {
+    mime == &mime::IMAGE_PNG ||
+    mime == &mime::IMAGE_GIF ||
+    mime == &mime::IMAGE_JPEG ||
+    mime == &mime::IMAGE_SVG ||
+    (
+        mime.type_() == mime::IMAGE && (
+            mime.subtype() == "avif" ||
+            mime.subtype() == "webp"
+        )
+    )
+}
+
+impl<C, U> ProdCore<C, U>
+where
+    C: DatabaseClient + Clone + Send + Sync + 'static,
+    U: Uploader + Send + Sync
+{
+    async fn check_superseded_by(
+        &self,
+        proj: Project,
+        target: &str
+    ) -> Result<String, CoreError>
+    {
+        // the target must be an existing, visible project
+        let target = match self.db.get_project_id(target).await {
+            Ok(target) => target,
+            Err(CoreError::NotAProject) =>
+                return Err(CoreError::InvalidSupersession),
+            Err(e) => return Err(e)
+        };
+
+        let target_row = self.db.get_project_row(target).await?;
+        if target_row.archived {
+            return Err(CoreError::InvalidSupersession);
+        }
+
+        let links = self.db.get_supersessions().await?;
+        check_supersession(&links, proj.0, target.0)?;
+
+        // return the current name, in case we were given an old one
+        Ok(target_row.name)
+    }
+
+    async fn notify_users<F>(
+        &self,
+        proj: Project,
+        users: Vec<String>,
+        make: F
+    )
+    where
+        F: Fn(String, String) -> Notification + Send
+    {
+        if users.is_empty() {
+            return;
+        }
+
+        // the change has been made already, so failing to look up the
+        // project name only means that nobody hears about it
+        let project = match self.db.get_project_row(proj).await {
+            Ok(row) => row.name,
+            Err(e) => {
+                eprintln!("failed to notify users of {}: {}", proj.0, e);
+                return;
+            }
+        };
+
+        for user in users {
+            self.notifier.notify(make(user, project.clone()));
+        }
+    }
+
+    async fn notify_release(
+        &self,
+        proj: Project,
+        pkg: Package,
+        version: &Version,
+        publisher: &str
+    )
+    {
+        // only the first file of a version announces the release
+        let pre = version.pre.as_deref().unwrap_or("");
+        let build = version.build.as_deref().unwrap_or("");
+        match self.db.get_files(pkg).await {
+            Ok(files) if files.iter()
+                .filter(|f|
+                    f.version_major == version.major &&
+                    f.version_minor == version.minor &&
+                    f.version_patch == version.patch &&
+                    f.version_pre == pre &&
+                    f.version_build == build
+                )
+                .count() == 1 => {},
+            Ok(_) => return,
+            Err(e) => {
+                eprintln!("failed to notify subscribers of {}: {}", proj.0, e);
+                return;
+            }
+        }
+
+        let package = match self.db.get_packages(proj).await {
+            Ok(rows) => match rows.into_iter().find(|r| r.package_id == pkg.0) {
+                Some(row) => row.name,
+                None => return
+            },
+            Err(e) => {
+                eprintln!("failed to notify subscribers of {}: {}", proj.0, e);
+                return;
+            }
+        };
+
+        // the publisher knows already
+        let subscribers = match self.db.get_subscribers(proj).await {
+            Ok(subscribers) => subscribers.users.into_iter()
+                .filter(|u| u != publisher)
+                .collect::<Vec<_>>(),
+            Err(e) => {
+                eprintln!("failed to notify subscribers of {}: {}", proj.0, e);
+                return;
+            }
+        };
+
+        let version = String::from(version);
+        self.notify_users(proj, subscribers, |user, project|
+            Notification::ReleasePublished {
+                user,
+                project,
+                package: package.clone(),
+                version: version.clone()
+            }
+        ).await;
+    }
+
+    async fn require_not_archived(
+        &self,
+        proj: Project
+    ) -> Result<(), CoreError>
+    {
+        // archived projects are read-only until unarchived
+        match self.db.get_project_row(proj).await?.archived {
+            true => Err(CoreError::ProjectArchived),
+            false => Ok(())
+        }
+    }
+
+    // Counting is fire-and-forget, so that it can neither slow nor fail
+    // the request being counted
+    fn record_view(&self, proj: Project) {
+        if !self.record_usage {
+            return;
+        }
+
+        let Ok(now) = self.now_nanos() else { return };
+        let db = self.db.clone();
+
+        tokio::spawn(async move {
+            let day = now / NANOS_PER_DAY;
+            if let Err(e) = db.increment_project_views(proj, day).await {
+                eprintln!("failed to count view of project {}: {e}", proj.0);
+            }
+        });
+    }
+
+    // a release is downloaded as its primary file, if it has one
+    async fn release_file_url(
+        &self,
+        pkg: Package,
+        release: FileRow
+    ) -> Result<String, CoreError>
+    {
+        let files = self.db.get_files(pkg).await?;
+        let (filename, url) = primary_file_row(&release.version, &files)
+            .map_or(
+                (release.filename, release.url),
+                |f| (f.filename.clone(), f.url.clone())
+            );
+
+        self.record_download(pkg, release.version, filename);
+        Ok(url)
+    }
+
+    // downloads are counted per file, so the legacy release file and
+    // each file added to a release are tallied separately
+    fn record_download(&self, pkg: Package, version: String, filename: String) {
+        if !self.record_usage {
+            return;
+        }
+
+        let Ok(now) = self.now_nanos() else { return };
+        let db = self.db.clone();
+
+        tokio::spawn(async move {
+            let day = now / NANOS_PER_DAY;
+            if let Err(e) = db.increment_file_downloads(pkg, &version, &filename, day).await {
+                eprintln!("failed to count download of {filename}: {e}");
+            }
+        });
+    }
+
+    fn now_nanos(&self) -> Result<i64, CoreError> {
+        (self.now)()
+            .timestamp_nanos_opt()
+            .ok_or(CoreError::InternalError)
+    }
+
+    async fn make_version_data(
+        &self,
+        r: FileRow
+    ) -> Result<FileData, CoreError>
+    {
+        let authors = self.db.get_authors(r.id)
+            .await?
+            .users;
+
+        make_file_data_with(r, authors)
+    }
+
+    async fn make_file_data(
+        &self,
+        r: FileRow
+    ) -> Result<FileData, CoreError>
+    {
+        // files have no authors of their own; they are credited to the
+        // authors of the release with which they were uploaded
+        let authors = self.db.get_file_authors(r.id)
+            .await?
+            .users;
+
+        make_file_data_with(r, authors)
+    }
+
+    async fn make_release_data(
+        &self,
+        vr: FileRow,
+        file_rows: &[FileRow]
+    ) -> Result<ReleaseData, CoreError>
+    {
+        let files = try_join_all(
+            file_rows.iter()
+                .filter(|fr| fr.version == vr.version)
+                .cloned()
+                .map(|fr| self.make_file_data(fr))
+        ).await?;
+
+        let primary_file = primary_file(&vr.version, file_rows)
+            .map(String::from);
+
+        let release = FileData {
+            primary_file,
+            ..self.make_version_data(vr).await?
+        };
+
+        Ok(
+            ReleaseData {
+                version: release.version.clone(),
+                files: std::iter::once(release)
+                    .chain(files)
+                    .collect()
+            }
+        )
+    }
+
+    async fn make_package_data<'s, RF, RR, FF, FR>(
+        &'s self,
+        pr: PackageRow,
+        get_release_rows: &RF,
+        get_files_rows: &FF
+    ) -> Result<PackageData, CoreError>
+    where
+        RF: Fn(&'s Self, Package) -> RR,
+        RR: Future<Output = Result<Vec<FileRow>, CoreError>>,
+        FF: Fn(&'s Self, Package) -> FR,
+        FR: Future<Output = Result<Vec<FileRow>, CoreError>>
+    {
+        let file_rows = get_files_rows(self, Package(pr.package_id)).await?;
+
+        let releases = try_join_all(
+            get_release_rows(self, Package(pr.package_id))
+                .await?
+                .into_iter()
+                .map(|vr| async {
+                    let primary_file = primary_file(&vr.version, &file_rows)
+                        .map(String::from);
+
+                    Ok::<_, CoreError>(
+                        FileData {
+                            primary_file,
+                            ..self.make_version_data(vr).await?
+                        }
+                    )
+                })
+        ).await?;
+
+        let files = try_join_all(
+            file_rows
+                .into_iter()
+                .map(|fr| self.make_file_data(fr))
+        ).await?;
+
+        // the recommendation is not revisioned, so omit it from views
+        // which predate the recommended release
+        let recommended_release = pr.recommended
+            .filter(|v| releases.iter().any(|r| &r.version == v));
+
+        Ok(
+            PackageData {
+                name: pr.name,
+                description: pr.description,
+                section: pr.section,
+                releases,
+                files,
+                recommended_release
+            }
+        )
+    }
+
+    async fn store_image<F, R, T>(
+        &self,
+        proj: Project,
+        img_name: &str,
+        content_type: &Mime,
+        content_length: Option<u64>,
+        stream: Box<dyn Stream<Item = Result<Bytes, io::Error>> + Send>,
+        record: F
+    ) -> Result<T, CoreError>
+    where
+        F: FnOnce(String, i64) -> R,
+        R: Future<Output = Result<T, CoreError>>
+    {
+        // santiy checks
+        if !image_mime_type_ok(content_type) {
+          return Err(CoreError::BadMimeType);
+        }
+
+        if content_length > Some(self.max_image_size) {
+          return Err(CoreError::TooLarge(self.max_image_size));
+        }
+
+        self.require_not_archived(proj).await?;
+
+        let now = self.now_nanos()?;
+
+        fail_point!("add_image_before_upload");
+
+        // write file
+        let url = self.uploader.upload(
+            img_name,
+            Box::into_pin(stream),
+            self.max_image_size
+        )
+            .await
+            .map_err(|e| match e {
+                UploadError::TooLarge => CoreError::TooLarge(self.max_image_size),
+                UploadError::IntegrityError => CoreError::IntegrityError,
+                _ => CoreError::InternalError
+            })?;
+
+        // update record
+        let recorded = async {
+            fail_point!("add_image_after_upload");
+            record(url, now).await
+        }.await;
+
+        if recorded.is_err() {
+            // don't leave behind a file which no record refers to
+            self.uploader.remove(img_name)
+                .await
+                .or(Err(CoreError::InternalError))?;
+        }
+
+        recorded
+    }
+
+    async fn get_project_impl<'s, RF, RR, FF, FR>(
+        &'s self,
+        proj: Project,
+        proj_row: ProjectRow,
+        tags: Vec<String>,
+        package_rows: Vec<PackageRow>,
+        get_release_rows: RF,
+        get_file_rows: FF,
+    ) -> Result<ProjectData, CoreError>
+    where
+        RF: Fn(&'s Self, Package) -> RR,
+        RR: Future<Output = Result<Vec<FileRow>, CoreError>>,
+        FF: Fn(&'s Self, Package) -> FR,
+        FR: Future<Output = Result<Vec<FileRow>, CoreError>>
+    {
+        let owners = self.get_owners(proj)
+            .await?
+            .users;
+
+        let packages = try_join_all(
+            package_rows
+                .into_iter()
+                .map(|pr| self.make_package_data(
+                    pr,
+                    &get_release_rows,
+                    &get_file_rows
+                ))
+        ).await?;
+
+        // a deleted successor is treated as no successor
+        let superseded_by = match proj_row.superseded_by {
+            Some(id) => self.db.get_project_summary_row(Project(id))
+                .await?
+                .map(ProjectSummary::from),
+            None => None
+        };
+
+        let supersedes = self.db.get_supersedes(proj).await?;
+
+        let gallery = self.db.get_gallery(proj).await?;
+
+        Ok(
+            ProjectData {
+                name: proj_row.name,
+                description: proj_row.description,
+                revision: proj_row.revision,
+                created_at: nanos_to_rfc3339(proj_row.created_at)?,
+                modified_at: nanos_to_rfc3339(proj_row.modified_at)?,
+                tags,
+                game: GameData {
+                    title: proj_row.game_title,
+                    title_sort_key: proj_row.game_title_sort,
+                    publisher: proj_row.game_publisher,
+                    year: proj_row.game_year
+                },
+                readme: proj_row.readme,
+                image: proj_row.image,
+                archived: proj_row.archived,
+                superseded_by,
+                supersedes,
+                owners,
+                package_sections: package_sections(&packages),
+                packages,
+                modified_by: proj_row.modified_by,
+                gallery
+            }
+        )
+    }
+
+    async fn get_projects_window(
+        &self,
+        anchor: &Anchor,
+        sort_by: SortBy,
+        dir: Direction,
+        facets: &[Facet],
+        limit_extra: u32
+    ) -> Result<Vec<ProjectSummaryRow>, CoreError>
+    {
+        match anchor {
+            Anchor::Start =>
+                self.db.get_projects_end_window(
+                    sort_by,
+                    dir,
+                    facets,
+                    limit_extra
+                ),
+            Anchor::After(field, id) =>
+                self.db.get_projects_mid_window(
+                    sort_by,
+                    dir,
+                    field,
+                    *id,
+                    facets,
+                    limit_extra
+                ),
+            Anchor::Before(field, id) =>
+                self.db.get_projects_mid_window(
+                    sort_by,
+                    dir.rev(),
+                    field,
+                    *id,
+                    facets,
+                    limit_extra
+                ),
+            Anchor::StartQuery(query) =>
+                self.db.get_projects_query_end_window(
+                    query,
+                    sort_by,
+                    dir,
+                    facets,
+                    limit_extra
+                ),
+            Anchor::AfterQuery(query, field, id) =>
+                self.db.get_projects_query_mid_window(
+                    query,
+                    sort_by,
+                    dir,
+                    field,
+                    *id,
+                    facets,
+                    limit_extra
+                ),
+            Anchor::BeforeQuery(query, field, id) =>
+                self.db.get_projects_query_mid_window(
+                    query,
+                    sort_by,
+                    dir.rev(),
+                    field,
+                    *id,
+                    facets,
+                    limit_extra
+                )
+        }.await
+    }
+
+    async fn get_projects_position(
+        &self,
+        seek: &Seek
+    ) -> Result<i64, CoreError>
+    {
+        match seek.anchor {
+            Anchor::Before(ref field, id) =>
+                self.db.get_projects_position(
+                    seek.sort_by,
+                    seek.dir,
+                    field,
+                    id,
+                    &seek.facets
+                ),
+            Anchor::BeforeQuery(ref query, ref field, id) =>
+                self.db.get_projects_query_position(
+                    query,
+                    seek.sort_by,
+                    seek.dir,
+                    field,
+                    id,
+                    &seek.facets
+                ),
+            Anchor::Start |
+            Anchor::After(..) |
+            Anchor::StartQuery(..) |
+            Anchor::AfterQuery(..) => unreachable!()
+        }.await
+    }
+
+    async fn get_projects_from(
+        &self,
+        seek: Seek,
+        limit: Limit
+    ) -> Result<(Option<Seek>, Option<Seek>, Vec<ProjectSummary>, i64, bool), CoreError>
+    {
+        // unpack the seek
+        let Seek { sort_by, dir, anchor, facets } = seek;
+
+        // try to get one extra so we can tell if we're at an endpoint
+        let limit_extra = limit.get() as u32 + 1;
+
+        // get the window
+        let mut projects = self.get_projects_window(
+            &anchor,
+            sort_by,
+            dir,
+            &facets,
+            limit_extra
+        ).await?;
+
+        // get the prev, next links
+        let (prev, next) = get_links(
+            &anchor,
+            sort_by,
+            dir,
+            &facets,
+            limit_extra,
+            &mut projects
+        )?;
+
+        // get the total number of responsive items
+        let (total, approximate) = match anchor {
+            Anchor::StartQuery(ref q) |
+            Anchor::AfterQuery(ref q, ..) |
+            Anchor::BeforeQuery(ref q, ..) =>
+                (self.db.get_projects_query_count(q, &facets).await?, false),
+            _ if facets.is_empty() => self.get_unfiltered_projects_count()
+                .await?,
+            _ => (self.db.get_projects_count(&facets).await?, false)
+        };
+
+        // convert the rows to summaries
+        let pi = projects.into_iter().map(ProjectSummary::from);
+        let psums = match anchor {
+            Anchor::Before(..) |
+            Anchor::BeforeQuery(..) => pi.rev().collect(),
+            _ => pi.collect()
+        };
+
+        Ok((prev, next, psums, total, approximate))
+    }
+
+    async fn get_unfiltered_projects_count(
+        &self
+    ) -> Result<(i64, bool), CoreError>
+    {
+        // only unfiltered counts are cached, as there are too many
+        // combinations of facets for caching those to be worthwhile
+        let now = self.now_nanos()?;
+
+        if let Some(total) = self.count_cache.get(now) {
+            return Ok((total, true));
+        }
+
+        let total = self.db.get_projects_count(&[]).await?;
+        self.count_cache.set(now, total);
+        Ok((total, false))
+    }
+}
+
+fn primary_file_row<'r>(
+    version: &str,
+    files: &'r [FileRow]
+) -> Option<&'r FileRow>
+{
+    let candidates = files.iter().filter(|f| f.version == version);
+
+    // a file marked as primary wins; otherwise take the newest module
+    candidates.clone()
+        .find(|f| f.is_primary)
+        .or_else(||
+            candidates
+                .filter(|f| f.filename.ends_with(".vmod"))
+                .max_by_key(|f| f.published_at)
+        )
+}
+
+fn primary_file<'r>(version: &str, files: &'r [FileRow]) -> Option<&'r str> {
+    primary_file_row(version, files).map(|f| f.filename.as_str())
+}
+
+fn normalize_tags(tags: &[String]) -> Vec<String> {
+    // trim whitespace, drop empty tags, and remove duplicates
+    let mut tags = tags.iter()
+        .map(|t| t.trim())
+        .filter(|t| !t.is_empty())
+        .map(String::from)
+        .collect::<Vec<_>>();
+
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+fn project_diff(
+    from: &ProjectData,
+    to: &ProjectData
+) -> Result<ProjectDiff, CoreError>
+{
+    let fields = [
+        ("name", to_json(&from.name)?, to_json(&to.name)?),
+        ("description", to_json(&from.description)?, to_json(&to.description)?),
+        ("tags", to_json(&from.tags)?, to_json(&to.tags)?),
+        ("game.title", to_json(&from.game.title)?, to_json(&to.game.title)?),
+        (
+            "game.title_sort_key",
+            to_json(&from.game.title_sort_key)?,
+            to_json(&to.game.title_sort_key)?
+        ),
+        (
+            "game.publisher",
+            to_json(&from.game.publisher)?,
+            to_json(&to.game.publisher)?
+        ),
+        ("game.year", to_json(&from.game.year)?, to_json(&to.game.year)?),
+        ("readme", to_json(&from.readme)?, to_json(&to.readme)?),
+        ("image", to_json(&from.image)?, to_json(&to.image)?),
+        ("packages", to_json(&from.packages)?, to_json(&to.packages)?)
+    ];
+
+    Ok(
+        ProjectDiff {
+            changed_fields: fields.into_iter()
+                .filter(|(_, from, to)| from != to)
+                .map(|(field, from, to)| (field.into(), FieldChange { from, to }))
+                .collect()
+        }
+    )
+}
+
+fn make_file_data_with(
+    r: FileRow,
+    authors: Vec<String>
+) -> Result<FileData, CoreError>
+{
+    Ok(
+        FileData {
+            version: r.version,
+            filename: r.filename,
+            url: r.url,
+            size: r.size,
+            checksum: r.checksum,
+            published_at: nanos_to_rfc3339(r.published_at)?,
+            published_by: r.published_by,
+            requires: "".into(),
+            authors,
+            primary_file: None
+        }
+    )
+}
+
+fn to_json<T: Serialize>(v: &T) -> Result<serde_json::Value, CoreError> {
+    serde_json::to_value(v).or(Err(CoreError::InternalError))
+}
+
+const PACKAGE_SECTION_MAX_LENGTH: usize = 32;
+
+const PACKAGE_NAME_MAX_LENGTH: usize = 64;
+
+// names which are routes under /packages, so would hide a package
+const RESERVED_PACKAGE_NAMES: &[&str] = &["order"];
+
+const RECENT_PROJECTS_MAX_LIMIT: Limit = Limit::new(20).unwrap();
+
+const MAX_REVISION_BATCH: usize = 10;
+
+// the number of days, ending today, in the daily stats breakdown
+const STATS_DAYS: i64 = 30;
+
+// names which are routes under /projects, so would hide a project
+const RESERVED_PROJECT_NAMES: &[&str] = &["popular", "random", "recent"];
+
+fn normalize_section(section: Option<&str>) -> Result<Option<String>, CoreError> {
+    // trim whitespace; a blank section is no section
+    match section.map(str::trim) {
+        None | Some("") => Ok(None),
+        Some(s) if s.chars().count() > PACKAGE_SECTION_MAX_LENGTH =>
+            Err(CoreError::InvalidPackageSection),
+        Some(s) => Ok(Some(s.into()))
+    }
+}
+
+fn package_sections(packages: &[PackageData]) -> Vec<PackageSection> {
+    // sections are ordered by name, with unsectioned packages last;
+    // packages keep their order within each section
+    let mut sections: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    let mut unsectioned = vec![];
+
+    for pkg in packages {
+        match &pkg.section {
+            Some(section) => sections.entry(section)
+                .or_default()
+                .push(pkg.name.clone()),
+            None => unsectioned.push(pkg.name.clone())
+        }
+    }
+
+    sections.into_iter()
+        .map(|(name, package_slugs)| PackageSection {
+            name: Some(name.into()),
+            package_slugs
+        })
+        .chain(
+            (!unsectioned.is_empty()).then_some(PackageSection {
+                name: None,
+                package_slugs: unsectioned
+            })
+        )
+        .collect()
+}
+
+const MAX_SUPERSESSION_CHAIN: usize = 3;
+
+fn check_supersession(
+    links: &[SupersessionRow],
+    proj: i64,
+    target: i64
+) -> Result<(), CoreError>
+{
+    // the existing link from proj, if any, is the one being replaced
+    let next: HashMap<i64, i64> = links.iter()
+        .filter(|l| l.project_id != proj)
+        .map(|l| (l.project_id, l.superseded_by))
+        .collect();
+
+    // follow the chain forward from the target; reaching proj means the
+    // new link would close a cycle
+    let mut after = 0;
+    let mut cur = target;
+    loop {
+        if cur == proj || after >= MAX_SUPERSESSION_CHAIN {
+            return Err(CoreError::InvalidSupersession);
+        }
+
+        match next.get(&cur) {
+            Some(&n) => {
+                after += 1;
+                cur = n;
+            },
+            None => break
+        }
+    }
+
+    // find the longest chain of predecessors leading to proj
+    let mut before = 0;
+    let mut level = vec![proj];
+    while before + 1 + after <= MAX_SUPERSESSION_CHAIN {
+        level = next.iter()
+            .filter(|(_, s)| level.contains(s))
+            .map(|(&p, _)| p)
+            .collect();
+
+        if level.is_empty() {
+            return Ok(());
+        }
+
+        before += 1;
+    }
+
+    Err(CoreError::InvalidSupersession)
+}
+
+// Project names appear as path elements in URLs, so are restricted to
+// letters, digits, underscores, and hyphens, starting with a letter or
+// digit, and from 1 to 64 characters long. Dots are excluded, so no name
+// can be "." or "..". Letters need not be ASCII; names which differ only
+// in how their characters are composed are caught as collisions when the
+// project is stored, as normalization folds them together. Names which
+// are also routes under /projects are reserved.
+fn check_new_project_name(projname: &str) -> Result<(), CoreError> {
+    // combining marks may follow the first character, for decomposed
+    // accented letters
+    static PAT: Lazy<Regex> = Lazy::new(||
+        Regex::new(r"^[\p{L}\p{N}][\p{L}\p{M}\p{N}_-]{0,63}$")
+            .expect("bad regex")
+    );
+
+    let reserved = RESERVED_PROJECT_NAMES.iter()
+        .any(|r| r.eq_ignore_ascii_case(projname));
+
+    if reserved || !PAT.is_match(projname) {
+        Err(CoreError::InvalidProjectName)
+    }
+    else {
+        Ok(())
+    }
+}
+
+fn check_package_name(pkgname: &str) -> Result<(), CoreError> {
+    // package names appear in URLs, so they must be usable as a path
+    // element; surrounding whitespace would make them ambiguous, as would
+    // zero-width characters, which are not whitespace but look like nothing
+    let blank = |c: char| c.is_whitespace() ||
+        matches!(c, '\u{200B}'..='\u{200D}' | '\u{2060}' | '\u{FEFF}');
+
+    if pkgname.is_empty() ||
+        pkgname.chars().count() > PACKAGE_NAME_MAX_LENGTH ||
+        pkgname.trim_matches(blank) != pkgname ||
+        pkgname.chars().any(|c| c.is_control() || c == '/') ||
+        RESERVED_PACKAGE_NAMES.contains(&pkgname)
+    {
+        Err(CoreError::InvalidPackageName)
+    }
+    else {
+        Ok(())
+    }
+}
+
+fn check_project_packages(
+    packages: &[ProjectPackagePost]
+) -> Result<Vec<ProjectPackagePost>, CoreError>
+{
+    // every bad entry is reported, not just the first
+    let mut errors = vec![];
+    let mut checked = Vec::with_capacity(packages.len());
+
+    for (i, pkg) in packages.iter().enumerate() {
+        if check_package_name(&pkg.name).is_err() {
+            errors.push(format!("packages[{i}].name is invalid"));
+        }
+        else if packages[..i].iter().any(|p| p.name == pkg.name) {
+            errors.push(format!("packages[{i}].name is a duplicate"));
+        }
+
+        match normalize_section(pkg.section.as_deref()) {
+            Ok(section) => checked.push(
+                ProjectPackagePost {
+                    section,
+                    ..pkg.clone()
+                }
+            ),
+            Err(_) => errors.push(format!("packages[{i}].section is invalid"))
+        }
+    }
+
+    match errors.is_empty() {
+        true => Ok(checked),
+        false => Err(CoreError::InvalidPackages(errors))
+    }
+}
+
+fn get_prev_for_before(
+    anchor: &Anchor,
+    sort_by: SortBy,
+    dir: Direction,
+    facets: &[Facet],
+    limit_extra: u32,
+    projects: &mut Vec<ProjectSummaryRow>
+) -> Result<Option<Seek>, CoreError>
+{
+    // make the prev link
+    if projects.len() == limit_extra as usize {
+        // there are more pages in the forward direction
+
+        // remove the "extra" item which proves we are not at the end
+        projects.pop();
+
+        // the prev page is after the last item
+        let last = projects.last().expect("element must exist");
+
+        let prev_anchor = match anchor {
+            Anchor::BeforeQuery(ref q, ..) => Anchor::BeforeQuery(
+                q.clone(),
+                last.sort_field(sort_by)?,
+                last.project_id as u32
+            ),
+            Anchor::Before(..) => Anchor::Before(
+                last.sort_field(sort_by)?,
+                last.project_id as u32
+            ),
+            Anchor::Start |
+            Anchor::StartQuery(..) |
+            Anchor::After(..) |
+            Anchor::AfterQuery(..) => unreachable!()
+        };
+
+        Ok(Some(Seek { anchor: prev_anchor, sort_by, dir, facets: facets.to_vec() }))
+    }
+    else {
+        // there are no pages in the forward direction
+        Ok(None)
+    }
+}
+
+fn get_next_for_before(
+    anchor: &Anchor,
+    sort_by: SortBy,
+    dir: Direction,
+    facets: &[Facet],
+    projects: &[ProjectSummaryRow]
+) -> Result<Option<Seek>, CoreError>
+{
+    // make the next link
+    if projects.is_empty() {
+        Ok(None)
+    }
+    else {
+        // the next page is before the first item
+        let first = projects.first().expect("element must exist");
+
+        let next_anchor = match anchor {
+            Anchor::BeforeQuery(ref q, ..) => Anchor::AfterQuery(
+                q.clone(),
+                first.sort_field(sort_by)?,
+                first.project_id as u32
+            ),
+            Anchor::Before(..) => Anchor::After(
+                first.sort_field(sort_by)?,
+                first.project_id as u32
+            ),
+            Anchor::Start |
+            Anchor::After(..) |
+            Anchor::StartQuery(..) |
+            Anchor::AfterQuery(..) => unreachable!()
+        };
+
+        Ok(Some(Seek { anchor: next_anchor, sort_by, dir, facets: facets.to_vec() }))
+    }
+}
+
+fn get_next_for_after(
+    anchor: &Anchor,
+    sort_by: SortBy,
+    dir: Direction,
+    facets: &[Facet],
+    limit_extra: u32,
+    projects: &mut Vec<ProjectSummaryRow>
+) -> Result<Option<Seek>, CoreError>
+{
+    // make the next link
+    if projects.len() == limit_extra as usize {
+        // there are more pages in the forward direction
+
+        // remove the "extra" item which proves we are not at the end
+        projects.pop();
+
+        // the next page is after the last item
+        let last = projects.last().expect("element must exist");
+
+        let next_anchor = match anchor {
+            Anchor::StartQuery(ref q) |
+            Anchor::AfterQuery(ref q, ..) => Anchor::AfterQuery(
+                q.clone(),
+                last.sort_field(sort_by)?,
+                last.project_id as u32
+            ),
+            Anchor::Start |
+            Anchor::After(..) => Anchor::After(
+                last.sort_field(sort_by)?,
+                last.project_id as u32
+            ),
+            Anchor::Before(..) |
+            Anchor::BeforeQuery(..) => unreachable!()
+        };
+
+        Ok(Some(Seek { anchor: next_anchor, sort_by, dir, facets: facets.to_vec() }))
+    }
+    else {
+        // there are no pages in the forward direction
+        Ok(None)
+    }
+}
+
+fn get_prev_for_after(
+    anchor: &Anchor,
+    sort_by: SortBy,
+    dir: Direction,
+    facets: &[Facet],
+    projects: &[ProjectSummaryRow]
+) -> Result<Option<Seek>, CoreError>
+{
+    // make the prev link
+    match anchor {
+        _ if projects.is_empty() => Ok(None),
+        Anchor::Start |
+        Anchor::StartQuery(_) => Ok(None),
+        Anchor::After(..) |
+        Anchor::AfterQuery(..) => {
+            // the previous page is before the first item
+            let first = projects.first().expect("element must exist");
+
+            let prev_anchor = match anchor {
+                Anchor::AfterQuery(ref q, ..) => Anchor::BeforeQuery(
+                    q.clone(),
+                    first.sort_field(sort_by)?,
+                    first.project_id as u32
+                ),
+                Anchor::After(..) => Anchor::Before(
+                    first.sort_field(sort_by)?,
+                    first.project_id as u32
+                ),
+                Anchor::Start |
+                    Anchor::StartQuery(..) |
+                Anchor::Before(..) |
+                Anchor::BeforeQuery(..) => unreachable!()
+            };
+
+            Ok(Some(Seek { anchor: prev_anchor, sort_by, dir, facets: facets.to_vec() }))
+        },
+        Anchor::Before(..) |
+        Anchor::BeforeQuery(..) => unreachable!()
+    }
+}
+
+fn get_links(
+    anchor: &Anchor,
+    sort_by: SortBy,
+    dir: Direction,
+    facets: &[Facet],
+    limit_extra: u32,
+    projects: &mut Vec<ProjectSummaryRow>
+) -> Result<(Option<Seek>, Option<Seek>), CoreError>
+{
+    match anchor {
+        Anchor::Before(..) |
+        Anchor::BeforeQuery(..) => {
+            let prev = get_prev_for_before(
+                anchor,
+                sort_by,
+                dir,
+                facets,
+                limit_extra,
+                projects
+            )?;
+
+            let next = get_next_for_before(
+                anchor,
+                sort_by,
+                dir,
+                facets,
+                projects
+            )?;
+
+            Ok((prev, next))
+        },
+        Anchor::Start |
+        Anchor::StartQuery(..) |
+        Anchor::After(..) |
+        Anchor::AfterQuery(..) => {
+            let next = get_next_for_after(
+                anchor,
+                sort_by,
+                dir,
+                facets,
+                limit_extra,
+                projects
+            )?;
+
+            let prev = get_prev_for_after(
+                anchor,
+                sort_by,
+                dir,
+                facets,
+                projects
+            )?;
+
+            Ok((prev, next))
+        }
+    }
+}
+
+impl ProjectSummaryRow {
+    fn sort_field(&self, sort_by: SortBy) -> Result<String, CoreError> {
+        Ok(
+            match sort_by {
+                SortBy::ProjectName => self.name.clone(),
+                SortBy::GameTitle => self.game_title_sort.clone(),
+                SortBy::ModificationTime => self.modified_at.to_string(),
+                SortBy::CreationTime => self.created_at.to_string(),
+                SortBy::Relevance => self.rank.to_string()
+            }
+        )
+    }
+}
+
+fn summary_timestamp(project_id: i64, ns: i64) -> String {
+    // One bad row must not take down every listing which includes it,
+    // so substitute the epoch; the admin timestamp report finds these.
+    nanos_to_rfc3339(ns).unwrap_or_else(|e| {
+        eprintln!("project {project_id} has a bad timestamp: {e}");
+        nanos_to_rfc3339(0).expect("epoch is in range")
+    })
+}
+
+fn summary_tags(project_id: i64, tags: &str) -> Vec<String> {
+    // as with timestamps, a bad row should not break the listing
+    serde_json::from_str(tags).unwrap_or_else(|e| {
+        eprintln!("project {project_id} has bad tags: {e}");
+        vec![]
+    })
+}
+
+impl From<ProjectSummaryRow> for ProjectSummary {
+    fn from(r: ProjectSummaryRow) -> Self {
+        ProjectSummary {
+            created_at: summary_timestamp(r.project_id, r.created_at),
+            modified_at: summary_timestamp(r.project_id, r.modified_at),
+            tags: summary_tags(r.project_id, &r.tags),
+            name: r.name,
+            description: r.description,
+            revision: r.revision,
+            game: GameData {
+                title: r.game_title,
+                title_sort_key: r.game_title_sort,
+                publisher: r.game_publisher,
+                year: r.game_year
+            },
+            modified_by: r.modified_by
+        }
+    }
+}
+
+impl TryFrom<RevisionRow> for ProjectRevisionSummary {
+    type Error = CoreError;
+
+    fn try_from(r: RevisionRow) -> Result<Self, Self::Error> {
+        Ok(
+            ProjectRevisionSummary {
+                revision: r.revision,
+                modified_at: nanos_to_rfc3339(r.modified_at)?,
+                modified_by: r.modified_by
+            }
+        )
+    }
+}
+
+impl TryFrom<FlagRow> for Flag {
+    type Error = CoreError;
+
+    fn try_from(r: FlagRow) -> Result<Self, Self::Error> {
+        Ok(
+            Flag {
+                flag_id: r.flag_id,
+                project: r.project,
+                flagged_by: r.flagged_by,
+                flagged_at: nanos_to_rfc3339(r.flagged_at)?,
+                flag: r.flag,
+                message: r.message,
+                resolved_at: r.resolved_at.map(nanos_to_rfc3339).transpose()?,
+                resolved_by: r.resolved_by
+            }
+        )
+    }
+}
+
+impl TryFrom<NotificationRow> for InboxItem {
+    type Error = CoreError;
+
+    fn try_from(r: NotificationRow) -> Result<Self, Self::Error> {
+        Ok(
+            InboxItem {
+                id: r.notification_id,
+                kind: r.kind,
+                project: r.project,
+                package: r.package,
+                version: r.version,
+                created_at: nanos_to_rfc3339(r.created_at)?,
+                read: r.read_at.is_some()
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use axum::{
+        extract::Query,
+        http::Uri
+    };
+    use std::sync::Mutex;
+
+    use crate::{
+        model::{GameDataPatch, TagChanges},
+        notify::NotificationSink,
+        pagination::Direction,
+        sqlite::{Pool, SqlxDatabaseClient},
+        upload::{DEFAULT_STORAGE_PREFIX_DEPTH, stream_to_writer}
+    };
+
+    const NOW: &str = "2023-11-12T15:50:06.419538067+00:00";
+
+    static NOW_DT: Lazy<DateTime<Utc>> = Lazy::new(||
+        DateTime::parse_from_rfc3339(NOW)
+            .unwrap()
+            .with_timezone(&Utc)
+    );
+
+    fn fake_now() -> DateTime<Utc> {
+        *NOW_DT
+    }
+
+    struct FakeUploader {}
+
+    #[async_trait]
+    impl Uploader for FakeUploader {
+        async fn upload<S>(
+            &self,
+            filename: &str,
+            stream: S,
+            max_size: u64
+        ) -> Result<String, UploadError>
+        where
+            S: Stream<Item = Result<Bytes, io::Error>> + Send
+        {
+            stream_to_writer(stream, tokio::io::sink(), max_size).await?;
+            Ok(format!("https://example.com/{filename}"))
+        }
+
+        async fn remove(
+            &self,
+            _filename: &str
+        ) -> Result<(), UploadError>
+        {
+            unreachable!();
+        }
+    }
+
+    fn make_core(
+        pool: Pool,
+        now: fn() -> DateTime<Utc>,
+        max_image_size: u64
+    ) -> ProdCore<SqlxDatabaseClient<sqlx::sqlite::Sqlite>, FakeUploader>
+    {
+        ProdCore {
+            db: SqlxDatabaseClient(pool),
+            uploader: FakeUploader {},
+            now,
+            max_image_size,
+            max_file_size: 1024,
+            storage_prefix_depth: DEFAULT_STORAGE_PREFIX_DEPTH,
+            non_numeric_years: NonNumericYears::default(),
+            max_limit: Limit::new(100).unwrap(),
+            default_limit: Limit::new(10).unwrap(),
+            count_cache: CountCache::default(),
+            seek_max_age: 0,
+            record_usage: false,
+            notifier: Notifier::default(),
+            readme_cache: ReadmeCache::default()
+        }
+    }
+
+    fn fake_project_summary(name: &str) -> ProjectSummary {
+        ProjectSummary {
+            name: name.into(),
+            description: "".into(),
+            revision: 1,
+            created_at: "1970-01-01T00:00:00+00:00".into(),
+            modified_at: format!(
+                "1970-01-01T00:00:00.0000000{:02}+00:00",
+                name.as_bytes()[0] - b'a' + 1
+            ),
+            tags: vec![],
+            game: GameData {
+                title: "".into(),
+                title_sort_key: "".into(),
+                publisher: "".into(),
+                year: "".into()
+            },
+            modified_by: Some("bob".into())
+        }
+    }
+
+    fn links(l: &[(i64, i64)]) -> Vec<SupersessionRow> {
+        l.iter()
+            .map(|&(project_id, superseded_by)| SupersessionRow {
+                project_id,
+                superseded_by
+            })
+            .collect()
+    }
+
+    #[test]
+    fn check_supersession_ok() {
+        check_supersession(&[], 1, 2).unwrap();
+    }
+
+    #[test]
+    fn check_supersession_self() {
+        assert_eq!(
+            check_supersession(&[], 1, 1).unwrap_err(),
+            CoreError::InvalidSupersession
+        );
+    }
+
+    #[test]
+    fn check_supersession_cycle() {
+        assert_eq!(
+            check_supersession(&links(&[(2, 3), (3, 1)]), 1, 2).unwrap_err(),
+            CoreError::InvalidSupersession
+        );
+    }
+
+    #[test]
+    fn check_supersession_replaces_own_link() {
+        // 1 -> 2 is replaced by 1 -> 3, so 2 -> 1 is no cycle
+        check_supersession(&links(&[(1, 2), (3, 4)]), 1, 3).unwrap();
+    }
+
+    #[test]
+    fn check_supersession_chain_max() {
+        // 0 -> 1 -> [2] -> 3
+        check_supersession(&links(&[(0, 1), (2, 3)]), 1, 2).unwrap();
+    }
+
+    #[test]
+    fn check_supersession_chain_too_long_after() {
+        // 1 -> [2] -> 3 -> 4 -> 5
+        assert_eq!(
+            check_supersession(&links(&[(2, 3), (3, 4), (4, 5)]), 1, 2)
+                .unwrap_err(),
+            CoreError::InvalidSupersession
+        );
+    }
+
+    #[test]
+    fn check_supersession_chain_too_long_before() {
+        // 0 -> 1 -> 2 -> [3] -> 4
+        assert_eq!(
+            check_supersession(&links(&[(0, 1), (1, 2), (3, 4)]), 2, 3)
+                .unwrap_err(),
+            CoreError::InvalidSupersession
+        );
+    }
+
+    #[test]
+    fn check_supersession_chain_too_long_branching() {
+        // 0 -> 1 and 5 -> 6 -> 1, then 1 -> [2] -> 3
+        assert_eq!(
+            check_supersession(&links(&[(0, 1), (5, 6), (6, 1), (2, 3)]), 1, 2)
+                .unwrap_err(),
+            CoreError::InvalidSupersession
+        );
+    }
+
+    #[test]
+    fn check_package_name_ok() {
+        check_package_name("Some Module").unwrap();
+    }
+
+    #[test]
+    fn check_package_name_empty() {
+        assert_eq!(
+            check_package_name("").unwrap_err(),
+            CoreError::InvalidPackageName
+        );
+    }
+
+    #[test]
+    fn check_package_name_too_long() {
+        assert_eq!(
+            check_package_name(&"x".repeat(PACKAGE_NAME_MAX_LENGTH + 1))
+                .unwrap_err(),
+            CoreError::InvalidPackageName
+        );
+    }
+
+    #[test]
+    fn check_package_name_slash() {
+        assert_eq!(
+            check_package_name("a/b").unwrap_err(),
+            CoreError::InvalidPackageName
+        );
+    }
+
+    #[test]
+    fn check_package_name_whitespace() {
+        assert_eq!(
+            check_package_name("   ").unwrap_err(),
+            CoreError::InvalidPackageName
+        );
+    }
+
+    #[test]
+    fn check_package_name_space() {
+        assert_eq!(
+            check_package_name(" ").unwrap_err(),
+            CoreError::InvalidPackageName
+        );
+    }
+
+    #[test]
+    fn check_package_name_tab() {
+        assert_eq!(
+            check_package_name("\t").unwrap_err(),
+            CoreError::InvalidPackageName
+        );
+    }
+
+    #[test]
+    fn check_package_name_newline() {
+        assert_eq!(
+            check_package_name("\n").unwrap_err(),
+            CoreError::InvalidPackageName
+        );
+    }
+
+    #[test]
+    fn check_package_name_zero_width_space() {
+        assert_eq!(
+            check_package_name("\u{200B}").unwrap_err(),
+            CoreError::InvalidPackageName
+        );
+    }
+
+    #[test]
+    fn check_package_name_trailing_zero_width_space() {
+        assert_eq!(
+            check_package_name("Some Module\u{200B}").unwrap_err(),
+            CoreError::InvalidPackageName
+        );
+    }
+
+    #[test]
+    fn check_package_name_reserved() {
+        assert_eq!(
+            check_package_name("order").unwrap_err(),
+            CoreError::InvalidPackageName
+        );
+        check_package_name("Order of Battle").unwrap();
+    }
+
+    #[test]
+    fn check_package_name_inner_space() {
+        check_package_name("Some  Module").unwrap();
+    }
+
+    #[test]
+    fn check_new_project_name_ok() {
+        check_new_project_name("acceptable_name").unwrap();
+    }
+
+    #[test]
+    fn check_new_project_name_non_ascii() {
+        assert_eq!(
+            check_new_project_name("💩").unwrap_err(),
+            CoreError::InvalidProjectName
+        );
+    }
+
+    #[test]
+    fn check_new_project_name_non_ascii_letters() {
+        check_new_project_name("café").unwrap();
+        check_new_project_name("CAFE\u{301}").unwrap();
+        check_new_project_name("宇宙戦艦ヤマト").unwrap();
+    }
+
+    #[test]
+    fn check_new_project_name_leading_mark() {
+        assert_eq!(
+            check_new_project_name("\u{301}abc").unwrap_err(),
+            CoreError::InvalidProjectName
+        );
+    }
+
+    #[test]
+    fn check_new_project_name_leading_non_alphanumeric() {
+        assert_eq!(
+            check_new_project_name("-abc").unwrap_err(),
+            CoreError::InvalidProjectName
+        );
+    }
+
+    #[test]
+    fn check_new_project_name_too_short() {
+        assert_eq!(
+            check_new_project_name("").unwrap_err(),
+            CoreError::InvalidProjectName
+        );
+    }
+
+    #[test]
+    fn check_new_project_name_too_long() {
+        assert_eq!(
+            check_new_project_name(&"x".repeat(100)).unwrap_err(),
+            CoreError::InvalidProjectName
+        );
+    }
+
+    #[test]
+    fn check_new_project_name_one_char() {
+        check_new_project_name("x").unwrap();
+    }
+
+    #[test]
+    fn check_new_project_name_max_length() {
+        check_new_project_name(&"x".repeat(64)).unwrap();
+    }
+
+    #[test]
+    fn check_new_project_name_max_length_exceeded() {
+        assert_eq!(
+            check_new_project_name(&"x".repeat(65)).unwrap_err(),
+            CoreError::InvalidProjectName
+        );
+    }
+
+    #[test]
+    fn check_new_project_name_leading_digit() {
+        check_new_project_name("1830").unwrap();
+    }
+
+    #[test]
+    fn check_new_project_name_hyphens() {
+        check_new_project_name("a-b-c").unwrap();
+    }
+
+    #[test]
+    fn check_new_project_name_dot() {
+        assert_eq!(
+            check_new_project_name("a.b").unwrap_err(),
+            CoreError::InvalidProjectName
+        );
+    }
+
+    #[test]
+    fn check_new_project_name_leading_underscore() {
+        assert_eq!(
+            check_new_project_name("_abc").unwrap_err(),
+            CoreError::InvalidProjectName
+        );
+    }
+
+    #[test]
+    fn check_new_project_name_reserved() {
+        assert_eq!(
+            check_new_project_name("random").unwrap_err(),
+            CoreError::InvalidProjectName
+        );
+        assert_eq!(
+            check_new_project_name("Random").unwrap_err(),
+            CoreError::InvalidProjectName
+        );
+        assert_eq!(
+            check_new_project_name("popular").unwrap_err(),
+            CoreError::InvalidProjectName
+        );
+        assert_eq!(
+            check_new_project_name("recent").unwrap_err(),
+            CoreError::InvalidProjectName
+        );
+        check_new_project_name("randomness").unwrap();
+    }
+
+    #[test]
+    fn check_new_project_name_space() {
+        assert_eq!(
+            check_new_project_name("a b").unwrap_err(),
+            CoreError::InvalidProjectName
+        );
+    }
+
+    fn fake_file_row(
+        id: i64,
+        version: &str,
+        filename: &str,
+        published_at: i64,
+        is_primary: bool
+    ) -> FileRow
+    {
+        FileRow {
+            id,
+            version: version.into(),
+            version_major: 0,
+            version_minor: 0,
+            version_patch: 0,
+            version_pre: "".into(),
+            version_build: "".into(),
+            filename: filename.into(),
+            url: "".into(),
+            size: 0,
+            checksum: "".into(),
+            published_at,
+            published_by: "bob".into(),
+            is_primary
+        }
+    }
+
+    #[test]
+    fn primary_file_marked() {
+        let files = [
+            fake_file_row(1, "1.0.0", "new.vmod", 2, false),
+            fake_file_row(2, "1.0.0", "maps.zip", 1, true)
+        ];
+        assert_eq!(primary_file("1.0.0", &files), Some("maps.zip"));
+    }
+
+    #[test]
+    fn primary_file_newest_module() {
+        let files = [
+            fake_file_row(1, "1.0.0", "old.vmod", 1, false),
+            fake_file_row(2, "1.0.0", "new.vmod", 3, false),
+            fake_file_row(3, "1.0.0", "notes.txt", 4, false),
+            fake_file_row(4, "1.0.1", "newer.vmod", 5, false)
+        ];
+        assert_eq!(primary_file("1.0.0", &files), Some("new.vmod"));
+    }
+
+    #[test]
+    fn primary_file_none() {
+        let files = [
+            fake_file_row(1, "1.0.0", "notes.txt", 1, false),
+            fake_file_row(2, "1.0.1", "new.vmod", 2, true)
+        ];
+        assert_eq!(primary_file("1.0.0", &files), None);
+    }
+
+    #[test]
+    fn is_module_ok() {
+        assert!(is_module("foo.vmod"));
+        assert!(is_module("foo.VMDX"));
+        assert!(!is_module("foo.vsav"));
+        assert!(!is_module("vmod"));
+    }
+
+    #[test]
+    fn module_version_matches_semver() {
+        let v = "1.2.3".parse::<Version>().unwrap();
+        assert!(module_version_matches("1.2.3", &v));
+        assert!(!module_version_matches("1.2.4", &v));
+    }
+
+    #[test]
+    fn module_version_matches_short() {
+        assert!(
+            module_version_matches("1.2", &"1.2.0".parse::<Version>().unwrap())
+        );
+        assert!(
+            module_version_matches("1", &"1.0.0".parse::<Version>().unwrap())
+        );
+        assert!(
+            !module_version_matches("1.2", &"1.2.1".parse::<Version>().unwrap())
+        );
+        assert!(
+            !module_version_matches("1.2", &"1.2.0-beta".parse::<Version>().unwrap())
+        );
+    }
+
+    #[test]
+    fn module_version_matches_junk() {
+        let v = "1.2.0".parse::<Version>().unwrap();
+        assert!(!module_version_matches("", &v));
+        assert!(!module_version_matches("one.two", &v));
+        assert!(!module_version_matches("1.2.0.0", &v));
+    }
+
+    #[test]
+    fn normalize_section_none() {
+        assert_eq!(normalize_section(None).unwrap(), None);
+    }
+
+    #[test]
+    fn normalize_section_blank() {
+        assert_eq!(normalize_section(Some("  ")).unwrap(), None);
+    }
+
+    #[test]
+    fn normalize_section_trimmed() {
+        assert_eq!(
+            normalize_section(Some(" Scenario Archives ")).unwrap(),
+            Some("Scenario Archives".into())
+        );
+    }
+
+    #[test]
+    fn normalize_section_max_length() {
+        let s = "é".repeat(PACKAGE_SECTION_MAX_LENGTH);
+        assert_eq!(normalize_section(Some(&s)).unwrap(), Some(s));
+    }
+
+    #[test]
+    fn normalize_section_too_long() {
+        assert_eq!(
+            normalize_section(
+                Some(&"x".repeat(PACKAGE_SECTION_MAX_LENGTH + 1))
+            ).unwrap_err(),
+            CoreError::InvalidPackageSection
+        );
+    }
+
+    fn fake_package_data(name: &str, section: Option<&str>) -> PackageData {
+        PackageData {
+            name: name.into(),
+            description: "".into(),
+            section: section.map(String::from),
+            releases: vec![],
+            files: vec![],
+            recommended_release: None
+        }
+    }
+
+    #[test]
+    fn package_sections_ok() {
+        assert_eq!(
+            package_sections(&[
+                fake_package_data("a", Some("Extensions")),
+                fake_package_data("b", None),
+                fake_package_data("c", Some("Downloads")),
+                fake_package_data("d", Some("Extensions")),
+                fake_package_data("e", None)
+            ]),
+            [
+                PackageSection {
+                    name: Some("Downloads".into()),
+                    package_slugs: vec!["c".into()]
+                },
+                PackageSection {
+                    name: Some("Extensions".into()),
+                    package_slugs: vec!["a".into(), "d".into()]
+                },
+                PackageSection {
+                    name: None,
+                    package_slugs: vec!["b".into(), "e".into()]
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn package_sections_empty() {
+        assert_eq!(package_sections(&[]), []);
+    }
+
+    #[test]
+    fn normalize_tags_ok() {
+        assert_eq!(
+            normalize_tags(&[
+                " wwii".into(),
+                "armor ".into(),
+                "".into(),
+                "armor".into(),
+                "  ".into(),
+                "east_front".into()
+            ]),
+            ["armor", "east_front", "wwii"]
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "ten_projects"))]
+    async fn get_projects_default_limit_ok(pool: Pool) {
+        let core = ProdCore {
+            default_limit: Limit::new(3).unwrap(),
+            ..make_core(pool, fake_now, 0)
+        };
+
+        assert_eq!(
+            core.get_projects(ProjectsParams::default())
+                .await
+                .unwrap()
+                .projects,
+            [
+                fake_project_summary("a"),
+                fake_project_summary("b"),
+                fake_project_summary("c")
+            ]
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "bad_timestamps"))]
+    async fn get_projects_bad_timestamp_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        // the listing survives, with the epoch in place of the bad value
+        let projects = core.get_projects(ProjectsParams::default())
+            .await
+            .unwrap()
+            .projects;
+
+        assert_eq!(
+            projects.iter()
+                .map(|p| (p.name.as_str(), p.modified_at.as_str()))
+                .collect::<Vec<_>>(),
+            [
+                ("a_game", "1970-01-01T00:00:00+00:00"),
+                ("test_game", "2023-12-14T15:50:06.419538067+00:00")
+            ]
+        );
+    }
+
+    fn year_params(year: &str) -> ProjectsParams {
+        ProjectsParams {
+            seek: Seek {
+                facets: vec![Facet::Year(year.into())],
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "non_numeric_year"))]
+    async fn get_projects_year_non_numeric_excluded(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        // "c. 1979" is an unknown year, not 1979
+        assert_eq!(
+            core.get_projects(year_params("1979"))
+                .await
+                .unwrap()
+                .projects
+                .into_iter()
+                .map(|p| p.name)
+                .collect::<Vec<_>>(),
+            ["test_game"]
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "non_numeric_year"))]
+    async fn get_projects_year_non_numeric_filter_unknown(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        assert_eq!(
+            core.get_projects(year_params("c. 1979")).await.unwrap_err(),
+            CoreError::MalformedQuery
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "non_numeric_year"))]
+    async fn get_projects_year_non_numeric_filter_literal(pool: Pool) {
+        let core = ProdCore {
+            non_numeric_years: NonNumericYears::Literal,
+            ..make_core(pool, fake_now, 0)
+        };
+
+        assert_eq!(
+            core.get_projects(year_params("c. 1979"))
+                .await
+                .unwrap()
+                .projects
+                .into_iter()
+                .map(|p| p.name)
+                .collect::<Vec<_>>(),
+            ["a_game"]
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "bad_timestamps"))]
+    async fn get_project_bad_timestamp(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        assert!(
+            matches!(
+                core.get_project(Project(6)).await.unwrap_err(),
+                CoreError::TimeError(_)
+            )
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "bad_timestamps"))]
+    async fn get_bad_timestamps_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        assert_eq!(
+            core.get_bad_timestamps().await.unwrap(),
+            BadTimestamps {
+                timestamps: vec![
+                    BadTimestamp {
+                        table: "projects".into(),
+                        id: 6,
+                        column: "modified_at".into(),
+                        value: -1
+                    }
+                ]
+            }
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn get_bad_timestamps_none(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        assert_eq!(
+            core.get_bad_timestamps().await.unwrap(),
+            BadTimestamps { timestamps: vec![] }
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn delete_project_has_releases(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        assert_eq!(
+            core.delete_project(Owner(1), Project(42), false)
+                .await
+                .unwrap_err(),
+            CoreError::ProjectHasReleases
+        );
+        assert_eq!(
+            core.get_project_id("test_game").await.unwrap(),
+            Project(42)
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages", "tags"))]
+    async fn delete_project_force_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        core.delete_project(Owner(1), Project(42), true).await.unwrap();
+
+        // the project is gone from lookups, listings, and batches
+        assert_eq!(
+            core.get_project_id("test_game").await.unwrap_err(),
+            CoreError::NotAProject
+        );
+        assert!(
+            core.get_projects_by_name(&["test_game".into()])
+                .await
+                .unwrap()
+                .projects
+                .is_empty()
+        );
+        assert_eq!(
+            core.get_projects(ProjectsParams::default())
+                .await
+                .unwrap()
+                .projects
+                .into_iter()
+                .map(|p| p.name)
+                .collect::<Vec<_>>(),
+            ["a_game"]
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "ten_projects"))]
+    async fn get_projects_after_delete_ok(pool: Pool) {
+        let core = ProdCore {
+            default_limit: Limit::new(3).unwrap(),
+            ..make_core(pool, fake_now, 0)
+        };
+
+        core.delete_project(Owner(1), Project(1), false).await.unwrap();
+
+        let projects = core.get_projects(ProjectsParams::default())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            projects.projects,
+            [
+                fake_project_summary("b"),
+                fake_project_summary("c"),
+                fake_project_summary("d")
+            ]
+        );
+        assert_eq!(projects.meta.total, 9);
+
+        assert_eq!(
+            core.get_project_id("a").await.unwrap_err(),
+            CoreError::NotAProject
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "ten_projects"))]
+    async fn get_projects_max_limit_ok(pool: Pool) {
+        let core = ProdCore {
+            max_limit: Limit::new(3).unwrap(),
+            ..make_core(pool, fake_now, 0)
+        };
+
+        let params = ProjectsParams {
+            limit: Limit::new(3),
+            ..Default::default()
+        };
+
+        assert_eq!(core.get_projects(params).await.unwrap().projects.len(), 3);
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages", "files", "file_downloads"))]
+    async fn get_popular_projects_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        assert_eq!(
+            core.get_popular_projects(None)
+                .await
+                .unwrap()
+                .projects
+                .into_iter()
+                .map(|p| p.name)
+                .collect::<Vec<_>>(),
+            ["test_game", "a_game"]
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages", "files", "file_downloads"))]
+    async fn get_popular_projects_limit_too_large(pool: Pool) {
+        let core = ProdCore {
+            max_limit: Limit::new(3).unwrap(),
+            ..make_core(pool, fake_now, 0)
+        };
+
+        assert_eq!(
+            core.get_popular_projects(Limit::new(4)).await.unwrap_err(),
+            CoreError::LimitOutOfRange
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages", "files"))]
+    async fn get_release_data_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+        let version = "1.2.3".parse::<Version>().unwrap();
+
+        let release = core.get_release_data(Project(42), Package(1), &version)
+            .await
+            .unwrap();
+
+        assert_eq!(release.version, "1.2.3");
+        assert_eq!(
+            release.files.iter()
+                .map(|f| f.filename.as_str())
+                .collect::<Vec<_>>(),
+            ["a_package-1.2.3", "a_package-1.2.3.vmod", "maps-1.2.3.zip"]
+        );
+        assert_eq!(
+            release.files[0].primary_file.as_deref(),
+            Some("maps-1.2.3.zip")
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages", "files"))]
+    async fn get_release_data_not_a_version(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+        let version = "1.0.0".parse::<Version>().unwrap();
+
+        assert_eq!(
+            core.get_release_data(Project(42), Package(1), &version)
+                .await
+                .unwrap_err(),
+            CoreError::NotAVersion
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "non_numeric_year"))]
+    async fn get_malformed_years_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        assert_eq!(
+            core.get_malformed_years().await.unwrap(),
+            MalformedYears {
+                projects: vec![
+                    MalformedYear {
+                        project: "a_game".into(),
+                        year: "c. 1979".into()
+                    }
+                ]
+            }
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "non_numeric_year"))]
+    async fn correct_year_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        let revision = core.get_project(Project(6)).await.unwrap().revision;
+
+        core.correct_year(User(1), Project(6), "1979").await.unwrap();
+
+        let proj = core.get_project(Project(6)).await.unwrap();
+        assert_eq!(proj.game.year, "1979");
+        assert_eq!(proj.revision, revision + 1);
+
+        assert_eq!(
+            core.get_malformed_years().await.unwrap(),
+            MalformedYears { projects: vec![] }
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "non_numeric_year"))]
+    async fn correct_year_invalid(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        assert_eq!(
+            core.correct_year(User(1), Project(6), "c. 1980")
+                .await
+                .unwrap_err(),
+            CoreError::InvalidYear
+        );
+
+        assert_eq!(
+            core.get_project(Project(6)).await.unwrap().game.year,
+            "c. 1979"
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages", "two_owners"))]
+    async fn get_forum_user_summary_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        assert_eq!(
+            core.get_forum_user_summary(User(1)).await.unwrap(),
+            ForumUserSummary {
+                version: FORUM_SUMMARY_VERSION,
+                username: "bob".into(),
+                owned_projects: 2,
+                recent_releases: vec![
+                    ForumRelease {
+                        project: "test_game".into(),
+                        package: "a_package".into(),
+                        version: "1.2.3".into(),
+                        published_at: "2023-12-09T15:56:29.180282477+00:00".into()
+                    }
+                ],
+                projects: vec![
+                    ForumProject {
+                        name: "test_game".into(),
+                        title: "A Game of Tests".into(),
+                        description: "Brian's Trademarked Game of Being a Test Case".into(),
+                        image: None,
+                        modified_at: "2023-12-14T15:50:06.419538067+00:00".into()
+                    },
+                    ForumProject {
+                        name: "a_game".into(),
+                        title: "Some Other Game".into(),
+                        description: "Another game".into(),
+                        image: None,
+                        modified_at: "2019-11-12T15:50:06.419538067+00:00".into()
+                    }
+                ]
+            }
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn get_forum_user_summary_not_a_user(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        assert_eq!(
+            core.get_forum_user_summary(User(0)).await.unwrap_err(),
+            CoreError::NotAUser
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "two_owners", "players"))]
+    async fn get_user_profile_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        assert_eq!(
+            core.get_user_profile(User(1)).await.unwrap(),
+            UserProfile {
+                username: "bob".into(),
+                owned_projects: 2,
+                played_projects: 1
+            }
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "two_owners", "players"))]
+    async fn get_user_profile_deleted(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        // deleted projects are not counted
+        core.delete_project(Owner(1), Project(42), true).await.unwrap();
+
+        assert_eq!(
+            core.get_user_profile(User(1)).await.unwrap(),
+            UserProfile {
+                username: "bob".into(),
+                owned_projects: 1,
+                played_projects: 0
+            }
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn get_user_profile_not_a_user(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        assert_eq!(
+            core.get_user_profile(User(0)).await.unwrap_err(),
+            CoreError::NotAUser
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages", "files", "two_owners", "players"))]
+    async fn get_library_stats_deleted(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        assert_eq!(core.get_library_stats().await.unwrap().projects, 2);
+
+        // deleted projects and their contents are not counted
+        core.delete_project(Owner(1), Project(42), true).await.unwrap();
+
+        assert_eq!(
+            core.get_library_stats().await.unwrap(),
+            LibraryStats {
+                projects: 1,
+                packages: 0,
+                releases: 0,
+                files: 0,
+                bytes: 0,
+                players: 0,
+                owners: 2,
+                flags: 0
+            }
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages", "files", "two_owners", "players"))]
+    async fn get_project_stats_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        assert_eq!(
+            core.get_project_stats(Project(42)).await.unwrap(),
+            ProjectStats {
+                files: 8,
+                bytes: 147379,
+                releases: 3,
+                packages: 3,
+                players: 2,
+                owners: 2,
+                flags: 0,
+                age_days: 0,
+                views: 0,
+                downloads: 0,
+                daily: (0..30)
+                    .rev()
+                    .map(|d| DailyStats {
+                        date: day_to_date(19673 - d).unwrap(),
+                        views: 0,
+                        downloads: 0
+                    })
+                    .collect()
+            }
+        );
+
+        // created four years, including one leap day, before now
+        assert_eq!(
+            core.get_project_stats(Project(6)).await.unwrap().age_days,
+            1461
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn get_projects_by_name_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+        let names = ["test_game".into(), "not_a_project".into()];
+
+        let by_name = core.get_projects_by_name(&names).await.unwrap();
+        assert_eq!(
+            by_name.projects.keys().collect::<Vec<_>>(),
+            ["test_game"]
+        );
+        assert_eq!(by_name.projects["test_game"].revision, 3);
+    }
+
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn get_projects_by_name_empty(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+        assert_eq!(
+            core.get_projects_by_name(&[]).await.unwrap(),
+            ProjectsByName::default()
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "one_owner"))]
+    async fn get_project_readme_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        let pd = ProjectDataPatch {
+            readme: Some(
+                "# Rules\n\n<script>alert('xss')</script>\n\n[x](javascript:alert(1))".into()
+            ),
+            ..Default::default()
+        };
+        core.update_project(Owner(1), Project(42), &pd, None).await.unwrap();
+
+        // the markdown is returned as stored
+        assert_eq!(
+            core.get_project_readme(Project(42), ReadmeFormat::Markdown)
+                .await
+                .unwrap(),
+            pd.readme.unwrap()
+        );
+
+        let html = core.get_project_readme(Project(42), ReadmeFormat::Html)
+            .await
+            .unwrap();
+        assert!(html.starts_with("<h1>Rules</h1>"));
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("alert('xss')"));
+        assert!(!html.contains("javascript"));
+
+        // the rendering is kept for the revision
+        assert_eq!(
+            core.readme_cache.get(Project(42), 4).as_deref(),
+            Some(html.as_str())
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "one_owner"))]
+    async fn get_project_readme_cached(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        // a cached rendering is used rather than rendering again
+        core.readme_cache.insert(Project(42), 3, "<p>cached</p>".into());
+        assert_eq!(
+            core.get_project_readme(Project(42), ReadmeFormat::Html)
+                .await
+                .unwrap(),
+            "<p>cached</p>"
+        );
+
+        // a new revision is rendered afresh
+        let pd = ProjectDataPatch {
+            readme: Some("new".into()),
+            ..Default::default()
+        };
+        core.update_project(Owner(1), Project(42), &pd, None).await.unwrap();
+        assert_eq!(
+            core.get_project_readme(Project(42), ReadmeFormat::Html)
+                .await
+                .unwrap(),
+            "<p>new</p>\n"
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages", "files", "flags"))]
+    async fn get_project_status_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+        assert_eq!(
+            core.get_project_status(Project(42)).await.unwrap(),
+            ProjectStatus {
+                open_flags: 1,
+                archived: false,
+                bytes: 147379
+            }
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "flags", "archived"))]
+    async fn get_project_status_archived(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+        assert_eq!(
+            core.get_project_status(Project(6)).await.unwrap(),
+            ProjectStatus {
+                open_flags: 0,
+                archived: true,
+                bytes: 0
+            }
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages", "files", "file_downloads", "project_views"))]
+    async fn get_project_stats_usage(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+        let stats = core.get_project_stats(Project(42)).await.unwrap();
+
+        assert_eq!(stats.views, 17);
+        assert_eq!(stats.downloads, 17);
+
+        // the breakdown has the last thirty days, ending today
+        assert_eq!(stats.daily.len(), 30);
+        assert_eq!(
+            stats.daily.first().unwrap(),
+            &DailyStats {
+                date: "2023-10-14".into(),
+                views: 0,
+                downloads: 0
+            }
+        );
+        assert_eq!(
+            stats.daily.iter()
+                .filter(|d| d.views > 0 || d.downloads > 0)
+                .collect::<Vec<_>>(),
+            [
+                &DailyStats {
+                    date: "2023-10-20".into(),
+                    views: 0,
+                    downloads: 1
+                },
+                &DailyStats {
+                    date: "2023-11-11".into(),
+                    views: 2,
+                    downloads: 0
+                },
+                &DailyStats {
+                    date: "2023-11-12".into(),
+                    views: 5,
+                    downloads: 3
+                }
+            ]
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn get_project_and_release_record_usage(pool: Pool) {
+        let core = ProdCore {
+            record_usage: true,
+            ..make_core(pool, fake_now, 0)
+        };
+
+        core.record_project_view(Project(42)).await;
+        core.get_release(Project(42), Package(1)).await.unwrap();
+
+        // counting happens in the background, so wait for it
+        for _ in 0..100 {
+            let stats = core.get_project_stats(Project(42)).await.unwrap();
+            if stats.views == 1 && stats.downloads == 1 {
+                return;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+
+        panic!("usage was not recorded");
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages", "files", "file_downloads"))]
+    async fn get_release_record_download(pool: Pool) {
+        // even the two projects out, so the tie is broken by project id
+        sqlx::query("DELETE FROM downloads WHERE package_id = 1 AND day = 19701")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let core = ProdCore {
+            record_usage: true,
+            ..make_core(pool, fake_now, 0)
+        };
+
+        let popular = || async {
+            core.get_popular_projects(None)
+                .await
+                .unwrap()
+                .projects
+                .into_iter()
+                .map(|p| p.name)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(popular().await, ["a_game", "test_game"]);
+        assert_eq!(core.get_project_stats(Project(42)).await.unwrap().downloads, 5);
+
+        core.get_release(Project(42), Package(1)).await.unwrap();
+
+        // counting happens in the background, so wait for it
+        for _ in 0..100 {
+            let stats = core.get_project_stats(Project(42)).await.unwrap();
+            if stats.downloads == 6 {
+                assert_eq!(stats.daily.last().unwrap().downloads, 1);
+                assert_eq!(popular().await, ["test_game", "a_game"]);
+                return;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+
+        panic!("download was not recorded");
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn get_project_does_not_record_view(pool: Pool) {
+        let core = ProdCore {
+            record_usage: true,
+            ..make_core(pool, fake_now, 0)
+        };
+
+        core.get_project(Project(42)).await.unwrap();
+
+        // give a stray background count the chance to land
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        assert_eq!(core.get_project_stats(Project(42)).await.unwrap().views, 0);
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages", "files"))]
+    async fn get_release_feed_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+        let feed = core.get_release_feed(Project(42)).await.unwrap();
+
+        assert_eq!(feed.project, "test_game");
+        assert_eq!(feed.title, "A Game of Tests");
+        assert_eq!(
+            feed.entries.iter()
+                .map(|e| (e.package.as_str(), e.version.as_str()))
+                .collect::<Vec<_>>(),
+            [
+                ("c_package", "0.1.0"),
+                ("a_package", "1.2.4"),
+                ("a_package", "1.2.3")
+            ]
+        );
+        assert_eq!(feed.updated, feed.entries[0].published_at);
+    }
+
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn get_release_feed_no_releases(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        assert_eq!(
+            core.get_release_feed(Project(6)).await.unwrap(),
+            ReleaseFeed {
+                project: "a_game".into(),
+                title: "Some Other Game".into(),
+                updated: "2019-11-12T15:50:06.419538067+00:00".into(),
+                entries: vec![]
+            }
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages", "files"))]
+    async fn get_releases_first_page(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        let params = ReleasesParams {
+            limit: Limit::new(1),
+            ..Default::default()
+        };
+
+        let releases = core.get_releases(Project(42), Package(1), params)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            releases.releases.iter()
+                .map(|r| (r.version.as_str(), r.files.len()))
+                .collect::<Vec<_>>(),
+            [("1.2.4", 4)]
+        );
+
+        assert_eq!(
+            releases.meta,
+            Pagination {
+                prev_page: None,
+                next_page: Some(
+                    SeekLink::from_release_anchor(
+                        &ReleaseAnchor::After("1.2.4".parse().unwrap()),
+                        Limit::new(1)
+                    )
+                ),
+                total: 2,
+                total_pages: 2,
+                approximate: false,
+                position: None
+            }
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages", "files"))]
+    async fn get_releases_last_page(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        let params = ReleasesParams {
+            anchor: ReleaseAnchor::After("1.2.4".parse().unwrap()),
+            limit: Limit::new(1)
+        };
+
+        let releases = core.get_releases(Project(42), Package(1), params)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            releases.releases.iter()
+                .map(|r| (r.version.as_str(), r.files.len()))
+                .collect::<Vec<_>>(),
+            [("1.2.3", 3)]
+        );
+
+        assert_eq!(
+            releases.meta,
+            Pagination {
+                prev_page: Some(
+                    SeekLink::from_release_anchor(
+                        &ReleaseAnchor::Before("1.2.3".parse().unwrap()),
+                        Limit::new(1)
+                    )
+                ),
+                next_page: None,
+                total: 2,
+                total_pages: 2,
+                approximate: false,
+                position: None
+            }
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages", "files"))]
+    async fn get_releases_limit_too_large(pool: Pool) {
+        let core = ProdCore {
+            max_limit: Limit::new(3).unwrap(),
+            ..make_core(pool, fake_now, 0)
+        };
+
+        let params = ReleasesParams {
+            limit: Limit::new(4),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            core.get_releases(Project(42), Package(1), params)
+                .await
+                .unwrap_err(),
+            CoreError::LimitOutOfRange
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "ten_projects"))]
+    async fn get_projects_limit_too_large(pool: Pool) {
+        let core = ProdCore {
+            max_limit: Limit::new(3).unwrap(),
+            ..make_core(pool, fake_now, 0)
+        };
+
+        let params = ProjectsParams {
+            limit: Limit::new(4),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            core.get_projects(params).await.unwrap_err(),
+            CoreError::LimitOutOfRange
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "ten_projects"))]
+    async fn get_projects_position_start(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        let params = ProjectsParams {
+            limit: Limit::new(3),
+            with_position: true,
+            ..Default::default()
+        };
+
+        let meta = core.get_projects(params).await.unwrap().meta;
+        assert_eq!(meta.total, 10);
+        assert_eq!(meta.total_pages, 4);
+        assert_eq!(meta.position, Some(0));
+    }
+
+    #[sqlx::test(fixtures("users", "ten_projects"))]
+    async fn get_projects_position_asc(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        let params = ProjectsParams {
+            seek: Seek {
+                sort_by: SortBy::ProjectName,
+                dir: Direction::Ascending,
+                anchor: Anchor::After("c".into(), 3),
+                facets: vec![]
+            },
+            limit: Limit::new(3),
+            with_position: true,
+            facet_counts: false,
+            issued_at: None
+        };
+
+        let projects = core.get_projects(params).await.unwrap();
+
+        assert_eq!(
+            projects.projects,
+            [
+                fake_project_summary("d"),
+                fake_project_summary("e"),
+                fake_project_summary("f")
+            ]
+        );
+
+        assert_eq!(
+            projects.meta,
+            Pagination {
+                prev_page: Some(
+                    SeekLink::new(
+                        &Seek {
+                            sort_by: SortBy::ProjectName,
+                            dir: Direction::Ascending,
+                            anchor: Anchor::Before("d".into(), 4),
+                            facets: vec![]
+                        },
+                        Limit::new(3)
+                    ).unwrap().with_position()
+                ),
+                next_page: Some(
+                    SeekLink::new(
+                        &Seek {
+                            sort_by: SortBy::ProjectName,
+                            dir: Direction::Ascending,
+                            anchor: Anchor::After("f".into(), 6),
+                            facets: vec![]
+                        },
+                        Limit::new(3)
+                    ).unwrap().with_position()
+                ),
+                total: 10,
+                total_pages: 4,
+                approximate: false,
+                position: Some(3)
+            }
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "ten_projects"))]
+    async fn get_projects_position_desc(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        let params = ProjectsParams {
+            seek: Seek {
+                sort_by: SortBy::ModificationTime,
+                dir: Direction::Descending,
+                anchor: Anchor::After("8".into(), 8),
+                facets: vec![]
+            },
+            limit: Limit::new(3),
+            with_position: true,
+            facet_counts: false,
+            issued_at: None
+        };
+
+        let projects = core.get_projects(params).await.unwrap();
+
+        assert_eq!(
+            projects.projects,
+            [
+                fake_project_summary("g"),
+                fake_project_summary("f"),
+                fake_project_summary("e")
+            ]
+        );
+
+        assert_eq!(projects.meta.position, Some(3));
+    }
+
+    #[sqlx::test(fixtures("users", "ten_projects"))]
+    async fn get_projects_position_before(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        let params = ProjectsParams {
+            seek: Seek {
+                sort_by: SortBy::ProjectName,
+                dir: Direction::Ascending,
+                anchor: Anchor::Before("h".into(), 8),
+                facets: vec![]
+            },
+            limit: Limit::new(3),
+            with_position: true,
+            facet_counts: false,
+            issued_at: None
+        };
+
+        let projects = core.get_projects(params).await.unwrap();
+
+        assert_eq!(
+            projects.projects,
+            [
+                fake_project_summary("e"),
+                fake_project_summary("f"),
+                fake_project_summary("g")
+            ]
+        );
+
+        assert_eq!(projects.meta.position, Some(4));
+    }
+
+    #[sqlx::test(fixtures("users", "proj_query_window"))]
+    async fn get_projects_position_query(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        let params = ProjectsParams {
+            seek: Seek {
+                sort_by: SortBy::ProjectName,
+                dir: Direction::Ascending,
+                anchor: Anchor::AfterQuery("abc".into(), "a".into(), 1),
+                facets: vec![]
+            },
+            limit: Limit::new(1),
+            with_position: true,
+            facet_counts: false,
+            issued_at: None
+        };
+
+        let meta = core.get_projects(params).await.unwrap().meta;
+        assert_eq!(meta.total, 3);
+        assert_eq!(meta.total_pages, 3);
+        assert_eq!(meta.position, Some(1));
+    }
+
+    #[sqlx::test(fixtures("users", "ten_projects"))]
+    async fn get_projects_without_position(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        let params = ProjectsParams {
+            seek: Seek {
+                sort_by: SortBy::ProjectName,
+                dir: Direction::Ascending,
+                anchor: Anchor::After("c".into(), 3),
+                facets: vec![]
+            },
+            limit: Limit::new(3),
+            with_position: false,
+            facet_counts: false,
+            issued_at: None
+        };
+
+        let meta = core.get_projects(params).await.unwrap().meta;
+        assert_eq!(meta.total_pages, 4);
+        assert_eq!(meta.position, None);
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "tags"))]
+    async fn get_projects_summary_tags(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        assert_eq!(
+            core.get_projects(ProjectsParams::default())
+                .await
+                .unwrap()
+                .projects
+                .into_iter()
+                .map(|p| (p.name, p.tags))
+                .collect::<Vec<_>>(),
+            [
+                ("a_game".into(), vec!["ancients".into()]),
+                ("test_game".into(), vec!["tactical".into(), "wwii".into()])
+            ]
+        );
+    }
+
+    #[test]
+    fn summary_tags_bad() {
+        assert!(summary_tags(1, "not json").is_empty());
+    }
+
+    #[sqlx::test(fixtures("users", "ten_projects"))]
+    async fn get_projects_seek_expired(pool: Pool) {
+        fn later_now() -> DateTime<Utc> {
+            *NOW_DT + chrono::Duration::seconds(61)
+        }
+
+        let core = ProdCore {
+            seek_max_age: 60,
+            ..make_core(pool.clone(), fake_now, 0)
+        };
+
+        let params = Query::<ProjectsParams>::try_from_uri(
+            &Uri::from_static("/projects?sort=p&limit=2")
+        ).unwrap().0;
+
+        let next_page = core.get_projects(params)
+            .await
+            .unwrap()
+            .meta
+            .next_page
+            .unwrap();
+
+        let next_params = || Query::<ProjectsParams>::try_from_uri(
+            &format!("/projects{}", next_page).parse::<Uri>().unwrap()
+        ).unwrap().0;
+
+        // the link carries the time at which it was issued
+        assert_eq!(
+            next_params().issued_at,
+            Some(fake_now().timestamp_nanos_opt().unwrap())
+        );
+
+        // the link is good until it is older than the max age
+        core.get_projects(next_params()).await.unwrap();
+
+        let core = ProdCore {
+            seek_max_age: 60,
+            ..make_core(pool, later_now, 0)
+        };
+
+        assert_eq!(
+            core.get_projects(next_params()).await.unwrap_err(),
+            CoreError::MalformedQuery
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "ten_projects"))]
+    async fn get_projects_seek_without_issued(pool: Pool) {
+        // a seek with no issue time, as if it had been cut off
+        let params = || Query::<ProjectsParams>::try_from_uri(
+            &Uri::from_static("/projects?seek=cCxhLGEsYWJjLCww")
+        ).unwrap().0;
+
+        let core = ProdCore {
+            seek_max_age: 60,
+            ..make_core(pool.clone(), fake_now, 0)
+        };
+
+        assert_eq!(
+            core.get_projects(params()).await.unwrap_err(),
+            CoreError::MalformedQuery
+        );
+
+        // seeks are good forever when there is no max age
+        let core = make_core(pool, fake_now, 0);
+        core.get_projects(params()).await.unwrap();
+    }
+
+    #[sqlx::test(fixtures("users", "ten_projects", "ten_projects_tags"))]
+    async fn get_projects_facets_survive_paging(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        let even = |name| ProjectSummary {
+            tags: vec!["even".into()],
+            ..fake_project_summary(name)
+        };
+
+        let params = Query::<ProjectsParams>::try_from_uri(
+            &Uri::from_static("/projects?sort=p&tag=even&limit=2")
+        ).unwrap().0;
+
+        let page1 = core.get_projects(params).await.unwrap();
+
+        assert_eq!(
+            page1.projects,
+            [
+                even("b"),
+                even("d")
+            ]
+        );
+
+        let next_page = page1.meta.next_page.unwrap();
+
+        assert_eq!(
+            next_page,
+            SeekLink::new(
+                &Seek {
+                    sort_by: SortBy::ProjectName,
+                    dir: Direction::Ascending,
+                    anchor: Anchor::After("d".into(), 4),
+                    facets: vec![Facet::Tag("even".into())]
+                },
+                Limit::new(2)
+            ).unwrap()
+        );
+
+        assert_eq!(page1.meta.total, 5);
+
+        // follow the next link; the facet must still apply
+        let params = Query::<ProjectsParams>::try_from_uri(
+            &format!("/projects{}", next_page).parse::<Uri>().unwrap()
+        ).unwrap().0;
+
+        let page2 = core.get_projects(params).await.unwrap();
+
+        assert_eq!(
+            page2.projects,
+            [
+                even("f"),
+                even("h")
+            ]
+        );
+
+        assert_eq!(
+            page2.meta.next_page,
+            Some(
+                SeekLink::new(
+                    &Seek {
+                        sort_by: SortBy::ProjectName,
+                        dir: Direction::Ascending,
+                        anchor: Anchor::After("h".into(), 8),
+                        facets: vec![Facet::Tag("even".into())]
+                    },
+                    Limit::new(2)
+                ).unwrap()
+            )
+        );
+
+        assert_eq!(page2.meta.total, 5);
+    }
+
+    #[sqlx::test(fixtures("users", "ten_projects", "ten_projects_tags"))]
+    async fn get_projects_facet_counts(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        let params = Query::<ProjectsParams>::try_from_uri(
+            &Uri::from_static("/projects?sort=p&limit=2&facet_counts=tags")
+        ).unwrap().0;
+
+        let page1 = core.get_projects(params).await.unwrap();
+
+        assert_eq!(
+            page1.facets,
+            Some(
+                FacetCounts {
+                    tags: BTreeMap::from([("even".into(), 5)])
+                }
+            )
+        );
+
+        // the next link asks for the counts again
+        let next_page = page1.meta.next_page.unwrap();
+        assert!(next_page.to_string().ends_with("&facet_counts=tags"));
+
+        let params = Query::<ProjectsParams>::try_from_uri(
+            &format!("/projects{}", next_page).parse::<Uri>().unwrap()
+        ).unwrap().0;
+
+        assert!(core.get_projects(params).await.unwrap().facets.is_some());
+    }
+
+    #[sqlx::test(fixtures("users", "ten_projects", "ten_projects_tags"))]
+    async fn get_projects_no_facet_counts(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        let params = Query::<ProjectsParams>::try_from_uri(
+            &Uri::from_static("/projects?sort=p&limit=2")
+        ).unwrap().0;
+
+        assert_eq!(core.get_projects(params).await.unwrap().facets, None);
+    }
+
+    #[sqlx::test(fixtures("users", "ten_projects"))]
+    async fn get_projects_pname_start_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        let (prev, next, summaries, total, _) = core.get_projects_from(
+            Seek {
+                sort_by: SortBy::ProjectName,
+                dir: Direction::Ascending,
+                anchor: Anchor::Start,
+                facets: vec![]
+            },
+            Limit::new(3).unwrap()
+        ).await.unwrap();
+
+        assert_eq!(
+            summaries,
+            [
+                fake_project_summary("a"),
+                fake_project_summary("b"),
+                fake_project_summary("c")
+            ]
+        );
+
+        assert_eq!(total, 10);
+
+        assert_eq!(prev, None);
+
+        assert_eq!(
+            next,
+            Some(
+                Seek {
+                    anchor: Anchor::After("c".into(), 3),
+                    sort_by: SortBy::ProjectName,
+                    dir: Direction::Ascending,
+                    facets: vec![]
+                }
+            )
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "ten_projects"))]
+    async fn get_projects_pname_end_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        let (prev, next, summaries, total, _) = core.get_projects_from(
+            Seek {
+                sort_by: SortBy::ProjectName,
+                dir: Direction::Descending,
+                anchor: Anchor::Start,
+                facets: vec![]
+            },
+            Limit::new(3).unwrap()
+        ).await.unwrap();
+
+        assert_eq!(
+            summaries,
+            [
+                fake_project_summary("j"),
+                fake_project_summary("i"),
+                fake_project_summary("h")
+            ]
+        );
+
+        assert_eq!(total, 10);
+
+        assert_eq!(prev, None);
+
+        assert_eq!(
+            next,
+            Some(
+                Seek {
+                    anchor: Anchor::After("h".into(), 8),
+                    sort_by: SortBy::ProjectName,
+                    dir: Direction::Descending,
+                    facets: vec![]
+                }
+            )
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "ten_projects"))]
+    async fn get_projects_pname_after_asc_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        let (prev, next, summaries, total, _) = core.get_projects_from(
+            Seek {
+                sort_by: SortBy::ProjectName,
+                dir: Direction::Ascending,
+                anchor: Anchor::After("a".into(), 1),
+                facets: vec![]
+            },
+            Limit::new(3).unwrap()
+        ).await.unwrap();
+
+        assert_eq!(
+            summaries,
+            [
+                fake_project_summary("b"),
+                fake_project_summary("c"),
+                fake_project_summary("d")
+            ]
+        );
+
+        assert_eq!(total, 10);
+
+        assert_eq!(
+            prev,
+            Some(
+                Seek {
+                    anchor: Anchor::Before("b".into(), 2),
+                    sort_by: SortBy::ProjectName,
+                    dir: Direction::Ascending,
+                    facets: vec![]
+                }
+            )
+        );
+
+        assert_eq!(
+            next,
+            Some(
+                Seek {
+                    anchor: Anchor::After("d".into(), 4),
+                    sort_by: SortBy::ProjectName,
+                    dir: Direction::Ascending,
+                    facets: vec![]
+                }
+            )
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "ten_projects"))]
+    async fn get_projects_pname_after_desc_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        let (prev, next, summaries, total, _) = core.get_projects_from(
+            Seek {
+                sort_by: SortBy::ProjectName,
+                dir: Direction::Descending,
+                anchor: Anchor::After("h".into(), 8),
+                facets: vec![]
+            },
+            Limit::new(3).unwrap()
+        ).await.unwrap();
+
+        assert_eq!(
+            summaries,
+            [
+                fake_project_summary("g"),
+                fake_project_summary("f"),
+                fake_project_summary("e")
+            ]
+        );
+
+        assert_eq!(total, 10);
+
+        assert_eq!(
+            prev,
+            Some(
+                Seek {
+                    anchor: Anchor::Before("g".into(), 7),
+                    sort_by: SortBy::ProjectName,
+                    dir: Direction::Descending,
+                    facets: vec![]
+                }
+            )
+        );
+
+        assert_eq!(
+            next,
+            Some(
+                Seek {
+                    anchor: Anchor::After("e".into(), 5),
+                    sort_by: SortBy::ProjectName,
+                    dir: Direction::Descending,
+                    facets: vec![]
+                }
+            )
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "ten_projects"))]
+    async fn get_projects_pname_before_asc_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        let (prev, next, summaries, total, _) = core.get_projects_from(
+            Seek {
+                sort_by: SortBy::ProjectName,
+                dir: Direction::Ascending,
+                anchor: Anchor::Before("e".into(), 5),
+                facets: vec![]
+            },
+            Limit::new(3).unwrap()
+        ).await.unwrap();
+
+        assert_eq!(
+            summaries,
+            [
+                fake_project_summary("b"),
+                fake_project_summary("c"),
+                fake_project_summary("d")
+            ]
+        );
+
+        assert_eq!(total, 10);
+
+        assert_eq!(
+            prev,
+            Some(
+                Seek {
+                    anchor: Anchor::Before("b".into(), 2),
+                    sort_by: SortBy::ProjectName,
+                    dir: Direction::Ascending,
+                    facets: vec![]
+                }
+            )
+        );
+
+        assert_eq!(
+            next,
+            Some(
+                Seek {
+                    anchor: Anchor::After("d".into(), 4),
+                    sort_by: SortBy::ProjectName,
+                    dir: Direction::Ascending,
+                    facets: vec![]
+                }
+            )
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "ten_projects"))]
+    async fn get_projects_pname_before_desc_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+        let (prev, next, summaries, total, _) = core.get_projects_from(
+            Seek {
+                sort_by: SortBy::ProjectName,
+                dir: Direction::Descending,
+                anchor: Anchor::Before("e".into(), 5),
+                facets: vec![]
+            },
+            Limit::new(3).unwrap()
+        ).await.unwrap();
+
+        assert_eq!(
+            summaries,
+            [
+                fake_project_summary("h"),
+                fake_project_summary("g"),
+                fake_project_summary("f")
+            ]
+        );
+
+        assert_eq!(total, 10);
+
+        assert_eq!(
+            prev,
+            Some(
+                Seek {
+                    anchor: Anchor::Before("h".into(), 8),
+                    sort_by: SortBy::ProjectName,
+                    dir: Direction::Descending,
+                    facets: vec![]
+                }
+            )
+        );
+
+        assert_eq!(
+            next,
+            Some(
+                Seek {
+                    anchor: Anchor::After("f".into(), 6),
+                    sort_by: SortBy::ProjectName,
+                    dir: Direction::Descending,
+                    facets: vec![]
+                }
+            )
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "ten_projects"))]
+    async fn get_projects_pname_before_asc_no_prev_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        let (prev, next, summaries, total, _) = core.get_projects_from(
+            Seek {
+                sort_by: SortBy::ProjectName,
+                dir: Direction::Ascending,
+                anchor: Anchor::Before("d".into(), 4),
+                facets: vec![]
+            },
+            Limit::new(3).unwrap()
+        ).await.unwrap();
+
+        assert_eq!(
+            summaries,
+            [
+                fake_project_summary("a"),
+                fake_project_summary("b"),
+                fake_project_summary("c")
+            ]
+        );
+
+        assert_eq!(total, 10);
+
+        assert_eq!(prev, None);
+
+        assert_eq!(
+            next,
+            Some(
+                Seek {
+                    anchor: Anchor::After("c".into(), 3),
+                    sort_by: SortBy::ProjectName,
+                    dir: Direction::Ascending,
+                    facets: vec![]
+                }
+            )
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "ten_projects"))]
+    async fn get_projects_pname_after_desc_no_prev_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        let (prev, next, summaries, total, _) = core.get_projects_from(
+            Seek {
+                sort_by: SortBy::ProjectName,
+                dir: Direction::Descending,
+                anchor: Anchor::Before("g".into(), 7),
+                facets: vec![]
+            },
+            Limit::new(3).unwrap()
+        ).await.unwrap();
+
+        assert_eq!(
+            summaries,
+            [
+                fake_project_summary("j"),
+                fake_project_summary("i"),
+                fake_project_summary("h")
+            ]
+        );
+
+        assert_eq!(total, 10);
+
+        assert_eq!(prev, None);
+
+        assert_eq!(
+            next,
+            Some(
+                Seek {
+                    anchor: Anchor::After("h".into(), 8),
+                    sort_by: SortBy::ProjectName,
+                    dir: Direction::Descending,
+                    facets: vec![]
+                }
+            )
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "ten_projects"))]
+    async fn get_projects_pname_after_asc_no_next_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        let (prev, next, summaries, total, _) = core.get_projects_from(
+            Seek {
+                sort_by: SortBy::ProjectName,
+                dir: Direction::Ascending,
+                anchor: Anchor::After("g".into(), 7),
+                facets: vec![]
+            },
+            Limit::new(3).unwrap()
+        ).await.unwrap();
+
+        assert_eq!(
+            summaries,
+            [
+                fake_project_summary("h"),
+                fake_project_summary("i"),
+                fake_project_summary("j")
+            ]
+        );
+
+        assert_eq!(total, 10);
+
+        assert_eq!(
+            prev,
+            Some(
+                Seek {
+                    anchor: Anchor::Before("h".into(), 8),
+                    sort_by: SortBy::ProjectName,
+                    dir: Direction::Ascending,
+                    facets: vec![]
+                }
+            )
+        );
+
+        assert_eq!(next, None);
+    }
+
+    #[sqlx::test(fixtures("users", "ten_projects"))]
+    async fn get_projects_pname_after_desc_no_next_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        let (prev, next, summaries, total, _) = core.get_projects_from(
+            Seek {
+                sort_by: SortBy::ProjectName,
+                dir: Direction::Descending,
+                anchor: Anchor::After("d".into(), 4),
+                facets: vec![]
+            },
+            Limit::new(3).unwrap()
+        ).await.unwrap();
+
+        assert_eq!(
+            summaries,
+            [
+                fake_project_summary("c"),
+                fake_project_summary("b"),
+                fake_project_summary("a")
+            ]
+        );
+
+        assert_eq!(total, 10);
+
+        assert_eq!(
+            prev,
+            Some(
+                Seek {
+                    anchor: Anchor::Before("c".into(), 3),
+                    sort_by: SortBy::ProjectName,
+                    dir: Direction::Descending,
+                    facets: vec![]
+                }
+            )
+        );
+
+        assert_eq!(next, None);
+    }
+
+    #[sqlx::test(fixtures("users", "ten_projects"))]
+    async fn get_recent_projects_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        // same order as get_projects_mtime_start_ok
+        assert_eq!(
+            core.get_recent_projects(Limit::new(3)).await.unwrap(),
+            RecentProjects {
+                projects: vec![
+                    fake_project_summary("j"),
+                    fake_project_summary("i"),
+                    fake_project_summary("h")
+                ]
+            }
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "ten_projects"))]
+    async fn get_recent_projects_default_limit(pool: Pool) {
+        let core = ProdCore {
+            default_limit: Limit::new(2).unwrap(),
+            ..make_core(pool, fake_now, 0)
+        };
+
+        assert_eq!(
+            core.get_recent_projects(None).await.unwrap().projects,
+            [
+                fake_project_summary("j"),
+                fake_project_summary("i")
+            ]
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "ten_projects"))]
+    async fn get_projects_mtime_start_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        let (prev, next, summaries, total, _) = core.get_projects_from(
+            Seek {
+                sort_by: SortBy::ModificationTime,
+                dir: Direction::Descending,
+                anchor: Anchor::Start,
+                facets: vec![]
+            },
+            Limit::new(3).unwrap()
+        ).await.unwrap();
+
+        assert_eq!(
+            summaries,
+            [
+                fake_project_summary("j"),
+                fake_project_summary("i"),
+                fake_project_summary("h")
+            ]
+        );
+
+        assert_eq!(total, 10);
+
+        assert_eq!(prev, None);
+
+        assert_eq!(
+            next,
+            Some(
+                Seek {
+                    anchor: Anchor::After(
+                        "8".into(),
+                        8
+                    ),
+                    sort_by: SortBy::ModificationTime,
+                    dir: Direction::Descending,
+                    facets: vec![]
+                }
+            )
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "ten_projects"))]
+    async fn get_projects_mtime_end_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        let (prev, next, summaries, total, _) = core.get_projects_from(
+            Seek {
+                sort_by: SortBy::ProjectName,
+                dir: Direction::Descending,
+                anchor: Anchor::Start,
+                facets: vec![]
+            },
+            Limit::new(3).unwrap()
+        ).await.unwrap();
+
+        assert_eq!(
+            summaries,
+            [
+                fake_project_summary("j"),
+                fake_project_summary("i"),
+                fake_project_summary("h")
+            ]
+        );
+
+        assert_eq!(total, 10);
+
+        assert_eq!(prev, None);
+
+        assert_eq!(
+            next,
+            Some(
+                Seek {
+                    anchor: Anchor::After("h".into(), 8),
+                    sort_by: SortBy::ProjectName,
+                    dir: Direction::Descending,
+                    facets: vec![]
+                }
+            )
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "ten_projects"))]
+    async fn get_projects_mtime_after_asc_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        let (prev, next, summaries, total, _) = core.get_projects_from(
+            Seek {
+                sort_by: SortBy::ModificationTime,
+                dir: Direction::Ascending,
+                anchor: Anchor::After(
+                    "1".into(),
+                    1
+                ),
+                facets: vec![]
+            },
+            Limit::new(3).unwrap()
+        ).await.unwrap();
+
+        assert_eq!(
+            summaries,
+            [
+                fake_project_summary("b"),
+                fake_project_summary("c"),
+                fake_project_summary("d")
+            ]
+        );
+
+        assert_eq!(total, 10);
+
+        assert_eq!(
+            prev,
+            Some(
+                Seek {
+                    anchor: Anchor::Before(
+                        "2".into(),
+                        2
+                    ),
+                    sort_by: SortBy::ModificationTime,
+                    dir: Direction::Ascending,
+                    facets: vec![]
+                }
+            )
+        );
+
+        assert_eq!(
+            next,
+            Some(
+                Seek {
+                    anchor: Anchor::After(
+                        "4".into(),
+                        4
+                    ),
+                    sort_by: SortBy::ModificationTime,
+                    dir: Direction::Ascending,
+                    facets: vec![]
+                }
+            )
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "ten_projects"))]
+    async fn get_projects_mtime_after_asc_old_link_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        // seeks in links issued before anchors carried nanoseconds
+        let seek = "m,a,a,1970-01-01T00:00:00.000000001+00:00,,1"
+            .parse::<Seek>()
+            .unwrap();
+
+        let (prev, next, summaries, total, _) = core.get_projects_from(
+            seek,
+            Limit::new(3).unwrap()
+        ).await.unwrap();
+
+        assert_eq!(
+            summaries,
+            [
+                fake_project_summary("b"),
+                fake_project_summary("c"),
+                fake_project_summary("d")
+            ]
+        );
+
+        assert_eq!(total, 10);
+
+        assert_eq!(
+            prev,
+            Some(
+                Seek {
+                    anchor: Anchor::Before("2".into(), 2),
+                    sort_by: SortBy::ModificationTime,
+                    dir: Direction::Ascending,
+                    facets: vec![]
+                }
+            )
+        );
+
+        assert_eq!(
+            next,
+            Some(
+                Seek {
+                    anchor: Anchor::After("4".into(), 4),
+                    sort_by: SortBy::ModificationTime,
+                    dir: Direction::Ascending,
+                    facets: vec![]
+                }
+            )
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "ten_projects"))]
+    async fn get_projects_mtime_after_asc_old_link_normalized_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        // a client may normalize the timestamp from an old link
+        let seek = "m,a,a,1970-01-01T00:00:00.000000001Z,,1"
+            .parse::<Seek>()
+            .unwrap();
+
+        let (_, _, summaries, ..) = core.get_projects_from(
+            seek,
+            Limit::new(3).unwrap()
+        ).await.unwrap();
+
+        assert_eq!(
+            summaries,
+            [
+                fake_project_summary("b"),
+                fake_project_summary("c"),
+                fake_project_summary("d")
+            ]
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "ten_projects"))]
+    async fn get_projects_mtime_after_asc_bad_time(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        let seek = "m,a,a,yesterday,,1".parse::<Seek>().unwrap();
+
+        assert_eq!(
+            core.get_projects_from(seek, Limit::new(3).unwrap())
+                .await
+                .unwrap_err(),
+            CoreError::MalformedQuery
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "ten_projects"))]
+    async fn get_projects_mtime_after_desc_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        let (prev, next, summaries, total, _) = core.get_projects_from(
+            Seek {
+                sort_by: SortBy::ModificationTime,
+                dir: Direction::Descending,
+                anchor: Anchor::After(
+                    "8".into(),
+                    8
+                ),
+                facets: vec![]
+            },
+            Limit::new(3).unwrap()
+        ).await.unwrap();
+
+        assert_eq!(
+            summaries,
+            [
+                fake_project_summary("g"),
+                fake_project_summary("f"),
+                fake_project_summary("e")
+            ]
+        );
+
+        assert_eq!(total, 10);
+
+        assert_eq!(
+            prev,
+            Some(
+                Seek {
+                    anchor: Anchor::Before(
+                        "7".into(),
+                        7
+                    ),
+                    sort_by: SortBy::ModificationTime,
+                    dir: Direction::Descending,
+                    facets: vec![]
+                }
+            )
+        );
+
+        assert_eq!(
+            next,
+            Some(
+                Seek {
+                    anchor: Anchor::After(
+                        "5".into(),
+                        5
+                    ),
+                    sort_by: SortBy::ModificationTime,
+                    dir: Direction::Descending,
+                    facets: vec![]
+                }
+            )
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "ten_projects"))]
+    async fn get_projects_mtime_before_asc_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        let (prev, next, summaries, total, _) = core.get_projects_from(
+            Seek {
+                sort_by: SortBy::ModificationTime,
+                dir: Direction::Ascending,
+                anchor: Anchor::Before(
+                    "5".into(),
+                    5
+                ),
+                facets: vec![]
+            },
+            Limit::new(3).unwrap()
+        ).await.unwrap();
+
+        assert_eq!(
+            summaries,
+            [
+                fake_project_summary("b"),
+                fake_project_summary("c"),
+                fake_project_summary("d")
+            ]
+        );
+
+        assert_eq!(total, 10);
+
+        assert_eq!(
+            prev,
+            Some(
+                Seek {
+                    anchor: Anchor::Before(
+                        "2".into(),
+                        2
+                    ),
+                    sort_by: SortBy::ModificationTime,
+                    dir: Direction::Ascending,
+                    facets: vec![]
+                }
+            )
+        );
+
+        assert_eq!(
+            next,
+            Some(
+                Seek {
+                    anchor: Anchor::After(
+                        "4".into(),
+                        4
+                    ),
+                    sort_by: SortBy::ModificationTime,
+                    dir: Direction::Ascending,
+                    facets: vec![]
+                }
+            )
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "ten_projects"))]
+    async fn get_projects_mtime_before_desc_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        let (prev, next, summaries, total, _) = core.get_projects_from(
+            Seek {
+                sort_by: SortBy::ModificationTime,
+                dir: Direction::Descending,
+                anchor: Anchor::Before(
+                    "6".into(),
+                    5
+                ),
+                facets: vec![]
+            },
+            Limit::new(3).unwrap()
+        ).await.unwrap();
+
+        assert_eq!(
+            summaries,
+            [
+                fake_project_summary("h"),
+                fake_project_summary("g"),
+                fake_project_summary("f")
+            ]
+        );
+
+        assert_eq!(total, 10);
+
+        assert_eq!(
+            prev,
+            Some(
+                Seek {
+                    anchor: Anchor::Before(
+                        "8".into(),
+                        8
+                    ),
+                    sort_by: SortBy::ModificationTime,
+                    dir: Direction::Descending,
+                    facets: vec![]
+                }
+            )
+        );
+
+        assert_eq!(
+            next,
+            Some(
+                Seek {
+                    anchor: Anchor::After(
+                        "6".into(),
+                        6
+                    ),
+                    sort_by: SortBy::ModificationTime,
+                    dir: Direction::Descending,
+                    facets: vec![]
+                }
+            )
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "one_owner", "packages"))]
+    async fn delete_package_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        core.delete_package(Owner(1), Project(42), Package(2), false)
+            .await
+            .unwrap();
+
+        let proj = core.get_project(Project(42)).await.unwrap();
+        assert_eq!(
+            proj.packages.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(),
+            ["a_package", "c_package"]
+        );
+        assert_eq!(proj.revision, 4);
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "one_owner", "packages"))]
+    async fn reorder_packages_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        core.reorder_packages(
+            Owner(1),
+            Project(42),
+            &["b_package".into(), "c_package".into(), "a_package".into()]
+        ).await.unwrap();
+
+        let proj = core.get_project(Project(42)).await.unwrap();
+        assert_eq!(
+            proj.packages.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(),
+            ["b_package", "c_package", "a_package"]
+        );
+        assert_eq!(proj.revision, 4);
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "one_owner", "packages", "authors", "files"))]
+    async fn get_package_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        // the package is as it appears in the whole project
+        let pd = core.get_project(Project(42)).await.unwrap();
+        assert_eq!(
+            core.get_package(Project(42), Package(1)).await.unwrap(),
+            pd.packages.into_iter().find(|p| p.name == "a_package").unwrap()
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "one_owner", "packages", "authors", "files"))]
+    async fn get_package_releases_newest_first(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+        assert_eq!(
+            core.get_package(Project(42), Package(1)).await.unwrap()
+                .releases
+                .into_iter()
+                .map(|r| r.version)
+                .collect::<Vec<_>>(),
+            ["1.2.4", "1.2.3"]
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn get_package_wrong_project(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+        assert_eq!(
+            core.get_package(Project(6), Package(1)).await.unwrap_err(),
+            CoreError::NotAPackage
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "one_owner", "packages", "authors", "files"))]
+    async fn delete_release_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+        let version = "1.2.3".parse::<Version>().unwrap();
+
+        core.delete_release(Owner(1), Project(42), Package(1), &version)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            core.get_release_data(Project(42), Package(1), &version)
+                .await
+                .unwrap_err(),
+            CoreError::NotAVersion
+        );
+        assert_eq!(core.get_project(Project(42)).await.unwrap().revision, 4);
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages", "archived"))]
+    async fn delete_release_archived(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+        assert_eq!(
+            core.delete_release(
+                Owner(1),
+                Project(6),
+                Package(4),
+                &"1.2.3".parse::<Version>().unwrap()
+            ).await.unwrap_err(),
+            CoreError::ProjectArchived
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "one_owner", "packages"))]
+    async fn delete_package_has_releases(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+        assert_eq!(
+            core.delete_package(Owner(1), Project(42), Package(1), false)
+                .await
+                .unwrap_err(),
+            CoreError::PackageHasReleases
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "one_owner", "packages", "authors", "files"))]
+    async fn delete_package_has_releases_force(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        core.delete_package(Owner(1), Project(42), Package(1), true)
+            .await
+            .unwrap();
+
+        let proj = core.get_project(Project(42)).await.unwrap();
+        assert_eq!(
+            proj.packages.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(),
+            ["b_package", "c_package"]
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "one_owner", "packages"))]
+    async fn update_package_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        core.update_package(
+            Owner(1),
+            Project(42),
+            Package(2),
+            &PackageDataPatch {
+                name: None,
+                description: Some("Extra scenarios".into()),
+                section: Some(Some(" Extras ".into()))
+            }
+        ).await.unwrap();
+
+        let pkg = core.get_package(Project(42), Package(2)).await.unwrap();
+        assert_eq!(pkg.description, "Extra scenarios");
+        assert_eq!(pkg.section.as_deref(), Some("Extras"));
+
+        // a null section clears it; an absent description is unchanged
+        core.update_package(
+            Owner(1),
+            Project(42),
+            Package(2),
+            &PackageDataPatch {
+                name: None,
+                description: None,
+                section: Some(None)
+            }
+        ).await.unwrap();
+
+        let pkg = core.get_package(Project(42), Package(2)).await.unwrap();
+        assert_eq!(pkg.description, "Extra scenarios");
+        assert_eq!(pkg.section, None);
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "one_owner", "packages"))]
+    async fn update_package_section_too_long(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+        assert!(
+            core.update_package(
+                Owner(1),
+                Project(42),
+                Package(2),
+                &PackageDataPatch {
+                    name: None,
+                    description: None,
+                    section: Some(Some("x".repeat(PACKAGE_SECTION_MAX_LENGTH + 1)))
+                }
+            ).await.is_err()
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "one_owner", "packages"))]
+    async fn update_package_wrong_project(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+        assert_eq!(
+            core.update_package(
+                Owner(1),
+                Project(6),
+                Package(2),
+                &PackageDataPatch {
+                    name: None,
+                    description: Some("x".into()),
+                    section: None
+                }
+            ).await.unwrap_err(),
+            CoreError::NotAPackage
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "one_owner", "packages", "archived"))]
+    async fn update_package_archived(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+        assert_eq!(
+            core.update_package(
+                Owner(1),
+                Project(6),
+                Package(2),
+                &PackageDataPatch {
+                    name: None,
+                    description: Some("x".into()),
+                    section: None
+                }
+            ).await.unwrap_err(),
+            CoreError::ProjectArchived
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "one_owner", "packages", "archived"))]
+    async fn delete_package_archived(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+        assert_eq!(
+            core.delete_package(Owner(1), Project(6), Package(2), false)
+                .await
+                .unwrap_err(),
+            CoreError::ProjectArchived
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "one_owner"))]
+    async fn create_package_description_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        let pkg_data = PackageDataPost {
+            description: "Extra scenarios".into(),
+            section: None
+        };
+
+        core.create_package(Owner(1), Project(6), "ext", &pkg_data)
+            .await
+            .unwrap();
+
+        let proj = core.get_project(Project(6)).await.unwrap();
+        assert_eq!(proj.packages[0].description, "Extra scenarios");
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "one_owner"))]
+    async fn package_description_at_revision(pool: Pool) {
+        fn later_now() -> DateTime<Utc> {
+            *NOW_DT + chrono::Duration::seconds(1)
+        }
+
+        let core = make_core(pool.clone(), fake_now, 0);
+
+        let pkg_data = PackageDataPost {
+            description: "Extra scenarios".into(),
+            section: None
+        };
+
+        core.create_package(Owner(1), Project(6), "ext", &pkg_data)
+            .await
+            .unwrap();
+
+        let created = core.get_project(Project(6)).await.unwrap();
+        assert_eq!(created.packages[0].description, "Extra scenarios");
+
+        // the only package, so the first one
+        let pkg = Package(1);
+
+        let core = make_core(pool, later_now, 0);
+
+        core.update_package(
+            Owner(1),
+            Project(6),
+            pkg,
+            &PackageDataPatch {
+                name: None,
+                description: Some("More scenarios".into()),
+                section: None
+            }
+        ).await.unwrap();
+
+        let updated = core.get_project(Project(6)).await.unwrap();
+        assert_eq!(updated.packages[0].description, "More scenarios");
+
+        // each revision shows the description as it was then
+        assert_eq!(
+            core.get_project_revision(Project(6), created.revision)
+                .await
+                .unwrap()
+                .packages[0]
+                .description,
+            "Extra scenarios"
+        );
+        assert_eq!(
+            core.get_project_revision(Project(6), updated.revision)
+                .await
+                .unwrap()
+                .packages[0]
+                .description,
+            "More scenarios"
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "one_owner"))]
+    async fn create_package_section_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        let pkg_data = PackageDataPost {
+            description: "".into(),
+            section: Some(" Extensions ".into())
+        };
+
+        core.create_package(Owner(1), Project(6), "ext", &pkg_data)
+            .await
+            .unwrap();
+
+        let pkg_data = PackageDataPost {
+            description: "".into(),
+            section: None
+        };
+
+        core.create_package(Owner(1), Project(6), "main", &pkg_data)
+            .await
+            .unwrap();
+
+        let expected = vec![
+            PackageSection {
+                name: Some("Extensions".into()),
+                package_slugs: vec!["ext".into()]
+            },
+            PackageSection {
+                name: None,
+                package_slugs: vec!["main".into()]
+            }
+        ];
+
+        let proj = core.get_project(Project(6)).await.unwrap();
+        assert_eq!(proj.packages[0].section, Some("Extensions".into()));
+        assert_eq!(proj.packages[1].section, None);
+        assert_eq!(proj.package_sections, expected);
+
+        // the revision which added the packages has the same sections
+        let proj = core.get_project_revision(Project(6), 3).await.unwrap();
+        assert_eq!(proj.package_sections, expected);
+
+        // the revision before the packages existed has none
+        let proj = core.get_project_revision(Project(6), 1).await.unwrap();
+        assert_eq!(proj.package_sections, []);
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "archived"))]
+    async fn create_package_archived(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        let pkg_data = PackageDataPost {
+            description: "".into(),
+            section: None
+        };
+
+        assert_eq!(
+            core.create_package(Owner(1), Project(6), "ext", &pkg_data)
+                .await
+                .unwrap_err(),
+            CoreError::ProjectArchived
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "one_owner", "archived"))]
+    async fn unarchive_project_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        assert!(core.get_project(Project(6)).await.unwrap().archived);
+
+        let pd = ProjectDataPatch {
+            archived: Some(false),
+            ..Default::default()
+        };
+
+        core.update_project(Owner(1), Project(6), &pd, None).await.unwrap();
+
+        assert!(!core.get_project(Project(6)).await.unwrap().archived);
+
+        let pkg_data = PackageDataPost {
+            description: "".into(),
+            section: None
+        };
+
+        core.create_package(Owner(1), Project(6), "ext", &pkg_data)
+            .await
+            .unwrap();
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "one_owner"))]
+    async fn update_project_superseded_by_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        // link and archive the old project in one go
+        let pd = ProjectDataPatch {
+            superseded_by: Some(Some("test_game".into())),
+            archived: Some(true),
+            ..Default::default()
+        };
+
+        core.update_project(Owner(1), Project(6), &pd, None).await.unwrap();
+
+        let old = core.get_project(Project(6)).await.unwrap();
+        assert!(old.archived);
+        assert_eq!(old.superseded_by.unwrap().name, "test_game");
+
+        assert_eq!(
+            core.get_project(Project(42)).await.unwrap().supersedes,
+            ["a_game"]
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "one_owner", "superseded"))]
+    async fn update_project_superseded_by_clear(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        let pd = ProjectDataPatch {
+            superseded_by: Some(None),
+            ..Default::default()
+        };
+
+        core.update_project(Owner(1), Project(6), &pd, None).await.unwrap();
+
+        assert_eq!(core.get_project(Project(6)).await.unwrap().superseded_by, None);
+        assert!(core.get_project(Project(42)).await.unwrap().supersedes.is_empty());
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "one_owner"))]
+    async fn update_project_superseded_by_not_a_project(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        let pd = ProjectDataPatch {
+            superseded_by: Some(Some("bogus".into())),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            core.update_project(Owner(1), Project(6), &pd, None).await.unwrap_err(),
+            CoreError::InvalidSupersession
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "one_owner", "archived"))]
+    async fn update_project_superseded_by_archived(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        let pd = ProjectDataPatch {
+            superseded_by: Some(Some("a_game".into())),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            core.update_project(Owner(1), Project(42), &pd, None).await.unwrap_err(),
+            CoreError::InvalidSupersession
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "one_owner", "superseded"))]
+    async fn update_project_superseded_by_cycle(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        let pd = ProjectDataPatch {
+            superseded_by: Some(Some("a_game".into())),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            core.update_project(Owner(1), Project(42), &pd, None).await.unwrap_err(),
+            CoreError::InvalidSupersession
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "superseded"))]
+    async fn get_projects_superseded(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        let params = ProjectsParams {
+            seek: Seek {
+                facets: vec![Facet::Superseded(false)],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        assert_eq!(
+            core.get_projects(params)
+                .await
+                .unwrap()
+                .projects
+                .into_iter()
+                .map(|p| p.name)
+                .collect::<Vec<_>>(),
+            ["test_game"]
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "one_owner"))]
+    async fn get_projects_approximate_count(pool: Pool) {
+        let core = ProdCore {
+            count_cache: CountCache::new(60),
+            ..make_core(pool, fake_now, 0)
+        };
+
+        // the first unfiltered count is exact and fills the cache
+        let meta = core.get_projects(ProjectsParams::default())
+            .await
+            .unwrap()
+            .meta;
+        assert_eq!((meta.total, meta.approximate), (2, false));
+
+        let pd = ProjectDataPatch {
+            archived: Some(true),
+            ..Default::default()
+        };
+        core.update_project(Owner(1), Project(6), &pd, None).await.unwrap();
+
+        // the next unfiltered count comes from the cache
+        let meta = core.get_projects(ProjectsParams::default())
+            .await
+            .unwrap()
+            .meta;
+        assert_eq!((meta.total, meta.approximate), (2, true));
+
+        // filtered counts are always exact
+        let params = ProjectsParams {
+            seek: Seek {
+                facets: vec![Facet::Archived(false)],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let meta = core.get_projects(params).await.unwrap().meta;
+        assert_eq!((meta.total, meta.approximate), (1, false));
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "archived"))]
+    async fn get_projects_archived(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        let names = |projects: Projects| projects.projects
+            .into_iter()
+            .map(|p| p.name)
+            .collect::<Vec<_>>();
+
+        // archived projects are excluded unless requested
+        assert_eq!(
+            names(core.get_projects(ProjectsParams::default()).await.unwrap()),
+            ["test_game"]
+        );
+
+        let params = ProjectsParams {
+            seek: Seek {
+                facets: vec![Facet::Archived(true)],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        assert_eq!(
+            names(core.get_projects(params).await.unwrap()),
+            ["a_game", "test_game"]
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "one_owner"))]
+    async fn create_package_section_too_long(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        let pkg_data = PackageDataPost {
+            description: "".into(),
+            section: Some("x".repeat(PACKAGE_SECTION_MAX_LENGTH + 1))
+        };
+
+        assert_eq!(
+            core.create_package(Owner(1), Project(6), "ext", &pkg_data)
+                .await
+                .unwrap_err(),
+            CoreError::InvalidPackageSection
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "two_owners", "packages", "authors"))]
+    async fn get_random_project_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+        let proj = core.get_random_project().await.unwrap();
+        let expected = core.get_project(
+            core.get_project_id(&proj.name).await.unwrap()
+        ).await.unwrap();
+        assert_eq!(proj, expected);
+    }
+
+    #[sqlx::test(fixtures("users"))]
+    async fn get_random_project_empty(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+        assert_eq!(
+            core.get_random_project().await.unwrap_err(),
+            CoreError::NotFound
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "two_owners", "packages", "package_descriptions", "files", "authors"))]
+    async fn get_project_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+        assert_eq!(
+            core.get_project(Project(42)).await.unwrap(),
+            ProjectData {
+                name: "test_game".into(),
+                description: "Brian's Trademarked Game of Being a Test Case".into(),
+                revision: 3,
+                created_at: "2023-11-12T15:50:06.419538067+00:00".into(),
+                modified_at: "2023-12-14T15:50:06.419538067+00:00".into(),
+                tags: vec![],
+                game: GameData {
+                    title: "A Game of Tests".into(),
+                    title_sort_key: "Game of Tests, A".into(),
+                    publisher: "Test Game Company".into(),
+                    year: "1979".into()
+                },
+                readme: "".into(),
+                image: None,
+                archived: false,
+                superseded_by: None,
+                supersedes: vec![],
+                owners: vec!["alice".into(), "bob".into()],
+                package_sections: vec![
+                    PackageSection {
+                        name: None,
+                        package_slugs: vec!["a_package".into(), "b_package".into(), "c_package".into()]
+                    }
+                ],
+                packages: vec![
+                    PackageData {
+                        name: "a_package".into(),
+                        description: "The module".into(),
+                        section: None,
+                        releases: vec![
+                            FileData {
+                                version: "1.2.4".into(),
+                                filename: "a_package-1.2.4".into(),
+                                url: "https://example.com/a_package-1.2.4".into(),
+                                size: 5678,
+                                checksum: "79fdd8fe3128f818e446e919cce5dcfb81815f8f4341c53f4d6b58ded48cebf2".into(),
+                                published_at: "2023-12-10T15:56:29.180282477+00:00".into(),
+                                published_by: "alice".into(),
+                                requires: "".into(),
+                                authors: vec!["alice".into(), "bob".into()],
+                                primary_file: Some("a_package-1.2.4-fixed.vmod".into())
+                            },
+                            FileData {
+                                version: "1.2.3".into(),
+                                filename: "a_package-1.2.3".into(),
+                                url: "https://example.com/a_package-1.2.3".into(),
+                                size: 1234,
+                                checksum: "c0e0fa7373a12b45a91e4f4d4e2e186442fc6ee9b346caa2fdc1c09026a2144a".into(),
+                                published_at: "2023-12-09T15:56:29.180282477+00:00".into(),
+                                published_by: "bob".into(),
+                                requires: "".into(),
+                                authors: vec!["alice".into()],
+                                primary_file: Some("maps-1.2.3.zip".into())
+                            }
+                        ],
+                        files: vec![
+                            FileData {
+                                version: "1.2.4".into(),
+                                filename: "a_package-1.2.4.vmod".into(),
+                                url: "https://example.com/a_package-1.2.4.vmod".into(),
+                                size: 5678,
+                                checksum: "79fdd8fe3128f818e446e919cce5dcfb81815f8f4341c53f4d6b58ded48cebf2".into(),
+                                published_at: "2023-12-10T15:56:29.180282477+00:00".into(),
+                                published_by: "alice".into(),
+                                requires: "".into(),
+                                authors: vec!["alice".into(), "bob".into()],
+                                primary_file: None
+                            },
+                            FileData {
+                                version: "1.2.4".into(),
+                                filename: "a_package-1.2.4-fixed.vmod".into(),
+                                url: "https://example.com/a_package-1.2.4-fixed.vmod".into(),
+                                size: 5679,
+                                checksum: "0d7e3f1ad1d1a5f0bcf0a39e8d3b8e8c4d0c8b9a9f7e1a2c3b4d5e6f7a8b9c0d".into(),
+                                published_at: "2023-12-11T15:56:29.180282477+00:00".into(),
+                                published_by: "alice".into(),
+                                requires: "".into(),
+                                authors: vec!["alice".into(), "bob".into()],
+                                primary_file: None
+                            },
+                            FileData {
+                                version: "1.2.4".into(),
+                                filename: "notes-1.2.4.txt".into(),
+                                url: "https://example.com/notes-1.2.4.txt".into(),
+                                size: 99,
+                                checksum: "5f6e7d8c9b0a1f2e3d4c5b6a7f8e9d0c1b2a3f4e5d6c7b8a9f0e1d2c3b4a5f6e".into(),
+                                published_at: "2023-12-12T15:56:29.180282477+00:00".into(),
+                                published_by: "alice".into(),
+                                requires: "".into(),
+                                authors: vec!["alice".into(), "bob".into()],
+                                primary_file: None
+                            },
+                            FileData {
+                                version: "1.2.3".into(),
+                                filename: "a_package-1.2.3.vmod".into(),
+                                url: "https://example.com/a_package-1.2.3.vmod".into(),
+                                size: 1234,
+                                checksum: "c0e0fa7373a12b45a91e4f4d4e2e186442fc6ee9b346caa2fdc1c09026a2144a".into(),
+                                published_at: "2023-12-09T15:56:29.180282477+00:00".into(),
+                                published_by: "bob".into(),
+                                requires: "".into(),
+                                authors: vec!["alice".into()],
+                                primary_file: None
+                            },
+                            FileData {
+                                version: "1.2.3".into(),
+                                filename: "maps-1.2.3.zip".into(),
+                                url: "https://example.com/maps-1.2.3.zip".into(),
+                                size: 4321,
+                                checksum: "3a94cbc9b8e0e64c4a71ffb3b2b1b5ab4e1d4c2a2e3d6b1bbf2a70b5c9c1f2d0".into(),
+                                published_at: "2023-12-09T15:56:29.180282477+00:00".into(),
+                                published_by: "bob".into(),
+                                requires: "".into(),
+                                authors: vec!["alice".into()],
+                                primary_file: None
+                            }
+                        ],
+                        recommended_release: None
+                    },
+                    PackageData {
+                        name: "b_package".into(),
+                        description: "".into(),
+                        section: None,
+                        releases: vec![],
+                        files: vec![],
+                        recommended_release: None
+                    },
+                    PackageData {
+                        name: "c_package".into(),
+                        description: "Extra scenarios".into(),
+                        section: None,
+                        releases: vec![
+                            FileData {
+                                version: "0.1.0".into(),
+                                filename: "c_package-0.1.0".into(),
+                                url: "https://example.com/c_package-0.1.0".into(),
+                                size: 123456,
+                                checksum: "a8f515e9e2de99919d1a987733296aaa951a4ba2aa0f7014c510bdbd60dc0efd".into(),
+                                published_at: "2023-12-15T15:56:29.180282477+00:00".into(),
+                                published_by: "chuck".into(),
+                                requires: "".into(),
+                                authors: vec![],
+                                primary_file: None
+                            }
+                        ],
+                        files: vec![],
+                        recommended_release: None
+                    }
+                ],
+                modified_by: Some("bob".into()),
+                gallery: vec![]
+            }
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "two_owners", "packages", "authors"))]
+    async fn get_project_revision_ok_current(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+        assert_eq!(
+            core.get_project_revision(Project(42), 3).await.unwrap(),
+            ProjectData {
+                name: "test_game".into(),
+                description: "Brian's Trademarked Game of Being a Test Case".into(),
+                revision: 3,
+                created_at: "2023-11-12T15:50:06.419538067+00:00".into(),
+                modified_at: "2023-12-14T15:50:06.419538067+00:00".into(),
+                tags: vec![],
+                game: GameData {
+                    title: "A Game of Tests".into(),
+                    title_sort_key: "Game of Tests, A".into(),
+                    publisher: "Test Game Company".into(),
+                    year: "1979".into()
+                },
+                readme: "".into(),
+                image: None,
+                archived: false,
+                superseded_by: None,
+                supersedes: vec![],
+                owners: vec!["alice".into(), "bob".into()],
+                package_sections: vec![
+                    PackageSection {
+                        name: None,
+                        package_slugs: vec!["a_package".into(), "b_package".into(), "c_package".into()]
+                    }
+                ],
+                packages: vec![
+                    PackageData {
+                        name: "a_package".into(),
+                        description: "".into(),
+                        section: None,
+                        releases: vec![
+                            FileData {
+                                version: "1.2.4".into(),
+                                filename: "a_package-1.2.4".into(),
+                                url: "https://example.com/a_package-1.2.4".into(),
+                                size: 5678,
+                                checksum: "79fdd8fe3128f818e446e919cce5dcfb81815f8f4341c53f4d6b58ded48cebf2".into(),
+                                published_at: "2023-12-10T15:56:29.180282477+00:00".into(),
+                                published_by: "alice".into(),
+                                requires: "".into(),
+                                authors: vec!["alice".into(), "bob".into()],
+                                primary_file: None
+                            },
+                            FileData {
+                                version: "1.2.3".into(),
+                                filename: "a_package-1.2.3".into(),
+                                url: "https://example.com/a_package-1.2.3".into(),
+                                size: 1234,
+                                checksum: "c0e0fa7373a12b45a91e4f4d4e2e186442fc6ee9b346caa2fdc1c09026a2144a".into(),
+                                published_at: "2023-12-09T15:56:29.180282477+00:00".into(),
+                                published_by: "bob".into(),
+                                requires: "".into(),
+                                authors: vec!["alice".into()],
+                                primary_file: None
+                            }
+                        ],
+                        files: vec![],
+                        recommended_release: None
+                    },
+                    PackageData {
+                        name: "b_package".into(),
+                        description: "".into(),
+                        section: None,
+                        releases: vec![],
+                        files: vec![],
+                        recommended_release: None
+                    },
+                    PackageData {
+                        name: "c_package".into(),
+                        description: "".into(),
+                        section: None,
+                        releases: vec![],
+                        files: vec![],
+                        recommended_release: None
+                    }
+                ],
+                modified_by: Some("bob".into()),
+                gallery: vec![]
+            }
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn get_project_revisions_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+        assert_eq!(
+            core.get_project_revisions(Project(42)).await.unwrap(),
+            ProjectRevisions {
+                revisions: vec![
+                    ProjectRevisionSummary {
+                        revision: 3,
+                        modified_at: "2023-12-14T15:50:06.419538067+00:00".into(),
+                        modified_by: "bob".into()
+                    },
+                    ProjectRevisionSummary {
+                        revision: 1,
+                        modified_at: "2023-11-12T15:50:06.419538067+00:00".into(),
+                        modified_by: "bob".into()
+                    }
+                ]
+            }
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "two_owners", "packages"))]
+    async fn get_project_revision_ok_old(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+        assert_eq!(
+            core.get_project_revision(Project(42), 1).await.unwrap(),
+            ProjectData {
+                name: "test_game".into(),
+                description: "Brian's Trademarked Game of Being a Test Case".into(),
+                revision: 1,
+                created_at: "2023-11-12T15:50:06.419538067+00:00".into(),
+                modified_at: "2023-11-12T15:50:06.419538067+00:00".into(),
+                tags: vec![],
+                game: GameData {
+                    title: "A Game of Tests".into(),
+                    title_sort_key: "Game of Tests, A".into(),
+                    publisher: "Test Game Company".into(),
+                    year: "1978".into()
+                },
+                readme: "".into(),
+                image: None,
+                archived: false,
+                superseded_by: None,
+                supersedes: vec![],
+                owners: vec!["alice".into(), "bob".into()],
+                package_sections: vec![
+                    PackageSection {
+                        name: None,
+                        package_slugs: vec!["b_package".into(), "c_package".into()]
+                    }
+                ],
+                packages: vec![
+                    PackageData {
+                        name: "b_package".into(),
+                        description: "".into(),
+                        section: None,
+                        releases: vec![],
+                        files: vec![],
+                        recommended_release: None
+                    },
+                    PackageData {
+                        name: "c_package".into(),
+                        description: "".into(),
+                        section: None,
+                        releases: vec![],
+                        files: vec![],
+                        recommended_release: None
+                    }
+                ],
+                modified_by: Some("bob".into()),
+                gallery: vec![]
+            }
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "two_owners", "packages"))]
+    async fn get_project_revision_batch_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        // 7 is out of range and 1 is repeated; both are skipped
+        let batch = core.get_project_revision_batch(Project(42), &[3, 1, 7, 1])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            batch.revisions,
+            [
+                core.get_project_revision(Project(42), 3).await.unwrap(),
+                core.get_project_revision(Project(42), 1).await.unwrap()
+            ]
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn get_project_revision_batch_too_many(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+        let revisions = (1..=MAX_REVISION_BATCH as i64 + 1).collect::<Vec<_>>();
+
+        assert_eq!(
+            core.get_project_revision_batch(Project(42), &revisions)
+                .await
+                .unwrap_err(),
+            CoreError::LimitOutOfRange
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn get_project_revision_batch_not_a_project(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        assert_eq!(
+            core.get_project_revision_batch(Project(0), &[1])
+                .await
+                .unwrap_err(),
+            CoreError::NotAProject
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn create_project_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        let user = User(1);
+        let name = "newproj";
+        let data = ProjectData {
+            name: name.into(),
+            description: "A New Game".into(),
+            revision: 1,
+            created_at: NOW.into(),
+            modified_at: NOW.into(),
+            tags: vec![],
+            game: GameData {
+                title: "Some New Game".into(),
+                title_sort_key: "Some New Game".into(),
+                publisher: "XYZ Games".into(),
+                year: "1999".into()
+            },
+            readme: "".into(),
+            image: None,
+            archived: false,
+            superseded_by: None,
+            supersedes: vec![],
+            owners: vec!["bob".into()],
+            package_sections: vec![],
+            packages: vec![],
+            modified_by: Some("bob".into()),
+            gallery: vec![]
+        };
+
+        let cdata = ProjectDataPost {
+            description: data.description.clone(),
+            tags: vec![],
+            game: GameData {
+                title: data.game.title.clone(),
+                title_sort_key: data.game.title_sort_key.clone(),
+                publisher: data.game.publisher.clone(),
+                year: data.game.year.clone()
+            },
+            readme: "".into(),
+            image: None,
+            packages: vec![]
+        };
+
+        core.create_project(user, name, &cdata).await.unwrap();
+        let proj = core.get_project_id(name).await.unwrap();
+        assert_eq!(core.get_project(proj).await.unwrap(), data);
     }
-    else {
-        // the next page is before the first item
-        let first = projects.first().expect("element must exist");
 
-        let next_anchor = match anchor {
-            Anchor::BeforeQuery(ref q, ..) => Anchor::AfterQuery(
-                q.clone(),
-                first.sort_field(sort_by)?,
-                first.project_id as u32
-            ),
-            Anchor::Before(..) => Anchor::After(
-                first.sort_field(sort_by)?,
-                first.project_id as u32
-            ),
-            Anchor::Start |
-            Anchor::After(..) |
-            Anchor::StartQuery(..) |
-            Anchor::AfterQuery(..) => unreachable!()
-        };
+    fn new_project_post(packages: Vec<ProjectPackagePost>) -> ProjectDataPost {
+        ProjectDataPost {
+            description: "A New Game".into(),
+            tags: vec![],
+            game: GameData {
+                title: "Some New Game".into(),
+                title_sort_key: "Some New Game".into(),
+                publisher: "XYZ Games".into(),
+                year: "1999".into()
+            },
+            readme: "".into(),
+            image: None,
+            packages
+        }
+    }
 
-        Ok(Some(Seek { anchor: next_anchor, sort_by, dir }))
+    fn package_post(name: &str, section: Option<&str>) -> ProjectPackagePost {
+        ProjectPackagePost {
+            name: name.into(),
+            description: format!("The {name}"),
+            section: section.map(String::from)
+        }
     }
-}
 
-fn get_next_for_after(
-    anchor: &Anchor,
-    sort_by: SortBy,
-    dir: Direction,
-    limit_extra: u32,
-    projects: &mut Vec<ProjectSummaryRow>
-) -> Result<Option<Seek>, CoreError>
-{
-    // make the next link
-    if projects.len() == limit_extra as usize {
-        // there are more pages in the forward direction
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn create_project_with_packages_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
 
-        // remove the "extra" item which proves we are not at the end
-        projects.pop();
+        let cdata = new_project_post(vec![
+            package_post("module", None),
+            package_post("scenarios", Some(" Extras "))
+        ]);
 
-        // the next page is after the last item
-        let last = projects.last().expect("element must exist");
+        let proj = core.create_project(User(1), "newproj", &cdata)
+            .await
+            .unwrap();
+        assert_eq!(core.get_project_id("newproj").await.unwrap(), proj);
 
-        let next_anchor = match anchor {
-            Anchor::StartQuery(ref q) |
-            Anchor::AfterQuery(ref q, ..) => Anchor::AfterQuery(
-                q.clone(),
-                last.sort_field(sort_by)?,
-                last.project_id as u32
-            ),
-            Anchor::Start |
-            Anchor::After(..) => Anchor::After(
-                last.sort_field(sort_by)?,
-                last.project_id as u32
-            ),
-            Anchor::Before(..) |
-            Anchor::BeforeQuery(..) => unreachable!()
-        };
+        let pd = core.get_project(proj).await.unwrap();
+        assert_eq!(pd.revision, 1);
+        assert_eq!(
+            pd.packages.iter()
+                .map(|p| (p.name.as_str(), p.description.as_str(), p.section.as_deref()))
+                .collect::<Vec<_>>(),
+            [
+                ("module", "The module", None),
+                ("scenarios", "The scenarios", Some("Extras"))
+            ]
+        );
+    }
 
-        Ok(Some(Seek { anchor: next_anchor, sort_by, dir }))
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn create_project_invalid_name(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        assert_eq!(
+            core.create_project(User(1), "a.b", &new_project_post(vec![]))
+                .await
+                .unwrap_err(),
+            CoreError::InvalidProjectName
+        );
     }
-    else {
-        // there are no pages in the forward direction
-        Ok(None)
+
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn create_project_reserved_name(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        assert_eq!(
+            core.create_project(User(1), "random", &new_project_post(vec![]))
+                .await
+                .unwrap_err(),
+            CoreError::InvalidProjectName
+        );
     }
-}
 
-fn get_prev_for_after(
-    anchor: &Anchor,
-    sort_by: SortBy,
-    dir: Direction,
-    projects: &[ProjectSummaryRow]
-) -> Result<Option<Seek>, CoreError>
-{
-    // make the prev link
-    match anchor {
-        _ if projects.is_empty() => Ok(None),
-        Anchor::Start |
-        Anchor::StartQuery(_) => Ok(None),
-        Anchor::After(..) |
-        Anchor::AfterQuery(..) => {
-            // the previous page is before the first item
-            let first = projects.first().expect("element must exist");
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn create_project_name_in_use_accents(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
 
-            let prev_anchor = match anchor {
-                Anchor::AfterQuery(ref q, ..) => Anchor::BeforeQuery(
-                    q.clone(),
-                    first.sort_field(sort_by)?,
-                    first.project_id as u32
-                ),
-                Anchor::After(..) => Anchor::Before(
-                    first.sort_field(sort_by)?,
-                    first.project_id as u32
-                ),
-                Anchor::Start |
-                    Anchor::StartQuery(..) |
-                Anchor::Before(..) |
-                Anchor::BeforeQuery(..) => unreachable!()
-            };
+        // precomposed é
+        core.create_project(User(1), "caf\u{e9}", &new_project_post(vec![]))
+            .await
+            .unwrap();
 
-            Ok(Some(Seek { anchor: prev_anchor, sort_by, dir }))
-        },
-        Anchor::Before(..) |
-        Anchor::BeforeQuery(..) => unreachable!()
+        // E followed by a combining acute accent
+        assert_eq!(
+            core.create_project(User(1), "CAFE\u{301}", &new_project_post(vec![]))
+                .await
+                .unwrap_err(),
+            CoreError::ProjectNameInUse
+        );
     }
-}
-
-fn get_links(
-    anchor: &Anchor,
-    sort_by: SortBy,
-    dir: Direction,
-    limit_extra: u32,
-    projects: &mut Vec<ProjectSummaryRow>
-) -> Result<(Option<Seek>, Option<Seek>), CoreError>
-{
-    match anchor {
-        Anchor::Before(..) |
-        Anchor::BeforeQuery(..) => {
-            let prev = get_prev_for_before(
-                anchor,
-                sort_by,
-                dir,
-                limit_extra,
-                projects
-            )?;
 
-            let next = get_next_for_before(
-                anchor,
-                sort_by,
-                dir,
-                projects
-            )?;
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn create_project_invalid_packages(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
 
-            Ok((prev, next))
-        },
-        Anchor::Start |
-        Anchor::StartQuery(..) |
-        Anchor::After(..) |
-        Anchor::AfterQuery(..) => {
-            let next = get_next_for_after(
-                anchor,
-                sort_by,
-                dir,
-                limit_extra,
-                projects
-            )?;
+        let cdata = new_project_post(vec![
+            package_post("module", None),
+            package_post("bad/name", None),
+            package_post("module", Some(&"x".repeat(PACKAGE_SECTION_MAX_LENGTH + 1)))
+        ]);
 
-            let prev = get_prev_for_after(
-                anchor,
-                sort_by,
-                dir,
-                projects
-            )?;
+        assert_eq!(
+            core.create_project(User(1), "newproj", &cdata).await.unwrap_err(),
+            CoreError::InvalidPackages(vec![
+                "packages[1].name is invalid".into(),
+                "packages[2].name is a duplicate".into(),
+                "packages[2].section is invalid".into()
+            ])
+        );
 
-            Ok((prev, next))
-        }
+        // nothing was created
+        assert_eq!(
+            core.get_project_id("newproj").await.unwrap_err(),
+            CoreError::NotAProject
+        );
     }
-}
 
-impl ProjectSummaryRow {
-    fn sort_field(&self, sort_by: SortBy) -> Result<String, CoreError> {
-        Ok(
-            match sort_by {
-                SortBy::ProjectName => self.name.clone(),
-                SortBy::GameTitle => self.game_title_sort.clone(),
-                SortBy::ModificationTime => nanos_to_rfc3339(self.modified_at)?,
-                SortBy::CreationTime => nanos_to_rfc3339(self.created_at)?,
-                SortBy::Relevance => self.rank.to_string()
-            }
-        )
-    }
-}
+    #[sqlx::test(fixtures("users", "projects", "two_owners"))]
+    async fn update_project_modified_by(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
 
-impl TryFrom<ProjectSummaryRow> for ProjectSummary {
-    type Error = CoreError;
+        let cdata = ProjectDataPatch {
+            description: Some("new description".into()),
+            ..Default::default()
+        };
 
-    fn try_from(r: ProjectSummaryRow) -> Result<Self, Self::Error> {
-        Ok(
-            ProjectSummary {
-                name: r.name,
-                description: r.description,
-                revision: r.revision,
-                created_at: nanos_to_rfc3339(r.created_at)?,
-                modified_at: nanos_to_rfc3339(r.modified_at)?,
-                tags: vec![],
-                game: GameData {
-                    title: r.game_title,
-                    title_sort_key: r.game_title_sort,
-                    publisher: r.game_publisher,
-                    year: r.game_year
-                }
-            }
-        )
+        core.update_project(Owner(2), Project(42), &cdata, None).await.unwrap();
+
+        // alice made the new revision
+        assert_eq!(
+            core.get_project(Project(42)).await.unwrap().modified_by,
+            Some("alice".into())
+        );
+        assert_eq!(
+            core.get_project_revision(Project(42), 4).await.unwrap().modified_by,
+            Some("alice".into())
+        );
+
+        // bob made the old one
+        assert_eq!(
+            core.get_project_revision(Project(42), 3).await.unwrap().modified_by,
+            Some("bob".into())
+        );
     }
-}
 
-#[cfg(test)]
-mod test {
-    use super::*;
+    #[sqlx::test(fixtures("users", "projects", "one_owner"))]
+    async fn update_project_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        let name = "test_game";
+        let new_data = ProjectData {
+            name: name.into(),
+            description: "new description".into(),
+            revision: 4,
+            created_at: "2023-11-12T15:50:06.419538067+00:00".into(),
+            modified_at: NOW.into(),
+            tags: vec![],
+            game: GameData {
+                title: "Some New Game".into(),
+                title_sort_key: "Some New Game".into(),
+                publisher: "XYZ Games".into(),
+                year: "1999".into()
+            },
+            readme: "".into(),
+            image: None,
+            archived: false,
+            superseded_by: None,
+            supersedes: vec![],
+            owners: vec!["bob".into()],
+            package_sections: vec![],
+            packages: vec![],
+            modified_by: Some("bob".into()),
+            gallery: vec![]
+        };
 
-    use crate::{
-        model::GameDataPatch,
-        pagination::Direction,
-        sqlite::{Pool, SqlxDatabaseClient},
-        upload::UploadError
-    };
+        let cdata = ProjectDataPatch {
+            name: None,
+            description: Some(new_data.description.clone()),
+            tags: Some(vec![]),
+            game: GameDataPatch {
+                title: Some(new_data.game.title.clone()),
+                title_sort_key: Some(new_data.game.title_sort_key.clone()),
+                publisher: Some(new_data.game.publisher.clone()),
+                year: Some(new_data.game.year.clone())
+            },
+            readme: Some("".into()),
+            image: None,
+            archived: None,
+            superseded_by: None
+        };
 
-    const NOW: &str = "2023-11-12T15:50:06.419538067+00:00";
+        let proj = core.get_project_id(name).await.unwrap();
+        let old_data = core.get_project(proj).await.unwrap();
+        core.update_project(Owner(1), Project(42), &cdata, None).await.unwrap();
+        // project has new data
+        assert_eq!(core.get_project(proj).await.unwrap(), new_data);
+        // old data is kept as a revision
+        assert_eq!(
+            core.get_project_revision(proj, 3).await.unwrap(),
+            old_data
+        );
+    }
 
-    static NOW_DT: Lazy<DateTime<Utc>> = Lazy::new(||
-        DateTime::parse_from_rfc3339(NOW)
-            .unwrap()
-            .with_timezone(&Utc)
-    );
+    #[sqlx::test(fixtures("users", "projects", "one_owner"))]
+    async fn update_project_title_and_publisher(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
 
-    fn fake_now() -> DateTime<Utc> {
-        *NOW_DT
-    }
+        // the sort key must not be lost when other game fields change too
+        let cdata = ProjectDataPatch {
+            game: GameDataPatch {
+                title: Some("The New Title".into()),
+                title_sort_key: Some("New Title, The".into()),
+                publisher: Some("XYZ Games".into()),
+                year: None
+            },
+            ..Default::default()
+        };
 
-    struct FakeUploader {}
+        core.update_project(Owner(1), Project(42), &cdata, None).await.unwrap();
 
-    #[async_trait]
-    impl Uploader for FakeUploader {
-        async fn upload<S>(
-            &self,
-            _filename: &str,
-            _stream: S
-        ) -> Result<String, UploadError>
-        where
-            S: Stream<Item = Result<Bytes, io::Error>> + Send
-        {
-            unreachable!();
-        }
-    }
+        let row = core.db.get_project_row(Project(42)).await.unwrap();
+        assert_eq!(row.game_title, "The New Title");
+        assert_eq!(row.game_title_sort, "New Title, The");
+        assert_eq!(row.game_publisher, "XYZ Games");
+        assert_eq!(row.game_year, "1979");
 
-    fn make_core(
-        pool: Pool,
-        now: fn() -> DateTime<Utc>,
-        max_image_size: u64
-    ) -> ProdCore<SqlxDatabaseClient<sqlx::sqlite::Sqlite>, FakeUploader>
-    {
-        ProdCore {
-            db: SqlxDatabaseClient(pool),
-            uploader: FakeUploader {},
-            now,
-            max_image_size
-        }
+        // the new revision records the same sort key
+        assert_eq!(
+            core.get_project_revision(Project(42), 4)
+                .await
+                .unwrap()
+                .game
+                .title_sort_key,
+            "New Title, The"
+        );
     }
 
-    fn fake_project_summary(name: &str) -> ProjectSummary {
-        ProjectSummary {
-            name: name.into(),
-            description: "".into(),
-            revision: 1,
-            created_at: "1970-01-01T00:00:00+00:00".into(),
-            modified_at: format!(
-                "1970-01-01T00:00:00.0000000{:02}+00:00",
-                name.as_bytes()[0] - b'a' + 1
-            ),
-            tags: vec![],
-            game: GameData {
-                title: "".into(),
-                title_sort_key: "".into(),
-                publisher: "".into(),
-                year: "".into()
-            }
-        }
-    }
+    #[sqlx::test(fixtures("users", "projects", "one_owner"))]
+    async fn update_project_rename_invalid_name(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
 
-    #[test]
-    fn check_new_project_name_ok() {
-        check_new_project_name("acceptable_name").unwrap();
-    }
+        let cdata = ProjectDataPatch {
+            name: Some("-bad name".into()),
+            ..Default::default()
+        };
 
-    #[test]
-    fn check_new_project_name_non_ascii() {
         assert_eq!(
-            check_new_project_name("💩").unwrap_err(),
+            core.update_project(Owner(1), Project(42), &cdata, None)
+                .await
+                .unwrap_err(),
             CoreError::InvalidProjectName
         );
     }
 
-    #[test]
-    fn check_new_project_name_leading_non_alphanumeric() {
+    #[sqlx::test(fixtures("users", "projects", "one_owner"))]
+    async fn update_project_rename_reserved_name(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        let cdata = ProjectDataPatch {
+            name: Some("random".into()),
+            ..Default::default()
+        };
+
         assert_eq!(
-            check_new_project_name("-abc").unwrap_err(),
+            core.update_project(Owner(1), Project(42), &cdata, None)
+                .await
+                .unwrap_err(),
             CoreError::InvalidProjectName
         );
     }
 
-    #[test]
-    fn check_new_project_name_too_short() {
+    #[sqlx::test(fixtures("users", "projects", "one_owner", "tags"))]
+    async fn get_project_diff_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
         assert_eq!(
-            check_new_project_name("").unwrap_err(),
-            CoreError::InvalidProjectName
+            core.get_project_diff(Project(42), 1, 3).await.unwrap(),
+            ProjectDiff {
+                changed_fields: [
+                    (
+                        "game.year".into(),
+                        FieldChange {
+                            from: "1978".into(),
+                            to: "1979".into()
+                        }
+                    ),
+                    (
+                        "tags".into(),
+                        FieldChange {
+                            from: serde_json::json!(["wwii"]),
+                            to: serde_json::json!(["tactical", "wwii"])
+                        }
+                    )
+                ].into()
+            }
         );
     }
 
-    #[test]
-    fn check_new_project_name_too_long() {
+    #[sqlx::test(fixtures("users", "projects", "one_owner", "tags"))]
+    async fn get_project_diff_same_revision(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
         assert_eq!(
-            check_new_project_name(&"x".repeat(100)).unwrap_err(),
-            CoreError::InvalidProjectName
+            core.get_project_diff(Project(42), 3, 3).await.unwrap_err(),
+            CoreError::MalformedQuery
         );
     }
 
-    #[sqlx::test(fixtures("users", "ten_projects"))]
-    async fn get_projects_pname_start_ok(pool: Pool) {
+    #[sqlx::test(fixtures("users", "projects", "one_owner", "tags"))]
+    async fn get_project_diff_not_a_revision(pool: Pool) {
         let core = make_core(pool, fake_now, 0);
 
-        let (prev, next, summaries, total) = core.get_projects_from(
-            Seek {
-                sort_by: SortBy::ProjectName,
-                dir: Direction::Ascending,
-                anchor: Anchor::Start
-            },
-            Limit::new(3).unwrap()
-        ).await.unwrap();
-
         assert_eq!(
-            summaries,
-            [
-                fake_project_summary("a"),
-                fake_project_summary("b"),
-                fake_project_summary("c")
-            ]
+            core.get_project_diff(Project(42), 1, 2).await.unwrap_err(),
+            CoreError::MalformedQuery
         );
+    }
 
-        assert_eq!(total, 10);
+    #[sqlx::test(fixtures("users", "projects", "one_owner", "tags"))]
+    async fn get_project_tags_sorted(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
 
-        assert_eq!(prev, None);
+        // the fixture inserts tags out of order; the project view and the
+        // revision view must agree on sorted order
+        assert_eq!(
+            core.get_project(Project(42)).await.unwrap().tags,
+            ["tactical", "wwii"]
+        );
 
         assert_eq!(
-            next,
-            Some(
-                Seek {
-                    anchor: Anchor::After("c".into(), 3),
-                    sort_by: SortBy::ProjectName,
-                    dir: Direction::Ascending
-                }
-            )
+            core.get_project_revision(Project(42), 3).await.unwrap().tags,
+            ["tactical", "wwii"]
         );
     }
 
-    #[sqlx::test(fixtures("users", "ten_projects"))]
-    async fn get_projects_pname_end_ok(pool: Pool) {
+    #[sqlx::test(fixtures("users", "projects", "one_owner", "tags"))]
+    async fn update_project_tags_normalized(pool: Pool) {
         let core = make_core(pool, fake_now, 0);
 
-        let (prev, next, summaries, total) = core.get_projects_from(
-            Seek {
-                sort_by: SortBy::ProjectName,
-                dir: Direction::Descending,
-                anchor: Anchor::Start
-            },
-            Limit::new(3).unwrap()
-        ).await.unwrap();
+        let proj = Project(42);
+
+        let cdata = ProjectDataPatch {
+            tags: Some(vec![
+                "wwii ".into(),
+                " armor".into(),
+                "armor".into(),
+                "".into(),
+                "wwii".into()
+            ]),
+            ..Default::default()
+        };
+
+        let result = core.update_project(Owner(1), proj, &cdata, None)
+            .await
+            .unwrap();
 
         assert_eq!(
-            summaries,
-            [
-                fake_project_summary("j"),
-                fake_project_summary("i"),
-                fake_project_summary("h")
-            ]
+            result,
+            ProjectDataPatchResult {
+                revision: 4,
+                tags: Some(
+                    TagChanges {
+                        tags: vec!["armor".into(), "wwii".into()],
+                        added: vec!["armor".into()],
+                        removed: vec!["tactical".into()]
+                    }
+                )
+            }
         );
 
-        assert_eq!(total, 10);
+        // the reported tags match what was stored
+        let mut tags = core.get_project(proj).await.unwrap().tags;
+        tags.sort();
+        assert_eq!(tags, result.tags.as_ref().unwrap().tags);
 
-        assert_eq!(prev, None);
+        let mut tags = core.get_project_revision(proj, 4).await.unwrap().tags;
+        tags.sort();
+        assert_eq!(tags, result.tags.as_ref().unwrap().tags);
+
+        // the old tags are kept with the old revision
+        let mut tags = core.get_project_revision(proj, 3).await.unwrap().tags;
+        tags.sort();
+        assert_eq!(tags, ["tactical", "wwii"]);
+    }
 
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn get_release_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
         assert_eq!(
-            next,
-            Some(
-                Seek {
-                    anchor: Anchor::After("h".into(), 8),
-                    sort_by: SortBy::ProjectName,
-                    dir: Direction::Descending
-                }
-            )
+            core.get_release(Project(42), Package(1)).await.unwrap(),
+            "https://example.com/a_package-1.2.4"
         );
     }
 
-    #[sqlx::test(fixtures("users", "ten_projects"))]
-    async fn get_projects_pname_after_asc_ok(pool: Pool) {
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn get_latest_release_url_no_files(pool: Pool) {
         let core = make_core(pool, fake_now, 0);
-
-        let (prev, next, summaries, total) = core.get_projects_from(
-            Seek {
-                sort_by: SortBy::ProjectName,
-                dir: Direction::Ascending,
-                anchor: Anchor::After("a".into(), 1)
-            },
-            Limit::new(3).unwrap()
-        ).await.unwrap();
-
         assert_eq!(
-            summaries,
-            [
-                fake_project_summary("b"),
-                fake_project_summary("c"),
-                fake_project_summary("d")
-            ]
+            core.get_latest_release_url(Project(42), Package(1)).await.unwrap(),
+            "https://example.com/a_package-1.2.4"
         );
+    }
 
-        assert_eq!(total, 10);
+    #[sqlx::test(fixtures("users", "projects", "packages", "files"))]
+    async fn get_latest_release_url_primary_file(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+        assert_eq!(
+            core.get_latest_release_url(Project(42), Package(1)).await.unwrap(),
+            "https://example.com/a_package-1.2.4-fixed.vmod"
+        );
+    }
 
+    #[sqlx::test(fixtures("users", "projects", "packages", "prereleases"))]
+    async fn get_latest_release_url_prerelease_older(pool: Pool) {
+        // 1.2.4-rc.1 precedes 1.2.4
+        let core = make_core(pool, fake_now, 0);
         assert_eq!(
-            prev,
-            Some(
-                Seek {
-                    anchor: Anchor::Before("b".into(), 2),
-                    sort_by: SortBy::ProjectName,
-                    dir: Direction::Ascending
-                }
-            )
+            core.get_latest_release_url(Project(42), Package(1)).await.unwrap(),
+            "https://example.com/a_package-1.2.4"
         );
+    }
 
+    #[sqlx::test(fixtures("users", "projects", "packages", "prereleases"))]
+    async fn get_latest_release_url_prerelease_newer(pool: Pool) {
+        // 2.0.0-beta follows 1.9.0
+        let core = make_core(pool, fake_now, 0);
         assert_eq!(
-            next,
-            Some(
-                Seek {
-                    anchor: Anchor::After("d".into(), 4),
-                    sort_by: SortBy::ProjectName,
-                    dir: Direction::Ascending
-                }
-            )
+            core.get_latest_release_url(Project(42), Package(2)).await.unwrap(),
+            "https://example.com/b_package-2.0.0-beta"
         );
     }
 
-    #[sqlx::test(fixtures("users", "ten_projects"))]
-    async fn get_projects_pname_after_desc_ok(pool: Pool) {
+    #[sqlx::test(fixtures("users", "projects", "one_owner", "packages"))]
+    async fn recommended_release_not_latest(pool: Pool) {
         let core = make_core(pool, fake_now, 0);
+        let version = "1.2.3".parse::<Version>().unwrap();
 
-        let (prev, next, summaries, total) = core.get_projects_from(
-            Seek {
-                sort_by: SortBy::ProjectName,
-                dir: Direction::Descending,
-                anchor: Anchor::After("h".into(), 8)
-            },
-            Limit::new(3).unwrap()
+        // with no recommendation, the latest is recommended
+        assert_eq!(
+            core.get_recommended_release_url(Project(42), Package(1))
+                .await
+                .unwrap(),
+            "https://example.com/a_package-1.2.4"
+        );
+
+        core.set_recommended_release(
+            Owner(1),
+            Project(42),
+            Package(1),
+            Some(&version)
         ).await.unwrap();
 
+        // the recommendation wins even though 1.2.4 is newer
         assert_eq!(
-            summaries,
-            [
-                fake_project_summary("g"),
-                fake_project_summary("f"),
-                fake_project_summary("e")
-            ]
+            core.get_recommended_release_url(Project(42), Package(1))
+                .await
+                .unwrap(),
+            "https://example.com/a_package-1.2.3"
         );
 
-        assert_eq!(total, 10);
-
         assert_eq!(
-            prev,
-            Some(
-                Seek {
-                    anchor: Anchor::Before("g".into(), 7),
-                    sort_by: SortBy::ProjectName,
-                    dir: Direction::Descending
-                }
-            )
+            core.get_package(Project(42), Package(1))
+                .await
+                .unwrap()
+                .recommended_release
+                .as_deref(),
+            Some("1.2.3")
         );
 
+        core.set_recommended_release(Owner(1), Project(42), Package(1), None)
+            .await
+            .unwrap();
+
         assert_eq!(
-            next,
-            Some(
-                Seek {
-                    anchor: Anchor::After("e".into(), 5),
-                    sort_by: SortBy::ProjectName,
-                    dir: Direction::Descending
-                }
-            )
+            core.get_recommended_release_url(Project(42), Package(1))
+                .await
+                .unwrap(),
+            "https://example.com/a_package-1.2.4"
         );
     }
 
-    #[sqlx::test(fixtures("users", "ten_projects"))]
-    async fn get_projects_pname_before_asc_ok(pool: Pool) {
+    #[sqlx::test(fixtures("users", "projects", "one_owner", "packages"))]
+    async fn recommended_release_deleted(pool: Pool) {
         let core = make_core(pool, fake_now, 0);
+        let version = "1.2.3".parse::<Version>().unwrap();
 
-        let (prev, next, summaries, total) = core.get_projects_from(
-            Seek {
-                sort_by: SortBy::ProjectName,
-                dir: Direction::Ascending,
-                anchor: Anchor::Before("e".into(), 5)
-            },
-            Limit::new(3).unwrap()
+        core.set_recommended_release(
+            Owner(1),
+            Project(42),
+            Package(1),
+            Some(&version)
         ).await.unwrap();
 
+        // deleting the recommended release removes the recommendation
+        core.delete_release(Owner(1), Project(42), Package(1), &version)
+            .await
+            .unwrap();
+
         assert_eq!(
-            summaries,
-            [
-                fake_project_summary("b"),
-                fake_project_summary("c"),
-                fake_project_summary("d")
-            ]
+            core.get_package(Project(42), Package(1))
+                .await
+                .unwrap()
+                .recommended_release,
+            None
         );
+    }
 
-        assert_eq!(total, 10);
-
+    #[sqlx::test(fixtures("users", "projects", "one_owner", "packages"))]
+    async fn set_recommended_release_not_a_version(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
         assert_eq!(
-            prev,
-            Some(
-                Seek {
-                    anchor: Anchor::Before("b".into(), 2),
-                    sort_by: SortBy::ProjectName,
-                    dir: Direction::Ascending
-                }
-            )
+            core.set_recommended_release(
+                Owner(1),
+                Project(42),
+                Package(1),
+                Some(&"9.9.9".parse::<Version>().unwrap())
+            ).await.unwrap_err(),
+            CoreError::NotAVersion
         );
+    }
 
+    #[sqlx::test(fixtures("users", "projects", "one_owner", "packages", "archived"))]
+    async fn set_recommended_release_archived(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
         assert_eq!(
-            next,
-            Some(
-                Seek {
-                    anchor: Anchor::After("d".into(), 4),
-                    sort_by: SortBy::ProjectName,
-                    dir: Direction::Ascending
-                }
-            )
+            core.set_recommended_release(Owner(1), Project(6), Package(4), None)
+                .await
+                .unwrap_err(),
+            CoreError::ProjectArchived
         );
     }
 
-    #[sqlx::test(fixtures("users", "ten_projects"))]
-    async fn get_projects_pname_before_desc_ok(pool: Pool) {
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn get_latest_release_url_no_releases(pool: Pool) {
         let core = make_core(pool, fake_now, 0);
-        let (prev, next, summaries, total) = core.get_projects_from(
-            Seek {
-                sort_by: SortBy::ProjectName,
-                dir: Direction::Descending,
-                anchor: Anchor::Before("e".into(), 5)
-            },
-            Limit::new(3).unwrap()
-        ).await.unwrap();
-
         assert_eq!(
-            summaries,
-            [
-                fake_project_summary("h"),
-                fake_project_summary("g"),
-                fake_project_summary("f")
-            ]
+            core.get_latest_release_url(Project(42), Package(2))
+                .await
+                .unwrap_err(),
+            CoreError::NotFound
         );
+    }
 
-        assert_eq!(total, 10);
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn get_release_version_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+        let version = "1.2.3".parse::<Version>().unwrap();
+        assert_eq!(
+            core.get_release_version(Project(42), Package(1), &version)
+                .await
+                .unwrap(),
+            "https://example.com/a_package-1.2.3"
+        );
+    }
 
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn get_release_version_not_a_version(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+        let version = "1.0.0".parse::<Version>().unwrap();
         assert_eq!(
-            prev,
-            Some(
-                Seek {
-                    anchor: Anchor::Before("h".into(), 8),
-                    sort_by: SortBy::ProjectName,
-                    dir: Direction::Descending
-                }
-            )
+            core.get_release_version(Project(42), Package(1), &version)
+                .await
+                .unwrap_err(),
+            CoreError::NotAVersion
         );
+    }
 
+    #[sqlx::test(fixtures("users", "projects", "one_owner"))]
+    async fn get_owners_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
         assert_eq!(
-            next,
-            Some(
-                Seek {
-                    anchor: Anchor::After("f".into(), 6),
-                    sort_by: SortBy::ProjectName,
-                    dir: Direction::Descending
-                }
-            )
+            core.get_owners(Project(42)).await.unwrap(),
+            Users { users: vec!["bob".into()] }
         );
     }
 
-    #[sqlx::test(fixtures("users", "ten_projects"))]
-    async fn get_projects_pname_before_asc_no_prev_ok(pool: Pool) {
+    #[sqlx::test(fixtures("users", "projects", "one_owner"))]
+    async fn user_is_owner_true(pool: Pool) {
         let core = make_core(pool, fake_now, 0);
+        assert!(core.user_is_owner(User(1), Project(42)).await.unwrap());
+    }
 
-        let (prev, next, summaries, total) = core.get_projects_from(
-            Seek {
-                sort_by: SortBy::ProjectName,
-                dir: Direction::Ascending,
-                anchor: Anchor::Before("d".into(), 4)
-            },
-            Limit::new(3).unwrap()
-        ).await.unwrap();
+    #[sqlx::test(fixtures("users", "projects", "one_owner"))]
+    async fn user_is_owner_false(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+        assert!(!core.user_is_owner(User(2), Project(42)).await.unwrap());
+    }
 
+    #[sqlx::test(fixtures("users", "projects", "one_owner"))]
+    async fn add_owners_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+        let users = Users { users: vec!["alice".into()] };
+        core.add_owners(&users, Project(42)).await.unwrap();
         assert_eq!(
-            summaries,
-            [
-                fake_project_summary("a"),
-                fake_project_summary("b"),
-                fake_project_summary("c")
-            ]
+            core.get_owners(Project(42)).await.unwrap(),
+            Users {
+                users: vec![
+                    "alice".into(),
+                    "bob".into()
+                ]
+            }
         );
+    }
 
-        assert_eq!(total, 10);
+    #[derive(Clone, Default)]
+    struct VecSink(Arc<Mutex<Vec<Notification>>>);
 
-        assert_eq!(prev, None);
+    impl NotificationSink for VecSink {
+        fn deliver(&self, notification: Notification) {
+            self.0.lock().unwrap().push(notification);
+        }
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "one_owner"))]
+    async fn add_owners_notifies(pool: Pool) {
+        let sink = VecSink::default();
+        let core = ProdCore {
+            notifier: Notifier::new(sink.clone()),
+            ..make_core(pool, fake_now, 0)
+        };
+
+        // bob is already an owner, so only alice is told
+        let users = Users { users: vec!["alice".into(), "bob".into()] };
+        core.add_owners(&users, Project(42)).await.unwrap();
 
         assert_eq!(
-            next,
-            Some(
-                Seek {
-                    anchor: Anchor::After("c".into(), 3),
-                    sort_by: SortBy::ProjectName,
-                    dir: Direction::Ascending
+            *sink.0.lock().unwrap(),
+            [
+                Notification::OwnerAdded {
+                    user: "alice".into(),
+                    project: "test_game".into()
                 }
-            )
+            ]
         );
     }
 
-    #[sqlx::test(fixtures("users", "ten_projects"))]
-    async fn get_projects_pname_after_desc_no_prev_ok(pool: Pool) {
-        let core = make_core(pool, fake_now, 0);
+    #[sqlx::test(fixtures("users", "projects", "two_owners"))]
+    async fn remove_owners_notifies(pool: Pool) {
+        let sink = VecSink::default();
+        let core = ProdCore {
+            notifier: Notifier::new(sink.clone()),
+            ..make_core(pool, fake_now, 0)
+        };
 
-        let (prev, next, summaries, total) = core.get_projects_from(
-            Seek {
-                sort_by: SortBy::ProjectName,
-                dir: Direction::Descending,
-                anchor: Anchor::Before("g".into(), 7)
-            },
-            Limit::new(3).unwrap()
-        ).await.unwrap();
+        let users = Users { users: vec!["bob".into()] };
+        core.remove_owners(&users, Project(42)).await.unwrap();
 
         assert_eq!(
-            summaries,
+            *sink.0.lock().unwrap(),
             [
-                fake_project_summary("j"),
-                fake_project_summary("i"),
-                fake_project_summary("h")
+                Notification::OwnerRemoved {
+                    user: "bob".into(),
+                    project: "test_game".into()
+                }
             ]
         );
+    }
 
-        assert_eq!(total, 10);
+    #[sqlx::test(fixtures("users", "projects", "two_owners"))]
+    async fn remove_owners_last_owner_no_notification(pool: Pool) {
+        let sink = VecSink::default();
+        let core = ProdCore {
+            notifier: Notifier::new(sink.clone()),
+            ..make_core(pool, fake_now, 0)
+        };
 
-        assert_eq!(prev, None);
+        let users = Users { users: vec!["alice".into(), "bob".into()] };
+        assert_eq!(
+            core.remove_owners(&users, Project(42)).await.unwrap_err(),
+            CoreError::CannotRemoveLastOwner
+        );
+        assert!(sink.0.lock().unwrap().is_empty());
+    }
 
+    #[sqlx::test(fixtures("users", "projects", "two_owners"))]
+    async fn remove_owners_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+        let users = Users { users: vec!["bob".into()] };
+        core.remove_owners(&users, Project(42)).await.unwrap();
         assert_eq!(
-            next,
-            Some(
-                Seek {
-                    anchor: Anchor::After("h".into(), 8),
-                    sort_by: SortBy::ProjectName,
-                    dir: Direction::Descending
-                }
-            )
+            core.get_owners(Project(42)).await.unwrap(),
+            Users { users: vec!["alice".into()] }
         );
     }
 
-    #[sqlx::test(fixtures("users", "ten_projects"))]
-    async fn get_projects_pname_after_asc_no_next_ok(pool: Pool) {
+    #[sqlx::test(fixtures("users", "projects", "one_owner"))]
+    async fn remove_owners_fail_if_last(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+        let users = Users { users: vec!["bob".into()] };
+        assert_eq!(
+            core.remove_owners(&users, Project(1)).await.unwrap_err(),
+            CoreError::CannotRemoveLastOwner
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "players"))]
+    async fn get_players_ok(pool: Pool) {
         let core = make_core(pool, fake_now, 0);
+        assert_eq!(
+            core.get_players(Project(42)).await.unwrap(),
+            Users {
+                users: vec![
+                    "alice".into(),
+                    "bob".into()
+                ]
+            }
+        );
+    }
 
-        let (prev, next, summaries, total) = core.get_projects_from(
-            Seek {
-                sort_by: SortBy::ProjectName,
-                dir: Direction::Ascending,
-                anchor: Anchor::After("g".into(), 7)
-            },
-            Limit::new(3).unwrap()
-        ).await.unwrap();
+    #[sqlx::test(fixtures("users", "projects", "players"))]
+    async fn add_player_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+        core.add_player(User(3), Project(42)).await.unwrap();
+        assert_eq!(
+            core.get_players(Project(42)).await.unwrap(),
+            Users {
+                users: vec![
+                    "alice".into(),
+                    "bob".into(),
+                    "chuck".into()
+                ]
+            }
+        );
+    }
 
+    #[sqlx::test(fixtures("users", "projects", "players"))]
+    async fn remove_player_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+        core.remove_player(User(1), Project(42)).await.unwrap();
         assert_eq!(
-            summaries,
-            [
-                fake_project_summary("h"),
-                fake_project_summary("i"),
-                fake_project_summary("j")
-            ]
+            core.get_players(Project(42)).await.unwrap(),
+            Users { users: vec!["alice".into()] }
         );
+    }
 
-        assert_eq!(total, 10);
-
+    #[sqlx::test(fixtures("users", "projects", "images"))]
+    async fn get_image_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
         assert_eq!(
-            prev,
-            Some(
-                Seek {
-                    anchor: Anchor::Before("h".into(), 8),
-                    sort_by: SortBy::ProjectName,
-                    dir: Direction::Ascending
-                }
-            )
+            core.get_image(Project(42), "img.png").await.unwrap(),
+            "https://example.com/images/img.png"
         );
-
-        assert_eq!(next, None);
     }
 
-    #[sqlx::test(fixtures("users", "ten_projects"))]
-    async fn get_projects_pname_after_desc_no_next_ok(pool: Pool) {
+    #[sqlx::test(fixtures("users", "projects", "images"))]
+    async fn get_image_not_a_project(pool: Pool) {
         let core = make_core(pool, fake_now, 0);
-
-        let (prev, next, summaries, total) = core.get_projects_from(
-            Seek {
-                sort_by: SortBy::ProjectName,
-                dir: Direction::Descending,
-                anchor: Anchor::After("d".into(), 4)
-            },
-            Limit::new(3).unwrap()
-        ).await.unwrap();
-
         assert_eq!(
-            summaries,
-            [
-                fake_project_summary("c"),
-                fake_project_summary("b"),
-                fake_project_summary("a")
-            ]
+            core.get_image(Project(1), "img.png").await.unwrap_err(),
+            CoreError::NotFound
         );
+    }
 
-        assert_eq!(total, 10);
-
+    #[sqlx::test(fixtures("users", "projects", "images"))]
+    async fn get_image_not_an_image(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
         assert_eq!(
-            prev,
-            Some(
-                Seek {
-                    anchor: Anchor::Before("c".into(), 3),
-                    sort_by: SortBy::ProjectName,
-                    dir: Direction::Descending
-                }
-            )
+            core.get_image(Project(42), "bogus").await.unwrap_err(),
+            CoreError::NotFound
         );
-
-        assert_eq!(next, None);
     }
 
-    #[sqlx::test(fixtures("users", "ten_projects"))]
-    async fn get_projects_mtime_start_ok(pool: Pool) {
+    #[sqlx::test(fixtures("users", "projects", "images"))]
+    async fn remove_image_ok(pool: Pool) {
         let core = make_core(pool, fake_now, 0);
 
-        let (prev, next, summaries, total) = core.get_projects_from(
-            Seek {
-                sort_by: SortBy::ModificationTime,
-                dir: Direction::Descending,
-                anchor: Anchor::Start
-            },
-            Limit::new(3).unwrap()
-        ).await.unwrap();
+        core.remove_image(Owner(1), Project(42), "img.png").await.unwrap();
 
         assert_eq!(
-            summaries,
-            [
-                fake_project_summary("j"),
-                fake_project_summary("i"),
-                fake_project_summary("h")
-            ]
+            core.get_image(Project(42), "img.png").await.unwrap_err(),
+            CoreError::NotFound
         );
 
-        assert_eq!(total, 10);
-
-        assert_eq!(prev, None);
+        // removing it again finds nothing to remove
+        assert_eq!(
+            core.remove_image(Owner(1), Project(42), "img.png")
+                .await
+                .unwrap_err(),
+            CoreError::NotFound
+        );
+    }
 
+    #[sqlx::test(fixtures("users", "projects", "images"))]
+    async fn remove_image_not_an_image(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
         assert_eq!(
-            next,
-            Some(
-                Seek {
-                    anchor: Anchor::After(
-                        "1970-01-01T00:00:00.000000008+00:00".into(),
-                        8
-                    ),
-                    sort_by: SortBy::ModificationTime,
-                    dir: Direction::Descending
-                }
-            )
+            core.remove_image(Owner(1), Project(42), "bogus")
+                .await
+                .unwrap_err(),
+            CoreError::NotFound
         );
     }
 
-    #[sqlx::test(fixtures("users", "ten_projects"))]
-    async fn get_projects_mtime_end_ok(pool: Pool) {
+    #[sqlx::test(fixtures("users", "projects", "images"))]
+    async fn remove_image_cover(pool: Pool) {
         let core = make_core(pool, fake_now, 0);
 
-        let (prev, next, summaries, total) = core.get_projects_from(
-            Seek {
-                sort_by: SortBy::ProjectName,
-                dir: Direction::Descending,
-                anchor: Anchor::Start
-            },
-            Limit::new(3).unwrap()
+        let set_cover = |image: Option<&str>| ProjectDataPatch {
+            image: Some(image.map(String::from)),
+            ..Default::default()
+        };
+
+        core.update_project(
+            Owner(1),
+            Project(42),
+            &set_cover(Some("img.png")),
+            None
         ).await.unwrap();
 
         assert_eq!(
-            summaries,
-            [
-                fake_project_summary("j"),
-                fake_project_summary("i"),
-                fake_project_summary("h")
-            ]
+            core.remove_image(Owner(1), Project(42), "img.png")
+                .await
+                .unwrap_err(),
+            CoreError::ImageInUse
         );
 
-        assert_eq!(total, 10);
+        // unsetting the cover image allows its removal
+        core.update_project(Owner(1), Project(42), &set_cover(None), None)
+            .await
+            .unwrap();
 
-        assert_eq!(prev, None);
+        core.remove_image(Owner(1), Project(42), "img.png").await.unwrap();
 
+        // a removed image cannot become the cover image
         assert_eq!(
-            next,
-            Some(
-                Seek {
-                    anchor: Anchor::After("h".into(), 8),
-                    sort_by: SortBy::ProjectName,
-                    dir: Direction::Descending
-                }
-            )
+            core.update_project(
+                Owner(1),
+                Project(42),
+                &set_cover(Some("img.png")),
+                None
+            ).await.unwrap_err(),
+            CoreError::NotFound
         );
     }
 
-    #[sqlx::test(fixtures("users", "ten_projects"))]
-    async fn get_projects_mtime_after_asc_ok(pool: Pool) {
-        let core = make_core(pool, fake_now, 0);
-
-        let (prev, next, summaries, total) = core.get_projects_from(
-            Seek {
-                sort_by: SortBy::ModificationTime,
-                dir: Direction::Ascending,
-                anchor: Anchor::After(
-                    "1970-01-01T00:00:00.000000001+00:00".into(),
-                    1
-                )
-            },
-            Limit::new(3).unwrap()
+    #[sqlx::test(fixtures("users", "projects", "images"))]
+    async fn remove_image_then_add(pool: Pool) {
+        let core = make_core(pool, fake_now, 1024);
+
+        core.remove_image(Owner(1), Project(42), "img.png").await.unwrap();
+
+        core.add_image(
+            Owner(1),
+            Project(42),
+            "img.png",
+            &mime::IMAGE_PNG,
+            None,
+            image_stream()
         ).await.unwrap();
 
         assert_eq!(
-            summaries,
-            [
-                fake_project_summary("b"),
-                fake_project_summary("c"),
-                fake_project_summary("d")
-            ]
+            core.get_image(Project(42), "img.png").await.unwrap(),
+            "https://example.com/img.png"
         );
+    }
 
-        assert_eq!(total, 10);
+    #[sqlx::test(fixtures("users", "projects", "images"))]
+    async fn remove_image_gallery(pool: Pool) {
+        let core = make_core(pool, fake_now, 1024);
+
+        core.add_gallery_image(
+            Owner(1),
+            Project(42),
+            "a.png",
+            &mime::IMAGE_PNG,
+            None,
+            image_stream()
+        ).await.unwrap();
 
-        assert_eq!(
-            prev,
-            Some(
-                Seek {
-                    anchor: Anchor::Before(
-                        "1970-01-01T00:00:00.000000002+00:00".into(),
-                        2
-                    ),
-                    sort_by: SortBy::ModificationTime,
-                    dir: Direction::Ascending
-                }
-            )
-        );
+        core.remove_image(Owner(1), Project(42), "a.png").await.unwrap();
+
+        assert!(core.get_project(Project(42)).await.unwrap().gallery.is_empty());
+    }
 
+    #[sqlx::test(fixtures("users", "projects", "archived"))]
+    async fn remove_image_archived(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
         assert_eq!(
-            next,
-            Some(
-                Seek {
-                    anchor: Anchor::After(
-                        "1970-01-01T00:00:00.000000004+00:00".into(),
-                        4
-                    ),
-                    sort_by: SortBy::ModificationTime,
-                    dir: Direction::Ascending
-                }
-            )
+            core.remove_image(Owner(1), Project(6), "img.png")
+                .await
+                .unwrap_err(),
+            CoreError::ProjectArchived
         );
     }
 
-    #[sqlx::test(fixtures("users", "ten_projects"))]
-    async fn get_projects_mtime_after_desc_ok(pool: Pool) {
+    #[sqlx::test(fixtures("users", "projects", "flags"))]
+    async fn get_flags_ok(pool: Pool) {
         let core = make_core(pool, fake_now, 0);
-
-        let (prev, next, summaries, total) = core.get_projects_from(
-            Seek {
-                sort_by: SortBy::ModificationTime,
-                dir: Direction::Descending,
-                anchor: Anchor::After(
-                    "1970-01-01T00:00:00.000000008+00:00".into(),
-                    8
-                )
-            },
-            Limit::new(3).unwrap()
-        ).await.unwrap();
-
         assert_eq!(
-            summaries,
-            [
-                fake_project_summary("g"),
-                fake_project_summary("f"),
-                fake_project_summary("e")
-            ]
+            core.get_flags(&[]).await.unwrap(),
+            Flags {
+                flags: vec![
+                    Flag {
+                        flag_id: 2,
+                        project: "a_game".into(),
+                        flagged_by: "chuck".into(),
+                        flagged_at: "2023-12-14T15:50:06.419538067+00:00".into(),
+                        flag: "spam".into(),
+                        message: None,
+                        resolved_at: Some("2024-01-06T19:23:26.419538067+00:00".into()),
+                        resolved_by: Some("bob".into())
+                    },
+                    Flag {
+                        flag_id: 1,
+                        project: "test_game".into(),
+                        flagged_by: "alice".into(),
+                        flagged_at: "2023-12-26T05:36:46.419538067+00:00".into(),
+                        flag: "inappropriate".into(),
+                        message: Some("rude words".into()),
+                        resolved_at: None,
+                        resolved_by: None
+                    }
+                ]
+            }
         );
+    }
 
-        assert_eq!(total, 10);
-
+    #[sqlx::test(fixtures("users", "projects", "flags"))]
+    async fn get_flags_unresolved_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
         assert_eq!(
-            prev,
-            Some(
-                Seek {
-                    anchor: Anchor::Before(
-                        "1970-01-01T00:00:00.000000007+00:00".into(),
-                        7
-                    ),
-                    sort_by: SortBy::ModificationTime,
-                    dir: Direction::Descending
-                }
-            )
+            core.get_flags(&[Facet::UnresolvedOnly]).await.unwrap(),
+            Flags {
+                flags: vec![
+                    Flag {
+                        flag_id: 1,
+                        project: "test_game".into(),
+                        flagged_by: "alice".into(),
+                        flagged_at: "2023-12-26T05:36:46.419538067+00:00".into(),
+                        flag: "inappropriate".into(),
+                        message: Some("rude words".into()),
+                        resolved_at: None,
+                        resolved_by: None
+                    }
+                ]
+            }
         );
+    }
 
+    #[sqlx::test(fixtures("users", "projects", "flags"))]
+    async fn resolve_flag_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+        core.resolve_flag(User(1), 1).await.unwrap();
         assert_eq!(
-            next,
-            Some(
-                Seek {
-                    anchor: Anchor::After(
-                        "1970-01-01T00:00:00.000000005+00:00".into(),
-                        5
-                    ),
-                    sort_by: SortBy::ModificationTime,
-                    dir: Direction::Descending
-                }
-            )
+            core.get_flags(&[Facet::UnresolvedOnly]).await.unwrap(),
+            Flags { flags: vec![] }
         );
     }
 
-    #[sqlx::test(fixtures("users", "ten_projects"))]
-    async fn get_projects_mtime_before_asc_ok(pool: Pool) {
-        let core = make_core(pool, fake_now, 0);
+    #[sqlx::test(fixtures("users", "projects", "two_owners", "flags"))]
+    async fn resolve_flag_notifies(pool: Pool) {
+        let sink = VecSink::default();
+        let core = ProdCore {
+            notifier: Notifier::new(sink.clone()),
+            ..make_core(pool, fake_now, 0)
+        };
 
-        let (prev, next, summaries, total) = core.get_projects_from(
-            Seek {
-                sort_by: SortBy::ModificationTime,
-                dir: Direction::Ascending,
-                anchor: Anchor::Before(
-                    "1970-01-01T00:00:00.000000005+00:00".into(),
-                    5
-                )
-            },
-            Limit::new(3).unwrap()
-        ).await.unwrap();
+        core.resolve_flag(User(1), 1).await.unwrap();
 
         assert_eq!(
-            summaries,
-            [
-                fake_project_summary("b"),
-                fake_project_summary("c"),
-                fake_project_summary("d")
+            *sink.0.lock().unwrap(),
+            [
+                Notification::FlagResolved {
+                    user: "alice".into(),
+                    project: "test_game".into()
+                },
+                Notification::FlagResolved {
+                    user: "bob".into(),
+                    project: "test_game".into()
+                }
             ]
         );
+    }
 
-        assert_eq!(total, 10);
+    #[sqlx::test(fixtures("users", "projects", "notifications"))]
+    async fn get_inbox_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        let params = InboxParams {
+            limit: Limit::new(2),
+            before: None
+        };
 
         assert_eq!(
-            prev,
-            Some(
-                Seek {
-                    anchor: Anchor::Before(
-                        "1970-01-01T00:00:00.000000002+00:00".into(),
-                        2
-                    ),
-                    sort_by: SortBy::ModificationTime,
-                    dir: Direction::Ascending
-                }
-            )
+            core.get_inbox(User(1), params).await.unwrap(),
+            Inbox {
+                notifications: vec![
+                    InboxItem {
+                        id: 4,
+                        kind: "release_published".into(),
+                        project: "test_game".into(),
+                        package: Some("a_package".into()),
+                        version: Some("1.2.4".into()),
+                        created_at: "2023-12-26T05:36:46.419538067+00:00".into(),
+                        read: false
+                    },
+                    InboxItem {
+                        id: 2,
+                        kind: "flag_resolved".into(),
+                        project: "test_game".into(),
+                        package: None,
+                        version: None,
+                        created_at: "2023-12-14T15:50:06.419538067+00:00".into(),
+                        read: false
+                    }
+                ],
+                unread: 2,
+                next_before: Some(2)
+            }
         );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "notifications"))]
+    async fn get_inbox_before_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        let params = InboxParams {
+            limit: Limit::new(2),
+            before: Some(2)
+        };
 
         assert_eq!(
-            next,
-            Some(
-                Seek {
-                    anchor: Anchor::After(
-                        "1970-01-01T00:00:00.000000004+00:00".into(),
-                        4
-                    ),
-                    sort_by: SortBy::ModificationTime,
-                    dir: Direction::Ascending
-                }
-            )
+            core.get_inbox(User(1), params).await.unwrap(),
+            Inbox {
+                notifications: vec![
+                    InboxItem {
+                        id: 1,
+                        kind: "owner_added".into(),
+                        project: "test_game".into(),
+                        package: None,
+                        version: None,
+                        created_at: "2023-11-12T15:50:06.419538067+00:00".into(),
+                        read: true
+                    }
+                ],
+                unread: 2,
+                next_before: None
+            }
         );
     }
 
-    #[sqlx::test(fixtures("users", "ten_projects"))]
-    async fn get_projects_mtime_before_desc_ok(pool: Pool) {
+    #[sqlx::test(fixtures("users", "projects", "notifications"))]
+    async fn get_inbox_limit_too_large(pool: Pool) {
         let core = make_core(pool, fake_now, 0);
 
-        let (prev, next, summaries, total) = core.get_projects_from(
-            Seek {
-                sort_by: SortBy::ModificationTime,
-                dir: Direction::Descending,
-                anchor: Anchor::Before(
-                    "1970-01-01T00:00:00.000000006+00:00".into(),
-                    5
-                )
-            },
-            Limit::new(3).unwrap()
-        ).await.unwrap();
+        let params = InboxParams {
+            limit: Limit::new(101),
+            before: None
+        };
 
         assert_eq!(
-            summaries,
-            [
-                fake_project_summary("h"),
-                fake_project_summary("g"),
-                fake_project_summary("f")
-            ]
+            core.get_inbox(User(1), params).await.unwrap_err(),
+            CoreError::LimitOutOfRange
         );
+    }
 
-        assert_eq!(total, 10);
+    #[sqlx::test(fixtures("users", "projects", "notifications"))]
+    async fn mark_inbox_read_ok(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
 
+        core.mark_inbox_read(User(1), Some(&[2])).await.unwrap();
         assert_eq!(
-            prev,
-            Some(
-                Seek {
-                    anchor: Anchor::Before(
-                        "1970-01-01T00:00:00.000000008+00:00".into(),
-                        8
-                    ),
-                    sort_by: SortBy::ModificationTime,
-                    dir: Direction::Descending
-                }
-            )
+            core.get_inbox(User(1), InboxParams::default()).await.unwrap().unread,
+            1
         );
 
+        core.mark_inbox_read(User(1), None).await.unwrap();
         assert_eq!(
-            next,
-            Some(
-                Seek {
-                    anchor: Anchor::After(
-                        "1970-01-01T00:00:00.000000006+00:00".into(),
-                        6
-                    ),
-                    sort_by: SortBy::ModificationTime,
-                    dir: Direction::Descending
-                }
-            )
+            core.get_inbox(User(1), InboxParams::default()).await.unwrap().unread,
+            0
+        );
+
+        // other users' notifications are untouched
+        assert_eq!(
+            core.get_inbox(User(2), InboxParams::default()).await.unwrap().unread,
+            1
         );
     }
 
-    #[sqlx::test(fixtures("users", "projects", "two_owners", "packages", "authors"))]
-    async fn get_project_ok(pool: Pool) {
+    #[sqlx::test(fixtures("users", "projects", "flags"))]
+    async fn resolve_flag_already_resolved(pool: Pool) {
         let core = make_core(pool, fake_now, 0);
         assert_eq!(
-            core.get_project(Project(42)).await.unwrap(),
-            ProjectData {
-                name: "test_game".into(),
-                description: "Brian's Trademarked Game of Being a Test Case".into(),
-                revision: 3,
-                created_at: "2023-11-12T15:50:06.419538067+00:00".into(),
-                modified_at: "2023-12-14T15:50:06.419538067+00:00".into(),
-                tags: vec![],
-                game: GameData {
-                    title: "A Game of Tests".into(),
-                    title_sort_key: "Game of Tests, A".into(),
-                    publisher: "Test Game Company".into(),
-                    year: "1979".into()
-                },
-                readme: "".into(),
-                image: None,
-                owners: vec!["alice".into(), "bob".into()],
-                packages: vec![
-                    PackageData {
-                        name: "a_package".into(),
-                        description: "".into(),
-                        releases: vec![
-                            FileData {
-                                version: "1.2.4".into(),
-                                filename: "a_package-1.2.4".into(),
-                                url: "https://example.com/a_package-1.2.4".into(),
-                                size: 5678,
-                                checksum: "79fdd8fe3128f818e446e919cce5dcfb81815f8f4341c53f4d6b58ded48cebf2".into(),
-                                published_at: "2023-12-10T15:56:29.180282477+00:00".into(),
-                                published_by: "alice".into(),
-                                requires: "".into(),
-                                authors: vec!["alice".into(), "bob".into()]
-                            },
-                            FileData {
-                                version: "1.2.3".into(),
-                                filename: "a_package-1.2.3".into(),
-                                url: "https://example.com/a_package-1.2.3".into(),
-                                size: 1234,
-                                checksum: "c0e0fa7373a12b45a91e4f4d4e2e186442fc6ee9b346caa2fdc1c09026a2144a".into(),
-                                published_at: "2023-12-09T15:56:29.180282477+00:00".into(),
-                                published_by: "bob".into(),
-                                requires: "".into(),
-                                authors: vec!["alice".into()]
-                            }
-                        ],
-                        files: vec![]
-                    },
-                    PackageData {
-                        name: "b_package".into(),
-                        description: "".into(),
-                        releases: vec![],
-                        files: vec![]
-                    },
-                    PackageData {
-                        name: "c_package".into(),
-                        description: "".into(),
-                        releases: vec![
-                            FileData {
-                                version: "0.1.0".into(),
-                                filename: "c_package-0.1.0".into(),
-                                url: "https://example.com/c_package-0.1.0".into(),
-                                size: 123456,
-                                checksum: "a8f515e9e2de99919d1a987733296aaa951a4ba2aa0f7014c510bdbd60dc0efd".into(),
-                                published_at: "2023-12-15T15:56:29.180282477+00:00".into(),
-                                published_by: "chuck".into(),
-                                requires: "".into(),
-                                authors: vec![]
-                            }
-                        ],
-                        files: vec![]
-                    }
-                ]
-            }
+            core.resolve_flag(User(1), 2).await.unwrap_err(),
+            CoreError::FlagAlreadyResolved
         );
     }
 
-    #[sqlx::test(fixtures("users", "projects", "two_owners", "packages", "authors"))]
-    async fn get_project_revision_ok_current(pool: Pool) {
+    #[sqlx::test(fixtures("users", "projects", "two_owners", "packages", "files"))]
+    async fn set_primary_file_ok(pool: Pool) {
         let core = make_core(pool, fake_now, 0);
+
+        let primaries = |pd: ProjectData| pd.packages[0].releases
+            .iter()
+            .map(|r| r.primary_file.clone())
+            .collect::<Vec<_>>();
+
         assert_eq!(
-            core.get_project_revision(Project(42), 3).await.unwrap(),
-            ProjectData {
-                name: "test_game".into(),
-                description: "Brian's Trademarked Game of Being a Test Case".into(),
-                revision: 3,
-                created_at: "2023-11-12T15:50:06.419538067+00:00".into(),
-                modified_at: "2023-12-14T15:50:06.419538067+00:00".into(),
-                tags: vec![],
-                game: GameData {
-                    title: "A Game of Tests".into(),
-                    title_sort_key: "Game of Tests, A".into(),
-                    publisher: "Test Game Company".into(),
-                    year: "1979".into()
-                },
-                readme: "".into(),
-                image: None,
-                owners: vec!["alice".into(), "bob".into()],
-                packages: vec![
-                    PackageData {
-                        name: "a_package".into(),
-                        description: "".into(),
-                        releases: vec![
-                            FileData {
-                                version: "1.2.4".into(),
-                                filename: "a_package-1.2.4".into(),
-                                url: "https://example.com/a_package-1.2.4".into(),
-                                size: 5678,
-                                checksum: "79fdd8fe3128f818e446e919cce5dcfb81815f8f4341c53f4d6b58ded48cebf2".into(),
-                                published_at: "2023-12-10T15:56:29.180282477+00:00".into(),
-                                published_by: "alice".into(),
-                                requires: "".into(),
-                                authors: vec!["alice".into(), "bob".into()]
-                            },
-                            FileData {
-                                version: "1.2.3".into(),
-                                filename: "a_package-1.2.3".into(),
-                                url: "https://example.com/a_package-1.2.3".into(),
-                                size: 1234,
-                                checksum: "c0e0fa7373a12b45a91e4f4d4e2e186442fc6ee9b346caa2fdc1c09026a2144a".into(),
-                                published_at: "2023-12-09T15:56:29.180282477+00:00".into(),
-                                published_by: "bob".into(),
-                                requires: "".into(),
-                                authors: vec!["alice".into()]
-                            }
-                        ],
-                        files: vec![]
-                    },
-                    PackageData {
-                        name: "b_package".into(),
-                        description: "".into(),
-                        releases: vec![],
-                        files: vec![]
-                    },
-                    PackageData {
-                        name: "c_package".into(),
-                        description: "".into(),
-                        releases: vec![],
-                        files: vec![]
-                    }
-                ]
-            }
+            primaries(core.get_project(Project(42)).await.unwrap()),
+            [
+                Some("a_package-1.2.4-fixed.vmod".into()),
+                Some("maps-1.2.3.zip".into())
+            ]
+        );
+
+        core.set_primary_file(
+            Owner(1),
+            Project(42),
+            Package(1),
+            &"1.2.4".parse::<Version>().unwrap(),
+            "a_package-1.2.4.vmod"
+        ).await.unwrap();
+
+        assert_eq!(
+            primaries(core.get_project(Project(42)).await.unwrap()),
+            [
+                Some("a_package-1.2.4.vmod".into()),
+                Some("maps-1.2.3.zip".into())
+            ]
         );
     }
 
-    #[sqlx::test(fixtures("users", "projects", "two_owners", "packages"))]
-    async fn get_project_revision_ok_old(pool: Pool) {
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn update_package_rename_reserved_name(pool: Pool) {
         let core = make_core(pool, fake_now, 0);
+
         assert_eq!(
-            core.get_project_revision(Project(42), 1).await.unwrap(),
-            ProjectData {
-                name: "test_game".into(),
-                description: "Brian's Trademarked Game of Being a Test Case".into(),
-                revision: 1,
-                created_at: "2023-11-12T15:50:06.419538067+00:00".into(),
-                modified_at: "2023-11-12T15:50:06.419538067+00:00".into(),
-                tags: vec![],
-                game: GameData {
-                    title: "A Game of Tests".into(),
-                    title_sort_key: "Game of Tests, A".into(),
-                    publisher: "Test Game Company".into(),
-                    year: "1978".into()
-                },
-                readme: "".into(),
-                image: None,
-                owners: vec!["alice".into(), "bob".into()],
-                packages: vec![
-                    PackageData {
-                        name: "b_package".into(),
-                        description: "".into(),
-                        releases: vec![],
-                        files: vec![]
-                    },
-                    PackageData {
-                        name: "c_package".into(),
-                        description: "".into(),
-                        releases: vec![],
-                        files: vec![]
-                    }
-                ]
-            }
+            core.update_package(
+                Owner(1),
+                Project(42),
+                Package(1),
+                &PackageDataPatch {
+                    name: Some("order".into()),
+                    description: None,
+                    section: None
+                }
+            ).await.unwrap_err(),
+            CoreError::InvalidPackageName
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn update_package_rename_invalid_name(pool: Pool) {
+        let core = make_core(pool, fake_now, 0);
+
+        assert_eq!(
+            core.update_package(
+                Owner(1),
+                Project(42),
+                Package(1),
+                &PackageDataPatch {
+                    name: Some(" a_package".into()),
+                    description: None,
+                    section: None
+                }
+            ).await.unwrap_err(),
+            CoreError::InvalidPackageName
         );
     }
 
     #[sqlx::test(fixtures("users", "projects", "packages"))]
-    async fn create_project_ok(pool: Pool) {
+    async fn add_file_after_rename_old_name(pool: Pool) {
         let core = make_core(pool, fake_now, 0);
 
-        let user = User(1);
-        let name = "newproj";
-        let data = ProjectData {
-            name: name.into(),
-            description: "A New Game".into(),
-            revision: 1,
-            created_at: NOW.into(),
-            modified_at: NOW.into(),
-            tags: vec![],
-            game: GameData {
-                title: "Some New Game".into(),
-                title_sort_key: "Some New Game".into(),
-                publisher: "XYZ Games".into(),
-                year: "1999".into()
-            },
-            readme: "".into(),
-            image: None,
-            owners: vec!["bob".into()],
-            packages: vec![]
-        };
+        core.update_package(
+            Owner(1),
+            Project(42),
+            Package(1),
+            &PackageDataPatch {
+                name: Some("renamed_package".into()),
+                description: None,
+                section: None
+            }
+        ).await.unwrap();
 
-        let cdata = ProjectDataPost {
-            description: data.description.clone(),
-            tags: vec![],
-            game: GameData {
-                title: data.game.title.clone(),
-                title_sort_key: data.game.title_sort_key.clone(),
-                publisher: data.game.publisher.clone(),
-                year: data.game.year.clone()
-            },
-            readme: "".into(),
-            image: None
-        };
+        // release URLs with the old name still reach the package
+        let pkg = core.get_package_id(Project(42), "a_package").await.unwrap();
+        assert_eq!(pkg, Package(1));
+
+        core.add_file(
+            Owner(1),
+            Project(42),
+            pkg,
+            &"1.2.3".parse::<Version>().unwrap(),
+            "scenario.vsav",
+            Bytes::from_static(b"some scenario")
+        ).await.unwrap();
 
-        core.create_project(user, name, &cdata).await.unwrap();
-        let proj = core.get_project_id(name).await.unwrap();
-        assert_eq!(core.get_project(proj).await.unwrap(), data);
+        let proj = core.get_project(Project(42)).await.unwrap();
+        let renamed = proj.packages.iter()
+            .find(|p| p.name == "renamed_package")
+            .unwrap();
+        assert!(
+            renamed.files.iter()
+                .any(|f| f.filename == "scenario.vsav" && f.version == "1.2.3")
+        );
     }
 
-    #[sqlx::test(fixtures("users", "projects", "one_owner"))]
-    async fn update_project_ok(pool: Pool) {
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn add_file_ok(pool: Pool) {
         let core = make_core(pool, fake_now, 0);
+        let data = Bytes::from_static(b"some scenario");
 
-        let name = "test_game";
-        let new_data = ProjectData {
-            name: name.into(),
-            description: "new description".into(),
-            revision: 4,
-            created_at: "2023-11-12T15:50:06.419538067+00:00".into(),
-            modified_at: NOW.into(),
-            tags: vec![],
-            game: GameData {
-                title: "Some New Game".into(),
-                title_sort_key: "Some New Game".into(),
-                publisher: "XYZ Games".into(),
-                year: "1999".into()
-            },
-            readme: "".into(),
-            image: None,
-            owners: vec!["bob".into()],
-            packages: vec![]
-        };
+        assert_eq!(
+            core.add_file(
+                Owner(1),
+                Project(42),
+                Package(1),
+                &"1.2.3".parse::<Version>().unwrap(),
+                "scenario.vsav",
+                data.clone()
+            ).await.unwrap(),
+            FileData {
+                version: "1.2.3".into(),
+                filename: "scenario.vsav".into(),
+                url: "https://example.com/3/9/39328baa0211e795a722ff1d70ce2d31b1cc74320f2710b84789587b1d17d303/scenario.vsav".into(),
+                size: data.len() as i64,
+                checksum: format!("{:x}", Sha256::digest(&data)),
+                published_at: NOW.into(),
+                published_by: "bob".into(),
+                requires: "".into(),
+                authors: vec![],
+                primary_file: None
+            }
+        );
+    }
 
-        let cdata = ProjectDataPatch {
-            description: Some(new_data.description.clone()),
-            tags: Some(vec![]),
-            game: GameDataPatch {
-                title: Some(new_data.game.title.clone()),
-                title_sort_key: Some(new_data.game.title_sort_key.clone()),
-                publisher: Some(new_data.game.publisher.clone()),
-                year: Some(new_data.game.year.clone())
-            },
-            readme: Some("".into()),
-            image: None
+    #[sqlx::test(fixtures("users", "projects", "packages", "players"))]
+    async fn add_file_notifies_subscribers(pool: Pool) {
+        let sink = VecSink::default();
+        let core = ProdCore {
+            notifier: Notifier::new(sink.clone()),
+            ..make_core(pool, fake_now, 0)
         };
 
-        let proj = core.get_project_id(name).await.unwrap();
-        let old_data = core.get_project(proj).await.unwrap();
-        core.update_project(Owner(1), Project(42), &cdata).await.unwrap();
-        // project has new data
-        assert_eq!(core.get_project(proj).await.unwrap(), new_data);
-        // old data is kept as a revision
+        core.subscribe(User(1), Project(42)).await.unwrap();
+        core.subscribe(User(2), Project(42)).await.unwrap();
+
+        let version = "1.2.3".parse::<Version>().unwrap();
+        let data = Bytes::from_static(b"some scenario");
+
+        core.add_file(
+            Owner(1),
+            Project(42),
+            Package(1),
+            &version,
+            "scenario.vsav",
+            data.clone()
+        ).await.unwrap();
+
+        // bob published it, so only alice is told
         assert_eq!(
-            core.get_project_revision(proj, 3).await.unwrap(),
-            old_data
+            *sink.0.lock().unwrap(),
+            [
+                Notification::ReleasePublished {
+                    user: "alice".into(),
+                    project: "test_game".into(),
+                    package: "a_package".into(),
+                    version: "1.2.3".into()
+                }
+            ]
         );
+
+        // further files for the same release are not announced
+        core.add_file(
+            Owner(1),
+            Project(42),
+            Package(1),
+            &version,
+            "other.vsav",
+            data
+        ).await.unwrap();
+
+        assert_eq!(sink.0.lock().unwrap().len(), 1);
     }
 
-    #[sqlx::test(fixtures("users", "projects", "packages"))]
-    async fn get_release_ok(pool: Pool) {
-        let core = make_core(pool, fake_now, 0);
+    #[sqlx::test(fixtures("users", "projects", "packages", "players"))]
+    async fn add_file_skips_unsubscribed(pool: Pool) {
+        let sink = VecSink::default();
+        let core = ProdCore {
+            notifier: Notifier::new(sink.clone()),
+            ..make_core(pool, fake_now, 0)
+        };
+
+        // alice and chuck play, but only alice wants to hear of releases
+        core.add_player(User(3), Project(42)).await.unwrap();
+        core.subscribe(User(2), Project(42)).await.unwrap();
+        core.subscribe(User(3), Project(42)).await.unwrap();
+        core.unsubscribe(User(3), Project(42)).await.unwrap();
+
+        core.add_file(
+            Owner(1),
+            Project(42),
+            Package(1),
+            &"1.2.3".parse::<Version>().unwrap(),
+            "scenario.vsav",
+            Bytes::from_static(b"some scenario")
+        ).await.unwrap();
+
         assert_eq!(
-            core.get_release(Project(42), Package(1)).await.unwrap(),
-            "https://example.com/a_package-1.2.4"
+            *sink.0.lock().unwrap(),
+            [
+                Notification::ReleasePublished {
+                    user: "alice".into(),
+                    project: "test_game".into(),
+                    package: "a_package".into(),
+                    version: "1.2.3".into()
+                }
+            ]
         );
     }
 
-    #[sqlx::test(fixtures("users", "projects", "packages"))]
-    async fn get_release_version_ok(pool: Pool) {
+    #[sqlx::test(fixtures("users", "projects", "players"))]
+    async fn subscribe_not_a_player(pool: Pool) {
         let core = make_core(pool, fake_now, 0);
-        let version = "1.2.3".parse::<Version>().unwrap();
         assert_eq!(
-            core.get_release_version(Project(42), Package(1), &version)
-                .await
-                .unwrap(),
-            "https://example.com/a_package-1.2.3"
+            core.subscribe(User(3), Project(42)).await.unwrap_err(),
+            CoreError::NotAPlayer
         );
     }
 
-    #[sqlx::test(fixtures("users", "projects", "packages"))]
-    async fn get_release_version_not_a_version(pool: Pool) {
+    #[sqlx::test(fixtures("users", "projects", "packages", "archived"))]
+    async fn add_file_archived(pool: Pool) {
         let core = make_core(pool, fake_now, 0);
-        let version = "1.0.0".parse::<Version>().unwrap();
+
         assert_eq!(
-            core.get_release_version(Project(42), Package(1), &version)
-                .await
-                .unwrap_err(),
-            CoreError::NotAVersion
+            core.add_file(
+                Owner(1),
+                Project(6),
+                Package(4),
+                &"1.2.3".parse::<Version>().unwrap(),
+                "scenario.vsav",
+                Bytes::from_static(b"some scenario")
+            ).await.unwrap_err(),
+            CoreError::ProjectArchived
         );
     }
 
-    #[sqlx::test(fixtures("users", "projects", "one_owner"))]
-    async fn get_owners_ok(pool: Pool) {
+    #[sqlx::test(fixtures("users", "projects", "archived"))]
+    async fn add_image_archived(pool: Pool) {
         let core = make_core(pool, fake_now, 0);
+
         assert_eq!(
-            core.get_owners(Project(42)).await.unwrap(),
-            Users { users: vec!["bob".into()] }
+            core.add_image(
+                Owner(1),
+                Project(6),
+                "image.png",
+                &mime::IMAGE_PNG,
+                None,
+                image_stream()
+            ).await.unwrap_err(),
+            CoreError::ProjectArchived
         );
     }
 
-    #[sqlx::test(fixtures("users", "projects", "one_owner"))]
-    async fn user_is_owner_true(pool: Pool) {
-        let core = make_core(pool, fake_now, 0);
-        assert!(core.user_is_owner(User(1), Project(42)).await.unwrap());
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn add_gallery_image_ok(pool: Pool) {
+        fn later_now() -> DateTime<Utc> {
+            *NOW_DT + chrono::Duration::seconds(1)
+        }
+
+        let core = make_core(pool.clone(), fake_now, 1024);
+
+        let a = core.add_gallery_image(
+            Owner(1),
+            Project(42),
+            "a.png",
+            &mime::IMAGE_PNG,
+            None,
+            image_stream()
+        ).await.unwrap();
+
+        let b = core.add_gallery_image(
+            Owner(1),
+            Project(42),
+            "b.png",
+            &mime::IMAGE_PNG,
+            None,
+            image_stream()
+        ).await.unwrap();
+
+        // replacing an image keeps its place in the gallery
+        let later_core = make_core(pool, later_now, 1024);
+
+        let a2 = later_core.add_gallery_image(
+            Owner(1),
+            Project(42),
+            "a.png",
+            &mime::IMAGE_PNG,
+            None,
+            image_stream()
+        ).await.unwrap();
+
+        assert_eq!(a, a2);
+        assert_ne!(a.id, b.id);
+
+        assert_eq!(
+            core.get_project(Project(42)).await.unwrap().gallery,
+            vec![
+                GalleryImage {
+                    id: a.id,
+                    filename: "a.png".into(),
+                    description: "".into()
+                },
+                GalleryImage {
+                    id: b.id,
+                    filename: "b.png".into(),
+                    description: "".into()
+                }
+            ]
+        );
     }
 
-    #[sqlx::test(fixtures("users", "projects", "one_owner"))]
-    async fn user_is_owner_false(pool: Pool) {
-        let core = make_core(pool, fake_now, 0);
-        assert!(!core.user_is_owner(User(2), Project(42)).await.unwrap());
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn add_gallery_image_too_large(pool: Pool) {
+        let core = make_core(pool, fake_now, 2);
+
+        assert_eq!(
+            core.add_gallery_image(
+                Owner(1),
+                Project(42),
+                "a.png",
+                &mime::IMAGE_PNG,
+                Some(3),
+                image_stream()
+            ).await.unwrap_err(),
+            CoreError::TooLarge(2)
+        );
     }
 
-    #[sqlx::test(fixtures("users", "projects", "one_owner"))]
-    async fn add_owners_ok(pool: Pool) {
-        let core = make_core(pool, fake_now, 0);
-        let users = Users { users: vec!["alice".into()] };
-        core.add_owners(&users, Project(42)).await.unwrap();
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn add_image_too_large_no_content_length(pool: Pool) {
+        let core = make_core(pool, fake_now, 4);
+
+        // a chunked body which never ends
+        let stream = futures::stream::repeat_with(
+            || Ok(Bytes::from_static(b"png"))
+        );
+
         assert_eq!(
-            core.get_owners(Project(42)).await.unwrap(),
-            Users {
-                users: vec![
-                    "alice".into(),
-                    "bob".into()
-                ]
-            }
+            core.add_image(
+                Owner(1),
+                Project(42),
+                "a.png",
+                &mime::IMAGE_PNG,
+                None,
+                Box::new(stream)
+            ).await.unwrap_err(),
+            CoreError::TooLarge(4)
         );
     }
 
-    #[sqlx::test(fixtures("users", "projects", "two_owners"))]
-    async fn remove_owners_ok(pool: Pool) {
-        let core = make_core(pool, fake_now, 0);
-        let users = Users { users: vec!["bob".into()] };
-        core.remove_owners(&users, Project(42)).await.unwrap();
+    #[sqlx::test(fixtures("users", "projects", "archived"))]
+    async fn add_gallery_image_archived(pool: Pool) {
+        let core = make_core(pool, fake_now, 1024);
+
         assert_eq!(
-            core.get_owners(Project(42)).await.unwrap(),
-            Users { users: vec!["alice".into()] }
+            core.add_gallery_image(
+                Owner(1),
+                Project(6),
+                "a.png",
+                &mime::IMAGE_PNG,
+                None,
+                image_stream()
+            ).await.unwrap_err(),
+            CoreError::ProjectArchived
         );
     }
 
-    #[sqlx::test(fixtures("users", "projects", "one_owner"))]
-    async fn remove_owners_fail_if_last(pool: Pool) {
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn add_file_module_ok(pool: Pool) {
         let core = make_core(pool, fake_now, 0);
-        let users = Users { users: vec!["bob".into()] };
+        let data = Bytes::from(std::fs::read("test/test.vmod").unwrap());
+
         assert_eq!(
-            core.remove_owners(&users, Project(1)).await.unwrap_err(),
-            CoreError::CannotRemoveLastOwner
+            core.add_file(
+                Owner(1),
+                Project(42),
+                Package(1),
+                &"0.0.0".parse::<Version>().unwrap(),
+                "test.vmod",
+                data
+            ).await.unwrap().filename,
+            "test.vmod"
         );
     }
 
-    #[sqlx::test(fixtures("users", "projects", "players"))]
-    async fn get_players_ok(pool: Pool) {
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn add_file_module_version_mismatch(pool: Pool) {
         let core = make_core(pool, fake_now, 0);
-        assert_eq!(
-            core.get_players(Project(42)).await.unwrap(),
-            Users {
-                users: vec![
-                    "alice".into(),
-                    "bob".into()
-                ]
-            }
+        let data = Bytes::from(std::fs::read("test/test.vmod").unwrap());
+
+        // CoreError equality ignores fields, so check them by matching
+        assert!(
+            matches!(
+                core.add_file(
+                    Owner(1),
+                    Project(42),
+                    Package(1),
+                    &"1.2.3".parse::<Version>().unwrap(),
+                    "test.vmod",
+                    data
+                ).await.unwrap_err(),
+                CoreError::ModuleVersionMismatch { expected, found }
+                    if expected == "1.2.3" && found == "0.0"
+            )
         );
     }
 
-    #[sqlx::test(fixtures("users", "projects", "players"))]
-    async fn add_player_ok(pool: Pool) {
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn add_file_invalid_module(pool: Pool) {
         let core = make_core(pool, fake_now, 0);
-        core.add_player(User(3), Project(42)).await.unwrap();
+
         assert_eq!(
-            core.get_players(Project(42)).await.unwrap(),
-            Users {
-                users: vec![
-                    "alice".into(),
-                    "bob".into(),
-                    "chuck".into()
-                ]
-            }
+            core.add_file(
+                Owner(1),
+                Project(42),
+                Package(1),
+                &"1.2.3".parse::<Version>().unwrap(),
+                "bogus.vmod",
+                Bytes::from_static(b"not a zip")
+            ).await.unwrap_err(),
+            CoreError::InvalidModule
         );
     }
 
-    #[sqlx::test(fixtures("users", "projects", "players"))]
-    async fn remove_player_ok(pool: Pool) {
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn add_file_invalid_filename(pool: Pool) {
         let core = make_core(pool, fake_now, 0);
-        core.remove_player(User(1), Project(42)).await.unwrap();
+
         assert_eq!(
-            core.get_players(Project(42)).await.unwrap(),
-            Users { users: vec!["alice".into()] }
+            core.add_file(
+                Owner(1),
+                Project(42),
+                Package(1),
+                &"1.2.3".parse::<Version>().unwrap(),
+                "../scenario.vsav",
+                Bytes::from_static(b"some scenario")
+            ).await.unwrap_err(),
+            CoreError::InvalidFilename
         );
     }
 
-    #[sqlx::test(fixtures("users", "projects", "images"))]
-    async fn get_image_ok(pool: Pool) {
-        let core = make_core(pool, fake_now, 0);
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn add_file_too_large(pool: Pool) {
+        let core = ProdCore {
+            max_file_size: 4,
+            storage_prefix_depth: DEFAULT_STORAGE_PREFIX_DEPTH,
+            ..make_core(pool, fake_now, 0)
+        };
+
         assert_eq!(
-            core.get_image(Project(42), "img.png").await.unwrap(),
-            "https://example.com/images/img.png"
+            core.add_file(
+                Owner(1),
+                Project(42),
+                Package(1),
+                &"1.2.3".parse::<Version>().unwrap(),
+                "scenario.vsav",
+                Bytes::from_static(b"some scenario")
+            ).await.unwrap_err(),
+            CoreError::TooLarge(4)
         );
     }
 
-    #[sqlx::test(fixtures("users", "projects", "images"))]
-    async fn get_image_not_a_project(pool: Pool) {
-        let core = make_core(pool, fake_now, 0);
+    #[cfg(feature = "failpoints")]
+    fn make_local_core(
+        pool: Pool,
+        uploads_directory: &std::path::Path
+    ) -> ProdCore<SqlxDatabaseClient<sqlx::sqlite::Sqlite>, LocalUploader>
+    {
+        ProdCore {
+            db: SqlxDatabaseClient(pool),
+            uploader: LocalUploader {
+                uploads_directory: uploads_directory.to_string_lossy().into(),
+                integrity_check: true
+            },
+            now: fake_now,
+            max_image_size: 1024,
+            max_file_size: 1024,
+            storage_prefix_depth: DEFAULT_STORAGE_PREFIX_DEPTH,
+            non_numeric_years: NonNumericYears::default(),
+            max_limit: Limit::new(100).unwrap(),
+            default_limit: Limit::new(10).unwrap(),
+            count_cache: CountCache::default(),
+            seek_max_age: 0,
+            record_usage: false,
+            notifier: Notifier::default(),
+            readme_cache: ReadmeCache::default()
+        }
+    }
+
+    fn image_stream() -> Box<dyn Stream<Item = Result<Bytes, io::Error>> + Send> {
+        Box::new(
+            futures::stream::once(async { Ok(Bytes::from_static(b"png")) })
+        )
+    }
+
+    #[cfg(feature = "failpoints")]
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn add_image_fail_before_upload(pool: Pool) {
+        let dir = tempfile::tempdir().unwrap();
+        let core = make_local_core(pool, dir.path());
+
+        crate::failpoints::enable("add_image_before_upload");
+
         assert_eq!(
-            core.get_image(Project(1), "img.png").await.unwrap_err(),
-            CoreError::NotFound
+            core.add_image(
+                Owner(1),
+                Project(42),
+                "image.png",
+                &mime::IMAGE_PNG,
+                None,
+                image_stream()
+            ).await.unwrap_err(),
+            CoreError::InternalError
         );
+
+        // nothing was written
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
     }
 
-    #[sqlx::test(fixtures("users", "projects", "images"))]
-    async fn get_image_not_an_image(pool: Pool) {
-        let core = make_core(pool, fake_now, 0);
+    #[cfg(feature = "failpoints")]
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn add_image_fail_after_upload(pool: Pool) {
+        let dir = tempfile::tempdir().unwrap();
+        let core = make_local_core(pool, dir.path());
+
+        crate::failpoints::enable("add_image_after_upload");
+
         assert_eq!(
-            core.get_image(Project(42), "bogus").await.unwrap_err(),
+            core.add_image(
+                Owner(1),
+                Project(42),
+                "image.png",
+                &mime::IMAGE_PNG,
+                None,
+                image_stream()
+            ).await.unwrap_err(),
+            CoreError::InternalError
+        );
+
+        // the uploaded file was cleaned up, and there is no record of it
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+        assert_eq!(
+            core.get_image(Project(42), "image.png").await.unwrap_err(),
             CoreError::NotFound
         );
     }
@@ -0,0 +1,298 @@
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{
+        HeaderMap, HeaderName, HeaderValue, Method,
+        header::{AUTHORIZATION, RETRY_AFTER}
+    },
+    middleware::Next,
+    response::{IntoResponse, Response}
+};
+use std::{
+    collections::HashMap,
+    net::{IpAddr, SocketAddr},
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH}
+};
+
+use crate::{
+    errors::AppError,
+    jwt::{self, DecodingKey}
+};
+
+pub const DEFAULT_WINDOW: u32 = 60;
+
+const X_FORWARDED_FOR: HeaderName = HeaderName::from_static("x-forwarded-for");
+const X_RATELIMIT_LIMIT: HeaderName = HeaderName::from_static("x-ratelimit-limit");
+const X_RATELIMIT_REMAINING: HeaderName = HeaderName::from_static("x-ratelimit-remaining");
+const X_RATELIMIT_RESET: HeaderName = HeaderName::from_static("x-ratelimit-reset");
+
+#[derive(Debug, Default)]
+struct Counts {
+    // the window to which the counts belong
+    window: u64,
+    hits: HashMap<(IpAddr, bool), u32>
+}
+
+#[derive(Debug, Default)]
+struct RateLimiterState {
+    // reads per window for anonymous requesters; 0 for no limit
+    anonymous_limit: u32,
+    // reads per window for authenticated requesters; 0 for no limit
+    user_limit: u32,
+    // window length in seconds
+    window: u32,
+    // proxies whose X-Forwarded-For we believe
+    trusted_proxies: Vec<IpAddr>,
+    counts: Mutex<Counts>
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct RateLimiter(Arc<RateLimiterState>);
+
+#[derive(Debug, Eq, PartialEq)]
+enum Verdict {
+    Unlimited,
+    Allow { limit: u32, remaining: u32, reset: u64 },
+    Deny { limit: u32, reset: u64 }
+}
+
+impl RateLimiter {
+    pub fn new(
+        anonymous_limit: u32,
+        user_limit: u32,
+        window: u32,
+        trusted_proxies: Vec<IpAddr>
+    ) -> Self
+    {
+        RateLimiter(
+            Arc::new(
+                RateLimiterState {
+                    anonymous_limit,
+                    user_limit,
+                    window: window.max(1),
+                    trusted_proxies,
+                    ..Default::default()
+                }
+            )
+        )
+    }
+
+    fn limit(&self, authenticated: bool) -> u32 {
+        if authenticated {
+            self.0.user_limit
+        }
+        else {
+            self.0.anonymous_limit
+        }
+    }
+
+    fn check(&self, addr: IpAddr, authenticated: bool, now: u64) -> Verdict {
+        let limit = self.limit(authenticated);
+        if limit == 0 {
+            return Verdict::Unlimited;
+        }
+
+        let window = self.0.window as u64;
+        let current = now / window;
+        let reset = (current + 1) * window - now;
+
+        let Ok(mut counts) = self.0.counts.lock() else {
+            return Verdict::Unlimited;
+        };
+
+        // fixed windows: forget everyone once a window ends
+        if counts.window != current {
+            counts.window = current;
+            counts.hits.clear();
+        }
+
+        let hits = counts.hits.entry((addr, authenticated)).or_insert(0);
+        if *hits >= limit {
+            Verdict::Deny { limit, reset }
+        }
+        else {
+            *hits += 1;
+            Verdict::Allow { limit, remaining: limit - *hits, reset }
+        }
+    }
+
+    // The client address is the peer, unless the peer is a trusted proxy,
+    // in which case it is the nearest untrusted hop in X-Forwarded-For.
+    fn real_addr(&self, peer: IpAddr, headers: &HeaderMap) -> IpAddr {
+        let trusted = &self.0.trusted_proxies;
+        if !trusted.contains(&peer) {
+            return peer;
+        }
+
+        headers.get_all(X_FORWARDED_FOR)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .filter_map(|a| a.trim().parse::<IpAddr>().ok())
+            .rev()
+            .find(|a| !trusted.contains(a))
+            .unwrap_or(peer)
+    }
+}
+
+fn authenticated(headers: &HeaderMap, key: &DecodingKey) -> bool {
+    headers.get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .is_some_and(|t| jwt::verify(t, key).is_ok())
+}
+
+fn add_limit_headers(
+    headers: &mut HeaderMap,
+    limit: u32,
+    remaining: u32,
+    reset: u64
+)
+{
+    headers.insert(X_RATELIMIT_LIMIT, HeaderValue::from(limit));
+    headers.insert(X_RATELIMIT_REMAINING, HeaderValue::from(remaining));
+    headers.insert(X_RATELIMIT_RESET, HeaderValue::from(reset));
+}
+
+fn too_many_requests(limit: u32, reset: u64) -> Response {
+    let mut response = (
+        [(RETRY_AFTER, HeaderValue::from(reset))],
+        AppError::TooManyRequests
+    ).into_response();
+
+    add_limit_headers(response.headers_mut(), limit, 0, reset);
+    response
+}
+
+pub async fn guard(
+    State(limiter): State<RateLimiter>,
+    State(key): State<DecodingKey>,
+    request: Request,
+    next: Next
+) -> Response
+{
+    // only reads are limited here; writes require a user anyway
+    let read = request.method() == Method::GET ||
+        request.method() == Method::HEAD;
+
+    let peer = request.extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(a)| a.ip());
+
+    let verdict = match (read, peer) {
+        (true, Some(peer)) => {
+            let addr = limiter.real_addr(peer, request.headers());
+            let authed = authenticated(request.headers(), &key);
+
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+
+            limiter.check(addr, authed, now)
+        },
+        _ => Verdict::Unlimited
+    };
+
+    match verdict {
+        Verdict::Unlimited => next.run(request).await,
+        Verdict::Allow { limit, remaining, reset } => {
+            let mut response = next.run(request).await;
+            add_limit_headers(response.headers_mut(), limit, remaining, reset);
+            response
+        },
+        Verdict::Deny { limit, reset } => too_many_requests(limit, reset)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const ALICE: IpAddr = IpAddr::V4(std::net::Ipv4Addr::new(192, 0, 2, 1));
+    const BOB: IpAddr = IpAddr::V4(std::net::Ipv4Addr::new(192, 0, 2, 2));
+    const PROXY: IpAddr = IpAddr::V4(std::net::Ipv4Addr::new(10, 0, 0, 1));
+
+    #[test]
+    fn rate_limiter_default_unlimited() {
+        let rl = RateLimiter::default();
+        for _ in 0..100 {
+            assert_eq!(rl.check(ALICE, false, 0), Verdict::Unlimited);
+        }
+    }
+
+    #[test]
+    fn rate_limiter_anonymous() {
+        let rl = RateLimiter::new(2, 0, 60, vec![]);
+        assert_eq!(
+            rl.check(ALICE, false, 10),
+            Verdict::Allow { limit: 2, remaining: 1, reset: 50 }
+        );
+        assert_eq!(
+            rl.check(ALICE, false, 11),
+            Verdict::Allow { limit: 2, remaining: 0, reset: 49 }
+        );
+        assert_eq!(
+            rl.check(ALICE, false, 12),
+            Verdict::Deny { limit: 2, reset: 48 }
+        );
+        // others are counted separately
+        assert_eq!(
+            rl.check(BOB, false, 12),
+            Verdict::Allow { limit: 2, remaining: 1, reset: 48 }
+        );
+        // authenticated requests are exempt
+        assert_eq!(rl.check(ALICE, true, 12), Verdict::Unlimited);
+        // the next window starts afresh
+        assert_eq!(
+            rl.check(ALICE, false, 60),
+            Verdict::Allow { limit: 2, remaining: 1, reset: 60 }
+        );
+    }
+
+    #[test]
+    fn rate_limiter_user_limit() {
+        let rl = RateLimiter::new(1, 2, 60, vec![]);
+        assert_eq!(
+            rl.check(ALICE, false, 0),
+            Verdict::Allow { limit: 1, remaining: 0, reset: 60 }
+        );
+        assert_eq!(
+            rl.check(ALICE, true, 0),
+            Verdict::Allow { limit: 2, remaining: 1, reset: 60 }
+        );
+        assert_eq!(
+            rl.check(ALICE, true, 0),
+            Verdict::Allow { limit: 2, remaining: 0, reset: 60 }
+        );
+        assert_eq!(
+            rl.check(ALICE, true, 0),
+            Verdict::Deny { limit: 2, reset: 60 }
+        );
+    }
+
+    #[test]
+    fn real_addr_untrusted_peer() {
+        let rl = RateLimiter::new(1, 0, 60, vec![PROXY]);
+        let mut headers = HeaderMap::new();
+        headers.insert(X_FORWARDED_FOR, "192.0.2.2".parse().unwrap());
+        assert_eq!(rl.real_addr(ALICE, &headers), ALICE);
+    }
+
+    #[test]
+    fn real_addr_trusted_peer() {
+        let rl = RateLimiter::new(1, 0, 60, vec![PROXY]);
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            X_FORWARDED_FOR,
+            "192.0.2.1, 192.0.2.2, 10.0.0.1".parse().unwrap()
+        );
+        assert_eq!(rl.real_addr(PROXY, &headers), BOB);
+    }
+
+    #[test]
+    fn real_addr_trusted_peer_no_header() {
+        let rl = RateLimiter::new(1, 0, 60, vec![PROXY]);
+        assert_eq!(rl.real_addr(PROXY, &HeaderMap::new()), PROXY);
+    }
+}
@@ -0,0 +1,127 @@
+use ammonia::Builder;
+use once_cell::sync::Lazy;
+use pulldown_cmark::{Options, Parser, html};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex}
+};
+
+use crate::model::Project;
+
+pub const MARKDOWN_CONTENT_TYPE: &str = "text/markdown; charset=utf-8";
+pub const HTML_CONTENT_TYPE: &str = "text/html; charset=utf-8";
+
+// The cache is emptied when it fills, rather than evicting carefully;
+// readmes are cheap enough to render that this is rarely noticed.
+const MAX_CACHED_READMES: usize = 1000;
+
+static SANITIZER: Lazy<Builder<'static>> = Lazy::new(|| {
+    let mut b = Builder::default();
+    b.url_schemes(HashSet::from(["http", "https", "mailto"]))
+        .link_rel(Some("noopener noreferrer nofollow"));
+    b
+});
+
+// Readmes are written by project owners, so whatever HTML they contain is
+// not to be trusted; everything which could run script is removed.
+pub fn render_html(markdown: &str) -> String {
+    let parser = Parser::new_ext(
+        markdown,
+        Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH
+    );
+
+    let mut unsafe_html = String::new();
+    html::push_html(&mut unsafe_html, parser);
+
+    SANITIZER.clean(&unsafe_html).to_string()
+}
+
+// keyed by project and revision
+type RenderedReadmes = HashMap<(i64, i64), Arc<str>>;
+
+// A revision never changes, so a readme rendered for one is good forever.
+#[derive(Clone, Debug, Default)]
+pub struct ReadmeCache(Arc<Mutex<RenderedReadmes>>);
+
+impl ReadmeCache {
+    pub fn get(&self, proj: Project, revision: i64) -> Option<Arc<str>> {
+        self.0.lock().ok()?.get(&(proj.0, revision)).cloned()
+    }
+
+    pub fn insert(&self, proj: Project, revision: i64, html: Arc<str>) {
+        if let Ok(mut cached) = self.0.lock() {
+            if cached.len() >= MAX_CACHED_READMES {
+                cached.clear();
+            }
+            cached.insert((proj.0, revision), html);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn render_html_markdown() {
+        assert_eq!(
+            render_html("# Title\n\nSome *emphasis*."),
+            "<h1>Title</h1>\n<p>Some <em>emphasis</em>.</p>\n"
+        );
+    }
+
+    #[test]
+    fn render_html_script() {
+        let html = render_html("Hi<script>alert('xss')</script>");
+        assert!(!html.contains("script"));
+        assert!(!html.contains("alert"));
+    }
+
+    #[test]
+    fn render_html_style() {
+        let html = render_html("<style>body { display: none }</style>ok");
+        assert!(!html.contains("style"));
+        assert!(!html.contains("display"));
+    }
+
+    #[test]
+    fn render_html_event_attribute() {
+        let html = render_html(r#"<img src="x.png" onerror="alert(1)">"#);
+        assert!(html.contains(r#"src="x.png""#));
+        assert!(!html.contains("onerror"));
+    }
+
+    #[test]
+    fn render_html_javascript_link() {
+        let html = render_html("[click](javascript:alert(1))");
+        assert!(!html.contains("javascript"));
+    }
+
+    #[test]
+    fn render_html_link_rel() {
+        assert_eq!(
+            render_html("[site](https://example.com)"),
+            "<p><a href=\"https://example.com\" rel=\"noopener noreferrer nofollow\">site</a></p>\n"
+        );
+    }
+
+    #[test]
+    fn readme_cache_get_insert() {
+        let cache = ReadmeCache::default();
+        assert_eq!(cache.get(Project(1), 2), None);
+        cache.insert(Project(1), 2, "<p>x</p>".into());
+        assert_eq!(cache.get(Project(1), 2).as_deref(), Some("<p>x</p>"));
+        assert_eq!(cache.get(Project(1), 3), None);
+    }
+
+    #[test]
+    fn readme_cache_full() {
+        let cache = ReadmeCache::default();
+        for i in 0..MAX_CACHED_READMES as i64 {
+            cache.insert(Project(i), 1, "".into());
+        }
+        cache.insert(Project(-1), 1, "".into());
+        assert_eq!(cache.get(Project(0), 1), None);
+        assert!(cache.get(Project(-1), 1).is_some());
+    }
+}
@@ -0,0 +1,43 @@
+use std::sync::Arc;
+
+#[derive(Clone, Debug)]
+pub struct RobotsTxt(pub Arc<String>);
+
+impl RobotsTxt {
+    pub fn new(api: &str, allow_api: bool) -> Self {
+        // Seek cursors are unique per page, so crawling them is pointless
+        // and churns the cache; they are disallowed even when the API is
+        // otherwise open to crawlers. The seek rule comes first for the
+        // benefit of crawlers which apply the first matching rule.
+        let api_rule = if allow_api { "Allow" } else { "Disallow" };
+
+        RobotsTxt(
+            Arc::new(
+                format!(
+                    "User-agent: *\nDisallow: {api}/*seek=\n{api_rule}: {api}/\n"
+                )
+            )
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn robots_txt_allow_api() {
+        assert_eq!(
+            *RobotsTxt::new("/api/v1", true).0,
+            "User-agent: *\nDisallow: /api/v1/*seek=\nAllow: /api/v1/\n"
+        );
+    }
+
+    #[test]
+    fn robots_txt_disallow_api() {
+        assert_eq!(
+            *RobotsTxt::new("/api/v1", false).0,
+            "User-agent: *\nDisallow: /api/v1/*seek=\nDisallow: /api/v1/\n"
+        );
+    }
+}
@@ -3,29 +3,42 @@ use sqlx::{
     Database, Executor,
     sqlite::Sqlite
 };
+use std::collections::HashMap;
 
+mod flags;
 mod images;
+mod notifications;
 mod packages;
 mod players;
 mod project;
 mod projects;
 mod releases;
+mod stats;
+mod tags;
+mod timestamps;
 mod users;
 
 use crate::{
     core::CoreError,
-    db::{DatabaseClient, FileRow, PackageRow, ProjectRow, ProjectSummaryRow},
-    model::{Owner, Package, PackageDataPost, Project, ProjectDataPatch, ProjectDataPost, User, Users},
-    pagination::{Direction, SortBy},
+    db::{DailyStatsRow, DatabaseClient, FileRow, FlagRow, NotificationRow, PackageRow, ProjectRow, ProjectStatsRow, ProjectStatusRow, ProjectYearRow, ProjectSummaryRow, ReleaseUrlRow, RevisionRow, SupersessionRow, TimestampRow, UserProfileRow, UserReleaseRow},
+    model::{GalleryImage, LibraryStats, Owner, Package, PackageDataPatch, PackageDataPost, Project, ProjectDataPatch, ProjectDataPatchResult, ProjectDataPost, SlugRecompute, User, Users},
+    notify::Notification,
+    pagination::{Direction, Facet, ReleaseAnchor, SortBy},
     time::rfc3339_to_nanos,
-    version::Version
+    version::{Requires, Version}
 };
 
 pub type Pool = sqlx::Pool<Sqlite>;
 
-#[derive(Clone)]
 pub struct SqlxDatabaseClient<DB: Database>(pub sqlx::Pool<DB>);
 
+// derived Clone would needlessly require DB: Clone
+impl<DB: Database> Clone for SqlxDatabaseClient<DB> {
+    fn clone(&self) -> Self {
+        SqlxDatabaseClient(self.0.clone())
+    }
+}
+
 #[async_trait]
 impl DatabaseClient for SqlxDatabaseClient<Sqlite> {
     async fn get_project_id(
@@ -36,19 +49,45 @@ impl DatabaseClient for SqlxDatabaseClient<Sqlite> {
         project::get_project_id(&self.0, projname).await
     }
 
+    async fn get_package_id(
+        &self,
+        proj: Project,
+        pkgname: &str
+    ) -> Result<Package, CoreError>
+    {
+        packages::get_package_id(&self.0, proj, pkgname).await
+    }
+
+    async fn get_random_project_id(
+        &self
+    ) -> Result<Project, CoreError>
+    {
+        project::get_random_project_id(&self.0).await
+    }
+
     async fn get_projects_count(
         &self,
+        facets: &[Facet]
     ) -> Result<i64, CoreError>
     {
-        projects::get_projects_count(&self.0).await
+        projects::get_projects_count(&self.0, facets).await
     }
 
     async fn get_projects_query_count(
         &self,
-        query: &str
+        query: &str,
+        facets: &[Facet]
     ) -> Result<i64, CoreError>
     {
-        projects::get_projects_query_count(&self.0, query).await
+        projects::get_projects_query_count(&self.0, query, facets).await
+    }
+
+    async fn get_tag_facet_counts(
+        &self,
+        facets: &[Facet]
+    ) -> Result<HashMap<String, i64>, CoreError>
+    {
+        projects::get_tag_facet_counts(&self.0, facets).await
     }
 
     async fn get_user_id(
@@ -59,6 +98,39 @@ impl DatabaseClient for SqlxDatabaseClient<Sqlite> {
         users::get_user_id(&self.0, username).await
     }
 
+    async fn get_username(
+        &self,
+        user: User
+    ) -> Result<String, CoreError>
+    {
+        users::get_username(&self.0, user).await
+    }
+
+    async fn get_user_profile_row(
+        &self,
+        user: User
+    ) -> Result<UserProfileRow, CoreError>
+    {
+        users::get_user_profile_row(&self.0, user).await
+    }
+
+    async fn get_owned_projects(
+        &self,
+        user: User
+    ) -> Result<Vec<ProjectSummaryRow>, CoreError>
+    {
+        projects::get_owned_projects(&self.0, user).await
+    }
+
+    async fn get_user_releases(
+        &self,
+        user: User,
+        limit: u32
+    ) -> Result<Vec<UserReleaseRow>, CoreError>
+    {
+        releases::get_user_releases(&self.0, user, limit).await
+    }
+
     async fn get_owners(
         &self,
         proj: Project
@@ -120,14 +192,61 @@ impl DatabaseClient for SqlxDatabaseClient<Sqlite> {
         users::has_owner(&self.0, proj).await
     }
 
+    async fn get_project_years(
+        &self
+    ) -> Result<Vec<ProjectYearRow>, CoreError>
+    {
+        projects::get_project_years(&self.0).await
+    }
+
+    async fn get_project_summary_row(
+        &self,
+        proj: Project
+    ) -> Result<Option<ProjectSummaryRow>, CoreError>
+    {
+        projects::get_project_summary_row(&self.0, proj).await
+    }
+
+    async fn get_project_summary_rows_by_name(
+        &self,
+        names: &[String]
+    ) -> Result<Vec<ProjectSummaryRow>, CoreError>
+    {
+        projects::get_project_summary_rows_by_name(&self.0, names).await
+    }
+
+    async fn get_supersedes(
+        &self,
+        proj: Project
+    ) -> Result<Vec<String>, CoreError>
+    {
+        projects::get_supersedes(&self.0, proj).await
+    }
+
+    async fn get_supersessions(
+        &self
+    ) -> Result<Vec<SupersessionRow>, CoreError>
+    {
+        projects::get_supersessions(&self.0).await
+    }
+
+    async fn get_popular_projects(
+        &self,
+        limit: u32
+    ) -> Result<Vec<ProjectSummaryRow>, CoreError>
+    {
+        projects::get_popular_projects(&self.0, limit).await
+    }
+
     async fn get_projects_end_window(
         &self,
         sort_by: SortBy,
         dir: Direction,
+        facets: &[Facet],
         limit: u32
     ) -> Result<Vec<ProjectSummaryRow>, CoreError>
     {
-        projects::get_projects_end_window(&self.0, sort_by, dir, limit).await
+        projects::get_projects_end_window(&self.0, sort_by, dir, facets, limit).await
     }
 
     async fn get_projects_query_end_window(
@@ -135,10 +254,11 @@ impl DatabaseClient for SqlxDatabaseClient<Sqlite> {
         query: &str,
         sort_by: SortBy,
         dir: Direction,
+        facets: &[Facet],
         limit: u32
     ) -> Result<Vec<ProjectSummaryRow>, CoreError>
     {
-        projects::get_projects_query_end_window(&self.0, query, sort_by, dir, limit).await
+        projects::get_projects_query_end_window(&self.0, query, sort_by, dir, facets, limit).await
     }
 
     async fn get_projects_mid_window(
@@ -147,6 +267,7 @@ impl DatabaseClient for SqlxDatabaseClient<Sqlite> {
         dir: Direction,
         field: &str,
         id: u32,
+        facets: &[Facet],
         limit: u32
     ) -> Result<Vec<ProjectSummaryRow>, CoreError>
     {
@@ -156,8 +277,9 @@ impl DatabaseClient for SqlxDatabaseClient<Sqlite> {
                 &self.0,
                 sort_by,
                 dir,
-                &rfc3339_to_nanos(field)?,
+                &time_field_to_nanos(field)?,
                 id,
+                facets,
                 limit
             ).await,
             _ => projects::get_projects_mid_window(
@@ -166,6 +288,7 @@ impl DatabaseClient for SqlxDatabaseClient<Sqlite> {
                 dir,
                 &field,
                 id,
+                facets,
                 limit
             ).await
         }
@@ -178,6 +301,7 @@ impl DatabaseClient for SqlxDatabaseClient<Sqlite> {
         dir: Direction,
         field: &str,
         id: u32,
+        facets: &[Facet],
         limit: u32
     ) -> Result<Vec<ProjectSummaryRow>, CoreError>
     {
@@ -188,8 +312,9 @@ impl DatabaseClient for SqlxDatabaseClient<Sqlite> {
                 query,
                 sort_by,
                 dir,
-                &rfc3339_to_nanos(field)?,
+                &time_field_to_nanos(field)?,
                 id,
+                facets,
                 limit
             ).await,
             SortBy::Relevance => projects::get_projects_query_mid_window(
@@ -199,6 +324,7 @@ impl DatabaseClient for SqlxDatabaseClient<Sqlite> {
                 dir,
                 &field.parse::<f64>().map_err(|_| CoreError::MalformedQuery)?,
                 id,
+                facets,
                 limit
             ).await,
             _ => projects::get_projects_query_mid_window(
@@ -208,18 +334,91 @@ impl DatabaseClient for SqlxDatabaseClient<Sqlite> {
                 dir,
                 &field,
                 id,
+                facets,
                 limit
             ).await
         }
     }
 
+    async fn get_projects_position(
+        &self,
+        sort_by: SortBy,
+        dir: Direction,
+        field: &str,
+        id: u32,
+        facets: &[Facet]
+    ) -> Result<i64, CoreError>
+    {
+        match sort_by {
+            SortBy::CreationTime |
+            SortBy::ModificationTime => projects::get_projects_position(
+                &self.0,
+                sort_by,
+                dir,
+                &time_field_to_nanos(field)?,
+                id,
+                facets
+            ).await,
+            _ => projects::get_projects_position(
+                &self.0,
+                sort_by,
+                dir,
+                &field,
+                id,
+                facets
+            ).await
+        }
+    }
+
+    async fn get_projects_query_position(
+        &self,
+        query: &str,
+        sort_by: SortBy,
+        dir: Direction,
+        field: &str,
+        id: u32,
+        facets: &[Facet]
+    ) -> Result<i64, CoreError>
+    {
+        match sort_by {
+            SortBy::CreationTime |
+            SortBy::ModificationTime => projects::get_projects_query_position(
+                &self.0,
+                query,
+                sort_by,
+                dir,
+                &time_field_to_nanos(field)?,
+                id,
+                facets
+            ).await,
+            SortBy::Relevance => projects::get_projects_query_position(
+                &self.0,
+                query,
+                sort_by,
+                dir,
+                &field.parse::<f64>().map_err(|_| CoreError::MalformedQuery)?,
+                id,
+                facets
+            ).await,
+            _ => projects::get_projects_query_position(
+                &self.0,
+                query,
+                sort_by,
+                dir,
+                &field,
+                id,
+                facets
+            ).await
+        }
+    }
+
     async fn create_project(
         &self,
         user: User,
         proj: &str,
         proj_data: &ProjectDataPost,
         now: i64
-    ) -> Result<(), CoreError>
+    ) -> Result<Project, CoreError>
     {
         project::create_project(&self.0, user, proj, proj_data, now).await
     }
@@ -229,10 +428,29 @@ impl DatabaseClient for SqlxDatabaseClient<Sqlite> {
         owner: Owner,
         proj: Project,
         proj_data: &ProjectDataPatch,
+        expected_revision: Option<i64>,
+        now: i64
+    ) -> Result<ProjectDataPatchResult, CoreError>
+    {
+        project::update_project(
+            &self.0,
+            owner,
+            proj,
+            proj_data,
+            expected_revision,
+            now
+        ).await
+    }
+
+    async fn delete_project(
+        &self,
+        owner: Owner,
+        proj: Project,
+        force: bool,
         now: i64
     ) -> Result<(), CoreError>
     {
-        project::update_project(&self.0, owner, proj, proj_data, now).await
+        project::delete_project(&self.0, owner, proj, force, now).await
     }
 
     async fn get_project_row(
@@ -252,6 +470,14 @@ impl DatabaseClient for SqlxDatabaseClient<Sqlite> {
         project::get_project_row_revision(&self.0, proj, revision).await
     }
 
+    async fn get_project_revisions(
+        &self,
+        proj: Project
+    ) -> Result<Vec<RevisionRow>, CoreError>
+    {
+        project::get_project_revisions(&self.0, proj).await
+    }
+
     async fn get_packages(
         &self,
         proj: Project
@@ -281,6 +507,53 @@ impl DatabaseClient for SqlxDatabaseClient<Sqlite> {
         packages::create_package(&self.0, owner, proj, pkg, pkg_data, now).await
     }
 
+    async fn update_package(
+        &self,
+        owner: Owner,
+        proj: Project,
+        pkg: Package,
+        pkg_data: &PackageDataPatch,
+        now: i64
+    ) -> Result<(), CoreError>
+    {
+        packages::update_package(&self.0, owner, proj, pkg, pkg_data, now).await
+    }
+
+    async fn delete_package(
+        &self,
+        owner: Owner,
+        proj: Project,
+        pkg: Package,
+        force: bool,
+        now: i64
+    ) -> Result<(), CoreError>
+    {
+        packages::delete_package(&self.0, owner, proj, pkg, force, now).await
+    }
+
+    async fn reorder_packages(
+        &self,
+        owner: Owner,
+        proj: Project,
+        order: &[String],
+        now: i64
+    ) -> Result<(), CoreError>
+    {
+        packages::reorder_packages(&self.0, owner, proj, order, now).await
+    }
+
+    async fn delete_release(
+        &self,
+        owner: Owner,
+        proj: Project,
+        pkg: Package,
+        version: &Version,
+        now: i64
+    ) -> Result<(), CoreError>
+    {
+        releases::delete_release(&self.0, owner, proj, pkg, version, now).await
+    }
+
     async fn get_releases(
         &self,
         pkg: Package
@@ -289,6 +562,24 @@ impl DatabaseClient for SqlxDatabaseClient<Sqlite> {
         releases::get_releases(&self.0, pkg).await
     }
 
+    async fn get_releases_window(
+        &self,
+        pkg: Package,
+        anchor: &ReleaseAnchor,
+        limit: u32
+    ) -> Result<Vec<FileRow>, CoreError>
+    {
+        releases::get_releases_window(&self.0, pkg, anchor, limit).await
+    }
+
+    async fn get_releases_count(
+        &self,
+        pkg: Package
+    ) -> Result<i64, CoreError>
+    {
+        releases::get_releases_count(&self.0, pkg).await
+    }
+
     async fn get_releases_at(
         &self,
         pkg: Package,
@@ -323,10 +614,18 @@ impl DatabaseClient for SqlxDatabaseClient<Sqlite> {
         get_authors(&self.0, pkg_ver_id).await
     }
 
+    async fn get_file_authors(
+        &self,
+        file_id: i64
+    ) -> Result<Users, CoreError>
+    {
+        get_file_authors(&self.0, file_id).await
+    }
+
     async fn get_release_url(
         &self,
         pkg: Package
-    ) -> Result<String, CoreError>
+    ) -> Result<ReleaseUrlRow, CoreError>
     {
         releases::get_release_url(&self.0, pkg).await
     }
@@ -335,17 +634,85 @@ impl DatabaseClient for SqlxDatabaseClient<Sqlite> {
         &self,
         pkg: Package,
         version: &Version
-    ) -> Result<String, CoreError>
+    ) -> Result<ReleaseUrlRow, CoreError>
     {
         releases::get_release_version_url(&self.0, pkg, version).await
     }
 
+    async fn set_recommended_release(
+        &self,
+        owner: Owner,
+        proj: Project,
+        pkg: Package,
+        version: Option<&Version>,
+        now: i64
+    ) -> Result<(), CoreError>
+    {
+        packages::set_recommended_release(
+            &self.0,
+            owner,
+            proj,
+            pkg,
+            version,
+            now
+        ).await
+    }
+
+    async fn set_primary_file(
+        &self,
+        owner: Owner,
+        proj: Project,
+        pkg: Package,
+        version: &Version,
+        filename: &str,
+        now: i64
+    ) -> Result<(), CoreError>
+    {
+        releases::set_primary_file(
+            &self.0,
+            owner,
+            proj,
+            pkg,
+            version,
+            filename,
+            now
+        ).await
+    }
+
+    async fn add_file_url(
+        &self,
+        owner: Owner,
+        proj: Project,
+        pkg: Package,
+        version: &Version,
+        filename: &str,
+        size: i64,
+        checksum: &str,
+        url: &str,
+        now: i64
+    ) -> Result<FileRow, CoreError>
+    {
+        releases::add_file_url(
+            &self.0,
+            owner,
+            proj,
+            pkg,
+            version,
+            filename,
+            size,
+            checksum,
+            url,
+            now
+        ).await
+    }
+
     async fn add_release_url(
         &self,
         owner: Owner,
         proj: Project,
         pkg: Package,
         version: &Version,
+        requires: Option<&Requires>,
         filename: &str,
         size: i64,
         checksum: &str,
@@ -359,6 +726,7 @@ impl DatabaseClient for SqlxDatabaseClient<Sqlite> {
             proj,
             pkg,
             version,
+            requires,
             filename,
             size,
             checksum,
@@ -393,6 +761,24 @@ impl DatabaseClient for SqlxDatabaseClient<Sqlite> {
         players::remove_player(&self.0, player, proj).await
     }
 
+    async fn get_subscribers(
+        &self,
+        proj: Project
+    ) -> Result<Users, CoreError>
+    {
+        players::get_subscribers(&self.0, proj).await
+    }
+
+    async fn set_subscribed(
+        &self,
+        player: User,
+        proj: Project,
+        subscribed: bool
+    ) -> Result<(), CoreError>
+    {
+        players::set_subscribed(&self.0, player, proj, subscribed).await
+    }
+
     async fn get_image_url(
         &self,
         proj: Project,
@@ -423,6 +809,192 @@ impl DatabaseClient for SqlxDatabaseClient<Sqlite> {
     {
         images::add_image_url(&self.0, owner, proj, img_name, url, now).await
     }
+
+    async fn remove_image(
+        &self,
+        owner: Owner,
+        proj: Project,
+        img_name: &str,
+        now: i64
+    ) -> Result<(), CoreError>
+    {
+        images::remove_image(&self.0, owner, proj, img_name, now).await
+    }
+
+    async fn get_gallery(
+        &self,
+        proj: Project
+    ) -> Result<Vec<GalleryImage>, CoreError>
+    {
+        images::get_gallery(&self.0, proj).await
+    }
+
+    async fn add_gallery_image(
+        &self,
+        owner: Owner,
+        proj: Project,
+        img_name: &str,
+        url: &str,
+        now: i64
+    ) -> Result<GalleryImage, CoreError>
+    {
+        images::add_gallery_image(&self.0, owner, proj, img_name, url, now).await
+    }
+
+    async fn get_tags(
+        &self,
+        proj: Project
+    ) -> Result<Vec<String>, CoreError>
+    {
+        tags::get_tags(&self.0, proj).await
+    }
+
+    async fn get_tags_at(
+        &self,
+        proj: Project,
+        revision: i64
+    ) -> Result<Vec<String>, CoreError>
+    {
+        tags::get_tags_at(&self.0, proj, revision).await
+    }
+
+    async fn get_flags(
+        &self,
+        facets: &[Facet]
+    ) -> Result<Vec<FlagRow>, CoreError>
+    {
+        flags::get_flags(&self.0, facets).await
+    }
+
+    async fn get_flag_project(
+        &self,
+        flag_id: i64
+    ) -> Result<Project, CoreError>
+    {
+        flags::get_flag_project(&self.0, flag_id).await
+    }
+
+    async fn resolve_flag(
+        &self,
+        admin: User,
+        flag_id: i64,
+        now: i64
+    ) -> Result<(), CoreError>
+    {
+        flags::resolve_flag(&self.0, admin, flag_id, now).await
+    }
+
+    async fn add_notification(
+        &self,
+        notification: &Notification,
+        now: i64
+    ) -> Result<(), CoreError>
+    {
+        notifications::add_notification(&self.0, notification, now).await
+    }
+
+    async fn get_notifications(
+        &self,
+        user: User,
+        before: Option<i64>,
+        limit: u32
+    ) -> Result<Vec<NotificationRow>, CoreError>
+    {
+        notifications::get_notifications(&self.0, user, before, limit).await
+    }
+
+    async fn get_unread_notifications_count(
+        &self,
+        user: User
+    ) -> Result<i64, CoreError>
+    {
+        notifications::get_unread_notifications_count(&self.0, user).await
+    }
+
+    async fn mark_notifications_read(
+        &self,
+        user: User,
+        ids: Option<&[i64]>,
+        now: i64
+    ) -> Result<(), CoreError>
+    {
+        notifications::mark_notifications_read(&self.0, user, ids, now).await
+    }
+
+    async fn recompute_normalized_names(
+        &self,
+        aliases: bool
+    ) -> Result<SlugRecompute, CoreError>
+    {
+        project::recompute_normalized_names(&self.0, aliases).await
+    }
+
+    async fn get_timestamps(
+        &self
+    ) -> Result<Vec<TimestampRow>, CoreError>
+    {
+        timestamps::get_timestamps(&self.0).await
+    }
+
+    async fn get_project_stats(
+        &self,
+        proj: Project
+    ) -> Result<ProjectStatsRow, CoreError>
+    {
+        stats::get_project_stats(&self.0, proj).await
+    }
+
+    async fn get_project_daily_stats(
+        &self,
+        proj: Project,
+        since: i64
+    ) -> Result<Vec<DailyStatsRow>, CoreError>
+    {
+        stats::get_project_daily_stats(&self.0, proj, since).await
+    }
+
+    async fn get_project_status(
+        &self,
+        proj: Project
+    ) -> Result<ProjectStatusRow, CoreError>
+    {
+        stats::get_project_status(&self.0, proj).await
+    }
+
+    async fn increment_project_views(
+        &self,
+        proj: Project,
+        day: i64
+    ) -> Result<(), CoreError>
+    {
+        stats::increment_project_views(&self.0, proj, day).await
+    }
+
+    async fn increment_file_downloads(
+        &self,
+        pkg: Package,
+        version: &str,
+        filename: &str,
+        day: i64
+    ) -> Result<(), CoreError>
+    {
+        stats::increment_file_downloads(&self.0, pkg, version, filename, day).await
+    }
+
+    async fn get_library_stats(
+        &self
+    ) -> Result<LibraryStats, CoreError>
+    {
+        stats::get_library_stats(&self.0).await
+    }
+}
+
+fn time_field_to_nanos(field: &str) -> Result<i64, CoreError> {
+    // Time anchors carry nanoseconds since the epoch, but links issued
+    // before that carried RFC 3339 timestamps, so accept those also
+    field.parse::<i64>()
+        .or_else(|_| rfc3339_to_nanos(field))
+        .or(Err(CoreError::MalformedQuery))
 }
 
 // TODO: move this... somewhere else
@@ -452,10 +1024,78 @@ ORDER BY users.username
     )
 }
 
+async fn get_file_authors<'e, E>(
+    ex: E,
+    file_id: i64
+) -> Result<Users, CoreError>
+where
+    E: Executor<'e, Database = Sqlite>
+{
+    // a file is credited to the authors of its release
+    Ok(
+        Users {
+            users: sqlx::query_scalar!(
+                "
+SELECT users.username
+FROM users
+JOIN authors
+ON users.user_id = authors.user_id
+JOIN releases
+ON authors.release_id = releases.release_id
+JOIN files
+ON releases.package_id = files.package_id
+    AND releases.version_major = files.version_major
+    AND releases.version_minor = files.version_minor
+    AND releases.version_patch = files.version_patch
+    AND releases.version_pre = files.version_pre
+    AND releases.version_build = files.version_build
+WHERE files.file_id = ?
+ORDER BY users.username
+                ",
+                file_id
+            )
+            .fetch_all(ex)
+            .await?
+        }
+    )
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
 
+    #[test]
+    fn time_field_to_nanos_nanos() {
+        assert_eq!(
+            time_field_to_nanos("1702569006419538067").unwrap(),
+            1702569006419538067
+        );
+    }
+
+    #[test]
+    fn time_field_to_nanos_rfc3339() {
+        assert_eq!(
+            time_field_to_nanos("2023-12-14T15:50:06.419538067+00:00").unwrap(),
+            1702569006419538067
+        );
+    }
+
+    #[test]
+    fn time_field_to_nanos_rfc3339_normalized() {
+        assert_eq!(
+            time_field_to_nanos("2023-12-14T15:50:06.4Z").unwrap(),
+            1702569006400000000
+        );
+    }
+
+    #[test]
+    fn time_field_to_nanos_bad() {
+        assert_eq!(
+            time_field_to_nanos("bogus").unwrap_err(),
+            CoreError::MalformedQuery
+        );
+    }
+
     #[sqlx::test(fixtures("users", "projects", "packages", "authors"))]
     async fn get_authors_ok(pool: Pool) {
         assert_eq!(
@@ -476,4 +1116,29 @@ mod test {
             Users { users: vec![] }
         );
     }
+
+    #[sqlx::test(fixtures("users", "projects", "packages", "files", "authors"))]
+    async fn get_file_authors_ok(pool: Pool) {
+        assert_eq!(
+            get_file_authors(&pool, 4).await.unwrap(),
+            Users {
+                users: vec![
+                    "alice".into(),
+                    "bob".into()
+                ]
+            }
+        );
+        assert_eq!(
+            get_file_authors(&pool, 2).await.unwrap(),
+            Users { users: vec!["alice".into()] }
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages", "files", "authors"))]
+    async fn get_file_authors_not_a_file(pool: Pool) {
+        assert_eq!(
+            get_file_authors(&pool, 0).await.unwrap(),
+            Users { users: vec![] }
+        );
+    }
 }
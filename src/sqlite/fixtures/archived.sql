@@ -0,0 +1 @@
+UPDATE projects SET archived = 1 WHERE project_id = 6;
@@ -0,0 +1,61 @@
+INSERT INTO packages (
+  package_id,
+  project_id,
+  name,
+  created_at,
+  created_by
+)
+VALUES
+  (4, 6, "d_package", 1702137389180282477, 1)
+;
+
+INSERT INTO files (
+  file_id,
+  package_id,
+  version,
+  version_major,
+  version_minor,
+  version_patch,
+  version_pre,
+  version_build,
+  url,
+  filename,
+  size,
+  checksum,
+  published_at,
+  published_by,
+  is_primary
+)
+VALUES
+  (
+    6,
+    4,
+    "0.1",
+    0,
+    1,
+    0,
+    "",
+    "",
+    "https://example.com/d_package-0.1.vmod",
+    "d_package-0.1.vmod",
+    5678,
+    "c0e3c0ee5e9ab6afb4db2b8bd5b1a2a8c4ddcb2fe3f2b7a6e6b8e3a43a7bb9ec",
+    1702137389180282477,
+    1,
+    0
+  )
+;
+
+INSERT INTO downloads (
+  package_id,
+  version,
+  filename,
+  day,
+  downloads
+)
+VALUES
+  (1, "1.2.3", "a_package-1.2.3.vmod", 19700, 3),
+  (1, "1.2.4", "a_package-1.2.4.vmod", 19700, 2),
+  (1, "1.2.4", "a_package-1.2.4.vmod", 19701, 4),
+  (4, "0.1", "d_package-0.1.vmod", 19700, 5)
+;
@@ -0,0 +1,103 @@
+INSERT INTO files (
+  file_id,
+  package_id,
+  version,
+  version_major,
+  version_minor,
+  version_patch,
+  version_pre,
+  version_build,
+  url,
+  filename,
+  size,
+  checksum,
+  published_at,
+  published_by,
+  is_primary
+)
+VALUES
+  (
+    1,
+    1,
+    "1.2.3",
+    1,
+    2,
+    3,
+    "",
+    "",
+    "https://example.com/a_package-1.2.3.vmod",
+    "a_package-1.2.3.vmod",
+    1234,
+    "c0e0fa7373a12b45a91e4f4d4e2e186442fc6ee9b346caa2fdc1c09026a2144a",
+    1702137389180282477,
+    1,
+    0
+  ),
+  (
+    2,
+    1,
+    "1.2.3",
+    1,
+    2,
+    3,
+    "",
+    "",
+    "https://example.com/maps-1.2.3.zip",
+    "maps-1.2.3.zip",
+    4321,
+    "3a94cbc9b8e0e64c4a71ffb3b2b1b5ab4e1d4c2a2e3d6b1bbf2a70b5c9c1f2d0",
+    1702137389180282477,
+    1,
+    1
+  ),
+  (
+    3,
+    1,
+    "1.2.4",
+    1,
+    2,
+    4,
+    "",
+    "",
+    "https://example.com/a_package-1.2.4.vmod",
+    "a_package-1.2.4.vmod",
+    5678,
+    "79fdd8fe3128f818e446e919cce5dcfb81815f8f4341c53f4d6b58ded48cebf2",
+    1702223789180282477,
+    2,
+    0
+  ),
+  (
+    4,
+    1,
+    "1.2.4",
+    1,
+    2,
+    4,
+    "",
+    "",
+    "https://example.com/a_package-1.2.4-fixed.vmod",
+    "a_package-1.2.4-fixed.vmod",
+    5679,
+    "0d7e3f1ad1d1a5f0bcf0a39e8d3b8e8c4d0c8b9a9f7e1a2c3b4d5e6f7a8b9c0d",
+    1702310189180282477,
+    2,
+    0
+  ),
+  (
+    5,
+    1,
+    "1.2.4",
+    1,
+    2,
+    4,
+    "",
+    "",
+    "https://example.com/notes-1.2.4.txt",
+    "notes-1.2.4.txt",
+    99,
+    "5f6e7d8c9b0a1f2e3d4c5b6a7f8e9d0c1b2a3f4e5d6c7b8a9f0e1d2c3b4a5f6e",
+    1702396589180282477,
+    2,
+    0
+  );
@@ -0,0 +1,15 @@
+INSERT INTO notifications (
+  notification_id,
+  user_id,
+  kind,
+  project,
+  package,
+  version,
+  created_at,
+  read_at
+)
+VALUES
+  (1, 1, "owner_added", "test_game", NULL, NULL, 1699804206419538067, 1700000000000000000),
+  (2, 1, "flag_resolved", "test_game", NULL, NULL, 1702569006419538067, NULL),
+  (3, 2, "owner_added", "test_game", NULL, NULL, 1702569006419538067, NULL),
+  (4, 1, "release_published", "test_game", "a_package", "1.2.4", 1703569006419538067, NULL);
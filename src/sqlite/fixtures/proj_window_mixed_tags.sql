@@ -0,0 +1,9 @@
+INSERT INTO tags (
+  project_id,
+  tag
+)
+VALUES
+  (1, "WWII"),
+  (2, "Ancients"),
+  (3, "wwii"),
+  (4, "WwIi");
@@ -0,0 +1,11 @@
+INSERT INTO tags (
+  project_id,
+  tag
+)
+VALUES
+  (1, "era:wwii"),
+  (2, "era:ancients"),
+  (2, "scale:operational"),
+  (3, "era:wwii:pacific"),
+  (4, "ERA:WWII"),
+  (4, "era");
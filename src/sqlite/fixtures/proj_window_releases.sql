@@ -0,0 +1,36 @@
+INSERT INTO packages (
+  package_id,
+  project_id,
+  name,
+  created_at,
+  created_by
+)
+VALUES
+  (1, 1, "a_package", 0, 1),
+  (2, 2, "b_package", 0, 1),
+  (3, 3, "c_package", 0, 1);
+
+INSERT INTO releases (
+  release_id,
+  package_id,
+  version,
+  version_major,
+  version_minor,
+  version_patch,
+  version_pre,
+  version_build,
+  url,
+  filename,
+  size,
+  checksum,
+  published_at,
+  published_by,
+  requires,
+  requires_major,
+  requires_minor,
+  requires_patch
+)
+VALUES
+  (1, 1, "1.0.0", 1, 0, 0, "", "", "", "a.vmod", 0, "", 0, 1, ">=3.7", 3, 7, 0),
+  (2, 2, "1.0.0", 1, 0, 0, "", "", "", "b.vmod", 0, "", 0, 1, "^3.6", 3, 6, 0),
+  (3, 3, "1.0.0", 1, 0, 0, "", "", "", "c.vmod", 0, "", 0, 1, "", NULL, NULL, NULL);
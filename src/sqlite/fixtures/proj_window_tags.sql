@@ -0,0 +1,9 @@
+INSERT INTO tags (
+  project_id,
+  tag
+)
+VALUES
+  (1, "wwii"),
+  (2, "ancients"),
+  (3, "wwii"),
+  (4, "wwii");
@@ -0,0 +1,24 @@
+INSERT INTO project_views (
+  project_id,
+  day,
+  views
+)
+VALUES
+  (42, 19600, 10),
+  (42, 19672, 2),
+  (42, 19673, 5),
+  (6, 19673, 1)
+;
+
+INSERT INTO downloads (
+  package_id,
+  version,
+  filename,
+  day,
+  downloads
+)
+VALUES
+  (1, "1.2.4", "a_package-1.2.4", 19600, 4),
+  (3, "0.1.0", "c_package-0.1.0", 19650, 1),
+  (1, "1.2.3", "a_package-1.2.3", 19673, 3)
+;
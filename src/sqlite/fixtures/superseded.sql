@@ -0,0 +1 @@
+UPDATE projects SET superseded_by = 42 WHERE project_id = 6;
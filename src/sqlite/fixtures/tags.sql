@@ -0,0 +1,18 @@
+INSERT INTO tags (
+  project_id,
+  tag
+)
+VALUES
+  (42, "wwii"),
+  (42, "tactical"),
+  (6, "ancients");
+
+INSERT INTO project_data_tags (
+  project_data_id,
+  tag
+)
+VALUES
+  (1, "wwii"),
+  (2, "wwii"),
+  (2, "tactical"),
+  (3, "ancients");
@@ -0,0 +1,223 @@
+use sqlx::{
+    Acquire, Executor, QueryBuilder,
+    sqlite::Sqlite
+};
+
+use crate::{
+    core::CoreError,
+    db::FlagRow,
+    model::{Project, User},
+    pagination::Facet
+};
+
+use super::projects::PushFacets;
+
+pub async fn get_flags<'e, 'f, E>(
+    ex: E,
+    facets: &'f [Facet]
+) -> Result<Vec<FlagRow>, CoreError>
+where
+    E: Executor<'e, Database = Sqlite>
+{
+    Ok(
+        QueryBuilder::new(
+            "
+SELECT
+    flags.flag_id,
+    projects.name AS project,
+    users.username AS flagged_by,
+    flags.flagged_at,
+    flags.flag,
+    flags.message,
+    flags.resolved_at,
+    resolvers.username AS resolved_by
+FROM flags
+JOIN projects
+ON flags.project_id = projects.project_id
+JOIN users
+ON flags.user_id = users.user_id
+LEFT JOIN users AS resolvers
+ON flags.resolved_by = resolvers.user_id"
+        )
+        .push_facets(facets, " WHERE ")
+        .push(" ORDER BY flags.flagged_at ASC, flags.flag_id ASC")
+        .build_query_as::<FlagRow>()
+        .fetch_all(ex)
+        .await?
+    )
+}
+
+pub async fn get_flag_project<'e, E>(
+    ex: E,
+    flag_id: i64
+) -> Result<Project, CoreError>
+where
+    E: Executor<'e, Database = Sqlite>
+{
+    sqlx::query_scalar!(
+        "
+SELECT project_id
+FROM flags
+WHERE flag_id = ?
+        ",
+        flag_id
+    )
+    .fetch_optional(ex)
+    .await?
+    .map(Project)
+    .ok_or(CoreError::NotAFlag)
+}
+
+pub async fn resolve_flag<'a, A>(
+    conn: A,
+    admin: User,
+    flag_id: i64,
+    now: i64
+) -> Result<(), CoreError>
+where
+    A: Acquire<'a, Database = Sqlite>
+{
+    let mut tx = conn.begin().await?;
+
+    let resolved_at = sqlx::query_scalar!(
+        "
+SELECT resolved_at
+FROM flags
+WHERE flag_id = ?
+        ",
+        flag_id
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(CoreError::NotAFlag)?;
+
+    if resolved_at.is_some() {
+        return Err(CoreError::FlagAlreadyResolved);
+    }
+
+    sqlx::query!(
+        "
+UPDATE flags
+SET resolved_at = ?,
+    resolved_by = ?
+WHERE flag_id = ?
+        ",
+        now,
+        admin.0,
+        flag_id
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    type Pool = sqlx::Pool<Sqlite>;
+
+    #[sqlx::test(fixtures("users", "projects", "flags"))]
+    async fn get_flags_ok(pool: Pool) {
+        assert_eq!(
+            get_flags(&pool, &[]).await.unwrap(),
+            [
+                FlagRow {
+                    flag_id: 2,
+                    project: "a_game".into(),
+                    flagged_by: "chuck".into(),
+                    flagged_at: 1702569006419538067,
+                    flag: "spam".into(),
+                    message: None,
+                    resolved_at: Some(1704569006419538067),
+                    resolved_by: Some("bob".into())
+                },
+                FlagRow {
+                    flag_id: 1,
+                    project: "test_game".into(),
+                    flagged_by: "alice".into(),
+                    flagged_at: 1703569006419538067,
+                    flag: "inappropriate".into(),
+                    message: Some("rude words".into()),
+                    resolved_at: None,
+                    resolved_by: None
+                }
+            ]
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "flags"))]
+    async fn get_flag_project_ok(pool: Pool) {
+        assert_eq!(get_flag_project(&pool, 2).await.unwrap(), Project(6));
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "flags"))]
+    async fn get_flag_project_not_a_flag(pool: Pool) {
+        assert_eq!(
+            get_flag_project(&pool, 3).await.unwrap_err(),
+            CoreError::NotAFlag
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn get_flags_none(pool: Pool) {
+        assert_eq!(get_flags(&pool, &[]).await.unwrap(), []);
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "flags"))]
+    async fn get_flags_unresolved(pool: Pool) {
+        assert_eq!(
+            get_flags(&pool, &[Facet::UnresolvedOnly]).await.unwrap(),
+            [
+                FlagRow {
+                    flag_id: 1,
+                    project: "test_game".into(),
+                    flagged_by: "alice".into(),
+                    flagged_at: 1703569006419538067,
+                    flag: "inappropriate".into(),
+                    message: Some("rude words".into()),
+                    resolved_at: None,
+                    resolved_by: None
+                }
+            ]
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "flags"))]
+    async fn resolve_flag_ok(pool: Pool) {
+        resolve_flag(&pool, User(3), 1, 1705569006419538067).await.unwrap();
+
+        let flags = get_flags(&pool, &[]).await.unwrap();
+        assert_eq!(flags[1].flag_id, 1);
+        assert_eq!(flags[1].resolved_at, Some(1705569006419538067));
+        assert_eq!(flags[1].resolved_by, Some("chuck".into()));
+
+        assert_eq!(
+            get_flags(&pool, &[Facet::UnresolvedOnly]).await.unwrap(),
+            []
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "flags"))]
+    async fn resolve_flag_already_resolved(pool: Pool) {
+        assert_eq!(
+            resolve_flag(&pool, User(3), 2, 1705569006419538067)
+                .await
+                .unwrap_err(),
+            CoreError::FlagAlreadyResolved
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "flags"))]
+    async fn resolve_flag_not_a_flag(pool: Pool) {
+        assert_eq!(
+            resolve_flag(&pool, User(3), 3, 1705569006419538067)
+                .await
+                .unwrap_err(),
+            CoreError::NotAFlag
+        );
+    }
+}
@@ -5,7 +5,7 @@ use sqlx::{
 
 use crate::{
     core::CoreError,
-    model::{Owner, Project},
+    model::{GalleryImage, Owner, Project},
     sqlite::project::update_project_non_project_data
 };
 
@@ -23,6 +23,7 @@ SELECT url
 FROM images
 WHERE project_id = ?
     AND filename = ?
+    AND NOT removed
 LIMIT 1
         ",
         proj.0,
@@ -86,7 +87,8 @@ ON CONFLICT(project_id, filename)
 DO UPDATE
 SET url = excluded.url,
     published_at = excluded.published_at,
-    published_by = excluded.published_by
+    published_by = excluded.published_by,
+    removed = 0
         ",
         proj.0,
         img_name,
@@ -175,6 +177,184 @@ where
     Ok(())
 }
 
+pub async fn remove_image<'a, A>(
+    conn: A,
+    owner: Owner,
+    proj: Project,
+    img_name: &str,
+    now: i64
+) -> Result<(), CoreError>
+where
+    A: Acquire<'a, Database = Sqlite>
+{
+    let mut tx = conn.begin().await?;
+
+    // the cover image must be unset before it can be removed
+    let cover = sqlx::query_scalar!(
+        "
+SELECT image
+FROM projects
+WHERE project_id = ?
+        ",
+        proj.0
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .flatten();
+
+    if cover.as_deref() == Some(img_name) {
+        return Err(CoreError::ImageInUse);
+    }
+
+    // the row remains, as past revisions of the project may refer to it
+    let removed = sqlx::query!(
+        "
+UPDATE images
+SET removed = 1
+WHERE project_id = ?
+    AND filename = ?
+    AND NOT removed
+        ",
+        proj.0,
+        img_name
+    )
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    if removed == 0 {
+        return Err(CoreError::NotFound);
+    }
+
+    sqlx::query!(
+        "
+DELETE FROM galleries
+WHERE project_id = ?
+    AND filename = ?
+        ",
+        proj.0,
+        img_name
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    // update project to reflect the change
+    update_project_non_project_data(&mut tx, owner, proj, now).await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+pub async fn get_gallery<'e, E>(
+    ex: E,
+    proj: Project
+) -> Result<Vec<GalleryImage>, CoreError>
+where
+    E: Executor<'e, Database = Sqlite>
+{
+    Ok(
+        sqlx::query_as!(
+            GalleryImage,
+            "
+SELECT
+    gallery_id AS id,
+    filename,
+    description
+FROM galleries
+WHERE project_id = ?
+ORDER BY position
+            ",
+            proj.0
+        )
+        .fetch_all(ex)
+        .await?
+    )
+}
+
+async fn append_gallery_row<'e, E>(
+    ex: E,
+    proj: Project,
+    img_name: &str
+) -> Result<(), CoreError>
+where
+    E: Executor<'e, Database = Sqlite>
+{
+    // an image already in the gallery keeps its place
+    sqlx::query!(
+        "
+INSERT INTO galleries (
+    project_id,
+    filename,
+    position
+)
+SELECT ?, ?, COALESCE(MAX(position) + 1, 0)
+FROM galleries
+WHERE project_id = ?
+ON CONFLICT(project_id, filename) DO NOTHING
+        ",
+        proj.0,
+        img_name,
+        proj.0
+    )
+    .execute(ex)
+    .await?;
+
+    Ok(())
+}
+
+async fn get_gallery_image<'e, E>(
+    ex: E,
+    proj: Project,
+    img_name: &str
+) -> Result<GalleryImage, CoreError>
+where
+    E: Executor<'e, Database = Sqlite>
+{
+    sqlx::query_as!(
+        GalleryImage,
+        "
+SELECT
+    gallery_id AS id,
+    filename,
+    description
+FROM galleries
+WHERE project_id = ?
+    AND filename = ?
+        ",
+        proj.0,
+        img_name
+    )
+    .fetch_optional(ex)
+    .await?
+    .ok_or(CoreError::NotFound)
+}
+
+pub async fn add_gallery_image<'a, A>(
+    conn: A,
+    owner: Owner,
+    proj: Project,
+    img_name: &str,
+    url: &str,
+    now: i64
+) -> Result<GalleryImage, CoreError>
+where
+    A: Acquire<'a, Database = Sqlite>
+{
+    let mut tx = conn.begin().await?;
+
+    // gallery images are stored and revisioned like any other image
+    add_image_url(&mut *tx, owner, proj, img_name, url, now).await?;
+
+    append_gallery_row(&mut *tx, proj, img_name).await?;
+
+    let item = get_gallery_image(&mut *tx, proj, img_name).await?;
+
+    tx.commit().await?;
+
+    Ok(item)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -291,4 +471,91 @@ mod test {
             )
         );
     }
+
+    #[sqlx::test(fixtures("users", "projects", "images"))]
+    async fn get_gallery_empty(pool: Pool) {
+        assert_eq!(get_gallery(&pool, Project(42)).await.unwrap(), vec![]);
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "images"))]
+    async fn add_gallery_image_appends(pool: Pool) {
+        let a = add_gallery_image(
+            &pool,
+            Owner(1),
+            Project(42),
+            "a.png",
+            "https://example.com/a.png",
+            1
+        ).await.unwrap();
+
+        // an existing image may be added to the gallery
+        let b = add_gallery_image(
+            &pool,
+            Owner(1),
+            Project(42),
+            "img.png",
+            "https://example.com/images/img.png",
+            2
+        ).await.unwrap();
+
+        assert_eq!(
+            get_gallery(&pool, Project(42)).await.unwrap(),
+            vec![a, b]
+        );
+        assert_eq!(
+            get_image_url(&pool, Project(42), "a.png").await.unwrap(),
+            "https://example.com/a.png"
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "images"))]
+    async fn add_gallery_image_not_a_project(pool: Pool) {
+        // This should not happen; the Project passed in should be good.
+        assert!(
+            matches!(
+                add_gallery_image(
+                    &pool,
+                    Owner(1),
+                    Project(0),
+                    "a.png",
+                    "https://example.com/a.png",
+                    0
+                ).await.unwrap_err(),
+                CoreError::DatabaseError(_)
+            )
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "images"))]
+    async fn remove_image_ok(pool: Pool) {
+        remove_image(&pool, Owner(1), Project(42), "img.png", 1)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            get_image_url(&pool, Project(42), "img.png").await.unwrap_err(),
+            CoreError::NotFound
+        );
+
+        // past versions remain available
+        assert_eq!(
+            get_image_url_at(
+                &pool,
+                Project(42),
+                "img.png",
+                1712012874000000000
+            ).await.unwrap(),
+            "https://example.com/images/img.png"
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "images"))]
+    async fn remove_image_not_an_image(pool: Pool) {
+        assert_eq!(
+            remove_image(&pool, Owner(1), Project(42), "bogus", 1)
+                .await
+                .unwrap_err(),
+            CoreError::NotFound
+        );
+    }
 }
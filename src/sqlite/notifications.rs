@@ -0,0 +1,264 @@
+use sqlx::{
+    Executor, QueryBuilder,
+    sqlite::Sqlite
+};
+
+use crate::{
+    core::CoreError,
+    db::NotificationRow,
+    model::User,
+    notify::Notification
+};
+
+pub async fn add_notification<'e, E>(
+    ex: E,
+    notification: &Notification,
+    now: i64
+) -> Result<(), CoreError>
+where
+    E: Executor<'e, Database = Sqlite>
+{
+    let user = notification.user();
+    let kind = notification.kind();
+    let project = notification.project();
+    let (package, version) = notification.release().unzip();
+
+    sqlx::query!(
+        "
+INSERT INTO notifications (
+    user_id,
+    kind,
+    project,
+    package,
+    version,
+    created_at
+)
+SELECT user_id, ?, ?, ?, ?, ?
+FROM users
+WHERE username = ?
+        ",
+        kind,
+        project,
+        package,
+        version,
+        now,
+        user
+    )
+    .execute(ex)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_notifications<'e, E>(
+    ex: E,
+    user: User,
+    before: Option<i64>,
+    limit: u32
+) -> Result<Vec<NotificationRow>, CoreError>
+where
+    E: Executor<'e, Database = Sqlite>
+{
+    // newest first; before is exclusive
+    let before = before.unwrap_or(i64::MAX);
+
+    Ok(
+        sqlx::query_as!(
+            NotificationRow,
+            "
+SELECT
+    notification_id,
+    kind,
+    project,
+    package,
+    version,
+    created_at,
+    read_at
+FROM notifications
+WHERE user_id = ?
+    AND notification_id < ?
+ORDER BY notification_id DESC
+LIMIT ?
+            ",
+            user.0,
+            before,
+            limit
+        )
+        .fetch_all(ex)
+        .await?
+    )
+}
+
+pub async fn get_unread_notifications_count<'e, E>(
+    ex: E,
+    user: User
+) -> Result<i64, CoreError>
+where
+    E: Executor<'e, Database = Sqlite>
+{
+    Ok(
+        sqlx::query_scalar!(
+            "
+SELECT COUNT(1) AS \"count!: i64\"
+FROM notifications
+WHERE user_id = ?
+    AND read_at IS NULL
+            ",
+            user.0
+        )
+        .fetch_one(ex)
+        .await?
+    )
+}
+
+pub async fn mark_notifications_read<'e, 'i, E>(
+    ex: E,
+    user: User,
+    ids: Option<&'i [i64]>,
+    now: i64
+) -> Result<(), CoreError>
+where
+    E: Executor<'e, Database = Sqlite>
+{
+    let mut qb = QueryBuilder::new("UPDATE notifications SET read_at = ");
+    qb.push_bind(now)
+        .push(" WHERE user_id = ")
+        .push_bind(user.0)
+        .push(" AND read_at IS NULL");
+
+    // all of the user's notifications are marked unless some are named
+    if let Some(ids) = ids {
+        qb.push(" AND notification_id IN (");
+        let mut qbs = qb.separated(", ");
+        for id in ids {
+            qbs.push_bind(id);
+        }
+        qb.push(")");
+    }
+
+    qb.build().execute(ex).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    type Pool = sqlx::Pool<Sqlite>;
+
+    fn ids(rows: Vec<NotificationRow>) -> Vec<i64> {
+        rows.into_iter().map(|r| r.notification_id).collect()
+    }
+
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn add_notification_ok(pool: Pool) {
+        let n = Notification::ReleasePublished {
+            user: "alice".into(),
+            project: "test_game".into(),
+            package: "a_package".into(),
+            version: "1.2.4".into()
+        };
+        add_notification(&pool, &n, 1702569006419538067).await.unwrap();
+
+        assert_eq!(
+            get_notifications(&pool, User(2), None, 10).await.unwrap(),
+            [
+                NotificationRow {
+                    notification_id: 1,
+                    kind: "release_published".into(),
+                    project: "test_game".into(),
+                    package: Some("a_package".into()),
+                    version: Some("1.2.4".into()),
+                    created_at: 1702569006419538067,
+                    read_at: None
+                }
+            ]
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn add_notification_not_a_user(pool: Pool) {
+        let n = Notification::OwnerAdded {
+            user: "nobody".into(),
+            project: "test_game".into()
+        };
+        add_notification(&pool, &n, 1702569006419538067).await.unwrap();
+
+        let count = sqlx::query_scalar!("SELECT COUNT(1) FROM notifications")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 0);
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "notifications"))]
+    async fn get_notifications_ok(pool: Pool) {
+        assert_eq!(
+            ids(get_notifications(&pool, User(1), None, 10).await.unwrap()),
+            [4, 2, 1]
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "notifications"))]
+    async fn get_notifications_window(pool: Pool) {
+        assert_eq!(
+            ids(get_notifications(&pool, User(1), None, 2).await.unwrap()),
+            [4, 2]
+        );
+        assert_eq!(
+            ids(get_notifications(&pool, User(1), Some(2), 2).await.unwrap()),
+            [1]
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "notifications"))]
+    async fn get_unread_notifications_count_ok(pool: Pool) {
+        assert_eq!(
+            get_unread_notifications_count(&pool, User(1)).await.unwrap(),
+            2
+        );
+        assert_eq!(
+            get_unread_notifications_count(&pool, User(3)).await.unwrap(),
+            0
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "notifications"))]
+    async fn mark_notifications_read_some(pool: Pool) {
+        mark_notifications_read(&pool, User(1), Some(&[4, 3]), 1704569006419538067)
+            .await
+            .unwrap();
+
+        // notification 3 belongs to someone else
+        assert_eq!(
+            get_unread_notifications_count(&pool, User(1)).await.unwrap(),
+            1
+        );
+        assert_eq!(
+            get_unread_notifications_count(&pool, User(2)).await.unwrap(),
+            1
+        );
+
+        let rows = get_notifications(&pool, User(1), None, 10).await.unwrap();
+        assert_eq!(rows[0].read_at, Some(1704569006419538067));
+        // already read, so unchanged
+        assert_eq!(rows[2].read_at, Some(1700000000000000000));
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "notifications"))]
+    async fn mark_notifications_read_all(pool: Pool) {
+        mark_notifications_read(&pool, User(1), None, 1704569006419538067)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            get_unread_notifications_count(&pool, User(1)).await.unwrap(),
+            0
+        );
+        assert_eq!(
+            get_unread_notifications_count(&pool, User(2)).await.unwrap(),
+            1
+        );
+    }
+}
@@ -1,15 +1,53 @@
 use sqlx::{
-    Acquire, Executor,
+    Acquire, Executor, Transaction,
     sqlite::Sqlite
 };
+use std::collections::HashSet;
 
 use crate::{
     core::CoreError,
     db::PackageRow,
-    model::{Owner, PackageDataPost, Project},
-    sqlite::project::update_project_non_project_data
+    model::{Owner, Package, PackageDataPatch, PackageDataPost, Project},
+    sqlite::{
+        project::update_project_non_project_data,
+        releases::get_release_version_url
+    },
+    version::Version
 };
 
+pub async fn get_package_id<'e, E>(
+    ex: E,
+    proj: Project,
+    pkgname: &str
+) -> Result<Package, CoreError>
+where
+    E: Executor<'e, Database = Sqlite>
+{
+    // an old name for a renamed package still finds it, unless another
+    // package has since taken that name
+    sqlx::query_scalar!(
+        "
+SELECT packages.package_id
+FROM packages
+LEFT JOIN package_redirects
+ON packages.package_id = package_redirects.package_id
+    AND package_redirects.name = ?
+WHERE packages.project_id = ?
+    AND (packages.name = ? OR package_redirects.name IS NOT NULL)
+ORDER BY packages.name = ? DESC
+LIMIT 1
+        ",
+        pkgname,
+        proj.0,
+        pkgname,
+        pkgname
+    )
+    .fetch_optional(ex)
+    .await?
+    .map(Package)
+    .ok_or(CoreError::NotAPackage)
+}
+
 pub async fn get_packages<'e, E>(
     ex: E,
     proj: Project
@@ -24,10 +62,20 @@ where
 SELECT
     package_id,
     name,
-    created_at
+    created_at,
+    description,
+    section,
+    (
+        SELECT version
+        FROM releases
+        WHERE releases.release_id = packages.recommended_release_id
+    ) AS \"recommended?: String\"
 FROM packages
 WHERE project_id = ?
-ORDER BY name COLLATE NOCASE ASC
+ORDER BY
+    sort_key IS NULL,
+    sort_key ASC,
+    name COLLATE NOCASE ASC
             ",
             proj.0
         )
@@ -49,14 +97,35 @@ where
             PackageRow,
             "
 SELECT
-    package_id,
-    name,
-    created_at
+    packages.package_id,
+    packages.name,
+    packages.created_at,
+    COALESCE(
+        (
+            SELECT package_description_revisions.description
+            FROM package_description_revisions
+            WHERE package_description_revisions.package_id = packages.package_id
+                AND package_description_revisions.modified_at <= ?
+            ORDER BY package_description_revisions.modified_at DESC
+            LIMIT 1
+        ),
+        packages.description
+    ) AS \"description!: String\",
+    packages.section,
+    (
+        SELECT version
+        FROM releases
+        WHERE releases.release_id = packages.recommended_release_id
+    ) AS \"recommended?: String\"
 FROM packages
-WHERE project_id = ?
-    AND created_at <= ?
-ORDER BY name COLLATE NOCASE ASC
+WHERE packages.project_id = ?
+    AND packages.created_at <= ?
+ORDER BY
+    packages.sort_key IS NULL,
+    packages.sort_key ASC,
+    packages.name COLLATE NOCASE ASC
             ",
+            date,
             proj.0,
             date
         )
@@ -65,6 +134,78 @@ ORDER BY name COLLATE NOCASE ASC
     )
 }
 
+async fn create_package_description_revision(
+    tx: &mut Transaction<'_, Sqlite>,
+    pkg: Package,
+    description: &str,
+    now: i64
+) -> Result<(), CoreError>
+{
+    // past project revisions show the description as it was then
+    sqlx::query!(
+        "
+INSERT INTO package_description_revisions (
+    package_id,
+    description,
+    modified_at
+)
+VALUES (?, ?, ?)
+ON CONFLICT(package_id, modified_at)
+DO UPDATE SET description = excluded.description
+        ",
+        pkg.0,
+        description,
+        now
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn create_package_row(
+    tx: &mut Transaction<'_, Sqlite>,
+    owner: Owner,
+    proj: Project,
+    pkg: &str,
+    description: &str,
+    section: Option<&str>,
+    now: i64
+) -> Result<(), CoreError>
+{
+    let pkg_id = sqlx::query!(
+        "
+INSERT INTO packages (
+    project_id,
+    name,
+    created_at,
+    created_by,
+    description,
+    section
+)
+VALUES (?, ?, ?, ?, ?, ?)
+            ",
+            proj.0,
+            pkg,
+            now,
+            owner.0,
+            description,
+            section
+    )
+    .execute(&mut **tx)
+    .await?
+    .last_insert_rowid();
+
+    create_package_description_revision(
+        tx,
+        Package(pkg_id),
+        description,
+        now
+    ).await?;
+
+    Ok(())
+}
+
 pub async fn create_package<'a, A>(
     conn: A,
     owner: Owner,
@@ -78,20 +219,428 @@ where
 {
     let mut tx = conn.begin().await?;
 
+    create_package_row(
+        &mut tx,
+        owner,
+        proj,
+        pkg,
+        &pkg_data.description,
+        pkg_data.section.as_deref(),
+        now
+    ).await?;
+
+    // update project to reflect the change
+    update_project_non_project_data(&mut tx, owner, proj, now).await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+async fn rename_package(
+    tx: &mut Transaction<'_, Sqlite>,
+    proj: Project,
+    pkg: Package,
+    new_name: &str
+) -> Result<(), CoreError>
+{
+    let old_name = sqlx::query_scalar!(
+        "
+SELECT name
+FROM packages
+WHERE package_id = ?
+    AND project_id = ?
+        ",
+        pkg.0,
+        proj.0
+    )
+    .fetch_optional(&mut **tx)
+    .await?
+    .ok_or(CoreError::NotAPackage)?;
+
+    if old_name == new_name {
+        return Ok(());
+    }
+
+    // the new name must not collide with any other package in the project
+    let in_use = sqlx::query_scalar!(
+        "
+SELECT 1
+FROM packages
+WHERE project_id = ?
+    AND name = ?
+    AND package_id != ?
+LIMIT 1
+        ",
+        proj.0,
+        new_name,
+        pkg.0
+    )
+    .fetch_optional(&mut **tx)
+    .await?
+    .is_some();
+
+    if in_use {
+        return Err(CoreError::PackageNameInUse);
+    }
+
+    // neither name is a redirect any longer, if it was one
     sqlx::query!(
         "
-INSERT INTO packages (
-    project_id,
+DELETE FROM package_redirects
+WHERE project_id = ?
+    AND (name = ? OR name = ?)
+        ",
+        proj.0,
+        old_name,
+        new_name
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    // send requests for the old name to the package
+    sqlx::query!(
+        "
+INSERT INTO package_redirects (
     name,
-    created_at,
-    created_by
+    project_id,
+    package_id
 )
-VALUES (?, ?, ?, ?)
+VALUES (?, ?, ?)
+        ",
+        old_name,
+        proj.0,
+        pkg.0
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn update_package<'a, A>(
+    conn: A,
+    owner: Owner,
+    proj: Project,
+    pkg: Package,
+    pkg_data: &PackageDataPatch,
+    now: i64
+) -> Result<(), CoreError>
+where
+    A: Acquire<'a, Database = Sqlite>
+{
+    let mut tx = conn.begin().await?;
+
+    if let Some(name) = &pkg_data.name {
+        rename_package(&mut tx, proj, pkg, name).await?;
+    }
+
+    // an absent section leaves the section alone; a null one clears it
+    let set_section = pkg_data.section.is_some();
+    let section = pkg_data.section.clone().flatten();
+
+    let updated = sqlx::query!(
+        "
+UPDATE packages
+SET name = COALESCE(?, name),
+    description = COALESCE(?, description),
+    section = CASE WHEN ? THEN ? ELSE section END
+WHERE package_id = ?
+    AND project_id = ?
+        ",
+        pkg_data.name,
+        pkg_data.description,
+        set_section,
+        section,
+        pkg.0,
+        proj.0
+    )
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    if updated == 0 {
+        return Err(CoreError::NotAPackage);
+    }
+
+    if let Some(description) = &pkg_data.description {
+        create_package_description_revision(&mut tx, pkg, description, now)
+            .await?;
+    }
+
+    // update project to reflect the change
+    update_project_non_project_data(&mut tx, owner, proj, now).await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+// The order must name each of the project's packages exactly once
+fn package_order_problems(
+    current: &[PackageRow],
+    order: &[String]
+) -> Vec<String>
+{
+    let mut seen = HashSet::new();
+    let mut problems = vec![];
+
+    for name in order {
+        if !seen.insert(name.as_str()) {
+            problems.push(format!("duplicate package {name}"));
+        }
+        else if !current.iter().any(|pr| &pr.name == name) {
+            problems.push(format!("unknown package {name}"));
+        }
+    }
+
+    problems.extend(
+        current.iter()
+            .filter(|pr| !seen.contains(pr.name.as_str()))
+            .map(|pr| format!("missing package {}", pr.name))
+    );
+
+    problems
+}
+
+pub async fn reorder_packages<'a, A>(
+    conn: A,
+    owner: Owner,
+    proj: Project,
+    order: &[String],
+    now: i64
+) -> Result<(), CoreError>
+where
+    A: Acquire<'a, Database = Sqlite>
+{
+    let mut tx = conn.begin().await?;
+
+    let current = get_packages(&mut *tx, proj).await?;
+
+    let problems = package_order_problems(&current, order);
+    if !problems.is_empty() {
+        return Err(CoreError::InvalidPackages(problems));
+    }
+
+    for (sort_key, name) in order.iter().enumerate() {
+        let sort_key = sort_key as i64;
+
+        sqlx::query!(
+            "
+UPDATE packages
+SET sort_key = ?
+WHERE project_id = ?
+    AND name = ?
             ",
+            sort_key,
             proj.0,
-            pkg,
-            now,
-            owner.0
+            name
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    // update project to reflect the change
+    update_project_non_project_data(&mut tx, owner, proj, now).await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+pub async fn set_recommended_release<'a, A>(
+    conn: A,
+    owner: Owner,
+    proj: Project,
+    pkg: Package,
+    version: Option<&Version>,
+    now: i64
+) -> Result<(), CoreError>
+where
+    A: Acquire<'a, Database = Sqlite>
+{
+    let mut tx = conn.begin().await?;
+
+    let release_id = match version {
+        Some(version) => Some(
+            get_release_version_url(&mut *tx, pkg, version)
+                .await?
+                .release_id
+        ),
+        None => None
+    };
+
+    let updated = sqlx::query!(
+        "
+UPDATE packages
+SET recommended_release_id = ?
+WHERE package_id = ?
+    AND project_id = ?
+        ",
+        release_id,
+        pkg.0,
+        proj.0
+    )
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    if updated == 0 {
+        return Err(CoreError::NotAPackage);
+    }
+
+    // update project to reflect the change
+    update_project_non_project_data(&mut tx, owner, proj, now).await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+async fn delete_package_contents(
+    tx: &mut Transaction<'_, Sqlite>,
+    pkg: Package
+) -> Result<(), CoreError>
+{
+    // remove everything which refers to the package's releases and files
+    sqlx::query!(
+        "
+UPDATE packages
+SET recommended_release_id = NULL
+WHERE package_id = ?
+        ",
+        pkg.0
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    sqlx::query!(
+        "
+DELETE FROM authors
+WHERE release_id IN (
+    SELECT release_id
+    FROM releases
+    WHERE package_id = ?
+)
+        ",
+        pkg.0
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    sqlx::query!(
+        "
+DELETE FROM downloads
+WHERE package_id = ?
+        ",
+        pkg.0
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    sqlx::query!(
+        "
+DELETE FROM files
+WHERE package_id = ?
+        ",
+        pkg.0
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    sqlx::query!(
+        "
+DELETE FROM releases
+WHERE package_id = ?
+        ",
+        pkg.0
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn delete_package<'a, A>(
+    conn: A,
+    owner: Owner,
+    proj: Project,
+    pkg: Package,
+    force: bool,
+    now: i64
+) -> Result<(), CoreError>
+where
+    A: Acquire<'a, Database = Sqlite>
+{
+    let mut tx = conn.begin().await?;
+
+    // the package must belong to the project before touching its contents
+    sqlx::query_scalar!(
+        "
+SELECT 1
+FROM packages
+WHERE package_id = ?
+    AND project_id = ?
+        ",
+        pkg.0,
+        proj.0
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(CoreError::NotAPackage)?;
+
+    if force {
+        delete_package_contents(&mut tx, pkg).await?;
+    }
+    else {
+        // only empty packages may be deleted unless forced
+        let has_releases = sqlx::query_scalar!(
+            "
+SELECT 1 FROM releases WHERE package_id = ?
+UNION ALL
+SELECT 1 FROM files WHERE package_id = ?
+LIMIT 1
+            ",
+            pkg.0,
+            pkg.0
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .is_some();
+
+        if has_releases {
+            return Err(CoreError::PackageHasReleases);
+        }
+    }
+
+    sqlx::query!(
+        "
+DELETE FROM package_redirects
+WHERE package_id = ?
+        ",
+        pkg.0
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query!(
+        "
+DELETE FROM package_description_revisions
+WHERE package_id = ?
+        ",
+        pkg.0
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query!(
+        "
+DELETE FROM packages
+WHERE package_id = ?
+    AND project_id = ?
+        ",
+        pkg.0,
+        proj.0
     )
     .execute(&mut *tx)
     .await?;
@@ -120,17 +669,26 @@ mod test {
                 PackageRow {
                     package_id: 1,
                     name: "a_package".into(),
-                    created_at: 1702137389180282477
+                    created_at: 1702137389180282477,
+                    description: "".into(),
+                    section: None,
+                    recommended: None
                 },
                 PackageRow {
                     package_id: 2,
                     name: "b_package".into(),
-                    created_at: 1667750189180282477
+                    created_at: 1667750189180282477,
+                    description: "".into(),
+                    section: None,
+                    recommended: None
                 },
                 PackageRow {
                     package_id: 3,
                     name: "c_package".into(),
-                    created_at: 1699286189180282477
+                    created_at: 1699286189180282477,
+                    description: "".into(),
+                    section: None,
+                    recommended: None
                 }
             ]
         );
@@ -164,7 +722,10 @@ mod test {
                 PackageRow {
                     package_id: 2,
                     name: "b_package".into(),
-                    created_at: 1667750189180282477
+                    created_at: 1667750189180282477,
+                    description: "".into(),
+                    section: None,
+                    recommended: None
                 }
             ]
         );
@@ -201,7 +762,8 @@ mod test {
             proj,
             "newpkg",
             &PackageDataPost {
-                description: "".into()
+                description: "A new package".into(),
+                section: None
             },
             1699804206419538067
         ).await.unwrap();
@@ -212,7 +774,10 @@ mod test {
                 PackageRow {
                     package_id: 4,
                     name: "newpkg".into(),
-                    created_at: 1699804206419538067
+                    created_at: 1699804206419538067,
+                    description: "A new package".into(),
+                    section: None,
+                    recommended: None
                 }
             ]
         );
@@ -223,6 +788,37 @@ mod test {
         );
     }
 
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn create_package_section_ok(pool: Pool) {
+        let proj = Project(6);
+
+        create_package(
+            &pool,
+            Owner(1),
+            proj,
+            "newpkg",
+            &PackageDataPost {
+                description: "".into(),
+                section: Some("Extensions".into())
+            },
+            1699804206419538067
+        ).await.unwrap();
+
+        assert_eq!(
+            get_packages(&pool, proj).await.unwrap(),
+            [
+                PackageRow {
+                    package_id: 4,
+                    name: "newpkg".into(),
+                    created_at: 1699804206419538067,
+                    description: "".into(),
+                    section: Some("Extensions".into()),
+                    recommended: None
+                }
+            ]
+        );
+    }
+
     #[sqlx::test(fixtures("users", "projects", "packages"))]
     async fn create_package_not_a_project(pool: Pool) {
         assert!(
@@ -233,7 +829,8 @@ mod test {
                     Project(0),
                     "newpkg",
                     &PackageDataPost {
-                        description: "".into()
+                        description: "".into(),
+                        section: None
                     },
                     1699804206419538067
                 ).await.unwrap_err(),
@@ -252,7 +849,8 @@ mod test {
                     Project(42),
                     "a_package",
                     &PackageDataPost {
-                        description: "".into()
+                        description: "".into(),
+                        section: None
                     },
                     1699804206419538067
                 ).await.unwrap_err(),
@@ -260,4 +858,451 @@ mod test {
             )
         );
     }
+
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn delete_package_ok(pool: Pool) {
+        let date = 1699804206419538067;
+
+        delete_package(&pool, Owner(1), Project(42), Package(2), false, date)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            get_packages(&pool, Project(42)).await.unwrap()
+                .into_iter()
+                .map(|r| r.name)
+                .collect::<Vec<_>>(),
+            ["a_package", "c_package"]
+        );
+
+        let proj_row = get_project_row(&pool, Project(42)).await.unwrap();
+        assert_eq!(proj_row.modified_at, date);
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn delete_package_has_releases(pool: Pool) {
+        assert_eq!(
+            delete_package(
+                &pool,
+                Owner(1),
+                Project(42),
+                Package(1),
+                false,
+                1699804206419538067
+            ).await.unwrap_err(),
+            CoreError::PackageHasReleases
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn delete_package_wrong_project(pool: Pool) {
+        assert_eq!(
+            delete_package(
+                &pool,
+                Owner(1),
+                Project(6),
+                Package(2),
+                false,
+                1699804206419538067
+            ).await.unwrap_err(),
+            CoreError::NotAPackage
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages", "files", "project_views"))]
+    async fn delete_package_has_releases_force(pool: Pool) {
+        delete_package(
+            &pool,
+            Owner(1),
+            Project(42),
+            Package(1),
+            true,
+            1699804206419538067
+        ).await.unwrap();
+
+        assert_eq!(
+            get_packages(&pool, Project(42)).await.unwrap()
+                .into_iter()
+                .map(|r| r.name)
+                .collect::<Vec<_>>(),
+            ["b_package", "c_package"]
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages", "files", "project_views"))]
+    async fn delete_package_wrong_project_force(pool: Pool) {
+        assert_eq!(
+            delete_package(
+                &pool,
+                Owner(1),
+                Project(6),
+                Package(1),
+                true,
+                1699804206419538067
+            ).await.unwrap_err(),
+            CoreError::NotAPackage
+        );
+
+        // nothing was removed from the package's own project
+        assert_eq!(get_packages(&pool, Project(42)).await.unwrap().len(), 3);
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn update_package_ok(pool: Pool) {
+        let date = 1699804206419538067;
+
+        update_package(
+            &pool,
+            Owner(1),
+            Project(42),
+            Package(2),
+            &PackageDataPatch {
+                name: None,
+                description: Some("Extra scenarios".into()),
+                section: Some(Some("Extras".into()))
+            },
+            date
+        ).await.unwrap();
+
+        let row = get_packages(&pool, Project(42)).await.unwrap()
+            .into_iter()
+            .find(|r| r.package_id == 2)
+            .unwrap();
+        assert_eq!(row.description, "Extra scenarios");
+        assert_eq!(row.section.as_deref(), Some("Extras"));
+
+        let proj_row = get_project_row(&pool, Project(42)).await.unwrap();
+        assert_eq!(proj_row.modified_at, date);
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn update_package_wrong_project(pool: Pool) {
+        assert_eq!(
+            update_package(
+                &pool,
+                Owner(1),
+                Project(6),
+                Package(2),
+                &PackageDataPatch {
+                    name: None,
+                    description: Some("x".into()),
+                    section: None
+                },
+                1699804206419538067
+            ).await.unwrap_err(),
+            CoreError::NotAPackage
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn reorder_packages_ok(pool: Pool) {
+        let date = 1699804206419538067;
+        let before = get_project_row(&pool, Project(42)).await.unwrap();
+
+        reorder_packages(
+            &pool,
+            Owner(1),
+            Project(42),
+            &["c_package".into(), "a_package".into(), "b_package".into()],
+            date
+        ).await.unwrap();
+
+        assert_eq!(
+            get_packages(&pool, Project(42)).await.unwrap()
+                .into_iter()
+                .map(|r| r.name)
+                .collect::<Vec<_>>(),
+            ["c_package", "a_package", "b_package"]
+        );
+
+        let after = get_project_row(&pool, Project(42)).await.unwrap();
+        assert_eq!(after.revision, before.revision + 1);
+        assert_eq!(after.modified_at, date);
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn reorder_packages_mismatch(pool: Pool) {
+        let before = get_project_row(&pool, Project(42)).await.unwrap();
+
+        assert_eq!(
+            reorder_packages(
+                &pool,
+                Owner(1),
+                Project(42),
+                &["c_package".into(), "c_package".into(), "x_package".into()],
+                1699804206419538067
+            ).await.unwrap_err(),
+            CoreError::InvalidPackages(vec![
+                "duplicate package c_package".into(),
+                "unknown package x_package".into(),
+                "missing package a_package".into(),
+                "missing package b_package".into()
+            ])
+        );
+
+        assert_eq!(
+            get_packages(&pool, Project(42)).await.unwrap()
+                .into_iter()
+                .map(|r| r.name)
+                .collect::<Vec<_>>(),
+            ["a_package", "b_package", "c_package"]
+        );
+
+        assert_eq!(
+            get_project_row(&pool, Project(42)).await.unwrap(),
+            before
+        );
+    }
+
+    fn rename(name: &str) -> PackageDataPatch {
+        PackageDataPatch {
+            name: Some(name.into()),
+            description: None,
+            section: None
+        }
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn get_package_id_ok(pool: Pool) {
+        assert_eq!(
+            get_package_id(&pool, Project(42), "b_package").await.unwrap(),
+            Package(2)
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn get_package_id_not_a_package(pool: Pool) {
+        assert_eq!(
+            get_package_id(&pool, Project(42), "x_package").await.unwrap_err(),
+            CoreError::NotAPackage
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn get_package_id_wrong_project(pool: Pool) {
+        assert_eq!(
+            get_package_id(&pool, Project(6), "b_package").await.unwrap_err(),
+            CoreError::NotAPackage
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn update_package_rename(pool: Pool) {
+        let date = 1699804206419538067;
+
+        update_package(
+            &pool,
+            Owner(1),
+            Project(42),
+            Package(2),
+            &rename("d_package"),
+            date
+        ).await.unwrap();
+
+        assert_eq!(
+            get_packages(&pool, Project(42)).await.unwrap()
+                .into_iter()
+                .map(|r| r.name)
+                .collect::<Vec<_>>(),
+            ["a_package", "c_package", "d_package"]
+        );
+
+        // both the old and the new names find the package
+        assert_eq!(
+            get_package_id(&pool, Project(42), "d_package").await.unwrap(),
+            Package(2)
+        );
+        assert_eq!(
+            get_package_id(&pool, Project(42), "b_package").await.unwrap(),
+            Package(2)
+        );
+
+        let proj_row = get_project_row(&pool, Project(42)).await.unwrap();
+        assert_eq!(proj_row.modified_at, date);
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn update_package_rename_back(pool: Pool) {
+        let date = 1699804206419538067;
+
+        for name in ["d_package", "b_package"] {
+            update_package(
+                &pool,
+                Owner(1),
+                Project(42),
+                Package(2),
+                &rename(name),
+                date
+            ).await.unwrap();
+        }
+
+        assert_eq!(
+            get_package_id(&pool, Project(42), "b_package").await.unwrap(),
+            Package(2)
+        );
+        assert_eq!(
+            get_package_id(&pool, Project(42), "d_package").await.unwrap(),
+            Package(2)
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn update_package_rename_in_use(pool: Pool) {
+        assert_eq!(
+            update_package(
+                &pool,
+                Owner(1),
+                Project(42),
+                Package(2),
+                &rename("a_package"),
+                1699804206419538067
+            ).await.unwrap_err(),
+            CoreError::PackageNameInUse
+        );
+
+        assert_eq!(
+            get_package_id(&pool, Project(42), "b_package").await.unwrap(),
+            Package(2)
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn update_package_rename_old_name_taken(pool: Pool) {
+        let date = 1699804206419538067;
+
+        // renaming another package to the old name takes it over
+        update_package(
+            &pool,
+            Owner(1),
+            Project(42),
+            Package(2),
+            &rename("d_package"),
+            date
+        ).await.unwrap();
+
+        update_package(
+            &pool,
+            Owner(1),
+            Project(42),
+            Package(3),
+            &rename("b_package"),
+            date
+        ).await.unwrap();
+
+        assert_eq!(
+            get_package_id(&pool, Project(42), "b_package").await.unwrap(),
+            Package(3)
+        );
+        assert_eq!(
+            get_package_id(&pool, Project(42), "c_package").await.unwrap(),
+            Package(3)
+        );
+        assert_eq!(
+            get_package_id(&pool, Project(42), "d_package").await.unwrap(),
+            Package(2)
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn delete_package_renamed(pool: Pool) {
+        let date = 1699804206419538067;
+
+        update_package(
+            &pool,
+            Owner(1),
+            Project(42),
+            Package(2),
+            &rename("d_package"),
+            date
+        ).await.unwrap();
+
+        delete_package(&pool, Owner(1), Project(42), Package(2), false, date)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            get_package_id(&pool, Project(42), "b_package").await.unwrap_err(),
+            CoreError::NotAPackage
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn get_packages_at_description(pool: Pool) {
+        let created = 1699804206419538067;
+        let updated = created + 1;
+
+        create_package(
+            &pool,
+            Owner(1),
+            Project(6),
+            "ext",
+            &PackageDataPost {
+                description: "Extra scenarios".into(),
+                section: None
+            },
+            created
+        ).await.unwrap();
+
+        let pkg = Package(get_packages(&pool, Project(6)).await.unwrap()[0].package_id);
+
+        update_package(
+            &pool,
+            Owner(1),
+            Project(6),
+            pkg,
+            &PackageDataPatch {
+                name: None,
+                description: Some("More scenarios".into()),
+                section: None
+            },
+            updated
+        ).await.unwrap();
+
+        assert_eq!(
+            get_packages_at(&pool, Project(6), created).await.unwrap()[0]
+                .description,
+            "Extra scenarios"
+        );
+        assert_eq!(
+            get_packages_at(&pool, Project(6), updated).await.unwrap()[0]
+                .description,
+            "More scenarios"
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn set_recommended_release_ok(pool: Pool) {
+        let version = "1.2.3".parse::<Version>().unwrap();
+
+        set_recommended_release(
+            &pool,
+            Owner(1),
+            Project(42),
+            Package(1),
+            Some(&version),
+            1699804206419538067
+        ).await.unwrap();
+
+        assert_eq!(
+            get_packages(&pool, Project(42)).await.unwrap()[0]
+                .recommended
+                .as_deref(),
+            Some("1.2.3")
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn set_recommended_release_wrong_project(pool: Pool) {
+        assert_eq!(
+            set_recommended_release(
+                &pool,
+                Owner(1),
+                Project(6),
+                Package(1),
+                None,
+                1699804206419538067
+            ).await.unwrap_err(),
+            CoreError::NotAPackage
+        );
+    }
 }
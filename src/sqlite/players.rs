@@ -84,6 +84,63 @@ WHERE user_id = ?
     Ok(())
 }
 
+pub async fn get_subscribers<'e, E>(
+    ex: E,
+    proj: Project
+) -> Result<Users, CoreError>
+where
+    E: Executor<'e, Database = Sqlite>
+{
+    Ok(
+        Users {
+            users: sqlx::query_scalar!(
+                "
+SELECT users.username
+FROM users
+JOIN players
+ON users.user_id = players.user_id
+WHERE players.project_id = ?
+    AND players.subscribed
+ORDER BY users.username
+                ",
+                proj.0
+            )
+            .fetch_all(ex)
+            .await?
+        }
+    )
+}
+
+pub async fn set_subscribed<'e, E>(
+    ex: E,
+    user: User,
+    proj: Project,
+    subscribed: bool
+) -> Result<(), CoreError>
+where
+    E: Executor<'e, Database = Sqlite>
+{
+    // only players may subscribe
+    match sqlx::query!(
+        "
+UPDATE players
+SET subscribed = ?
+WHERE user_id = ?
+    AND project_id = ?
+        ",
+        subscribed,
+        user.0,
+        proj.0
+    )
+    .execute(ex)
+    .await?
+    .rows_affected()
+    {
+        0 => Err(CoreError::NotAPlayer),
+        _ => Ok(())
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -231,4 +288,41 @@ mod test {
         // However, it's not an error if it does, just a no-op.
         remove_player(&pool, User(3), Project(0)).await.unwrap();
     }
+
+    #[sqlx::test(fixtures("users", "projects", "players"))]
+    async fn set_subscribed_ok(pool: Pool) {
+        assert_eq!(
+            get_subscribers(&pool, Project(42)).await.unwrap(),
+            Users { users: vec![] }
+        );
+        set_subscribed(&pool, User(2), Project(42), true).await.unwrap();
+        assert_eq!(
+            get_subscribers(&pool, Project(42)).await.unwrap(),
+            Users { users: vec!["alice".into()] }
+        );
+        set_subscribed(&pool, User(2), Project(42), false).await.unwrap();
+        assert_eq!(
+            get_subscribers(&pool, Project(42)).await.unwrap(),
+            Users { users: vec![] }
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "players"))]
+    async fn set_subscribed_not_a_player(pool: Pool) {
+        assert_eq!(
+            set_subscribed(&pool, User(3), Project(42), true).await.unwrap_err(),
+            CoreError::NotAPlayer
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "players"))]
+    async fn remove_player_unsubscribes(pool: Pool) {
+        set_subscribed(&pool, User(2), Project(42), true).await.unwrap();
+        remove_player(&pool, User(2), Project(42)).await.unwrap();
+        add_player(&pool, User(2), Project(42)).await.unwrap();
+        assert_eq!(
+            get_subscribers(&pool, Project(42)).await.unwrap(),
+            Users { users: vec![] }
+        );
+    }
 }
@@ -2,37 +2,124 @@ use sqlx::{
     Acquire, Executor, QueryBuilder, Transaction,
     sqlite::Sqlite
 };
+use std::collections::BTreeMap;
+use unicode_normalization::UnicodeNormalization;
 
 use crate::{
     core::CoreError,
-    db::ProjectRow,
-    model::{Owner, Project, ProjectDataPatch, ProjectDataPost, User},
-    sqlite::users::add_owner
+    db::{ProjectRow, RevisionRow},
+    model::{Owner, Project, ProjectDataPatch, ProjectDataPatchResult, ProjectDataPost, SlugChange, SlugCollision, SlugRecompute, User},
+    sqlite::{
+        packages::create_package_row,
+        tags::{add_project_data_tags, add_tags, get_tags, replace_tags},
+        users::add_owner
+    }
 };
 
-pub async fn get_project_id<'e, E>(
-    ex: E,
+pub async fn get_project_id<'a, A>(
+    conn: A,
     projname: &str
 ) -> Result<Project, CoreError>
 where
-    E: Executor<'e, Database = Sqlite>
+    A: Acquire<'a, Database = Sqlite>
 {
-    sqlx::query_scalar!(
+    let mut conn = conn.acquire().await?;
+
+    // the current name takes precedence over any old name
+    let proj_id = sqlx::query_scalar!(
         "
 SELECT project_id
 FROM projects
 WHERE name = ?
+    AND deleted_at IS NULL
+        ",
+        projname
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    if let Some(proj_id) = proj_id {
+        return Ok(Project(proj_id));
+    }
+
+    // an old name for a renamed project points to its current name
+    let current = sqlx::query_scalar!(
+        "
+SELECT projects.name
+FROM project_redirects
+JOIN projects
+ON projects.project_id = project_redirects.project_id
+WHERE project_redirects.name = ?
+    AND projects.deleted_at IS NULL
         ",
         projname
     )
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    match current {
+        Some(name) => Err(CoreError::ProjectMoved(name)),
+        None => Err(CoreError::NotAProject)
+    }
+}
+
+pub async fn get_random_project_id<'e, E>(
+    ex: E
+) -> Result<Project, CoreError>
+where
+    E: Executor<'e, Database = Sqlite>
+{
+    sqlx::query_scalar!(
+        "
+SELECT project_id
+FROM projects
+WHERE deleted_at IS NULL
+ORDER BY RANDOM()
+LIMIT 1
+        "
+    )
     .fetch_optional(ex)
     .await?
     .map(Project)
-    .ok_or(CoreError::NotAProject)
+    .ok_or(CoreError::NotFound)
 }
 
+// Names which differ only in case, in how their characters are composed,
+// or in hyphens versus underscores are the same name. Changing this rule
+// can make existing names collide; the slug recompute admin endpoint
+// reports any such collisions.
 fn normalize_project_name(proj: &str) -> String {
-    proj.to_lowercase().replace('-', "_")
+    proj.to_lowercase().nfkc().collect::<String>().replace('-', "_")
+}
+
+async fn project_name_in_use<'e, E>(
+    ex: E,
+    proj_norm: &str,
+    except: Option<Project>
+) -> Result<bool, CoreError>
+where
+    E: Executor<'e, Database = Sqlite>
+{
+    // deleted projects keep their names, so are included
+    let except = except.map(|p| p.0);
+
+    Ok(
+        sqlx::query_scalar!(
+            "
+SELECT 1
+FROM projects
+WHERE normalized_name = ?
+    AND (? IS NULL OR project_id != ?)
+LIMIT 1
+            ",
+            proj_norm,
+            except,
+            except
+        )
+        .fetch_optional(ex)
+        .await?
+        .is_some()
+    )
 }
 
 async fn create_project_row<'e, E>(
@@ -189,18 +276,27 @@ pub async fn create_project<'a, A>(
     name: &str,
     pd: &ProjectDataPost,
     now: i64
-) -> Result<(), CoreError>
+) -> Result<Project, CoreError>
 where
     A: Acquire<'a, Database = Sqlite>
 {
     let mut tx = conn.begin().await?;
 
+    // the name must not collide with the name of any other project
+    let norm = normalize_project_name(name);
+    if project_name_in_use(&mut *tx, &norm, None).await? {
+        return Err(CoreError::ProjectNameInUse);
+    }
+
     // create project row
     let proj = create_project_row(&mut *tx, owner, name, pd, now).await?;
 
     // associate new owner with the project
     add_owner(&mut *tx, owner, proj).await?;
 
+    // tag the project
+    add_tags(&mut tx, proj, &pd.tags).await?;
+
     // create project revision
     let dr = ProjectDataRow {
         project_id: proj.0,
@@ -215,6 +311,8 @@ where
 
     let project_data_id = create_project_data_row(&mut *tx, &dr).await?;
 
+    add_project_data_tags(&mut tx, project_data_id, &pd.tags).await?;
+
     let rr = ProjectRevisionRow {
         project_id: proj.0,
         name,
@@ -227,9 +325,22 @@ where
 
     create_project_revision_row(&mut *tx, &rr).await?;
 
+    // create the initial packages; any failure undoes the whole project
+    for pkg in &pd.packages {
+        create_package_row(
+            &mut tx,
+            Owner(owner.0),
+            proj,
+            &pkg.name,
+            &pkg.description,
+            pkg.section.as_deref(),
+            now
+        ).await?;
+    }
+
     tx.commit().await?;
 
-    Ok(())
+    Ok(proj)
 }
 
 async fn update_project_row<'e, E>(
@@ -257,6 +368,14 @@ where
         .push("modified_by = ")
         .push_bind_unseparated(owner.0);
 
+    if let Some(name) = &pd.name {
+        qbs
+            .push("name = ")
+            .push_bind_unseparated(name)
+            .push("normalized_name = ")
+            .push_bind_unseparated(normalize_project_name(name));
+    }
+
     if let Some(description) = &pd.description {
         qbs.push("description = ").push_bind_unseparated(description);
     }
@@ -285,6 +404,18 @@ where
         qbs.push("image = ").push_bind_unseparated(image);
     }
 
+    if let Some(archived) = pd.archived {
+        qbs.push("archived = ").push_bind_unseparated(archived);
+    }
+
+    if let Some(superseded_by) = &pd.superseded_by {
+        // a null or unknown name clears the link
+        qbs
+            .push("superseded_by = (SELECT project_id FROM projects WHERE name = ")
+            .push_bind_unseparated(superseded_by)
+            .push_unseparated(" AND deleted_at IS NULL)");
+    }
+
     qb
         .push(" WHERE project_id = ")
         .push_bind(proj.0)
@@ -295,13 +426,168 @@ where
     Ok(())
 }
 
+async fn rename_project(
+    tx: &mut Transaction<'_, Sqlite>,
+    proj: Project,
+    old_name: &str,
+    new_name: &str
+) -> Result<(), CoreError>
+{
+    // the new name must not collide with the name of any other project
+    let new_norm = normalize_project_name(new_name);
+
+    if project_name_in_use(&mut **tx, &new_norm, Some(proj)).await? {
+        return Err(CoreError::ProjectNameInUse);
+    }
+
+    // the new name is no longer a redirect, if it was one
+    sqlx::query!(
+        "
+DELETE FROM project_redirects
+WHERE name = ?
+        ",
+        new_name
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    // send requests for the old name to the new one
+    sqlx::query!(
+        "
+INSERT INTO project_redirects (
+    name,
+    project_id
+)
+VALUES (?, ?)
+        ",
+        old_name,
+        proj.0
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+// Only projects have normalized names; package names are compared as
+// stored, so have nothing to recompute.
+pub async fn recompute_normalized_names<'a, A>(
+    conn: A,
+    aliases: bool
+) -> Result<SlugRecompute, CoreError>
+where
+    A: Acquire<'a, Database = Sqlite>
+{
+    let mut tx = conn.begin().await?;
+
+    // deleted projects keep their names, so are included
+    let rows = sqlx::query!(
+        "
+SELECT
+    project_id,
+    name,
+    normalized_name
+FROM projects
+ORDER BY name
+        "
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    // group the projects by both their stored and recomputed names, so
+    // that a name which would be shared by two projects is caught
+    let mut claims: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+    for r in &rows {
+        let norm = normalize_project_name(&r.name);
+        claims.entry(norm.clone()).or_default().push(&r.name);
+        if r.normalized_name != norm {
+            claims.entry(r.normalized_name.clone())
+                .or_default()
+                .push(&r.name);
+        }
+    }
+
+    let mut result = SlugRecompute::default();
+    let mut collisions = BTreeMap::new();
+
+    for r in &rows {
+        let norm = normalize_project_name(&r.name);
+        if r.normalized_name == norm {
+            continue;
+        }
+
+        // never overwrite when the new name is claimed by another project
+        let claimants = &claims[&norm];
+        if claimants.len() > 1 {
+            collisions.insert(norm, claimants);
+            continue;
+        }
+
+        sqlx::query!(
+            "
+UPDATE projects
+SET normalized_name = ?
+WHERE project_id = ?
+            ",
+            norm,
+            r.project_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        // the old normalized name becomes an alias for the project, unless
+        // it is already the name of a project or of an alias
+        if aliases && r.normalized_name != r.name {
+            sqlx::query!(
+                "
+INSERT OR IGNORE INTO project_redirects (
+    name,
+    project_id
+)
+SELECT ?, ?
+WHERE NOT EXISTS (
+    SELECT 1
+    FROM projects
+    WHERE name = ?
+)
+                ",
+                r.normalized_name,
+                r.project_id,
+                r.normalized_name
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        result.changed.push(
+            SlugChange {
+                project: r.name.clone(),
+                from: r.normalized_name.clone(),
+                to: norm
+            }
+        );
+    }
+
+    result.collisions = collisions.into_iter()
+        .map(|(slug, projects)| SlugCollision {
+            slug,
+            projects: projects.iter().copied().map(String::from).collect()
+        })
+        .collect();
+
+    tx.commit().await?;
+
+    Ok(result)
+}
+
 pub async fn update_project<'a, A>(
     conn: A,
     owner: Owner,
     proj: Project,
     pd: &ProjectDataPatch,
+    expected_revision: Option<i64>,
     now: i64
-) -> Result<(), CoreError>
+) -> Result<ProjectDataPatchResult, CoreError>
 where
     A: Acquire<'a, Database = Sqlite>
 {
@@ -309,11 +595,32 @@ where
 
     // get project
     let row = get_project_row(&mut *tx, proj).await?;
+
+    // refuse to overwrite changes the client has not seen
+    if expected_revision.is_some_and(|r| r != row.revision) {
+        return Err(CoreError::RevisionMismatch);
+    }
+
     let revision = row.revision + 1;
 
+    // rename project
+    let name = match &pd.name {
+        Some(name) if *name != row.name => {
+            rename_project(&mut tx, proj, &row.name, name).await?;
+            name
+        },
+        _ => &row.name
+    };
+
     // update project
     update_project_row(&mut *tx, owner, proj, revision, pd, now).await?;
 
+    // update tags
+    let tag_changes = match &pd.tags {
+        Some(tags) => Some(replace_tags(&mut tx, proj, tags).await?),
+        None => None
+    };
+
     // create project revision
     let dr = ProjectDataRow {
         project_id: proj.0,
@@ -328,9 +635,16 @@ where
 
     let project_data_id = create_project_data_row(&mut *tx, &dr).await?;
 
+    let tags = match &tag_changes {
+        Some(tc) => tc.tags.clone(),
+        None => get_tags(&mut *tx, proj).await?
+    };
+
+    add_project_data_tags(&mut tx, project_data_id, &tags).await?;
+
     let rr = ProjectRevisionRow {
         project_id: proj.0,
-        name: &row.name,
+        name,
         created_at: row.created_at,
         modified_at: now,
         modified_by: owner.0,
@@ -342,6 +656,66 @@ where
 
     tx.commit().await?;
 
+    Ok(
+        ProjectDataPatchResult {
+            revision,
+            tags: tag_changes
+        }
+    )
+}
+
+pub async fn delete_project<'a, A>(
+    conn: A,
+    owner: Owner,
+    proj: Project,
+    force: bool,
+    now: i64
+) -> Result<(), CoreError>
+where
+    A: Acquire<'a, Database = Sqlite>
+{
+    let mut tx = conn.begin().await?;
+
+    // refuse to delete projects with releases unless forced
+    if !force {
+        let has_releases = sqlx::query_scalar!(
+            "
+SELECT 1
+FROM releases
+JOIN packages
+ON releases.package_id = packages.package_id
+WHERE packages.project_id = ?
+LIMIT 1
+            ",
+            proj.0
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .is_some();
+
+        if has_releases {
+            return Err(CoreError::ProjectHasReleases);
+        }
+    }
+
+    // tombstone the project; its revisions and files are retained
+    sqlx::query!(
+        "
+UPDATE projects
+SET deleted_at = ?,
+    deleted_by = ?
+WHERE project_id = ?
+    AND deleted_at IS NULL
+        ",
+        now,
+        owner.0,
+        proj.0
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
     Ok(())
 }
 
@@ -362,13 +736,15 @@ SELECT
     revision,
     created_at,
     modified_at,
-    modified_by,
+    (SELECT username FROM users WHERE users.user_id = projects.modified_by) AS \"modified_by?: String\",
     game_title,
     game_title_sort,
     game_publisher,
     game_year,
     readme,
-    image
+    image,
+    archived AS \"archived: bool\",
+    superseded_by
 FROM projects
 WHERE project_id = ?
 LIMIT 1
@@ -398,16 +774,20 @@ SELECT
     project_revisions.revision,
     project_revisions.created_at,
     project_revisions.modified_at,
-    project_revisions.modified_by,
+    (SELECT username FROM users WHERE users.user_id = project_revisions.modified_by) AS \"modified_by?: String\",
     project_data.game_title,
     project_data.game_title_sort,
     project_data.game_publisher,
     project_data.game_year,
     project_data.image,
-    project_data.readme
+    project_data.readme,
+    projects.archived AS \"archived: bool\",
+    projects.superseded_by
 FROM project_revisions
 JOIN project_data
 ON project_revisions.project_data_id = project_data.project_data_id
+JOIN projects
+ON project_revisions.project_id = projects.project_id
 WHERE project_revisions.project_id = ?
     AND project_revisions.revision = ?
 LIMIT 1
@@ -420,6 +800,34 @@ LIMIT 1
     .ok_or(CoreError::NotARevision)
 }
 
+pub async fn get_project_revisions<'e, E>(
+    ex: E,
+    proj: Project
+) -> Result<Vec<RevisionRow>, CoreError>
+where
+    E: Executor<'e, Database = Sqlite>
+{
+    Ok(
+        sqlx::query_as!(
+            RevisionRow,
+            "
+SELECT
+    project_revisions.revision,
+    project_revisions.modified_at,
+    users.username AS modified_by
+FROM project_revisions
+JOIN users
+ON project_revisions.modified_by = users.user_id
+WHERE project_revisions.project_id = ?
+ORDER BY project_revisions.revision DESC
+            ",
+            proj.0
+        )
+        .fetch_all(ex)
+        .await?
+    )
+}
+
 async fn get_project_data_id<'e, E>(
     ex: E,
     proj: Project,
@@ -492,7 +900,10 @@ mod test {
 
     use once_cell::sync::Lazy;
 
-    use crate::model::GameData;
+    use crate::{
+        model::{GameData, ProjectPackagePost, TagChanges},
+        sqlite::tags::get_tags_at
+    };
 
     type Pool = sqlx::Pool<Sqlite>;
 
@@ -512,6 +923,79 @@ mod test {
         );
     }
 
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn get_random_project_id_ok(pool: Pool) {
+        let proj = get_random_project_id(&pool).await.unwrap();
+        assert!(proj == Project(6) || proj == Project(42));
+    }
+
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn get_random_project_id_skips_deleted(pool: Pool) {
+        delete_project(&pool, Owner(1), Project(42), false, 1702569006419538067)
+            .await
+            .unwrap();
+
+        for _ in 0..10 {
+            assert_eq!(
+                get_random_project_id(&pool).await.unwrap(),
+                Project(6)
+            );
+        }
+    }
+
+    #[sqlx::test(fixtures("users"))]
+    async fn get_random_project_id_empty(pool: Pool) {
+        assert_eq!(
+            get_random_project_id(&pool).await.unwrap_err(),
+            CoreError::NotFound
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn delete_project_ok(pool: Pool) {
+        delete_project(&pool, Owner(1), Project(42), false, 1702569006419538067)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            get_project_id(&pool, "test_game").await.unwrap_err(),
+            CoreError::NotAProject
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn delete_project_has_releases(pool: Pool) {
+        assert_eq!(
+            delete_project(&pool, Owner(1), Project(42), false, 1702569006419538067)
+                .await
+                .unwrap_err(),
+            CoreError::ProjectHasReleases
+        );
+
+        assert_eq!(
+            get_project_id(&pool, "test_game").await.unwrap(),
+            Project(42)
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn delete_project_has_releases_force(pool: Pool) {
+        delete_project(&pool, Owner(1), Project(42), true, 1702569006419538067)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            get_project_id(&pool, "test_game").await.unwrap_err(),
+            CoreError::NotAProject
+        );
+
+        // the project's history is retained
+        assert_eq!(
+            get_project_row(&pool, Project(42)).await.unwrap().name,
+            "test_game"
+        );
+    }
+
     #[test]
     fn normalize_project_names() {
         assert_eq!(normalize_project_name("foo"), "foo");
@@ -528,13 +1012,15 @@ mod test {
             revision: 1,
             created_at: 1699804206419538067,
             modified_at: 1699804206419538067,
-            modified_by: 1,
+            modified_by: Some("bob".into()),
             game_title: "A Game of Tests".into(),
             game_title_sort: "Game of Tests, A".into(),
             game_publisher: "Test Game Company".into(),
             game_year: "1979".into(),
             readme: "".into(),
-            image: None
+            image: None,
+            archived: false,
+            superseded_by: None
         }
     );
 
@@ -549,7 +1035,8 @@ mod test {
                 year: CREATE_ROW.game_year.clone()
             },
             readme: "".into(),
-            image: None
+            image: None,
+            packages: vec![]
         }
     );
 
@@ -560,7 +1047,7 @@ mod test {
             CoreError::NotAProject
         );
 
-        create_project(
+        let proj = create_project(
             &pool,
             User(1),
             &CREATE_ROW.name,
@@ -568,7 +1055,10 @@ mod test {
             CREATE_ROW.created_at
         ).await.unwrap();
 
-        let proj = get_project_id(&pool, &CREATE_ROW.name).await.unwrap();
+        assert_eq!(
+            get_project_id(&pool, &CREATE_ROW.name).await.unwrap(),
+            proj
+        );
 
         assert_eq!(
             get_project_row(&pool, proj).await.unwrap(),
@@ -577,17 +1067,107 @@ mod test {
     }
 
     #[sqlx::test(fixtures("users"))]
-    async fn create_project_not_a_user(pool: Pool) {
-        assert_eq!(
-            get_project_id(&pool, &CREATE_ROW.name).await.unwrap_err(),
-            CoreError::NotAProject
-        );
+    async fn create_project_packages_rollback(pool: Pool) {
+        let pkg = ProjectPackagePost {
+            name: "module".into(),
+            description: "".into(),
+            section: None
+        };
+
+        // the second package collides with the first
+        let data = ProjectDataPost {
+            packages: vec![pkg.clone(), pkg],
+            ..CREATE_DATA.clone()
+        };
 
         assert!(
             matches!(
                 create_project(
                     &pool,
-                    User(0),
+                    User(1),
+                    &CREATE_ROW.name,
+                    &data,
+                    CREATE_ROW.created_at
+                ).await.unwrap_err(),
+                CoreError::DatabaseError(_)
+            )
+        );
+
+        assert_eq!(
+            get_project_id(&pool, &CREATE_ROW.name).await.unwrap_err(),
+            CoreError::NotAProject
+        );
+    }
+
+    #[sqlx::test(fixtures("users"))]
+    async fn create_project_name_in_use_accents(pool: Pool) {
+        // precomposed é
+        create_project(
+            &pool,
+            User(1),
+            "caf\u{e9}",
+            &CREATE_DATA,
+            CREATE_ROW.created_at
+        ).await.unwrap();
+
+        // E followed by a combining acute accent
+        assert_eq!(
+            create_project(
+                &pool,
+                User(1),
+                "CAFE\u{301}",
+                &CREATE_DATA,
+                CREATE_ROW.created_at
+            ).await.unwrap_err(),
+            CoreError::ProjectNameInUse
+        );
+    }
+
+    #[test]
+    fn normalize_project_name_non_ascii() {
+        // scripts without case or hyphens are left as they are; such names
+        // appear percent-encoded in URLs, which is valid, so no ASCII
+        // fallback is substituted
+        assert_eq!(normalize_project_name("宇宙戦艦ヤマト"), "宇宙戦艦ヤマト");
+    }
+
+    #[sqlx::test(fixtures("users"))]
+    async fn get_project_id_non_ascii(pool: Pool) {
+        let proj = create_project(
+            &pool,
+            User(1),
+            "宇宙戦艦ヤマト",
+            &CREATE_DATA,
+            CREATE_ROW.created_at
+        ).await.unwrap();
+
+        assert_eq!(get_project_id(&pool, "宇宙戦艦ヤマト").await.unwrap(), proj);
+    }
+
+    #[test]
+    fn normalize_project_name_composition() {
+        assert_eq!(
+            normalize_project_name("Caf\u{e9}-Game"),
+            normalize_project_name("CAFE\u{301}_game")
+        );
+        assert_ne!(
+            normalize_project_name("cafe"),
+            normalize_project_name("caf\u{e9}")
+        );
+    }
+
+    #[sqlx::test(fixtures("users"))]
+    async fn create_project_not_a_user(pool: Pool) {
+        assert_eq!(
+            get_project_id(&pool, &CREATE_ROW.name).await.unwrap_err(),
+            CoreError::NotAProject
+        );
+
+        assert!(
+            matches!(
+                create_project(
+                    &pool,
+                    User(0),
                     &CREATE_ROW.name,
                     &CREATE_DATA,
                     CREATE_ROW.created_at
@@ -614,17 +1194,15 @@ mod test {
             Project(row.project_id)
         );
 
-        assert!(
-            matches!(
-                create_project(
-                    &pool,
-                    User(1),
-                    &row.name,
-                    &CREATE_DATA,
-                    row.created_at
-                ).await.unwrap_err(),
-                CoreError::DatabaseError(_)
-            )
+        assert_eq!(
+            create_project(
+                &pool,
+                User(1),
+                &row.name,
+                &CREATE_DATA,
+                row.created_at
+            ).await.unwrap_err(),
+            CoreError::ProjectNameInUse
         );
     }
 
@@ -641,17 +1219,15 @@ mod test {
             Project(row.project_id)
         );
 
-        assert!(
-            matches!(
-                create_project(
-                    &pool,
-                    User(1),
-                    &row.name,
-                    &CREATE_DATA,
-                    row.created_at
-                ).await.unwrap_err(),
-                CoreError::DatabaseError(_)
-            )
+        assert_eq!(
+            create_project(
+                &pool,
+                User(1),
+                &row.name,
+                &CREATE_DATA,
+                row.created_at
+            ).await.unwrap_err(),
+            CoreError::ProjectNameInUse
         );
     }
 
@@ -668,17 +1244,15 @@ mod test {
             Project(row.project_id)
         );
 
-        assert!(
-            matches!(
-                create_project(
-                    &pool,
-                    User(1),
-                    &row.name,
-                    &CREATE_DATA,
-                    row.created_at
-                ).await.unwrap_err(),
-                CoreError::DatabaseError(_)
-            )
+        assert_eq!(
+            create_project(
+                &pool,
+                User(1),
+                &row.name,
+                &CREATE_DATA,
+                row.created_at
+            ).await.unwrap_err(),
+            CoreError::ProjectNameInUse
         );
     }
 
@@ -697,6 +1271,7 @@ mod test {
             Owner(1),
             proj,
             &pd,
+            None,
             1702569006419538068
         ).await.unwrap();
 
@@ -707,6 +1282,419 @@ mod test {
         assert_eq!(new_row.revision, orig_row.revision + 1);
     }
 
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn update_project_expected_revision_ok(pool: Pool) {
+        let proj = Project(42);
+        let orig_row = get_project_row(&pool, proj).await.unwrap();
+
+        let pd = ProjectDataPatch {
+            description: Some("foo".into()),
+            ..Default::default()
+        };
+
+        update_project(
+            &pool,
+            Owner(1),
+            proj,
+            &pd,
+            Some(orig_row.revision),
+            1702569006419538068
+        ).await.unwrap();
+
+        let new_row = get_project_row(&pool, proj).await.unwrap();
+        assert_eq!(new_row.revision, orig_row.revision + 1);
+    }
+
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn update_project_expected_revision_mismatch(pool: Pool) {
+        let proj = Project(42);
+        let orig_row = get_project_row(&pool, proj).await.unwrap();
+
+        let pd = ProjectDataPatch {
+            description: Some("foo".into()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            update_project(
+                &pool,
+                Owner(1),
+                proj,
+                &pd,
+                Some(orig_row.revision - 1),
+                1702569006419538068
+            ).await.unwrap_err(),
+            CoreError::RevisionMismatch
+        );
+
+        // nothing was written
+        assert_eq!(get_project_row(&pool, proj).await.unwrap(), orig_row);
+    }
+
+    #[sqlx::test(fixtures("users"))]
+    async fn create_project_tags_ok(pool: Pool) {
+        let data = ProjectDataPost {
+            tags: vec!["tactical".into(), "wwii".into()],
+            ..CREATE_DATA.clone()
+        };
+
+        create_project(
+            &pool,
+            User(1),
+            &CREATE_ROW.name,
+            &data,
+            CREATE_ROW.created_at
+        ).await.unwrap();
+
+        let proj = get_project_id(&pool, &CREATE_ROW.name).await.unwrap();
+
+        let mut tags = get_tags(&pool, proj).await.unwrap();
+        tags.sort();
+        assert_eq!(tags, data.tags);
+
+        let mut tags = get_tags_at(&pool, proj, 1).await.unwrap();
+        tags.sort();
+        assert_eq!(tags, data.tags);
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "tags"))]
+    async fn update_project_tags_ok(pool: Pool) {
+        let proj = Project(42);
+
+        let pd = ProjectDataPatch {
+            tags: Some(vec!["armor".into(), "wwii".into()]),
+            ..Default::default()
+        };
+
+        let result = update_project(
+            &pool,
+            Owner(1),
+            proj,
+            &pd,
+            None,
+            1702569006419538068
+        ).await.unwrap();
+
+        assert_eq!(
+            result,
+            ProjectDataPatchResult {
+                revision: 4,
+                tags: Some(
+                    TagChanges {
+                        tags: vec!["armor".into(), "wwii".into()],
+                        added: vec!["armor".into()],
+                        removed: vec!["tactical".into()]
+                    }
+                )
+            }
+        );
+
+        let mut tags = get_tags(&pool, proj).await.unwrap();
+        tags.sort();
+        assert_eq!(tags, result.tags.as_ref().unwrap().tags);
+
+        let mut tags = get_tags_at(&pool, proj, 4).await.unwrap();
+        tags.sort();
+        assert_eq!(tags, result.tags.as_ref().unwrap().tags);
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "tags"))]
+    async fn update_project_no_tags_ok(pool: Pool) {
+        let proj = Project(42);
+
+        let pd = ProjectDataPatch {
+            description: Some("foo".into()),
+            ..Default::default()
+        };
+
+        let result = update_project(
+            &pool,
+            Owner(1),
+            proj,
+            &pd,
+            None,
+            1702569006419538068
+        ).await.unwrap();
+
+        assert_eq!(
+            result,
+            ProjectDataPatchResult {
+                revision: 4,
+                tags: None
+            }
+        );
+
+        // the new revision retains the current tags
+        let mut tags = get_tags_at(&pool, proj, 4).await.unwrap();
+        tags.sort();
+        assert_eq!(tags, ["tactical", "wwii"]);
+    }
+
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn update_project_rename_ok(pool: Pool) {
+        let proj = Project(42);
+
+        let pd = ProjectDataPatch {
+            name: Some("Test_Game_2e".into()),
+            ..Default::default()
+        };
+
+        update_project(
+            &pool,
+            Owner(1),
+            proj,
+            &pd,
+            None,
+            1702569006419538068
+        ).await.unwrap();
+
+        let row = get_project_row(&pool, proj).await.unwrap();
+        assert_eq!(row.name, "Test_Game_2e");
+        assert_eq!(row.revision, 4);
+
+        // the new name finds the project
+        assert_eq!(
+            get_project_id(&pool, "Test_Game_2e").await.unwrap(),
+            proj
+        );
+
+        // the old name points to the new name
+        assert!(
+            matches!(
+                get_project_id(&pool, "test_game").await.unwrap_err(),
+                CoreError::ProjectMoved(name) if name == "Test_Game_2e"
+            )
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn update_project_rename_twice_ok(pool: Pool) {
+        let proj = Project(42);
+
+        for name in ["second_name", "third_name"] {
+            let pd = ProjectDataPatch {
+                name: Some(name.into()),
+                ..Default::default()
+            };
+
+            update_project(
+                &pool,
+                Owner(1),
+                proj,
+                &pd,
+                None,
+                1702569006419538068
+            ).await.unwrap();
+        }
+
+        // every old name points to the current name
+        for name in ["test_game", "second_name"] {
+            assert!(
+                matches!(
+                    get_project_id(&pool, name).await.unwrap_err(),
+                    CoreError::ProjectMoved(name) if name == "third_name"
+                )
+            );
+        }
+
+        // renaming back to an old name reclaims it
+        let pd = ProjectDataPatch {
+            name: Some("test_game".into()),
+            ..Default::default()
+        };
+
+        update_project(
+            &pool,
+            Owner(1),
+            proj,
+            &pd,
+            None,
+            1702569006419538068
+        ).await.unwrap();
+
+        assert_eq!(get_project_id(&pool, "test_game").await.unwrap(), proj);
+    }
+
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn recompute_normalized_names_unchanged(pool: Pool) {
+        assert_eq!(
+            recompute_normalized_names(&pool, true).await.unwrap(),
+            SlugRecompute::default()
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn recompute_normalized_names_rule_change(pool: Pool) {
+        // a normalized name as an older rule which kept hyphens made it
+        sqlx::query!(
+            "
+UPDATE projects
+SET name = 'Test-Game', normalized_name = 'test-game'
+WHERE project_id = 42
+            "
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        assert_eq!(
+            recompute_normalized_names(&pool, true).await.unwrap(),
+            SlugRecompute {
+                changed: vec![
+                    SlugChange {
+                        project: "Test-Game".into(),
+                        from: "test-game".into(),
+                        to: "test_game".into()
+                    }
+                ],
+                collisions: vec![]
+            }
+        );
+
+        let norm = sqlx::query_scalar!(
+            "
+SELECT normalized_name
+FROM projects
+WHERE project_id = 42
+            "
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+        assert_eq!(norm, "test_game");
+
+        // the old normalized name is now an alias
+        assert!(
+            matches!(
+                get_project_id(&pool, "test-game").await.unwrap_err(),
+                CoreError::ProjectMoved(name) if name == "Test-Game"
+            )
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn recompute_normalized_names_no_aliases(pool: Pool) {
+        sqlx::query!(
+            "
+UPDATE projects
+SET name = 'Test-Game', normalized_name = 'test-game'
+WHERE project_id = 42
+            "
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        assert_eq!(
+            recompute_normalized_names(&pool, false)
+                .await
+                .unwrap()
+                .changed
+                .len(),
+            1
+        );
+
+        assert_eq!(
+            get_project_id(&pool, "test-game").await.unwrap_err(),
+            CoreError::NotAProject
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn recompute_normalized_names_collision(pool: Pool) {
+        // under the current rule, this would collide with a_game
+        sqlx::query!(
+            "
+UPDATE projects
+SET name = 'A-Game', normalized_name = 'a-game'
+WHERE project_id = 42
+            "
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        assert_eq!(
+            recompute_normalized_names(&pool, true).await.unwrap(),
+            SlugRecompute {
+                changed: vec![],
+                collisions: vec![
+                    SlugCollision {
+                        slug: "a_game".into(),
+                        projects: vec!["A-Game".into(), "a_game".into()]
+                    }
+                ]
+            }
+        );
+
+        // the colliding project is left alone
+        let norm = sqlx::query_scalar!(
+            "
+SELECT normalized_name
+FROM projects
+WHERE project_id = 42
+            "
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+        assert_eq!(norm, "a-game");
+    }
+
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn update_project_archive_ok(pool: Pool) {
+        let proj = Project(42);
+
+        for archived in [true, false] {
+            let pd = ProjectDataPatch {
+                archived: Some(archived),
+                ..Default::default()
+            };
+
+            update_project(
+                &pool,
+                Owner(1),
+                proj,
+                &pd,
+                None,
+                1702569006419538068
+            ).await.unwrap();
+
+            assert_eq!(
+                get_project_row(&pool, proj).await.unwrap().archived,
+                archived
+            );
+        }
+    }
+
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn update_project_rename_collision(pool: Pool) {
+        let pd = ProjectDataPatch {
+            name: Some("A-Game".into()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            update_project(
+                &pool,
+                Owner(1),
+                Project(42),
+                &pd,
+                None,
+                1702569006419538068
+            ).await.unwrap_err(),
+            CoreError::ProjectNameInUse
+        );
+
+        // nothing changed
+        assert_eq!(
+            get_project_id(&pool, "test_game").await.unwrap(),
+            Project(42)
+        );
+    }
+
     #[sqlx::test(fixtures("users", "projects"))]
     async fn update_project_not_a_project(pool: Pool) {
         let pd = ProjectDataPatch {
@@ -720,6 +1708,7 @@ mod test {
                 Owner(1),
                 Project(0),
                 &pd,
+                None,
                 0
             ).await.unwrap_err(),
             CoreError::NotAProject
@@ -741,6 +1730,7 @@ mod test {
                     Owner(0),
                     Project(42),
                     &pd,
+                    None,
                     0
                 ).await.unwrap_err(),
                 CoreError::DatabaseError(_)
@@ -812,13 +1802,15 @@ mod test {
             revision: 3,
             created_at: 1699804206419538067,
             modified_at: 1702569006419538067,
-            modified_by: 1,
+            modified_by: Some("bob".into()),
             game_title: "A Game of Tests".into(),
             game_title_sort: "Game of Tests, A".into(),
             game_publisher: "Test Game Company".into(),
             game_year: "1979".into(),
             readme: "".into(),
-            image: None
+            image: None,
+            archived: false,
+            superseded_by: None
         }
     );
 
@@ -830,13 +1822,15 @@ mod test {
             revision: 1,
             created_at: 1699804206419538067,
             modified_at: 1699804206419538067,
-            modified_by: 1,
+            modified_by: Some("bob".into()),
             game_title: "A Game of Tests".into(),
             game_title_sort: "Game of Tests, A".into(),
             game_publisher: "Test Game Company".into(),
             game_year: "1978".into(),
             readme: "".into(),
-            image: None
+            image: None,
+            archived: false,
+            superseded_by: None
         }
     );
 
@@ -888,4 +1882,32 @@ mod test {
             CoreError::NotARevision
         );
     }
+
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn get_project_revisions_ok(pool: Pool) {
+        assert_eq!(
+            get_project_revisions(&pool, Project(42)).await.unwrap(),
+            [
+                RevisionRow {
+                    revision: 3,
+                    modified_at: 1702569006419538067,
+                    modified_by: "bob".into()
+                },
+                RevisionRow {
+                    revision: 1,
+                    modified_at: 1699804206419538067,
+                    modified_by: "bob".into()
+                }
+            ]
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn get_project_revisions_not_a_project(pool: Pool) {
+        // This should not happen; the Project passed in should be good.
+        assert_eq!(
+            get_project_revisions(&pool, Project(0)).await.unwrap(),
+            []
+        );
+    }
 }
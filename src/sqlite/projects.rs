@@ -2,48 +2,190 @@ use sqlx::{
     Encode, Executor, QueryBuilder, Type,
     sqlite::Sqlite
 };
+use std::collections::HashMap;
 
 use crate::{
     core::CoreError,
-    db::ProjectSummaryRow,
-    pagination::{Direction, SortBy}
+    db::{ProjectSummaryRow, ProjectYearRow, SupersessionRow},
+    model::{Project, User},
+    pagination::{Direction, Facet, SortBy, tag_namespace}
 };
 
-pub async fn get_projects_count<'e, E>(
-    ex: E
+pub trait PushFacets<'f> {
+    fn push_facets(
+        &mut self,
+        facets: &'f [Facet],
+        prefix: &str
+    ) -> &mut Self;
+
+    fn push_project_facets(
+        &mut self,
+        facets: &'f [Facet]
+    ) -> &mut Self;
+}
+
+impl<'f> PushFacets<'f> for QueryBuilder<'f, Sqlite> {
+    fn push_facets(
+        &mut self,
+        facets: &'f [Facet],
+        prefix: &str
+    ) -> &mut Self
+    {
+        // prefix is the connective joining the facets to what precedes them
+        let mut conj = prefix;
+
+        for facet in facets {
+            self.push(conj);
+
+            match facet {
+                // tags match regardless of case; EXISTS lets each project
+                // be checked against the tags index without a DISTINCT
+                Facet::Tag(tag) => match tag_namespace(tag) {
+                    // a bare namespace matches any tag it prefixes
+                    Some(_) => self
+                        .push("EXISTS (SELECT 1 FROM tags WHERE tags.project_id = projects.project_id AND substr(tags.tag, 1, length(")
+                        .push_bind(tag)
+                        .push(")) = ")
+                        .push_bind(tag)
+                        .push(" COLLATE NOCASE)"),
+                    None => self
+                        .push("EXISTS (SELECT 1 FROM tags WHERE tags.project_id = projects.project_id AND tags.tag = ")
+                        .push_bind(tag)
+                        .push(" COLLATE NOCASE)")
+                },
+                Facet::Publisher(publisher) => self
+                    .push("projects.game_publisher = ")
+                    .push_bind(publisher),
+                Facet::Year(year) => self
+                    .push("projects.game_year = ")
+                    .push_bind(year),
+                // including archived projects admits both 0 and 1
+                Facet::Archived(include) => self
+                    .push("projects.archived <= ")
+                    .push_bind(include),
+                // excluding superseded projects admits only unlinked ones
+                Facet::Superseded(include) => self
+                    .push("(projects.superseded_by IS NULL OR ")
+                    .push_bind(include)
+                    .push(")"),
+                // NB: only meaningful for queries which join flags
+                Facet::UnresolvedOnly => self
+                    .push("flags.resolved_at IS NULL"),
+                // some release must run on the given version
+                Facet::MaxVassal(v) => self
+                    .push(
+                        "projects.project_id IN (
+    SELECT packages.project_id
+    FROM releases
+    JOIN packages
+    ON releases.package_id = packages.package_id
+    WHERE releases.requires_major IS NULL
+        OR (
+            releases.requires_major,
+            releases.requires_minor,
+            releases.requires_patch
+        ) <= ("
+                    )
+                    .push_bind(v.major)
+                    .push(", ")
+                    .push_bind(v.minor)
+                    .push(", ")
+                    .push_bind(v.patch)
+                    .push("))")
+            };
+
+            conj = " AND ";
+        }
+
+        self
+    }
+
+    fn push_project_facets(
+        &mut self,
+        facets: &'f [Facet]
+    ) -> &mut Self
+    {
+        // archived projects are listed only when asked for
+        if !facets.iter().any(|f| matches!(f, Facet::Archived(_))) {
+            self.push(" AND projects.archived = 0");
+        }
+
+        self.push_facets(facets, " AND ")
+    }
+}
+
+pub async fn get_projects_count<'e, 'f, E>(
+    ex: E,
+    facets: &'f [Facet]
 ) -> Result<i64, CoreError>
 where
     E: Executor<'e, Database = Sqlite>
 {
     Ok(
-        sqlx::query_scalar!(
+        QueryBuilder::new(
             "
 SELECT COUNT(1)
 FROM projects
-            "
+WHERE projects.deleted_at IS NULL"
         )
+        .push_project_facets(facets)
+        .build_query_scalar::<i64>()
         .fetch_one(ex)
         .await?
-        .into()
     )
 }
 
-pub async fn get_projects_query_count<'e, E>(
+pub async fn get_tag_facet_counts<'e, 'f, E>(
+    ex: E,
+    facets: &'f [Facet]
+) -> Result<HashMap<String, i64>, CoreError>
+where
+    E: Executor<'e, Database = Sqlite>
+{
+    // count over the same projects which get_projects_count counts
+    Ok(
+        QueryBuilder::new(
+            "
+SELECT tags.tag, COUNT(1)
+FROM tags
+JOIN projects
+ON tags.project_id = projects.project_id
+WHERE projects.deleted_at IS NULL"
+        )
+        .push_project_facets(facets)
+        .push(" GROUP BY tags.tag")
+        .build_query_as::<(String, i64)>()
+        .fetch_all(ex)
+        .await?
+        .into_iter()
+        .collect()
+    )
+}
+
+pub async fn get_projects_query_count<'e, 'f, E>(
     ex: E,
-    query: &str
+    query: &'f str,
+    facets: &'f [Facet]
 ) -> Result<i64, CoreError>
 where
     E: Executor<'e, Database = Sqlite>
 {
+    // counting needs no rank, so the matches are only rowids to look up
     Ok(
-        sqlx::query_scalar!(
+        QueryBuilder::new(
             "
 SELECT COUNT(1)
-FROM projects_fts
-WHERE projects_fts MATCH ?
-            ",
-            query
+FROM projects
+WHERE projects.project_id IN (
+    SELECT projects_fts.rowid
+    FROM projects_fts
+    WHERE projects_fts MATCH "
         )
+        .push_bind(query)
+        .push(")")
+        .push(" AND projects.deleted_at IS NULL")
+        .push_project_facets(facets)
+        .build_query_scalar::<i64>()
         .fetch_one(ex)
         .await?
     )
@@ -78,10 +220,11 @@ impl Direction {
     }
 }
 
-pub async fn get_projects_end_window<'e, E>(
+pub async fn get_projects_end_window<'e, 'f, E>(
     ex: E,
     sort_by: SortBy,
     dir: Direction,
+    facets: &'f [Facet],
     limit: u32
 ) -> Result<Vec<ProjectSummaryRow>, CoreError>
 where
@@ -98,14 +241,26 @@ SELECT
     revision,
     created_at,
     modified_at,
+    (SELECT username FROM users WHERE users.user_id = projects.modified_by) AS modified_by,
     game_title,
     game_title_sort,
     game_publisher,
     game_year,
-    image
+    image,
+    (
+        SELECT json_group_array(tag)
+        FROM (
+            SELECT tag
+            FROM tags
+            WHERE tags.project_id = projects.project_id
+            ORDER BY tag
+        )
+    ) AS tags
 FROM projects
-ORDER BY "
+WHERE projects.deleted_at IS NULL"
         )
+        .push_project_facets(facets)
+        .push(" ORDER BY ")
         .push(sort_by.field())
         .push(" ")
         .push(dir.dir())
@@ -119,11 +274,281 @@ ORDER BY "
     )
 }
 
-pub async fn get_projects_query_end_window<'e, E>(
+pub async fn get_project_years<'e, E>(
+    ex: E
+) -> Result<Vec<ProjectYearRow>, CoreError>
+where
+    E: Executor<'e, Database = Sqlite>
+{
+    Ok(
+        sqlx::query_as!(
+            ProjectYearRow,
+            "
+SELECT
+    name,
+    game_year
+FROM projects
+WHERE deleted_at IS NULL
+ORDER BY name
+            "
+        )
+        .fetch_all(ex)
+        .await?
+    )
+}
+
+pub async fn get_project_summary_row<'e, E>(
+    ex: E,
+    proj: Project
+) -> Result<Option<ProjectSummaryRow>, CoreError>
+where
+    E: Executor<'e, Database = Sqlite>
+{
+    Ok(
+        sqlx::query_as!(
+            ProjectSummaryRow,
+            "
+SELECT
+    0.0 AS \"rank!: f64\",
+    project_id,
+    name,
+    description,
+    revision,
+    created_at,
+    modified_at,
+    (SELECT username FROM users WHERE users.user_id = projects.modified_by) AS \"modified_by?: String\",
+    game_title,
+    game_title_sort,
+    game_publisher,
+    game_year,
+    image,
+    (
+        SELECT json_group_array(tag)
+        FROM (
+            SELECT tag
+            FROM tags
+            WHERE tags.project_id = projects.project_id
+            ORDER BY tag
+        )
+    ) AS \"tags!: String\"
+FROM projects
+WHERE project_id = ?
+    AND deleted_at IS NULL
+LIMIT 1
+            ",
+            proj.0
+        )
+        .fetch_optional(ex)
+        .await?
+    )
+}
+
+pub async fn get_project_summary_rows_by_name<'e, 'n, E>(
+    ex: E,
+    names: &'n [String]
+) -> Result<Vec<ProjectSummaryRow>, CoreError>
+where
+    E: Executor<'e, Database = Sqlite>
+{
+    let mut qb = QueryBuilder::new(
+        "
+SELECT
+    0.0 AS rank,
+    project_id,
+    name,
+    description,
+    revision,
+    created_at,
+    modified_at,
+    (SELECT username FROM users WHERE users.user_id = projects.modified_by) AS modified_by,
+    game_title,
+    game_title_sort,
+    game_publisher,
+    game_year,
+    image,
+    (
+        SELECT json_group_array(tag)
+        FROM (
+            SELECT tag
+            FROM tags
+            WHERE tags.project_id = projects.project_id
+            ORDER BY tag
+        )
+    ) AS tags
+FROM projects
+WHERE projects.deleted_at IS NULL
+    AND projects.name IN ("
+    );
+
+    let mut qbs = qb.separated(", ");
+    for name in names {
+        qbs.push_bind(name);
+    }
+
+    Ok(
+        qb.push(") ORDER BY project_id")
+            .build_query_as::<ProjectSummaryRow>()
+            .fetch_all(ex)
+            .await?
+    )
+}
+
+pub async fn get_supersedes<'e, E>(
+    ex: E,
+    proj: Project
+) -> Result<Vec<String>, CoreError>
+where
+    E: Executor<'e, Database = Sqlite>
+{
+    Ok(
+        sqlx::query_scalar!(
+            "
+SELECT name
+FROM projects
+WHERE superseded_by = ?
+    AND deleted_at IS NULL
+ORDER BY name
+            ",
+            proj.0
+        )
+        .fetch_all(ex)
+        .await?
+    )
+}
+
+pub async fn get_supersessions<'e, E>(
+    ex: E
+) -> Result<Vec<SupersessionRow>, CoreError>
+where
+    E: Executor<'e, Database = Sqlite>
+{
+    Ok(
+        sqlx::query_as!(
+            SupersessionRow,
+            "
+SELECT
+    project_id,
+    superseded_by AS \"superseded_by!\"
+FROM projects
+WHERE superseded_by IS NOT NULL
+ORDER BY project_id
+            "
+        )
+        .fetch_all(ex)
+        .await?
+    )
+}
+
+pub async fn get_popular_projects<'e, E>(
+    ex: E,
+    limit: u32
+) -> Result<Vec<ProjectSummaryRow>, CoreError>
+where
+    E: Executor<'e, Database = Sqlite>
+{
+    Ok(
+        QueryBuilder::new(
+            "
+SELECT
+    0.0 AS rank,
+    projects.project_id,
+    projects.name,
+    projects.description,
+    projects.revision,
+    projects.created_at,
+    projects.modified_at,
+    (SELECT username FROM users WHERE users.user_id = projects.modified_by) AS modified_by,
+    projects.game_title,
+    projects.game_title_sort,
+    projects.game_publisher,
+    projects.game_year,
+    projects.image,
+    (
+        SELECT json_group_array(tag)
+        FROM (
+            SELECT tag
+            FROM tags
+            WHERE tags.project_id = projects.project_id
+            ORDER BY tag
+        )
+    ) AS tags
+FROM projects
+JOIN (
+    SELECT
+        packages.project_id,
+        SUM(downloads.downloads) AS downloads
+    FROM downloads
+    JOIN packages
+    ON downloads.package_id = packages.package_id
+    GROUP BY packages.project_id
+) AS dl
+ON projects.project_id = dl.project_id
+WHERE projects.deleted_at IS NULL
+    AND dl.downloads > 0
+ORDER BY dl.downloads DESC, projects.project_id ASC
+LIMIT "
+        )
+        .push_bind(limit)
+        .build_query_as::<ProjectSummaryRow>()
+        .fetch_all(ex)
+        .await?
+    )
+}
+
+pub async fn get_owned_projects<'e, E>(
+    ex: E,
+    user: User
+) -> Result<Vec<ProjectSummaryRow>, CoreError>
+where
+    E: Executor<'e, Database = Sqlite>
+{
+    Ok(
+        sqlx::query_as!(
+            ProjectSummaryRow,
+            "
+SELECT
+    0.0 AS \"rank!: f64\",
+    projects.project_id,
+    projects.name,
+    projects.description,
+    projects.revision,
+    projects.created_at,
+    projects.modified_at,
+    (SELECT username FROM users WHERE users.user_id = projects.modified_by) AS \"modified_by?: String\",
+    projects.game_title,
+    projects.game_title_sort,
+    projects.game_publisher,
+    projects.game_year,
+    projects.image,
+    (
+        SELECT json_group_array(tag)
+        FROM (
+            SELECT tag
+            FROM tags
+            WHERE tags.project_id = projects.project_id
+            ORDER BY tag
+        )
+    ) AS \"tags!: String\"
+FROM projects
+JOIN owners
+ON projects.project_id = owners.project_id
+WHERE owners.user_id = ?
+    AND projects.deleted_at IS NULL
+ORDER BY projects.modified_at DESC, projects.project_id ASC
+            ",
+            user.0
+        )
+        .fetch_all(ex)
+        .await?
+    )
+}
+
+pub async fn get_projects_query_end_window<'e, 'f, E>(
     ex: E,
-    query: &str,
+    query: &'f str,
     sort_by: SortBy,
     dir: Direction,
+    facets: &'f [Facet],
     limit: u32
 ) -> Result<Vec<ProjectSummaryRow>, CoreError>
 where
@@ -140,17 +565,29 @@ SELECT
     projects.revision,
     projects.created_at,
     projects.modified_at,
+    (SELECT username FROM users WHERE users.user_id = projects.modified_by) AS modified_by,
     projects.game_title,
     projects.game_title_sort,
     projects.game_publisher,
     projects.game_year,
-    projects.image
+    projects.image,
+    (
+        SELECT json_group_array(tag)
+        FROM (
+            SELECT tag
+            FROM tags
+            WHERE tags.project_id = projects.project_id
+            ORDER BY tag
+        )
+    ) AS tags
 FROM projects
 JOIN projects_fts AS fts
 ON projects.project_id = fts.rowid
 WHERE projects_fts MATCH "
         )
         .push_bind(query)
+        .push(" AND projects.deleted_at IS NULL")
+        .push_project_facets(facets)
         .push(" ORDER BY ")
         .push(sort_by.field())
         .push(" ")
@@ -171,6 +608,7 @@ pub async fn get_projects_mid_window<'e, 'f, E, F>(
     dir: Direction,
     field: &'f F,
     id: u32,
+    facets: &'f [Facet],
     limit: u32
 ) -> Result<Vec<ProjectSummaryRow>, CoreError>
 where
@@ -188,13 +626,23 @@ SELECT
     revision,
     created_at,
     modified_at,
+    (SELECT username FROM users WHERE users.user_id = projects.modified_by) AS modified_by,
     game_title,
     game_title_sort,
     game_publisher,
     game_year,
-    image
+    image,
+    (
+        SELECT json_group_array(tag)
+        FROM (
+            SELECT tag
+            FROM tags
+            WHERE tags.project_id = projects.project_id
+            ORDER BY tag
+        )
+    ) AS tags
 FROM projects
-WHERE "
+WHERE ("
         )
         .push(sort_by.field())
         .push(" ")
@@ -209,7 +657,10 @@ WHERE "
         .push(dir.op())
         .push(" ")
         .push_bind(id)
-        .push(") ORDER BY ")
+        .push("))")
+        .push(" AND projects.deleted_at IS NULL")
+        .push_project_facets(facets)
+        .push(" ORDER BY ")
         .push(sort_by.field())
         .push(" ")
         .push(dir.dir())
@@ -223,6 +674,7 @@ WHERE "
     )
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn get_projects_query_mid_window<'e, 'f, E, F>(
     ex: E,
     query: &'f str,
@@ -230,6 +682,7 @@ pub async fn get_projects_query_mid_window<'e, 'f, E, F>(
     dir: Direction,
     field: &'f F,
     id: u32,
+    facets: &'f [Facet],
     limit: u32
 ) -> Result<Vec<ProjectSummaryRow>, CoreError>
 where
@@ -250,11 +703,21 @@ SELECT
     projects.revision,
     projects.created_at,
     projects.modified_at,
+    (SELECT username FROM users WHERE users.user_id = projects.modified_by) AS modified_by,
     projects.game_title,
     projects.game_title_sort,
     projects.game_publisher,
     projects.game_year,
-    projects.image
+    projects.image,
+    (
+        SELECT json_group_array(tag)
+        FROM (
+            SELECT tag
+            FROM tags
+            WHERE tags.project_id = projects.project_id
+            ORDER BY tag
+        )
+    ) AS tags
 FROM projects
 JOIN (
     SELECT
@@ -264,7 +727,7 @@ JOIN (
     WHERE projects_fts MATCH "
         )
         .push_bind(query)
-        .push(") AS fts ON fts.rowid = projects.project_id WHERE ")
+        .push(") AS fts ON fts.rowid = projects.project_id WHERE (")
         .push(sort_by.field())
         .push(dir.op())
         .push(" ")
@@ -277,7 +740,10 @@ JOIN (
         .push(dir.op())
         .push(" ")
         .push_bind(id)
-        .push(") ORDER BY ")
+        .push("))")
+        .push(" AND projects.deleted_at IS NULL")
+        .push_project_facets(facets)
+        .push(" ORDER BY ")
         .push(sort_by.field())
         .push(" ")
         .push(dir.dir())
@@ -291,34 +757,469 @@ JOIN (
     )
 }
 
-#[cfg(test)]
-mod test {
-    use super::*;
-
-    type Pool = sqlx::Pool<Sqlite>;
-
-    #[sqlx::test(fixtures("users", "projects"))]
-    async fn get_projects_count_ok(pool: Pool) {
-        assert_eq!(get_projects_count(&pool).await.unwrap(), 2);
-    }
+pub async fn get_projects_position<'e, 'f, E, F>(
+    ex: E,
+    sort_by: SortBy,
+    dir: Direction,
+    field: &'f F,
+    id: u32,
+    facets: &'f [Facet]
+) -> Result<i64, CoreError>
+where
+    E: Executor<'e, Database = Sqlite>,
+    F: Send + Sync + Encode<'f, Sqlite> + Type<Sqlite>
+{
+    // count the rows which precede the anchor in the sort order
+    let op = dir.rev().op();
 
-    #[track_caller]
-    fn assert_projects_window(
-        act: Result<Vec<ProjectSummaryRow>, CoreError>,
-        exp: &[&str]
+    Ok(
+        QueryBuilder::new(
+            "
+SELECT COUNT(1)
+FROM projects
+WHERE ("
+        )
+        .push(sort_by.field())
+        .push(" ")
+        .push(op)
+        .push(" ")
+        .push_bind(field)
+        .push(" OR (")
+        .push(sort_by.field())
+        .push(" = ")
+        .push_bind(field)
+        .push(" AND project_id ")
+        .push(op)
+        .push(" ")
+        .push_bind(id)
+        .push("))")
+        .push(" AND projects.deleted_at IS NULL")
+        .push_project_facets(facets)
+        .build_query_scalar::<i64>()
+        .fetch_one(ex)
+        .await?
     )
-    {
-        assert_eq!(
-            act.unwrap().into_iter().map(|r| r.name).collect::<Vec<_>>(),
-            exp
-        );
+}
+
+pub async fn get_projects_query_position<'e, 'f, E, F>(
+    ex: E,
+    query: &'f str,
+    sort_by: SortBy,
+    dir: Direction,
+    field: &'f F,
+    id: u32,
+    facets: &'f [Facet]
+) -> Result<i64, CoreError>
+where
+    E: Executor<'e, Database = Sqlite>,
+    F: Send + Sync + Encode<'f, Sqlite> + Type<Sqlite>
+{
+    // count the rows which precede the anchor in the sort order
+    let op = dir.rev().op();
+
+    Ok(
+        QueryBuilder::new(
+            "
+SELECT COUNT(1)
+FROM projects
+JOIN (
+    SELECT
+        projects_fts.rowid,
+        projects_fts.rank
+    FROM projects_fts
+    WHERE projects_fts MATCH "
+        )
+        .push_bind(query)
+        .push(") AS fts ON fts.rowid = projects.project_id WHERE (")
+        .push(sort_by.field())
+        .push(" ")
+        .push(op)
+        .push(" ")
+        .push_bind(field)
+        .push(" OR (")
+        .push(sort_by.field())
+        .push(" = ")
+        .push_bind(field)
+        .push(" AND project_id ")
+        .push(op)
+        .push(" ")
+        .push_bind(id)
+        .push("))")
+        .push(" AND projects.deleted_at IS NULL")
+        .push_project_facets(facets)
+        .build_query_scalar::<i64>()
+        .fetch_one(ex)
+        .await?
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::{
+        model::Package,
+        sqlite::stats::increment_file_downloads
+    };
+
+    type Pool = sqlx::Pool<Sqlite>;
+
+    async fn popular_names(pool: &Pool, limit: u32) -> Vec<String> {
+        get_popular_projects(pool, limit)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|r| r.name)
+            .collect()
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "superseded"))]
+    async fn get_project_summary_row_ok(pool: Pool) {
+        assert_eq!(
+            get_project_summary_row(&pool, Project(42))
+                .await
+                .unwrap()
+                .unwrap()
+                .name,
+            "test_game"
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn get_project_summary_row_not_a_project(pool: Pool) {
+        assert_eq!(
+            get_project_summary_row(&pool, Project(1)).await.unwrap(),
+            None
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn get_project_summary_rows_by_name_ok(pool: Pool) {
+        let names = [
+            "test_game".into(),
+            "not_a_project".into(),
+            "a_game".into()
+        ];
+
+        assert_eq!(
+            get_project_summary_rows_by_name(&pool, &names)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|r| r.name)
+                .collect::<Vec<_>>(),
+            ["a_game", "test_game"]
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn get_project_summary_rows_by_name_none(pool: Pool) {
+        assert_eq!(
+            get_project_summary_rows_by_name(&pool, &["nope".into()])
+                .await
+                .unwrap(),
+            []
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "superseded"))]
+    async fn get_supersedes_ok(pool: Pool) {
+        assert_eq!(
+            get_supersedes(&pool, Project(42)).await.unwrap(),
+            ["a_game"]
+        );
+        assert!(get_supersedes(&pool, Project(6)).await.unwrap().is_empty());
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "superseded"))]
+    async fn get_supersessions_ok(pool: Pool) {
+        assert_eq!(
+            get_supersessions(&pool).await.unwrap(),
+            [
+                SupersessionRow {
+                    project_id: 6,
+                    superseded_by: 42
+                }
+            ]
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn get_project_years_ok(pool: Pool) {
+        assert_eq!(
+            get_project_years(&pool).await.unwrap(),
+            [
+                ProjectYearRow {
+                    name: "a_game".into(),
+                    game_year: "1993".into()
+                },
+                ProjectYearRow {
+                    name: "test_game".into(),
+                    game_year: "1979".into()
+                }
+            ]
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "two_owners"))]
+    async fn get_owned_projects_ok(pool: Pool) {
+        assert_eq!(
+            get_owned_projects(&pool, User(1))
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|r| r.name)
+                .collect::<Vec<_>>(),
+            ["test_game", "a_game"]
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "two_owners"))]
+    async fn get_owned_projects_none(pool: Pool) {
+        assert_eq!(get_owned_projects(&pool, User(3)).await.unwrap(), []);
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages", "files", "file_downloads"))]
+    async fn get_popular_projects_ok(pool: Pool) {
+        assert_eq!(popular_names(&pool, 10).await, ["test_game", "a_game"]);
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages", "files", "file_downloads"))]
+    async fn get_popular_projects_limit(pool: Pool) {
+        assert_eq!(popular_names(&pool, 1).await, ["test_game"]);
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages", "files", "file_downloads"))]
+    async fn get_popular_projects_deleted(pool: Pool) {
+        sqlx::query("UPDATE projects SET deleted_at = 1 WHERE project_id = 42")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        assert_eq!(popular_names(&pool, 10).await, ["a_game"]);
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages", "project_views"))]
+    async fn get_popular_projects_release_downloads(pool: Pool) {
+        assert_eq!(popular_names(&pool, 10).await, ["test_game"]);
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages", "files", "file_downloads"))]
+    async fn get_popular_projects_counted_downloads(pool: Pool) {
+        for _ in 0..5 {
+            increment_file_downloads(&pool, Package(4), "0.1", "d_package-0.1.vmod", 19702)
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(popular_names(&pool, 10).await, ["a_game", "test_game"]);
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages", "files"))]
+    async fn get_popular_projects_no_downloads(pool: Pool) {
+        assert_eq!(popular_names(&pool, 10).await, Vec::<String>::new());
+    }
+
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn get_projects_count_ok(pool: Pool) {
+        assert_eq!(get_projects_count(&pool, &[]).await.unwrap(), 2);
+    }
+
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn get_projects_count_deleted(pool: Pool) {
+        sqlx::query("UPDATE projects SET deleted_at = 1 WHERE project_id = 42")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        assert_eq!(get_projects_count(&pool, &[]).await.unwrap(), 1);
+        assert_eq!(
+            get_projects_end_window(
+                &pool,
+                SortBy::ProjectName,
+                Direction::Ascending,
+                &[],
+                3
+            )
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|r| r.name)
+            .collect::<Vec<_>>(),
+            ["a_game"]
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "archived"))]
+    async fn get_projects_count_archived(pool: Pool) {
+        // archived projects are excluded by default
+        assert_eq!(get_projects_count(&pool, &[]).await.unwrap(), 1);
+        assert_eq!(
+            get_projects_count(&pool, &[Facet::Archived(false)]).await.unwrap(),
+            1
+        );
+        assert_eq!(
+            get_projects_count(&pool, &[Facet::Archived(true)]).await.unwrap(),
+            2
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "archived"))]
+    async fn get_projects_end_window_archived(pool: Pool) {
+        assert_projects_window(
+            get_projects_end_window(
+                &pool,
+                SortBy::ProjectName,
+                Direction::Ascending,
+                &[],
+                3
+            ).await,
+            &["test_game"]
+        );
+
+        assert_projects_window(
+            get_projects_end_window(
+                &pool,
+                SortBy::ProjectName,
+                Direction::Ascending,
+                &[Facet::Archived(true)],
+                3
+            ).await,
+            &["a_game", "test_game"]
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "tags"))]
+    async fn get_projects_end_window_tags(pool: Pool) {
+        assert_eq!(
+            get_projects_end_window(
+                &pool,
+                SortBy::ProjectName,
+                Direction::Ascending,
+                &[],
+                3
+            )
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|r| (r.name, r.tags))
+            .collect::<Vec<_>>(),
+            [
+                ("a_game".into(), "[\"ancients\"]".into()),
+                ("test_game".into(), "[\"tactical\",\"wwii\"]".into())
+            ]
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn get_projects_end_window_no_tags(pool: Pool) {
+        assert!(
+            get_projects_end_window(
+                &pool,
+                SortBy::ProjectName,
+                Direction::Ascending,
+                &[],
+                3
+            )
+            .await
+            .unwrap()
+            .into_iter()
+            .all(|r| r.tags == "[]")
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "superseded"))]
+    async fn get_projects_count_superseded(pool: Pool) {
+        // superseded projects are included by default
+        assert_eq!(get_projects_count(&pool, &[]).await.unwrap(), 2);
+        assert_eq!(
+            get_projects_count(&pool, &[Facet::Superseded(true)]).await.unwrap(),
+            2
+        );
+        assert_eq!(
+            get_projects_count(&pool, &[Facet::Superseded(false)]).await.unwrap(),
+            1
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "superseded"))]
+    async fn get_projects_end_window_superseded(pool: Pool) {
+        assert_projects_window(
+            get_projects_end_window(
+                &pool,
+                SortBy::ProjectName,
+                Direction::Ascending,
+                &[Facet::Superseded(false)],
+                3
+            ).await,
+            &["test_game"]
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "proj_window", "proj_window_tags"))]
+    async fn get_projects_count_facets(pool: Pool) {
+        assert_eq!(
+            get_projects_count(
+                &pool, &[Facet::Tag("wwii".into())]
+            ).await.unwrap(),
+            3
+        );
+
+        assert_eq!(
+            get_projects_count(
+                &pool,
+                &[
+                    Facet::Tag("wwii".into()),
+                    Facet::Tag("ancients".into())
+                ]
+            ).await.unwrap(),
+            0
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "proj_window", "proj_window_tags"))]
+    async fn get_tag_facet_counts_ok(pool: Pool) {
+        assert_eq!(
+            get_tag_facet_counts(&pool, &[]).await.unwrap(),
+            HashMap::from([("wwii".into(), 3), ("ancients".into(), 1)])
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "proj_window", "proj_window_tags"))]
+    async fn get_tag_facet_counts_facets(pool: Pool) {
+        assert_eq!(
+            get_tag_facet_counts(
+                &pool, &[Facet::Tag("ancients".into())]
+            ).await.unwrap(),
+            HashMap::from([("ancients".into(), 1)])
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "proj_window"))]
+    async fn get_tag_facet_counts_no_tags(pool: Pool) {
+        assert_eq!(
+            get_tag_facet_counts(&pool, &[]).await.unwrap(),
+            HashMap::new()
+        );
+    }
+
+    #[track_caller]
+    fn assert_projects_window(
+        act: Result<Vec<ProjectSummaryRow>, CoreError>,
+        exp: &[&str]
+    )
+    {
+        assert_eq!(
+            act.unwrap().into_iter().map(|r| r.name).collect::<Vec<_>>(),
+            exp
+        );
     }
 
     #[sqlx::test]
     async fn get_projects_end_window_asc_empty(pool: Pool) {
         assert_projects_window(
             get_projects_end_window(
-                &pool, SortBy::ProjectName, Direction::Ascending, 3
+                &pool, SortBy::ProjectName, Direction::Ascending, &[], 3
             ).await,
             &[]
         );
@@ -328,7 +1229,7 @@ mod test {
     async fn get_projects_end_window_asc_not_all(pool: Pool) {
         assert_projects_window(
             get_projects_end_window(
-                &pool, SortBy::ProjectName, Direction::Ascending, 3
+                &pool, SortBy::ProjectName, Direction::Ascending, &[], 3
             ).await,
             &["a", "b", "c"]
         );
@@ -338,7 +1239,7 @@ mod test {
     async fn get_projects_end_window_asc_past_end(pool: Pool) {
         assert_projects_window(
             get_projects_end_window(
-                &pool, SortBy::ProjectName, Direction::Ascending, 5
+                &pool, SortBy::ProjectName, Direction::Ascending, &[], 5
             ).await,
             &["a", "b", "c", "d"]
         );
@@ -348,7 +1249,7 @@ mod test {
     async fn get_projects_end_window_desc_empty(pool: Pool) {
         assert_projects_window(
             get_projects_end_window(
-                &pool, SortBy::ProjectName, Direction::Descending, 3
+                &pool, SortBy::ProjectName, Direction::Descending, &[], 3
             ).await,
             &[]
         );
@@ -358,7 +1259,7 @@ mod test {
     async fn get_projects_end_window_desc_not_all(pool: Pool) {
         assert_projects_window(
             get_projects_end_window(
-                &pool, SortBy::ProjectName, Direction::Descending, 3
+                &pool, SortBy::ProjectName, Direction::Descending, &[], 3
             ).await,
             &["d", "c", "b"]
         );
@@ -368,7 +1269,7 @@ mod test {
     async fn get_projects_end_window_desc_past_start(pool: Pool) {
         assert_projects_window(
             get_projects_end_window(
-                &pool, SortBy::ProjectName, Direction::Descending, 5
+                &pool, SortBy::ProjectName, Direction::Descending, &[], 5
             ).await,
             &["d", "c", "b", "a"]
         );
@@ -378,7 +1279,7 @@ mod test {
     async fn get_projects_mid_window_asc_empty(pool: Pool) {
         assert_projects_window(
             get_projects_mid_window(
-                &pool, SortBy::ProjectName, Direction::Ascending, &"a", 1, 3
+                &pool, SortBy::ProjectName, Direction::Ascending, &"a", 1, &[], 3
             ).await,
             &[]
         );
@@ -388,7 +1289,7 @@ mod test {
     async fn get_projects_mid_window_asc_not_all(pool: Pool) {
         assert_projects_window(
             get_projects_mid_window(
-                &pool, SortBy::ProjectName, Direction::Ascending, &"b", 2, 3
+                &pool, SortBy::ProjectName, Direction::Ascending, &"b", 2, &[], 3
             ).await,
             &["c", "d"]
         );
@@ -398,7 +1299,7 @@ mod test {
     async fn get_projects_mid_window_asc_past_end(pool: Pool) {
         assert_projects_window(
             get_projects_mid_window(
-                &pool, SortBy::ProjectName, Direction::Ascending, &"d", 4, 3
+                &pool, SortBy::ProjectName, Direction::Ascending, &"d", 4, &[], 3
             ).await,
             &[]
         );
@@ -408,7 +1309,7 @@ mod test {
     async fn get_projects_mid_window_desc_empty(pool: Pool) {
         assert_projects_window(
             get_projects_mid_window(
-                &pool, SortBy::ProjectName, Direction::Descending, &"a", 1, 3
+                &pool, SortBy::ProjectName, Direction::Descending, &"a", 1, &[], 3
             ).await,
             &[]
         );
@@ -418,7 +1319,7 @@ mod test {
     async fn get_projects_mid_window_desc_not_all(pool: Pool) {
         assert_projects_window(
             get_projects_mid_window(
-                &pool, SortBy::ProjectName, Direction::Descending, &"b", 2, 3
+                &pool, SortBy::ProjectName, Direction::Descending, &"b", 2, &[], 3
             ).await,
             &["a"]
         );
@@ -428,7 +1329,7 @@ mod test {
     async fn get_projects_mid_window_desc_past_start(pool: Pool) {
         assert_projects_window(
             get_projects_mid_window(
-                &pool, SortBy::ProjectName, Direction::Descending, &"d", 4, 3
+                &pool, SortBy::ProjectName, Direction::Descending, &"d", 4, &[], 3
             ).await,
             &["c", "b", "a"]
         );
@@ -438,7 +1339,7 @@ mod test {
     async fn get_projects_query_end_window_asc_empty(pool: Pool) {
         assert_projects_window(
             get_projects_query_end_window(
-                &pool, "abc", SortBy::ProjectName, Direction::Ascending, 3
+                &pool, "abc", SortBy::ProjectName, Direction::Ascending, &[], 3
             ).await,
             &[]
         );
@@ -448,7 +1349,7 @@ mod test {
     async fn get_projects_query_end_window_asc_not_all(pool: Pool) {
         assert_projects_window(
             get_projects_query_end_window(
-                &pool, "abc", SortBy::ProjectName, Direction::Ascending, 1
+                &pool, "abc", SortBy::ProjectName, Direction::Ascending, &[], 1
             ).await,
             &["a"]
         );
@@ -458,7 +1359,7 @@ mod test {
     async fn get_projects_query_end_window_asc_past_end(pool: Pool) {
         assert_projects_window(
             get_projects_query_end_window(
-                &pool, "abc", SortBy::ProjectName, Direction::Ascending, 5
+                &pool, "abc", SortBy::ProjectName, Direction::Ascending, &[], 5
             ).await,
             &["a", "c", "d"]
         );
@@ -468,7 +1369,7 @@ mod test {
     async fn get_projects_query_end_window_desc_empty(pool: Pool) {
         assert_projects_window(
             get_projects_query_end_window(
-                &pool, "abc", SortBy::ProjectName, Direction::Descending, 3
+                &pool, "abc", SortBy::ProjectName, Direction::Descending, &[], 3
             ).await,
             &[]
         );
@@ -478,7 +1379,7 @@ mod test {
     async fn get_projects_query_end_window_desc_not_all(pool: Pool) {
         assert_projects_window(
             get_projects_query_end_window(
-                &pool, "abc", SortBy::ProjectName, Direction::Descending, 1
+                &pool, "abc", SortBy::ProjectName, Direction::Descending, &[], 1
             ).await,
             &["d"]
         );
@@ -488,7 +1389,7 @@ mod test {
     async fn get_projects_query_end_window_desc_past_start(pool: Pool) {
         assert_projects_window(
             get_projects_query_end_window(
-                &pool, "abc", SortBy::ProjectName, Direction::Descending, 5
+                &pool, "abc", SortBy::ProjectName, Direction::Descending, &[], 5
             ).await,
             &["d", "c", "a"]
         );
@@ -498,7 +1399,7 @@ mod test {
     async fn get_projects_query_mid_window_asc_empty(pool: Pool) {
         assert_projects_window(
             get_projects_query_mid_window(
-                &pool, "abc", SortBy::ProjectName, Direction::Ascending, &"a", 1, 3
+                &pool, "abc", SortBy::ProjectName, Direction::Ascending, &"a", 1, &[], 3
             ).await,
             &[]
         );
@@ -508,7 +1409,7 @@ mod test {
     async fn get_projects_query_mid_window_asc_not_all(pool: Pool) {
         assert_projects_window(
             get_projects_query_mid_window(
-                &pool, "abc", SortBy::ProjectName, Direction::Ascending, &"b", 2, 3
+                &pool, "abc", SortBy::ProjectName, Direction::Ascending, &"b", 2, &[], 3
             ).await,
             &["c", "d"]
         );
@@ -518,7 +1419,7 @@ mod test {
     async fn get_projects_query_mid_window_asc_past_end(pool: Pool) {
         assert_projects_window(
             get_projects_query_mid_window(
-                &pool, "abc", SortBy::ProjectName, Direction::Ascending, &"d", 4, 3
+                &pool, "abc", SortBy::ProjectName, Direction::Ascending, &"d", 4, &[], 3
             ).await,
             &[]
         );
@@ -528,7 +1429,7 @@ mod test {
     async fn get_projects_query_mid_window_desc_empty(pool: Pool) {
         assert_projects_window(
             get_projects_query_mid_window(
-                &pool, "abc", SortBy::ProjectName, Direction::Descending, &"a", 1, 3
+                &pool, "abc", SortBy::ProjectName, Direction::Descending, &"a", 1, &[], 3
             ).await,
             &[]
         );
@@ -538,7 +1439,7 @@ mod test {
     async fn get_projects_query_mid_window_desc_not_all(pool: Pool) {
         assert_projects_window(
             get_projects_query_mid_window(
-                &pool, "abc", SortBy::ProjectName, Direction::Descending, &"d", 4, 1
+                &pool, "abc", SortBy::ProjectName, Direction::Descending, &"d", 4, &[], 1
             ).await,
             &["c"]
         );
@@ -548,9 +1449,426 @@ mod test {
     async fn get_projects_query_mid_window_desc_past_start(pool: Pool) {
         assert_projects_window(
             get_projects_query_mid_window(
-                &pool, "abc", SortBy::ProjectName, Direction::Descending, &"d", 4, 5
+                &pool, "abc", SortBy::ProjectName, Direction::Descending, &"d", 4, &[], 5
             ).await,
             &["c", "a"]
         );
     }
+
+    #[sqlx::test(fixtures("users", "proj_window"))]
+    async fn get_projects_position_asc(pool: Pool) {
+        assert_eq!(
+            get_projects_position(
+                &pool, SortBy::ProjectName, Direction::Ascending, &"c", 3, &[]
+            ).await.unwrap(),
+            2
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "proj_window"))]
+    async fn get_projects_position_asc_first(pool: Pool) {
+        assert_eq!(
+            get_projects_position(
+                &pool, SortBy::ProjectName, Direction::Ascending, &"a", 1, &[]
+            ).await.unwrap(),
+            0
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "proj_window"))]
+    async fn get_projects_position_desc(pool: Pool) {
+        assert_eq!(
+            get_projects_position(
+                &pool, SortBy::ProjectName, Direction::Descending, &"c", 3, &[]
+            ).await.unwrap(),
+            1
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "proj_window"))]
+    async fn get_projects_position_ties(pool: Pool) {
+        // all rows have the same mtime, so the id breaks ties
+        assert_eq!(
+            get_projects_position(
+                &pool, SortBy::ModificationTime, Direction::Ascending, &0, 3, &[]
+            ).await.unwrap(),
+            2
+        );
+
+        assert_eq!(
+            get_projects_position(
+                &pool, SortBy::ModificationTime, Direction::Descending, &0, 3, &[]
+            ).await.unwrap(),
+            1
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "proj_query_window"))]
+    async fn get_projects_query_position_asc(pool: Pool) {
+        assert_eq!(
+            get_projects_query_position(
+                &pool, "abc", SortBy::ProjectName, Direction::Ascending, &"c", 3, &[]
+            ).await.unwrap(),
+            1
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "proj_query_window"))]
+    async fn get_projects_query_position_desc(pool: Pool) {
+        assert_eq!(
+            get_projects_query_position(
+                &pool, "abc", SortBy::ProjectName, Direction::Descending, &"c", 3, &[]
+            ).await.unwrap(),
+            1
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "proj_query_window"))]
+    async fn get_projects_query_position_filtered(pool: Pool) {
+        // only rows which match the query are counted
+        assert_eq!(
+            get_projects_query_position(
+                &pool, "xyz", SortBy::ProjectName, Direction::Ascending, &"d", 4, &[]
+            ).await.unwrap(),
+            1
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "proj_window", "proj_window_tags"))]
+    async fn get_projects_end_window_facets(pool: Pool) {
+        assert_projects_window(
+            get_projects_end_window(
+                &pool,
+                SortBy::ProjectName,
+                Direction::Ascending,
+                &[Facet::Tag("wwii".into()), Facet::Publisher("".into())],
+                5
+            ).await,
+            &["a", "c", "d"]
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "proj_window", "proj_window_tags"))]
+    async fn get_projects_mid_window_facets(pool: Pool) {
+        assert_projects_window(
+            get_projects_mid_window(
+                &pool,
+                SortBy::ProjectName,
+                Direction::Ascending,
+                &"a",
+                1,
+                &[Facet::Tag("wwii".into())],
+                5
+            ).await,
+            &["c", "d"]
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "proj_window", "proj_window_tags"))]
+    async fn get_projects_position_facets(pool: Pool) {
+        // only rows which have the facets are counted
+        assert_eq!(
+            get_projects_position(
+                &pool,
+                SortBy::ProjectName,
+                Direction::Ascending,
+                &"d",
+                4,
+                &[Facet::Tag("wwii".into())]
+            ).await.unwrap(),
+            2
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "proj_window", "proj_window_releases"))]
+    async fn get_projects_end_window_max_vassal(pool: Pool) {
+        // a requires 3.7, b requires 3.6, c has no requirement,
+        // d has no releases
+        assert_projects_window(
+            get_projects_end_window(
+                &pool,
+                SortBy::ProjectName,
+                Direction::Ascending,
+                &[Facet::MaxVassal("3.6.0".parse().unwrap())],
+                5
+            ).await,
+            &["b", "c"]
+        );
+
+        assert_projects_window(
+            get_projects_end_window(
+                &pool,
+                SortBy::ProjectName,
+                Direction::Ascending,
+                &[Facet::MaxVassal("3.7.0".parse().unwrap())],
+                5
+            ).await,
+            &["a", "b", "c"]
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "proj_window", "proj_window_mixed_tags"))]
+    async fn get_projects_end_window_tag_case_insensitive(pool: Pool) {
+        for tag in ["wwii", "WWII", "wWiI"] {
+            assert_projects_window(
+                get_projects_end_window(
+                    &pool,
+                    SortBy::ProjectName,
+                    Direction::Ascending,
+                    &[Facet::Tag(tag.into())],
+                    5
+                ).await,
+                &["a", "c", "d"]
+            );
+
+            assert_eq!(
+                get_projects_count(
+                    &pool, &[Facet::Tag(tag.into())]
+                ).await.unwrap(),
+                3
+            );
+        }
+    }
+
+    #[sqlx::test(fixtures("users", "proj_window", "proj_window_ns_tags"))]
+    async fn get_projects_end_window_tag_namespaced(pool: Pool) {
+        for (tag, expected) in [
+            ("era:wwii", &["a", "d"][..]),
+            ("era:wwii:pacific", &["c"]),
+            ("era:", &["a", "b", "c", "d"]),
+            ("ERA:", &["a", "b", "c", "d"]),
+            ("scale:", &["b"]),
+            ("era:wwii:", &[]),
+            ("era", &["d"]),
+            ("bogus:", &[])
+        ] {
+            assert_projects_window(
+                get_projects_end_window(
+                    &pool,
+                    SortBy::ProjectName,
+                    Direction::Ascending,
+                    &[Facet::Tag(tag.into())],
+                    5
+                ).await,
+                expected
+            );
+
+            assert_eq!(
+                get_projects_count(
+                    &pool, &[Facet::Tag(tag.into())]
+                ).await.unwrap(),
+                expected.len() as i64
+            );
+        }
+    }
+
+    const GENERATED_PROJECTS: i64 = 3000;
+
+    // Generates projects with a spread of publishers, years, tags, and
+    // states, so that every facet combination selects a different subset.
+    async fn generate_projects(pool: &Pool) {
+        sqlx::query(
+            "
+INSERT INTO projects (
+    project_id,
+    name,
+    normalized_name,
+    created_at,
+    modified_at,
+    modified_by,
+    revision,
+    description,
+    game_title,
+    game_title_sort,
+    game_publisher,
+    game_year,
+    readme,
+    image,
+    archived,
+    superseded_by,
+    deleted_at
+)
+WITH RECURSIVE n(i) AS (
+    SELECT 1
+    UNION ALL
+    SELECT i + 1 FROM n WHERE i < ?
+)
+SELECT
+    1000 + i,
+    'gen_' || i,
+    'gen_' || i,
+    i,
+    i,
+    1,
+    1,
+    CASE WHEN i % 5 = 0 THEN 'alpha' ELSE 'beta' END,
+    'Game ' || i,
+    'Game ' || i,
+    'Publisher ' || (i % 7),
+    CAST(1970 + i % 11 AS TEXT),
+    '',
+    NULL,
+    i % 13 = 0,
+    CASE WHEN i % 17 = 0 THEN 1000 + i - 1 END,
+    CASE WHEN i % 19 = 0 THEN 1 END
+FROM n
+            "
+        )
+        .bind(GENERATED_PROJECTS)
+        .execute(pool)
+        .await
+        .unwrap();
+
+        sqlx::query(
+            "
+INSERT INTO tags (project_id, tag)
+SELECT project_id, 'wwii' FROM projects WHERE project_id % 3 = 0
+UNION ALL
+SELECT project_id, 'Theater:Pacific' FROM projects WHERE project_id % 4 = 0
+UNION ALL
+SELECT project_id, 'theater:europe' FROM projects WHERE project_id % 6 = 1
+            "
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    struct NaiveProject {
+        project_id: i64,
+        publisher: String,
+        year: String,
+        archived: bool,
+        superseded: bool,
+        deleted: bool,
+        tags: Vec<String>
+    }
+
+    fn naive_tag_matches(tag: &str, facet: &str) -> bool {
+        match tag_namespace(facet) {
+            Some(_) => tag.get(..facet.len())
+                .is_some_and(|p| p.eq_ignore_ascii_case(facet)),
+            None => tag.eq_ignore_ascii_case(facet)
+        }
+    }
+
+    // Counts by filtering every project in turn, as a check on the SQL
+    fn naive_count(
+        projects: &[NaiveProject],
+        matches: Option<&[i64]>,
+        facets: &[Facet]
+    ) -> i64
+    {
+        let archived_facet = facets.iter().any(|f| matches!(f, Facet::Archived(_)));
+
+        projects.iter()
+            .filter(|p| !p.deleted)
+            .filter(|p| archived_facet || !p.archived)
+            .filter(|p| matches.is_none_or(|m| m.contains(&p.project_id)))
+            .filter(|p| facets.iter().all(|f| match f {
+                Facet::Tag(t) => p.tags.iter().any(|pt| naive_tag_matches(pt, t)),
+                Facet::Publisher(publisher) => &p.publisher == publisher,
+                Facet::Year(year) => &p.year == year,
+                Facet::Archived(include) => *include || !p.archived,
+                Facet::Superseded(include) => *include || !p.superseded,
+                _ => unreachable!()
+            }))
+            .count() as i64
+    }
+
+    #[sqlx::test(fixtures("users"))]
+    async fn get_projects_count_matches_naive(pool: Pool) {
+        generate_projects(&pool).await;
+
+        let rows = sqlx::query_as::<_, (i64, String, String, bool, bool, bool, Option<String>)>(
+            "
+SELECT
+    projects.project_id,
+    projects.game_publisher,
+    projects.game_year,
+    projects.archived,
+    projects.superseded_by IS NOT NULL,
+    projects.deleted_at IS NOT NULL,
+    group_concat(tags.tag, ',')
+FROM projects
+LEFT JOIN tags
+ON projects.project_id = tags.project_id
+GROUP BY projects.project_id
+            "
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+
+        assert_eq!(rows.len() as i64, GENERATED_PROJECTS);
+
+        let projects = rows.into_iter()
+            .map(|r| NaiveProject {
+                project_id: r.0,
+                publisher: r.1,
+                year: r.2,
+                archived: r.3,
+                superseded: r.4,
+                deleted: r.5,
+                tags: r.6.map(|t| t.split(',').map(String::from).collect())
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<_>>();
+
+        let query = "alpha";
+        let matches = sqlx::query_scalar::<_, i64>(
+            "SELECT rowid FROM projects_fts WHERE projects_fts MATCH ?"
+        )
+        .bind(query)
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+
+        let tag_sets = [
+            vec![],
+            vec![Facet::Tag("wwii".into())],
+            vec![Facet::Tag("WWII".into())],
+            vec![Facet::Tag("theater:".into())],
+            vec![
+                Facet::Tag("wwii".into()),
+                Facet::Tag("theater:pacific".into())
+            ]
+        ];
+        let publishers = [None, Some(Facet::Publisher("Publisher 3".into()))];
+        let years = [None, Some(Facet::Year("1975".into()))];
+        let archived = [None, Some(Facet::Archived(true)), Some(Facet::Archived(false))];
+        let superseded = [None, Some(Facet::Superseded(false))];
+
+        for tags in &tag_sets {
+            for publisher in &publishers {
+                for year in &years {
+                    for arch in &archived {
+                        for sup in &superseded {
+                            let facets = tags.iter()
+                                .chain(publisher)
+                                .chain(year)
+                                .chain(arch)
+                                .chain(sup)
+                                .cloned()
+                                .collect::<Vec<_>>();
+
+                            assert_eq!(
+                                get_projects_count(&pool, &facets).await.unwrap(),
+                                naive_count(&projects, None, &facets),
+                                "{facets:?}"
+                            );
+
+                            assert_eq!(
+                                get_projects_query_count(&pool, query, &facets)
+                                    .await
+                                    .unwrap(),
+                                naive_count(&projects, Some(&matches), &facets),
+                                "{query} {facets:?}"
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
 }
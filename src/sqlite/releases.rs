@@ -7,10 +7,11 @@ use std::cmp::Ordering;
 
 use crate::{
     core::CoreError,
-    db::FileRow,
-    model::{Owner, Package, Project},
+    db::{FileRow, ReleaseUrlRow, UserReleaseRow},
+    model::{Owner, Package, Project, User},
+    pagination::ReleaseAnchor,
     sqlite::project::update_project_non_project_data,
-    version::Version
+    version::{Requires, Version}
 };
 
 impl From<&FileRow> for Version {
@@ -27,6 +28,9 @@ impl From<&FileRow> for Version {
 
 #[derive(Debug, Deserialize)]
 struct ReducedFileRow {
+    release_id: i64,
+    version: String,
+    filename: String,
     url: String,
     version_major: i64,
     version_minor: i64,
@@ -79,7 +83,8 @@ SELECT
     releases.size,
     releases.checksum,
     releases.published_at,
-    users.username AS published_by
+    users.username AS published_by,
+    0 AS \"is_primary!: bool\"
 FROM releases
 JOIN users
 ON releases.published_by = users.user_id
@@ -100,6 +105,96 @@ ORDER BY
     Ok(releases)
 }
 
+pub async fn get_releases_window<'e, E>(
+    ex: E,
+    pkg: Package,
+    anchor: &ReleaseAnchor,
+    limit: u32
+) -> Result<Vec<FileRow>, CoreError>
+where
+    E: Executor<'e, Database = Sqlite>
+{
+    // Version order is semver order, which SQL cannot express for
+    // prerelease identifiers, so the window is taken after sorting
+    let releases = get_releases(ex, pkg).await?;
+    let limit = limit as usize;
+
+    Ok(
+        match anchor {
+            ReleaseAnchor::Start => releases.into_iter()
+                .take(limit)
+                .collect(),
+            ReleaseAnchor::After(v) => releases.into_iter()
+                .filter(|r| Version::from(r) < *v)
+                .take(limit)
+                .collect(),
+            ReleaseAnchor::Before(v) => {
+                let mut before = releases.into_iter()
+                    .filter(|r| Version::from(r) > *v)
+                    .collect::<Vec<_>>();
+                before.drain(..before.len().saturating_sub(limit));
+                before
+            }
+        }
+    )
+}
+
+pub async fn get_releases_count<'e, E>(
+    ex: E,
+    pkg: Package
+) -> Result<i64, CoreError>
+where
+    E: Executor<'e, Database = Sqlite>
+{
+    Ok(
+        sqlx::query_scalar!(
+            "
+SELECT COUNT(1) AS \"count!: i64\"
+FROM releases
+WHERE package_id = ?
+            ",
+            pkg.0
+        )
+        .fetch_one(ex)
+        .await?
+    )
+}
+
+pub async fn get_user_releases<'e, E>(
+    ex: E,
+    user: User,
+    limit: u32
+) -> Result<Vec<UserReleaseRow>, CoreError>
+where
+    E: Executor<'e, Database = Sqlite>
+{
+    Ok(
+        sqlx::query_as!(
+            UserReleaseRow,
+            "
+SELECT
+    projects.name AS project,
+    packages.name AS package,
+    releases.version,
+    releases.published_at
+FROM releases
+JOIN packages
+ON releases.package_id = packages.package_id
+JOIN projects
+ON packages.project_id = projects.project_id
+WHERE releases.published_by = ?
+    AND projects.deleted_at IS NULL
+ORDER BY releases.published_at DESC, releases.release_id DESC
+LIMIT ?
+            ",
+            user.0,
+            limit
+        )
+        .fetch_all(ex)
+        .await?
+    )
+}
+
 pub async fn get_releases_at<'e, E>(
     ex: E,
     pkg: Package,
@@ -124,7 +219,8 @@ SELECT
     releases.size,
     releases.checksum,
     releases.published_at,
-    users.username AS published_by
+    users.username AS published_by,
+    0 AS \"is_primary!: bool\"
 FROM releases
 JOIN users
 ON releases.published_by = users.user_id
@@ -170,7 +266,8 @@ SELECT
     files.size,
     files.checksum,
     files.published_at,
-    users.username AS published_by
+    users.username AS published_by,
+    files.is_primary AS \"is_primary: bool\"
 FROM files
 JOIN users
 ON files.published_by = users.user_id
@@ -215,7 +312,8 @@ SELECT
     files.size,
     files.checksum,
     files.published_at,
-    users.username AS published_by
+    users.username AS published_by,
+    files.is_primary AS \"is_primary: bool\"
 FROM files
 JOIN users
 ON files.published_by = users.user_id
@@ -242,16 +340,21 @@ pub async fn get_release_version_url<'e, E>(
     ex: E,
     pkg: Package,
     version: &Version
-) -> Result<String, CoreError>
+) -> Result<ReleaseUrlRow, CoreError>
 where
     E: Executor<'e, Database = Sqlite>
 {
     let pre = version.pre.as_deref().unwrap_or("");
     let build = version.build.as_deref().unwrap_or("");
 
-    sqlx::query_scalar!(
+    sqlx::query_as!(
+        ReleaseUrlRow,
         "
-SELECT url
+SELECT
+    release_id,
+    version,
+    filename,
+    url
 FROM releases
 WHERE package_id = ?
     AND version_major = ?
@@ -276,7 +379,7 @@ LIMIT 1
 pub async fn get_release_url<'e, E>(
     ex: E,
     pkg: Package
-) -> Result<String, CoreError>
+) -> Result<ReleaseUrlRow, CoreError>
 where
     E: Executor<'e, Database = Sqlite>
 {
@@ -284,6 +387,9 @@ where
         ReducedFileRow,
         "
 SELECT
+    release_id,
+    version,
+    filename,
     url,
     version_major,
     version_minor,
@@ -305,7 +411,12 @@ ORDER BY
     .await?
     .into_iter()
     .min_by(file_row_desc_cmp)
-    .map(|r| r.url)
+    .map(|r| ReleaseUrlRow {
+        release_id: r.release_id,
+        version: r.version,
+        filename: r.filename,
+        url: r.url
+    })
     .ok_or(CoreError::NotAPackage)
 }
 
@@ -315,6 +426,7 @@ async fn create_release_row<'e, E>(
     proj: Project,
     pkg: Package,
     version: &Version,
+    requires: Option<&Requires>,
     filename: &str,
     size: i64,
     checksum: &str,
@@ -328,6 +440,13 @@ where
     let pre = version.pre.as_deref().unwrap_or("");
     let build = version.build.as_deref().unwrap_or("");
 
+    // store the least satisfying version so we can compare numerically
+    let rstr = requires.map(String::from).unwrap_or_default();
+    let rmin = requires.and_then(Requires::minimum);
+    let rmajor = rmin.as_ref().map(|v| v.major);
+    let rminor = rmin.as_ref().map(|v| v.minor);
+    let rpatch = rmin.as_ref().map(|v| v.patch);
+
     sqlx::query!(
         "
 INSERT INTO releases (
@@ -343,9 +462,13 @@ INSERT INTO releases (
     size,
     checksum,
     published_at,
-    published_by
+    published_by,
+    requires,
+    requires_major,
+    requires_minor,
+    requires_patch
 )
-VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
         ",
         pkg.0,
         vstr,
@@ -359,7 +482,11 @@ VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
         size,
         checksum,
         now,
-        owner.0
+        owner.0,
+        rstr,
+        rmajor,
+        rminor,
+        rpatch
     )
     .execute(ex)
     .await?;
@@ -367,12 +494,225 @@ VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
+async fn create_file_row<'e, E>(
+    ex: E,
+    owner: Owner,
+    pkg: Package,
+    version: &Version,
+    filename: &str,
+    size: i64,
+    checksum: &str,
+    url: &str,
+    now: i64
+) -> Result<i64, CoreError>
+where
+    E: Executor<'e, Database = Sqlite>
+{
+    let vstr = String::from(version);
+    let pre = version.pre.as_deref().unwrap_or("");
+    let build = version.build.as_deref().unwrap_or("");
+
+    Ok(
+        sqlx::query_scalar!(
+            "
+INSERT INTO files (
+    package_id,
+    version,
+    version_major,
+    version_minor,
+    version_patch,
+    version_pre,
+    version_build,
+    url,
+    filename,
+    size,
+    checksum,
+    published_at,
+    published_by
+)
+VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+RETURNING file_id
+            ",
+            pkg.0,
+            vstr,
+            version.major,
+            version.minor,
+            version.patch,
+            pre,
+            build,
+            url,
+            filename,
+            size,
+            checksum,
+            now,
+            owner.0
+        )
+        .fetch_one(ex)
+        .await?
+    )
+}
+
+async fn get_file_row<'e, E>(
+    ex: E,
+    file_id: i64
+) -> Result<FileRow, CoreError>
+where
+    E: Executor<'e, Database = Sqlite>
+{
+    Ok(
+        sqlx::query_as!(
+            FileRow,
+            "
+SELECT
+    files.file_id AS id,
+    files.version,
+    files.version_major,
+    files.version_minor,
+    files.version_patch,
+    files.version_pre,
+    files.version_build,
+    files.url,
+    files.filename,
+    files.size,
+    files.checksum,
+    files.published_at,
+    users.username AS published_by,
+    files.is_primary AS \"is_primary: bool\"
+FROM files
+JOIN users
+ON files.published_by = users.user_id
+WHERE files.file_id = ?
+            ",
+            file_id
+        )
+        .fetch_one(ex)
+        .await?
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn add_file_url<'a, A>(
+    conn: A,
+    owner: Owner,
+    proj: Project,
+    pkg: Package,
+    version: &Version,
+    filename: &str,
+    size: i64,
+    checksum: &str,
+    url: &str,
+    now: i64
+) -> Result<FileRow, CoreError>
+where
+    A: Acquire<'a, Database = Sqlite>
+{
+    let mut tx = conn.begin().await?;
+
+    // insert file row
+    let file_id = create_file_row(
+        &mut *tx,
+        owner,
+        pkg,
+        version,
+        filename,
+        size,
+        checksum,
+        url,
+        now
+    ).await?;
+
+    let row = get_file_row(&mut *tx, file_id).await?;
+
+    // update project to reflect the change
+    update_project_non_project_data(&mut tx, owner, proj, now).await?;
+
+    tx.commit().await?;
+
+    Ok(row)
+}
+
+pub async fn set_primary_file<'a, A>(
+    conn: A,
+    owner: Owner,
+    proj: Project,
+    pkg: Package,
+    version: &Version,
+    filename: &str,
+    now: i64
+) -> Result<(), CoreError>
+where
+    A: Acquire<'a, Database = Sqlite>
+{
+    let mut tx = conn.begin().await?;
+
+    let pre = version.pre.as_deref().unwrap_or("");
+    let build = version.build.as_deref().unwrap_or("");
+
+    let file_id = sqlx::query_scalar!(
+        "
+SELECT file_id
+FROM files
+WHERE package_id = ?
+    AND version_major = ?
+    AND version_minor = ?
+    AND version_patch = ?
+    AND version_pre = ?
+    AND version_build = ?
+    AND filename = ?
+ORDER BY published_at DESC
+LIMIT 1
+        ",
+        pkg.0,
+        version.major,
+        version.minor,
+        version.patch,
+        pre,
+        build,
+        filename
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(CoreError::NotFound)?;
+
+    // mark the file as primary and unmark all others for this version
+    sqlx::query!(
+        "
+UPDATE files
+SET is_primary = file_id = ?
+WHERE package_id = ?
+    AND version_major = ?
+    AND version_minor = ?
+    AND version_patch = ?
+    AND version_pre = ?
+    AND version_build = ?
+        ",
+        file_id,
+        pkg.0,
+        version.major,
+        version.minor,
+        version.patch,
+        pre,
+        build
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    // update project to reflect the change
+    update_project_non_project_data(&mut tx, owner, proj, now).await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
 pub async fn add_release_url<'a, A>(
     conn: A,
     owner: Owner,
     proj: Project,
     pkg: Package,
     version: &Version,
+    requires: Option<&Requires>,
     filename: &str,
     size: i64,
     checksum: &str,
@@ -391,6 +731,7 @@ where
         proj,
         pkg,
         version,
+        requires,
         filename,
         size,
         checksum,
@@ -406,6 +747,102 @@ where
     Ok(())
 }
 
+pub async fn delete_release<'a, A>(
+    conn: A,
+    owner: Owner,
+    proj: Project,
+    pkg: Package,
+    version: &Version,
+    now: i64
+) -> Result<(), CoreError>
+where
+    A: Acquire<'a, Database = Sqlite>
+{
+    let mut tx = conn.begin().await?;
+
+    let pre = version.pre.as_deref().unwrap_or("");
+    let build = version.build.as_deref().unwrap_or("");
+
+    let release_id = get_release_version_url(&mut *tx, pkg, version)
+        .await?
+        .release_id;
+
+    // remove everything which refers to the release
+    sqlx::query!(
+        "
+UPDATE packages
+SET recommended_release_id = NULL
+WHERE recommended_release_id = ?
+        ",
+        release_id
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query!(
+        "
+DELETE FROM authors
+WHERE release_id = ?
+        ",
+        release_id
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query!(
+        "
+DELETE FROM downloads
+WHERE package_id = ?
+    AND version = (
+        SELECT version
+        FROM releases
+        WHERE release_id = ?
+    )
+        ",
+        pkg.0,
+        release_id
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query!(
+        "
+DELETE FROM files
+WHERE package_id = ?
+    AND version_major = ?
+    AND version_minor = ?
+    AND version_patch = ?
+    AND version_pre = ?
+    AND version_build = ?
+        ",
+        pkg.0,
+        version.major,
+        version.minor,
+        version.patch,
+        pre,
+        build
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query!(
+        "
+DELETE FROM releases
+WHERE release_id = ?
+        ",
+        release_id
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    // update project to reflect the change
+    update_project_non_project_data(&mut tx, owner, proj, now).await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -428,7 +865,8 @@ mod test {
             size: 1234,
             checksum: "c0e0fa7373a12b45a91e4f4d4e2e186442fc6ee9b346caa2fdc1c09026a2144a".into(),
             published_at: 1702137389180282477,
-            published_by: "bob".into()
+            published_by: "bob".into(),
+            is_primary: false
         }
     );
 
@@ -446,7 +884,8 @@ mod test {
             size: 5678,
             checksum: "79fdd8fe3128f818e446e919cce5dcfb81815f8f4341c53f4d6b58ded48cebf2".into(),
             published_at: 1702223789180282477,
-            published_by: "alice".into()
+            published_by: "alice".into(),
+            is_primary: false
         }
     );
 
@@ -468,6 +907,80 @@ mod test {
         );
     }
 
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn get_releases_window_start(pool: Pool) {
+        assert_eq!(
+            get_releases_window(&pool, Package(1), &ReleaseAnchor::Start, 1)
+                .await
+                .unwrap(),
+            vec![ RR_1_2_4.clone() ]
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn get_releases_window_after(pool: Pool) {
+        let anchor = ReleaseAnchor::After("1.2.4".parse().unwrap());
+        assert_eq!(
+            get_releases_window(&pool, Package(1), &anchor, 5)
+                .await
+                .unwrap(),
+            vec![ RR_1_2_3.clone() ]
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn get_releases_window_before(pool: Pool) {
+        let anchor = ReleaseAnchor::Before("1.2.3".parse().unwrap());
+        assert_eq!(
+            get_releases_window(&pool, Package(1), &anchor, 5)
+                .await
+                .unwrap(),
+            vec![ RR_1_2_4.clone() ]
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn get_releases_window_before_limited(pool: Pool) {
+        // the releases nearest the anchor are kept
+        let anchor = ReleaseAnchor::Before("1.0.0".parse().unwrap());
+        assert_eq!(
+            get_releases_window(&pool, Package(1), &anchor, 1)
+                .await
+                .unwrap(),
+            vec![ RR_1_2_3.clone() ]
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn get_user_releases_ok(pool: Pool) {
+        assert_eq!(
+            get_user_releases(&pool, User(1), 5).await.unwrap(),
+            [
+                UserReleaseRow {
+                    project: "test_game".into(),
+                    package: "a_package".into(),
+                    version: "1.2.3".into(),
+                    published_at: 1702137389180282477
+                }
+            ]
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn get_user_releases_limit(pool: Pool) {
+        assert_eq!(get_user_releases(&pool, User(1), 0).await.unwrap(), []);
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn get_user_releases_none(pool: Pool) {
+        assert_eq!(get_user_releases(&pool, User(0), 5).await.unwrap(), []);
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn get_releases_count_ok(pool: Pool) {
+        assert_eq!(get_releases_count(&pool, Package(1)).await.unwrap(), 2);
+    }
+
     #[sqlx::test(fixtures("users", "projects", "packages"))]
     async fn get_releases_at_all(pool: Pool) {
         assert_eq!(
@@ -510,7 +1023,12 @@ mod test {
     async fn get_release_url_ok(pool: Pool) {
         assert_eq!(
             get_release_url(&pool, Package(1)).await.unwrap(),
-            "https://example.com/a_package-1.2.4"
+            ReleaseUrlRow {
+                release_id: 2,
+                version: "1.2.4".into(),
+                filename: "a_package-1.2.4".into(),
+                url: "https://example.com/a_package-1.2.4".into()
+            }
         );
     }
 
@@ -534,7 +1052,12 @@ mod test {
         };
         assert_eq!(
             get_release_version_url(&pool, pkg, &version).await.unwrap(),
-            "https://example.com/a_package-1.2.4"
+            ReleaseUrlRow {
+                release_id: 2,
+                version: "1.2.4".into(),
+                filename: "a_package-1.2.4".into(),
+                url: "https://example.com/a_package-1.2.4".into()
+            }
         );
     }
 
@@ -593,6 +1116,7 @@ mod test {
             Project(42),
             Package(1),
             &version,
+            None,
             "new_thing.vmod",
             123456,
             "",
@@ -601,6 +1125,103 @@ mod test {
         ).await.unwrap();
     }
 
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn add_file_url_ok(pool: Pool) {
+        let row = add_file_url(
+            &pool,
+            Owner(1),
+            Project(42),
+            Package(1),
+            &"1.2.3".parse::<Version>().unwrap(),
+            "scenario.vsav",
+            1234,
+            "abcd",
+            "https://example.com/scenario.vsav",
+            1702569006419538067
+        ).await.unwrap();
+
+        assert_eq!(
+            row,
+            FileRow {
+                id: row.id,
+                version: "1.2.3".into(),
+                version_major: 1,
+                version_minor: 2,
+                version_patch: 3,
+                version_pre: "".into(),
+                version_build: "".into(),
+                url: "https://example.com/scenario.vsav".into(),
+                filename: "scenario.vsav".into(),
+                size: 1234,
+                checksum: "abcd".into(),
+                published_at: 1702569006419538067,
+                published_by: "bob".into(),
+                is_primary: false
+            }
+        );
+
+        assert_eq!(get_files(&pool, Package(1)).await.unwrap(), [row]);
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn add_file_url_not_a_package(pool: Pool) {
+        assert!(
+            matches!(
+                add_file_url(
+                    &pool,
+                    Owner(1),
+                    Project(42),
+                    Package(0),
+                    &"1.2.3".parse::<Version>().unwrap(),
+                    "scenario.vsav",
+                    1234,
+                    "abcd",
+                    "https://example.com/scenario.vsav",
+                    1702569006419538067
+                ).await.unwrap_err(),
+                CoreError::DatabaseError(_)
+            )
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn add_release_url_requires_ok(pool: Pool) {
+        add_release_url(
+            &pool,
+            Owner(1),
+            Project(42),
+            Package(1),
+            &"1.2.5".parse().unwrap(),
+            Some(&">=3.7.1, <4".parse().unwrap()),
+            "new_thing.vmod",
+            123456,
+            "",
+            "https://example.com/new_thing.vmod",
+            0
+        ).await.unwrap();
+
+        let row = sqlx::query!(
+            "
+SELECT
+    requires,
+    requires_major,
+    requires_minor,
+    requires_patch
+FROM releases
+WHERE filename = 'new_thing.vmod'
+            "
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+        assert_eq!(row.requires, ">=3.7.1, <4");
+        assert_eq!(
+            (row.requires_major, row.requires_minor, row.requires_patch),
+            (Some(3), Some(7), Some(1))
+        );
+    }
+
     #[sqlx::test(fixtures("users", "projects", "packages"))]
     async fn add_release_url_not_a_user(pool: Pool) {
         // This should not happen; the Owner passed in should be good.
@@ -618,6 +1239,7 @@ mod test {
                         pre: None,
                         build: None
                     },
+                    None,
                     "new_thing.vmod",
                     123456,
                     "",
@@ -646,6 +1268,7 @@ mod test {
                         pre: None,
                         build: None
                     },
+                    None,
                     "new_thing.vmod",
                     123456,
                     "",
@@ -674,6 +1297,7 @@ mod test {
                         pre: None,
                         build: None
                     },
+                    None,
                     "new_thing.vmod",
                     123456,
                     "",
@@ -702,6 +1326,7 @@ mod test {
                         pre: None,
                         build: None
                     },
+                    None,
                     "new_thing.vmod",
                     123456,
                     "",
@@ -712,4 +1337,122 @@ mod test {
             )
         );
     }
+
+    async fn primary_files(pool: &Pool, pkg: Package) -> Vec<(String, bool)> {
+        get_files(pool, pkg)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|f| (f.filename, f.is_primary))
+            .collect()
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages", "files"))]
+    async fn set_primary_file_ok(pool: Pool) {
+        set_primary_file(
+            &pool,
+            Owner(1),
+            Project(42),
+            Package(1),
+            &"1.2.3".parse::<Version>().unwrap(),
+            "a_package-1.2.3.vmod",
+            1702569006419538067
+        ).await.unwrap();
+
+        let files = primary_files(&pool, Package(1)).await;
+        assert!(files.contains(&("a_package-1.2.3.vmod".into(), true)));
+        assert!(files.contains(&("maps-1.2.3.zip".into(), false)));
+        assert!(files.iter().all(|(f, p)| !p || f == "a_package-1.2.3.vmod"));
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages", "files"))]
+    async fn set_primary_file_wrong_version(pool: Pool) {
+        assert_eq!(
+            set_primary_file(
+                &pool,
+                Owner(1),
+                Project(42),
+                Package(1),
+                &"1.2.4".parse::<Version>().unwrap(),
+                "a_package-1.2.3.vmod",
+                1702569006419538067
+            ).await.unwrap_err(),
+            CoreError::NotFound
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages", "files"))]
+    async fn set_primary_file_not_a_file(pool: Pool) {
+        assert_eq!(
+            set_primary_file(
+                &pool,
+                Owner(1),
+                Project(42),
+                Package(1),
+                &"1.2.3".parse::<Version>().unwrap(),
+                "bogus.vmod",
+                1702569006419538067
+            ).await.unwrap_err(),
+            CoreError::NotFound
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages", "files", "file_downloads", "project_views"))]
+    async fn delete_release_ok(pool: Pool) {
+        let version = "1.2.3".parse::<Version>().unwrap();
+
+        delete_release(
+            &pool,
+            Owner(1),
+            Project(42),
+            Package(1),
+            &version,
+            1702569006419538067
+        ).await.unwrap();
+
+        assert_eq!(
+            get_release_version_url(&pool, Package(1), &version)
+                .await
+                .unwrap_err(),
+            CoreError::NotAVersion
+        );
+
+        // files of other versions are untouched
+        assert!(
+            get_files(&pool, Package(1))
+                .await
+                .unwrap()
+                .iter()
+                .all(|f| f.version != "1.2.3")
+        );
+        assert!(
+            get_files(&pool, Package(1))
+                .await
+                .unwrap()
+                .iter()
+                .any(|f| f.version == "1.2.4")
+        );
+        assert!(
+            get_release_version_url(
+                &pool,
+                Package(1),
+                &"1.2.4".parse::<Version>().unwrap()
+            ).await.is_ok()
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn delete_release_not_a_version(pool: Pool) {
+        assert_eq!(
+            delete_release(
+                &pool,
+                Owner(1),
+                Project(42),
+                Package(1),
+                &"9.9.9".parse::<Version>().unwrap(),
+                1702569006419538067
+            ).await.unwrap_err(),
+            CoreError::NotAVersion
+        );
+    }
 }
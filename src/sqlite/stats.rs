@@ -0,0 +1,496 @@
+use sqlx::{
+    Executor,
+    sqlite::Sqlite
+};
+
+use crate::{
+    core::CoreError,
+    db::{DailyStatsRow, ProjectStatsRow, ProjectStatusRow},
+    model::{LibraryStats, Package, Project}
+};
+
+pub async fn get_library_stats<'e, E>(
+    ex: E
+) -> Result<LibraryStats, CoreError>
+where
+    E: Executor<'e, Database = Sqlite>
+{
+    // deleted projects and everything in them are not counted; players
+    // and owners are counted once each, however many projects they have
+    Ok(
+        sqlx::query_as!(
+            LibraryStats,
+            "
+WITH live_packages AS (
+    SELECT packages.package_id
+    FROM packages
+    JOIN projects
+    ON packages.project_id = projects.project_id
+    WHERE projects.deleted_at IS NULL
+)
+SELECT
+    (
+        SELECT COUNT(1)
+        FROM projects
+        WHERE projects.deleted_at IS NULL
+    ) AS \"projects!: i64\",
+    (
+        SELECT COUNT(1)
+        FROM live_packages
+    ) AS \"packages!: i64\",
+    (
+        SELECT COUNT(1)
+        FROM releases
+        WHERE releases.package_id IN live_packages
+    ) AS \"releases!: i64\",
+    (
+        SELECT COUNT(1)
+        FROM releases
+        WHERE releases.package_id IN live_packages
+    ) + (
+        SELECT COUNT(1)
+        FROM files
+        WHERE files.package_id IN live_packages
+    ) AS \"files!: i64\",
+    (
+        SELECT COALESCE(SUM(releases.size), 0)
+        FROM releases
+        WHERE releases.package_id IN live_packages
+    ) + (
+        SELECT COALESCE(SUM(files.size), 0)
+        FROM files
+        WHERE files.package_id IN live_packages
+    ) AS \"bytes!: i64\",
+    (
+        SELECT COUNT(DISTINCT players.user_id)
+        FROM players
+        JOIN projects
+        ON players.project_id = projects.project_id
+        WHERE projects.deleted_at IS NULL
+    ) AS \"players!: i64\",
+    (
+        SELECT COUNT(DISTINCT owners.user_id)
+        FROM owners
+        JOIN projects
+        ON owners.project_id = projects.project_id
+        WHERE projects.deleted_at IS NULL
+    ) AS \"owners!: i64\",
+    (
+        SELECT COUNT(1)
+        FROM flags
+        JOIN projects
+        ON flags.project_id = projects.project_id
+        WHERE projects.deleted_at IS NULL
+    ) AS \"flags!: i64\"
+            "
+        )
+        .fetch_one(ex)
+        .await?
+    )
+}
+
+pub async fn get_project_stats<'e, E>(
+    ex: E,
+    proj: Project
+) -> Result<ProjectStatsRow, CoreError>
+where
+    E: Executor<'e, Database = Sqlite>
+{
+    // each release is itself a file, in addition to the other files
+    // uploaded with it
+    sqlx::query_as!(
+        ProjectStatsRow,
+        "
+SELECT
+    (
+        SELECT COUNT(1)
+        FROM releases
+        JOIN packages
+        ON releases.package_id = packages.package_id
+        WHERE packages.project_id = projects.project_id
+    ) + (
+        SELECT COUNT(1)
+        FROM files
+        JOIN packages
+        ON files.package_id = packages.package_id
+        WHERE packages.project_id = projects.project_id
+    ) AS \"files!: i64\",
+    (
+        SELECT COALESCE(SUM(releases.size), 0)
+        FROM releases
+        JOIN packages
+        ON releases.package_id = packages.package_id
+        WHERE packages.project_id = projects.project_id
+    ) + (
+        SELECT COALESCE(SUM(files.size), 0)
+        FROM files
+        JOIN packages
+        ON files.package_id = packages.package_id
+        WHERE packages.project_id = projects.project_id
+    ) AS \"bytes!: i64\",
+    (
+        SELECT COUNT(1)
+        FROM releases
+        JOIN packages
+        ON releases.package_id = packages.package_id
+        WHERE packages.project_id = projects.project_id
+    ) AS \"releases!: i64\",
+    (
+        SELECT COUNT(1)
+        FROM packages
+        WHERE packages.project_id = projects.project_id
+    ) AS \"packages!: i64\",
+    (
+        SELECT COUNT(1)
+        FROM players
+        WHERE players.project_id = projects.project_id
+    ) AS \"players!: i64\",
+    (
+        SELECT COUNT(1)
+        FROM owners
+        WHERE owners.project_id = projects.project_id
+    ) AS \"owners!: i64\",
+    (
+        SELECT COUNT(1)
+        FROM flags
+        WHERE flags.project_id = projects.project_id
+    ) AS \"flags!: i64\",
+    (
+        SELECT COALESCE(SUM(project_views.views), 0)
+        FROM project_views
+        WHERE project_views.project_id = projects.project_id
+    ) AS \"views!: i64\",
+    (
+        SELECT COALESCE(SUM(downloads.downloads), 0)
+        FROM downloads
+        JOIN packages
+        ON downloads.package_id = packages.package_id
+        WHERE packages.project_id = projects.project_id
+    ) AS \"downloads!: i64\",
+    projects.created_at
+FROM projects
+WHERE projects.project_id = ?
+        ",
+        proj.0
+    )
+    .fetch_optional(ex)
+    .await?
+    .ok_or(CoreError::NotAProject)
+}
+
+pub async fn get_project_daily_stats<'e, E>(
+    ex: E,
+    proj: Project,
+    since: i64
+) -> Result<Vec<DailyStatsRow>, CoreError>
+where
+    E: Executor<'e, Database = Sqlite>
+{
+    // days with neither views nor downloads have no row
+    Ok(
+        sqlx::query_as!(
+            DailyStatsRow,
+            "
+WITH counts AS (
+    SELECT
+        project_views.day AS day,
+        project_views.views AS views,
+        0 AS downloads
+    FROM project_views
+    WHERE project_views.project_id = ?
+        AND project_views.day >= ?
+    UNION ALL
+    SELECT
+        downloads.day AS day,
+        0 AS views,
+        downloads.downloads AS downloads
+    FROM downloads
+    JOIN packages
+    ON downloads.package_id = packages.package_id
+    WHERE packages.project_id = ?
+        AND downloads.day >= ?
+)
+SELECT
+    day AS \"day!: i64\",
+    SUM(views) AS \"views!: i64\",
+    SUM(downloads) AS \"downloads!: i64\"
+FROM counts
+GROUP BY day
+ORDER BY day
+            ",
+            proj.0,
+            since,
+            proj.0,
+            since
+        )
+        .fetch_all(ex)
+        .await?
+    )
+}
+
+pub async fn increment_project_views<'e, E>(
+    ex: E,
+    proj: Project,
+    day: i64
+) -> Result<(), CoreError>
+where
+    E: Executor<'e, Database = Sqlite>
+{
+    sqlx::query!(
+        "
+INSERT INTO project_views (
+    project_id,
+    day,
+    views
+)
+VALUES (?, ?, 1)
+ON CONFLICT(project_id, day)
+DO UPDATE
+SET views = views + 1
+        ",
+        proj.0,
+        day
+    )
+    .execute(ex)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn increment_file_downloads<'e, E>(
+    ex: E,
+    pkg: Package,
+    version: &str,
+    filename: &str,
+    day: i64
+) -> Result<(), CoreError>
+where
+    E: Executor<'e, Database = Sqlite>
+{
+    sqlx::query!(
+        "
+INSERT INTO downloads (
+    package_id,
+    version,
+    filename,
+    day,
+    downloads
+)
+VALUES (?, ?, ?, ?, 1)
+ON CONFLICT(package_id, version, filename, day)
+DO UPDATE
+SET downloads = downloads + 1
+        ",
+        pkg.0,
+        version,
+        filename,
+        day
+    )
+    .execute(ex)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_project_status<'e, E>(
+    ex: E,
+    proj: Project
+) -> Result<ProjectStatusRow, CoreError>
+where
+    E: Executor<'e, Database = Sqlite>
+{
+    // resolved flags need no attention, so only open ones are counted
+    sqlx::query_as!(
+        ProjectStatusRow,
+        "
+SELECT
+    (
+        SELECT COUNT(1)
+        FROM flags
+        WHERE flags.project_id = projects.project_id
+            AND flags.resolved_at IS NULL
+    ) AS \"open_flags!: i64\",
+    projects.archived AS \"archived: bool\",
+    (
+        SELECT COALESCE(SUM(releases.size), 0)
+        FROM releases
+        JOIN packages
+        ON releases.package_id = packages.package_id
+        WHERE packages.project_id = projects.project_id
+    ) + (
+        SELECT COALESCE(SUM(files.size), 0)
+        FROM files
+        JOIN packages
+        ON files.package_id = packages.package_id
+        WHERE packages.project_id = projects.project_id
+    ) AS \"bytes!: i64\"
+FROM projects
+WHERE projects.project_id = ?
+        ",
+        proj.0
+    )
+    .fetch_optional(ex)
+    .await?
+    .ok_or(CoreError::NotAProject)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    type Pool = sqlx::Pool<Sqlite>;
+
+    #[sqlx::test(fixtures("users", "projects", "packages", "files", "two_owners", "players", "flags"))]
+    async fn get_project_stats_ok(pool: Pool) {
+        assert_eq!(
+            get_project_stats(&pool, Project(42)).await.unwrap(),
+            ProjectStatsRow {
+                files: 8,
+                bytes: 147379,
+                releases: 3,
+                packages: 3,
+                players: 2,
+                owners: 2,
+                flags: 1,
+                views: 0,
+                downloads: 0,
+                created_at: 1699804206419538067
+            }
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages", "files", "flags"))]
+    async fn get_project_status_ok(pool: Pool) {
+        assert_eq!(
+            get_project_status(&pool, Project(42)).await.unwrap(),
+            ProjectStatusRow {
+                open_flags: 1,
+                archived: false,
+                bytes: 147379
+            }
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "flags", "archived"))]
+    async fn get_project_status_archived(pool: Pool) {
+        // the only flag on this project is resolved
+        assert_eq!(
+            get_project_status(&pool, Project(6)).await.unwrap(),
+            ProjectStatusRow {
+                open_flags: 0,
+                archived: true,
+                bytes: 0
+            }
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn get_project_status_not_a_project(pool: Pool) {
+        assert_eq!(
+            get_project_status(&pool, Project(1)).await.unwrap_err(),
+            CoreError::NotAProject
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages", "files", "file_downloads", "project_views"))]
+    async fn get_project_stats_usage(pool: Pool) {
+        let row = get_project_stats(&pool, Project(42)).await.unwrap();
+        assert_eq!(row.views, 17);
+        assert_eq!(row.downloads, 17);
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages", "files", "file_downloads", "project_views"))]
+    async fn get_project_daily_stats_ok(pool: Pool) {
+        assert_eq!(
+            get_project_daily_stats(&pool, Project(42), 19650).await.unwrap(),
+            [
+                DailyStatsRow { day: 19650, views: 0, downloads: 1 },
+                DailyStatsRow { day: 19672, views: 2, downloads: 0 },
+                DailyStatsRow { day: 19673, views: 5, downloads: 3 },
+                DailyStatsRow { day: 19700, views: 0, downloads: 5 },
+                DailyStatsRow { day: 19701, views: 0, downloads: 4 }
+            ]
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn get_project_daily_stats_none(pool: Pool) {
+        assert_eq!(
+            get_project_daily_stats(&pool, Project(42), 0).await.unwrap(),
+            []
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn increment_project_views_ok(pool: Pool) {
+        increment_project_views(&pool, Project(42), 19673).await.unwrap();
+        increment_project_views(&pool, Project(42), 19673).await.unwrap();
+        increment_project_views(&pool, Project(42), 19674).await.unwrap();
+
+        assert_eq!(
+            get_project_daily_stats(&pool, Project(42), 0).await.unwrap(),
+            [
+                DailyStatsRow { day: 19673, views: 2, downloads: 0 },
+                DailyStatsRow { day: 19674, views: 1, downloads: 0 }
+            ]
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages"))]
+    async fn increment_file_downloads_ok(pool: Pool) {
+        increment_file_downloads(&pool, Package(1), "1.2.3", "a_package-1.2.3", 19673).await.unwrap();
+        increment_file_downloads(&pool, Package(1), "1.2.3", "a_package-1.2.3", 19673).await.unwrap();
+        increment_file_downloads(&pool, Package(3), "0.1.0", "c_package-0.1.0", 19673).await.unwrap();
+        increment_file_downloads(&pool, Package(1), "1.2.3", "a_package-1.2.3", 19674).await.unwrap();
+
+        assert_eq!(
+            get_project_daily_stats(&pool, Project(42), 0).await.unwrap(),
+            [
+                DailyStatsRow { day: 19673, views: 0, downloads: 3 },
+                DailyStatsRow { day: 19674, views: 0, downloads: 1 }
+            ]
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "packages", "files", "two_owners", "players", "flags"))]
+    async fn get_library_stats_ok(pool: Pool) {
+        assert_eq!(
+            get_library_stats(&pool).await.unwrap(),
+            LibraryStats {
+                projects: 2,
+                packages: 3,
+                releases: 3,
+                files: 8,
+                bytes: 147379,
+                players: 2,
+                owners: 2,
+                flags: 2
+            }
+        );
+    }
+
+    #[sqlx::test]
+    async fn get_library_stats_empty(pool: Pool) {
+        assert_eq!(
+            get_library_stats(&pool).await.unwrap(),
+            LibraryStats {
+                projects: 0,
+                packages: 0,
+                releases: 0,
+                files: 0,
+                bytes: 0,
+                players: 0,
+                owners: 0,
+                flags: 0
+            }
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn get_project_stats_not_a_project(pool: Pool) {
+        assert_eq!(
+            get_project_stats(&pool, Project(1)).await.unwrap_err(),
+            CoreError::NotAProject
+        );
+    }
+}
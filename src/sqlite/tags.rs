@@ -0,0 +1,303 @@
+use sqlx::{
+    Executor, Transaction,
+    sqlite::Sqlite
+};
+
+use crate::{
+    core::CoreError,
+    model::{Project, TagChanges}
+};
+
+pub async fn get_tags<'e, E>(
+    ex: E,
+    proj: Project
+) -> Result<Vec<String>, CoreError>
+where
+    E: Executor<'e, Database = Sqlite>
+{
+    Ok(
+        sqlx::query_scalar!(
+            "
+SELECT tag
+FROM tags
+WHERE project_id = ?
+ORDER BY tag
+            ",
+            proj.0
+        )
+        .fetch_all(ex)
+        .await?
+    )
+}
+
+pub async fn get_tags_at<'e, E>(
+    ex: E,
+    proj: Project,
+    revision: i64
+) -> Result<Vec<String>, CoreError>
+where
+    E: Executor<'e, Database = Sqlite>
+{
+    Ok(
+        sqlx::query_scalar!(
+            "
+SELECT project_data_tags.tag
+FROM project_data_tags
+JOIN project_revisions
+ON project_data_tags.project_data_id = project_revisions.project_data_id
+WHERE project_revisions.project_id = ?
+    AND project_revisions.revision = ?
+ORDER BY project_data_tags.tag
+            ",
+            proj.0,
+            revision
+        )
+        .fetch_all(ex)
+        .await?
+    )
+}
+
+async fn add_tag<'e, E>(
+    ex: E,
+    proj: Project,
+    tag: &str
+) -> Result<(), CoreError>
+where
+    E: Executor<'e, Database = Sqlite>
+{
+    sqlx::query!(
+        "
+INSERT INTO tags (
+    project_id,
+    tag
+)
+VALUES (?, ?)
+        ",
+        proj.0,
+        tag
+    )
+    .execute(ex)
+    .await?;
+
+    Ok(())
+}
+
+async fn remove_tag<'e, E>(
+    ex: E,
+    proj: Project,
+    tag: &str
+) -> Result<(), CoreError>
+where
+    E: Executor<'e, Database = Sqlite>
+{
+    sqlx::query!(
+        "
+DELETE FROM tags
+WHERE project_id = ?
+    AND tag = ?
+        ",
+        proj.0,
+        tag
+    )
+    .execute(ex)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn add_tags(
+    tx: &mut Transaction<'_, Sqlite>,
+    proj: Project,
+    tags: &[String]
+) -> Result<(), CoreError>
+{
+    for tag in tags {
+        add_tag(&mut **tx, proj, tag).await?;
+    }
+
+    Ok(())
+}
+
+pub async fn replace_tags(
+    tx: &mut Transaction<'_, Sqlite>,
+    proj: Project,
+    tags: &[String]
+) -> Result<TagChanges, CoreError>
+{
+    let old_tags = get_tags(&mut **tx, proj).await?;
+
+    let mut added = tags.iter()
+        .filter(|t| !old_tags.contains(t))
+        .cloned()
+        .collect::<Vec<_>>();
+    added.sort();
+
+    let mut removed = old_tags.into_iter()
+        .filter(|t| !tags.contains(t))
+        .collect::<Vec<_>>();
+    removed.sort();
+
+    for tag in &removed {
+        remove_tag(&mut **tx, proj, tag).await?;
+    }
+
+    add_tags(tx, proj, &added).await?;
+
+    let mut tags = tags.to_vec();
+    tags.sort();
+
+    Ok(TagChanges { tags, added, removed })
+}
+
+pub async fn add_project_data_tags(
+    tx: &mut Transaction<'_, Sqlite>,
+    project_data_id: i64,
+    tags: &[String]
+) -> Result<(), CoreError>
+{
+    for tag in tags {
+        sqlx::query!(
+            "
+INSERT INTO project_data_tags (
+    project_data_id,
+    tag
+)
+VALUES (?, ?)
+            ",
+            project_data_id,
+            tag
+        )
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    type Pool = sqlx::Pool<Sqlite>;
+
+    async fn sorted_tags(pool: &Pool, proj: Project) -> Vec<String> {
+        let mut tags = get_tags(pool, proj).await.unwrap();
+        tags.sort();
+        tags
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "tags"))]
+    async fn get_tags_ok(pool: Pool) {
+        // tags are inserted unsorted; they come back sorted
+        assert_eq!(
+            get_tags(&pool, Project(42)).await.unwrap(),
+            ["tactical", "wwii"]
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "tags"))]
+    async fn get_tags_not_a_project(pool: Pool) {
+        // This should not happen; the Project passed in should be good.
+        // However, it's not an error if it does.
+        assert_eq!(
+            get_tags(&pool, Project(0)).await.unwrap(),
+            Vec::<String>::new()
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "tags"))]
+    async fn get_tags_at_ok(pool: Pool) {
+        assert_eq!(
+            get_tags_at(&pool, Project(42), 1).await.unwrap(),
+            ["wwii"]
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "tags"))]
+    async fn get_tags_at_sorted(pool: Pool) {
+        // tags are inserted unsorted; they come back sorted
+        assert_eq!(
+            get_tags_at(&pool, Project(42), 3).await.unwrap(),
+            ["tactical", "wwii"]
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "tags"))]
+    async fn get_tags_at_not_a_revision(pool: Pool) {
+        assert_eq!(
+            get_tags_at(&pool, Project(42), 2).await.unwrap(),
+            Vec::<String>::new()
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "tags"))]
+    async fn replace_tags_ok(pool: Pool) {
+        let mut tx = pool.begin().await.unwrap();
+
+        let changes = replace_tags(
+            &mut tx,
+            Project(42),
+            &["wwii".into(), "east_front".into(), "armor".into()]
+        ).await.unwrap();
+
+        tx.commit().await.unwrap();
+
+        assert_eq!(
+            changes,
+            TagChanges {
+                tags: vec!["armor".into(), "east_front".into(), "wwii".into()],
+                added: vec!["armor".into(), "east_front".into()],
+                removed: vec!["tactical".into()]
+            }
+        );
+
+        assert_eq!(sorted_tags(&pool, Project(42)).await, changes.tags);
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "tags"))]
+    async fn replace_tags_unchanged(pool: Pool) {
+        let mut tx = pool.begin().await.unwrap();
+
+        let changes = replace_tags(
+            &mut tx,
+            Project(42),
+            &["wwii".into(), "tactical".into()]
+        ).await.unwrap();
+
+        tx.commit().await.unwrap();
+
+        assert_eq!(
+            changes,
+            TagChanges {
+                tags: vec!["tactical".into(), "wwii".into()],
+                added: vec![],
+                removed: vec![]
+            }
+        );
+
+        assert_eq!(sorted_tags(&pool, Project(42)).await, changes.tags);
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "tags"))]
+    async fn replace_tags_clear(pool: Pool) {
+        let mut tx = pool.begin().await.unwrap();
+
+        let changes = replace_tags(&mut tx, Project(42), &[]).await.unwrap();
+
+        tx.commit().await.unwrap();
+
+        assert_eq!(
+            changes,
+            TagChanges {
+                tags: vec![],
+                added: vec![],
+                removed: vec!["tactical".into(), "wwii".into()]
+            }
+        );
+
+        assert_eq!(
+            get_tags(&pool, Project(42)).await.unwrap(),
+            Vec::<String>::new()
+        );
+    }
+}
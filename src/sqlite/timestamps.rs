@@ -0,0 +1,75 @@
+use sqlx::{
+    Executor,
+    sqlite::Sqlite
+};
+
+use crate::{
+    core::CoreError,
+    db::TimestampRow
+};
+
+pub async fn get_timestamps<'e, E>(
+    ex: E
+) -> Result<Vec<TimestampRow>, CoreError>
+where
+    E: Executor<'e, Database = Sqlite>
+{
+    Ok(
+        sqlx::query_as!(
+            TimestampRow,
+            "
+SELECT
+    'projects' AS \"table_name!: String\",
+    project_id AS \"id!: i64\",
+    'created_at' AS \"column_name!: String\",
+    created_at AS \"value!: i64\"
+FROM projects
+UNION ALL
+SELECT
+    'projects',
+    project_id,
+    'modified_at',
+    modified_at
+FROM projects
+UNION ALL
+SELECT
+    'releases',
+    release_id,
+    'published_at',
+    published_at
+FROM releases
+UNION ALL
+SELECT
+    'files',
+    file_id,
+    'published_at',
+    published_at
+FROM files
+ORDER BY 1, 2, 3
+            "
+        )
+        .fetch_all(ex)
+        .await?
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    type Pool = sqlx::Pool<Sqlite>;
+
+    #[sqlx::test(fixtures("users", "projects"))]
+    async fn get_timestamps_ok(pool: Pool) {
+        assert_eq!(
+            get_timestamps(&pool)
+                .await
+                .unwrap()
+                .into_iter()
+                .filter(|r| r.table_name == "projects" && r.id == 6)
+                .map(|r| r.column_name)
+                .collect::<Vec<_>>(),
+            ["created_at", "modified_at"]
+        );
+    }
+}
@@ -5,6 +5,7 @@ use sqlx::{
 
 use crate::{
     core::CoreError,
+    db::UserProfileRow,
     model::{Project, User, Users}
 };
 
@@ -30,6 +31,66 @@ LIMIT 1
     .ok_or(CoreError::NotAUser)
 }
 
+pub async fn get_username<'e, E>(
+    ex: E,
+    user: User
+) -> Result<String, CoreError>
+where
+    E: Executor<'e, Database = Sqlite>
+{
+    sqlx::query_scalar!(
+        "
+SELECT username
+FROM users
+WHERE user_id = ?
+LIMIT 1
+        ",
+        user.0
+    )
+    .fetch_optional(ex)
+    .await?
+    .ok_or(CoreError::NotAUser)
+}
+
+pub async fn get_user_profile_row<'e, E>(
+    ex: E,
+    user: User
+) -> Result<UserProfileRow, CoreError>
+where
+    E: Executor<'e, Database = Sqlite>
+{
+    sqlx::query_as!(
+        UserProfileRow,
+        "
+SELECT
+    users.username,
+    (
+        SELECT COUNT(*)
+        FROM owners
+        JOIN projects
+        ON owners.project_id = projects.project_id
+        WHERE owners.user_id = users.user_id
+            AND projects.deleted_at IS NULL
+    ) AS \"owned_projects!: i64\",
+    (
+        SELECT COUNT(*)
+        FROM players
+        JOIN projects
+        ON players.project_id = projects.project_id
+        WHERE players.user_id = users.user_id
+            AND projects.deleted_at IS NULL
+    ) AS \"played_projects!: i64\"
+FROM users
+WHERE users.user_id = ?
+LIMIT 1
+        ",
+        user.0
+    )
+    .fetch_optional(ex)
+    .await?
+    .ok_or(CoreError::NotAUser)
+}
+
 pub async fn get_owners<'e, E>(
     ex: E,
     proj: Project
@@ -209,6 +270,51 @@ mod test {
 
     type Pool = sqlx::Pool<Sqlite>;
 
+    #[sqlx::test(fixtures("users"))]
+    async fn get_username_ok(pool: Pool) {
+        assert_eq!(get_username(&pool, User(1)).await.unwrap(), "bob");
+    }
+
+    #[sqlx::test(fixtures("users"))]
+    async fn get_username_not_a_user(pool: Pool) {
+        assert_eq!(
+            get_username(&pool, User(0)).await.unwrap_err(),
+            CoreError::NotAUser
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "one_owner", "players"))]
+    async fn get_user_profile_row_ok(pool: Pool) {
+        assert_eq!(
+            get_user_profile_row(&pool, User(1)).await.unwrap(),
+            UserProfileRow {
+                username: "bob".into(),
+                owned_projects: 1,
+                played_projects: 1
+            }
+        );
+    }
+
+    #[sqlx::test(fixtures("users", "projects", "one_owner", "players"))]
+    async fn get_user_profile_row_no_projects(pool: Pool) {
+        assert_eq!(
+            get_user_profile_row(&pool, User(3)).await.unwrap(),
+            UserProfileRow {
+                username: "chuck".into(),
+                owned_projects: 0,
+                played_projects: 0
+            }
+        );
+    }
+
+    #[sqlx::test(fixtures("users"))]
+    async fn get_user_profile_row_not_a_user(pool: Pool) {
+        assert_eq!(
+            get_user_profile_row(&pool, User(0)).await.unwrap_err(),
+            CoreError::NotAUser
+        );
+    }
+
     #[sqlx::test(fixtures("users", "projects", "one_owner"))]
     async fn get_owners_ok(pool: Pool) {
         assert_eq!(
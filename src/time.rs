@@ -1,9 +1,15 @@
 use chrono::{DateTime, Utc};
 
+pub const NANOS_PER_SEC: i64 = 1_000_000_000;
+pub const SECS_PER_DAY: i64 = 24 * 60 * 60;
+pub const NANOS_PER_DAY: i64 = SECS_PER_DAY * NANOS_PER_SEC;
+
 #[derive(Debug, thiserror::Error, Eq, PartialEq)]
 pub enum Error {
     #[error("{0} is out of range")]
     OutOfRangeNs(i64),
+    #[error("day {0} is out of range")]
+    OutOfRangeDay(i64),
     #[error("{0} is out of range")]
     OutOfRangeDateTime(DateTime<Utc>),
     #[error("{0}")]
@@ -26,3 +32,12 @@ pub fn rfc3339_to_nanos(s: &str) -> Result<i64, Error> {
     dt.timestamp_nanos_opt()
         .ok_or(Error::OutOfRangeDateTime(dt))
 }
+
+pub fn day_to_date(day: i64) -> Result<String, Error> {
+    Ok(
+        DateTime::<Utc>::from_timestamp(day * SECS_PER_DAY, 0)
+            .ok_or(Error::OutOfRangeDay(day))?
+            .format("%Y-%m-%d")
+            .to_string()
+    )
+}
@@ -3,29 +3,53 @@ use axum::{
     body::Bytes
 };
 use futures::Stream;
+use sha2::{Digest, Sha256};
 use std::{
     io,
-    path::Path
+    path::{Component, Path}
 };
 use thiserror::Error;
 use tokio::{
     fs::File,
     io::{
+        AsyncReadExt,
         AsyncWrite,
+        AsyncWriteExt,
         BufWriter
     }
 };
-use tokio_util::io::StreamReader;
+use tokio_util::io::{ReaderStream, StreamReader};
 
 #[derive(Debug, Error)]
 pub enum UploadError {
     #[error("I/O error")]
     IOError(#[from] io::Error),
     #[error("Invalid filename")]
-    InvalidFilename
+    InvalidFilename,
+    #[error("Too large")]
+    TooLarge,
+    #[error("Integrity check failed")]
+    IntegrityError
 }
 
-fn require_filename(path: &str) -> Result<&str, UploadError> {
+#[derive(Debug, Error)]
+pub enum StreamError {
+    #[error("I/O error")]
+    IoError(#[from] io::Error),
+    #[error("Too large")]
+    TooLarge
+}
+
+impl From<StreamError> for UploadError {
+    fn from(e: StreamError) -> Self {
+        match e {
+            StreamError::IoError(e) => UploadError::IOError(e),
+            StreamError::TooLarge => UploadError::TooLarge
+        }
+    }
+}
+
+pub fn require_filename(path: &str) -> Result<&str, UploadError> {
     let p = Path::new(path);
 
     if p.file_name().is_some() && p.components().count() == 1 {
@@ -36,38 +60,129 @@ fn require_filename(path: &str) -> Result<&str, UploadError> {
     }
 }
 
+fn require_storage_path(path: &str) -> Result<&str, UploadError> {
+    let p = Path::new(path);
+
+    if p.file_name().is_some() &&
+        p.components().all(|c| matches!(c, Component::Normal(_)))
+    {
+        Ok(path)
+    }
+    else {
+        Err(UploadError::InvalidFilename)
+    }
+}
+
+pub const DEFAULT_STORAGE_PREFIX_DEPTH: usize = 2;
+
+// Spread stored files over prefix directories taken from the leading
+// characters of their hash, one character per level, so that no single
+// directory or key prefix in the store becomes a hot spot. The full hash
+// is the last directory, so files with different content never share a
+// path even when they have the same name.
+pub fn storage_path(hash: &str, filename: &str, depth: usize) -> String {
+    hash.chars()
+        .take(depth)
+        .map(String::from)
+        .chain([hash.to_owned(), filename.to_owned()])
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+// A disk which fails silently could store something other than what was
+// written, so the stored file is read back and hashed again.
+async fn verify_file(
+    path: &Path,
+    hash: &str
+) -> Result<(), UploadError>
+{
+    let file = ReaderStream::new(File::open(path).await?);
+    let (reread, _) = stream_to_writer(file, tokio::io::sink(), u64::MAX)
+        .await?;
+
+    if reread == hash {
+        Ok(())
+    }
+    else {
+        Err(UploadError::IntegrityError)
+    }
+}
+
 pub async fn stream_to_file<S>(
     uploads_directory: &str,
     path: &str,
-    stream: S
+    stream: S,
+    max_size: u64,
+    integrity_check: bool
 ) -> Result<(), UploadError>
 where
     S: Stream<Item = Result<Bytes, io::Error>>,
 {
-    let filename = require_filename(path)?;
-    let path = std::path::Path::new(uploads_directory).join(filename);
-    let file = BufWriter::new(File::create(path).await?);
+    let path = require_storage_path(path)?;
+    let path = std::path::Path::new(uploads_directory).join(path);
+
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+
+    let file = BufWriter::new(File::create(&path).await?);
 //    let mut file = tokio::io::sink();
 
-    stream_to_writer(stream, file).await
+    let written = match stream_to_writer(stream, file, max_size).await {
+        Ok((hash, _)) if integrity_check => verify_file(&path, &hash).await,
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.into())
+    };
+
+    if written.is_err() {
+        // don't leave behind a partial or damaged file
+        tokio::fs::remove_file(&path).await?;
+    }
+
+    written
 }
 
+// Returns the SHA-256 hash and the size of what was written
 pub async fn stream_to_writer<S, W>(
     stream: S,
-    writer: W
-) -> Result<(), UploadError>
+    writer: W,
+    max_size: u64
+) -> Result<(String, u64), StreamError>
 where
     S: Stream<Item = Result<Bytes, io::Error>>,
     W: AsyncWrite
 {
-    let reader = StreamReader::new(stream);
+    // Without a Content-Length, the size of a body is known only once it
+    // ends, and it might never end; so read at most one byte past the
+    // limit, which is enough to tell that the limit was exceeded.
+    let reader = StreamReader::new(stream).take(max_size.saturating_add(1));
 
     futures::pin_mut!(reader);
     futures::pin_mut!(writer);
 
-    tokio::io::copy(&mut reader, &mut writer).await?;
+    let mut hasher = Sha256::new();
+    let mut size = 0;
+    let mut buf = vec![0; 8192];
+
+    loop {
+        let len = reader.read(&mut buf).await?;
+        if len == 0 {
+            break;
+        }
+
+        hasher.update(&buf[..len]);
+        writer.write_all(&buf[..len]).await?;
+        size += len as u64;
+    }
 
-    Ok(())
+    writer.flush().await?;
+
+    if size > max_size {
+        Err(StreamError::TooLarge)
+    }
+    else {
+        Ok((format!("{:x}", hasher.finalize()), size))
+    }
 }
 
 #[async_trait]
@@ -75,14 +190,21 @@ pub trait Uploader {
     async fn upload<S>(
         &self,
         _filename: &str,
-        _stream: S
+        _stream: S,
+        _max_size: u64
     ) -> Result<String, UploadError>
     where
         S: Stream<Item = Result<Bytes, io::Error>> + Send;
+
+    async fn remove(
+        &self,
+        _filename: &str
+    ) -> Result<(), UploadError>;
 }
 
 pub struct LocalUploader {
-    pub uploads_directory: String
+    pub uploads_directory: String,
+    pub integrity_check: bool
 }
 
 #[async_trait]
@@ -90,13 +212,198 @@ impl Uploader for LocalUploader {
     async fn upload<S>(
         &self,
         filename: &str,
-        stream: S
+        stream: S,
+        max_size: u64
     ) -> Result<String, UploadError>
     where
         S: Stream<Item = Result<Bytes, io::Error>> + Send
     {
-        stream_to_file("uploads", filename, stream).await?;
+        stream_to_file(
+            &self.uploads_directory,
+            filename,
+            stream,
+            max_size,
+            self.integrity_check
+        ).await?;
 
         Ok(format!("http://localhost:3000/uploads/{filename}"))
     }
+
+    async fn remove(
+        &self,
+        filename: &str
+    ) -> Result<(), UploadError>
+    {
+        let filename = require_storage_path(filename)?;
+        let path = Path::new(&self.uploads_directory).join(filename);
+        Ok(tokio::fs::remove_file(path).await?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use futures::StreamExt;
+
+    const HASH: &str = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+
+    #[test]
+    fn storage_path_default_depth() {
+        assert_eq!(
+            storage_path(HASH, "test.vmod", DEFAULT_STORAGE_PREFIX_DEPTH),
+            format!("9/f/{HASH}/test.vmod")
+        );
+    }
+
+    #[test]
+    fn storage_path_configured_depth() {
+        assert_eq!(
+            storage_path(HASH, "test.vmod", 4),
+            format!("9/f/8/6/{HASH}/test.vmod")
+        );
+    }
+
+    #[test]
+    fn storage_path_no_prefix() {
+        assert_eq!(
+            storage_path(HASH, "test.vmod", 0),
+            format!("{HASH}/test.vmod")
+        );
+    }
+
+    #[tokio::test]
+    async fn storage_path_prefix_collision() {
+        // same prefix, same name, different content
+        let a = "9f00000000000000000000000000000000000000000000000000000000000000";
+        let b = "9fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff";
+
+        let dir = tempfile::tempdir().unwrap();
+        let uploader = LocalUploader {
+            uploads_directory: dir.path().to_string_lossy().into(),
+            integrity_check: true
+        };
+
+        for (hash, content) in [(a, "aaa"), (b, "bbb")] {
+            uploader.upload(
+                &storage_path(hash, "test.vmod", DEFAULT_STORAGE_PREFIX_DEPTH),
+                futures::stream::once(async move {
+                    Ok(Bytes::from_static(content.as_bytes()))
+                }),
+                1024
+            ).await.unwrap();
+        }
+
+        for (hash, content) in [(a, "aaa"), (b, "bbb")] {
+            assert_eq!(
+                std::fs::read_to_string(
+                    dir.path().join(
+                        storage_path(hash, "test.vmod", DEFAULT_STORAGE_PREFIX_DEPTH)
+                    )
+                ).unwrap(),
+                content
+            );
+        }
+    }
+
+    fn endless_stream() -> impl Stream<Item = Result<Bytes, io::Error>> {
+        futures::stream::repeat_with(|| Ok(Bytes::from_static(b"0123456789")))
+    }
+
+    #[tokio::test]
+    async fn stream_to_writer_at_limit() {
+        let mut buf = vec![];
+        assert_eq!(
+            stream_to_writer(endless_stream().take(3), &mut buf, 30)
+                .await
+                .unwrap(),
+            (format!("{:x}", Sha256::digest(&buf)), 30)
+        );
+        assert_eq!(buf.len(), 30);
+    }
+
+    #[tokio::test]
+    async fn stream_to_writer_one_past_limit() {
+        let mut buf = vec![];
+        assert!(
+            matches!(
+                stream_to_writer(endless_stream().take(3), &mut buf, 29).await,
+                Err(StreamError::TooLarge)
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn stream_to_writer_endless() {
+        // a body which never ends is cut off one byte past the limit
+        let mut buf = vec![];
+        assert!(
+            matches!(
+                stream_to_writer(endless_stream(), &mut buf, 25).await,
+                Err(StreamError::TooLarge)
+            )
+        );
+        assert_eq!(buf.len(), 26);
+    }
+
+    #[tokio::test]
+    async fn upload_too_large_removes_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let uploader = LocalUploader {
+            uploads_directory: dir.path().to_string_lossy().into(),
+            integrity_check: true
+        };
+
+        assert!(
+            matches!(
+                uploader.upload("test.vmod", endless_stream(), 25).await,
+                Err(UploadError::TooLarge)
+            )
+        );
+        assert!(!dir.path().join("test.vmod").exists());
+    }
+
+    #[tokio::test]
+    async fn verify_file_ok() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test.vmod");
+        std::fs::write(&path, "aaa").unwrap();
+
+        verify_file(&path, &format!("{:x}", Sha256::digest(b"aaa")))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn verify_file_mismatch() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test.vmod");
+        std::fs::write(&path, "aab").unwrap();
+
+        assert!(
+            matches!(
+                verify_file(&path, &format!("{:x}", Sha256::digest(b"aaa")))
+                    .await,
+                Err(UploadError::IntegrityError)
+            )
+        );
+    }
+
+    #[test]
+    fn require_storage_path_ok() {
+        assert_eq!(
+            require_storage_path("9/f/test.vmod").unwrap(),
+            "9/f/test.vmod"
+        );
+    }
+
+    #[test]
+    fn require_storage_path_parent() {
+        assert!(require_storage_path("9/../../test.vmod").is_err());
+    }
+
+    #[test]
+    fn require_storage_path_absolute() {
+        assert!(require_storage_path("/etc/passwd").is_err());
+    }
 }
@@ -111,6 +111,80 @@ impl Ord for Version {
     }
 }
 
+#[derive(Debug, Eq, Error, PartialEq)]
+#[error("{0} is malformed")]
+pub struct MalformedRequires(String);
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+#[serde(try_from = "&str")]
+pub struct Requires(semver::VersionReq);
+
+impl Requires {
+    pub fn minimum(&self) -> Option<Version> {
+        // The comparators are conjoined, so the least satisfying version
+        // is the greatest of their lower bounds. Prerelease tags are
+        // ignored; upper bounds don't matter here.
+        self.0.comparators
+            .iter()
+            .filter_map(|c| {
+                let minor = c.minor.unwrap_or(0);
+                let patch = c.patch.unwrap_or(0);
+
+                let (major, minor, patch) = match c.op {
+                    semver::Op::Exact |
+                    semver::Op::GreaterEq |
+                    semver::Op::Tilde |
+                    semver::Op::Caret |
+                    semver::Op::Wildcard => (c.major, minor, patch),
+                    // bump the least significant component given
+                    semver::Op::Greater => match (c.minor, c.patch) {
+                        (None, _) => (c.major + 1, 0, 0),
+                        (Some(minor), None) => (c.major, minor + 1, 0),
+                        (Some(minor), Some(patch)) => (c.major, minor, patch + 1)
+                    },
+                    _ => return None
+                };
+
+                Some(
+                    Version {
+                        major: i64::try_from(major).ok()?,
+                        minor: i64::try_from(minor).ok()?,
+                        patch: i64::try_from(patch).ok()?,
+                        pre: None,
+                        build: None
+                    }
+                )
+            })
+            .max()
+    }
+}
+
+impl From<&Requires> for String {
+    fn from(r: &Requires) -> Self {
+        r.0.to_string()
+    }
+}
+
+impl TryFrom<&str> for Requires {
+    type Error = MalformedRequires;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        match s.parse::<semver::VersionReq>() {
+            // a requirement must constrain the version somehow
+            Ok(req) if !req.comparators.is_empty() => Ok(Requires(req)),
+            _ => Err(MalformedRequires(s.into()))
+        }
+    }
+}
+
+impl FromStr for Requires {
+    type Err = MalformedRequires;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Requires::try_from(s)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -312,4 +386,131 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn string_to_requires_ok() {
+        assert_eq!(
+            String::from(&">= 3.7.12".parse::<Requires>().unwrap()),
+            ">=3.7.12"
+        );
+    }
+
+    #[test]
+    fn string_to_requires_range_ok() {
+        assert_eq!(
+            String::from(&">=3.6.0,  <3.8".parse::<Requires>().unwrap()),
+            ">=3.6.0, <3.8"
+        );
+    }
+
+    #[test]
+    fn string_to_requires_pre_ok() {
+        assert_eq!(
+            String::from(&">=3.7.0-beta.1".parse::<Requires>().unwrap()),
+            ">=3.7.0-beta.1"
+        );
+    }
+
+    #[test]
+    fn string_to_requires_bare_version_ok() {
+        assert_eq!(
+            String::from(&"3.7.12".parse::<Requires>().unwrap()),
+            "^3.7.12"
+        );
+    }
+
+    #[test]
+    fn string_to_requires_empty() {
+        assert!(
+            matches!(
+                "".parse::<Requires>().unwrap_err(),
+                MalformedRequires(_)
+            )
+        );
+    }
+
+    #[test]
+    fn string_to_requires_wildcard() {
+        assert!(
+            matches!(
+                "*".parse::<Requires>().unwrap_err(),
+                MalformedRequires(_)
+            )
+        );
+    }
+
+    #[test]
+    fn string_to_requires_bogus() {
+        assert!(
+            matches!(
+                "bogus".parse::<Requires>().unwrap_err(),
+                MalformedRequires(_)
+            )
+        );
+    }
+
+    #[test]
+    fn string_to_requires_bad_operator() {
+        assert!(
+            matches!(
+                "=> 3.7.12".parse::<Requires>().unwrap_err(),
+                MalformedRequires(_)
+            )
+        );
+    }
+
+    #[test]
+    fn string_to_requires_trailing_comma() {
+        assert!(
+            matches!(
+                ">= 3.7.12,".parse::<Requires>().unwrap_err(),
+                MalformedRequires(_)
+            )
+        );
+    }
+
+    #[test]
+    fn string_to_requires_too_many_components() {
+        assert!(
+            matches!(
+                ">= 3.7.12.1".parse::<Requires>().unwrap_err(),
+                MalformedRequires(_)
+            )
+        );
+    }
+
+    #[track_caller]
+    fn assert_minimum(req: &str, exp: Option<&str>) {
+        assert_eq!(
+            req.parse::<Requires>().unwrap().minimum(),
+            exp.map(|v| v.parse::<Version>().unwrap())
+        );
+    }
+
+    #[test]
+    fn requires_minimum_greater_eq() {
+        assert_minimum(">=3.7", Some("3.7.0"));
+    }
+
+    #[test]
+    fn requires_minimum_caret() {
+        assert_minimum("^3.6.2", Some("3.6.2"));
+    }
+
+    #[test]
+    fn requires_minimum_greater() {
+        assert_minimum(">3", Some("4.0.0"));
+        assert_minimum(">3.6", Some("3.7.0"));
+        assert_minimum(">3.6.1", Some("3.6.2"));
+    }
+
+    #[test]
+    fn requires_minimum_conjunction() {
+        assert_minimum(">=3.6, >=3.7.1, <4", Some("3.7.1"));
+    }
+
+    #[test]
+    fn requires_minimum_no_lower_bound() {
+        assert_minimum("<3.7", None);
+    }
 }
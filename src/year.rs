@@ -0,0 +1,94 @@
+use serde::Deserialize;
+
+// The single policy for years which are not numbers, such as "c. 1942"
+// or "unknown"; anything filtering, sorting, or counting projects by year
+// must consult this rather than deciding for itself
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum NonNumericYears {
+    // non-numeric years are unknown, so no year filter selects them
+    #[default]
+    Unknown,
+    // non-numeric years are compared as strings, like numeric ones
+    Literal
+}
+
+impl NonNumericYears {
+    pub fn filterable(self, year: &str) -> bool {
+        match self {
+            NonNumericYears::Unknown => is_numeric_year(year),
+            NonNumericYears::Literal => true
+        }
+    }
+}
+
+pub fn is_numeric_year(year: &str) -> bool {
+    !year.is_empty() && year.bytes().all(|b| b.is_ascii_digit())
+}
+
+// A plausible year has four digits and no leading zero; an empty year
+// is merely unknown, so is not malformed
+pub fn is_plausible_year(year: &str) -> bool {
+    is_numeric_year(year) && year.len() == 4 && !year.starts_with('0')
+}
+
+pub fn is_malformed_year(year: &str) -> bool {
+    !year.is_empty() && !is_plausible_year(year)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn is_numeric_year_ok() {
+        assert!(is_numeric_year("1979"));
+        assert!(is_numeric_year("0"));
+    }
+
+    #[test]
+    fn is_numeric_year_not() {
+        assert!(!is_numeric_year(""));
+        assert!(!is_numeric_year("c. 1979"));
+        assert!(!is_numeric_year("1979?"));
+        assert!(!is_numeric_year(" 1979"));
+        assert!(!is_numeric_year("-1979"));
+        assert!(!is_numeric_year("unknown"));
+    }
+
+    #[test]
+    fn is_plausible_year_ok() {
+        assert!(is_plausible_year("1979"));
+        assert!(is_plausible_year("2024"));
+    }
+
+    #[test]
+    fn is_plausible_year_not() {
+        assert!(!is_plausible_year(""));
+        assert!(!is_plausible_year("0"));
+        assert!(!is_plausible_year("0979"));
+        assert!(!is_plausible_year("979"));
+        assert!(!is_plausible_year("19790"));
+        assert!(!is_plausible_year("c. 1979"));
+    }
+
+    #[test]
+    fn is_malformed_year_ok() {
+        assert!(is_malformed_year("c. 1979"));
+        assert!(is_malformed_year("79"));
+        assert!(!is_malformed_year("1979"));
+        assert!(!is_malformed_year(""));
+    }
+
+    #[test]
+    fn filterable_unknown() {
+        assert!(NonNumericYears::Unknown.filterable("1979"));
+        assert!(!NonNumericYears::Unknown.filterable("c. 1979"));
+    }
+
+    #[test]
+    fn filterable_literal() {
+        assert!(NonNumericYears::Literal.filterable("1979"));
+        assert!(NonNumericYears::Literal.filterable("c. 1979"));
+    }
+}